@@ -101,8 +101,29 @@ pub enum AstNode<'ast> {
     Import(&'ast ast::ImportItem<'ast>),
     /// A subroutine declaration.
     SubroutineDecl(&'ast ast::SubroutineDecl<'ast>),
+    /// A subroutine port, given as `(port, type)`.
+    SubroutinePort(&'ast ast::SubroutinePort<'ast>, NodeId),
+    /// A subroutine port declared in the subroutine body, given as `(name,
+    /// decl, type)`.
+    SubroutinePortDecl(
+        &'ast ast::VarDeclName<'ast>,
+        &'ast ast::SubroutinePortDecl<'ast>,
+        NodeId,
+    ),
     /// An interface.
     Interface(&'ast ast::Interface<'ast>),
+    /// A concurrent assertion, given as `(item, assertion)`.
+    Assertion(&'ast ast::Item<'ast>, &'ast ast::Assertion<'ast>),
+    /// A foreach-loop index variable.
+    ForeachIndex(&'ast ast::ForeachIndex<'ast>),
+    /// A let declaration.
+    LetDecl(&'ast ast::LetDecl<'ast>),
+    /// A formal argument of a let declaration.
+    LetArg(&'ast ast::LetArg<'ast>),
+    /// A class declaration.
+    Class(&'ast ast::ClassDecl<'ast>),
+    /// A variable bound by a pattern.
+    PatternVar(&'ast ast::PatternVar<'ast>),
 }
 
 impl<'a> AstNode<'a> {
@@ -134,7 +155,15 @@ impl<'a> AstNode<'a> {
             AstNode::EnumVariant(x, _, _) => Some(x),
             AstNode::Import(x) => Some(x),
             AstNode::SubroutineDecl(x) => Some(x),
+            AstNode::SubroutinePort(x, _) => Some(x),
+            AstNode::SubroutinePortDecl(x, _, _) => Some(x),
             AstNode::Interface(x) => Some(x),
+            AstNode::Assertion(x, _) => Some(x),
+            AstNode::ForeachIndex(x) => Some(x),
+            AstNode::LetDecl(x) => Some(x),
+            AstNode::LetArg(x) => Some(x),
+            AstNode::Class(x) => Some(x),
+            AstNode::PatternVar(x) => Some(x),
             _ => None,
         }
     }
@@ -164,9 +193,11 @@ impl<'a> AstNode<'a> {
                 ))
                 .into_iter(),
             ),
-            AllNode::VarDecl(x) => {
-                Box::new(x.names.iter().map(move |n| AstNode::VarDecl(n, x, n.id())))
-            }
+            AllNode::VarDecl(x) => Box::new(
+                x.names
+                    .iter()
+                    .map(move |n| AstNode::VarDecl(n, x, x.ty.id())),
+            ),
             // AllNode::NetDecl(x) => x.names.iter().map(|n| AstNode::NetDecl(n, x, n.id())),
             AllNode::Procedure(x) => Box::new(Some(AstNode::Proc(x)).into_iter()),
             AllNode::Stmt(x) => Box::new(Some(AstNode::Stmt(x)).into_iter()),
@@ -192,6 +223,11 @@ impl<'a> AstNode<'a> {
             AllNode::ImportItem(x) => Box::new(Some(AstNode::Import(x)).into_iter()),
             AllNode::SubroutineDecl(x) => Box::new(Some(AstNode::SubroutineDecl(x)).into_iter()),
             AllNode::Interface(x) => Box::new(Some(AstNode::Interface(x)).into_iter()),
+            AllNode::ForeachIndex(x) => Box::new(Some(AstNode::ForeachIndex(x)).into_iter()),
+            AllNode::LetDecl(x) => Box::new(Some(AstNode::LetDecl(x)).into_iter()),
+            AllNode::LetArg(x) => Box::new(Some(AstNode::LetArg(x)).into_iter()),
+            AllNode::ClassDecl(x) => Box::new(Some(AstNode::Class(x)).into_iter()),
+            AllNode::PatternVar(x) => Box::new(Some(AstNode::PatternVar(x)).into_iter()),
             _ => Box::new(None.into_iter()),
         }
     }
@@ -225,7 +261,15 @@ impl<'ast> HasSpan for AstNode<'ast> {
             AstNode::EnumVariant(x, _, _) => x.span(),
             AstNode::Import(x) => x.span(),
             AstNode::SubroutineDecl(x) => x.span(),
+            AstNode::SubroutinePort(x, _) => x.span(),
+            AstNode::SubroutinePortDecl(_, x, _) => x.span,
             AstNode::Interface(x) => x.span(),
+            AstNode::Assertion(_, x) => x.span,
+            AstNode::ForeachIndex(x) => x.span(),
+            AstNode::LetDecl(x) => x.span(),
+            AstNode::LetArg(x) => x.span(),
+            AstNode::Class(x) => x.span(),
+            AstNode::PatternVar(x) => x.span(),
         }
     }
 
@@ -256,7 +300,15 @@ impl<'ast> HasSpan for AstNode<'ast> {
             AstNode::EnumVariant(x, _, _) => x.human_span(),
             AstNode::Import(x) => x.human_span(),
             AstNode::SubroutineDecl(x) => x.human_span(),
+            AstNode::SubroutinePort(x, _) => x.human_span(),
+            AstNode::SubroutinePortDecl(x, _, _) => x.human_span(),
             AstNode::Interface(x) => x.human_span(),
+            AstNode::Assertion(_, x) => x.label.map(|(_, span)| span).unwrap_or(x.span),
+            AstNode::ForeachIndex(x) => x.human_span(),
+            AstNode::LetDecl(x) => x.human_span(),
+            AstNode::LetArg(x) => x.human_span(),
+            AstNode::Class(x) => x.human_span(),
+            AstNode::PatternVar(x) => x.human_span(),
         }
     }
 }
@@ -290,7 +342,15 @@ impl<'ast> HasDesc for AstNode<'ast> {
             AstNode::EnumVariant(x, _, _) => "enum variant",
             AstNode::Import(x) => "import",
             AstNode::SubroutineDecl(x) => "subroutine declaration",
+            AstNode::SubroutinePort(x, _) => "subroutine port",
+            AstNode::SubroutinePortDecl(x, _, _) => "subroutine port",
             AstNode::Interface(x) => "interface",
+            AstNode::Assertion(..) => "concurrent assertion",
+            AstNode::ForeachIndex(_) => "foreach index",
+            AstNode::LetDecl(_) => "let declaration",
+            AstNode::LetArg(_) => "let argument",
+            AstNode::Class(_) => "class declaration",
+            AstNode::PatternVar(_) => "pattern variable",
         }
     }
 
@@ -321,7 +381,18 @@ impl<'ast> HasDesc for AstNode<'ast> {
             AstNode::EnumVariant(x, _, _) => x.to_definite_string(),
             AstNode::Import(x) => x.to_definite_string(),
             AstNode::SubroutineDecl(x) => x.to_definite_string(),
+            AstNode::SubroutinePort(x, _) => x.to_definite_string(),
+            AstNode::SubroutinePortDecl(x, _, _) => x.to_definite_string(),
             AstNode::Interface(x) => x.to_definite_string(),
+            AstNode::Assertion(_, x) => match x.label {
+                Some((name, _)) => format!("concurrent assertion `{}`", name),
+                None => "concurrent assertion".to_string(),
+            },
+            AstNode::ForeachIndex(x) => x.to_definite_string(),
+            AstNode::LetDecl(x) => x.to_definite_string(),
+            AstNode::LetArg(x) => x.to_definite_string(),
+            AstNode::Class(x) => x.to_definite_string(),
+            AstNode::PatternVar(x) => x.to_definite_string(),
         }
     }
 }
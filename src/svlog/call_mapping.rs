@@ -0,0 +1,199 @@
+// Copyright (c) 2016-2020 Fabian Schuiki
+
+//! A mapping from a function, task, or method call's arguments to the called
+//! subroutine's ports.
+
+use crate::crate_prelude::*;
+use crate::hir::HirNode;
+use itertools::Itertools;
+use std::sync::Arc;
+
+/// A mapping of the arguments of a call to the ports of a subroutine.
+#[derive(Clone, Debug)]
+pub struct CallMapping<'a> {
+    /// The corresponding call expression.
+    pub hir: &'a hir::Expr<'a>,
+    /// The called subroutine.
+    pub subroutine: &'a hir::Subroutine,
+    /// The parametrization of the called subroutine. This differs from the
+    /// caller's for methods of parametrized classes.
+    pub env: ParamEnv,
+    /// The expression assigned to each argument. The arguments are in the
+    /// order they are declared in the subroutine.
+    pub args: Vec<CallArgMapping>,
+}
+
+/// An expression assigned to a subroutine argument in a call.
+#[derive(Copy, Clone, Debug)]
+pub struct CallArgMapping {
+    /// The subroutine argument.
+    pub arg: hir::SubroutineArg,
+    /// The expression assigned to the argument. This is either an expression
+    /// in the call, or the argument's default value.
+    pub expr: NodeId,
+    /// Whether the argument's default value is used.
+    pub is_default: bool,
+}
+
+impl<'a> CallMapping<'a> {
+    /// Find the argument an expression is assigned to.
+    pub fn find(&self, expr: NodeId) -> Option<&CallArgMapping> {
+        self.args.iter().find(|a| a.expr == expr && !a.is_default)
+    }
+}
+
+/// Determine the mapping of a function, task, or method call's arguments.
+///
+/// For `new` expressions this maps the arguments to the constructor of the
+/// class, which must exist.
+#[moore_derive::query]
+pub(crate) fn call_mapping<'a>(
+    cx: &impl Context<'a>,
+    Ref(expr): Ref<'a, hir::Expr<'a>>,
+    env: ParamEnv,
+) -> Result<Arc<CallMapping<'a>>> {
+    let (target, call_args, env) = match expr.kind {
+        hir::ExprKind::FunctionCall(target, ref args) => {
+            (target, args, class::member_env(cx, expr.id, target, env))
+        }
+        hir::ExprKind::MethodCall(_, _, ref args) | hir::ExprKind::New(ref args) => {
+            match cx.called_method(Ref(expr), env)? {
+                Some(method) => (method.id, args, method.env),
+                None => bug_span!(
+                    expr.span,
+                    cx,
+                    "call mapping for `{}` without subroutine",
+                    expr.span.extract()
+                ),
+            }
+        }
+        _ => unreachable!("call mapping for non-call {:?}", expr),
+    };
+    let subroutine = match cx.hir_of(target)? {
+        HirNode::Subroutine(x) => x,
+        x => unreachable!("call target is not a subroutine: {:?}", x),
+    };
+
+    // Assign the positional and named arguments to the subroutine's ports.
+    let mut assigned: Vec<Option<Option<NodeId>>> = vec![None; subroutine.args.len()];
+    let mut failed = false;
+    let mut seen_named = false;
+    for (index, arg) in call_args.iter().enumerate() {
+        let pos = match arg.name {
+            None => {
+                if seen_named {
+                    cx.emit(
+                        DiagBuilder2::error("positional argument after named arguments")
+                            .span(arg.span),
+                    );
+                    failed = true;
+                    continue;
+                }
+                if index >= subroutine.args.len() {
+                    cx.emit(
+                        DiagBuilder2::error(format!(
+                            "{} only has {} argument(s)",
+                            subroutine.desc_full(),
+                            subroutine.args.len()
+                        ))
+                        .span(arg.span),
+                    );
+                    failed = true;
+                    continue;
+                }
+                index
+            }
+            Some(name) => {
+                seen_named = true;
+                match subroutine
+                    .args
+                    .iter()
+                    .position(|a| a.name.value == name.value)
+                {
+                    Some(pos) => pos,
+                    None => {
+                        cx.emit(
+                            DiagBuilder2::error(format!(
+                                "no argument `{}` in {}",
+                                name,
+                                subroutine.desc_full()
+                            ))
+                            .span(name.span)
+                            .add_note(format!(
+                                "Declared arguments are {}",
+                                subroutine
+                                    .args
+                                    .iter()
+                                    .map(|a| format!("`{}`", a.name))
+                                    .format(", ")
+                            )),
+                        );
+                        failed = true;
+                        continue;
+                    }
+                }
+            }
+        };
+        if assigned[pos].is_some() {
+            cx.emit(
+                DiagBuilder2::error(format!(
+                    "argument `{}` assigned multiple times",
+                    subroutine.args[pos].name
+                ))
+                .span(arg.span),
+            );
+            failed = true;
+            continue;
+        }
+        assigned[pos] = Some(arg.expr);
+    }
+
+    // Fill in the default values for any arguments not assigned.
+    let mut args = vec![];
+    for (&arg, assigned) in subroutine.args.iter().zip(assigned.into_iter()) {
+        if let Some(Some(expr)) = assigned {
+            args.push(CallArgMapping {
+                arg,
+                expr,
+                is_default: false,
+            });
+            continue;
+        }
+        let default = match cx.hir_of(arg.id)? {
+            HirNode::VarDecl(decl) => decl.init,
+            _ => unreachable!(),
+        };
+        match default {
+            Some(expr) => args.push(CallArgMapping {
+                arg,
+                expr,
+                is_default: true,
+            }),
+            None => {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "argument `{}` of {} not assigned",
+                        arg.name,
+                        subroutine.desc_full()
+                    ))
+                    .span(expr.span)
+                    .add_note(format!(
+                        "`{}` has no default value and must be assigned in the call",
+                        arg.name
+                    )),
+                );
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        return Err(());
+    }
+    Ok(Arc::new(CallMapping {
+        hir: expr,
+        subroutine,
+        env,
+        args,
+    }))
+}
@@ -0,0 +1,492 @@
+// Copyright (c) 2016-2020 Fabian Schuiki
+
+//! Class declarations, their members, and their inheritance hierarchy.
+
+use crate::crate_prelude::*;
+use crate::{hir::HirNode, resolver::DefNode, ty::UnpackedType};
+use std::{collections::HashSet, sync::Arc};
+
+/// Determine the class a class declaration extends.
+///
+/// Returns `None` if the class has no base class. Emits an error if the class
+/// extends itself, either directly or through other classes.
+#[moore_derive::query]
+pub(crate) fn base_class_decl<'a>(
+    cx: &impl Context<'a>,
+    Ref(decl): Ref<'a, ast::ClassDecl<'a>>,
+) -> Result<Option<&'a ast::ClassDecl<'a>>> {
+    let ty = match decl.extends {
+        Some((ref ty, _)) => ty,
+        None => return Ok(None),
+    };
+
+    // Resolve the name of the base class in the scope the class is declared
+    // in. Resolving it within the class itself would look at the inherited
+    // members, which in turn depend on the base class.
+    let name = match extended_name(ty) {
+        Some(name) => name,
+        None => return unsupported_base_class(cx, ty),
+    };
+    let def = cx.resolve_local_or_error(name, cx.scope_location(decl), false)?;
+    let base = match def.node {
+        DefNode::Ast(node) => node.as_all().get_class_decl(),
+        _ => None,
+    };
+    let base = match base {
+        Some(base) => base,
+        None => {
+            cx.emit(
+                DiagBuilder2::error(format!("`{}` is not a class", name))
+                    .span(name.span)
+                    .add_note(format!("`{}` was declared here:", name))
+                    .span(def.node.span()),
+            );
+            return Err(());
+        }
+    };
+
+    // Follow the chain of base classes to make sure it does not lead back to
+    // this class. Chains that run into a cycle further up are reported by the
+    // classes in the cycle.
+    let mut chain = vec![];
+    let mut visited: HashSet<*const ast::ClassDecl> = HashSet::new();
+    visited.insert(decl as *const _);
+    let mut next = Some(base);
+    while let Some(class) = next {
+        if !visited.insert(class as *const _) {
+            if class as *const _ != decl as *const _ {
+                break;
+            }
+            let mut d =
+                DiagBuilder2::error(format!("class `{}` cannot extend itself", decl.name))
+                    .span(name.span);
+            for (i, link) in chain.iter().enumerate() {
+                let extended = chain.get(i + 1).cloned().unwrap_or(decl);
+                d = d
+                    .add_note(format!("`{}` extends `{}` here:", link.name, extended.name))
+                    .span(link.extends.as_ref().unwrap().0.span());
+            }
+            cx.emit(d);
+            return Err(());
+        }
+        chain.push(class);
+        next = extended_class(cx, class);
+    }
+    Ok(Some(base))
+}
+
+/// Find the name of the class in an `extends` clause.
+fn extended_name<'a>(ty: &'a ast::Type<'a>) -> Option<Spanned<Name>> {
+    match ty.kind.data {
+        ast::NamedType(name) => Some(name),
+        ast::SpecializedType(ref inner, _) => match inner.kind.data {
+            ast::NamedType(name) => Some(name),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Find the class a class declaration extends, without emitting diagnostics.
+///
+/// Returns `None` if the class has no base class, or the base class cannot be
+/// resolved. Errors in the latter case are reported by `base_class_decl`.
+fn extended_class<'a>(
+    cx: &impl Context<'a>,
+    decl: &'a ast::ClassDecl<'a>,
+) -> Option<&'a ast::ClassDecl<'a>> {
+    let name = extended_name(&decl.extends.as_ref()?.0)?;
+    match cx.resolve_local(name.value, cx.scope_location(decl), false) {
+        Ok(Some(def)) => match def.node {
+            DefNode::Ast(node) => node.as_all().get_class_decl(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn unsupported_base_class<'a, T>(cx: &impl Context<'a>, ty: &'a ast::Type<'a>) -> Result<T> {
+    cx.emit(
+        DiagBuilder2::error(format!("unsupported: base class `{}`", ty.span().extract()))
+            .span(ty.span()),
+    );
+    Err(())
+}
+
+/// The members of a class, including the ones it inherits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassDetails<'a> {
+    /// The HIR of the class.
+    pub hir: &'a hir::Class<'a>,
+    /// The parametrization of the class.
+    pub env: ParamEnv,
+    /// The details of the base class, if the class extends another one.
+    pub base: Option<Arc<ClassDetails<'a>>>,
+    /// The properties of the class, inherited ones first. Property `i` is
+    /// stored in slot `i + 1` of an object; slot 0 holds the id of the class
+    /// specialization the object was created as.
+    pub props: Vec<ClassMember>,
+    /// The methods of the class. Inherited methods are replaced by the ones
+    /// overriding them.
+    pub methods: Vec<ClassMember>,
+}
+
+/// A property or method of a class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClassMember {
+    /// The declaration of the member.
+    pub id: NodeId,
+    /// The name of the member.
+    pub name: Name,
+    /// The class which declares the member.
+    pub class: NodeId,
+    /// The parametrization of the class which declares the member.
+    pub env: ParamEnv,
+    /// Whether the member is a virtual method.
+    pub virt: bool,
+}
+
+impl<'a> ClassDetails<'a> {
+    /// Find a property by name, together with the slot it occupies.
+    pub fn find_prop(&self, name: Name) -> Option<(usize, &ClassMember)> {
+        self.props
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, m)| m.name == name)
+            .map(|(i, m)| (i + 1, m))
+    }
+
+    /// Find a property by its declaration, together with the slot it occupies.
+    pub fn find_prop_by_id(&self, id: NodeId) -> Option<(usize, &ClassMember)> {
+        self.props
+            .iter()
+            .enumerate()
+            .find(|(_, m)| m.id == id)
+            .map(|(i, m)| (i + 1, m))
+    }
+
+    /// Find a method by name.
+    pub fn find_method(&self, name: Name) -> Option<&ClassMember> {
+        self.methods.iter().find(|m| m.name == name)
+    }
+
+    /// Find any member by its declaration.
+    pub fn find_member(&self, id: NodeId) -> Option<&ClassMember> {
+        self.props
+            .iter()
+            .chain(self.methods.iter())
+            .find(|m| m.id == id)
+            .or_else(|| self.base.as_ref().and_then(|b| b.find_member(id)))
+    }
+
+    /// Find the constructor declared by the class itself, if any.
+    pub fn own_constructor(&self) -> Option<&ClassMember> {
+        self.find_method(constructor_name())
+            .filter(|m| m.class == self.hir.id)
+    }
+
+    /// The number of slots occupied by an object of the class.
+    pub fn num_slots(&self) -> usize {
+        self.props.len() + 1
+    }
+
+    /// Check whether this class is or derives from another class.
+    pub fn derives_from(&self, class: NodeId) -> bool {
+        self.hir.id == class || self.base.as_ref().map_or(false, |b| b.derives_from(class))
+    }
+
+    /// Find the details of this class or one of its base classes.
+    pub fn find_class(&self, class: NodeId) -> Option<&ClassDetails<'a>> {
+        if self.hir.id == class {
+            Some(self)
+        } else {
+            self.base.as_ref().and_then(|b| b.find_class(class))
+        }
+    }
+}
+
+/// The name of class constructors.
+pub fn constructor_name() -> Name {
+    Name::from("new")
+}
+
+/// Determine the members of a class.
+#[moore_derive::query]
+pub(crate) fn class_details<'a>(
+    cx: &impl Context<'a>,
+    class: NodeId,
+    env: ParamEnv,
+) -> Result<Arc<ClassDetails<'a>>> {
+    let hir = match cx.hir_of(class)? {
+        HirNode::Class(x) => x,
+        _ => unreachable!(),
+    };
+
+    // Determine the parametrization of the base class and start out with its
+    // members.
+    let base = match hir.ast.extends {
+        Some((ref ty, _)) => {
+            cx.base_class_decl(Ref(hir.ast))?;
+            let ty = cx.packed_type_from_ast(Ref(ty), env, None);
+            match ty.get_class() {
+                Some(base) => Some(cx.class_details(base.ast.id(), base.env)?),
+                None => return Err(()),
+            }
+        }
+        None => None,
+    };
+    let (mut props, mut methods) = match base {
+        Some(ref base) => (base.props.clone(), base.methods.clone()),
+        None => (vec![], vec![]),
+    };
+
+    // Add the class's own properties.
+    for &id in &hir.props {
+        let name = match cx.hir_of(id)? {
+            HirNode::VarDecl(x) => x.name.value,
+            _ => unreachable!(),
+        };
+        props.push(ClassMember {
+            id,
+            name,
+            class: hir.id,
+            env,
+            virt: false,
+        });
+    }
+
+    // Add the class's own methods, replacing the ones they override. A method
+    // that overrides a virtual one is virtual itself.
+    for &(id, virt) in &hir.methods {
+        let name = match cx.hir_of(id)? {
+            HirNode::Subroutine(x) => x.name.value,
+            _ => unreachable!(),
+        };
+        let member = ClassMember {
+            id,
+            name,
+            class: hir.id,
+            env,
+            virt,
+        };
+        match methods.iter_mut().find(|m| m.name == name) {
+            Some(inherited) => {
+                *inherited = ClassMember {
+                    virt: virt || inherited.virt,
+                    ..member
+                }
+            }
+            None => methods.push(member),
+        }
+    }
+
+    Ok(Arc::new(ClassDetails {
+        hir,
+        env,
+        base,
+        props,
+        methods,
+    }))
+}
+
+/// Determine the type of a handle to a class.
+pub fn class_type<'a>(
+    cx: &impl Context<'a>,
+    class: &'a ast::ClassDecl<'a>,
+    env: ParamEnv,
+) -> &'a UnpackedType<'a> {
+    UnpackedType::make(cx, ty::ClassType { ast: class, env })
+}
+
+/// Find the class declaration a node is nested in.
+pub fn enclosing_class<'a>(cx: &impl Context<'a>, node: NodeId) -> Option<&'a ast::ClassDecl<'a>> {
+    let mut next = cx.ast_for_id(node).get_parent();
+    while let Some(node) = next {
+        if let Some(class) = node.as_all().get_class_decl() {
+            return Some(class);
+        }
+        next = node.get_parent();
+    }
+    None
+}
+
+/// Find the class which declares a property or method.
+///
+/// Returns `None` if the node is not a class member.
+pub fn declaring_class<'a>(
+    cx: &impl Context<'a>,
+    member: NodeId,
+) -> Option<&'a ast::ClassDecl<'a>> {
+    let ast = cx.ast_for_id(member);
+    if ast.as_all().is_class_decl() {
+        return None;
+    }
+    cx.scope_location(ast).scope.as_all().get_class_decl()
+}
+
+/// Check whether a constructor explicitly calls the constructor of its base
+/// class through `super.new(...)`.
+pub fn calls_super_new<'a>(cx: &impl Context<'a>, ctor: &hir::Subroutine) -> Result<bool> {
+    for &stmt in &ctor.stmts {
+        let expr = match cx.hir_of(stmt)? {
+            HirNode::Stmt(hir::Stmt {
+                kind: hir::StmtKind::Expr(expr),
+                ..
+            }) => expr,
+            _ => continue,
+        };
+        let object = match cx.hir_of(*expr)? {
+            HirNode::Expr(hir::Expr {
+                kind: hir::ExprKind::MethodCall(object, name, _),
+                ..
+            }) if name.value == constructor_name() => object,
+            _ => continue,
+        };
+        if let HirNode::Expr(hir::Expr {
+            kind: hir::ExprKind::Super,
+            ..
+        }) = cx.hir_of(*object)?
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Find the class member a node refers to, as seen from the class the node is
+/// nested in.
+///
+/// Inherited members carry the parametrization of the base class which
+/// declares them, rather than the one of the class the node is nested in.
+/// Returns `None` if the target is not a class member.
+pub fn member_at<'a>(
+    cx: &impl Context<'a>,
+    at: NodeId,
+    target: NodeId,
+    env: ParamEnv,
+) -> Result<Option<ClassMember>> {
+    if declaring_class(cx, target).is_none() {
+        return Ok(None);
+    }
+    let class = match enclosing_class(cx, at) {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    let details = cx.class_details(class.id(), env)?;
+    Ok(details.find_member(target).cloned())
+}
+
+/// Determine the parametrization of a node referred to from another node.
+///
+/// This is the parametrization of the declaring class for inherited class
+/// members, and `env` for everything else.
+pub fn member_env<'a>(
+    cx: &impl Context<'a>,
+    at: NodeId,
+    target: NodeId,
+    env: ParamEnv,
+) -> ParamEnv {
+    match member_at(cx, at, target, env) {
+        Ok(Some(member)) => member.env,
+        _ => env,
+    }
+}
+
+/// Determine the method called by an expression.
+///
+/// Handles method calls such as `a.foo()`, calls to methods from within a
+/// class such as `foo()`, and `new` expressions. Returns `None` for calls to
+/// regular subroutines, for `new` if the class has no constructor, and for
+/// built-in methods of dynamic arrays, queues, and associative arrays.
+#[moore_derive::query]
+pub(crate) fn called_method<'a>(
+    cx: &impl Context<'a>,
+    Ref(expr): Ref<'a, hir::Expr<'a>>,
+    env: ParamEnv,
+) -> Result<Option<ClassMember>> {
+    match expr.kind {
+        hir::ExprKind::FunctionCall(target, _) => member_at(cx, expr.id, target, env),
+        hir::ExprKind::MethodCall(object, name, _) => {
+            let ty = cx.need_self_determined_type(object, env);
+            if ty.is_error() {
+                return Err(());
+            }
+            if ty.get_container().is_some() || ty.get_enum().is_some() || ty.is_string() {
+                return Ok(None);
+            }
+            let class = match ty.get_class() {
+                Some(x) => x,
+                None => {
+                    cx.emit(
+                        DiagBuilder2::error(format!(
+                            "cannot call method `{}` on value of type `{}`",
+                            name, ty
+                        ))
+                        .span(name.span)
+                        .add_note(format!(
+                            "`{}` is not a class handle",
+                            cx.span(object).extract()
+                        ))
+                        .span(cx.span(object)),
+                    );
+                    return Err(());
+                }
+            };
+            let details = cx.class_details(class.ast.id(), class.env)?;
+            match details.find_method(name.value) {
+                Some(m) => Ok(Some(*m)),
+                None => {
+                    cx.emit(
+                        DiagBuilder2::error(format!("no method `{}` in class `{}`", name, ty))
+                            .span(name.span),
+                    );
+                    Err(())
+                }
+            }
+        }
+        hir::ExprKind::New(_) => {
+            let class = match cx.type_of_expr(Ref(expr), env).get_class() {
+                Some(x) => x,
+                None => return Err(()),
+            };
+            let details = cx.class_details(class.ast.id(), class.env)?;
+            Ok(details.find_method(constructor_name()).cloned())
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Find the methods overriding a virtual method in specializations of classes
+/// derived from a class.
+///
+/// Returns the details of each derived class specialization whose base is the
+/// specialization in `details`, together with the method called on objects of
+/// that specialization.
+pub fn virtual_overrides<'a>(
+    cx: &impl Context<'a>,
+    details: &ClassDetails<'a>,
+    method: &ClassMember,
+) -> Vec<(Arc<ClassDetails<'a>>, ClassMember)> {
+    let classes: HashSet<NodeId> = cx.gcx().classes().collect();
+    let mut overrides = vec![];
+    for env in cx.param_envs() {
+        let id = match cx.param_env_data(env).module() {
+            Some(id) if id != details.hir.id && classes.contains(&id) => id,
+            _ => continue,
+        };
+        let derived = match cx.class_details(id, env) {
+            Ok(x) => x,
+            Err(()) => continue,
+        };
+        match derived.find_class(details.hir.id) {
+            Some(base) if base.env == details.env => (),
+            _ => continue,
+        }
+        match derived.find_method(method.name) {
+            Some(&m) if m.id != method.id => overrides.push((derived, m)),
+            _ => (),
+        }
+    }
+    overrides
+}
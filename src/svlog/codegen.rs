@@ -3,17 +3,22 @@
 //! This module implements LLHD code generation.
 
 use crate::{
+    ast_map::AstNode,
+    class::{self, ClassDetails},
+    container,
     crate_prelude::*,
-    hir::{AccessedNode, HirNode},
+    hir::{AccessTable, AccessedNode, HirNode},
+    inst_details::{inst_array_element, inst_array_len, inst_array_offsets},
     port_list::PortList,
-    resolver::InstTarget,
+    resolver::{hierarchical_scope_name, InstTarget},
     ty::UnpackedType,
+    typeck::ext_port_expr_bits,
     value::{Value, ValueKind},
     ParamEnv,
 };
 use num::{BigInt, One, ToPrimitive, Zero};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
     iter::{once, repeat},
     ops::{Deref, DerefMut},
     rc::Rc,
@@ -51,6 +56,11 @@ impl<'gcx, C> CodeGenerator<'gcx, C> {
 struct Tables<'gcx> {
     module_defs: HashMap<NodeEnvId, Result<Rc<EmittedModule<'gcx>>>>,
     module_signatures: HashMap<NodeEnvId, (llhd::ir::UnitName, llhd::ir::Signature)>,
+    function_defs: HashMap<NodeEnvId, Result<Rc<EmittedFunction>>>,
+    module_exports: HashMap<NodeEnvId, BTreeSet<NodeId>>,
+    resolution_fns:
+        HashMap<(ast::NetType, usize, usize), (llhd::ir::UnitName, llhd::ir::Signature)>,
+    class_ids: HashMap<(NodeId, ParamEnv), usize>,
     interned_types: HashMap<&'gcx UnpackedType<'gcx>, Result<llhd::Type>>,
 }
 
@@ -65,7 +75,7 @@ impl<'gcx, C> Deref for CodeGenerator<'gcx, C> {
 impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
     /// Emit the code for a module and all its dependent modules.
     pub fn emit_module(&mut self, id: NodeId) -> Result<Rc<EmittedModule<'gcx>>> {
-        self.emit_module_with_env(id, self.default_param_env())
+        self.emit_module_entity(id, self.default_param_env(), true)
     }
 
     /// Emit the code for a module and all its dependent modules.
@@ -73,6 +83,20 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
         &mut self,
         id: NodeId,
         env: ParamEnv,
+    ) -> Result<Rc<EmittedModule<'gcx>>> {
+        self.emit_module_entity(id, env, false)
+    }
+
+    /// Emit the code for a module and all its dependent modules.
+    ///
+    /// The `root` module of the design collects the declarations accessed
+    /// hierarchically through the instances below it, and finishes the
+    /// simulation once all programs in the design have completed.
+    fn emit_module_entity(
+        &mut self,
+        id: NodeId,
+        env: ParamEnv,
+        root: bool,
     ) -> Result<Rc<EmittedModule<'gcx>>> {
         if let Some(x) = self.tables.module_defs.get(&id.env(env)) {
             return x.clone();
@@ -88,11 +112,54 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
             emit_port_details(self.cx, hir, env);
         }
 
+        // Find the declarations that are accessed hierarchically through the
+        // instances below the root.
+        if root {
+            self.collect_module_exports(&hir.block, env)?;
+        }
+
         // Determine entity type and port names.
-        let ports = self.determine_module_ports(&hir.ports_new.int, env)?;
+        let mut ports = self.determine_module_ports(&hir.ports_new.int, env)?;
+
+        // Package variables are passed through the hierarchy as additional
+        // outputs, such that each ends up as a single signal at the top.
+        let mut globals = BTreeSet::new();
+        self.collect_package_vars(&hir.block, env, &mut globals)?;
+        for &id in &globals {
+            let ty = self.type_of(id, env)?;
+            ports.sig.add_output(llhd::signal_ty(self.emit_type(ty)?));
+        }
+        ports.globals = globals.into_iter().collect();
+
+        // Declarations accessed hierarchically from outside the module, as in
+        // `foo.x`, are passed up as additional outputs as well.
+        let exports = self
+            .tables
+            .module_exports
+            .get(&id.env(env))
+            .cloned()
+            .unwrap_or_default();
+        for &id in &exports {
+            let ty = self.type_of(id, env)?;
+            ports.sig.add_output(llhd::signal_ty(self.emit_type(ty)?));
+        }
+        ports.exports = exports.into_iter().collect();
+
+        // Modules containing programs signal the completion of the programs
+        // through an additional output, such that the root can finish the
+        // simulation once all of them are done.
+        let program = hir.kind == ast::ModuleKind::Program;
+        if !root && self.contains_programs(&hir.block, program, env)? {
+            ports.sig.add_output(llhd::signal_ty(llhd::int_ty(1)));
+            ports.program_done = true;
+        }
 
-        // Pick an entity name.
+        // Pick an entity name. Nested modules are prefixed with the name of
+        // their parent.
         let mut entity_name: String = hir.name.value.into();
+        if let Some(parent) = self.scope_location(hir.ast).scope.as_all().get_module() {
+            entity_name = format!("{}.{}", parent.name, entity_name);
+        }
         if env != self.default_param_env() {
             entity_name.push_str(&format!(".param{}", env.0));
         }
@@ -114,6 +181,16 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
             interned_lvalues: Default::default(),
             interned_rvalues: Default::default(),
             shadows: Default::default(),
+            inlined: Default::default(),
+            this: None,
+            sync_signals: Default::default(),
+            generations: Default::default(),
+            sampled: Default::default(),
+            fork_branch: Default::default(),
+            exits: Default::default(),
+            nets: Default::default(),
+            program,
+            program_done: Default::default(),
         };
 
         // Assign proper port names and collect ports into a lookup table.
@@ -125,7 +202,55 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
         for (index, port) in ports.outputs.iter().enumerate() {
             let arg = gen.builder.output_arg(index);
             gen.builder.set_name(arg, port.name.clone());
-            gen.values.insert(port.accnode, arg);
+            match port.kind {
+                ModulePortKind::InoutValue | ModulePortKind::InoutUnknown => (),
+                _ => {
+                    gen.values.insert(port.accnode, arg);
+                }
+            }
+        }
+
+        for (index, &id) in ports.globals.iter().enumerate() {
+            let arg = gen.builder.output_arg(ports.outputs.len() + index);
+            let name = match (enclosing_package(gen.cx, id), gen.hir_of(id)?) {
+                (Some(pkg), HirNode::VarDecl(decl)) => format!("{}.{}", pkg.name, decl.name),
+                _ => unreachable!(),
+            };
+            gen.builder.set_name(arg, name);
+            gen.values.insert(id.into(), arg);
+        }
+        let first_export = ports.outputs.len() + ports.globals.len();
+        for (index, &id) in ports.exports.iter().enumerate() {
+            let arg = gen.builder.output_arg(first_export + index);
+            let name = match gen.hir_of(id)? {
+                HirNode::VarDecl(decl) => format!("{}.export", decl.name),
+                _ => unreachable!(),
+            };
+            gen.builder.set_name(arg, name);
+        }
+
+        // Inout ports are nets whose drivers within the module are resolved
+        // into the value and unknown outputs of the port.
+        for (index, port) in ports.outputs.iter().enumerate() {
+            if let ModulePortKind::InoutValue = port.kind {
+                let net_ty = match port.port.kind {
+                    ast::VarKind::Net { ty, .. } => ty,
+                    ast::VarKind::Var => ast::NetType::Wire,
+                };
+                let net = ResolvedNet {
+                    ty: net_ty,
+                    width: port.ty.get_bit_size().unwrap_or(0),
+                    name: port.port.name.to_string(),
+                    signal: gen.values[&port.accnode],
+                    unknown: None,
+                    outputs: Some((
+                        gen.builder.output_arg(index),
+                        gen.builder.output_arg(index + 1),
+                    )),
+                    drivers: vec![],
+                };
+                gen.nets.insert(port.accnode, net);
+            }
         }
 
         debug!("  Ports:");
@@ -140,10 +265,26 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
 
         // Emit the actual contents of the entity.
         gen.emit_module_block(id, env, &hir.block, &entity_name)?;
+        let done = match ports.program_done {
+            true => {
+                let arg = gen.builder.output_arg(first_export + ports.exports.len());
+                gen.builder.set_name(arg, "program.done".to_string());
+                Some(arg)
+            }
+            false => None,
+        };
+        gen.emit_program_done(&entity_name, done);
 
-        // Assign default values to undriven output ports.
+        // Resolve the drivers of the inout ports.
         for port in ports.outputs.iter() {
-            let value = gen.values[&port.accnode];
+            if let Some(net) = gen.nets.remove(&port.accnode) {
+                gen.emit_net_resolution(net)?;
+            }
+        }
+
+        // Assign default values to undriven output ports.
+        for (index, port) in ports.outputs.iter().enumerate() {
+            let value = gen.builder.output_arg(index);
             let driven = gen
                 .builder
                 .all_insts()
@@ -166,9 +307,40 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
             )?;
             let zero_time = llhd::value::TimeValue::new(num::zero(), 0, 0);
             let zero_time = gen.builder.ins().const_time(zero_time);
-            gen.builder
-                .ins()
-                .drv(gen.values[&port.accnode], default_value, zero_time);
+            gen.builder.ins().drv(value, default_value, zero_time);
+        }
+
+        // Assign the initial values of package variables.
+        for (index, &id) in ports.globals.iter().enumerate() {
+            let init = match gen.hir_of(id)? {
+                HirNode::VarDecl(decl) => decl.init,
+                _ => unreachable!(),
+            };
+            if let Some(init) = init {
+                let value = gen.builder.output_arg(ports.outputs.len() + index);
+                let init = gen.emit_const(gen.constant_value_of(init, env), env, gen.span(init))?;
+                let zero_time = llhd::value::TimeValue::new(num::zero(), 0, 0);
+                let zero_time = gen.builder.ins().const_time(zero_time);
+                gen.builder.ins().drv(value, init, zero_time);
+            }
+        }
+
+        // Connect the exported declarations to their outputs, and assign the
+        // initial values of exported variables.
+        for (index, &id) in ports.exports.iter().enumerate() {
+            let arg = gen.builder.output_arg(first_export + index);
+            let value = gen.emitted_value(id);
+            gen.builder.ins().con(value, arg);
+            let init = match gen.hir_of(id)? {
+                HirNode::VarDecl(decl) if decl.kind.is_var() => decl.init,
+                _ => None,
+            };
+            if let Some(init) = init {
+                let init = gen.emit_const(gen.constant_value_of(init, env), env, gen.span(init))?;
+                let zero_time = llhd::value::TimeValue::new(num::zero(), 0, 0);
+                let zero_time = gen.builder.ins().const_time(zero_time);
+                gen.builder.ins().drv(arg, init, zero_time);
+            }
         }
 
         let unit = self.into.add_unit(ent);
@@ -258,6 +430,27 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
                         sig.add_input(llty);
                         inputs.push(mp);
                     }
+                    // Inout ports read the resolved value of the connected
+                    // net, and drive their contribution to it as a value and
+                    // an unknown output.
+                    ast::PortDir::Inout if ty.coalesces_to_llhd_scalar() => {
+                        for (suffix, kind) in vec![
+                            ("drv", ModulePortKind::InoutValue),
+                            ("unk", ModulePortKind::InoutUnknown),
+                        ] {
+                            sig.add_output(llty.clone());
+                            outputs.push(ModulePort {
+                                port,
+                                ty,
+                                name: format!("{}.{}", port.name, suffix),
+                                accnode: mp.accnode,
+                                default: None,
+                                kind,
+                            });
+                        }
+                        sig.add_input(llty);
+                        inputs.push(mp);
+                    }
                     ast::PortDir::Inout | ast::PortDir::Output => {
                         sig.add_output(llty);
                         outputs.push(mp);
@@ -272,6 +465,9 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
             sig,
             inputs,
             outputs,
+            globals: vec![],
+            exports: vec![],
+            program_done: false,
         })
     }
 
@@ -298,9 +494,7 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
         let mut result = vec![];
         for x in signals {
             let (decl_id, name, default) = x?;
-            let mut sig_ty = self.type_of(decl_id, intf.env)?.clone();
-            sig_ty.dims.extend(dims);
-            let sig_ty = sig_ty.intern(self.cx);
+            let sig_ty = transposed_type(self.cx, self.type_of(decl_id, intf.env)?, dims);
             result.push(IntfSignal {
                 decl_id,
                 ty: sig_ty,
@@ -312,11 +506,15 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
     }
 
     /// Emit the code for a procedure.
+    ///
+    /// Procedures in a program run in the reactive region, and the initial
+    /// procedures of a program drive an additional output once they complete.
     fn emit_procedure(
         &mut self,
         id: NodeId,
         env: ParamEnv,
         name_prefix: &str,
+        program: bool,
     ) -> Result<EmittedProcedure> {
         let hir = match self.hir_of(id)? {
             HirNode::Proc(x) => x,
@@ -326,34 +524,14 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
         // Find the accessed nodes.
         let acc = self.accessed_nodes(hir.stmt, env)?;
         trace!("Process accesses {:#?}", acc);
-        let mut sig = llhd::ir::Signature::new();
-        let mut inputs = vec![];
-        let mut outputs = vec![];
-        for &id in acc.read.iter().filter(|id| !acc.written.contains(id)) {
-            sig.add_input(llhd::signal_ty(self.emit_type(match id {
-                AccessedNode::Regular(id) => self.type_of(id, env)?,
-                AccessedNode::Intf(intf, id) => {
-                    let intf_ty = self.type_of(intf, env)?;
-                    let intf_ty_inner = intf_ty.resolve_full().core.get_interface().unwrap();
-                    let mut sig_ty = self.type_of(id, intf_ty_inner.env)?.clone();
-                    sig_ty.dims.extend(&intf_ty.dims);
-                    sig_ty.intern(self.cx)
-                }
-            })?));
-            inputs.push(id);
-        }
-        for &id in acc.written.iter() {
-            sig.add_output(llhd::signal_ty(self.emit_type(match id {
-                AccessedNode::Regular(id) => self.type_of(id, env)?,
-                AccessedNode::Intf(intf, id) => {
-                    let intf_ty = self.type_of(intf, env)?;
-                    let intf_ty_inner = intf_ty.resolve_full().core.get_interface().unwrap();
-                    let mut sig_ty = self.type_of(id, intf_ty_inner.env)?.clone();
-                    sig_ty.dims.extend(&intf_ty.dims);
-                    sig_ty.intern(self.cx)
-                }
-            })?));
-            outputs.push(id);
+        let (mut sig, inputs, outputs) = self.emit_process_signature(&acc, env)?;
+        let started = self.started_stmts(hir.stmt)?;
+        let mut sync_inputs = vec![];
+        let mut sync_outputs = vec![];
+        self.add_sync_ports(&started, env, &mut sig, &mut sync_inputs, &mut sync_outputs)?;
+        let program_done = program && hir.kind == ast::ProcedureKind::Initial;
+        if program_done {
+            sig.add_output(llhd::signal_ty(llhd::int_ty(1)));
         }
         trace!("Process Inputs: {:?}", inputs);
         trace!("Process Outputs: {:?}", outputs);
@@ -383,41 +561,8 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
         let mut builder = llhd::ir::UnitBuilder::new_anonymous(&mut prok);
 
         // Assign names to inputs and outputs.
-        let guess_name = |id| {
-            let (prefix, id) = match id {
-                AccessedNode::Regular(id) => (None, id),
-                AccessedNode::Intf(inst_id, id) => {
-                    let inst_name = match self.hir_of(inst_id).ok()? {
-                        HirNode::IntPort(x) => Some(x.name),
-                        HirNode::Inst(x) => Some(x.name),
-                        _ => None,
-                    };
-                    (inst_name, id)
-                }
-            };
-            let name = match self.hir_of(id).ok()? {
-                HirNode::VarDecl(x) => Some(x.name),
-                HirNode::IntPort(x) => Some(x.name),
-                _ => None,
-            };
-            match (prefix, name) {
-                (Some(prefix), Some(name)) => Some(format!("{}.{}", prefix, name)),
-                (None, Some(name)) => Some(format!("{}", name)),
-                _ => None,
-            }
-        };
-        for (i, &id) in inputs.iter().enumerate() {
-            if let Some(name) = guess_name(id) {
-                let value = builder.input_arg(i);
-                builder.set_name(value, name);
-            }
-        }
-        for (i, &id) in outputs.iter().enumerate() {
-            if let Some(name) = guess_name(id) {
-                let value = builder.output_arg(i);
-                builder.set_name(value, name);
-            }
-        }
+        self.name_process_args(&mut builder, &inputs, &outputs);
+        self.name_sync_args(&mut builder, &inputs, &outputs, &sync_inputs, &sync_outputs);
 
         // Create a mapping from read/written nodes to process parameters.
         let mut values = HashMap::new();
@@ -436,6 +581,16 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
             interned_lvalues: Default::default(),
             interned_rvalues: Default::default(),
             shadows: Default::default(),
+            inlined: Default::default(),
+            this: None,
+            sync_signals: Default::default(),
+            generations: Default::default(),
+            sampled: Default::default(),
+            fork_branch: Default::default(),
+            exits: Default::default(),
+            nets: Default::default(),
+            program,
+            program_done: Default::default(),
         };
         let entry_blk = pg.add_nameless_block();
         pg.builder.append_to(entry_blk);
@@ -443,20 +598,9 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
         // Determine which values are both read and written. These require
         // shadow variables to emulate the expected behaviour under blocking
         // assignments.
-        let input_set: HashSet<_> = acc.read.iter().cloned().collect();
-        let output_set: HashSet<_> = acc.written.iter().cloned().collect();
-        for &id in input_set.intersection(&output_set) {
-            let init = pg.builder.ins().prb(pg.values[&id.into()]);
-            let shadow = pg.builder.ins().var(init);
-            if let Some(name) = pg
-                .builder
-                .get_name(pg.values[&id.into()])
-                .map(|name| format!("{}.shadow", name))
-            {
-                pg.builder.set_name(shadow, name);
-            }
-            pg.shadows.insert(id.into(), shadow);
-        }
+        pg.emit_shadows(&acc);
+        pg.map_sync_args(&sync_inputs, &sync_outputs);
+        pg.emit_generation_vars(&started);
 
         // Emit prologue and determine which basic block to jump back to.
         let head_blk = match hir.kind {
@@ -490,6 +634,10 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
                 pg.emit_shadow_update();
                 entry_blk // This block is ignored for final blocks
             }
+            ast::ProcedureKind::Initial => {
+                pg.emit_reactive_wait();
+                entry_blk
+            }
             _ => entry_blk,
         };
 
@@ -499,6 +647,14 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
         // Emit epilogue.
         match hir.kind {
             ast::ProcedureKind::Initial => {
+                if program_done {
+                    let done = pg.builder.output_arg(outputs.len() + sync_outputs.len());
+                    pg.builder.set_name(done, "done".to_string());
+                    let one = pg.builder.ins().const_int((1, 1));
+                    let delay = llhd::value::TimeValue::new(num::zero(), 0, 1);
+                    let delay = pg.builder.ins().const_time(delay);
+                    pg.builder.ins().drv(done, one, delay);
+                }
                 pg.builder.ins().halt();
             }
             ast::ProcedureKind::Always
@@ -516,1143 +672,4501 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
             unit: self.into.add_unit(prok),
             inputs,
             outputs,
+            sync_inputs,
+            sync_outputs,
+            program_done,
         })
     }
 
-    /// Map a type to an LLHD type (interned).
-    fn emit_type(&mut self, ty: &'gcx UnpackedType<'gcx>) -> Result<llhd::Type> {
-        if let Some(x) = self.tables.interned_types.get(&ty) {
-            x.clone()
-        } else {
-            let x = self.emit_type_uninterned(ty);
-            self.tables.interned_types.insert(ty, x.clone());
-            x
+    /// Determine the signature of a process from the nodes it accesses.
+    ///
+    /// Returns the signature, and the nodes that correspond to its inputs and
+    /// outputs.
+    fn emit_process_signature(
+        &mut self,
+        acc: &AccessTable,
+        env: ParamEnv,
+    ) -> Result<(llhd::ir::Signature, Vec<AccessedNode>, Vec<AccessedNode>)> {
+        let mut sig = llhd::ir::Signature::new();
+        let mut inputs = vec![];
+        let mut outputs = vec![];
+        for &id in acc.read.iter().filter(|id| !acc.written.contains(id)) {
+            sig.add_input(llhd::signal_ty(self.emit_type(match id {
+                AccessedNode::Regular(id) => self.type_of(id, env)?,
+                AccessedNode::Intf(inst, id) => self.transposed_signal_type(inst, id, env)?,
+            })?));
+            inputs.push(id);
         }
+        for &id in acc.written.iter() {
+            sig.add_output(llhd::signal_ty(self.emit_type(match id {
+                AccessedNode::Regular(id) => self.type_of(id, env)?,
+                AccessedNode::Intf(inst, id) => self.transposed_signal_type(inst, id, env)?,
+            })?));
+            outputs.push(id);
+        }
+        Ok((sig, inputs, outputs))
     }
 
-    /// Map a type to an LLHD type.
-    fn emit_type_uninterned(&mut self, ty: &'gcx UnpackedType<'gcx>) -> Result<llhd::Type> {
-        if ty.is_error() {
-            return Err(());
-        }
-        let ty = ty.resolve_full();
+    /// Determine the type of the signal that carries a declaration across all
+    /// elements of an interface or module instance array.
+    fn transposed_signal_type(
+        &self,
+        inst: NodeId,
+        decl: NodeId,
+        env: ParamEnv,
+    ) -> Result<&'gcx UnpackedType<'gcx>> {
+        let inst_ty = self.type_of(inst, env)?;
+        let inner_env = match inst_ty.resolve_full().core {
+            ty::UnpackedCore::Interface(ref x) => x.env,
+            ty::UnpackedCore::Module(ref x) => x.env,
+            _ => unreachable!(),
+        };
+        Ok(transposed_type(
+            self.cx,
+            self.type_of(decl, inner_env)?,
+            &inst_ty.dims,
+        ))
+    }
 
-        // Handle things that coalesce easily to scalars.
-        if ty.coalesces_to_llhd_scalar() {
-            return Ok(llhd::int_ty(ty.get_bit_size().unwrap()));
+    /// Assign names to the inputs and outputs of a process.
+    fn name_process_args(
+        &self,
+        builder: &mut llhd::ir::UnitBuilder,
+        inputs: &[AccessedNode],
+        outputs: &[AccessedNode],
+    ) {
+        let guess_name = |id| {
+            let (prefix, id) = match id {
+                AccessedNode::Regular(id) => (None, id),
+                AccessedNode::Intf(inst_id, id) => {
+                    let inst_name = match self.hir_of(inst_id).ok()? {
+                        HirNode::IntPort(x) => Some(x.name),
+                        HirNode::Inst(x) => Some(x.name),
+                        _ => None,
+                    };
+                    (inst_name, id)
+                }
+            };
+            let name = match self.hir_of(id).ok()? {
+                HirNode::VarDecl(x) => Some(x.name),
+                HirNode::IntPort(x) => Some(x.name),
+                _ => None,
+            };
+            match (prefix, name) {
+                (Some(prefix), Some(name)) => Some(format!("{}.{}", prefix, name)),
+                (None, Some(name)) => Some(format!("{}", name)),
+                _ => None,
+            }
+        };
+        for (i, &id) in inputs.iter().enumerate() {
+            if let Some(name) = guess_name(id) {
+                let value = builder.input_arg(i);
+                builder.set_name(value, name);
+            }
+        }
+        for (i, &id) in outputs.iter().enumerate() {
+            if let Some(name) = guess_name(id) {
+                let value = builder.output_arg(i);
+                builder.set_name(value, name);
+            }
         }
+    }
 
-        // Handle arrays.
-        if let Some(dim) = ty.outermost_dim() {
-            let size = match dim.get_size() {
-                Some(size) => size,
-                None => panic!("cannot map unsized array `{}` to LLHD", ty),
-            };
-            let inner = ty.pop_dim(self.cx).unwrap();
-            return Ok(llhd::array_ty(size, self.emit_type(inner)?));
+    /// Find the deferred assertions and fork-join blocks started by a
+    /// procedure or fork branch.
+    ///
+    /// Fork-join blocks nested within the branches of another fork-join block
+    /// are started by that branch, and are not included.
+    fn started_stmts(&self, stmt_id: NodeId) -> Result<Vec<&'gcx hir::Stmt>> {
+        let mut finder = StartedFinder {
+            cx: self.cx,
+            stmts: vec![],
+        };
+        hir::Visitor::visit_node_with_id(&mut finder, stmt_id, false);
+        Ok(finder.stmts)
+    }
+
+    /// Determine the name of a synchronization signal.
+    fn sync_signal_name(&self, signal: SyncSignal) -> String {
+        let stmt_name = |id: NodeId| match self.hir_of(id) {
+            Ok(HirNode::Stmt(hir::Stmt {
+                label: Some(label), ..
+            })) => label.value.to_string(),
+            Ok(HirNode::Stmt(hir::Stmt {
+                kind: hir::StmtKind::Assertion { .. },
+                ..
+            })) => format!("assert{}", id.as_usize()),
+            _ => format!("fork{}", id.as_usize()),
+        };
+        match signal {
+            SyncSignal::Ctl(id) => format!("{}.ctl", stmt_name(id)),
+            SyncSignal::Cond(id) => format!("{}.cond", stmt_name(id)),
+            SyncSignal::Value(stmt_id, id) => match self.hir_of(id) {
+                Ok(HirNode::VarDecl(x)) => format!("{}.{}", stmt_name(stmt_id), x.name),
+                _ => format!("{}.value{}", stmt_name(stmt_id), id.as_usize()),
+            },
+            SyncSignal::Done(id) => format!("fork.branch{}.done", id.as_usize()),
         }
+    }
 
-        // Handle structs.
-        if let Some(strukt) = ty.get_struct() {
-            let mut types = vec![];
-            for member in &strukt.members {
-                types.push(self.emit_type(member.ty)?);
+    /// Determine the type of a synchronization signal.
+    fn sync_signal_type(&mut self, signal: SyncSignal, env: ParamEnv) -> Result<llhd::Type> {
+        match signal {
+            SyncSignal::Ctl(..) | SyncSignal::Done(..) => Ok(llhd::int_ty(32)),
+            SyncSignal::Cond(..) => Ok(llhd::int_ty(1)),
+            SyncSignal::Value(_, id) => {
+                let ty = self.type_of(id, env)?;
+                self.emit_type(ty)
             }
-            return Ok(llhd::struct_ty(types));
         }
+    }
 
-        // Handle packed types.
-        if let Some(packed) = ty.get_packed() {
-            let packed = packed.resolve_full();
-            return match packed.core {
-                ty::PackedCore::Error => Ok(llhd::void_ty()),
-                ty::PackedCore::Void => Ok(llhd::void_ty()),
-                ty::PackedCore::IntAtom(ty::IntAtomType::Time) => Ok(llhd::time_ty()),
-                ty::PackedCore::Enum(ref enm) => self.emit_type(enm.base.to_unpacked(self.cx)),
-                _ => unreachable!("emitting `{}` should have been handled above", packed),
-            };
+    /// Find the procedure a statement is nested in.
+    fn enclosing_proc(&self, stmt_id: NodeId) -> Result<NodeId> {
+        let mut proc_id = stmt_id;
+        while let Some(parent) = self.parent_node_id(proc_id) {
+            proc_id = parent;
+            if let HirNode::Proc(_) = self.hir_of(proc_id)? {
+                break;
+            }
         }
+        Ok(proc_id)
+    }
 
-        // Everything else we cannot do.
-        error!("Cannot map type {:#?}", ty);
-        panic!("cannot map `{}` to LLHD", ty);
+    /// Find the variables passed by value to a statement executed in a
+    /// separate process.
+    ///
+    /// These are the variables read by the statement which are declared in the
+    /// procedure enclosing the statement that started it, for example the
+    /// procedure containing a deferred assertion or fork-join block. Their
+    /// value is captured when the process is started.
+    fn passed_values(
+        &self,
+        stmt_id: NodeId,
+        started_id: NodeId,
+        env: ParamEnv,
+    ) -> Result<Vec<NodeId>> {
+        let acc = self.accessed_nodes(stmt_id, env)?;
+        let proc_id = self.enclosing_proc(started_id)?;
+        Ok(acc
+            .read
+            .iter()
+            .filter(|node| !acc.written.contains(node))
+            .map(|node| node.id())
+            .filter(|&id| self.is_parent_of(proc_id, id))
+            .collect())
     }
 
-    /// Execute the initialization step of a generate loop.
-    fn execute_genvar_init(&mut self, id: NodeId, env: ParamEnv) -> Result<ParamEnv> {
-        let hir = self.hir_of(id)?;
-        match hir {
-            HirNode::GenvarDecl(_) => Ok(env),
-            HirNode::Stmt(stmt) => match stmt.kind {
-                hir::StmtKind::Assign {
-                    lhs,
-                    rhs,
-                    kind: hir::AssignKind::Block(ast::AssignOp::Identity),
-                } => {
-                    let target_id = self.resolve_node(lhs, env)?;
-                    let init_value = self.constant_value_of(rhs, env);
-                    let mut env_data = self.param_env_data(env).clone();
-                    env_data.set_value(target_id, init_value);
-                    Ok(self.intern_param_env(env_data))
+    /// Find the variables passed by value to the action blocks of a deferred
+    /// assertion, or to any branch of a fork-join block.
+    fn started_values(&self, stmt: &hir::Stmt, env: ParamEnv) -> Result<Vec<NodeId>> {
+        let mut values = BTreeSet::new();
+        match stmt.kind {
+            hir::StmtKind::Assertion {
+                pass_stmt,
+                fail_stmt,
+                ..
+            } => {
+                for stmt_id in pass_stmt.into_iter().chain(fail_stmt) {
+                    values.extend(self.passed_values(stmt_id, stmt.id, env)?);
                 }
-                _ => unreachable!(),
-            },
-            _ => unreachable!(),
+            }
+            hir::StmtKind::Fork {
+                ref decls,
+                ref branches,
+                ..
+            } => {
+                // Lower the declarations first, such that the variables they
+                // declare are known to be local to the enclosing procedure.
+                for &decl_id in decls {
+                    self.hir_of(decl_id)?;
+                }
+                for &branch_id in branches {
+                    values.extend(self.passed_values(branch_id, stmt.id, env)?);
+                }
+            }
+            _ => (),
         }
+        Ok(values.into_iter().collect())
     }
 
-    /// Execute the iteration step of a generate loop.
-    fn execute_genvar_step(&mut self, id: NodeId, env: ParamEnv) -> Result<ParamEnv> {
-        let hir = self.hir_of(id)?;
-        let mut env_data = self.param_env_data(env).clone();
-        let next = match hir {
-            HirNode::Expr(expr) => match expr.kind {
-                hir::ExprKind::Unary(op, target_id) => {
-                    let target_id = self.resolve_node(target_id, env)?;
-                    let current_value = self.constant_value_of(target_id, env);
-                    let next_value = match current_value.kind {
-                        ValueKind::Int(ref v, ..) => match op {
-                            hir::UnaryOp::PostInc | hir::UnaryOp::PreInc => Some(v + 1),
-                            hir::UnaryOp::PostDec | hir::UnaryOp::PreDec => Some(v - 1),
-                            _ => None,
-                        }
-                        .map(|v| value::make_int(current_value.ty, v)),
-                        _ => unreachable!(),
-                    };
-                    next_value.map(|v| (target_id, self.intern_value(v)))
-                }
-                hir::ExprKind::Assign { .. } => {
-                    let mir = self.mir_rvalue(id, env);
-                    match mir.kind {
-                        mir::RvalueKind::Error => return Err(()),
-                        mir::RvalueKind::Assignment { lvalue, rvalue, .. } => {
-                            let target_id = match lvalue.kind {
-                                mir::LvalueKind::Error => return Err(()),
-                                mir::LvalueKind::Genvar(id) => id,
-                                _ => unreachable!(),
-                            };
-                            let next_value = self.const_mir_rvalue(Ref(rvalue));
-                            Some((target_id, next_value))
-                        }
-                        _ => unreachable!(),
+    /// Assign names to the synchronization inputs and outputs of a process.
+    fn name_sync_args(
+        &self,
+        builder: &mut llhd::ir::UnitBuilder,
+        inputs: &[AccessedNode],
+        outputs: &[AccessedNode],
+        sync_inputs: &[SyncSignal],
+        sync_outputs: &[SyncSignal],
+    ) {
+        for (i, &signal) in sync_inputs.iter().enumerate() {
+            let value = builder.input_arg(inputs.len() + i);
+            builder.set_name(value, self.sync_signal_name(signal));
+        }
+        for (i, &signal) in sync_outputs.iter().enumerate() {
+            let value = builder.output_arg(outputs.len() + i);
+            builder.set_name(value, self.sync_signal_name(signal));
+        }
+    }
+
+    /// Add the signals that synchronize the statements started by a process
+    /// with the processes executing them to the process' signature.
+    fn add_sync_ports(
+        &mut self,
+        stmts: &[&hir::Stmt],
+        env: ParamEnv,
+        sig: &mut llhd::ir::Signature,
+        inputs: &mut Vec<SyncSignal>,
+        outputs: &mut Vec<SyncSignal>,
+    ) -> Result<()> {
+        for stmt in stmts {
+            let mut signals = vec![SyncSignal::Ctl(stmt.id)];
+            for id in self.started_values(stmt, env)? {
+                signals.push(SyncSignal::Value(stmt.id, id));
+            }
+            for signal in signals {
+                sig.add_output(llhd::signal_ty(self.sync_signal_type(signal, env)?));
+                outputs.push(signal);
+            }
+            match stmt.kind {
+                hir::StmtKind::Fork { ref branches, .. } => {
+                    for &branch_id in branches {
+                        sig.add_input(llhd::signal_ty(llhd::int_ty(32)));
+                        inputs.push(SyncSignal::Done(branch_id));
                     }
                 }
-                _ => None,
-            },
-            _ => None,
-        };
-        match next {
-            Some((target_id, next_value)) => {
-                env_data.set_value(target_id, next_value);
-                return Ok(self.intern_param_env(env_data));
+                _ => {
+                    sig.add_output(llhd::signal_ty(llhd::int_ty(1)));
+                    outputs.push(SyncSignal::Cond(stmt.id));
+                }
             }
-            None => {
+        }
+        Ok(())
+    }
+
+    /// Emit the process that executes the action blocks of a deferred
+    /// assertion.
+    ///
+    /// The enclosing procedure checks the condition and passes its outcome,
+    /// together with the local variables read by the action blocks, by
+    /// advancing the generation on the assertion's control signal. The process
+    /// then waits for a delta cycle before executing the action blocks. If the
+    /// assertion is executed again in the meantime, for example because a
+    /// combinational procedure re-evaluates, the pending report is flushed in
+    /// favour of the new one. LLHD offers no way to wait for the end of a time
+    /// step, so this approximates the Observed region of `#0` assertions as
+    /// well as the Postponed region of `final` assertions.
+    fn emit_deferred_assertion(
+        &mut self,
+        id: NodeId,
+        env: ParamEnv,
+        name_prefix: &str,
+        program: bool,
+    ) -> Result<EmittedProcedure> {
+        let hir = match self.hir_of(id)? {
+            HirNode::Stmt(x) => x,
+            _ => unreachable!(),
+        };
+        let (pass_stmt, fail_stmt) = match hir.kind {
+            hir::StmtKind::Assertion {
+                pass_stmt,
+                fail_stmt,
+                ..
+            } => (pass_stmt, fail_stmt),
+            _ => unreachable!(),
+        };
+
+        // Find the accessed nodes of the action blocks. The variables local to
+        // the enclosing procedure are passed by value and cannot be assigned.
+        let mut acc = AccessTable {
+            node_id: id,
+            read: Default::default(),
+            written: Default::default(),
+        };
+        for stmt_id in pass_stmt.into_iter().chain(fail_stmt) {
+            let stmt_acc = self.accessed_nodes(stmt_id, env)?;
+            acc.read.extend(stmt_acc.read.iter().cloned());
+            acc.written.extend(stmt_acc.written.iter().cloned());
+        }
+        let proc_id = self.enclosing_proc(id)?;
+        let mut failed = false;
+        for &node in acc.written.iter() {
+            if self.is_parent_of(proc_id, node.id()) {
+                let decl = self.hir_of(node.id())?;
                 self.emit(
                     DiagBuilder2::error(format!(
-                        "{} is not a valid genvar iteration step",
-                        hir.desc_full()
+                        "unsupported: {} assigned in the action block of a deferred assertion",
+                        decl.desc_full()
                     ))
-                    .span(hir.human_span()),
+                    .span(hir.span()),
                 );
-                Err(())
+                failed = true;
             }
         }
-    }
-}
+        if failed {
+            return Err(());
+        }
+        let captured = self.started_values(hir, env)?;
+        acc.read.retain(|node| !captured.contains(&node.id()));
+        let (mut sig, inputs, outputs) = self.emit_process_signature(&acc, env)?;
+        let mut sync_inputs = vec![SyncSignal::Ctl(id), SyncSignal::Cond(id)];
+        sync_inputs.extend(
+            captured
+                .iter()
+                .map(|&value_id| SyncSignal::Value(id, value_id)),
+        );
+        for &signal in &sync_inputs {
+            sig.add_input(llhd::signal_ty(self.sync_signal_type(signal, env)?));
+        }
+        let sync_outputs = vec![];
 
-/// A code generator for functions, processes, and entities.
-struct UnitGenerator<'a, 'gcx, C> {
-    /// The global code generator.
-    gen: &'a mut CodeGenerator<'gcx, C>,
-    /// The builder into which instructions are emitted.
-    builder: &'a mut llhd::ir::UnitBuilder<'a>,
-    /// The emitted LLHD values for various nodes.
-    values: &'a mut HashMap<AccessedNode, llhd::ir::Value>,
-    /// The constant values emitted into the unit.
-    interned_consts: HashMap<Value<'gcx>, Result<llhd::ir::Value>>,
-    /// The MIR lvalues emitted into the unit.
-    interned_lvalues: HashMap<NodeId, Result<(llhd::ir::Value, Option<llhd::ir::Value>)>>,
-    /// The MIR rvalues emitted into the unit.
-    interned_rvalues: HashMap<(NodeId, Mode), Result<(llhd::ir::Value, Mode)>>,
-    /// The shadow variables introduced to handle signals which are both read
-    /// and written in a process.
-    shadows: HashMap<AccessedNode, llhd::ir::Value>,
-}
+        // Create process and entry block.
+        let proc_name = format!("{}.assert.{}.{}", name_prefix, id.as_usize(), env.0);
+        let mut prok = llhd::ir::UnitData::new(
+            llhd::ir::UnitKind::Process,
+            llhd::ir::UnitName::Local(proc_name),
+            sig,
+        );
+        let mut builder = llhd::ir::UnitBuilder::new_anonymous(&mut prok);
+        self.name_process_args(&mut builder, &inputs, &outputs);
+        self.name_sync_args(&mut builder, &inputs, &outputs, &sync_inputs, &sync_outputs);
 
-impl<'a, 'gcx, C> Deref for UnitGenerator<'a, 'gcx, C> {
-    type Target = CodeGenerator<'gcx, C>;
+        // Create a mapping from read/written nodes to process parameters.
+        let mut values = HashMap::new();
+        for (&id, arg) in inputs
+            .iter()
+            .zip(builder.input_args())
+            .chain(outputs.iter().zip(builder.output_args()))
+        {
+            values.insert(id.into(), arg);
+        }
+        let mut pg = UnitGenerator {
+            gen: self,
+            builder: &mut builder,
+            values: &mut values,
+            interned_consts: Default::default(),
+            interned_lvalues: Default::default(),
+            interned_rvalues: Default::default(),
+            shadows: Default::default(),
+            inlined: Default::default(),
+            this: None,
+            sync_signals: Default::default(),
+            generations: Default::default(),
+            sampled: Default::default(),
+            fork_branch: Default::default(),
+            exits: Default::default(),
+            nets: Default::default(),
+            program,
+            program_done: Default::default(),
+        };
+        let entry_blk = pg.add_nameless_block();
+        pg.builder.append_to(entry_blk);
+        pg.emit_shadows(&acc);
+        pg.map_sync_args(&sync_inputs, &sync_outputs);
+        let ctl = pg.sync_signals[&SyncSignal::Ctl(id)];
+        let cond = pg.sync_signals[&SyncSignal::Cond(id)];
+        let zero = pg.builder.ins().const_int((32, 0));
+        let gen = pg.builder.ins().var(zero);
+        pg.builder.set_name(gen, "gen".to_string());
+
+        // Wait for the assertion to be executed.
+        let idle_blk = pg.add_named_block("idle");
+        let wait_blk = pg.add_named_block("wait");
+        let start_blk = pg.add_named_block("start");
+        pg.builder.ins().br(idle_blk);
+        pg.builder.append_to(idle_blk);
+        let now = pg.builder.ins().prb(ctl);
+        let prev = pg.builder.ins().ld(gen);
+        let changed = pg.builder.ins().neq(now, prev);
+        pg.builder.ins().br_cond(changed, wait_blk, start_blk);
+        pg.builder.append_to(wait_blk);
+        pg.builder.ins().wait(idle_blk, vec![ctl]);
+
+        // Wait for a delta cycle, and start over if the assertion is executed
+        // again in the meantime.
+        let check_blk = pg.add_named_block("check");
+        let report_blk = pg.add_named_block("report");
+        pg.builder.append_to(start_blk);
+        pg.builder.ins().st(gen, now);
+        let one_delta = llhd::value::TimeValue::new(num::zero(), 1, 0);
+        let one_delta = pg.builder.ins().const_time(one_delta);
+        pg.builder.ins().wait_time(check_blk, one_delta, vec![ctl]);
+        pg.builder.append_to(check_blk);
+        let now = pg.builder.ins().prb(ctl);
+        let prev = pg.builder.ins().ld(gen);
+        let changed = pg.builder.ins().neq(now, prev);
+        pg.builder.ins().br_cond(changed, report_blk, idle_blk);
+
+        // Copy the values passed to the action blocks and execute them.
+        pg.builder.append_to(report_blk);
+        for &value_id in &captured {
+            let signal = pg.sync_signals[&SyncSignal::Value(id, value_id)];
+            let value = pg.builder.ins().prb(signal);
+            let value = pg.builder.ins().var(value);
+            if let Ok(HirNode::VarDecl(x)) = pg.hir_of(value_id) {
+                pg.builder.set_name(value, x.name.value.to_string());
+            }
+            pg.set_emitted_value(value_id, value);
+        }
+        pg.flush_mir();
+        pg.emit_shadow_update();
+        let cond = pg.builder.ins().prb(cond);
+        pg.emit_assertion_actions(hir, cond, env)?;
+        pg.builder.ins().br(idle_blk);
 
-    fn deref(&self) -> &CodeGenerator<'gcx, C> {
-        &self.gen
+        Ok(EmittedProcedure {
+            unit: self.into.add_unit(prok),
+            inputs,
+            outputs,
+            sync_inputs,
+            sync_outputs,
+            program_done: false,
+        })
     }
-}
 
-impl<'a, 'gcx, C> DerefMut for UnitGenerator<'a, 'gcx, C> {
-    fn deref_mut(&mut self) -> &mut CodeGenerator<'gcx, C> {
-        &mut self.gen
-    }
-}
+    /// Emit the code for a branch of a fork-join block.
+    ///
+    /// The branch is emitted as a separate process which idles until the
+    /// fork-join block's control signal indicates a new generation has been
+    /// started. The branch then executes its statement once for every
+    /// generation it has not yet executed, and signals completion by driving
+    /// the generation onto its done signal. This allows a fork-join block to
+    /// be re-entered while its branches are still running, for example in a
+    /// loop, although the pending generations all see the values passed by
+    /// the most recent start. If the control signal indicates that the branch
+    /// was disabled, the branch stops at the next point where it resumes from
+    /// a timing control.
+    fn emit_fork_branch(
+        &mut self,
+        id: NodeId,
+        fork_id: NodeId,
+        env: ParamEnv,
+        name_prefix: &str,
+        program: bool,
+    ) -> Result<EmittedProcedure> {
+        // Find the accessed nodes. Since the branch runs in its own process,
+        // the variables local to the enclosing procedure are passed to it by
+        // value and cannot be assigned.
+        let mut acc = (*self.accessed_nodes(id, env)?).clone();
+        trace!("Fork branch accesses {:#?}", acc);
+        let proc_id = self.enclosing_proc(fork_id)?;
+        let mut failed = false;
+        for &node in acc.written.iter() {
+            if self.is_parent_of(proc_id, node.id()) {
+                let hir = self.hir_of(node.id())?;
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "unsupported: {} assigned in a fork-join branch",
+                        hir.desc_full()
+                    ))
+                    .span(self.span(id))
+                    .add_note(format!(
+                        "{} is declared in the procedure enclosing the fork-join block:",
+                        hir.desc_full()
+                    ))
+                    .span(hir.human_span())
+                    .add_note(
+                        "Fork-join branches run as separate processes and receive a copy of \
+                         such variables when the fork-join block is started",
+                    ),
+                );
+                failed = true;
+            }
+        }
+        if failed {
+            return Err(());
+        }
+        let captured = self.passed_values(id, fork_id, env)?;
+        acc.read.retain(|node| !captured.contains(&node.id()));
+        let (mut sig, inputs, outputs) = self.emit_process_signature(&acc, env)?;
+        let started = self.started_stmts(id)?;
+        let mut sync_inputs = vec![SyncSignal::Ctl(fork_id)];
+        let mut sync_outputs = vec![SyncSignal::Done(id)];
+        sig.add_input(llhd::signal_ty(llhd::int_ty(32)));
+        sig.add_output(llhd::signal_ty(llhd::int_ty(32)));
+        for &value_id in &captured {
+            let signal = SyncSignal::Value(fork_id, value_id);
+            sig.add_input(llhd::signal_ty(self.sync_signal_type(signal, env)?));
+            sync_inputs.push(signal);
+        }
+        self.add_sync_ports(&started, env, &mut sig, &mut sync_inputs, &mut sync_outputs)?;
 
-impl<'a, 'b, 'gcx, C> UnitGenerator<'a, 'gcx, &'b C>
-where
-    C: Context<'gcx> + 'b,
-{
-    fn emitted_value(&self, src: impl Into<AccessedNode>) -> llhd::ir::Value {
-        let src = src.into();
-        match self.values.get(&src) {
-            Some(&v) => v,
-            None => bug_span!(
-                self.span(match src {
-                    AccessedNode::Regular(id) => id,
-                    AccessedNode::Intf(_, id) => id,
-                }),
-                self.cx,
-                "no value emitted for {:?}",
-                src
-            ),
+        // Create process and entry block.
+        let proc_name = format!("{}.fork.{}.{}", name_prefix, id.as_usize(), env.0);
+        let mut prok = llhd::ir::UnitData::new(
+            llhd::ir::UnitKind::Process,
+            llhd::ir::UnitName::Local(proc_name),
+            sig,
+        );
+        let mut builder = llhd::ir::UnitBuilder::new_anonymous(&mut prok);
+        self.name_process_args(&mut builder, &inputs, &outputs);
+        self.name_sync_args(&mut builder, &inputs, &outputs, &sync_inputs, &sync_outputs);
+
+        // Create a mapping from read/written nodes to process parameters.
+        let mut values = HashMap::new();
+        for (&id, arg) in inputs
+            .iter()
+            .zip(builder.input_args())
+            .chain(outputs.iter().zip(builder.output_args()))
+        {
+            values.insert(id.into(), arg);
         }
-    }
+        let mut pg = UnitGenerator {
+            gen: self,
+            builder: &mut builder,
+            values: &mut values,
+            interned_consts: Default::default(),
+            interned_lvalues: Default::default(),
+            interned_rvalues: Default::default(),
+            shadows: Default::default(),
+            inlined: Default::default(),
+            this: None,
+            sync_signals: Default::default(),
+            generations: Default::default(),
+            sampled: Default::default(),
+            fork_branch: Default::default(),
+            exits: Default::default(),
+            nets: Default::default(),
+            program,
+            program_done: Default::default(),
+        };
+        let entry_blk = pg.add_nameless_block();
+        pg.builder.append_to(entry_blk);
+        pg.emit_shadows(&acc);
+        pg.map_sync_args(&sync_inputs, &sync_outputs);
+        pg.emit_generation_vars(&started);
+        let ctl = pg.sync_signals[&SyncSignal::Ctl(fork_id)];
+        let done = pg.sync_signals[&SyncSignal::Done(id)];
+        let zero = pg.builder.ins().const_int((32, 0));
+        let gen = pg.builder.ins().var(zero);
+        pg.builder.set_name(gen, "gen".to_string());
+        let abort_blk = pg.add_named_block("abort");
+        pg.fork_branch = Some(ForkBranch {
+            ctl,
+            gen,
+            abort_blk,
+        });
 
-    fn set_emitted_value(&mut self, src: impl Into<AccessedNode>, value: llhd::ir::Value) {
-        let src = src.into();
-        self.values.insert(src, value);
-    }
+        // Wait for a new generation to be started. Generations started while
+        // the branch is still running are not lost, but executed one after the
+        // other once the branch is done. If the branch is disabled while idle,
+        // it only signals completion.
+        let idle_blk = pg.add_named_block("idle");
+        let wait_blk = pg.add_named_block("wait");
+        let check_blk = pg.add_named_block("check");
+        let start_blk = pg.add_named_block("start");
+        pg.builder.ins().br(idle_blk);
+        pg.builder.append_to(idle_blk);
+        let now = pg.builder.ins().prb(ctl);
+        let prev = pg.builder.ins().ld(gen);
+        let changed = pg.builder.ins().neq(now, prev);
+        pg.builder.ins().br_cond(changed, wait_blk, check_blk);
+        pg.builder.append_to(wait_blk);
+        pg.builder.ins().wait(idle_blk, vec![ctl]);
+        pg.builder.append_to(check_blk);
+        let disabled = pg.builder.ins().ext_slice(now, 0, 1);
+        pg.builder.ins().br_cond(disabled, start_blk, abort_blk);
+
+        // Copy the values passed to the branch, execute the branch for the
+        // next pending generation, and signal completion.
+        pg.builder.append_to(start_blk);
+        let one = pg.builder.ins().const_int((32, 1));
+        let next = pg.builder.ins().or(prev, one);
+        let next = pg.builder.ins().add(next, one);
+        pg.builder.ins().st(gen, next);
+        for &value_id in &captured {
+            let signal = pg.sync_signals[&SyncSignal::Value(fork_id, value_id)];
+            let value = pg.builder.ins().prb(signal);
+            let value = pg.builder.ins().var(value);
+            if let Ok(HirNode::VarDecl(x)) = pg.hir_of(value_id) {
+                pg.builder.set_name(value, x.name.value.to_string());
+            }
+            pg.set_emitted_value(value_id, value);
+        }
+        pg.flush_mir(); // ensure we don't reuse earlier expr probe
+        pg.emit_shadow_update();
+        pg.emit_stmt(id, env)?;
+        let now = pg.builder.ins().ld(gen);
+        pg.emit_blocking_assign_llhd((done, None), now)?;
+        pg.builder.ins().br(idle_blk);
+
+        // If the branch is disabled, disable any fork-join blocks it started
+        // and signal completion.
+        pg.builder.append_to(abort_blk);
+        pg.emit_fork_disable();
+        let now = pg.builder.ins().prb(ctl);
+        pg.builder.ins().st(gen, now);
+        pg.emit_blocking_assign_llhd((done, None), now)?;
+        pg.builder.ins().br(idle_blk);
 
-    /// Clear the cached MIR lvalues and rvalues. This should be called before
-    /// or after emitting an expression, and at least for every statement.
-    /// Otherwise MIR codegen might reuse values that have become out-of-date
-    /// due to time passing in between statements.
-    fn flush_mir(&mut self) {
-        self.interned_lvalues.clear();
-        self.interned_rvalues.clear();
+        Ok(EmittedProcedure {
+            unit: self.into.add_unit(prok),
+            inputs,
+            outputs,
+            sync_inputs,
+            sync_outputs,
+            program_done: false,
+        })
     }
 
-    /// Emit the code for the contents of a module.
-    fn emit_module_block(
+    /// Emit the checker process for a concurrent assertion.
+    ///
+    /// The process samples the boolean terms of the assertion's property at
+    /// every tick of its clock and keeps a history of their past values. Once
+    /// all terms of an evaluation attempt have been sampled, the attempt is
+    /// checked and the result driven onto an additional `i1` output signal
+    /// which is high while the assertion is violated.
+    fn emit_checker(
         &mut self,
         id: NodeId,
         env: ParamEnv,
-        hir: &hir::ModuleBlock,
         name_prefix: &str,
-    ) -> Result<()> {
-        // Emit declarations.
-        for &decl_id in &hir.decls {
-            let hir = match self.hir_of(decl_id)? {
-                HirNode::VarDecl(x) => x,
-                _ => unreachable!(),
-            };
-            let ty = self.type_of(decl_id, env)?;
-            let value = self.emit_varnet_decl(decl_id, ty, env, hir.init)?;
-            self.builder.set_name(value, hir.name.value.into());
-            self.values.insert(decl_id.into(), value.into());
-        }
+    ) -> Result<EmittedProcedure> {
+        let hir = match self.hir_of(id)? {
+            HirNode::Assertion(x) => x,
+            _ => unreachable!(),
+        };
+        let checker = self.checker_of(id, env)?;
+        trace!("Checker for {:?}: {:#?}", id, checker);
 
-        // Emit interface instances.
-        for &inst_id in &hir.insts {
-            // Resolve the instantiation details.
-            let inst = match self.hir_of(inst_id)? {
-                HirNode::Inst(x) => x,
-                _ => unreachable!(),
-            };
-            let inst = self.inst_details(Ref(inst), env)?;
+        // Find the accessed nodes.
+        let acc = self.accessed_nodes(id, env)?;
+        trace!("Checker accesses {:#?}", acc);
+        let (mut sig, inputs, outputs) = self.emit_process_signature(&acc, env)?;
+        sig.add_output(llhd::signal_ty(llhd::int_ty(1)));
 
-            // Compute the array dimensions for the signals.
-            // let mut dims = vec![];
-            let inst_ty = self.type_of_inst(Ref(inst.hir), env);
-            let intf_ty = match inst_ty.resolve_full().core.get_interface() {
-                Some(x) => x,
-                None => continue,
-            };
-            trace!(
-                "Interface instance is of type `{}` ({:?})",
-                inst_ty,
-                intf_ty
-            );
-
-            // Expand the interface declarations.
-            let signals = self.determine_interface_signals(intf_ty, &inst_ty.dims)?;
-            let mut signal_lookup = HashMap::new();
-            for signal in signals {
-                let value =
-                    self.emit_varnet_decl(signal.decl_id, signal.ty, intf_ty.env, signal.default)?;
-                self.builder
-                    .set_name(value, format!("{}.{}", inst.hir.name, signal.name));
-                let src = AccessedNode::Intf(inst_id, signal.decl_id);
-                trace!(
-                    "Emitted value for {:?} {}.{}",
-                    src,
-                    inst.hir.name,
-                    signal.name
-                );
-                self.values.insert(src, value.into());
-                signal_lookup.insert(signal.decl_id, value);
-            }
-
-            // Generate the code for the port assignments.
-            let port_list = self.canonicalize_ports(intf_ty.ast);
-            let ports = self.determine_module_ports(&port_list.int, intf_ty.env)?;
-            let (inputs, outputs) = self.emit_port_connections(
-                port_list,
-                inst.as_ref(),
-                &ports.inputs,
-                &ports.outputs,
-            )?;
-            trace!("Attaching interface inputs {:?}", inputs);
-            trace!("Attaching interface outputs {:?}", outputs);
-            trace!("Signal lookup: {:?}", signal_lookup);
+        // Create process and entry block.
+        let proc_name = format!("{}.assert.{}.{}", name_prefix, id.as_usize(), env.0);
+        let mut prok = llhd::ir::UnitData::new(
+            llhd::ir::UnitKind::Process,
+            llhd::ir::UnitName::Local(proc_name),
+            sig,
+        );
+        let mut builder = llhd::ir::UnitBuilder::new_anonymous(&mut prok);
+        self.name_process_args(&mut builder, &inputs, &outputs);
+        let violation = builder.output_arg(outputs.len());
+        builder.set_name(violation, "violation".to_string());
 
-            // Actually wire up the ports.
-            let inputs = inputs.into_iter().zip(ports.inputs.iter());
-            let outputs = outputs.into_iter().zip(ports.outputs.iter());
-            for (assigned, port) in inputs.chain(outputs) {
-                trace!(
-                    "Assign `{}` ({:?}) = {:?}",
-                    port.name,
-                    port.port.id,
-                    assigned
-                );
-                self.builder
-                    .ins()
-                    .con(signal_lookup[&port.port.id], assigned);
-            }
+        // Create a mapping from read/written nodes to process parameters.
+        let mut values = HashMap::new();
+        for (&id, arg) in inputs
+            .iter()
+            .zip(builder.input_args())
+            .chain(outputs.iter().zip(builder.output_args()))
+        {
+            values.insert(id.into(), arg);
+        }
+        let mut pg = UnitGenerator {
+            gen: self,
+            builder: &mut builder,
+            values: &mut values,
+            interned_consts: Default::default(),
+            interned_lvalues: Default::default(),
+            interned_rvalues: Default::default(),
+            shadows: Default::default(),
+            inlined: Default::default(),
+            this: None,
+            sync_signals: Default::default(),
+            generations: Default::default(),
+            sampled: Default::default(),
+            fork_branch: Default::default(),
+            exits: Default::default(),
+            nets: Default::default(),
+            program: false,
+            program_done: Default::default(),
+        };
+        let entry_blk = pg.add_nameless_block();
+        pg.builder.append_to(entry_blk);
+        pg.emit_shadows(&acc);
+
+        // Allocate the history of the terms. A term at offset `i` is needed
+        // `length - 1 - i` ticks after it has been sampled.
+        let last = checker.length - 1;
+        let zero = pg.builder.ins().const_int((1, 0));
+        let one = pg.builder.ins().const_int((1, 1));
+        let mut antecedent = vec![];
+        for &(offset, expr) in &checker.antecedent {
+            antecedent.push((expr, pg.emit_history(zero, last - offset, "ant")));
+        }
+        let mut consequent = vec![];
+        for &(offset, expr) in &checker.consequent {
+            consequent.push((expr, pg.emit_history(zero, last - offset, "cons")));
+        }
+        let valid = pg.emit_history(zero, last, "valid");
+        let disable = match hir.disable {
+            Some(expr) => Some((expr, pg.emit_history(zero, last, "disable"))),
+            None => None,
+        };
+        for call in &checker.sampled {
+            let ty = pg.mir_rvalue(call.arg, env).ty;
+            let ty = pg.emit_type(ty)?;
+            let init = pg.emit_zero_for_type(&ty);
+            let history = pg.emit_history(init, call.depth, "sampled");
+            pg.sampled.insert(call.call, history);
         }
 
-        // Emit assignments.
-        for &assign_id in &hir.assigns {
-            let hir = match self.hir_of(assign_id)? {
-                HirNode::Assign(x) => x,
-                _ => unreachable!(),
-            };
-
-            // Map the assignment to an MIR node.
-            let assign_mir = self.mir_assignment_from_concurrent(Ref(hir), env);
-            debug!("Concurrent assignment: {:#?}", assign_mir);
-
-            // Simplify the assignment to eliminate concatenations on the
-            // left-hand side.
-            let simplified = self.mir_simplify_assignment(Ref(assign_mir));
-            debug!("Simplified to: {:#?}", simplified);
+        // Wait for the clock to tick.
+        let tick_blk = pg.add_named_block("tick");
+        pg.builder.ins().br(tick_blk);
+        pg.builder.append_to(tick_blk);
+        pg.emit_event_wait(hir.clock, env)?;
+
+        // Sample the terms and determine their value at the tick they are
+        // checked for.
+        let mut antecedent_value = one;
+        for (expr, history) in antecedent {
+            let now = pg.emit_rvalue_bool(expr, env)?;
+            let value = pg.emit_history_shift(&history, now);
+            antecedent_value = pg.builder.ins().and(antecedent_value, value);
+        }
+        let mut consequent_value = one;
+        for (expr, history) in consequent {
+            let now = pg.emit_rvalue_bool(expr, env)?;
+            let value = pg.emit_history_shift(&history, now);
+            consequent_value = pg.builder.ins().and(consequent_value, value);
+        }
 
-            // Check for sanity.
-            for &assign in &simplified {
-                assert_type!(assign.rhs.ty, assign.lhs.ty, assign.rhs.span, self.cx);
-                if assign.is_error() {
-                    return Err(());
-                }
+        // Only check attempts which started after the checker was started,
+        // and which saw no `disable iff` condition.
+        let mut enabled = pg.emit_history_shift(&valid, one);
+        if let Some((expr, history)) = disable {
+            let now = pg.emit_rvalue_bool(expr, env)?;
+            let mut disabled = now;
+            for &past in &history {
+                let past = pg.builder.ins().ld(past);
+                disabled = pg.builder.ins().or(disabled, past);
             }
+            pg.emit_history_shift(&history, now);
+            let disabled = pg.builder.ins().not(disabled);
+            enabled = pg.builder.ins().and(enabled, disabled);
+        }
+        let matched = pg.builder.ins().and(enabled, antecedent_value);
+        pg.builder.set_name(matched, "matched".to_string());
+
+        // Sample the arguments of the sampled value functions. The histories
+        // are updated after the checks, which still need the old values.
+        let mut sampled = vec![];
+        for call in &checker.sampled {
+            let now = pg.emit_rvalue(call.arg, env)?;
+            sampled.push((call.call, now));
+        }
 
-            // Emit the assignments.
-            let delay = llhd::value::TimeValue::new(num::zero(), 0, 1);
-            let delay = self.builder.ins().const_time(delay);
-            for &assign in &simplified {
-                let lhs = self.emit_mir_lvalue(assign.lhs)?;
-                let rhs = self.emit_mir_rvalue(assign.rhs)?;
-                self.builder.ins().drv(lhs.0, rhs, delay);
+        // Flag a violation if the antecedent matched but the consequent did
+        // not.
+        let failed = pg.builder.ins().not(consequent_value);
+        let failed = pg.builder.ins().and(matched, failed);
+        pg.builder.set_name(failed, "failed".to_string());
+        pg.emit_blocking_assign_llhd((violation, None), failed)?;
+
+        // Execute the action block.
+        let pass_blk = pg.add_named_block("assert_pass");
+        let fail_blk = pg.add_named_block("assert_fail");
+        let final_blk = pg.add_named_block("assert_exit");
+        pg.builder.ins().br_cond(failed, pass_blk, fail_blk);
+        pg.builder.append_to(pass_blk);
+        if let Some(pass_stmt) = hir.pass_stmt {
+            let action_blk = pg.add_named_block("assert_pass_action");
+            pg.builder.ins().br_cond(matched, final_blk, action_blk);
+            pg.builder.append_to(action_blk);
+            pg.emit_stmt(pass_stmt, env)?;
+        }
+        pg.builder.ins().br(final_blk);
+        pg.builder.append_to(fail_blk);
+        match hir.fail_stmt {
+            Some(fail_stmt) => pg.emit_stmt(fail_stmt, env)?,
+            None => {
+                // Report the failure like `$error` would.
+                let what = match hir.kind {
+                    hir::AssertionKind::Assume => "assumption",
+                    _ => "assertion",
+                };
+                let format = pg.default_report(id, hir.span, &format!("{} failed", what));
+                pg.emit_display(hir::DisplayTask::Error, &format, &[])?;
             }
         }
+        pg.builder.ins().br(final_blk);
+        pg.builder.append_to(final_blk);
 
-        // Emit module instantiations.
-        for &inst_id in &hir.insts {
-            // Resolve the instantiation details.
-            let inst = match self.hir_of(inst_id)? {
-                HirNode::Inst(x) => x,
-                _ => unreachable!(),
-            };
-            let inst = self.inst_details(Ref(inst), env)?;
-            let target_module = match inst.target.kind {
-                InstTarget::Module(x) => self.hir_of_module(x)?,
-                _ => continue,
-            };
+        // Update the histories of the sampled value functions.
+        for (call, now) in sampled {
+            let history = pg.sampled[&call].clone();
+            pg.emit_history_shift(&history, now);
+        }
+        pg.builder.ins().br(tick_blk);
 
-            // Emit the instantiated module.
-            let target = self.emit_module_with_env(target_module.id, inst.inner_env)?;
+        Ok(EmittedProcedure {
+            unit: self.into.add_unit(prok),
+            inputs,
+            outputs,
+            sync_inputs: vec![],
+            sync_outputs: vec![],
+            program_done: false,
+        })
+    }
 
-            // Prepare the port assignments.
-            let (inputs, outputs) = self.emit_port_connections(
-                target_module.ports_new,
-                inst.as_ref(),
-                &target.ports.inputs,
-                &target.ports.outputs,
-            )?;
+    /// Emit the code for a function.
+    fn emit_function(&mut self, id: NodeId, env: ParamEnv) -> Result<Rc<EmittedFunction>> {
+        if let Some(x) = self.tables.function_defs.get(&id.env(env)) {
+            return x.clone();
+        }
+        let result = self.emit_function_uninterned(id, env);
+        self.tables
+            .function_defs
+            .insert(id.env(env), result.clone());
+        result
+    }
 
-            // Instantiate the module.
-            let ext_unit = self.builder.add_extern(
-                self.into.unit(target.unit).name().clone(),
-                self.into.unit(target.unit).sig().clone(),
+    /// Emit the code for a function, without checking whether it has already
+    /// been emitted.
+    fn emit_function_uninterned(
+        &mut self,
+        id: NodeId,
+        env: ParamEnv,
+    ) -> Result<Rc<EmittedFunction>> {
+        let hir = match self.hir_of(id)? {
+            HirNode::Subroutine(x) => x,
+            _ => unreachable!(),
+        };
+        info!("Emit function `{}` with {:?}", hir.name, env);
+
+        // Find the accessed nodes. Functions cannot modify anything outside
+        // their body, and anything they read is passed in as an additional
+        // argument.
+        let acc = self.accessed_nodes(id, env)?;
+        trace!("Function accesses {:#?}", acc);
+        if let Some(&written) = acc.written.iter().next() {
+            let written = self.hir_of(written.id())?;
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "unsupported: {} assigns to {}",
+                    hir.desc_full(),
+                    written.desc_full()
+                ))
+                .span(hir.human_span())
+                .add_note("Functions may only assign to their arguments and local variables."),
             );
-            if !inst.hir.ast.dims.is_empty() {
-                bug_span!(
-                    inst.hir.ast.span(),
-                    self.cx,
-                    "instance arrays of modules not supported"
-                );
-            }
-            self.builder.ins().inst(ext_unit, inputs, outputs);
-            // TODO: Annotate instance name once LLHD allows that.
+            return Err(());
         }
 
-        // Emit generate blocks.
-        for &gen_id in &hir.gens {
-            let hir = match self.hir_of(gen_id)? {
-                HirNode::Gen(x) => x,
-                _ => unreachable!(),
-            };
-            #[allow(unreachable_patterns)]
-            match hir.kind {
-                hir::GenKind::If {
-                    cond,
-                    ref main_body,
-                    ref else_body,
-                } => {
-                    let k = self.constant_value_of(cond, env);
-                    if k.is_false() {
-                        if let Some(else_body) = else_body {
-                            self.emit_module_block(id, env, else_body, name_prefix)?;
-                        }
-                    } else {
-                        self.emit_module_block(id, env, main_body, name_prefix)?;
-                    }
-                }
-                hir::GenKind::For {
-                    ref init,
-                    cond,
-                    step,
-                    ref body,
-                } => {
-                    let mut local_env = env;
-                    for &i in init {
-                        local_env = self.execute_genvar_init(i, local_env)?;
-                    }
-                    while self.constant_value_of(cond, local_env).is_true() {
-                        self.emit_module_block(id, local_env, body, name_prefix)?;
-                        local_env = self.execute_genvar_step(step, local_env)?;
-                    }
-                }
-                _ => return self.unimp_msg("code generation for", hir),
-            }
+        // Determine the function signature.
+        let mut sig = llhd::ir::Signature::new();
+        for arg in &hir.args {
+            let ty = self.type_of(arg.id, env)?;
+            sig.add_input(self.emit_type(ty)?);
         }
-
-        // Emit and instantiate procedures.
-        for &proc_id in &hir.procs {
-            let prok = self.emit_procedure(proc_id, env, name_prefix)?;
-            let lookup_value = |&id: &AccessedNode| match self.values.get(&id) {
-                Some(v) => v.clone(),
-                None => {
+        let mut inputs = vec![];
+        for &node in &acc.read {
+            let ty = match node {
+                AccessedNode::Regular(id) => self.type_of(id, env)?,
+                AccessedNode::Intf(..) => {
                     self.emit(
-                        DiagBuilder2::bug(format!(
-                            "{} used as input/output of {}, but no value has been emitted",
-                            self.hir_of(id.id()).unwrap().desc_full(),
-                            self.hir_of(proc_id).unwrap().desc_full(),
+                        DiagBuilder2::error(format!(
+                            "unsupported: interface signal or hierarchical reference accessed in {}",
+                            hir.desc_full()
                         ))
-                        .span(self.span(id.id())),
+                        .span(hir.human_span()),
                     );
-                    panic!("no value emitted for {:?}", id);
+                    return Err(());
                 }
             };
-            let inputs = prok.inputs.iter().map(lookup_value).collect();
-            let outputs = prok.outputs.iter().map(lookup_value).collect();
-            let ext_unit = self.builder.add_extern(
-                self.into.unit(prok.unit).name().clone(),
-                self.into.unit(prok.unit).sig().clone(),
-            );
-            self.builder.ins().inst(ext_unit, inputs, outputs);
+            sig.add_input(self.emit_type(ty)?);
+            inputs.push(node);
+        }
+        let retty = self.type_of(id, env)?;
+        if !retty.is_void() {
+            sig.set_return_type(self.emit_type(retty)?);
+        }
+        trace!("Function Inputs: {:?}", inputs);
+        trace!("Function Signature: {}", sig);
+
+        // Register the function before emitting its body, such that recursive
+        // calls can refer to it.
+        let name = llhd::ir::UnitName::Local(format!("{}.{}.{}", hir.name, id.as_usize(), env.0));
+        let emitted = Rc::new(EmittedFunction {
+            name: name.clone(),
+            sig: sig.clone(),
+            inputs: inputs.clone(),
+        });
+        self.tables
+            .function_defs
+            .insert(id.env(env), Ok(emitted.clone()));
+
+        // Emit the body. If this fails, the function registered above must not
+        // be used by any later calls.
+        match self.emit_function_body(id, env, name, sig, &inputs) {
+            Ok(func) => {
+                self.into.add_unit(func);
+                Ok(emitted)
+            }
+            Err(()) => {
+                self.tables.function_defs.insert(id.env(env), Err(()));
+                Err(())
+            }
         }
-
-        Ok(())
     }
 
-    /// Emit code for the connections made in a port list.
-    fn emit_port_connections(
+    /// Emit the body of a function into a new LLHD unit.
+    fn emit_function_body(
         &mut self,
-        port_list: &PortList<'gcx>,
-        inst: &InstDetails<'gcx>,
-        inputs: &[ModulePort<'gcx>],
-        outputs: &[ModulePort<'gcx>],
-    ) -> Result<(Vec<llhd::ir::Value>, Vec<llhd::ir::Value>)> {
-        // Map the values associated with the external ports to internal
-        // ports.
-        let mut port_mapping_int: HashMap<NodeId, NodeEnvId> = HashMap::new();
-        for port in &port_list.ext_pos {
-            let mapping = match inst.ports.find(port.id) {
-                Some(m) => m,
-                None => continue,
-            };
-            if port.exprs.len() > 1 {
-                self.emit(
-                    DiagBuilder2::bug("port expressions with concatenations not supported")
-                        .span(inst.hir.span())
-                        .add_note("Port declared here:")
-                        .span(port.span),
-                );
-                continue;
+        id: NodeId,
+        env: ParamEnv,
+        name: llhd::ir::UnitName,
+        sig: llhd::ir::Signature,
+        inputs: &[AccessedNode],
+    ) -> Result<llhd::ir::UnitData> {
+        let hir = match self.hir_of(id)? {
+            HirNode::Subroutine(x) => x,
+            _ => unreachable!(),
+        };
+        let retty = self.type_of(id, env)?;
+
+        // Create function and assign names to the arguments.
+        let mut func = llhd::ir::UnitData::new(llhd::ir::UnitKind::Function, name, sig);
+        let mut builder = llhd::ir::UnitBuilder::new_anonymous(&mut func);
+        for (i, arg) in hir.args.iter().enumerate() {
+            let value = builder.input_arg(i);
+            builder.set_name(value, arg.name.value.to_string());
+        }
+        let mut values = HashMap::new();
+        for (i, &node) in inputs.iter().enumerate() {
+            let value = builder.input_arg(hir.args.len() + i);
+            if let Ok(HirNode::VarDecl(x)) = self.hir_of(node.id()) {
+                builder.set_name(value, x.name.value.to_string());
+            } else if let Ok(HirNode::IntPort(x)) = self.hir_of(node.id()) {
+                builder.set_name(value, x.name.value.to_string());
             }
-            let expr = match port.exprs.iter().next() {
-                Some(m) => m,
-                None => continue,
-            };
-            if !expr.selects.is_empty() {
-                self.emit(
-                    DiagBuilder2::bug("port expressions with selections not supported")
-                        .span(inst.hir.span())
-                        .add_note("Port declared here:")
-                        .span(port.span),
-                );
-                continue;
+            values.insert(node, value);
+        }
+        let mut pg = UnitGenerator {
+            gen: self,
+            builder: &mut builder,
+            values: &mut values,
+            interned_consts: Default::default(),
+            interned_lvalues: Default::default(),
+            interned_rvalues: Default::default(),
+            shadows: Default::default(),
+            inlined: Default::default(),
+            this: None,
+            sync_signals: Default::default(),
+            generations: Default::default(),
+            sampled: Default::default(),
+            fork_branch: Default::default(),
+            exits: Default::default(),
+            nets: Default::default(),
+            program: false,
+            program_done: Default::default(),
+        };
+        let entry_blk = pg.add_nameless_block();
+        pg.builder.append_to(entry_blk);
+
+        // Arguments may be assigned to in the function body, so keep them in
+        // variables. The same holds for the return value.
+        for (i, arg) in hir.args.iter().enumerate() {
+            let value = pg.builder.input_arg(i);
+            let var = pg.builder.ins().var(value);
+            pg.builder.set_name(var, format!("{}.var", arg.name));
+            pg.set_emitted_value(arg.id, var);
+        }
+        let retvar = if !retty.is_void() {
+            let ty = pg.emit_type(retty)?;
+            let init = pg.emit_zero_for_type(&ty);
+            let var = pg.builder.ins().var(init);
+            pg.builder.set_name(var, format!("{}.ret", hir.name));
+            pg.set_emitted_value(id, var);
+            Some(var)
+        } else {
+            None
+        };
+
+        // Emit the function body and epilogue.
+        pg.emit_exitable(id, ExitKind::Subroutine, |pg| {
+            for &stmt in &hir.stmts {
+                pg.emit_stmt(stmt, env)?;
             }
-            let int = &port_list.int[expr.port];
-            if port_mapping_int.insert(int.id, mapping).is_some() {
-                self.emit(
-                    DiagBuilder2::error(format!("port `{}` connected multiple times", int.name))
-                        .span(self.span(mapping.id())),
-                );
+            Ok(())
+        })?;
+        match retvar {
+            Some(var) => {
+                let value = pg.builder.ins().ld(var);
+                pg.builder.ins().ret_value(value);
+            }
+            None => {
+                pg.builder.ins().ret();
             }
         }
-        trace!("Internal Port Mapping: {:?}", port_mapping_int);
 
-        // Connect to the actual internal ports emitted as the module's port
-        // interface.
-        let mut map_port = |port: &ModulePort<'gcx>, lvalue: bool| {
-            trace!(
-                "Mapping port `{}` of type `{}` as {}",
-                port.name,
-                port.ty,
-                match lvalue {
-                    true => "lvalue",
-                    false => "rvalue",
-                }
-            );
-            if let Some(&mapping) = port_mapping_int.get(&port.port.id) {
-                // Emit the assigned node as rvalue or lvalue, depending on
-                // the port direction.
-                if lvalue {
-                    let mir = self.mir_lvalue(mapping.id(), mapping.env());
-                    if mir.is_error() {
-                        return Err(());
-                    }
-                    let mir = match port.kind {
-                        ModulePortKind::Port => mir,
-                        ModulePortKind::IntfSignal { decl_id, env, .. } => {
-                            self.arena().alloc_mir_lvalue(mir::Lvalue {
-                                id: NodeId::alloc(),
-                                origin: mir.origin,
-                                env,
-                                span: mir.span,
-                                ty: port.ty,
-                                kind: mir::LvalueKind::IntfSignal(mir, decl_id),
-                            })
-                        }
-                    };
-                    self.emit_mir_lvalue(mir).map(|(x, _)| x)
-                } else {
-                    let mir = self.mir_rvalue(mapping.id(), mapping.env());
-                    if mir.is_error() {
-                        return Err(());
-                    }
-                    let mir = match port.kind {
-                        ModulePortKind::Port => mir,
-                        ModulePortKind::IntfSignal { decl_id, env, .. } => {
-                            self.arena().alloc_mir_rvalue(mir::Rvalue {
-                                id: NodeId::alloc(),
-                                origin: mir.origin,
-                                env,
-                                span: mir.span,
-                                ty: port.ty,
-                                kind: mir::RvalueKind::IntfSignal(mir, decl_id),
-                                konst: false,
-                            })
-                        }
-                    };
-                    self.emit_mir_rvalue_mode(mir, Mode::Signal)
+        Ok(func)
+    }
+
+    /// Emit the resolution function for a net type (interned).
+    ///
+    /// Four-valued logic is represented as a value and an unknown bit plane.
+    /// A bit is `0` or `1` if its unknown bit is clear, and `x` or `z`,
+    /// respectively, if it is set. The function takes the value and unknown
+    /// planes of each of `count` drivers of a net of `width` bits, and
+    /// returns the planes of the resolved value as a struct.
+    fn emit_resolution_function(
+        &mut self,
+        ty: ast::NetType,
+        width: usize,
+        count: usize,
+    ) -> (llhd::ir::UnitName, llhd::ir::Signature) {
+        if let Some(x) = self.tables.resolution_fns.get(&(ty, width, count)) {
+            return x.clone();
+        }
+        let name = llhd::ir::UnitName::Local(format!("{}.resolve.{}.{}", ty, width, count));
+        let mut sig = llhd::ir::Signature::new();
+        for _ in 0..count {
+            sig.add_input(llhd::int_ty(width));
+            sig.add_input(llhd::int_ty(width));
+        }
+        sig.set_return_type(llhd::struct_ty(vec![
+            llhd::int_ty(width),
+            llhd::int_ty(width),
+        ]));
+
+        let mut func =
+            llhd::ir::UnitData::new(llhd::ir::UnitKind::Function, name.clone(), sig.clone());
+        let mut builder = llhd::ir::UnitBuilder::new_anonymous(&mut func);
+        let entry_blk = builder.block();
+        builder.append_to(entry_blk);
+
+        // Determine the bits driven to 0, 1, and x by any of the drivers.
+        let zero = builder.ins().const_int((width, 0));
+        let mut zeros = zero;
+        let mut ones = zero;
+        let mut xs = zero;
+        for i in 0..count {
+            let value = builder.input_arg(2 * i);
+            let unknown = builder.input_arg(2 * i + 1);
+            builder.set_name(value, format!("value{}", i));
+            builder.set_name(unknown, format!("unknown{}", i));
+            let known = builder.ins().not(unknown);
+            let inv = builder.ins().not(value);
+            let bits = builder.ins().and(inv, known);
+            zeros = builder.ins().or(zeros, bits);
+            let bits = builder.ins().and(value, known);
+            ones = builder.ins().or(ones, bits);
+            let bits = builder.ins().and(value, unknown);
+            xs = builder.ins().or(xs, bits);
+        }
+        let driven = builder.ins().or(zeros, ones);
+        let driven = builder.ins().or(driven, xs);
+        let undriven = builder.ins().not(driven);
+
+        // Combine them according to the net type. Bits driven to both 0 and 1
+        // are x, except for wired-AND and wired-OR nets, where 0 and 1 win,
+        // respectively. Undriven bits are z, except for pulled nets.
+        let (ones, xs, zs) = match ty {
+            ast::NetType::Supply0 => (zero, zero, zero),
+            ast::NetType::Supply1 => (builder.ins().not(zero), zero, zero),
+            ast::NetType::WireAnd | ast::NetType::TriAnd => {
+                let not_zeros = builder.ins().not(zeros);
+                let xs = builder.ins().and(xs, not_zeros);
+                let not_xs = builder.ins().not(xs);
+                let ones = builder.ins().and(ones, not_zeros);
+                let ones = builder.ins().and(ones, not_xs);
+                (ones, xs, undriven)
+            }
+            ast::NetType::WireOr | ast::NetType::TriOr => {
+                let not_ones = builder.ins().not(ones);
+                let xs = builder.ins().and(xs, not_ones);
+                (ones, xs, undriven)
+            }
+            _ => {
+                let conflicts = builder.ins().and(zeros, ones);
+                let xs = builder.ins().or(xs, conflicts);
+                let not_xs = builder.ins().not(xs);
+                let ones = builder.ins().and(ones, not_xs);
+                match ty {
+                    ast::NetType::Tri0 => (ones, xs, zero),
+                    ast::NetType::Tri1 => (builder.ins().or(ones, undriven), xs, zero),
+                    _ => (ones, xs, undriven),
                 }
-            } else {
-                // Emit an auxiliary signal with the default value for this
-                // port or type.
-                let ty = self.type_of_int_port(Ref(port.port), inst.inner_env);
-                let value = match port.port.data.as_ref().and_then(|d| d.default) {
-                    Some(default) => {
-                        self.emit_rvalue_mode(default, inst.inner_env, Mode::Signal)?
-                    }
-                    None => {
-                        let v = self.type_default_value(ty);
-                        let v = self.emit_const(v, inst.inner_env, port.port.span)?;
-                        self.builder.ins().sig(v)
-                    }
-                };
-                self.builder
-                    .set_name(value, format!("{}.{}.default", inst.hir.name, port.name));
-                Ok(value)
             }
         };
-        let inputs = inputs
-            .iter()
-            .map(|p| map_port(p, false))
-            .collect::<Result<Vec<_>>>()?;
-        let outputs = outputs
-            .iter()
-            .map(|p| map_port(p, true))
-            .collect::<Result<Vec<_>>>()?;
-        Ok((inputs, outputs))
+        let value = builder.ins().or(ones, xs);
+        let unknown = builder.ins().or(xs, zs);
+        let result = builder.ins().strukt(vec![value, unknown]);
+        builder.ins().ret_value(result);
+
+        self.into.add_unit(func);
+        let result = (name, sig);
+        self.tables
+            .resolution_fns
+            .insert((ty, width, count), result.clone());
+        result
     }
 
-    /// Map a value to an LLHD constant (interned).
-    fn emit_const(
-        &mut self,
-        value: Value<'gcx>,
-        env: ParamEnv,
-        span: Span,
-    ) -> Result<llhd::ir::Value> {
-        if let Some(x) = self.interned_consts.get(value) {
+    /// Map a type to an LLHD type (interned).
+    fn emit_type(&mut self, ty: &'gcx UnpackedType<'gcx>) -> Result<llhd::Type> {
+        if let Some(x) = self.tables.interned_types.get(&ty) {
             x.clone()
         } else {
-            let x = self.emit_const_uninterned(value, env, span);
-            // self.interned_consts.insert(value, x.clone());
+            let x = self.emit_type_uninterned(ty);
+            self.tables.interned_types.insert(ty, x.clone());
             x
         }
     }
 
-    /// Map a value to an LLHD constant.
-    fn emit_const_uninterned(
-        &mut self,
-        value: Value<'gcx>,
-        env: ParamEnv,
-        span: Span,
-    ) -> Result<llhd::ir::Value> {
-        if value.ty.is_error() {
+    /// Map a type to an LLHD type.
+    fn emit_type_uninterned(&mut self, ty: &'gcx UnpackedType<'gcx>) -> Result<llhd::Type> {
+        if ty.is_error() {
             return Err(());
         }
-        match value.kind {
-            ValueKind::Int(ref k, ..) => {
-                let size = value.ty.simple_bit_vector(self.cx, span).size;
-                Ok(self.builder.ins().const_int((size, k.clone())))
+        let ty = ty.resolve_full();
+
+        // Handle things that coalesce easily to scalars.
+        if ty.coalesces_to_llhd_scalar() {
+            return Ok(llhd::int_ty(ty.get_bit_size().unwrap()));
+        }
+
+        // Handle class handles, dynamically sized containers, and strings,
+        // which refer to storage on the heap.
+        if ty.get_class().is_some() || ty.get_container().is_some() || ty.is_string() {
+            return Ok(llhd::int_ty(64));
+        }
+
+        // Handle reals, which are represented by their IEEE 754 bits.
+        if let Some(real) = ty.get_real() {
+            return Ok(llhd::int_ty(real.bit_size()));
+        }
+
+        // Handle arrays.
+        if let Some(dim) = ty.outermost_dim() {
+            let size = match dim.get_size() {
+                Some(size) => size,
+                None => panic!("cannot map unsized array `{}` to LLHD", ty),
+            };
+            let inner = ty.pop_dim(self.cx).unwrap();
+            return Ok(llhd::array_ty(size, self.emit_type(inner)?));
+        }
+
+        // Handle unions, which are stored as a bit vector that is large enough
+        // to hold any of their members.
+        if let Some(strukt) = ty.get_union() {
+            return Ok(llhd::int_ty(strukt.get_bit_size().unwrap()));
+        }
+
+        // Handle structs.
+        if let Some(strukt) = ty.get_struct() {
+            let mut types = vec![];
+            for member in &strukt.members {
+                types.push(self.emit_type(member.ty)?);
             }
-            ValueKind::Time(ref k) => Ok(self
-                .builder
-                .ins()
-                .const_time(llhd::value::TimeValue::new(k.clone(), 0, 0))),
-            ValueKind::StructOrArray(ref v) => {
-                if let Some(_dim) = value.ty.outermost_dim() {
-                    let fields: Result<Vec<_>> = v
-                        .iter()
-                        .map(|v| self.emit_const(v, env, span).map(Into::into))
-                        .collect();
-                    Ok(self.builder.ins().array(fields?))
-                } else if let Some(_strukt) = value.ty.get_struct() {
-                    let fields: Result<Vec<_>> = v
-                        .iter()
-                        .map(|v| self.emit_const(v, env, span).map(Into::into))
-                        .collect();
-                    Ok(self.builder.ins().strukt(fields?))
+            return Ok(llhd::struct_ty(types));
+        }
+
+        // Handle packed types.
+        if let Some(packed) = ty.get_packed() {
+            let packed = packed.resolve_full();
+            return match packed.core {
+                ty::PackedCore::Error => Ok(llhd::void_ty()),
+                ty::PackedCore::Void => Ok(llhd::void_ty()),
+                ty::PackedCore::IntAtom(ty::IntAtomType::Time) => Ok(llhd::time_ty()),
+                ty::PackedCore::Enum(ref enm) => self.emit_type(enm.base.to_unpacked(self.cx)),
+                _ => unreachable!("emitting `{}` should have been handled above", packed),
+            };
+        }
+
+        // Everything else we cannot do.
+        error!("Cannot map type {:#?}", ty);
+        panic!("cannot map `{}` to LLHD", ty);
+    }
+
+    /// Determine the bodies of a generate block that are instantiated, and
+    /// the parameter environment of each.
+    fn gen_block_bodies(
+        &mut self,
+        gen_id: NodeId,
+        env: ParamEnv,
+    ) -> Result<Vec<(&'gcx hir::ModuleBlock, ParamEnv)>> {
+        let hir = match self.hir_of(gen_id)? {
+            HirNode::Gen(x) => x,
+            _ => unreachable!(),
+        };
+        let mut bodies = vec![];
+        #[allow(unreachable_patterns)]
+        match hir.kind {
+            hir::GenKind::If {
+                cond,
+                ref main_body,
+                ref else_body,
+            } => {
+                let k = self.constant_value_of(cond, env);
+                if k.is_false() {
+                    if let Some(else_body) = else_body {
+                        bodies.push((else_body, env));
+                    }
                 } else {
-                    panic!(
-                        "invalid type `{}` for const struct/array value {:#?}",
-                        value.ty, value
-                    );
+                    bodies.push((main_body, env));
                 }
             }
-            ValueKind::Error => Err(()),
-            _ => panic!(
-                "invalid combination of type `{}` and value {:#?}",
-                value.ty, value
-            ),
+            hir::GenKind::For {
+                ref init,
+                cond,
+                step,
+                ref body,
+            } => {
+                let mut local_env = env;
+                for &i in init {
+                    local_env = self.execute_genvar_init(i, local_env)?;
+                }
+                while self.constant_value_of(cond, local_env).is_true() {
+                    bodies.push((body, local_env));
+                    local_env = self.execute_genvar_step(step, local_env)?;
+                }
+            }
+            hir::GenKind::Case {
+                expr,
+                ref items,
+                ref default,
+            } => {
+                let k = self.constant_value_of(expr, env);
+                let body = items
+                    .iter()
+                    .find(|(labels, _)| {
+                        labels.iter().any(|&label| {
+                            let v = self.constant_value_of(label, env);
+                            match (k.get_int(), v.get_int()) {
+                                (Some(a), Some(b)) => a == b,
+                                _ => k.kind == v.kind,
+                            }
+                        })
+                    })
+                    .map(|(_, body)| body)
+                    .or(default.as_ref());
+                if let Some(body) = body {
+                    bodies.push((body, env));
+                }
+            }
+            _ => return self.unimp_msg("code generation for", hir),
         }
+        Ok(bodies)
     }
 
-    /// Emit the zero value for an LLHD type.
-    ///
-    /// This function is ultimately expected to be moved into LLHD.
-    fn emit_zero_for_type(&mut self, ty: &llhd::Type) -> llhd::ir::Value {
-        match **ty {
-            llhd::TimeType => {
-                self.builder
-                    .ins()
-                    .const_time(llhd::value::TimeValue::new(num::zero(), 0, 0))
-            }
-            llhd::IntType(w) => self.builder.ins().const_int((w, 0)),
-            llhd::SignalType(ref ty) => {
-                let inner = self.emit_zero_for_type(ty);
-                self.builder.ins().sig(inner)
+    /// Collect the package variables accessed by a module block and the
+    /// modules it instantiates.
+    fn collect_package_vars(
+        &mut self,
+        hir: &'gcx hir::ModuleBlock,
+        env: ParamEnv,
+        into: &mut BTreeSet<NodeId>,
+    ) -> Result<()> {
+        let mut accessed = vec![];
+        for &proc_id in &hir.procs {
+            match self.hir_of(proc_id)? {
+                HirNode::Proc(x) => accessed.push(self.accessed_nodes(x.stmt, env)?),
+                _ => unreachable!(),
             }
-            llhd::PointerType(ref ty) => {
-                let inner = self.emit_zero_for_type(ty);
-                self.builder.ins().var(inner)
+        }
+        for &id in hir.assigns.iter().chain(hir.asserts.iter()) {
+            accessed.push(self.accessed_nodes(id, env)?);
+        }
+        for table in accessed {
+            for node in table.read.iter().chain(table.written.iter()) {
+                if let AccessedNode::Regular(id) = *node {
+                    if enclosing_package(self.cx, id).is_some() {
+                        into.insert(id);
+                    }
+                }
             }
-            llhd::ArrayType(l, ref ty) => {
-                let inner = self.emit_zero_for_type(ty);
-                self.builder.ins().array_uniform(l, inner)
+        }
+        for &inst_id in &hir.insts {
+            let inst = match self.hir_of(inst_id)? {
+                HirNode::Inst(x) => x,
+                _ => unreachable!(),
+            };
+            let inst = self.inst_details(Ref(inst), env)?;
+            if let InstTarget::Module(x) = inst.target.kind {
+                let module = self.hir_of_module(x)?;
+                self.collect_package_vars(&module.block, inst.inner_env, into)?;
             }
-            llhd::StructType(ref tys) => {
-                let inner = tys.iter().map(|ty| self.emit_zero_for_type(ty)).collect();
-                self.builder.ins().strukt(inner)
+        }
+        for &gen_id in &hir.gens {
+            for (body, env) in self.gen_block_bodies(gen_id, env)? {
+                self.collect_package_vars(body, env, into)?;
             }
-            _ => panic!("no zero-value for type {}", ty),
         }
+        Ok(())
     }
 
-    /// Get the type of an LLHD value.
-    fn llhd_type(&self, value: llhd::ir::Value) -> llhd::Type {
-        self.builder.value_type(value)
-    }
-
-    /// Emit the code for an rvalue.
-    fn emit_rvalue(&mut self, expr_id: NodeId, env: ParamEnv) -> Result<llhd::ir::Value> {
-        self.emit_rvalue_mode(expr_id, env, Mode::Value)
-    }
-
-    /// Emit the code for an rvalue.
-    fn emit_rvalue_mode(
+    /// Check whether a module block or the modules it instantiates contain
+    /// initial procedures of a program.
+    fn contains_programs(
         &mut self,
-        expr_id: NodeId,
+        hir: &'gcx hir::ModuleBlock,
+        program: bool,
         env: ParamEnv,
-        mode: Mode,
-    ) -> Result<llhd::ir::Value> {
-        let mir = self.mir_rvalue(expr_id, env);
-        self.emit_mir_rvalue_mode(mir, mode)
-    }
-
-    /// Emit the code for an MIR rvalue.
-    fn emit_mir_rvalue(&mut self, mir: &'gcx mir::Rvalue<'gcx>) -> Result<llhd::ir::Value> {
-        self.emit_mir_rvalue_mode(mir, Mode::Value)
-    }
-
-    /// Emit the code for an MIR rvalue.
-    fn emit_mir_rvalue_mode(
-        &mut self,
-        mir: &'gcx mir::Rvalue<'gcx>,
-        mode: Mode,
-    ) -> Result<llhd::ir::Value> {
-        let (value, actual_mode) = if let Some(x) = self.interned_rvalues.get(&(mir.id, mode)) {
-            x.clone()?
-        } else {
-            let x = self.emit_mir_rvalue_uninterned(mir, mode);
-            self.interned_rvalues.insert((mir.id, mode), x);
-            x?
-        };
-
-        match (mode, actual_mode) {
-            (Mode::Signal, Mode::Value) => {
-                let ty = self.llhd_type(value);
-                let init = self.emit_zero_for_type(&ty);
-                let sig = self.builder.ins().sig(init);
-                let delay = llhd::value::TimeValue::new(num::zero(), 1, 0);
-                let delay_const = self.builder.ins().const_time(delay);
-                self.builder.ins().drv(sig, value, delay_const);
-                Ok(sig)
+    ) -> Result<bool> {
+        if program {
+            for &proc_id in &hir.procs {
+                match self.hir_of(proc_id)? {
+                    HirNode::Proc(x) if x.kind == ast::ProcedureKind::Initial => return Ok(true),
+                    HirNode::Proc(_) => (),
+                    _ => unreachable!(),
+                }
+            }
+        }
+        for &inst_id in &hir.insts {
+            let inst = match self.hir_of(inst_id)? {
+                HirNode::Inst(x) => x,
+                _ => unreachable!(),
+            };
+            let inst = self.inst_details(Ref(inst), env)?;
+            if let InstTarget::Module(x) = inst.target.kind {
+                let module = self.hir_of_module(x)?;
+                let program = module.kind == ast::ModuleKind::Program;
+                if self.contains_programs(&module.block, program, inst.inner_env)? {
+                    return Ok(true);
+                }
+            }
+        }
+        for &gen_id in &hir.gens {
+            for (body, env) in self.gen_block_bodies(gen_id, env)? {
+                if self.contains_programs(body, program, env)? {
+                    return Ok(true);
+                }
             }
-            (Mode::Value, Mode::Signal) => unreachable!(),
-            _ => Ok(value),
         }
+        Ok(false)
     }
 
-    /// Emit the code for an MIR rvalue.
+    /// Collect the declarations accessed hierarchically through the module
+    /// instances visible in a module block and the modules it instantiates.
     ///
-    /// Wrapper around `emit_mir_rvalue_inner` that asserts the LLHD type of the
-    /// result matches expectations.
-    fn emit_mir_rvalue_uninterned(
-        &mut self,
-        mir: &'gcx mir::Rvalue<'gcx>,
-        mode_hint: Mode,
-    ) -> Result<(llhd::ir::Value, Mode)> {
-        let result = self.emit_mir_rvalue_inner(mir, mode_hint);
-        match result {
-            Ok((result, actual_mode)) => {
-                let llty_exp = self.emit_type(mir.ty)?;
-                let llty_exp = match actual_mode {
-                    Mode::Value => llty_exp,
-                    Mode::Signal => llhd::signal_ty(llty_exp),
-                };
-                let llty_act = self.llhd_type(result);
-                assert_span!(
-                    llty_exp == llty_act,
-                    mir.span,
-                    self.cx,
-                    "codegen for MIR rvalue `{}` should produce `{}`, but got `{}`; {:#?}",
-                    mir.span.extract(),
-                    llty_exp,
-                    llty_act,
-                    mir
-                );
+    /// The declarations are recorded per instantiated module, such that the
+    /// module can pass them up as additional outputs.
+    fn collect_module_exports(&mut self, hir: &'gcx hir::ModuleBlock, env: ParamEnv) -> Result<()> {
+        let mut accessed = vec![];
+        for &proc_id in &hir.procs {
+            match self.hir_of(proc_id)? {
+                HirNode::Proc(x) => accessed.push(self.accessed_nodes(x.stmt, env)?),
+                _ => unreachable!(),
             }
-            Err(()) => (),
         }
-        result
+        for &id in hir.assigns.iter().chain(hir.asserts.iter()) {
+            accessed.push(self.accessed_nodes(id, env)?);
+        }
+        let mut insts = vec![];
+        for &inst_id in &hir.insts {
+            let inst = match self.hir_of(inst_id)? {
+                HirNode::Inst(x) => x,
+                _ => unreachable!(),
+            };
+            let inst = self.inst_details(Ref(inst), env)?;
+            for &(_, expr) in &inst.ports.0 {
+                accessed.push(self.accessed_nodes(expr.id(), expr.env())?);
+            }
+            insts.push(inst);
+        }
+        for table in accessed {
+            for node in table.read.iter().chain(table.written.iter()) {
+                if let AccessedNode::Intf(inst, id) = *node {
+                    if let Some(module) = self.type_of(inst, env)?.resolve_full().core.get_module()
+                    {
+                        self.tables
+                            .module_exports
+                            .entry(module.ast.id().env(module.env))
+                            .or_default()
+                            .insert(id);
+                    }
+                }
+            }
+        }
+        for inst in insts {
+            if let InstTarget::Module(x) = inst.target.kind {
+                let module = self.hir_of_module(x)?;
+                self.collect_module_exports(&module.block, inst.inner_env)?;
+            }
+        }
+        for &gen_id in &hir.gens {
+            for (body, env) in self.gen_block_bodies(gen_id, env)? {
+                self.collect_module_exports(body, env)?;
+            }
+        }
+        Ok(())
     }
 
-    /// Emit the code for an MIR rvalue.
-    ///
-    /// The `mode_hint` parameter provides a hint if the caller ultimately wants
-    /// the result to be a signal or a value. For example, port connections to
-    /// an instance would set the hint to `Mode::Signal`, while a binary expr
-    /// would set it to `Mode::Value`. The function is free to ignore the hint,
-    /// but will provide the actual mode it produced as part of the return
-    /// value.
-    fn emit_mir_rvalue_inner(
-        &mut self,
-        mir: &'gcx mir::Rvalue<'gcx>,
-        mode_hint: Mode,
-    ) -> Result<(llhd::ir::Value, Mode)> {
-        // If the value is a constant, emit the fully folded constant value.
-        if mir.is_const() {
-            let value = self.const_mir_rvalue(mir.into());
-            return self
-                .emit_const(value, mir.env, mir.span)
-                .map(|v| (v, Mode::Value));
+    /// Execute the initialization step of a generate loop.
+    fn execute_genvar_init(&mut self, id: NodeId, env: ParamEnv) -> Result<ParamEnv> {
+        let hir = self.hir_of(id)?;
+        match hir {
+            HirNode::GenvarDecl(_) => Ok(env),
+            HirNode::Stmt(stmt) => match stmt.kind {
+                hir::StmtKind::Assign {
+                    lhs,
+                    rhs,
+                    kind: hir::AssignKind::Block(ast::AssignOp::Identity),
+                } => {
+                    let target_id = self.resolve_node(lhs, env)?;
+                    let init_value = self.constant_value_of(rhs, env);
+                    let mut env_data = self.param_env_data(env).clone();
+                    env_data.set_value(target_id, init_value);
+                    Ok(self.intern_param_env(env_data))
+                }
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
         }
+    }
 
-        let value = match mir.kind {
-            mir::RvalueKind::Var(id) | mir::RvalueKind::Port(id) => {
-                let sig = self
-                    .shadows
-                    .get(&id.into())
-                    .cloned()
-                    .unwrap_or_else(|| self.emitted_value(id));
-                if mode_hint == Mode::Signal && self.llhd_type(sig).is_signal() {
-                    return Ok((sig, Mode::Signal));
-                } else {
-                    Ok(self.emit_prb_or_var(sig))
+    /// Execute the iteration step of a generate loop.
+    fn execute_genvar_step(&mut self, id: NodeId, env: ParamEnv) -> Result<ParamEnv> {
+        let hir = self.hir_of(id)?;
+        let mut env_data = self.param_env_data(env).clone();
+        let next = match hir {
+            HirNode::Expr(expr) => match expr.kind {
+                hir::ExprKind::Unary(op, target_id) => {
+                    let target_id = self.resolve_node(target_id, env)?;
+                    let current_value = self.constant_value_of(target_id, env);
+                    let next_value = match current_value.kind {
+                        ValueKind::Int(ref v, ..) => match op {
+                            hir::UnaryOp::PostInc | hir::UnaryOp::PreInc => Some(v + 1),
+                            hir::UnaryOp::PostDec | hir::UnaryOp::PreDec => Some(v - 1),
+                            _ => None,
+                        }
+                        .map(|v| value::make_int(current_value.ty, v)),
+                        _ => unreachable!(),
+                    };
+                    next_value.map(|v| (target_id, self.intern_value(v)))
+                }
+                hir::ExprKind::Assign { .. } => {
+                    let mir = self.mir_rvalue(id, env);
+                    match mir.kind {
+                        mir::RvalueKind::Error => return Err(()),
+                        mir::RvalueKind::Assignment { lvalue, rvalue, .. } => {
+                            let target_id = match lvalue.kind {
+                                mir::LvalueKind::Error => return Err(()),
+                                mir::LvalueKind::Genvar(id) => id,
+                                _ => unreachable!(),
+                            };
+                            let next_value = self.const_mir_rvalue(Ref(rvalue));
+                            Some((target_id, next_value))
+                        }
+                        _ => unreachable!(),
+                    }
                 }
+                _ => None,
+            },
+            _ => None,
+        };
+        match next {
+            Some((target_id, next_value)) => {
+                env_data.set_value(target_id, next_value);
+                return Ok(self.intern_param_env(env_data));
             }
-
-            mir::RvalueKind::Intf(_) => {
+            None => {
                 self.emit(
-                    DiagBuilder2::error("interface cannot be used in an expression").span(mir.span),
+                    DiagBuilder2::error(format!(
+                        "{} is not a valid genvar iteration step",
+                        hir.desc_full()
+                    ))
+                    .span(hir.human_span()),
                 );
                 Err(())
             }
+        }
+    }
+}
 
-            // Interface signals require special care, because they are emitted
-            // in a transposed fashion.
-            mir::RvalueKind::IntfSignal(value, signal) => {
-                return self.emit_rvalue_interface(value, signal, mode_hint);
-            }
+/// A code generator for functions, processes, and entities.
+struct UnitGenerator<'a, 'gcx, C> {
+    /// The global code generator.
+    gen: &'a mut CodeGenerator<'gcx, C>,
+    /// The builder into which instructions are emitted.
+    builder: &'a mut llhd::ir::UnitBuilder<'a>,
+    /// The emitted LLHD values for various nodes.
+    values: &'a mut HashMap<AccessedNode, llhd::ir::Value>,
+    /// The constant values emitted into the unit.
+    interned_consts: HashMap<Value<'gcx>, Result<llhd::ir::Value>>,
+    /// The MIR lvalues emitted into the unit.
+    interned_lvalues: HashMap<NodeId, Result<(llhd::ir::Value, Option<llhd::ir::Value>)>>,
+    /// The MIR rvalues emitted into the unit.
+    interned_rvalues: HashMap<(NodeId, Mode), Result<(llhd::ir::Value, Mode)>>,
+    /// The shadow variables introduced to handle signals which are both read
+    /// and written in a process.
+    shadows: HashMap<AccessedNode, llhd::ir::Value>,
+    /// The subroutines currently being inlined into the unit.
+    inlined: Vec<NodeId>,
+    /// The object on which the method currently being inlined operates.
+    this: Option<llhd::ir::Value>,
+    /// The signals that synchronize the unit with the processes it starts.
+    sync_signals: HashMap<SyncSignal, llhd::ir::Value>,
+    /// The deferred assertions and fork-join blocks started by the unit,
+    /// together with the variable that tracks their current generation.
+    generations: Vec<(NodeId, llhd::ir::Value)>,
+    /// The history of past values kept for calls to sampled value functions.
+    /// The variables hold the values 1, 2, etc. clock ticks ago.
+    sampled: HashMap<NodeId, Vec<llhd::ir::Value>>,
+    /// The fork-join branch the unit implements, if any.
+    fork_branch: Option<ForkBranch>,
+    /// The statements and subroutines currently being emitted which can be
+    /// exited early, innermost last.
+    exits: Vec<ExitTarget>,
+    /// The nets whose drivers are combined by a resolution function.
+    nets: HashMap<AccessedNode, ResolvedNet>,
+    /// Whether the unit belongs to a program, whose processes run in the
+    /// reactive region.
+    program: bool,
+    /// The signals that indicate the completion of the initial procedures of
+    /// a program.
+    program_done: Vec<llhd::ir::Value>,
+}
 
-            mir::RvalueKind::CastValueDomain { value, .. } => {
-                // TODO(fschuiki): Turn this into an actual `iN` to `lN` cast.
-                return self.emit_mir_rvalue_inner(value, mode_hint);
-            }
+impl<'a, 'gcx, C> Deref for UnitGenerator<'a, 'gcx, C> {
+    type Target = CodeGenerator<'gcx, C>;
 
-            mir::RvalueKind::Transmute(value) => {
-                // Transmute is a simple no-op.
-                return self.emit_mir_rvalue_inner(value, mode_hint);
-            }
+    fn deref(&self) -> &CodeGenerator<'gcx, C> {
+        &self.gen
+    }
+}
 
-            mir::RvalueKind::CastSign(_, value) => {
-                // Sign conversions are no-ops in LLHD since they merely
-                // influence the type system.
-                return self.emit_mir_rvalue_inner(value, mode_hint);
+impl<'a, 'gcx, C> DerefMut for UnitGenerator<'a, 'gcx, C> {
+    fn deref_mut(&mut self) -> &mut CodeGenerator<'gcx, C> {
+        &mut self.gen
+    }
+}
+
+impl<'a, 'b, 'gcx, C> UnitGenerator<'a, 'gcx, &'b C>
+where
+    C: Context<'gcx> + 'b,
+{
+    fn emitted_value(&self, src: impl Into<AccessedNode>) -> llhd::ir::Value {
+        let src = src.into();
+        match self.values.get(&src) {
+            Some(&v) => v,
+            None => bug_span!(
+                self.span(match src {
+                    AccessedNode::Regular(id) => id,
+                    AccessedNode::Intf(_, id) => id,
+                }),
+                self.cx,
+                "no value emitted for {:?}",
+                src
+            ),
+        }
+    }
+
+    fn set_emitted_value(&mut self, src: impl Into<AccessedNode>, value: llhd::ir::Value) {
+        let src = src.into();
+        self.values.insert(src, value);
+    }
+
+    /// Clear the cached MIR lvalues and rvalues. This should be called before
+    /// or after emitting an expression, and at least for every statement.
+    /// Otherwise MIR codegen might reuse values that have become out-of-date
+    /// due to time passing in between statements.
+    fn flush_mir(&mut self) {
+        self.interned_lvalues.clear();
+        self.interned_rvalues.clear();
+    }
+
+    /// Emit the code for the contents of a module.
+    fn emit_module_block(
+        &mut self,
+        id: NodeId,
+        env: ParamEnv,
+        hir: &'gcx hir::ModuleBlock,
+        name_prefix: &str,
+    ) -> Result<()> {
+        // Emit declarations. The drivers are collected for every emitted
+        // block, such that each iteration of a generate loop only sees the
+        // drivers of its own nets.
+        let mut net_drivers = HashMap::new();
+        self.collect_net_drivers(hir, env, &mut net_drivers)?;
+        for &decl_id in &hir.decls {
+            let hir = match self.hir_of(decl_id)? {
+                HirNode::VarDecl(x) => x,
+                _ => unreachable!(),
+            };
+            let ty = self.type_of(decl_id, env)?;
+            let drivers = net_drivers.get(&decl_id).map(Vec::as_slice);
+            let net_ty = self.net_resolution_type(hir, ty, drivers.unwrap_or(&[]))?;
+            let init = match net_ty {
+                Some(_) => None,
+                None => hir.init,
+            };
+            let value = self.emit_varnet_decl(decl_id, ty, env, init)?;
+            self.builder.set_name(value, hir.name.value.into());
+            self.values.insert(decl_id.into(), value.into());
+
+            // Nets with multiple drivers are resolved, in which case the
+            // initial value becomes one of the drivers. The unknown plane of
+            // the resolved value is kept in a separate signal next to the net,
+            // which is initially `z`.
+            if let Some(net_ty) = net_ty {
+                let width = ty.get_bit_size().unwrap_or(0);
+                let zero = self.builder.ins().const_int((width, 0));
+                let ones = self.builder.ins().not(zero);
+                let unknown = self.builder.ins().sig(ones);
+                self.builder.set_name(unknown, format!("{}.unk", hir.name.value));
+                let net = ResolvedNet {
+                    ty: net_ty,
+                    width,
+                    name: hir.name.value.to_string(),
+                    signal: value,
+                    unknown: Some(unknown),
+                    outputs: None,
+                    drivers: vec![],
+                };
+                self.nets.insert(decl_id.into(), net);
+                if let Some(init) = hir.init {
+                    let (value, unknown) = self.add_net_driver(decl_id.into());
+                    let mir = self.mir_rvalue(init, env);
+                    let (rhs, rhs_unknown) = self.emit_mir_rvalue_planes(mir)?;
+                    let delay = llhd::value::TimeValue::new(num::zero(), 0, 1);
+                    let delay = self.builder.ins().const_time(delay);
+                    self.builder.ins().drv(value, rhs, delay);
+                    self.builder.ins().drv(unknown, rhs_unknown, delay);
+                }
+            }
+        }
+
+        // Emit interface instances.
+        for &inst_id in &hir.insts {
+            // Resolve the instantiation details.
+            let inst = match self.hir_of(inst_id)? {
+                HirNode::Inst(x) => x,
+                _ => unreachable!(),
+            };
+            let inst = self.inst_details(Ref(inst), env)?;
+
+            // Compute the array dimensions for the signals.
+            // let mut dims = vec![];
+            let inst_ty = self.type_of_inst(Ref(inst.hir), env);
+            let intf_ty = match inst_ty.resolve_full().core.get_interface() {
+                Some(x) => x,
+                None => continue,
+            };
+            trace!(
+                "Interface instance is of type `{}` ({:?})",
+                inst_ty,
+                intf_ty
+            );
+
+            // Expand the interface declarations.
+            let signals = self.determine_interface_signals(intf_ty, &inst_ty.dims)?;
+            let mut signal_lookup = HashMap::new();
+            for signal in signals {
+                let value =
+                    self.emit_varnet_decl(signal.decl_id, signal.ty, intf_ty.env, signal.default)?;
+                self.builder
+                    .set_name(value, format!("{}.{}", inst.hir.name, signal.name));
+                let src = AccessedNode::Intf(inst_id, signal.decl_id);
+                trace!(
+                    "Emitted value for {:?} {}.{}",
+                    src,
+                    inst.hir.name,
+                    signal.name
+                );
+                self.values.insert(src, value.into());
+                signal_lookup.insert(signal.decl_id, value);
+            }
+
+            // Generate the code for the port assignments.
+            let port_list = self.canonicalize_ports(intf_ty.ast);
+            let ports = self.determine_module_ports(&port_list.int, intf_ty.env)?;
+            let (inputs, outputs) = self.emit_port_connections(
+                port_list,
+                inst.as_ref(),
+                0,
+                1,
+                &ports.inputs,
+                &ports.outputs,
+            )?;
+            trace!("Attaching interface inputs {:?}", inputs);
+            trace!("Attaching interface outputs {:?}", outputs);
+            trace!("Signal lookup: {:?}", signal_lookup);
+
+            // Actually wire up the ports.
+            let inputs = inputs.into_iter().zip(ports.inputs.iter());
+            let outputs = outputs.into_iter().zip(ports.outputs.iter());
+            for (assigned, port) in inputs.chain(outputs) {
+                match port.kind {
+                    ModulePortKind::InoutValue | ModulePortKind::InoutUnknown => continue,
+                    _ => (),
+                }
+                trace!(
+                    "Assign `{}` ({:?}) = {:?}",
+                    port.name,
+                    port.port.id,
+                    assigned
+                );
+                self.builder
+                    .ins()
+                    .con(signal_lookup[&port.port.id], assigned);
+            }
+        }
+
+        // Emit the signals that carry the declarations accessed hierarchically
+        // in module instances. Like interface signals, they are transposed
+        // such that there is one signal for each declaration across all
+        // elements of an instance array.
+        for &inst_id in &hir.insts {
+            let inst = match self.hir_of(inst_id)? {
+                HirNode::Inst(x) => x,
+                _ => unreachable!(),
+            };
+            let inst_ty = self.type_of_inst(Ref(inst), env);
+            let module = match inst_ty.resolve_full().core.get_module() {
+                Some(x) => x,
+                None => continue,
+            };
+            let exports = match self
+                .tables
+                .module_exports
+                .get(&module.ast.id().env(module.env))
+            {
+                Some(x) => x.clone(),
+                None => continue,
+            };
+            for decl_id in exports {
+                let ty = self.transposed_signal_type(inst_id, decl_id, env)?;
+                let value = self.emit_const(self.type_default_value(ty), env, inst.span)?;
+                let value = self.builder.ins().sig(value);
+                let name = match self.hir_of(decl_id)? {
+                    HirNode::VarDecl(x) => x.name,
+                    _ => unreachable!(),
+                };
+                self.builder
+                    .set_name(value, format!("{}.{}", inst.name, name));
+                self.values
+                    .insert(AccessedNode::Intf(inst_id, decl_id), value.into());
+            }
+        }
+
+        // Emit assignments.
+        for &assign_id in &hir.assigns {
+            let hir = match self.hir_of(assign_id)? {
+                HirNode::Assign(x) => x,
+                _ => unreachable!(),
+            };
+
+            // Map the assignment to an MIR node.
+            let assign_mir = self.mir_assignment_from_concurrent(Ref(hir), env);
+            debug!("Concurrent assignment: {:#?}", assign_mir);
+
+            // Simplify the assignment to eliminate concatenations on the
+            // left-hand side.
+            let simplified = self.mir_simplify_assignment(Ref(assign_mir));
+            debug!("Simplified to: {:#?}", simplified);
+
+            // Check for sanity.
+            for &assign in &simplified {
+                if assign.is_error() {
+                    return Err(());
+                }
+                assert_type!(assign.rhs.ty, assign.lhs.ty, assign.rhs.span, self.cx);
+            }
+
+            // Emit the assignments.
+            let delay = llhd::value::TimeValue::new(num::zero(), 0, 1);
+            let delay = self.builder.ins().const_time(delay);
+            for &assign in &simplified {
+                let (lhs, unknown) = self.emit_net_driver(assign.lhs)?;
+                match unknown {
+                    Some(unknown) => {
+                        let (rhs, rhs_unknown) = self.emit_mir_rvalue_planes(assign.rhs)?;
+                        self.builder.ins().drv(lhs, rhs, delay);
+                        self.builder.ins().drv(unknown, rhs_unknown, delay);
+                    }
+                    None => {
+                        let rhs = self.emit_mir_rvalue(assign.rhs)?;
+                        self.builder.ins().drv(lhs, rhs, delay);
+                    }
+                }
+            }
+        }
+
+        // Emit module instantiations.
+        for &inst_id in &hir.insts {
+            // Resolve the instantiation details.
+            let inst = match self.hir_of(inst_id)? {
+                HirNode::Inst(x) => x,
+                _ => unreachable!(),
+            };
+            let inst = self.inst_details(Ref(inst), env)?;
+            let target_module = match inst.target.kind {
+                InstTarget::Module(x) => self.hir_of_module(x)?,
+                _ => continue,
+            };
+
+            // Emit the instantiated module. The completion of the programs it
+            // contains is tracked alongside the programs of this module.
+            let target = self.emit_module_with_env(target_module.id, inst.inner_env)?;
+            let program_done = match target.ports.program_done {
+                true => {
+                    let zero = self.builder.ins().const_int((1, 0));
+                    let done = self.builder.ins().sig(zero);
+                    self.builder
+                        .set_name(done, format!("{}.done", inst.hir.name));
+                    self.program_done.push(done);
+                    Some(done)
+                }
+                false => None,
+            };
+            let ext_unit = self.builder.add_extern(
+                self.into.unit(target.unit).name().clone(),
+                self.into.unit(target.unit).sig().clone(),
+            );
+
+            // Instantiate the module, once for every element of an instance
+            // array.
+            let inst_ty = self.type_of_inst(Ref(inst.hir), env);
+            let count = inst_array_len(self.cx, inst.hir, env)?;
+            for index in 0..count {
+                let (inputs, outputs) = self.emit_port_connections(
+                    target_module.ports_new,
+                    inst.as_ref(),
+                    index,
+                    count,
+                    &target.ports.inputs,
+                    &target.ports.outputs,
+                )?;
+                let mut exports = vec![];
+                for &decl_id in &target.ports.exports {
+                    let mut value = self.values[&AccessedNode::Intf(inst_id, decl_id)];
+                    for offset in inst_array_offsets(&inst_ty.dims, index).unwrap() {
+                        value = self.builder.ins().ext_field(value, offset);
+                    }
+                    exports.push(value);
+                }
+                let outputs = outputs
+                    .into_iter()
+                    .chain(
+                        target
+                            .ports
+                            .globals
+                            .iter()
+                            .map(|&id| self.values[&id.into()]),
+                    )
+                    .chain(exports)
+                    .chain(program_done)
+                    .collect();
+                self.builder.ins().inst(ext_unit, inputs, outputs);
+                // TODO: Annotate instance name once LLHD allows that.
+            }
+        }
+
+        // Emit generate blocks.
+        for &gen_id in &hir.gens {
+            for (body, env) in self.gen_block_bodies(gen_id, env)? {
+                self.emit_module_block(id, env, body, name_prefix)?;
+            }
+        }
+
+        // Create the signals that synchronize deferred assertions with the
+        // processes that execute their action blocks, and fork-join blocks
+        // with their branches. Both are emitted as separate processes.
+        let mut owners = vec![];
+        for &proc_id in &hir.procs {
+            match self.hir_of(proc_id)? {
+                HirNode::Proc(x) => owners.push(x.stmt),
+                _ => unreachable!(),
+            }
+        }
+        let mut deferred = vec![];
+        let mut branches = vec![];
+        while let Some(owner) = owners.pop() {
+            for stmt in self.started_stmts(owner)? {
+                self.emit_sync_signal(SyncSignal::Ctl(stmt.id), env)?;
+                for value_id in self.started_values(stmt, env)? {
+                    self.emit_sync_signal(SyncSignal::Value(stmt.id, value_id), env)?;
+                }
+                match stmt.kind {
+                    hir::StmtKind::Fork {
+                        branches: ref ids, ..
+                    } => {
+                        for &branch_id in ids {
+                            self.emit_sync_signal(SyncSignal::Done(branch_id), env)?;
+                            branches.push((branch_id, stmt.id));
+                            owners.push(branch_id);
+                        }
+                    }
+                    _ => {
+                        self.emit_sync_signal(SyncSignal::Cond(stmt.id), env)?;
+                        deferred.push(stmt.id);
+                    }
+                }
+            }
+        }
+
+        // Emit and instantiate procedures.
+        let program = self.program;
+        for &proc_id in &hir.procs {
+            let prok = self.emit_procedure(proc_id, env, name_prefix, program)?;
+            self.emit_procedure_inst(proc_id, prok);
+        }
+        for assert_id in deferred {
+            let prok = self.emit_deferred_assertion(assert_id, env, name_prefix, program)?;
+            self.emit_procedure_inst(assert_id, prok);
+        }
+        for (branch_id, fork_id) in branches {
+            let prok = self.emit_fork_branch(branch_id, fork_id, env, name_prefix, program)?;
+            self.emit_procedure_inst(branch_id, prok);
+        }
+
+        // Emit concurrent assertions.
+        for &assert_id in &hir.asserts {
+            let name = match self.hir_of(assert_id)? {
+                HirNode::Assertion(x) => match x.label {
+                    Some(label) => format!("{}.violation", label.value),
+                    None => format!("assert{}.violation", assert_id.as_usize()),
+                },
+                _ => unreachable!(),
+            };
+            let prok = self.emit_checker(assert_id, env, name_prefix)?;
+            let mut inputs = vec![];
+            let mut outputs = vec![];
+            for id in &prok.inputs {
+                inputs.push(self.values[id]);
+            }
+            for id in &prok.outputs {
+                outputs.push(self.values[id]);
+            }
+            let zero = self.builder.ins().const_int((1, 0));
+            let violation = self.builder.ins().sig(zero);
+            self.builder.set_name(violation, name);
+            outputs.push(violation);
+            let ext_unit = self.builder.add_extern(
+                self.into.unit(prok.unit).name().clone(),
+                self.into.unit(prok.unit).sig().clone(),
+            );
+            self.builder.ins().inst(ext_unit, inputs, outputs);
+        }
+
+        // Resolve the drivers of the nets declared in this block.
+        for &decl_id in &hir.decls {
+            if let Some(net) = self.nets.remove(&decl_id.into()) {
+                self.emit_net_resolution(net)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Instantiate an emitted procedure.
+    fn emit_procedure_inst(&mut self, proc_id: NodeId, prok: EmittedProcedure) {
+        let lookup_value = |&id: &AccessedNode| match self.values.get(&id) {
+            Some(v) => v.clone(),
+            None => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "{} used as input/output of {}, but no value has been emitted",
+                        self.hir_of(id.id()).unwrap().desc_full(),
+                        self.hir_of(proc_id).unwrap().desc_full(),
+                    ))
+                    .span(self.span(id.id())),
+                );
+                panic!("no value emitted for {:?}", id);
+            }
+        };
+        let inputs = prok
+            .inputs
+            .iter()
+            .map(lookup_value)
+            .chain(prok.sync_inputs.iter().map(|s| self.sync_signals[s]))
+            .collect();
+        let mut outputs: Vec<_> = prok
+            .outputs
+            .iter()
+            .map(lookup_value)
+            .chain(prok.sync_outputs.iter().map(|s| self.sync_signals[s]))
+            .collect();
+        if prok.program_done {
+            let zero = self.builder.ins().const_int((1, 0));
+            let done = self.builder.ins().sig(zero);
+            self.builder
+                .set_name(done, format!("initial{}.done", proc_id.as_usize()));
+            self.program_done.push(done);
+            outputs.push(done);
+        }
+        let ext_unit = self.builder.add_extern(
+            self.into.unit(prok.unit).name().clone(),
+            self.into.unit(prok.unit).sig().clone(),
+        );
+        self.builder.ins().inst(ext_unit, inputs, outputs);
+    }
+
+    /// Emit a synchronization signal.
+    fn emit_sync_signal(&mut self, signal: SyncSignal, env: ParamEnv) -> Result<()> {
+        let ty = self.sync_signal_type(signal, env)?;
+        let zero = self.emit_zero_for_type(&ty);
+        let value = self.builder.ins().sig(zero);
+        let name = self.sync_signal_name(signal);
+        self.builder.set_name(value, name);
+        self.sync_signals.insert(signal, value);
+        Ok(())
+    }
+
+    /// Emit code for the connections made in a port list.
+    ///
+    /// For instance arrays, `index` selects the instance among the `count`
+    /// instances of the array, counting from the left-most one.
+    fn emit_port_connections(
+        &mut self,
+        port_list: &'gcx PortList<'gcx>,
+        inst: &InstDetails<'gcx>,
+        index: usize,
+        count: usize,
+        inputs: &[ModulePort<'gcx>],
+        outputs: &[ModulePort<'gcx>],
+    ) -> Result<(Vec<llhd::ir::Value>, Vec<llhd::ir::Value>)> {
+        // Map the values associated with the external ports to internal
+        // ports. External ports with concatenations or selects map slices of
+        // the connected value to slices of the internal ports.
+        let mut port_mapping_int: HashMap<NodeId, NodeEnvId> = HashMap::new();
+        let mut port_slices_int: HashMap<NodeId, Vec<PortSlice>> = HashMap::new();
+        for port in &port_list.ext_pos {
+            let mapping = match inst.ports.find(port.id) {
+                Some(m) => m,
+                None => continue,
+            };
+            match port.exprs.as_slice() {
+                [expr] if expr.selects.is_empty() => {
+                    let int = &port_list.int[expr.port];
+                    if port_mapping_int.insert(int.id, mapping).is_some() {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "port `{}` connected multiple times",
+                                int.name
+                            ))
+                            .span(self.span(mapping.id())),
+                        );
+                    }
+                }
+                exprs => {
+                    // The left-most expression maps to the MSBs of the
+                    // connected value.
+                    let mut conn_offset = 0;
+                    for expr in exprs.iter().rev() {
+                        let int = &port_list.int[expr.port];
+                        let (port_offset, width) =
+                            ext_port_expr_bits(self.cx, port_list, expr, inst.inner_env)?;
+                        if int.dir == ast::PortDir::Inout {
+                            self.emit(
+                                DiagBuilder2::error(format!(
+                                    "inout port `{}` cannot be part of a port expression",
+                                    int.name
+                                ))
+                                .span(port.span)
+                                .add_note(
+                                    "Only input and output ports can be concatenated or \
+                                     selected from in port expressions",
+                                )
+                                .add_note(format!("Port `{}` declared here:", int.name))
+                                .span(int.span),
+                            );
+                            return Err(());
+                        }
+                        port_slices_int.entry(int.id).or_default().push(PortSlice {
+                            mapping,
+                            conn_offset,
+                            port_offset,
+                            width,
+                        });
+                        conn_offset += width;
+                    }
+                }
+            }
+        }
+        for int in &port_list.int {
+            if port_mapping_int.contains_key(&int.id) && port_slices_int.contains_key(&int.id) {
+                self.emit(
+                    DiagBuilder2::error(format!("port `{}` connected multiple times", int.name))
+                        .span(inst.hir.span()),
+                );
+            }
+        }
+        trace!("Internal Port Mapping: {:?}", port_mapping_int);
+        trace!("Internal Port Slices: {:?}", port_slices_int);
+
+        // Connect to the actual internal ports emitted as the module's port
+        // interface. The unknown bits of an inout port are produced alongside
+        // its value, which immediately precedes them among the outputs.
+        let mut inout_unknown = None;
+        let mut map_port = |port: &ModulePort<'gcx>, lvalue: bool| {
+            trace!(
+                "Mapping port `{}` of type `{}` as {}",
+                port.name,
+                port.ty,
+                match lvalue {
+                    true => "lvalue",
+                    false => "rvalue",
+                }
+            );
+            if let Some(&mapping) = port_mapping_int.get(&port.port.id) {
+                // Emit the assigned node as rvalue or lvalue, depending on
+                // the port direction.
+                if lvalue {
+                    if let ModulePortKind::InoutUnknown = port.kind {
+                        if let Some(unknown) = inout_unknown.take() {
+                            return Ok(unknown);
+                        }
+                        let zero = self.emit_const(
+                            self.type_default_value(port.ty),
+                            inst.inner_env,
+                            port.port.span,
+                        )?;
+                        let value = self.builder.ins().sig(zero);
+                        self.builder
+                            .set_name(value, format!("{}.{}.unused", inst.hir.name, port.name));
+                        return Ok(value);
+                    }
+                    let mir = self.mir_lvalue(mapping.id(), mapping.env());
+                    if mir.is_error() {
+                        return Err(());
+                    }
+                    let element = inst_array_element(
+                        self.cx,
+                        inst.hir,
+                        mapping.env(),
+                        mir.ty,
+                        port.ty,
+                        index,
+                    );
+                    let mir = match port.kind {
+                        ModulePortKind::Port
+                        | ModulePortKind::InoutValue
+                        | ModulePortKind::InoutUnknown
+                            if element.is_some() =>
+                        {
+                            let mut mir = mir;
+                            for offset in element.unwrap() {
+                                let base = self.emit_inst_array_base(offset, mir.origin, mir.env);
+                                mir = self.arena().alloc_mir_lvalue(mir::Lvalue {
+                                    id: NodeId::alloc(),
+                                    origin: mir.origin,
+                                    env: mir.env,
+                                    span: mir.span,
+                                    ty: mir.ty.pop_dim(self.cx).unwrap(),
+                                    kind: mir::LvalueKind::Index {
+                                        value: mir,
+                                        base,
+                                        length: 0,
+                                    },
+                                });
+                            }
+                            mir
+                        }
+                        ModulePortKind::Port
+                        | ModulePortKind::InoutValue
+                        | ModulePortKind::InoutUnknown => {
+                            match inst_array_slice(mir.ty, port.ty, index, count) {
+                                Some((base, length)) => {
+                                    let base = self.emit_inst_array_base(base, mir.origin, mir.env);
+                                    self.arena().alloc_mir_lvalue(mir::Lvalue {
+                                        id: NodeId::alloc(),
+                                        origin: mir.origin,
+                                        env: mir.env,
+                                        span: mir.span,
+                                        ty: port.ty,
+                                        kind: mir::LvalueKind::Index {
+                                            value: mir,
+                                            base,
+                                            length,
+                                        },
+                                    })
+                                }
+                                None => mir,
+                            }
+                        }
+                        ModulePortKind::IntfSignal { decl_id, env, .. } => {
+                            self.arena().alloc_mir_lvalue(mir::Lvalue {
+                                id: NodeId::alloc(),
+                                origin: mir.origin,
+                                env,
+                                span: mir.span,
+                                ty: port.ty,
+                                kind: mir::LvalueKind::IntfSignal(mir, decl_id),
+                            })
+                        }
+                    };
+                    match port.kind {
+                        ModulePortKind::IntfSignal { .. } => {
+                            self.emit_mir_lvalue(mir).map(|(x, _)| x)
+                        }
+                        ModulePortKind::InoutValue => {
+                            let (value, unknown) = self.emit_net_driver(mir)?;
+                            inout_unknown = unknown;
+                            Ok(value)
+                        }
+                        _ => {
+                            let (value, unknown) = self.emit_net_driver(mir)?;
+                            if let Some(unknown) = unknown {
+                                self.emit_known_driver(unknown, port.ty)?;
+                            }
+                            Ok(value)
+                        }
+                    }
+                } else {
+                    let mir = self.mir_rvalue(mapping.id(), mapping.env());
+                    if mir.is_error() {
+                        return Err(());
+                    }
+                    let element = inst_array_element(
+                        self.cx,
+                        inst.hir,
+                        mapping.env(),
+                        mir.ty,
+                        port.ty,
+                        index,
+                    );
+                    let mir = match port.kind {
+                        ModulePortKind::Port
+                        | ModulePortKind::InoutValue
+                        | ModulePortKind::InoutUnknown
+                            if element.is_some() =>
+                        {
+                            let mut mir = mir;
+                            for offset in element.unwrap() {
+                                let base = self.emit_inst_array_base(offset, mir.origin, mir.env);
+                                mir = self.arena().alloc_mir_rvalue(mir::Rvalue {
+                                    id: NodeId::alloc(),
+                                    origin: mir.origin,
+                                    env: mir.env,
+                                    span: mir.span,
+                                    ty: mir.ty.pop_dim(self.cx).unwrap(),
+                                    kind: mir::RvalueKind::Index {
+                                        value: mir,
+                                        base,
+                                        length: 0,
+                                    },
+                                    konst: false,
+                                });
+                            }
+                            mir
+                        }
+                        ModulePortKind::Port
+                        | ModulePortKind::InoutValue
+                        | ModulePortKind::InoutUnknown => {
+                            match inst_array_slice(mir.ty, port.ty, index, count) {
+                                Some((base, length)) => {
+                                    let base = self.emit_inst_array_base(base, mir.origin, mir.env);
+                                    self.arena().alloc_mir_rvalue(mir::Rvalue {
+                                        id: NodeId::alloc(),
+                                        origin: mir.origin,
+                                        env: mir.env,
+                                        span: mir.span,
+                                        ty: port.ty,
+                                        kind: mir::RvalueKind::Index {
+                                            value: mir,
+                                            base,
+                                            length,
+                                        },
+                                        konst: false,
+                                    })
+                                }
+                                None => mir,
+                            }
+                        }
+                        ModulePortKind::IntfSignal { decl_id, env, .. } => {
+                            self.arena().alloc_mir_rvalue(mir::Rvalue {
+                                id: NodeId::alloc(),
+                                origin: mir.origin,
+                                env,
+                                span: mir.span,
+                                ty: port.ty,
+                                kind: mir::RvalueKind::IntfSignal(mir, decl_id),
+                                konst: false,
+                            })
+                        }
+                    };
+                    self.emit_mir_rvalue_mode(mir, Mode::Signal)
+                }
+            } else if let Some(slices) = port_slices_int.get(&port.port.id) {
+                self.emit_port_slices(port, slices, lvalue, inst.inner_env)
+            } else {
+                // Emit an auxiliary signal with the default value for this
+                // port or type.
+                let ty = self.type_of_int_port(Ref(port.port), inst.inner_env);
+                let value = match port.port.data.as_ref().and_then(|d| d.default) {
+                    Some(default) => {
+                        self.emit_rvalue_mode(default, inst.inner_env, Mode::Signal)?
+                    }
+                    None => {
+                        let v = self.type_default_value(ty);
+                        let v = self.emit_const(v, inst.inner_env, port.port.span)?;
+                        self.builder.ins().sig(v)
+                    }
+                };
+                self.builder
+                    .set_name(value, format!("{}.{}.default", inst.hir.name, port.name));
+                Ok(value)
+            }
+        };
+        let inputs = inputs
+            .iter()
+            .map(|p| map_port(p, false))
+            .collect::<Result<Vec<_>>>()?;
+        let outputs = outputs
+            .iter()
+            .map(|p| map_port(p, true))
+            .collect::<Result<Vec<_>>>()?;
+        Ok((inputs, outputs))
+    }
+
+    /// Emit the connection of an internal port to slices of connected values.
+    ///
+    /// Creates an auxiliary signal for the port. For inputs, the slices of the
+    /// connected values are driven onto the signal. For outputs, the slices of
+    /// the signal are driven onto the connected values.
+    fn emit_port_slices(
+        &mut self,
+        port: &ModulePort<'gcx>,
+        slices: &[PortSlice],
+        lvalue: bool,
+        env: ParamEnv,
+    ) -> Result<llhd::ir::Value> {
+        let init = self.type_default_value(port.ty);
+        let init = self.emit_const(init, env, port.port.span)?;
+        let aux = self.builder.ins().sig(init);
+        self.builder.set_name(aux, format!("{}.slices", port.name));
+        let delay = llhd::value::TimeValue::new(num::zero(), 0, 1);
+        let delay = self.builder.ins().const_time(delay);
+        for slice in slices {
+            let (value, target) = if lvalue {
+                let conn = self.mir_lvalue(slice.mapping.id(), slice.mapping.env());
+                let conn_width = conn.ty.get_bit_size().unwrap_or(0);
+                if slice.conn_offset + slice.width > conn_width {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` is too narrow to be connected to port `{}`",
+                            conn.span.extract(),
+                            port.name
+                        ))
+                        .span(conn.span)
+                        .add_note(format!(
+                            "Port `{}` drives bits {} to {} of the connection, but `{}` has \
+                             type `{}`",
+                            port.name,
+                            slice.conn_offset,
+                            slice.conn_offset + slice.width - 1,
+                            conn.span.extract(),
+                            conn.ty
+                        )),
+                    );
+                    return Err(());
+                }
+                let (conn, unknown) = self.emit_net_driver(conn)?;
+                if let Some(unknown) = unknown {
+                    let zero = self.builder.ins().const_int((slice.width, 0));
+                    let unknown =
+                        self.builder
+                            .ins()
+                            .ext_slice(unknown, slice.conn_offset, slice.width);
+                    self.builder.ins().drv(unknown, zero, delay);
+                }
+                let value = self.builder.ins().prb(aux);
+                (
+                    self.builder
+                        .ins()
+                        .ext_slice(value, slice.port_offset, slice.width),
+                    self.builder
+                        .ins()
+                        .ext_slice(conn, slice.conn_offset, slice.width),
+                )
+            } else {
+                let conn = self.mir_rvalue(slice.mapping.id(), slice.mapping.env());
+                let conn = self.emit_mir_rvalue(conn)?;
+                (
+                    self.builder
+                        .ins()
+                        .ext_slice(conn, slice.conn_offset, slice.width),
+                    self.builder
+                        .ins()
+                        .ext_slice(aux, slice.port_offset, slice.width),
+                )
+            };
+            self.builder.ins().drv(target, value, delay);
+        }
+        Ok(aux)
+    }
+
+    /// Create the MIR constant for the base of an instance array slice.
+    fn emit_inst_array_base(
+        &self,
+        base: usize,
+        expr: NodeId,
+        env: ParamEnv,
+    ) -> &'gcx mir::Rvalue<'gcx> {
+        let builder = mir::lower::rvalue::Builder {
+            cx: self.cx,
+            span: self.span(expr),
+            expr,
+            env,
+        };
+        builder.constant_u32(base as u32)
+    }
+
+    /// Collect the bits of each net driven by the continuous assignments and
+    /// instances in a module block, as offset and width.
+    fn collect_net_drivers(
+        &mut self,
+        hir: &'gcx hir::ModuleBlock,
+        env: ParamEnv,
+        into: &mut HashMap<NodeId, Vec<(usize, usize)>>,
+    ) -> Result<()> {
+        // Net declarations with an initial value drive the entire net.
+        for &decl_id in &hir.decls {
+            let decl = match self.hir_of(decl_id)? {
+                HirNode::VarDecl(x) => x,
+                _ => unreachable!(),
+            };
+            if decl.kind.is_net() && decl.init.is_some() {
+                if let Some(width) = self.type_of(decl_id, env)?.get_bit_size() {
+                    into.entry(decl_id).or_default().push((0, width));
+                }
+            }
+        }
+
+        // Continuous assignments drive the bits of their left-hand side.
+        for &assign_id in &hir.assigns {
+            let hir = match self.hir_of(assign_id)? {
+                HirNode::Assign(x) => x,
+                _ => unreachable!(),
+            };
+            let assign_mir = self.mir_assignment_from_concurrent(Ref(hir), env);
+            for assign in self.mir_simplify_assignment(Ref(assign_mir)) {
+                if let Some((id, offset, width)) = lvalue_net_bits(self.cx, assign.lhs) {
+                    into.entry(id).or_default().push((offset, width));
+                }
+            }
+        }
+
+        // Module instances drive the values connected to their output and
+        // inout ports.
+        for &inst_id in &hir.insts {
+            let inst = match self.hir_of(inst_id)? {
+                HirNode::Inst(x) => x,
+                _ => unreachable!(),
+            };
+            let inst = self.inst_details(Ref(inst), env)?;
+            let target_module = match inst.target.kind {
+                InstTarget::Module(x) => self.hir_of_module(x)?,
+                _ => continue,
+            };
+            let port_list = target_module.ports_new;
+            let count = inst_array_len(self.cx, inst.hir, env)?;
+            for port in &port_list.ext_pos {
+                let mapping = match inst.ports.find(port.id) {
+                    Some(m) => m,
+                    None => continue,
+                };
+                let driven = port
+                    .exprs
+                    .iter()
+                    .any(|expr| match port_list.int[expr.port].dir {
+                        ast::PortDir::Output | ast::PortDir::Inout => true,
+                        _ => false,
+                    });
+                if !driven {
+                    continue;
+                }
+                let mir = self.mir_lvalue(mapping.id(), mapping.env());
+                let (id, offset, width) = match lvalue_net_bits(self.cx, mir) {
+                    Some(x) => x,
+                    None => continue,
+                };
+                let mut bits = vec![(offset, width)];
+                if let [expr] = port.exprs.as_slice() {
+                    if expr.selects.is_empty() {
+                        let int = &port_list.int[expr.port];
+                        let port_ty = self.type_of_int_port(Ref(int), inst.inner_env);
+                        bits = (0..count)
+                            .map(
+                                |index| match inst_array_slice(mir.ty, port_ty, index, count) {
+                                    Some((base, length)) => (offset + base, length),
+                                    None => (offset, width),
+                                },
+                            )
+                            .collect();
+                    }
+                }
+                into.entry(id).or_default().extend(bits);
+            }
+        }
+
+        // Generate blocks contribute the drivers of their instantiated bodies.
+        for &gen_id in &hir.gens {
+            for (body, env) in self.gen_block_bodies(gen_id, env)? {
+                self.collect_net_drivers(body, env, into)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Determine whether the drivers of a declared net need to be resolved.
+    ///
+    /// Returns the net type if multiple drivers drive the same bits of the
+    /// net, or if the net type ties or pulls the net to a value.
+    fn net_resolution_type(
+        &mut self,
+        hir: &hir::VarDecl,
+        ty: &'gcx UnpackedType<'gcx>,
+        bits: &[(usize, usize)],
+    ) -> Result<Option<ast::NetType>> {
+        let net_ty = match hir.kind {
+            ast::VarKind::Net { ty, .. } => ty,
+            ast::VarKind::Var => return Ok(None),
+        };
+        if !ty.coalesces_to_llhd_scalar() {
+            return Ok(None);
+        }
+        let overlap = bits.iter().enumerate().any(|(i, &(a_offset, a_width))| {
+            bits[i + 1..].iter().any(|&(b_offset, b_width)| {
+                a_offset < b_offset + b_width && b_offset < a_offset + a_width
+            })
+        });
+        match net_ty {
+            ast::NetType::Supply0
+            | ast::NetType::Supply1
+            | ast::NetType::Tri0
+            | ast::NetType::Tri1 => Ok(Some(net_ty)),
+            ast::NetType::Uwire if overlap => {
+                self.emit(
+                    DiagBuilder2::error(format!("`{}` has multiple drivers", hir.name))
+                        .span(hir.human_span())
+                        .add_note("Nets of type `uwire` may only have a single driver"),
+                );
+                Err(())
+            }
+            _ if overlap => Ok(Some(net_ty)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Add a driver to a resolved net.
+    ///
+    /// Returns the value and unknown signal of the driver. The driver is
+    /// initially `z`.
+    fn add_net_driver(&mut self, node: AccessedNode) -> (llhd::ir::Value, llhd::ir::Value) {
+        let (width, name, index) = {
+            let net = &self.nets[&node];
+            (net.width, net.name.clone(), net.drivers.len())
+        };
+        let zero = self.builder.ins().const_int((width, 0));
+        let ones = self.builder.ins().not(zero);
+        let value = self.builder.ins().sig(zero);
+        self.builder
+            .set_name(value, format!("{}.drv{}", name, index));
+        let unknown = self.builder.ins().sig(ones);
+        self.builder
+            .set_name(unknown, format!("{}.unk{}", name, index));
+        self.nets
+            .get_mut(&node)
+            .unwrap()
+            .drivers
+            .push((value, unknown));
+        (value, unknown)
+    }
+
+    /// Emit an lvalue that is continuously driven, e.g. by an assignment or
+    /// an instance output.
+    ///
+    /// If the lvalue targets a resolved net, a new driver is added to the
+    /// net, and the lvalue is emitted once into the driver's value and once
+    /// into its unknown signal. Otherwise the lvalue targets the signal itself
+    /// and no unknown signal is returned.
+    fn emit_net_driver(
+        &mut self,
+        mir: &'gcx mir::Lvalue<'gcx>,
+    ) -> Result<(llhd::ir::Value, Option<llhd::ir::Value>)> {
+        let node = match lvalue_net_bits(self.cx, mir) {
+            Some((id, ..)) if self.nets.contains_key(&id.into()) => AccessedNode::from(id),
+            _ => return self.emit_mir_lvalue(mir).map(|(x, _)| (x, None)),
+        };
+        let signal = self.values[&node];
+        let (value, unknown) = self.add_net_driver(node);
+        let emit_onto = |gen: &mut Self, target| {
+            gen.flush_mir();
+            gen.values.insert(node, target);
+            let result = gen.emit_mir_lvalue(mir).map(|(x, _)| x);
+            gen.values.insert(node, signal);
+            gen.flush_mir();
+            result
+        };
+        let value = emit_onto(self, value)?;
+        let unknown = emit_onto(self, unknown)?;
+        Ok((value, Some(unknown)))
+    }
+
+    /// Mark all bits of a driver as known, i.e. driven to 0 or 1.
+    fn emit_known_driver(
+        &mut self,
+        unknown: llhd::ir::Value,
+        ty: &'gcx UnpackedType<'gcx>,
+    ) -> Result<()> {
+        let width = match ty.get_bit_size() {
+            Some(w) => w,
+            None => return Err(()),
+        };
+        let zero = self.builder.ins().const_int((width, 0));
+        let delay = llhd::value::TimeValue::new(num::zero(), 0, 1);
+        let delay = self.builder.ins().const_time(delay);
+        self.builder.ins().drv(unknown, zero, delay);
+        Ok(())
+    }
+
+    /// Emit the value and unknown planes of a value driven onto a resolved
+    /// net.
+    ///
+    /// This tracks the `x` and `z` bits of constants and resolved nets through
+    /// conditional operators and concatenations, and considers all other
+    /// values to be known.
+    fn emit_mir_rvalue_planes(
+        &mut self,
+        mir: &'gcx mir::Rvalue<'gcx>,
+    ) -> Result<(llhd::ir::Value, llhd::ir::Value)> {
+        let width = mir.ty.simple_bit_vector(self.cx, mir.span).size;
+        let net_unknown = match mir.kind {
+            mir::RvalueKind::Var(id) | mir::RvalueKind::Port(id) => {
+                self.nets.get(&id.into()).and_then(|net| net.unknown)
+            }
+            _ => None,
+        };
+        if let Some(unknown) = net_unknown {
+            let value = self.emit_mir_rvalue(mir)?;
+            let unknown = self.builder.ins().prb(unknown);
+            return Ok((value, unknown));
+        }
+        if mir.is_const() {
+            let value = self.const_mir_rvalue(mir.into());
+            if let ValueKind::Int(ref int, ref special_bits, ref x_bits) = value.kind {
+                // The special bits are stored MSB first, and a leftmost `x`
+                // or `z` extends to the full width of the value.
+                let len = special_bits.len();
+                let mut value = int.clone();
+                let mut unknown = BigInt::zero();
+                for i in 0..width {
+                    let index = len.saturating_sub(i + 1);
+                    if special_bits.get(index) == Some(true) {
+                        // The value bit distinguishes `x` from `z`.
+                        let bit = BigInt::one() << i;
+                        unknown |= bit.clone();
+                        value |= bit.clone();
+                        if x_bits.get(index) != Some(true) {
+                            value -= bit;
+                        }
+                    }
+                }
+                return Ok((
+                    self.builder.ins().const_int((width, value)),
+                    self.builder.ins().const_int((width, unknown)),
+                ));
+            }
+        }
+        match mir.kind {
+            mir::RvalueKind::Ternary {
+                cond,
+                true_value,
+                false_value,
+            } => {
+                let cond = self.emit_mir_rvalue(cond)?;
+                let (true_value, true_unknown) = self.emit_mir_rvalue_planes(true_value)?;
+                let (false_value, false_unknown) = self.emit_mir_rvalue_planes(false_value)?;
+                let values = self.builder.ins().array(vec![false_value, true_value]);
+                let unknowns = self.builder.ins().array(vec![false_unknown, true_unknown]);
+                Ok((
+                    self.builder.ins().mux(values, cond),
+                    self.builder.ins().mux(unknowns, cond),
+                ))
+            }
+            mir::RvalueKind::Concat(ref values) => {
+                let zero = self.builder.ins().const_int((width, 0));
+                let mut result = (zero, zero);
+                let mut offset = 0;
+                for value in values.iter().rev() {
+                    let value_width = value.ty.simple_bit_vector(self.cx, value.span).size;
+                    let (value, unknown) = self.emit_mir_rvalue_planes(value)?;
+                    result = (
+                        self.builder
+                            .ins()
+                            .ins_slice(result.0, value, offset, value_width),
+                        self.builder
+                            .ins()
+                            .ins_slice(result.1, unknown, offset, value_width),
+                    );
+                    offset += value_width;
+                }
+                Ok(result)
+            }
+            mir::RvalueKind::CastValueDomain { value, .. }
+            | mir::RvalueKind::CastSign(_, value)
+            | mir::RvalueKind::Transmute(value)
+                if value.ty.get_bit_size() == Some(width) =>
+            {
+                self.emit_mir_rvalue_planes(value)
+            }
+            _ => {
+                let value = self.emit_mir_rvalue(mir)?;
+                let zero = self.builder.ins().const_int((width, 0));
+                Ok((value, zero))
+            }
+        }
+    }
+
+    /// Combine the drivers of a resolved net.
+    ///
+    /// The value and unknown planes of the resolved value are driven onto the
+    /// value and unknown outputs of an inout port, or onto the signal and
+    /// unknown signal of a declared net.
+    fn emit_net_resolution(&mut self, net: ResolvedNet) -> Result<()> {
+        let (name, sig) = self.emit_resolution_function(net.ty, net.width, net.drivers.len());
+        let ext_unit = self.builder.add_extern(name, sig);
+        let mut args = vec![];
+        for &(value, unknown) in &net.drivers {
+            args.push(self.builder.ins().prb(value));
+            args.push(self.builder.ins().prb(unknown));
+        }
+        let inst = self.builder.ins().call(ext_unit, args);
+        let result = self.builder.inst_result(inst);
+        let value = self.builder.ins().ext_field(result, 0);
+        let unknown = self.builder.ins().ext_field(result, 1);
+        self.builder
+            .set_name(value, format!("{}.resolved", net.name));
+        let delay = llhd::value::TimeValue::new(num::zero(), 0, 1);
+        let delay = self.builder.ins().const_time(delay);
+        let (value_output, unknown_output) = match net.outputs {
+            Some(outputs) => outputs,
+            None => (net.signal, net.unknown.unwrap()),
+        };
+        self.builder.ins().drv(value_output, value, delay);
+        self.builder.ins().drv(unknown_output, unknown, delay);
+        Ok(())
+    }
+
+    /// Map a value to an LLHD constant (interned).
+    fn emit_const(
+        &mut self,
+        value: Value<'gcx>,
+        env: ParamEnv,
+        span: Span,
+    ) -> Result<llhd::ir::Value> {
+        if let Some(x) = self.interned_consts.get(value) {
+            x.clone()
+        } else {
+            let x = self.emit_const_uninterned(value, env, span);
+            // self.interned_consts.insert(value, x.clone());
+            x
+        }
+    }
+
+    /// Map a value to an LLHD constant.
+    fn emit_const_uninterned(
+        &mut self,
+        value: Value<'gcx>,
+        env: ParamEnv,
+        span: Span,
+    ) -> Result<llhd::ir::Value> {
+        if value.ty.is_error() {
+            return Err(());
+        }
+        match value.kind {
+            ValueKind::Int(ref k, ..) => {
+                let size = match value.ty.get_union() {
+                    Some(strukt) => strukt.get_bit_size().unwrap(),
+                    None => value.ty.simple_bit_vector(self.cx, span).size,
+                };
+                Ok(self.builder.ins().const_int((size, k.clone())))
+            }
+            ValueKind::Time(ref k) => Ok(self
+                .builder
+                .ins()
+                .const_time(llhd::value::TimeValue::new(k.clone(), 0, 0))),
+            ValueKind::Real(bits) => match value.ty.get_real() {
+                Some(ty::RealType::ShortReal) => {
+                    let bits = (f64::from_bits(bits) as f32).to_bits();
+                    Ok(self.builder.ins().const_int((32, bits)))
+                }
+                _ => Ok(self.builder.ins().const_int((64, bits))),
+            },
+            ValueKind::StructOrArray(ref v) => {
+                if let Some(_dim) = value.ty.outermost_dim() {
+                    let fields: Result<Vec<_>> = v
+                        .iter()
+                        .map(|v| self.emit_const(v, env, span).map(Into::into))
+                        .collect();
+                    Ok(self.builder.ins().array(fields?))
+                } else if let Some(_strukt) = value.ty.get_struct() {
+                    let fields: Result<Vec<_>> = v
+                        .iter()
+                        .map(|v| self.emit_const(v, env, span).map(Into::into))
+                        .collect();
+                    Ok(self.builder.ins().strukt(fields?))
+                } else {
+                    panic!(
+                        "invalid type `{}` for const struct/array value {:#?}",
+                        value.ty, value
+                    );
+                }
+            }
+            // The empty string is the null handle, and all other strings are
+            // copied onto the heap.
+            ValueKind::String(ref bytes) if bytes.is_empty() => {
+                Ok(self.builder.ins().const_int((64, 0)))
+            }
+            ValueKind::String(ref bytes) => {
+                let mut chars = vec![];
+                for &c in bytes.iter().chain(Some(&0)) {
+                    chars.push(self.builder.ins().const_int((8, c)));
+                }
+                let chars = self.builder.ins().array(chars);
+                Ok(self.emit_heap_intrinsic("llhd.string.new", vec![chars], llhd::int_ty(64)))
+            }
+            // Null handles and empty containers refer to no storage.
+            ValueKind::Null => Ok(self.builder.ins().const_int((64, 0))),
+            ValueKind::Error => Err(()),
+            _ => panic!(
+                "invalid combination of type `{}` and value {:#?}",
+                value.ty, value
+            ),
+        }
+    }
+
+    /// Emit the zero value for an LLHD type.
+    ///
+    /// This function is ultimately expected to be moved into LLHD.
+    fn emit_zero_for_type(&mut self, ty: &llhd::Type) -> llhd::ir::Value {
+        match **ty {
+            llhd::TimeType => {
+                self.builder
+                    .ins()
+                    .const_time(llhd::value::TimeValue::new(num::zero(), 0, 0))
+            }
+            llhd::IntType(w) => self.builder.ins().const_int((w, 0)),
+            llhd::SignalType(ref ty) => {
+                let inner = self.emit_zero_for_type(ty);
+                self.builder.ins().sig(inner)
+            }
+            llhd::PointerType(ref ty) => {
+                let inner = self.emit_zero_for_type(ty);
+                self.builder.ins().var(inner)
+            }
+            llhd::ArrayType(l, ref ty) => {
+                let inner = self.emit_zero_for_type(ty);
+                self.builder.ins().array_uniform(l, inner)
+            }
+            llhd::StructType(ref tys) => {
+                let inner = tys.iter().map(|ty| self.emit_zero_for_type(ty)).collect();
+                self.builder.ins().strukt(inner)
+            }
+            _ => panic!("no zero-value for type {}", ty),
+        }
+    }
+
+    /// Get the type of an LLHD value.
+    fn llhd_type(&self, value: llhd::ir::Value) -> llhd::Type {
+        self.builder.value_type(value)
+    }
+
+    /// Emit the duration of a delay.
+    ///
+    /// Delays of type `time` are used as they are. Unitless delays are scaled
+    /// to the time unit in effect for the delay. Constant delays are scaled
+    /// here, while delays computed at runtime are passed to an LLHD function
+    /// such as `llhd.time.from_uint`, together with the time unit and
+    /// precision. The simulator is expected to multiply the value by the unit
+    /// and round the result to the precision.
+    fn emit_delay(&mut self, expr_id: NodeId, env: ParamEnv) -> Result<llhd::ir::Value> {
+        let mir = self.mir_rvalue(expr_id, env);
+        if mir.is_error() {
+            return Err(());
+        }
+        if mir.ty.get_packed().map(|p| p.is_time()).unwrap_or(false) {
+            return self.emit_mir_rvalue(mir);
+        }
+        if !mir.is_const() {
+            let name = if mir.ty.is_real() {
+                "llhd.time.from_real"
+            } else if let Some(sbvt) = mir.ty.get_simple_bit_vector() {
+                match sbvt.sign.is_signed() {
+                    true => "llhd.time.from_sint",
+                    false => "llhd.time.from_uint",
+                }
+            } else {
+                self.emit(
+                    DiagBuilder2::error(format!("`{}` is not a valid delay", mir.span.extract()))
+                        .span(mir.span)
+                        .add_note(format!("Delay is of type `{}`", mir.ty)),
+                );
+                return Err(());
+            };
+            let value = self.emit_mir_rvalue(mir)?;
+            return Ok(self.emit_time_conversion(name, value, llhd::time_ty(), expr_id));
+        }
+        let value = self.const_mir_rvalue(mir.into());
+        let value = match value.kind {
+            ValueKind::Int(ref v, ..) => Some(num::BigRational::from_integer(v.clone())),
+            ValueKind::Real(v) => num::BigRational::from_float(f64::from_bits(v)),
+            _ => None,
+        };
+        let value = match value {
+            Some(v) => v,
+            None => {
+                self.emit(
+                    DiagBuilder2::error(format!("`{}` is not a valid delay", mir.span.extract()))
+                        .span(mir.span)
+                        .add_note(format!("Delay is of type `{}`", mir.ty)),
+                );
+                return Err(());
+            }
+        };
+        let duration = self.timescale_of(expr_id).scale(&value);
+        Ok(self
+            .builder
+            .ins()
+            .const_time(llhd::value::TimeValue::new(duration, 0, 0)))
+    }
+
+    /// Emit a call to an LLHD function converting between numbers and time,
+    /// such as `llhd.time.from_uint` or `llhd.time.to_uint`.
+    ///
+    /// The value is followed by the time unit and precision in effect for
+    /// `origin`, which the simulator uses to scale the value and round the
+    /// result.
+    fn emit_time_conversion(
+        &mut self,
+        name: &str,
+        value: llhd::ir::Value,
+        ty: llhd::Type,
+        origin: NodeId,
+    ) -> llhd::ir::Value {
+        let timescale = self.timescale_of(origin);
+        let unit = self.builder.ins().const_time(llhd::value::TimeValue::new(
+            timescale.unit.clone(),
+            0,
+            0,
+        ));
+        let prec = self.builder.ins().const_time(llhd::value::TimeValue::new(
+            timescale.prec.clone(),
+            0,
+            0,
+        ));
+        let mut sig = llhd::ir::Signature::new();
+        sig.add_input(self.llhd_type(value));
+        sig.add_input(llhd::time_ty());
+        sig.add_input(llhd::time_ty());
+        sig.set_return_type(ty);
+        let ext_unit = self
+            .builder
+            .add_extern(llhd::ir::UnitName::Global(name.into()), sig);
+        let inst = self.builder.ins().call(ext_unit, vec![value, unit, prec]);
+        self.builder.inst_result(inst)
+    }
+
+    /// Emit the code for an rvalue.
+    fn emit_rvalue(&mut self, expr_id: NodeId, env: ParamEnv) -> Result<llhd::ir::Value> {
+        self.emit_rvalue_mode(expr_id, env, Mode::Value)
+    }
+
+    /// Emit the code for an rvalue.
+    fn emit_rvalue_mode(
+        &mut self,
+        expr_id: NodeId,
+        env: ParamEnv,
+        mode: Mode,
+    ) -> Result<llhd::ir::Value> {
+        let mir = self.mir_rvalue(expr_id, env);
+        self.emit_mir_rvalue_mode(mir, mode)
+    }
+
+    /// Emit the code for an MIR rvalue.
+    fn emit_mir_rvalue(&mut self, mir: &'gcx mir::Rvalue<'gcx>) -> Result<llhd::ir::Value> {
+        self.emit_mir_rvalue_mode(mir, Mode::Value)
+    }
+
+    /// Emit the code for an MIR rvalue that is stored in a variable.
+    ///
+    /// Containers are copied through an intrinsic such as `llhd.queue.copy`,
+    /// unless the rvalue creates a new container. This gives containers value
+    /// semantics, such that modifying one variable never affects another.
+    fn emit_mir_rvalue_copy(&mut self, mir: &'gcx mir::Rvalue<'gcx>) -> Result<llhd::ir::Value> {
+        let value = self.emit_mir_rvalue(mir)?;
+        let dim = match mir.ty.get_container() {
+            Some(dim) => dim,
+            None => return Ok(value),
+        };
+        match mir.kind {
+            mir::RvalueKind::ArrayNew { .. } | mir::RvalueKind::Null => Ok(value),
+            _ => {
+                let name = format!("{}.copy", container::llhd_prefix(dim));
+                Ok(self.emit_heap_intrinsic(&name, vec![value], llhd::int_ty(64)))
+            }
+        }
+    }
+
+    /// Emit the code for an MIR rvalue.
+    fn emit_mir_rvalue_mode(
+        &mut self,
+        mir: &'gcx mir::Rvalue<'gcx>,
+        mode: Mode,
+    ) -> Result<llhd::ir::Value> {
+        let (value, actual_mode) = if let Some(x) = self.interned_rvalues.get(&(mir.id, mode)) {
+            x.clone()?
+        } else {
+            let x = self.emit_mir_rvalue_uninterned(mir, mode);
+            self.interned_rvalues.insert((mir.id, mode), x);
+            x?
+        };
+
+        match (mode, actual_mode) {
+            (Mode::Signal, Mode::Value) => {
+                let ty = self.llhd_type(value);
+                let init = self.emit_zero_for_type(&ty);
+                let sig = self.builder.ins().sig(init);
+                let delay = llhd::value::TimeValue::new(num::zero(), 1, 0);
+                let delay_const = self.builder.ins().const_time(delay);
+                self.builder.ins().drv(sig, value, delay_const);
+                Ok(sig)
+            }
+            (Mode::Value, Mode::Signal) => unreachable!(),
+            _ => Ok(value),
+        }
+    }
+
+    /// Emit the code for an MIR rvalue.
+    ///
+    /// Wrapper around `emit_mir_rvalue_inner` that asserts the LLHD type of the
+    /// result matches expectations.
+    fn emit_mir_rvalue_uninterned(
+        &mut self,
+        mir: &'gcx mir::Rvalue<'gcx>,
+        mode_hint: Mode,
+    ) -> Result<(llhd::ir::Value, Mode)> {
+        let result = self.emit_mir_rvalue_inner(mir, mode_hint);
+        match result {
+            Ok((result, actual_mode)) => {
+                let llty_exp = self.emit_type(mir.ty)?;
+                let llty_exp = match actual_mode {
+                    Mode::Value => llty_exp,
+                    Mode::Signal => llhd::signal_ty(llty_exp),
+                };
+                let llty_act = self.llhd_type(result);
+                assert_span!(
+                    llty_exp == llty_act,
+                    mir.span,
+                    self.cx,
+                    "codegen for MIR rvalue `{}` should produce `{}`, but got `{}`; {:#?}",
+                    mir.span.extract(),
+                    llty_exp,
+                    llty_act,
+                    mir
+                );
+            }
+            Err(()) => (),
+        }
+        result
+    }
+
+    /// Emit the code for an MIR rvalue.
+    ///
+    /// The `mode_hint` parameter provides a hint if the caller ultimately wants
+    /// the result to be a signal or a value. For example, port connections to
+    /// an instance would set the hint to `Mode::Signal`, while a binary expr
+    /// would set it to `Mode::Value`. The function is free to ignore the hint,
+    /// but will provide the actual mode it produced as part of the return
+    /// value.
+    fn emit_mir_rvalue_inner(
+        &mut self,
+        mir: &'gcx mir::Rvalue<'gcx>,
+        mode_hint: Mode,
+    ) -> Result<(llhd::ir::Value, Mode)> {
+        // If the value is a constant, emit the fully folded constant value.
+        if mir.is_const() {
+            let value = self.const_mir_rvalue(mir.into());
+            return self
+                .emit_const(value, mir.env, mir.span)
+                .map(|v| (v, Mode::Value));
+        }
+
+        let value = match mir.kind {
+            mir::RvalueKind::Var(id) | mir::RvalueKind::Port(id) => {
+                let sig = self
+                    .shadows
+                    .get(&id.into())
+                    .cloned()
+                    .unwrap_or_else(|| self.emitted_value(id));
+                if mode_hint == Mode::Signal && self.llhd_type(sig).is_signal() {
+                    return Ok((sig, Mode::Signal));
+                } else {
+                    Ok(self.emit_prb_or_var(sig))
+                }
+            }
+
+            mir::RvalueKind::Intf(_) => {
+                self.emit(
+                    DiagBuilder2::error("interface cannot be used in an expression").span(mir.span),
+                );
+                Err(())
+            }
+
+            // Interface signals require special care, because they are emitted
+            // in a transposed fashion.
+            mir::RvalueKind::IntfSignal(value, signal) => {
+                return self.emit_rvalue_interface(value, signal, mode_hint);
+            }
+
+            mir::RvalueKind::CastValueDomain { value, .. } => {
+                // TODO(fschuiki): Turn this into an actual `iN` to `lN` cast.
+                return self.emit_mir_rvalue_inner(value, mode_hint);
+            }
+
+            mir::RvalueKind::Transmute(value) => {
+                // Transmute is a simple no-op.
+                return self.emit_mir_rvalue_inner(value, mode_hint);
+            }
+
+            mir::RvalueKind::CastSign(_, value) => {
+                // Sign conversions are no-ops in LLHD since they merely
+                // influence the type system.
+                return self.emit_mir_rvalue_inner(value, mode_hint);
+            }
+
+            mir::RvalueKind::CastToBool(value) => {
+                let value = self.emit_mir_rvalue(value)?;
+                let ty = self.llhd_type(value);
+                let zero = self.emit_zero_for_type(&ty);
+                Ok(self.builder.ins().neq(value, zero))
+            }
+
+            mir::RvalueKind::Truncate(target_width, value) => {
+                let llvalue = self.emit_mir_rvalue(value)?;
+                Ok(self.builder.ins().ext_slice(llvalue, 0, target_width))
+            }
+
+            mir::RvalueKind::ZeroExtend(_, value) => {
+                let width = value.ty.simple_bit_vector(self.cx, value.span).size;
+                let llty = self.emit_type(mir.ty)?;
+                let result = self.emit_zero_for_type(&llty);
+                let value = self.emit_mir_rvalue(value)?;
+                let result = self.builder.ins().ins_slice(result, value, 0, width);
+                self.builder.set_name(result, "zext".to_string());
+                Ok(result)
+            }
+
+            mir::RvalueKind::SignExtend(_, value) => {
+                let width = value.ty.simple_bit_vector(self.cx, value.span).size;
+                let llty = self.emit_type(mir.ty)?;
+                let value = self.emit_mir_rvalue(value)?;
+                let sign = self.builder.ins().ext_slice(value, width - 1, 1);
+                let zeros = self.emit_zero_for_type(&llty);
+                let ones = self.builder.ins().not(zeros);
+                let mux = self.builder.ins().array(vec![zeros, ones]);
+                let mux = self.builder.ins().mux(mux, sign);
+                let result = self.builder.ins().ins_slice(mux, value, 0, width);
+                self.builder.set_name(result, "sext".to_string());
+                Ok(result)
+            }
+
+            mir::RvalueKind::ConstructArray(ref indices) => {
+                let llvalue = (0..indices.len())
+                    .map(|i| self.emit_mir_rvalue(indices[&i]))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(self.builder.ins().array(llvalue))
+            }
+
+            mir::RvalueKind::ConstructStruct(ref members) => {
+                let members = members
+                    .iter()
+                    .map(|&v| self.emit_mir_rvalue(v))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(self.builder.ins().strukt(members))
+            }
+
+            mir::RvalueKind::Const(k) => self.emit_const(k, mir.env, mir.span),
+
+            mir::RvalueKind::Index {
+                value,
+                base,
+                length,
+            } => {
+                let target = self.emit_mir_rvalue(value)?;
+                self.emit_rvalue_index(value.ty, target, base, length)
+            }
+
+            mir::RvalueKind::Member { value, field } => {
+                let target = self.emit_mir_rvalue(value)?;
+                let value = self.builder.ins().ext_field(target, field);
+                // let name = format!(
+                //     "{}.{}",
+                //     self.builder
+                //
+                //         .get_name(target)
+                //         .map(String::from)
+                //         .unwrap_or_else(|| "struct".into()),
+                //     field
+                // );
+                // self.builder.set_name(value, name);
+                Ok(value)
+            }
+
+            mir::RvalueKind::UnaryBitwise { op, arg } => {
+                let arg = self.emit_mir_rvalue(arg)?;
+                Ok(match op {
+                    mir::UnaryBitwiseOp::Not => self.builder.ins().not(arg),
+                })
+            }
+
+            mir::RvalueKind::BinaryBitwise { op, lhs, rhs } => {
+                let lhs = self.emit_mir_rvalue(lhs)?;
+                let rhs = self.emit_mir_rvalue(rhs)?;
+                Ok(match op {
+                    mir::BinaryBitwiseOp::And => self.builder.ins().and(lhs, rhs),
+                    mir::BinaryBitwiseOp::Or => self.builder.ins().or(lhs, rhs),
+                    mir::BinaryBitwiseOp::Xor => self.builder.ins().xor(lhs, rhs),
+                })
+            }
+
+            mir::RvalueKind::IntComp {
+                op, lhs, rhs, sign, ..
+            } => {
+                let lhs = self.emit_mir_rvalue(lhs)?;
+                let rhs = self.emit_mir_rvalue(rhs)?;
+                let signed = sign.is_signed();
+                Ok(match op {
+                    mir::IntCompOp::Eq => self.builder.ins().eq(lhs, rhs),
+                    mir::IntCompOp::Neq => self.builder.ins().neq(lhs, rhs),
+                    mir::IntCompOp::Lt if signed => self.builder.ins().slt(lhs, rhs),
+                    mir::IntCompOp::Leq if signed => self.builder.ins().sle(lhs, rhs),
+                    mir::IntCompOp::Gt if signed => self.builder.ins().sgt(lhs, rhs),
+                    mir::IntCompOp::Geq if signed => self.builder.ins().sge(lhs, rhs),
+                    mir::IntCompOp::Lt => self.builder.ins().ult(lhs, rhs),
+                    mir::IntCompOp::Leq => self.builder.ins().ule(lhs, rhs),
+                    mir::IntCompOp::Gt => self.builder.ins().ugt(lhs, rhs),
+                    mir::IntCompOp::Geq => self.builder.ins().uge(lhs, rhs),
+                })
+            }
+
+            mir::RvalueKind::IntToReal(sign, value) => {
+                let name = match sign {
+                    ty::Sign::Signed => "llhd.real.from_sint",
+                    ty::Sign::Unsigned => "llhd.real.from_uint",
+                };
+                let value = self.emit_mir_rvalue(value)?;
+                self.emit_real_intrinsic(name, vec![value], mir.ty)
+            }
+
+            mir::RvalueKind::RealToInt { trunc, value } => {
+                let name = match trunc {
+                    true => "llhd.real.trunc",
+                    false => "llhd.real.round",
+                };
+                let value = self.emit_mir_rvalue(value)?;
+                self.emit_real_intrinsic(name, vec![value], mir.ty)
+            }
+
+            mir::RvalueKind::ResizeReal(value) => {
+                let value = self.emit_mir_rvalue(value)?;
+                self.emit_real_intrinsic("llhd.real.resize", vec![value], mir.ty)
+            }
+
+            mir::RvalueKind::RealToTime(value) => {
+                let value = self.emit_mir_rvalue(value)?;
+                Ok(self.emit_time_conversion(
+                    "llhd.time.from_real",
+                    value,
+                    llhd::time_ty(),
+                    mir.origin,
+                ))
+            }
+
+            mir::RvalueKind::RealUnaryArith { op, arg } => {
+                let arg = self.emit_mir_rvalue(arg)?;
+                Ok(match op {
+                    // Negation flips the sign bit.
+                    mir::RealUnaryArithOp::Neg => {
+                        let width = self.llhd_type(arg).unwrap_int();
+                        let sign = self
+                            .builder
+                            .ins()
+                            .const_int((width, BigInt::one() << (width - 1)));
+                        self.builder.ins().xor(arg, sign)
+                    }
+                })
+            }
+
+            mir::RvalueKind::RealBinaryArith { op, lhs, rhs } => {
+                let lhs = self.emit_mir_rvalue(lhs)?;
+                let rhs = self.emit_mir_rvalue(rhs)?;
+                self.emit_real_intrinsic(op.llhd_name(), vec![lhs, rhs], mir.ty)
+            }
+
+            mir::RvalueKind::RealComp { op, lhs, rhs } => {
+                let lhs = self.emit_mir_rvalue(lhs)?;
+                let rhs = self.emit_mir_rvalue(rhs)?;
+                self.emit_real_intrinsic(op.llhd_name(), vec![lhs, rhs], mir.ty)
             }
 
-            mir::RvalueKind::CastToBool(value) => {
-                let value = self.emit_mir_rvalue(value)?;
-                let ty = self.llhd_type(value);
-                let zero = self.emit_zero_for_type(&ty);
-                Ok(self.builder.ins().neq(value, zero))
+            mir::RvalueKind::IntUnaryArith { op, arg, .. } => {
+                let arg = self.emit_mir_rvalue(arg)?;
+                Ok(match op {
+                    mir::IntUnaryArithOp::Neg => self.builder.ins().neg(arg),
+                })
+            }
+
+            mir::RvalueKind::IntBinaryArith {
+                op, lhs, rhs, sign, ..
+            } => {
+                let lhs_ll = self.emit_mir_rvalue(lhs)?;
+                let rhs_ll = self.emit_mir_rvalue(rhs)?;
+                let signed = sign.is_signed();
+                Ok(match op {
+                    mir::IntBinaryArithOp::Add => self.builder.ins().add(lhs_ll, rhs_ll),
+                    mir::IntBinaryArithOp::Sub => self.builder.ins().sub(lhs_ll, rhs_ll),
+                    mir::IntBinaryArithOp::Mul if signed => self.builder.ins().smul(lhs_ll, rhs_ll),
+                    mir::IntBinaryArithOp::Div if signed => self.builder.ins().sdiv(lhs_ll, rhs_ll),
+                    mir::IntBinaryArithOp::Mod if signed => self.builder.ins().smod(lhs_ll, rhs_ll),
+                    mir::IntBinaryArithOp::Mul => self.builder.ins().umul(lhs_ll, rhs_ll),
+                    mir::IntBinaryArithOp::Div => self.builder.ins().udiv(lhs_ll, rhs_ll),
+                    mir::IntBinaryArithOp::Mod => self.builder.ins().umod(lhs_ll, rhs_ll),
+
+                    // The `x**y` operator requires special love, because there
+                    // is no direct equivalent for it in LLHD.
+                    mir::IntBinaryArithOp::Pow => {
+                        // If the exponent is a constant, we simply unroll.
+                        if rhs.is_const() {
+                            let count = self.const_mir_rvalue_int(Ref(rhs))?;
+                            let mut value = lhs_ll;
+                            for _ in 1..count.to_usize().unwrap() {
+                                value = self.builder.ins().umul(value, lhs_ll);
+                            }
+                            return Ok((value, Mode::Value));
+                        }
+
+                        // If the base is a constant power of two, we translate
+                        // `x**y` into `1 << (y * log2(x))`.
+                        if lhs.is_const() {
+                            let base = self.const_mir_rvalue_int(Ref(lhs))?.to_usize();
+                            // `log2(base)`
+                            let lg2 = base.and_then(|base| {
+                                if base.is_power_of_two() {
+                                    Some(base.trailing_zeros())
+                                } else {
+                                    None
+                                }
+                            });
+                            // `y * log2(base)`
+                            let rhs_ll = lg2.map(|lg2| {
+                                let width = self.llhd_type(rhs_ll).len();
+                                let lg2 = self.builder.ins().const_int((width, BigInt::from(lg2)));
+                                self.builder.ins().umul(lg2, rhs_ll)
+                            });
+                            if let Some(rhs_ll) = rhs_ll {
+                                // `1`
+                                let width = self.llhd_type(lhs_ll).len();
+                                let lhs_ll = self.builder.ins().const_int((width, BigInt::one()));
+                                // `1 << (y * log2(base))`
+                                let zeros = self.emit_zero_for_type(&self.llhd_type(lhs_ll));
+                                return Ok((
+                                    self.builder.ins().shl(lhs_ll, zeros, rhs_ll),
+                                    Mode::Value,
+                                ));
+                            }
+                        }
+
+                        // Otherwise we just complain.
+                        self.emit(
+                            DiagBuilder2::error("`**` operator on non-constants not supported")
+                                .span(mir.span),
+                        );
+                        return Err(());
+                    }
+                })
             }
 
-            mir::RvalueKind::Truncate(target_width, value) => {
-                let llvalue = self.emit_mir_rvalue(value)?;
-                Ok(self.builder.ins().ext_slice(llvalue, 0, target_width))
+            mir::RvalueKind::Concat(ref values) => {
+                let mut offset = 0;
+                let llty = self.emit_type(mir.ty)?;
+                let mut result = self.emit_zero_for_type(&llty);
+                trace!(
+                    "Concatenating {} values into `{}` (as `{}`)",
+                    values.len(),
+                    mir.ty,
+                    llty
+                );
+                for value in values.iter().rev() {
+                    let width = value.ty.simple_bit_vector(self.cx, value.span).size;
+                    let llval = self.emit_mir_rvalue(value)?;
+                    trace!(
+                        " - Value has width {}, type `{}`, in LLHD `{}`",
+                        width,
+                        value.ty,
+                        self.llhd_type(llval)
+                    );
+                    result = self.builder.ins().ins_slice(result, llval, offset, width);
+                    offset += width;
+                }
+                self.builder.set_name(result, "concat".to_string());
+                Ok(result)
             }
 
-            mir::RvalueKind::ZeroExtend(_, value) => {
+            mir::RvalueKind::Repeat(times, value) => {
                 let width = value.ty.simple_bit_vector(self.cx, value.span).size;
-                let llty = self.emit_type(mir.ty)?;
-                let result = self.emit_zero_for_type(&llty);
                 let value = self.emit_mir_rvalue(value)?;
-                let result = self.builder.ins().ins_slice(result, value, 0, width);
-                self.builder.set_name(result, "zext".to_string());
+                let llty = self.emit_type(mir.ty)?;
+                let mut result = self.emit_zero_for_type(&llty);
+                for i in 0..times {
+                    result = self
+                        .builder
+                        .ins()
+                        .ins_slice(result, value, i * width, width);
+                }
+                self.builder.set_name(result, "repeat".to_string());
                 Ok(result)
             }
 
-            mir::RvalueKind::SignExtend(_, value) => {
-                let width = value.ty.simple_bit_vector(self.cx, value.span).size;
-                let llty = self.emit_type(mir.ty)?;
+            mir::RvalueKind::Shift {
+                op,
+                arith,
+                value,
+                amount,
+            } => {
                 let value = self.emit_mir_rvalue(value)?;
-                let sign = self.builder.ins().ext_slice(value, width - 1, 1);
-                let zeros = self.emit_zero_for_type(&llty);
-                let ones = self.builder.ins().not(zeros);
-                let mux = self.builder.ins().array(vec![zeros, ones]);
-                let mux = self.builder.ins().mux(mux, sign);
-                let result = self.builder.ins().ins_slice(mux, value, 0, width);
-                self.builder.set_name(result, "sext".to_string());
+                let amount = self.emit_mir_rvalue(amount)?;
+                let value_ty = self.builder.unit().value_type(value);
+                let hidden = self.emit_zero_for_type(&value_ty);
+                let hidden = if arith && op == mir::ShiftOp::Right {
+                    let ones = self.builder.ins().not(hidden);
+                    let sign = self
+                        .builder
+                        .ins()
+                        .ext_slice(value, value_ty.unwrap_int() - 1, 1);
+                    let mux = self.builder.ins().array(vec![hidden, ones]);
+                    self.builder.ins().mux(mux, sign)
+                } else {
+                    hidden
+                };
+                Ok(match op {
+                    mir::ShiftOp::Left => self.builder.ins().shl(value, hidden, amount),
+                    mir::ShiftOp::Right => self.builder.ins().shr(value, hidden, amount),
+                })
+            }
+
+            mir::RvalueKind::Ternary {
+                cond,
+                true_value,
+                false_value,
+            } => {
+                let cond = self.emit_mir_rvalue(cond)?;
+                let true_value = self.emit_mir_rvalue(true_value)?;
+                let false_value = self.emit_mir_rvalue(false_value)?;
+                let array = self.builder.ins().array(vec![false_value, true_value]);
+                Ok(self.builder.ins().mux(array, cond))
+            }
+
+            mir::RvalueKind::Reduction { op, arg } => {
+                let width = arg.ty.simple_bit_vector(self.cx, arg.span).size;
+                let arg = self.emit_mir_rvalue(arg)?;
+                let mut value = self.builder.ins().ext_slice(arg, 0, 1);
+                for i in 1..width {
+                    let bit = self.builder.ins().ext_slice(arg, i, 1);
+                    value = match op {
+                        mir::BinaryBitwiseOp::And => self.builder.ins().and(value, bit),
+                        mir::BinaryBitwiseOp::Or => self.builder.ins().or(value, bit),
+                        mir::BinaryBitwiseOp::Xor => self.builder.ins().xor(value, bit),
+                    };
+                }
+                Ok(value)
+            }
+
+            mir::RvalueKind::Assignment {
+                lvalue,
+                rvalue,
+                result,
+            } => {
+                self.emit_mir_blocking_assign(lvalue, rvalue)?;
+                self.emit_mir_rvalue(result)
+            }
+
+            // Strings are packed into and unpacked from the bits of their
+            // characters, with the last character in the least significant
+            // bits.
+            mir::RvalueKind::PackString(value) => {
+                let value = self.emit_mir_rvalue(value)?;
+                let ty = self.emit_type(mir.ty)?;
+                Ok(self.emit_heap_intrinsic("llhd.string.to_bits", vec![value], ty))
+            }
+            mir::RvalueKind::UnpackString(value) => {
+                let value = self.emit_mir_rvalue(value)?;
+                let ty = self.emit_type(mir.ty)?;
+                Ok(self.emit_heap_intrinsic("llhd.string.from_bits", vec![value], ty))
+            }
+
+            mir::RvalueKind::IntToTime(sign, value) => {
+                let name = match sign.is_signed() {
+                    true => "llhd.time.from_sint",
+                    false => "llhd.time.from_uint",
+                };
+                let value = self.emit_mir_rvalue(value)?;
+                Ok(self.emit_time_conversion(name, value, llhd::time_ty(), mir.origin))
+            }
+
+            mir::RvalueKind::TimeToInt(value) => {
+                let ty = self.emit_type(mir.ty)?;
+                let value = self.emit_mir_rvalue(value)?;
+                Ok(self.emit_time_conversion("llhd.time.to_uint", value, ty, mir.origin))
+            }
+
+            // Strings compare lexically, which the `llhd.string.compare`
+            // intrinsic indicates by a negative, zero, or positive result.
+            mir::RvalueKind::StringComp { op, lhs, rhs } => {
+                let args = vec![self.emit_mir_rvalue(lhs)?, self.emit_mir_rvalue(rhs)?];
+                let order = self.emit_heap_intrinsic("llhd.string.compare", args, llhd::int_ty(32));
+                let zero = self.builder.ins().const_int((32, 0));
+                Ok(match op {
+                    mir::StringCompOp::Eq => self.builder.ins().eq(order, zero),
+                    mir::StringCompOp::Neq => self.builder.ins().neq(order, zero),
+                    mir::StringCompOp::Lt => self.builder.ins().slt(order, zero),
+                    mir::StringCompOp::Leq => self.builder.ins().sle(order, zero),
+                    mir::StringCompOp::Gt => self.builder.ins().sgt(order, zero),
+                    mir::StringCompOp::Geq => self.builder.ins().sge(order, zero),
+                })
+            }
+
+            // Strings are concatenated pairwise and then repeated.
+            mir::RvalueKind::StringConcat { repeat, ref values } => {
+                let ty = self.emit_type(mir.ty)?;
+                let mut result = self.emit_mir_rvalue(values[0])?;
+                for &value in &values[1..] {
+                    let value = self.emit_mir_rvalue(value)?;
+                    result = self.emit_heap_intrinsic(
+                        "llhd.string.concat",
+                        vec![result, value],
+                        ty.clone(),
+                    );
+                }
+                if let Some(repeat) = repeat {
+                    let repeat = self.emit_mir_rvalue(repeat)?;
+                    result =
+                        self.emit_heap_intrinsic("llhd.string.repeat", vec![result, repeat], ty);
+                }
                 Ok(result)
             }
 
-            mir::RvalueKind::ConstructArray(ref indices) => {
-                let llvalue = (0..indices.len())
-                    .map(|i| self.emit_mir_rvalue(indices[&i]))
-                    .collect::<Result<Vec<_>>>()?;
-                Ok(self.builder.ins().array(llvalue))
-            }
+            mir::RvalueKind::StringMethod { .. } => match self.emit_string_method(mir)? {
+                Some(value) => Ok(value),
+                None => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` does not return a value",
+                            mir.span.extract()
+                        ))
+                        .span(mir.span),
+                    );
+                    Err(())
+                }
+            },
+
+            mir::RvalueKind::StringFormat {
+                ref format,
+                ref args,
+            } => {
+                let mut sig = llhd::ir::Signature::new();
+                let mut values = vec![];
+                self.emit_format_args(format, args, &mut sig, &mut values)?;
+                sig.set_return_type(self.emit_type(mir.ty)?);
+                let ext_unit = self
+                    .builder
+                    .add_extern(llhd::ir::UnitName::Global("llhd.string.format".into()), sig);
+                let inst = self.builder.ins().call(ext_unit, values);
+                Ok(self.builder.inst_result(inst))
+            }
+
+            mir::RvalueKind::Display { .. } => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` does not return a value",
+                        mir.span.extract()
+                    ))
+                    .span(mir.span),
+                );
+                Err(())
+            }
+
+            mir::RvalueKind::Sampled { func, value } => {
+                let prev = match self.sampled.get(&mir.origin) {
+                    Some(history) => history[history.len() - 1],
+                    None => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "`{}` is only supported in concurrent assertions",
+                                mir.span.extract()
+                            ))
+                            .span(mir.span),
+                        );
+                        return Err(());
+                    }
+                };
+                let prev = self.builder.ins().ld(prev);
+                let now = self.emit_mir_rvalue(value)?;
+                let lsb = |this: &mut Self, value| {
+                    if this.llhd_type(value).unwrap_int() > 1 {
+                        this.builder.ins().ext_slice(value, 0, 1)
+                    } else {
+                        value
+                    }
+                };
+                Ok(match func {
+                    mir::SampledFunc::Rose | mir::SampledFunc::Fell => {
+                        let (one, zero) = match func {
+                            mir::SampledFunc::Rose => (now, prev),
+                            _ => (prev, now),
+                        };
+                        let one = lsb(self, one);
+                        let zero = lsb(self, zero);
+                        let zero = self.builder.ins().not(zero);
+                        self.builder.ins().and(one, zero)
+                    }
+                    mir::SampledFunc::Stable => self.builder.ins().eq(now, prev),
+                    mir::SampledFunc::Past => prev,
+                })
+            }
+
+            mir::RvalueKind::Intrinsic(op) => match self.emit_intrinsic(op, mir.ty, mir.origin)? {
+                Some(value) => Ok(value),
+                None => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` does not return a value",
+                            mir.span.extract()
+                        ))
+                        .span(mir.span),
+                    );
+                    Err(())
+                }
+            },
+
+            mir::RvalueKind::Call { target, ref args } => {
+                match self.emit_call(mir, target, args)? {
+                    Some(value) => Ok(value),
+                    None => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "`{}` does not return a value",
+                                mir.span.extract()
+                            ))
+                            .span(mir.span),
+                        );
+                        Err(())
+                    }
+                }
+            }
+
+            mir::RvalueKind::This => match self.this {
+                Some(value) => Ok(value),
+                None => bug_span!(mir.span, self.cx, "`this` used outside of a method"),
+            },
+
+            mir::RvalueKind::Null => Ok(self.builder.ins().const_int((64, 0))),
+
+            mir::RvalueKind::Property { object, slot } => {
+                let object = self.emit_mir_rvalue(object)?;
+                let ptr = self.emit_object_slot(object, slot, mir.ty)?;
+                Ok(self.builder.ins().ld(ptr))
+            }
+
+            mir::RvalueKind::New {
+                class,
+                env,
+                ref args,
+            } => self.emit_new(mir, class, env, args),
+
+            mir::RvalueKind::MethodCall { .. } => match self.emit_method_call(mir)? {
+                Some(value) => Ok(value),
+                None => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` does not return a value",
+                            mir.span.extract()
+                        ))
+                        .span(mir.span),
+                    );
+                    Err(())
+                }
+            },
+
+            mir::RvalueKind::ArrayNew { size, init } => {
+                let mut args = vec![self.emit_mir_rvalue(size)?];
+                if let Some(init) = init {
+                    args.push(self.emit_mir_rvalue(init)?);
+                }
+                Ok(self.emit_heap_intrinsic("llhd.darr.new", args, llhd::int_ty(64)))
+            }
+
+            mir::RvalueKind::ContainerElem { container, index } => {
+                let dim = container.ty.get_container().unwrap();
+                let name = format!("{}.get", container::llhd_prefix(dim));
+                let args = vec![
+                    self.emit_mir_rvalue(container)?,
+                    self.emit_mir_rvalue(index)?,
+                ];
+                let ty = self.emit_type(mir.ty)?;
+                Ok(self.emit_heap_intrinsic(&name, args, ty))
+            }
+
+            mir::RvalueKind::ContainerMethod { .. } => match self.emit_container_method(mir)? {
+                Some(value) => Ok(value),
+                None => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` does not return a value",
+                            mir.span.extract()
+                        ))
+                        .span(mir.span),
+                    );
+                    Err(())
+                }
+            },
+
+            // Enum names are looked up in a constant table which holds the
+            // characters of each name as a bit vector, with an empty name for
+            // values that are not a variant.
+            mir::RvalueKind::EnumName { index, ref names } => {
+                let width = mir.ty.simple_bit_vector(self.cx, mir.span).size;
+                let mut table = vec![];
+                for name in names.iter().map(String::as_str).chain(Some("")) {
+                    let chars = BigInt::from_bytes_be(num::bigint::Sign::Plus, name.as_bytes());
+                    table.push(self.builder.ins().const_int((width, chars)));
+                }
+                let table = self.builder.ins().array(table);
+                let index = self.emit_mir_rvalue(index)?;
+                Ok(self.builder.ins().mux(table, index))
+            }
+
+            // Pattern matches assign the matched parts of the value to the
+            // pattern variables, such that they are available wherever the
+            // match succeeded.
+            mir::RvalueKind::Matches { cond, ref bindings } => {
+                for &(id, value) in bindings {
+                    let init = self.emit_mir_rvalue(value)?;
+                    let var = self.builder.ins().var(init);
+                    if let HirNode::PatternVar(x) = self.hir_of(id)? {
+                        self.builder.set_name(var, x.name.value.to_string());
+                    }
+                    self.set_emitted_value(id, var);
+                }
+                self.emit_mir_rvalue(cond)
+            }
+
+            mir::RvalueKind::Error => Err(()),
+        };
+
+        value.map(|v| (v, Mode::Value))
+    }
+
+    /// Emit a call to a function or task.
+    ///
+    /// Returns the value returned by the function, or `None` if the subroutine
+    /// does not return a value.
+    fn emit_call(
+        &mut self,
+        mir: &'gcx mir::Rvalue<'gcx>,
+        target: NodeId,
+        args: &[mir::CallArg<'gcx>],
+    ) -> Result<Option<llhd::ir::Value>> {
+        // LLHD functions can neither suspend execution nor assign to anything
+        // outside their body. Tasks and subroutines with arguments other than
+        // inputs are therefore inlined into the caller.
+        let hir = match self.hir_of(target)? {
+            HirNode::Subroutine(x) => x,
+            _ => unreachable!(),
+        };
+        let all_inputs = args.iter().all(|arg| match arg {
+            mir::CallArg::Input(..) => true,
+            _ => false,
+        });
+        if hir.is_task() || !all_inputs {
+            return self.emit_inlined_call(hir, mir.env, mir.span, args, None);
+        }
+
+        // Otherwise emit the function and call it.
+        let func = self.emit_function(target, mir.env)?;
+        let mut values = vec![];
+        for arg in args {
+            match *arg {
+                mir::CallArg::Input(value) => values.push(self.emit_mir_rvalue_copy(value)?),
+                _ => unreachable!(),
+            }
+        }
+        for &id in &func.inputs {
+            let sig = self
+                .shadows
+                .get(&id)
+                .cloned()
+                .unwrap_or_else(|| self.emitted_value(id));
+            values.push(self.emit_prb_or_var(sig));
+        }
+        let ext_unit = self.builder.add_extern(func.name.clone(), func.sig.clone());
+        let inst = self.builder.ins().call(ext_unit, values);
+        if func.sig.has_return_type() {
+            Ok(Some(self.builder.inst_result(inst)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Emit a call to one of the display or severity system tasks.
+    ///
+    /// These map to a call to the `llhd.display` intrinsic, which a simulator
+    /// is expected to provide. Its arguments are the severity as an `i8` (0 for
+    /// `$display` and `$write`, 1 to 4 for `$info`, `$warning`, `$error`, and
+    /// `$fatal`), the NUL-terminated format string as an array of `i8`, and the
+    /// values to be formatted. Strings are passed as their `i64` handle and
+    /// formatted with an uppercase `%S`.
+    fn emit_display(
+        &mut self,
+        task: hir::DisplayTask,
+        format: &str,
+        args: &[&'gcx mir::Rvalue<'gcx>],
+    ) -> Result<()> {
+        let severity = match task {
+            hir::DisplayTask::Display | hir::DisplayTask::Write => 0,
+            hir::DisplayTask::Info => 1,
+            hir::DisplayTask::Warning => 2,
+            hir::DisplayTask::Error => 3,
+            hir::DisplayTask::Fatal => 4,
+        };
+        let mut sig = llhd::ir::Signature::new();
+        let mut values = vec![];
+        sig.add_input(llhd::int_ty(8));
+        values.push(self.builder.ins().const_int((8, severity)));
+        self.emit_format_args(format, args, &mut sig, &mut values)?;
+        let ext_unit = self
+            .builder
+            .add_extern(llhd::ir::UnitName::Global("llhd.display".into()), sig);
+        self.builder.ins().call(ext_unit, values);
+        Ok(())
+    }
+
+    /// Emit the format string and arguments passed to a formatting intrinsic.
+    ///
+    /// The format string is passed as a NUL-terminated array of `i8`, followed
+    /// by the values to be formatted.
+    fn emit_format_args(
+        &mut self,
+        format: &str,
+        args: &[&'gcx mir::Rvalue<'gcx>],
+        sig: &mut llhd::ir::Signature,
+        values: &mut Vec<llhd::ir::Value>,
+    ) -> Result<()> {
+        let mut chars = vec![];
+        for c in format.bytes().chain(Some(0)) {
+            chars.push(self.builder.ins().const_int((8, c)));
+        }
+        sig.add_input(llhd::array_ty(chars.len(), llhd::int_ty(8)));
+        values.push(self.builder.ins().array(chars));
+        for &arg in args {
+            let value = self.emit_mir_rvalue(arg)?;
+            sig.add_input(self.llhd_type(value));
+            values.push(value);
+        }
+        Ok(())
+    }
 
-            mir::RvalueKind::ConstructStruct(ref members) => {
-                let members = members
-                    .iter()
-                    .map(|&v| self.emit_mir_rvalue(v))
-                    .collect::<Result<Vec<_>>>()?;
-                Ok(self.builder.ins().strukt(members))
-            }
+    /// Emit a call to a simulator intrinsic.
+    ///
+    /// Intrinsics map to calls to LLHD functions which the simulator is
+    /// expected to provide. They take no arguments, except for the time unit
+    /// passed to `llhd.time` and `llhd.realtime`, and return a value of the
+    /// given type, unless it is `void`. Since `$finish` terminates the
+    /// simulation, processes additionally halt after calling it.
+    fn emit_intrinsic(
+        &mut self,
+        op: mir::Intrinsic,
+        ty: &'gcx UnpackedType<'gcx>,
+        origin: NodeId,
+    ) -> Result<Option<llhd::ir::Value>> {
+        let mut sig = llhd::ir::Signature::new();
+        let mut args = vec![];
+        if !ty.is_void() {
+            sig.set_return_type(self.emit_type(ty)?);
+        }
 
-            mir::RvalueKind::Const(k) => self.emit_const(k, mir.env, mir.span),
+        // Pass the time unit in effect for `$time` and `$realtime`, which the
+        // simulator rounds or scales the current time to.
+        if op == mir::Intrinsic::Time || op == mir::Intrinsic::RealTime {
+            let unit = self.timescale_of(origin).unit.clone();
+            sig.add_input(llhd::time_ty());
+            args.push(
+                self.builder
+                    .ins()
+                    .const_time(llhd::value::TimeValue::new(unit, 0, 0)),
+            );
+        }
 
-            mir::RvalueKind::Index {
-                value,
-                base,
-                length,
-            } => {
-                let target = self.emit_mir_rvalue(value)?;
-                self.emit_rvalue_index(value.ty, target, base, length)
-            }
+        let ext_unit = self
+            .builder
+            .add_extern(llhd::ir::UnitName::Global(op.llhd_name().into()), sig);
+        let inst = self.builder.ins().call(ext_unit, args);
+        let result = if ty.is_void() {
+            None
+        } else {
+            Some(self.builder.inst_result(inst))
+        };
+        if op == mir::Intrinsic::Finish && self.builder.unit().kind() == llhd::ir::UnitKind::Process
+        {
+            self.builder.ins().halt();
+            let blk = self.add_nameless_block();
+            self.builder.append_to(blk);
+        }
+        Ok(result)
+    }
 
-            mir::RvalueKind::Member { value, field } => {
-                let target = self.emit_mir_rvalue(value)?;
-                let value = self.builder.ins().ext_field(target, field);
-                // let name = format!(
-                //     "{}.{}",
-                //     self.builder
-                //
-                //         .get_name(target)
-                //         .map(String::from)
-                //         .unwrap_or_else(|| "struct".into()),
-                //     field
-                // );
-                // self.builder.set_name(value, name);
-                Ok(value)
-            }
+    /// Emit a call to a floating-point intrinsic.
+    ///
+    /// LLHD has no floating-point types, so reals are represented by their
+    /// IEEE 754 bits as `i64`, or `i32` for `shortreal`. Operations on them map
+    /// to calls to LLHD functions such as `llhd.real.add`, which the simulator
+    /// is expected to provide for each combination of argument widths.
+    fn emit_real_intrinsic(
+        &mut self,
+        name: &str,
+        args: Vec<llhd::ir::Value>,
+        ty: &'gcx UnpackedType<'gcx>,
+    ) -> Result<llhd::ir::Value> {
+        let mut sig = llhd::ir::Signature::new();
+        for &arg in &args {
+            sig.add_input(self.llhd_type(arg));
+        }
+        sig.set_return_type(self.emit_type(ty)?);
+        let ext_unit = self
+            .builder
+            .add_extern(llhd::ir::UnitName::Global(name.into()), sig);
+        let inst = self.builder.ins().call(ext_unit, args);
+        Ok(self.builder.inst_result(inst))
+    }
 
-            mir::RvalueKind::UnaryBitwise { op, arg } => {
-                let arg = self.emit_mir_rvalue(arg)?;
-                Ok(match op {
-                    mir::UnaryBitwiseOp::Not => self.builder.ins().not(arg),
-                })
-            }
+    /// Emit a call to a function or task by inlining its body.
+    ///
+    /// Input and inout arguments are copied into variables before the body,
+    /// output and inout arguments are copied back after it. Ref arguments
+    /// directly refer to the passed signal or variable. Methods additionally
+    /// receive the object they operate on as `this`.
+    fn emit_inlined_call(
+        &mut self,
+        hir: &'gcx hir::Subroutine,
+        env: ParamEnv,
+        span: Span,
+        args: &[mir::CallArg<'gcx>],
+        this: Option<llhd::ir::Value>,
+    ) -> Result<Option<llhd::ir::Value>> {
+        info!("Inline {} with {:?}", hir.desc_full(), env);
+        if self.inlined.contains(&hir.id) {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "unsupported: recursive call to {}",
+                    hir.desc_full()
+                ))
+                .span(span)
+                .add_note(format!(
+                    "{} is inlined into the caller, which is not possible for recursive calls",
+                    hir.desc_full()
+                )),
+            );
+            return Err(());
+        }
 
-            mir::RvalueKind::BinaryBitwise { op, lhs, rhs } => {
-                let lhs = self.emit_mir_rvalue(lhs)?;
-                let rhs = self.emit_mir_rvalue(rhs)?;
-                Ok(match op {
-                    mir::BinaryBitwiseOp::And => self.builder.ins().and(lhs, rhs),
-                    mir::BinaryBitwiseOp::Or => self.builder.ins().or(lhs, rhs),
-                    mir::BinaryBitwiseOp::Xor => self.builder.ins().xor(lhs, rhs),
-                })
+        // Bind the arguments.
+        let mut refs = vec![];
+        for (arg, &mir_arg) in hir.args.iter().zip(args.iter()) {
+            let init = match mir_arg {
+                mir::CallArg::Input(value) | mir::CallArg::Inout(_, value) => {
+                    self.emit_mir_rvalue_copy(value)?
+                }
+                mir::CallArg::Output(_) => {
+                    let ty = self.type_of(arg.id, env)?;
+                    let ty = self.emit_type(ty)?;
+                    self.emit_zero_for_type(&ty)
+                }
+                mir::CallArg::Ref(lvalue) => {
+                    let (value, shadow) = self.emit_mir_lvalue(lvalue)?;
+                    self.set_emitted_value(arg.id, value);
+                    if let Some(shadow) = shadow {
+                        self.shadows.insert(arg.id.into(), shadow);
+                    }
+                    refs.push(arg.id);
+                    continue;
+                }
+            };
+            let var = self.builder.ins().var(init);
+            self.builder
+                .set_name(var, format!("{}.{}", hir.name, arg.name));
+            self.set_emitted_value(arg.id, var);
+        }
+        let retvar = if hir.is_function() {
+            let retty = self.type_of(hir.id, env)?;
+            if retty.is_void() {
+                None
+            } else {
+                let ty = self.emit_type(retty)?;
+                let init = self.emit_zero_for_type(&ty);
+                let var = self.builder.ins().var(init);
+                self.builder.set_name(var, format!("{}.ret", hir.name));
+                self.set_emitted_value(hir.id, var);
+                Some(var)
             }
+        } else {
+            None
+        };
 
-            mir::RvalueKind::IntComp {
-                op, lhs, rhs, sign, ..
-            } => {
-                let lhs = self.emit_mir_rvalue(lhs)?;
-                let rhs = self.emit_mir_rvalue(rhs)?;
-                let signed = sign.is_signed();
-                Ok(match op {
-                    mir::IntCompOp::Eq => self.builder.ins().eq(lhs, rhs),
-                    mir::IntCompOp::Neq => self.builder.ins().neq(lhs, rhs),
-                    mir::IntCompOp::Lt if signed => self.builder.ins().slt(lhs, rhs),
-                    mir::IntCompOp::Leq if signed => self.builder.ins().sle(lhs, rhs),
-                    mir::IntCompOp::Gt if signed => self.builder.ins().sgt(lhs, rhs),
-                    mir::IntCompOp::Geq if signed => self.builder.ins().sge(lhs, rhs),
-                    mir::IntCompOp::Lt => self.builder.ins().ult(lhs, rhs),
-                    mir::IntCompOp::Leq => self.builder.ins().ule(lhs, rhs),
-                    mir::IntCompOp::Gt => self.builder.ins().ugt(lhs, rhs),
-                    mir::IntCompOp::Geq => self.builder.ins().uge(lhs, rhs),
-                })
-            }
+        // Emit the body.
+        self.flush_mir();
+        self.inlined.push(hir.id);
+        let outer_this = std::mem::replace(&mut self.this, this);
+        let result = self.emit_exitable(hir.id, ExitKind::Subroutine, |pg| {
+            hir.stmts
+                .iter()
+                .try_for_each(|&stmt| pg.emit_stmt(stmt, env))
+        });
+        self.this = outer_this;
+        self.inlined.pop();
+        result?;
+        self.flush_mir();
 
-            mir::RvalueKind::IntUnaryArith { op, arg, .. } => {
-                let arg = self.emit_mir_rvalue(arg)?;
-                Ok(match op {
-                    mir::IntUnaryArithOp::Neg => self.builder.ins().neg(arg),
-                })
+        // Copy the output arguments back and unbind the ref arguments.
+        for (arg, &mir_arg) in hir.args.iter().zip(args.iter()) {
+            match mir_arg {
+                mir::CallArg::Output(lvalue) | mir::CallArg::Inout(lvalue, _) => {
+                    let var = self.emitted_value(arg.id);
+                    let value = self.builder.ins().ld(var);
+                    let lvalue = self.emit_mir_lvalue(lvalue)?;
+                    self.emit_blocking_assign_llhd(lvalue, value)?;
+                }
+                _ => (),
             }
+        }
+        for id in refs {
+            self.values.remove(&id.into());
+            self.shadows.remove(&id.into());
+        }
+        Ok(retvar.map(|var| self.builder.ins().ld(var)))
+    }
 
-            mir::RvalueKind::IntBinaryArith {
-                op, lhs, rhs, sign, ..
-            } => {
-                let lhs_ll = self.emit_mir_rvalue(lhs)?;
-                let rhs_ll = self.emit_mir_rvalue(rhs)?;
-                let signed = sign.is_signed();
-                Ok(match op {
-                    mir::IntBinaryArithOp::Add => self.builder.ins().add(lhs_ll, rhs_ll),
-                    mir::IntBinaryArithOp::Sub => self.builder.ins().sub(lhs_ll, rhs_ll),
-                    mir::IntBinaryArithOp::Mul if signed => self.builder.ins().smul(lhs_ll, rhs_ll),
-                    mir::IntBinaryArithOp::Div if signed => self.builder.ins().sdiv(lhs_ll, rhs_ll),
-                    mir::IntBinaryArithOp::Mod if signed => self.builder.ins().smod(lhs_ll, rhs_ll),
-                    mir::IntBinaryArithOp::Mul => self.builder.ins().umul(lhs_ll, rhs_ll),
-                    mir::IntBinaryArithOp::Div => self.builder.ins().udiv(lhs_ll, rhs_ll),
-                    mir::IntBinaryArithOp::Mod => self.builder.ins().umod(lhs_ll, rhs_ll),
+    /// Emit a pointer to a slot of an object.
+    ///
+    /// Objects live on a heap managed by the simulator and are referred to by
+    /// an `i64` handle. The `llhd.obj.field` intrinsic maps a handle and a slot
+    /// to a pointer to the value stored in that slot. Slot 0 holds the id of
+    /// the class the object was created as, the properties follow.
+    fn emit_object_slot(
+        &mut self,
+        object: llhd::ir::Value,
+        slot: usize,
+        ty: &'gcx UnpackedType<'gcx>,
+    ) -> Result<llhd::ir::Value> {
+        let ty = self.emit_type(ty)?;
+        Ok(self.emit_object_slot_llhd(object, slot, ty))
+    }
 
-                    // The `x**y` operator requires special love, because there
-                    // is no direct equivalent for it in LLHD.
-                    mir::IntBinaryArithOp::Pow => {
-                        // If the exponent is a constant, we simply unroll.
-                        if rhs.is_const() {
-                            let count = self.const_mir_rvalue_int(Ref(rhs))?;
-                            let mut value = lhs_ll;
-                            for _ in 1..count.to_usize().unwrap() {
-                                value = self.builder.ins().umul(value, lhs_ll);
-                            }
-                            return Ok((value, Mode::Value));
-                        }
+    /// Emit a pointer to a slot of an object, given the slot's LLHD type.
+    fn emit_object_slot_llhd(
+        &mut self,
+        object: llhd::ir::Value,
+        slot: usize,
+        ty: llhd::Type,
+    ) -> llhd::ir::Value {
+        let mut sig = llhd::ir::Signature::new();
+        sig.add_input(llhd::int_ty(64));
+        sig.add_input(llhd::int_ty(32));
+        sig.set_return_type(llhd::pointer_ty(ty));
+        let ext_unit = self
+            .builder
+            .add_extern(llhd::ir::UnitName::Global("llhd.obj.field".into()), sig);
+        let slot = self.builder.ins().const_int((32, slot));
+        let inst = self.builder.ins().call(ext_unit, vec![object, slot]);
+        self.builder.inst_result(inst)
+    }
 
-                        // If the base is a constant power of two, we translate
-                        // `x**y` into `1 << (y * log2(x))`.
-                        if lhs.is_const() {
-                            let base = self.const_mir_rvalue_int(Ref(lhs))?.to_usize();
-                            // `log2(base)`
-                            let lg2 = base.and_then(|base| {
-                                if base.is_power_of_two() {
-                                    Some(base.trailing_zeros())
-                                } else {
-                                    None
-                                }
-                            });
-                            // `y * log2(base)`
-                            let rhs_ll = lg2.map(|lg2| {
-                                let width = self.llhd_type(rhs_ll).len();
-                                let lg2 = self.builder.ins().const_int((width, BigInt::from(lg2)));
-                                self.builder.ins().umul(lg2, rhs_ll)
-                            });
-                            if let Some(rhs_ll) = rhs_ll {
-                                // `1`
-                                let width = self.llhd_type(lhs_ll).len();
-                                let lhs_ll = self.builder.ins().const_int((width, BigInt::one()));
-                                // `1 << (y * log2(base))`
-                                let zeros = self.emit_zero_for_type(&self.llhd_type(lhs_ll));
-                                return Ok((
-                                    self.builder.ins().shl(lhs_ll, zeros, rhs_ll),
-                                    Mode::Value,
-                                ));
-                            }
-                        }
+    /// Determine the id of a class specialization (interned).
+    ///
+    /// Objects record the id of the specialization they were created as, such
+    /// that virtual method calls can dispatch on it.
+    fn class_id(&mut self, class: NodeId, env: ParamEnv) -> usize {
+        let next = self.tables.class_ids.len() + 1;
+        *self.tables.class_ids.entry((class, env)).or_insert(next)
+    }
 
-                        // Otherwise we just complain.
-                        self.emit(
-                            DiagBuilder2::error("`**` operator on non-constants not supported")
-                                .span(mir.span),
-                        );
-                        return Err(());
-                    }
-                })
-            }
+    /// Emit the creation of an object through `new`.
+    ///
+    /// Allocates the object, records its class, initializes its properties,
+    /// and runs its constructor.
+    fn emit_new(
+        &mut self,
+        mir: &'gcx mir::Rvalue<'gcx>,
+        class: NodeId,
+        env: ParamEnv,
+        args: &[mir::CallArg<'gcx>],
+    ) -> Result<llhd::ir::Value> {
+        let details = self.class_details(class, env)?;
 
-            mir::RvalueKind::Concat(ref values) => {
-                let mut offset = 0;
-                let llty = self.emit_type(mir.ty)?;
-                let mut result = self.emit_zero_for_type(&llty);
-                trace!(
-                    "Concatenating {} values into `{}` (as `{}`)",
-                    values.len(),
-                    mir.ty,
-                    llty
-                );
-                for value in values.iter().rev() {
-                    let width = value.ty.simple_bit_vector(self.cx, value.span).size;
-                    let llval = self.emit_mir_rvalue(value)?;
-                    trace!(
-                        " - Value has width {}, type `{}`, in LLHD `{}`",
-                        width,
-                        value.ty,
-                        self.llhd_type(llval)
-                    );
-                    result = self.builder.ins().ins_slice(result, llval, offset, width);
-                    offset += width;
+        // Allocate the object.
+        let mut sig = llhd::ir::Signature::new();
+        sig.add_input(llhd::int_ty(32));
+        sig.set_return_type(llhd::int_ty(64));
+        let ext_unit = self
+            .builder
+            .add_extern(llhd::ir::UnitName::Global("llhd.obj.new".into()), sig);
+        let num_slots = self.builder.ins().const_int((32, details.num_slots()));
+        let inst = self.builder.ins().call(ext_unit, vec![num_slots]);
+        let object = self.builder.inst_result(inst);
+        self.builder
+            .set_name(object, format!("{}.obj", details.hir.name));
+
+        // Record the class specialization for dynamic dispatch.
+        let id_ptr = self.emit_object_slot_llhd(object, 0, llhd::int_ty(32));
+        let id = self.class_id(class, env);
+        let id = self.builder.ins().const_int((32, id));
+        self.builder.ins().st(id_ptr, id);
+
+        // Initialize the properties, base class first.
+        for (i, prop) in details.props.iter().enumerate() {
+            let ty = self.type_of(prop.id, prop.env)?;
+            let init = match self.hir_of(prop.id)? {
+                HirNode::VarDecl(decl) => decl.init,
+                _ => unreachable!(),
+            };
+            let value = match init {
+                Some(init) => {
+                    let init = self.mir_rvalue(init, prop.env);
+                    self.emit_mir_rvalue_copy(init)?
                 }
-                self.builder.set_name(result, "concat".to_string());
-                Ok(result)
-            }
+                None => {
+                    let ty = self.emit_type(ty)?;
+                    self.emit_zero_for_type(&ty)
+                }
+            };
+            let ptr = self.emit_object_slot(object, i + 1, ty)?;
+            self.builder.ins().st(ptr, value);
+        }
 
-            mir::RvalueKind::Repeat(times, value) => {
-                let width = value.ty.simple_bit_vector(self.cx, value.span).size;
-                let value = self.emit_mir_rvalue(value)?;
-                let llty = self.emit_type(mir.ty)?;
-                let mut result = self.emit_zero_for_type(&llty);
-                for i in 0..times {
-                    result = self
-                        .builder
-                        .ins()
-                        .ins_slice(result, value, i * width, width);
+        // Run the constructor.
+        self.emit_constructor(&details, object, args, mir.span)?;
+        Ok(object)
+    }
+
+    /// Emit the constructor of a class for an object.
+    ///
+    /// A constructor that does not call `super.new(...)` implicitly calls the
+    /// constructor of the base class without arguments first. Classes without
+    /// a constructor pass the arguments on to their base class.
+    fn emit_constructor(
+        &mut self,
+        details: &ClassDetails<'gcx>,
+        object: llhd::ir::Value,
+        args: &[mir::CallArg<'gcx>],
+        span: Span,
+    ) -> Result<()> {
+        let ctor = match details.own_constructor() {
+            Some(&ctor) => ctor,
+            None => {
+                return match details.base {
+                    Some(ref base) => self.emit_constructor(base, object, args, span),
+                    None => Ok(()),
                 }
-                self.builder.set_name(result, "repeat".to_string());
-                Ok(result)
             }
+        };
+        let hir = match self.hir_of(ctor.id)? {
+            HirNode::Subroutine(x) => x,
+            _ => unreachable!(),
+        };
+        if let Some(ref base) = details.base {
+            if !class::calls_super_new(self.cx, hir)? {
+                self.emit_implicit_constructor(base, object, span)?;
+            }
+        }
+        self.emit_inlined_call(hir, ctor.env, span, args, Some(object))?;
+        Ok(())
+    }
 
-            mir::RvalueKind::Shift {
-                op,
-                arith,
-                value,
-                amount,
-            } => {
-                let value = self.emit_mir_rvalue(value)?;
-                let amount = self.emit_mir_rvalue(amount)?;
-                let value_ty = self.builder.unit().value_type(value);
-                let hidden = self.emit_zero_for_type(&value_ty);
-                let hidden = if arith && op == mir::ShiftOp::Right {
-                    let ones = self.builder.ins().not(hidden);
-                    let sign = self
-                        .builder
-                        .ins()
-                        .ext_slice(value, value_ty.unwrap_int() - 1, 1);
-                    let mux = self.builder.ins().array(vec![hidden, ones]);
-                    self.builder.ins().mux(mux, sign)
-                } else {
-                    hidden
+    /// Emit the constructor of a base class implicitly called without
+    /// arguments.
+    fn emit_implicit_constructor(
+        &mut self,
+        details: &ClassDetails<'gcx>,
+        object: llhd::ir::Value,
+        span: Span,
+    ) -> Result<()> {
+        let mut args = vec![];
+        if let Some(&ctor) = details.find_method(class::constructor_name()) {
+            let hir = match self.hir_of(ctor.id)? {
+                HirNode::Subroutine(x) => x,
+                _ => unreachable!(),
+            };
+            for arg in &hir.args {
+                let default = match self.hir_of(arg.id)? {
+                    HirNode::VarDecl(decl) => decl.init,
+                    _ => unreachable!(),
                 };
-                Ok(match op {
-                    mir::ShiftOp::Left => self.builder.ins().shl(value, hidden, amount),
-                    mir::ShiftOp::Right => self.builder.ins().shr(value, hidden, amount),
-                })
+                match (arg.dir, default) {
+                    (ast::SubroutinePortDir::Input, Some(default)) => {
+                        args.push(mir::CallArg::Input(self.mir_rvalue(default, ctor.env)));
+                    }
+                    _ => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "constructor of base class `{}` requires arguments",
+                                details.hir.name
+                            ))
+                            .span(span)
+                            .add_note("call `super.new(...)` in the derived class's constructor"),
+                        );
+                        return Err(());
+                    }
+                }
             }
+        }
+        self.emit_constructor(details, object, &args, span)
+    }
 
-            mir::RvalueKind::Ternary {
-                cond,
-                true_value,
-                false_value,
-            } => {
-                let cond = self.emit_mir_rvalue(cond)?;
-                let true_value = self.emit_mir_rvalue(true_value)?;
-                let false_value = self.emit_mir_rvalue(false_value)?;
-                let array = self.builder.ins().array(vec![false_value, true_value]);
-                Ok(self.builder.ins().mux(array, cond))
+    /// Emit a call to a method of an object.
+    ///
+    /// Calls to virtual methods dispatch on the class specialization the
+    /// object was created as, which is stored in its first slot. Every
+    /// override of the method is inlined into its own branch, within the
+    /// parametrization of the specialization.
+    fn emit_method_call(
+        &mut self,
+        mir: &'gcx mir::Rvalue<'gcx>,
+    ) -> Result<Option<llhd::ir::Value>> {
+        let (object, target, env, virt, args) = match mir.kind {
+            mir::RvalueKind::MethodCall {
+                object,
+                target,
+                env,
+                virt,
+                ref args,
+            } => (object, target, env, virt, args),
+            _ => unreachable!(),
+        };
+        let hir = match self.hir_of(target)? {
+            HirNode::Subroutine(x) => x,
+            _ => unreachable!(),
+        };
+        let object_value = self.emit_mir_rvalue(object)?;
+
+        // Calls to `super.new(...)` run the base class constructor, including
+        // the constructors it calls in turn.
+        if hir.name.value == class::constructor_name() {
+            let class = match class::declaring_class(self.cx, target) {
+                Some(x) => x,
+                None => bug_span!(mir.span, self.cx, "constructor outside of a class"),
+            };
+            let details = self.class_details(class.id(), env)?;
+            self.emit_constructor(&details, object_value, args, mir.span)?;
+            return Ok(None);
+        }
+
+        // Determine the overrides of virtual methods.
+        let overrides = if virt {
+            let class = match object.ty.get_class() {
+                Some(x) => x,
+                None => bug_span!(mir.span, self.cx, "method call on non-class value"),
+            };
+            let details = self.class_details(class.ast.id(), class.env)?;
+            match details.find_member(target) {
+                Some(method) => class::virtual_overrides(self.cx, &details, method),
+                None => vec![],
             }
+        } else {
+            vec![]
+        };
+        if overrides.is_empty() {
+            return self.emit_inlined_call(hir, env, mir.span, args, Some(object_value));
+        }
 
-            mir::RvalueKind::Reduction { op, arg } => {
-                let width = arg.ty.simple_bit_vector(self.cx, arg.span).size;
-                let arg = self.emit_mir_rvalue(arg)?;
-                let mut value = self.builder.ins().ext_slice(arg, 0, 1);
-                for i in 1..width {
-                    let bit = self.builder.ins().ext_slice(arg, i, 1);
-                    value = match op {
-                        mir::BinaryBitwiseOp::And => self.builder.ins().and(value, bit),
-                        mir::BinaryBitwiseOp::Or => self.builder.ins().or(value, bit),
-                        mir::BinaryBitwiseOp::Xor => self.builder.ins().xor(value, bit),
-                    };
-                }
-                Ok(value)
+        // Branch to the override for the object's class, falling back to the
+        // target of the call.
+        let result = if mir.ty.is_void() {
+            None
+        } else {
+            let ty = self.emit_type(mir.ty)?;
+            let init = self.emit_zero_for_type(&ty);
+            let var = self.builder.ins().var(init);
+            self.builder.set_name(var, format!("{}.result", hir.name));
+            Some(var)
+        };
+        let id_ptr = self.emit_object_slot_llhd(object_value, 0, llhd::int_ty(32));
+        let class_id = self.builder.ins().ld(id_ptr);
+        let exit_blk = self.add_named_block("dispatch_exit");
+        for (derived, method) in overrides {
+            let call_blk = self.add_named_block("dispatch_call");
+            let next_blk = self.add_named_block("dispatch_next");
+            let id = self.class_id(derived.hir.id, derived.env);
+            let id = self.builder.ins().const_int((32, id));
+            let matches = self.builder.ins().eq(class_id, id);
+            self.builder.ins().br_cond(matches, next_blk, call_blk);
+            self.builder.append_to(call_blk);
+            self.flush_mir();
+            let hir = match self.hir_of(method.id)? {
+                HirNode::Subroutine(x) => x,
+                _ => unreachable!(),
+            };
+            let value =
+                self.emit_inlined_call(hir, method.env, mir.span, args, Some(object_value))?;
+            if let (Some(var), Some(value)) = (result, value) {
+                self.builder.ins().st(var, value);
             }
+            self.builder.ins().br(exit_blk);
+            self.builder.append_to(next_blk);
+        }
+        self.flush_mir();
+        let value = self.emit_inlined_call(hir, env, mir.span, args, Some(object_value))?;
+        if let (Some(var), Some(value)) = (result, value) {
+            self.builder.ins().st(var, value);
+        }
+        self.builder.ins().br(exit_blk);
+        self.builder.append_to(exit_blk);
+        self.flush_mir();
+        Ok(result.map(|var| self.builder.ins().ld(var)))
+    }
 
-            mir::RvalueKind::Assignment {
+    /// Emit a call to an intrinsic operating on a dynamic array, queue,
+    /// associative array, or string.
+    ///
+    /// Containers and strings are `i64` handles to storage on the heap, which
+    /// the simulator manages, with the null handle referring to an empty
+    /// container or string. Intrinsics such as `llhd.queue.push_back` are
+    /// expected for each combination of argument types. Intrinsics which modify
+    /// a container return its updated handle.
+    fn emit_heap_intrinsic(
+        &mut self,
+        name: &str,
+        args: Vec<llhd::ir::Value>,
+        ty: llhd::Type,
+    ) -> llhd::ir::Value {
+        let mut sig = llhd::ir::Signature::new();
+        for &arg in &args {
+            sig.add_input(self.llhd_type(arg));
+        }
+        sig.set_return_type(ty);
+        let ext_unit = self
+            .builder
+            .add_extern(llhd::ir::UnitName::Global(name.into()), sig);
+        let inst = self.builder.ins().call(ext_unit, args);
+        self.builder.inst_result(inst)
+    }
+
+    /// Emit a call to a built-in method of a string.
+    ///
+    /// Returns the value returned by the method, or `None` if the method does
+    /// not return a value.
+    fn emit_string_method(
+        &mut self,
+        mir: &'gcx mir::Rvalue<'gcx>,
+    ) -> Result<Option<llhd::ir::Value>> {
+        let (method, string, lvalue, args) = match mir.kind {
+            mir::RvalueKind::StringMethod {
+                method,
+                string,
                 lvalue,
-                rvalue,
-                result,
-            } => {
-                self.emit_mir_blocking_assign(lvalue, rvalue)?;
-                self.emit_mir_rvalue(result)
+                ref args,
+            } => (method, string, lvalue, args),
+            _ => unreachable!(),
+        };
+        let mut values = vec![self.emit_mir_rvalue(string)?];
+        for &arg in args {
+            values.push(self.emit_mir_rvalue(arg)?);
+        }
+        let name = format!("llhd.string.{}", method.llhd_name());
+
+        // Modifications produce a new string, which is assigned back.
+        if method.modifies() {
+            let handle = self.emit_heap_intrinsic(&name, values, llhd::int_ty(64));
+            let lvalue = self.emit_mir_lvalue(lvalue.unwrap())?;
+            self.emit_blocking_assign_llhd(lvalue, handle)?;
+            return Ok(None);
+        }
+        let ty = self.emit_type(mir.ty)?;
+        Ok(Some(self.emit_heap_intrinsic(&name, values, ty)))
+    }
+
+    /// Emit a call to a built-in method of a dynamic array, queue, or
+    /// associative array.
+    ///
+    /// Returns the value returned by the method, or `None` if the method does
+    /// not return a value.
+    fn emit_container_method(
+        &mut self,
+        mir: &'gcx mir::Rvalue<'gcx>,
+    ) -> Result<Option<llhd::ir::Value>> {
+        let (method, container, lvalue, args) = match mir.kind {
+            mir::RvalueKind::ContainerMethod {
+                method,
+                container,
+                lvalue,
+                ref args,
+            } => (method, container, lvalue, args),
+            _ => unreachable!(),
+        };
+        let prefix = container::llhd_prefix(container.ty.get_container().unwrap());
+        let handle_ty = llhd::int_ty(64);
+        let handle = self.emit_mir_rvalue(container)?;
+        let mut values = vec![handle];
+        let mut index_lvalue = None;
+        for arg in args {
+            match *arg {
+                mir::CallArg::Input(value) => values.push(self.emit_mir_rvalue_copy(value)?),
+                mir::CallArg::Ref(lvalue) => {
+                    let (sig, shadow) = self.emit_mir_lvalue(lvalue)?;
+                    values.push(self.emit_prb_or_var(shadow.unwrap_or(sig)));
+                    index_lvalue = Some(((sig, shadow), lvalue.ty));
+                }
+                _ => unreachable!(),
             }
+        }
+        let name = format!("{}.{}", prefix, method.llhd_name());
 
-            mir::RvalueKind::PackString(value) | mir::RvalueKind::UnpackString(value) => bug_span!(
-                value.span,
-                self.cx,
-                "codegen for string packing/unpacking not implemented"
-            ),
+        let result = match method {
+            // Queries return their result directly.
+            container::ContainerMethod::Size | container::ContainerMethod::Exists => {
+                let ty = self.emit_type(mir.ty)?;
+                Some(self.emit_heap_intrinsic(&name, values, ty))
+            }
 
-            mir::RvalueKind::StringComp { .. } => bug_span!(
-                mir.span,
-                self.cx,
-                "runtime string comparisons not implemented"
-            ),
+            // Iteration returns whether an index was found, and a separate
+            // intrinsic provides the index itself.
+            container::ContainerMethod::First
+            | container::ContainerMethod::Last
+            | container::ContainerMethod::Next
+            | container::ContainerMethod::Prev => {
+                let ty = self.emit_type(mir.ty)?;
+                let found = self.emit_heap_intrinsic(&name, values.clone(), ty);
+                let (lvalue, index_ty) = index_lvalue.unwrap();
+                let index_ty = self.emit_type(index_ty)?;
+                let index = self.emit_heap_intrinsic(&format!("{}_index", name), values, index_ty);
+                self.emit_blocking_assign_llhd(lvalue, index)?;
+                Some(found)
+            }
 
-            mir::RvalueKind::Error => Err(()),
-        };
+            // Popping reads the element before removing it.
+            container::ContainerMethod::PopFront | container::ContainerMethod::PopBack => {
+                let peek = match method {
+                    container::ContainerMethod::PopFront => "front",
+                    _ => "back",
+                };
+                let ty = self.emit_type(mir.ty)?;
+                let value =
+                    self.emit_heap_intrinsic(&format!("{}.{}", prefix, peek), vec![handle], ty);
+                let handle = self.emit_heap_intrinsic(&name, values, handle_ty);
+                let lvalue = self.emit_mir_lvalue(lvalue.unwrap())?;
+                self.emit_blocking_assign_llhd(lvalue, handle)?;
+                Some(value)
+            }
 
-        value.map(|v| (v, Mode::Value))
+            // Modifications update the container.
+            container::ContainerMethod::Delete
+            | container::ContainerMethod::PushFront
+            | container::ContainerMethod::PushBack
+            | container::ContainerMethod::Insert => {
+                let handle = self.emit_heap_intrinsic(&name, values, handle_ty);
+                let lvalue = self.emit_mir_lvalue(lvalue.unwrap())?;
+                self.emit_blocking_assign_llhd(lvalue, handle)?;
+                None
+            }
+        };
+        Ok(result)
     }
 
     fn emit_prb_or_var(&mut self, sig: llhd::ir::Value) -> llhd::ir::Value {
@@ -1870,7 +5384,10 @@ where
                 self.emit_lvalue_index(value.ty, inner, base, length)
             }
 
-            mir::LvalueKind::Repeat(..) | mir::LvalueKind::Concat(..) => {
+            mir::LvalueKind::Repeat(..)
+            | mir::LvalueKind::Concat(..)
+            | mir::LvalueKind::Unpack(..)
+            | mir::LvalueKind::Stream { .. } => {
                 bug_span!(
                     mir.span,
                     self.cx,
@@ -1879,6 +5396,40 @@ where
                 );
             }
 
+            mir::LvalueKind::Property { object, slot } => {
+                let object = self.emit_mir_rvalue(object)?;
+                Ok((self.emit_object_slot(object, slot, mir.ty)?, None))
+            }
+
+            mir::LvalueKind::ContainerElem {
+                container,
+                value,
+                index,
+            } => {
+                let dim = value.ty.get_container().unwrap();
+                let prefix = container::llhd_prefix(dim);
+                let mut handle = self.emit_mir_rvalue(value)?;
+                let index = self.emit_mir_rvalue(index)?;
+
+                // Accessing an element of an associative array creates it.
+                if let ty::UnpackedDim::Assoc(_) = dim {
+                    handle = self.emit_heap_intrinsic(
+                        "llhd.assoc.insert",
+                        vec![handle, index],
+                        llhd::int_ty(64),
+                    );
+                    let lvalue = self.emit_mir_lvalue(container)?;
+                    self.emit_blocking_assign_llhd(lvalue, handle)?;
+                }
+
+                let ty = llhd::pointer_ty(self.emit_type(mir.ty)?);
+                let name = format!("{}.elem", prefix);
+                Ok((
+                    self.emit_heap_intrinsic(&name, vec![handle, index], ty),
+                    None,
+                ))
+            }
+
             // Errors from MIR lowering have already been reported. Just abort.
             mir::LvalueKind::Error => Err(()),
 
@@ -1982,6 +5533,109 @@ where
         bb
     }
 
+    /// Emit code that can be exited early, e.g. by a `disable` or `return`.
+    fn emit_exitable(
+        &mut self,
+        id: NodeId,
+        kind: ExitKind,
+        emit: impl FnOnce(&mut Self) -> Result<()>,
+    ) -> Result<()> {
+        self.exits.push(ExitTarget {
+            id,
+            kind,
+            exit_blk: None,
+            continue_blk: None,
+        });
+        let result = emit(self);
+        let target = self.exits.pop().unwrap();
+        result?;
+        if let Some(exit_blk) = target.exit_blk {
+            self.builder.ins().br(exit_blk);
+            self.builder.append_to(exit_blk);
+        }
+        Ok(())
+    }
+
+    /// Branch to the exit block of an exit target, or to its continue block
+    /// if it is a loop.
+    fn emit_exit_jump(&mut self, index: usize, cont: bool) {
+        let existing = if cont {
+            self.exits[index].continue_blk
+        } else {
+            self.exits[index].exit_blk
+        };
+        let blk = match existing {
+            Some(blk) => blk,
+            None => {
+                let blk = self.add_named_block(match (self.exits[index].kind, cont) {
+                    (_, true) => "loop_step",
+                    (ExitKind::Subroutine, _) => "return",
+                    _ => "disable",
+                });
+                if cont {
+                    self.exits[index].continue_blk = Some(blk);
+                } else {
+                    self.exits[index].exit_blk = Some(blk);
+                }
+                blk
+            }
+        };
+        self.builder.ins().br(blk);
+
+        // Any code following the jump is unreachable, but still needs a block
+        // to be emitted into.
+        let dead_blk = self.add_nameless_block();
+        self.builder.append_to(dead_blk);
+    }
+
+    /// Determine the values a `foreach` index iterates over.
+    ///
+    /// Returns the first index, the index one past the last, and the step
+    /// between indices. Ranges are iterated from left to right, such that
+    /// `[7:0]` counts down and `[0:7]` counts up.
+    fn foreach_range(&mut self, index: NodeId, env: ParamEnv) -> Result<(isize, isize, isize)> {
+        let hir = match self.hir_of(index)? {
+            HirNode::ForeachIndex(x) => x,
+            _ => unreachable!(),
+        };
+        let ty = self.type_of(hir.array, env)?;
+        let range = match ty.dims().nth(hir.dim) {
+            Some(ty::Dim::Unpacked(ty::UnpackedDim::Array(size))) => Some((0, size as isize - 1)),
+            Some(dim) => dim.get_range().map(|r| (r.left(), r.right())),
+            None => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` has only {} dimension(s) to iterate over",
+                        self.span(hir.array).extract(),
+                        ty.dims().count()
+                    ))
+                    .span(hir.human_span())
+                    .add_note(format!(
+                        "`{}` is of type `{}`",
+                        self.span(hir.array).extract(),
+                        ty
+                    )),
+                );
+                return Err(());
+            }
+        };
+        match range {
+            Some((left, right)) if left <= right => Ok((left, right + 1, 1)),
+            Some((left, right)) => Ok((left, right - 1, -1)),
+            None => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "unsupported: foreach over dimension `{}` of `{}`",
+                        ty.dims().nth(hir.dim).unwrap(),
+                        ty
+                    ))
+                    .span(hir.human_span()),
+                );
+                Err(())
+            }
+        }
+    }
+
     /// Emit the code for a statement.
     fn emit_stmt(&mut self, stmt_id: NodeId, env: ParamEnv) -> Result<()> {
         self.flush_mir();
@@ -1994,6 +5648,23 @@ where
 
     /// Emit the code for a statement, given its HIR.
     fn emit_stmt_regular(&mut self, stmt_id: NodeId, hir: &hir::Stmt, env: ParamEnv) -> Result<()> {
+        // Labelled statements may be exited early by a `disable`.
+        if hir.label.is_some() {
+            self.emit_exitable(stmt_id, ExitKind::Label, |pg| {
+                pg.emit_stmt_unlabelled(stmt_id, hir, env)
+            })
+        } else {
+            self.emit_stmt_unlabelled(stmt_id, hir, env)
+        }
+    }
+
+    /// Emit the code for a statement, given its HIR, ignoring its label.
+    fn emit_stmt_unlabelled(
+        &mut self,
+        stmt_id: NodeId,
+        hir: &hir::Stmt,
+        env: ParamEnv,
+    ) -> Result<()> {
         debug!("Emit stmt `{}`", {
             let s = hir.span.extract();
             if s.len() > 40 {
@@ -2023,10 +5694,10 @@ where
 
                 // Check for sanity.
                 for &assign in &simplified {
-                    assert_type!(assign.rhs.ty, assign.lhs.ty, assign.rhs.span, self.cx);
                     if assign.is_error() {
                         return Err(());
                     }
+                    assert_type!(assign.rhs.ty, assign.lhs.ty, assign.rhs.span, self.cx);
                 }
 
                 // Emit the appropriate assignments based on the assignment
@@ -2035,7 +5706,7 @@ where
                     hir::AssignKind::Block(_) => {
                         for &assign in &simplified {
                             let lhs_lv = self.emit_mir_lvalue(assign.lhs)?;
-                            let rhs_rv = self.emit_mir_rvalue(assign.rhs)?;
+                            let rhs_rv = self.emit_mir_rvalue_copy(assign.rhs)?;
                             self.emit_blocking_assign_llhd(lhs_lv, rhs_rv)?;
                         }
                     }
@@ -2044,15 +5715,15 @@ where
                         let delay_const = self.builder.ins().const_time(delay);
                         for &assign in &simplified {
                             let lhs_lv = self.emit_mir_lvalue(assign.lhs)?;
-                            let rhs_rv = self.emit_mir_rvalue(assign.rhs)?;
+                            let rhs_rv = self.emit_mir_rvalue_copy(assign.rhs)?;
                             self.builder.ins().drv(lhs_lv.0, rhs_rv, delay_const);
                         }
                     }
                     hir::AssignKind::NonblockDelay(delay) => {
-                        let delay = self.emit_rvalue(delay, env)?;
+                        let delay = self.emit_delay(delay, env)?;
                         for &assign in &simplified {
                             let lhs_lv = self.emit_mir_lvalue(assign.lhs)?;
-                            let rhs_rv = self.emit_mir_rvalue(assign.rhs)?;
+                            let rhs_rv = self.emit_mir_rvalue_copy(assign.rhs)?;
                             self.builder.ins().drv(lhs_lv.0, rhs_rv, delay);
                         }
                     }
@@ -2063,81 +5734,20 @@ where
                 stmt,
             } => {
                 let resume_blk = self.add_nameless_block();
-                let duration = self.emit_rvalue(expr_id, env)?.into();
+                let duration = self.emit_delay(expr_id, env)?;
                 self.builder.ins().wait_time(resume_blk, duration, vec![]);
                 self.builder.append_to(resume_blk);
                 self.flush_mir(); // ensure we don't reuse earlier expr probe
                 self.emit_shadow_update();
+                self.emit_disable_check();
+                self.emit_reactive_wait();
                 self.emit_stmt(stmt, env)?;
             }
             hir::StmtKind::Timed {
                 control: hir::TimingControl::ExplicitEvent(expr_id),
                 stmt,
             } => {
-                let expr_hir = match self.hir_of(expr_id)? {
-                    HirNode::EventExpr(x) => x,
-                    _ => unreachable!(),
-                };
-                trace!("would now emit event checking code for {:#?}", expr_hir);
-
-                // Store initial values of the expressions the event is
-                // sensitive to.
-                let init_blk = self.add_named_block("init");
-                self.builder.ins().br(init_blk);
-                self.builder.append_to(init_blk);
-                let mut init_values = vec![];
-                for event in &expr_hir.events {
-                    init_values.push(self.emit_rvalue(event.expr, env)?);
-                }
-
-                // Wait for any of the inputs to those expressions to change.
-                let check_blk = self.add_named_block("check");
-                let mut trigger_on = vec![];
-                for event in &expr_hir.events {
-                    let acc = self.accessed_nodes(event.expr, env)?;
-                    for &id in &acc.read {
-                        trigger_on.push(self.emitted_value(id).clone());
-                    }
-                }
-                self.builder.ins().wait(check_blk, trigger_on);
-                self.builder.append_to(check_blk);
-                self.flush_mir(); // ensure we don't reuse earlier expr probe
-                self.emit_shadow_update();
-
-                // Check if any of the events happened and produce a single bit
-                // value that represents this.
-                let mut event_cond = None;
-                for (event, init_value) in expr_hir.events.iter().zip(init_values.into_iter()) {
-                    trace!(
-                        "would now emit check if {:?} changed according to {:#?}",
-                        init_value,
-                        event
-                    );
-                    let now_value = self.emit_rvalue(event.expr, env)?;
-                    let mut trigger = self.emit_event_trigger(event.edge, init_value, now_value)?;
-                    for &iff in &event.iff {
-                        let iff_value = self.emit_rvalue_bool(iff, env)?;
-                        trigger = self.builder.ins().and(trigger, iff_value);
-                        self.builder.set_name(trigger, "iff".to_string());
-                    }
-                    event_cond = Some(match event_cond {
-                        Some(chain) => {
-                            let value = self.builder.ins().or(chain, trigger);
-                            self.builder.set_name(value, "event_or".to_string());
-                            value
-                        }
-                        None => trigger,
-                    });
-                }
-
-                // If the event happened, branch to a new block which will
-                // contain the subsequent statements. Otherwise jump back up to
-                // the initial block.
-                if let Some(event_cond) = event_cond {
-                    let event_blk = self.add_named_block("event");
-                    self.builder.ins().br_cond(event_cond, init_blk, event_blk);
-                    self.builder.append_to(event_blk);
-                }
+                self.emit_event_wait(expr_id, env)?;
 
                 // Emit the actual statement.
                 self.emit_stmt(stmt, env)?;
@@ -2157,14 +5767,49 @@ where
                 self.builder.append_to(trigger_blk);
                 self.flush_mir(); // ensure we don't reuse earlier expr probe
                 self.emit_shadow_update();
+                self.emit_disable_check();
+                self.emit_reactive_wait();
 
                 // Emit the actual statement.
                 self.emit_stmt(stmt, env)?;
             }
             hir::StmtKind::Expr(expr_id) => {
-                self.emit_rvalue(expr_id, env)?;
+                // Calls to void functions and system tasks produce no value,
+                // so handle them separately.
+                let mir = self.mir_rvalue(expr_id, env);
+                match mir.kind {
+                    mir::RvalueKind::Call { target, ref args } if mir.ty.is_void() => {
+                        self.emit_call(mir, target, args)?;
+                    }
+                    mir::RvalueKind::MethodCall { .. } if mir.ty.is_void() => {
+                        self.emit_method_call(mir)?;
+                    }
+                    mir::RvalueKind::ContainerMethod { .. } if mir.ty.is_void() => {
+                        self.emit_container_method(mir)?;
+                    }
+                    mir::RvalueKind::StringMethod { .. } if mir.ty.is_void() => {
+                        self.emit_string_method(mir)?;
+                    }
+                    mir::RvalueKind::Display {
+                        task,
+                        ref format,
+                        ref args,
+                    } => {
+                        self.emit_display(task, format, args)?;
+                    }
+                    mir::RvalueKind::Intrinsic(op) if mir.ty.is_void() => {
+                        self.emit_intrinsic(op, mir.ty, mir.origin)?;
+                    }
+                    _ => {
+                        self.emit_mir_rvalue(mir)?;
+                    }
+                }
+            }
+            hir::StmtKind::If { up: Some(up), .. } => {
+                self.emit_unique_if(hir, up, env)?;
             }
             hir::StmtKind::If {
+                up: None,
                 cond,
                 main_stmt,
                 else_stmt,
@@ -2204,6 +5849,16 @@ where
                         self.emit_stmt(init, env)?;
                         None
                     }
+                    hir::LoopKind::Foreach(index) => {
+                        let (start, _, _) = self.foreach_range(index, env)?;
+                        let start = self.builder.ins().const_int((32, BigInt::from(start)));
+                        let var = self.builder.ins().var(start);
+                        if let HirNode::ForeachIndex(x) = self.hir_of(index)? {
+                            self.builder.set_name(var, x.name.value.to_string());
+                        }
+                        self.set_emitted_value(index, var);
+                        None
+                    }
                 };
 
                 // Emit the loop prologue.
@@ -2221,6 +5876,13 @@ where
                     hir::LoopKind::While(cond) => Some(self.emit_rvalue_bool(cond, env)?),
                     hir::LoopKind::Do(_) => None,
                     hir::LoopKind::For(_, cond, _) => Some(self.emit_rvalue_bool(cond, env)?),
+                    hir::LoopKind::Foreach(index) => {
+                        let (_, end, _) = self.foreach_range(index, env)?;
+                        let var = self.emitted_value(index);
+                        let value = self.builder.ins().ld(var);
+                        let end = self.builder.ins().const_int((32, BigInt::from(end)));
+                        Some(self.builder.ins().neq(value, end))
+                    }
                 };
                 if let Some(enter_cond) = enter_cond {
                     let entry_blk = self.add_named_block("loop_continue");
@@ -2228,8 +5890,35 @@ where
                     self.builder.append_to(entry_blk);
                 }
 
-                // Emit the loop body.
-                self.emit_stmt(body, env)?;
+                // Emit the loop body. Breaking out of the inner loops of a
+                // multi-dimensional `foreach` exits the entire statement.
+                let is_inner_foreach = match kind {
+                    hir::LoopKind::Foreach(_) => match self.parent_node_id(stmt_id) {
+                        Some(parent) => match self.hir_of(parent)? {
+                            HirNode::ForeachIndex(_) => true,
+                            _ => false,
+                        },
+                        None => false,
+                    },
+                    _ => false,
+                };
+                let break_blk = match self.exits.last() {
+                    Some(outer) if is_inner_foreach => outer.exit_blk,
+                    _ => Some(exit_blk),
+                };
+                self.exits.push(ExitTarget {
+                    id: stmt_id,
+                    kind: ExitKind::Loop,
+                    exit_blk: break_blk,
+                    continue_blk: None,
+                });
+                let result = self.emit_stmt(body, env);
+                let target = self.exits.pop().unwrap();
+                result?;
+                if let Some(continue_blk) = target.continue_blk {
+                    self.builder.ins().br(continue_blk);
+                    self.builder.append_to(continue_blk);
+                }
 
                 // Emit the epilogue.
                 let continue_cond = match kind {
@@ -2240,7 +5929,7 @@ where
                         let one = self
                             .builder
                             .ins()
-                            .const_int((ty.get_bit_size().unwrap(), 0));
+                            .const_int((ty.get_bit_size().unwrap(), 1));
                         let value = self.builder.ins().sub(value, one);
                         self.builder.ins().st(repeat_var, value);
                         None
@@ -2251,6 +5940,15 @@ where
                         self.emit_rvalue(step, env)?;
                         None
                     }
+                    hir::LoopKind::Foreach(index) => {
+                        let (_, _, step) = self.foreach_range(index, env)?;
+                        let var = self.emitted_value(index);
+                        let value = self.builder.ins().ld(var);
+                        let step = self.builder.ins().const_int((32, BigInt::from(step)));
+                        let value = self.builder.ins().add(value, step);
+                        self.builder.ins().st(var, value);
+                        None
+                    }
                 };
                 match continue_cond {
                     Some(cond) => self.builder.ins().br_cond(cond, exit_blk, body_blk),
@@ -2265,6 +5963,7 @@ where
             }
 
             hir::StmtKind::Case {
+                up,
                 expr,
                 ref ways,
                 default,
@@ -2272,12 +5971,15 @@ where
             } => {
                 let expr = self.emit_rvalue(expr, env)?;
                 let final_blk = self.add_named_block("case_exit");
-                for &(ref way_exprs, stmt) in ways {
+                let mut checks = vec![];
+                let mut labels = vec![];
+                for &(ref way_exprs, _) in ways {
                     let mut last_check = self.builder.ins().const_int((1, 0));
+                    let mut way_labels = vec![];
                     for &way_expr in way_exprs {
                         // Determine the constant value of the label.
                         let way_const = self.constant_value_of(way_expr, env);
-                        let (_, special_bits, x_bits) = match &way_const.kind {
+                        let (value, special_bits, x_bits) = match &way_const.kind {
                             ValueKind::Int(v, s, x) => (v, s, x),
                             _ => panic!("case constant evaluates to non-integer"),
                         };
@@ -2307,8 +6009,10 @@ where
                                     mask |= BigInt::one();
                                 }
                             }
-                            self.builder.ins().const_int((way_width, mask))
+                            mask
                         });
+                        way_labels.push((value.clone(), mask.clone()));
+                        let mask = mask.map(|mask| self.builder.ins().const_int((way_width, mask)));
 
                         // Filter the comparison values through the mask.
                         let (lhs, rhs) = match mask {
@@ -2319,27 +6023,182 @@ where
                             None => (expr, way_expr),
                         };
 
-                        // Perform the comparison and branch.
+                        // Perform the comparison.
                         let check = self.builder.ins().eq(lhs, rhs);
                         last_check = self.builder.ins().or(last_check, check);
                     }
+                    checks.push(last_check);
+                    labels.push(way_labels);
+                }
+
+                // Branch to the first way that matches.
+                for (i, &(_, stmt)) in ways.iter().enumerate() {
                     let taken_blk = self.add_named_block("case_body");
                     let untaken_blk = self.add_nameless_block();
                     self.builder
                         .ins()
-                        .br_cond(last_check, untaken_blk, taken_blk);
+                        .br_cond(checks[i], untaken_blk, taken_blk);
                     self.builder.append_to(taken_blk);
+
+                    // For `unique` and `unique0`, check that none of the
+                    // subsequent ways match as well. Ways whose labels cannot
+                    // match the same value as this way's are skipped.
+                    if up == Some(ast::UniquePriority::Unique)
+                        || up == Some(ast::UniquePriority::Unique0)
+                    {
+                        let overlaps: Vec<_> = (i + 1..ways.len())
+                            .filter(|&j| {
+                                labels[i]
+                                    .iter()
+                                    .any(|a| labels[j].iter().any(|b| case_labels_overlap(a, b)))
+                            })
+                            .map(|j| checks[j])
+                            .collect();
+                        if !overlaps.is_empty() {
+                            let mut overlap = self.builder.ins().const_int((1, 0));
+                            for check in overlaps {
+                                overlap = self.builder.ins().or(overlap, check);
+                            }
+                            self.emit_unique_violation(
+                                up.unwrap(),
+                                "case",
+                                "multiple items match",
+                                hir.id,
+                                Some(overlap),
+                            )?;
+                        }
+                    }
+
                     self.emit_stmt(stmt, env)?;
                     self.builder.ins().br(final_blk);
                     self.builder.append_to(untaken_blk);
                 }
-                if let Some(default) = default {
-                    self.emit_stmt(default, env)?;
+                match default {
+                    Some(default) => self.emit_stmt(default, env)?,
+                    None if up == Some(ast::UniquePriority::Unique)
+                        || up == Some(ast::UniquePriority::Priority) =>
+                    {
+                        self.emit_unique_violation(
+                            up.unwrap(),
+                            "case",
+                            "no item matches",
+                            hir.id,
+                            None,
+                        )?;
+                    }
+                    None => (),
                 }
                 self.builder.ins().br(final_blk);
                 self.builder.append_to(final_blk);
             }
 
+            hir::StmtKind::Fork {
+                ref decls, join, ..
+            } => {
+                for &decl in decls {
+                    self.emit_stmt(decl, env)?;
+                }
+                self.emit_generation_start(stmt_id, env)?;
+                match join {
+                    ast::JoinKind::All => self.emit_fork_wait(&[stmt_id], true)?,
+                    ast::JoinKind::Any => self.emit_fork_wait(&[stmt_id], false)?,
+                    ast::JoinKind::None => (),
+                }
+            }
+            hir::StmtKind::WaitFork => {
+                let forks: Vec<_> = self.generations.iter().map(|&(id, _)| id).collect();
+                self.emit_fork_wait(&forks, true)?;
+            }
+            hir::StmtKind::DisableFork => self.emit_fork_disable(),
+            hir::StmtKind::Break | hir::StmtKind::Continue => {
+                let is_break = hir.kind == hir::StmtKind::Break;
+                let index = self
+                    .exits
+                    .iter()
+                    .rposition(|t| t.kind != ExitKind::Label)
+                    .filter(|&i| self.exits[i].kind == ExitKind::Loop);
+                match index {
+                    Some(index) => self.emit_exit_jump(index, !is_break),
+                    None => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "`{}` outside of a loop",
+                                if is_break { "break" } else { "continue" }
+                            ))
+                            .span(hir.span),
+                        );
+                        return Err(());
+                    }
+                }
+            }
+            hir::StmtKind::Return(expr) => {
+                let index = match self
+                    .exits
+                    .iter()
+                    .rposition(|t| t.kind == ExitKind::Subroutine)
+                {
+                    Some(index) => index,
+                    None => {
+                        self.emit(
+                            DiagBuilder2::error("`return` outside of a function or task")
+                                .span(hir.span),
+                        );
+                        return Err(());
+                    }
+                };
+                let sub_id = self.exits[index].id;
+                if let Some(expr) = expr {
+                    if self.type_of(sub_id, env)?.is_void() {
+                        let sub = self.hir_of(sub_id)?;
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "{} cannot return a value",
+                                sub.desc_full()
+                            ))
+                            .span(self.span(expr)),
+                        );
+                        return Err(());
+                    }
+                    let value = self.emit_rvalue(expr, env)?;
+                    let var = self.emitted_value(sub_id);
+                    self.builder.ins().st(var, value);
+                }
+                self.emit_exit_jump(index, false);
+            }
+            hir::StmtKind::Disable(target) => {
+                match self.exits.iter().rposition(|t| t.id == target) {
+                    Some(index) => self.emit_exit_jump(index, false),
+                    None => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "unsupported: `{}` disables a block outside the current process",
+                                hir.span.extract()
+                            ))
+                            .span(hir.span)
+                            .add_note(
+                                "Only blocks in the same procedure or subroutine can be disabled",
+                            ),
+                        );
+                        return Err(());
+                    }
+                }
+            }
+
+            hir::StmtKind::Assertion { deferred, cond, .. } => {
+                let cond = self.emit_rvalue_bool(cond, env)?;
+                match deferred {
+                    // Pass the outcome to the process executing the action
+                    // blocks of the deferred assertion.
+                    Some(_) => {
+                        self.generation_var(stmt_id)?;
+                        let signal = self.sync_signals[&SyncSignal::Cond(stmt_id)];
+                        self.emit_blocking_assign_llhd((signal, None), cond)?;
+                        self.emit_generation_start(stmt_id, env)?;
+                    }
+                    None => self.emit_assertion_actions(hir, cond, env)?,
+                }
+            }
+
             _ => {
                 error!("{:#?}", hir);
                 return self.unimp_msg("code generation for", hir);
@@ -2348,6 +6207,205 @@ where
         Ok(())
     }
 
+    /// Emit the action blocks of an assertion, given the outcome of its
+    /// condition.
+    ///
+    /// Assertions and assumptions without a fail statement report the failure
+    /// like `$error` would.
+    fn emit_assertion_actions(
+        &mut self,
+        hir: &hir::Stmt,
+        cond: llhd::ir::Value,
+        env: ParamEnv,
+    ) -> Result<()> {
+        let (kind, pass_stmt, fail_stmt) = match hir.kind {
+            hir::StmtKind::Assertion {
+                kind,
+                pass_stmt,
+                fail_stmt,
+                ..
+            } => (kind, pass_stmt, fail_stmt),
+            _ => unreachable!(),
+        };
+        let pass_blk = self.add_named_block("assert_pass");
+        let fail_blk = self.add_named_block("assert_fail");
+        self.builder.ins().br_cond(cond, fail_blk, pass_blk);
+        let final_blk = self.add_named_block("assert_exit");
+        self.builder.append_to(pass_blk);
+        if let Some(pass_stmt) = pass_stmt {
+            self.emit_stmt(pass_stmt, env)?;
+        }
+        self.builder.ins().br(final_blk);
+        self.builder.append_to(fail_blk);
+        match fail_stmt {
+            Some(fail_stmt) => self.emit_stmt(fail_stmt, env)?,
+            None if kind != hir::AssertionKind::Cover => {
+                let what = match kind {
+                    hir::AssertionKind::Assume => "assumption",
+                    _ => "assertion",
+                };
+                let format = self.default_report(hir.id, hir.span, &format!("{} failed", what));
+                self.emit_display(hir::DisplayTask::Error, &format, &[])?;
+            }
+            None => (),
+        }
+        self.builder.ins().br(final_blk);
+        self.builder.append_to(final_blk);
+        Ok(())
+    }
+
+    /// Emit the code for an `if` statement with a `unique`, `unique0`, or
+    /// `priority` qualifier.
+    ///
+    /// The qualifier applies to the entire chain of `else if` statements. Once
+    /// a condition holds, `unique` and `unique0` check the conditions further
+    /// down the chain and report a violation if any of them holds as well. Each
+    /// condition is evaluated exactly once. If
+    /// no condition holds and the chain has no final `else`, `unique` and
+    /// `priority` report a violation.
+    fn emit_unique_if(
+        &mut self,
+        hir: &hir::Stmt,
+        up: ast::UniquePriority,
+        env: ParamEnv,
+    ) -> Result<()> {
+        // Collect the conditions and statements of the chain.
+        let mut chain = vec![];
+        let mut stmt = hir;
+        let else_stmt = loop {
+            let else_stmt = match stmt.kind {
+                hir::StmtKind::If {
+                    cond,
+                    main_stmt,
+                    else_stmt,
+                    ..
+                } => {
+                    chain.push((cond, main_stmt));
+                    else_stmt
+                }
+                _ => unreachable!(),
+            };
+            let next = match else_stmt {
+                Some(id) => self.hir_of(id)?,
+                None => break None,
+            };
+            match next {
+                HirNode::Stmt(x) if x.label.is_none() => match x.kind {
+                    hir::StmtKind::If { up: None, .. } => stmt = x,
+                    _ => break else_stmt,
+                },
+                _ => break else_stmt,
+            }
+        };
+
+        // Evaluate the conditions. Since `unique` and `unique0` check every
+        // condition against the ones further down the chain, all of them are
+        // evaluated up front, such that each is evaluated only once.
+        let mut conds = vec![];
+        if up != ast::UniquePriority::Priority {
+            for &(cond, _) in &chain {
+                conds.push(self.emit_rvalue_bool(cond, env)?);
+            }
+        }
+
+        // Emit the chain.
+        let final_blk = self.add_named_block("if_exit");
+        for (i, &(cond, main_stmt)) in chain.iter().enumerate() {
+            let main_blk = self.add_named_block("if_true");
+            let else_blk = self.add_named_block("if_false");
+            let cond = match conds.get(i) {
+                Some(&cond) => cond,
+                None => self.emit_rvalue_bool(cond, env)?,
+            };
+            self.builder.ins().br_cond(cond, else_blk, main_blk);
+            self.builder.append_to(main_blk);
+            if up != ast::UniquePriority::Priority && i + 1 < chain.len() {
+                let mut overlap = self.builder.ins().const_int((1, 0));
+                for &cond in &conds[i + 1..] {
+                    overlap = self.builder.ins().or(overlap, cond);
+                }
+                self.emit_unique_violation(
+                    up,
+                    "if",
+                    "multiple conditions match",
+                    hir.id,
+                    Some(overlap),
+                )?;
+            }
+            self.emit_stmt(main_stmt, env)?;
+            self.builder.ins().br(final_blk);
+            self.builder.append_to(else_blk);
+        }
+        match else_stmt {
+            Some(else_stmt) => self.emit_stmt(else_stmt, env)?,
+            None if up != ast::UniquePriority::Unique0 => {
+                self.emit_unique_violation(up, "if", "no condition matches", hir.id, None)?;
+            }
+            None => (),
+        }
+        self.builder.ins().br(final_blk);
+        self.builder.append_to(final_blk);
+        Ok(())
+    }
+
+    /// Emit a report of a `unique`, `unique0`, or `priority` violation.
+    ///
+    /// The violation is reported like `$warning` would, if `cond` holds or is
+    /// omitted. The report is issued immediately rather than deferred to the
+    /// Observed region, so a procedure that re-evaluates within the same time
+    /// step reports violations of transient values as well.
+    fn emit_unique_violation(
+        &mut self,
+        up: ast::UniquePriority,
+        stmt: &str,
+        reason: &str,
+        id: NodeId,
+        cond: Option<llhd::ir::Value>,
+    ) -> Result<()> {
+        let skip_blk = match cond {
+            Some(cond) => {
+                let report_blk = self.add_named_block("violation");
+                let skip_blk = self.add_nameless_block();
+                self.builder.ins().br_cond(cond, skip_blk, report_blk);
+                self.builder.append_to(report_blk);
+                Some(skip_blk)
+            }
+            None => None,
+        };
+        let up = match up {
+            ast::UniquePriority::Unique => "unique",
+            ast::UniquePriority::Unique0 => "unique0",
+            ast::UniquePriority::Priority => "priority",
+        };
+        let message = format!("{} {} violation: {}", up, stmt, reason);
+        let format = self.default_report(id, self.span(id), &message);
+        self.emit_display(hir::DisplayTask::Warning, &format, &[])?;
+        if let Some(skip_blk) = skip_blk {
+            self.builder.ins().br(skip_blk);
+            self.builder.append_to(skip_blk);
+        }
+        Ok(())
+    }
+
+    /// Format a message reported by the simulator in the absence of a
+    /// user-provided action, such as a failed assertion without an `else`
+    /// block.
+    ///
+    /// The message is prefixed with the source location of `span` and
+    /// followed by the hierarchical name of the scope containing `id`. The
+    /// result is used as the format string of a display task, so any `%` in
+    /// the path or scope name is escaped.
+    fn default_report(&self, id: NodeId, span: Span, message: &str) -> String {
+        let loc = span.begin();
+        format!(
+            "{}:{}: {} in {}\n",
+            loc.source.get_path().replace('%', "%%"),
+            loc.human_line(),
+            message,
+            hierarchical_scope_name(self.cx, id).replace('%', "%%")
+        )
+    }
+
     /// Emit the code for a variable declaration statement, given its HIR.
     fn emit_stmt_var_decl(
         &mut self,
@@ -2365,7 +6423,10 @@ where
         );
         let ty = self.emit_type(ty)?;
         let init = match hir.init {
-            Some(expr) => self.emit_rvalue(expr, env)?,
+            Some(expr) => {
+                let mir = self.mir_rvalue(expr, env);
+                self.emit_mir_rvalue_copy(mir)?
+            }
             None => self.emit_zero_for_type(&ty),
         };
         let value = self.builder.ins().var(init);
@@ -2374,6 +6435,80 @@ where
         Ok(())
     }
 
+    /// Emit the code to wait for an event expression to trigger.
+    ///
+    /// Upon return, the builder is positioned in a block that is reached once
+    /// the event has occurred.
+    fn emit_event_wait(&mut self, expr_id: NodeId, env: ParamEnv) -> Result<()> {
+        let expr_hir = match self.hir_of(expr_id)? {
+            HirNode::EventExpr(x) => x,
+            _ => unreachable!(),
+        };
+        trace!("would now emit event checking code for {:#?}", expr_hir);
+
+        // Store initial values of the expressions the event is
+        // sensitive to.
+        let init_blk = self.add_named_block("init");
+        self.builder.ins().br(init_blk);
+        self.builder.append_to(init_blk);
+        let mut init_values = vec![];
+        for event in &expr_hir.events {
+            init_values.push(self.emit_rvalue(event.expr, env)?);
+        }
+
+        // Wait for any of the inputs to those expressions to change.
+        let check_blk = self.add_named_block("check");
+        let mut trigger_on = vec![];
+        for event in &expr_hir.events {
+            let acc = self.accessed_nodes(event.expr, env)?;
+            for &id in &acc.read {
+                trigger_on.push(self.emitted_value(id).clone());
+            }
+        }
+        self.builder.ins().wait(check_blk, trigger_on);
+        self.builder.append_to(check_blk);
+        self.flush_mir(); // ensure we don't reuse earlier expr probe
+        self.emit_shadow_update();
+        self.emit_disable_check();
+
+        // Check if any of the events happened and produce a single bit
+        // value that represents this.
+        let mut event_cond = None;
+        for (event, init_value) in expr_hir.events.iter().zip(init_values.into_iter()) {
+            trace!(
+                "would now emit check if {:?} changed according to {:#?}",
+                init_value,
+                event
+            );
+            let now_value = self.emit_rvalue(event.expr, env)?;
+            let mut trigger = self.emit_event_trigger(event.edge, init_value, now_value)?;
+            for &iff in &event.iff {
+                let iff_value = self.emit_rvalue_bool(iff, env)?;
+                trigger = self.builder.ins().and(trigger, iff_value);
+                self.builder.set_name(trigger, "iff".to_string());
+            }
+            event_cond = Some(match event_cond {
+                Some(chain) => {
+                    let value = self.builder.ins().or(chain, trigger);
+                    self.builder.set_name(value, "event_or".to_string());
+                    value
+                }
+                None => trigger,
+            });
+        }
+
+        // If the event happened, branch to a new block which will
+        // contain the subsequent statements. Otherwise jump back up to
+        // the initial block.
+        if let Some(event_cond) = event_cond {
+            let event_blk = self.add_named_block("event");
+            self.builder.ins().br_cond(event_cond, init_blk, event_blk);
+            self.builder.append_to(event_blk);
+        }
+        self.emit_reactive_wait();
+        Ok(())
+    }
+
     /// Emit the code to check if a certain edge occurred between two values.
     fn emit_event_trigger(
         &mut self,
@@ -2434,7 +6569,7 @@ where
         rvalue: &'gcx mir::Rvalue<'gcx>,
     ) -> Result<()> {
         let lv = self.emit_mir_lvalue(lvalue)?;
-        let rv = self.emit_mir_rvalue(rvalue)?;
+        let rv = self.emit_mir_rvalue_copy(rvalue)?;
         self.emit_blocking_assign_llhd(lv, rv)
     }
 
@@ -2470,6 +6605,341 @@ where
         Ok(())
     }
 
+    /// Map the synchronization inputs and outputs of a process to their
+    /// signals.
+    fn map_sync_args(&mut self, sync_inputs: &[SyncSignal], sync_outputs: &[SyncSignal]) {
+        let num_inputs = self.builder.input_args().count() - sync_inputs.len();
+        let num_outputs = self.builder.output_args().count() - sync_outputs.len();
+        for (i, &signal) in sync_inputs.iter().enumerate() {
+            let value = self.builder.input_arg(num_inputs + i);
+            self.sync_signals.insert(signal, value);
+        }
+        for (i, &signal) in sync_outputs.iter().enumerate() {
+            let value = self.builder.output_arg(num_outputs + i);
+            self.sync_signals.insert(signal, value);
+        }
+    }
+
+    /// Allocate the variables that track the generation of the deferred
+    /// assertions and fork-join blocks started by the unit.
+    ///
+    /// The variables are initialized from the control signals, such that
+    /// processes which re-enter their entry block continue counting.
+    fn emit_generation_vars(&mut self, stmts: &[&hir::Stmt]) {
+        for stmt in stmts {
+            let ctl = self.sync_signals[&SyncSignal::Ctl(stmt.id)];
+            let init = self.builder.ins().prb(ctl);
+            let value = self.builder.ins().var(init);
+            let name = format!("{}.gen", self.sync_signal_name(SyncSignal::Ctl(stmt.id)));
+            self.builder.set_name(value, name);
+            self.generations.push((stmt.id, value));
+        }
+    }
+
+    /// Find the variable that tracks the generation of a deferred assertion or
+    /// fork-join block.
+    fn generation_var(&mut self, stmt_id: NodeId) -> Result<llhd::ir::Value> {
+        match self.generations.iter().find(|&&(id, _)| id == stmt_id) {
+            Some(&(_, value)) => Ok(value),
+            None => {
+                let what = match self.hir_of(stmt_id)? {
+                    HirNode::Stmt(hir::Stmt {
+                        kind: hir::StmtKind::Assertion { .. },
+                        ..
+                    }) => "deferred assertion",
+                    _ => "fork-join block",
+                };
+                self.emit(
+                    DiagBuilder2::error(format!("unsupported: {} in subroutine", what))
+                        .span(self.span(stmt_id)),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Start the process executing a deferred assertion's action blocks, or
+    /// the branches of a fork-join block.
+    ///
+    /// The values passed to the process are captured, and the generation is
+    /// advanced to the next even number, which starts the process and clears
+    /// a previous disable.
+    fn emit_generation_start(&mut self, stmt_id: NodeId, env: ParamEnv) -> Result<()> {
+        let var = self.generation_var(stmt_id)?;
+        let stmt = match self.hir_of(stmt_id)? {
+            HirNode::Stmt(x) => x,
+            _ => unreachable!(),
+        };
+        for value_id in self.started_values(stmt, env)? {
+            let signal = self.sync_signals[&SyncSignal::Value(stmt_id, value_id)];
+            let value = self.emitted_value(value_id);
+            let value = self.builder.ins().ld(value);
+            self.emit_blocking_assign_llhd((signal, None), value)?;
+        }
+        let ctl = self.sync_signals[&SyncSignal::Ctl(stmt_id)];
+        let one = self.builder.ins().const_int((32, 1));
+        let gen = self.builder.ins().ld(var);
+        let gen = self.builder.ins().or(gen, one);
+        let gen = self.builder.ins().add(gen, one);
+        self.builder.ins().st(var, gen);
+        self.emit_blocking_assign_llhd((ctl, None), gen)
+    }
+
+    /// Disable the branches of all fork-join blocks started by the unit.
+    ///
+    /// The lowest bit of the generation is set, which instructs any running
+    /// branches to stop.
+    fn emit_fork_disable(&mut self) {
+        for (fork_id, var) in self.generations.clone() {
+            if let Ok(HirNode::Stmt(hir::Stmt {
+                kind: hir::StmtKind::Assertion { .. },
+                ..
+            })) = self.hir_of(fork_id)
+            {
+                continue;
+            }
+            let ctl = self.sync_signals[&SyncSignal::Ctl(fork_id)];
+            let one = self.builder.ins().const_int((32, 1));
+            let gen = self.builder.ins().ld(var);
+            let gen = self.builder.ins().or(gen, one);
+            self.builder.ins().st(var, gen);
+            let one_epsilon = llhd::value::TimeValue::new(num::zero(), 0, 1);
+            let one_epsilon = self.builder.ins().const_time(one_epsilon);
+            self.builder.ins().drv(ctl, gen, one_epsilon);
+        }
+    }
+
+    /// Wait for all or any of the branches of fork-join blocks to finish.
+    ///
+    /// A branch has finished once it has signalled completion for the current
+    /// generation, or for the current generation with the disable bit set.
+    fn emit_fork_wait(&mut self, forks: &[NodeId], all: bool) -> Result<()> {
+        let mut done = vec![];
+        for &fork_id in forks {
+            let var = self.generation_var(fork_id)?;
+            if let HirNode::Stmt(hir::Stmt {
+                kind: hir::StmtKind::Fork { ref branches, .. },
+                ..
+            }) = self.hir_of(fork_id)?
+            {
+                for &branch_id in branches {
+                    done.push((var, self.sync_signals[&SyncSignal::Done(branch_id)]));
+                }
+            }
+        }
+        if done.is_empty() {
+            return Ok(());
+        }
+        let check_blk = self.add_named_block("join_check");
+        let wait_blk = self.add_named_block("join_wait");
+        let exit_blk = self.add_named_block("join_exit");
+        self.builder.ins().br(check_blk);
+        self.builder.append_to(check_blk);
+        self.emit_disable_check();
+        let one = self.builder.ins().const_int((32, 1));
+        let mut cond = None;
+        for &(var, sig) in &done {
+            let gen = self.builder.ins().ld(var);
+            let gen = self.builder.ins().or(gen, one);
+            let value = self.builder.ins().prb(sig);
+            let value = self.builder.ins().or(value, one);
+            let finished = self.builder.ins().eq(value, gen);
+            cond = Some(match cond {
+                Some(chain) if all => self.builder.ins().and(chain, finished),
+                Some(chain) => self.builder.ins().or(chain, finished),
+                None => finished,
+            });
+        }
+        self.builder
+            .ins()
+            .br_cond(cond.unwrap(), wait_blk, exit_blk);
+        self.builder.append_to(wait_blk);
+        let trigger_on = done.iter().map(|&(_, sig)| sig).collect();
+        self.builder.ins().wait(check_blk, trigger_on);
+        self.builder.append_to(exit_blk);
+        self.flush_mir(); // ensure we don't reuse earlier expr probe
+        self.emit_shadow_update();
+        Ok(())
+    }
+
+    /// Emit a check whether the fork-join branch the unit implements has been
+    /// disabled.
+    ///
+    /// This is called whenever the unit resumes from waiting.
+    fn emit_disable_check(&mut self) {
+        let branch = match self.fork_branch {
+            Some(x) => x,
+            None => return,
+        };
+        let now = self.builder.ins().prb(branch.ctl);
+        let disabled = self.builder.ins().ext_slice(now, 0, 1);
+        let resume_blk = self.add_nameless_block();
+        self.builder
+            .ins()
+            .br_cond(disabled, resume_blk, branch.abort_blk);
+        self.builder.append_to(resume_blk);
+    }
+
+    /// Defer the remainder of a program process to the reactive region.
+    ///
+    /// LLHD has no notion of scheduling regions, so this is an approximation.
+    /// Blocking assignments take effect after one epsilon and nonblocking
+    /// assignments after one delta step. Waiting for one delta step therefore
+    /// lets the assignments pending in the design's active and NBA regions
+    /// take effect before the program observes their results. Events caused
+    /// by these assignments may still be pending, however, such that the
+    /// program can observe values which are not yet settled. Does nothing
+    /// outside of programs.
+    fn emit_reactive_wait(&mut self) {
+        if !self.program {
+            return;
+        }
+        let resume_blk = self.add_named_block("reactive");
+        let delay = llhd::value::TimeValue::new(num::zero(), 1, 0);
+        let delay = self.builder.ins().const_time(delay);
+        self.builder.ins().wait_time(resume_blk, delay, vec![]);
+        self.builder.append_to(resume_blk);
+        self.flush_mir(); // ensure we don't reuse earlier expr probe
+        self.emit_shadow_update();
+    }
+
+    /// Signal the completion of the programs in a module.
+    ///
+    /// If the module has a `done` output, it is driven once the initial
+    /// procedures of its programs and of the programs in its instances have
+    /// all completed. Otherwise the module is the root of the design, and a
+    /// process is emitted that calls `$finish` at that point.
+    fn emit_program_done(&mut self, name_prefix: &str, done: Option<llhd::ir::Value>) {
+        if self.program_done.is_empty() {
+            return;
+        }
+        if let Some(done) = done {
+            let mut all_done = None;
+            for signal in self.program_done.clone() {
+                let value = self.builder.ins().prb(signal);
+                all_done = Some(match all_done {
+                    Some(chain) => self.builder.ins().and(chain, value),
+                    None => value,
+                });
+            }
+            let delay = llhd::value::TimeValue::new(num::zero(), 0, 1);
+            let delay = self.builder.ins().const_time(delay);
+            self.builder.ins().drv(done, all_done.unwrap(), delay);
+            return;
+        }
+        let mut sig = llhd::ir::Signature::new();
+        for _ in &self.program_done {
+            sig.add_input(llhd::signal_ty(llhd::int_ty(1)));
+        }
+        let mut prok = llhd::ir::UnitData::new(
+            llhd::ir::UnitKind::Process,
+            llhd::ir::UnitName::Local(format!("{}.finish", name_prefix)),
+            sig,
+        );
+        let mut builder = llhd::ir::UnitBuilder::new_anonymous(&mut prok);
+        let entry_blk = builder.block();
+        let check_blk = builder.block();
+        let idle_blk = builder.block();
+        let finish_blk = builder.block();
+        builder.set_block_name(check_blk, "check".to_string());
+        builder.set_block_name(idle_blk, "idle".to_string());
+        builder.set_block_name(finish_blk, "finish".to_string());
+        builder.append_to(entry_blk);
+        builder.ins().br(check_blk);
+
+        // Check whether all initial procedures have completed.
+        builder.append_to(check_blk);
+        let mut all_done = None;
+        for arg in builder.input_args().collect::<Vec<_>>() {
+            builder.set_name(arg, "done".to_string());
+            let done = builder.ins().prb(arg);
+            all_done = Some(match all_done {
+                Some(chain) => builder.ins().and(chain, done),
+                None => done,
+            });
+        }
+        builder
+            .ins()
+            .br_cond(all_done.unwrap(), idle_blk, finish_blk);
+
+        // Otherwise wait for any of them to complete.
+        builder.append_to(idle_blk);
+        let trigger_on = builder.input_args().collect();
+        builder.ins().wait(check_blk, trigger_on);
+
+        // Finish the simulation.
+        builder.append_to(finish_blk);
+        let ext_unit = builder.add_extern(
+            llhd::ir::UnitName::Global(mir::Intrinsic::Finish.llhd_name().into()),
+            llhd::ir::Signature::new(),
+        );
+        builder.ins().call(ext_unit, vec![]);
+        builder.ins().halt();
+
+        let unit = self.into.add_unit(prok);
+        let ext_unit = self.builder.add_extern(
+            self.into.unit(unit).name().clone(),
+            self.into.unit(unit).sig().clone(),
+        );
+        let inputs = self.program_done.clone();
+        self.builder.ins().inst(ext_unit, inputs, vec![]);
+    }
+
+    /// Allocate the variables that hold the past values of an expression.
+    ///
+    /// The variables hold the value 1, 2, etc. clock ticks ago, up to `depth`.
+    fn emit_history(
+        &mut self,
+        init: llhd::ir::Value,
+        depth: usize,
+        name: &str,
+    ) -> Vec<llhd::ir::Value> {
+        (0..depth)
+            .map(|i| {
+                let value = self.builder.ins().var(init);
+                self.builder
+                    .set_name(value, format!("{}.past{}", name, i + 1));
+                value
+            })
+            .collect()
+    }
+
+    /// Push a new value into the history of an expression.
+    ///
+    /// Returns the oldest value in the history, or the new value if the
+    /// history is empty.
+    fn emit_history_shift(
+        &mut self,
+        history: &[llhd::ir::Value],
+        now: llhd::ir::Value,
+    ) -> llhd::ir::Value {
+        let mut value = now;
+        for &past in history {
+            let prev = self.builder.ins().ld(past);
+            self.builder.ins().st(past, value);
+            value = prev;
+        }
+        value
+    }
+
+    /// Emit the shadow variables for signals which are both read and written.
+    fn emit_shadows(&mut self, acc: &AccessTable) {
+        let input_set: HashSet<_> = acc.read.iter().cloned().collect();
+        let output_set: HashSet<_> = acc.written.iter().cloned().collect();
+        for &id in input_set.intersection(&output_set) {
+            let init = self.builder.ins().prb(self.values[&id.into()]);
+            let shadow = self.builder.ins().var(init);
+            if let Some(name) = self
+                .builder
+                .get_name(self.values[&id.into()])
+                .map(|name| format!("{}.shadow", name))
+            {
+                self.builder.set_name(shadow, name);
+            }
+            self.shadows.insert(id.into(), shadow);
+        }
+    }
+
     /// Emit the code to update the shadow variables of signals.
     fn emit_shadow_update(&mut self) {
         for (&id, &shadow) in &self.shadows {
@@ -2611,6 +7081,93 @@ pub struct EmittedProcedure {
     inputs: Vec<AccessedNode>,
     /// The nodes used as lvalues.
     outputs: Vec<AccessedNode>,
+    /// The synchronization signals read, following the regular inputs.
+    sync_inputs: Vec<SyncSignal>,
+    /// The synchronization signals driven, following the regular outputs.
+    sync_outputs: Vec<SyncSignal>,
+    /// Whether the procedure drives a completion signal after all other
+    /// outputs, as done by the initial procedures of a program.
+    program_done: bool,
+}
+
+/// A signal used to synchronize a procedure with a process it starts, such as
+/// the process executing a deferred assertion's action blocks, or a branch of
+/// a fork-join block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SyncSignal {
+    /// The signal that starts the process executing a deferred assertion's
+    /// action blocks, or starts and disables the branches of a fork-join block.
+    Ctl(NodeId),
+    /// The signal that passes the outcome of a deferred assertion's condition
+    /// to the process that executes its action blocks.
+    Cond(NodeId),
+    /// The signal that passes the value of a variable to the action blocks of
+    /// a deferred assertion, or to the branches of a fork-join block.
+    Value(NodeId, NodeId),
+    /// The signal that indicates that a fork-join branch has finished.
+    Done(NodeId),
+}
+
+/// The state of a process that implements a fork-join branch.
+#[derive(Clone, Copy)]
+struct ForkBranch {
+    /// The signal that starts and disables the branch.
+    ctl: llhd::ir::Value,
+    /// The variable that holds the generation currently being executed.
+    gen: llhd::ir::Value,
+    /// The block to branch to when the branch is disabled.
+    abort_blk: llhd::ir::Block,
+}
+
+/// A statement or subroutine that can be exited early by a `break`,
+/// `continue`, `return`, or `disable` statement.
+struct ExitTarget {
+    /// The loop, labelled statement, or subroutine.
+    id: NodeId,
+    /// What kind of construct is being exited.
+    kind: ExitKind,
+    /// The block to branch to in order to exit. Allocated on first use.
+    exit_blk: Option<llhd::ir::Block>,
+    /// The block to branch to in order to start the next loop iteration.
+    /// Allocated on first use.
+    continue_blk: Option<llhd::ir::Block>,
+}
+
+/// The different constructs that can be exited early.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExitKind {
+    /// A loop, exited by `break` and `continue`.
+    Loop,
+    /// A labelled statement, exited by `disable`.
+    Label,
+    /// A function or task, exited by `return`.
+    Subroutine,
+}
+
+/// Result of emitting a function.
+pub struct EmittedFunction {
+    /// The name of the emitted LLHD unit.
+    name: llhd::ir::UnitName,
+    /// The signature of the emitted LLHD unit.
+    sig: llhd::ir::Signature,
+    /// The nodes outside the function that it reads. These are passed as
+    /// additional arguments after the function's regular arguments.
+    inputs: Vec<AccessedNode>,
+}
+
+/// Add the dimensions of an interface or module instance array to the type of
+/// a declaration within the instance.
+///
+/// The instance dimensions are outermost, such that `a[0][1].x` selects
+/// `a.x[0][1]`.
+fn transposed_type<'a>(
+    cx: &impl Context<'a>,
+    ty: &'a UnpackedType<'a>,
+    dims: &[ty::UnpackedDim<'a>],
+) -> &'a UnpackedType<'a> {
+    let mut sig_ty = ty.clone();
+    sig_ty.dims = dims.iter().chain(ty.dims.iter()).cloned().collect();
+    sig_ty.intern(cx)
 }
 
 /// A module's port interface.
@@ -2622,6 +7179,15 @@ pub struct ModuleIntf<'a> {
     pub inputs: Vec<ModulePort<'a>>,
     /// The outputs of the module.
     pub outputs: Vec<ModulePort<'a>>,
+    /// The package variables accessed by the module, passed as additional
+    /// outputs after the regular ones.
+    pub globals: Vec<NodeId>,
+    /// The declarations accessed hierarchically from outside the module,
+    /// passed as additional outputs after the package variables.
+    pub exports: Vec<NodeId>,
+    /// Whether the module contains programs, whose completion is signalled
+    /// through an additional output after the exported declarations.
+    pub program_done: bool,
 }
 
 /// A canonicalized port of a module.
@@ -2642,6 +7208,120 @@ pub struct ModulePort<'a> {
     pub kind: ModulePortKind<'a>,
 }
 
+/// A slice of a port connection mapped onto a slice of an internal port.
+#[derive(Debug)]
+struct PortSlice {
+    /// The connected value.
+    mapping: NodeEnvId,
+    /// The offset of the slice within the connected value.
+    conn_offset: usize,
+    /// The offset of the slice within the internal port.
+    port_offset: usize,
+    /// The width of the slice.
+    width: usize,
+}
+
+/// Determine the part of a port connection passed to one instance of an
+/// instance array.
+///
+/// Returns the base and length of the connection's slice for the instance at
+/// `index`, or `None` if the connection is replicated to all instances. The
+/// left-most instance is connected to the most significant bits.
+fn inst_array_slice(
+    ty: &UnpackedType,
+    port_ty: &UnpackedType,
+    index: usize,
+    count: usize,
+) -> Option<(usize, usize)> {
+    if count <= 1 {
+        return None;
+    }
+    let width = port_ty.get_bit_size()?;
+    if ty.get_bit_size()? != width * count {
+        return None;
+    }
+    Some(((count - 1 - index) * width, width))
+}
+
+/// A net whose drivers are combined by a resolution function.
+#[derive(Debug)]
+struct ResolvedNet {
+    /// The net type, which determines how the drivers are combined.
+    ty: ast::NetType,
+    /// The width of the net in bits.
+    width: usize,
+    /// The name of the net.
+    name: String,
+    /// The signal of the net.
+    signal: llhd::ir::Value,
+    /// The signal holding the unknown plane of a declared net. Inout ports
+    /// only receive the value plane of the net they are connected to.
+    unknown: Option<llhd::ir::Value>,
+    /// The value and unknown outputs of an inout port. If present, the
+    /// drivers are resolved onto these instead of the net's signal.
+    outputs: Option<(llhd::ir::Value, llhd::ir::Value)>,
+    /// The value and unknown signal of each driver.
+    drivers: Vec<(llhd::ir::Value, llhd::ir::Value)>,
+}
+
+/// Determine the package a variable is declared in, if any.
+fn enclosing_package<'gcx>(
+    cx: &impl Context<'gcx>,
+    id: NodeId,
+) -> Option<&'gcx ast::Package<'gcx>> {
+    let name = match cx.ast_of(id).ok()? {
+        AstNode::VarDecl(name, ..) | AstNode::NetDecl(name, ..) => name,
+        _ => return None,
+    };
+    cx.scope_location(name).scope.as_all().get_package()
+}
+
+/// Check whether two case labels can match the same value.
+///
+/// Each label is given as its value and the mask of bits compared by `casez`
+/// and `casex`, if any. The labels overlap if they agree in all bits compared
+/// by both.
+fn case_labels_overlap(a: &(BigInt, Option<BigInt>), b: &(BigInt, Option<BigInt>)) -> bool {
+    let mut diff = &a.0 ^ &b.0;
+    for mask in a.1.iter().chain(b.1.iter()) {
+        diff &= mask;
+    }
+    diff.is_zero()
+}
+
+/// Determine the net or variable targeted by an lvalue.
+///
+/// Returns the declaration together with the offset and width of the targeted
+/// bits. Selects with a non-constant base conservatively target all bits.
+fn lvalue_net_bits<'gcx>(
+    cx: &impl Context<'gcx>,
+    mir: &'gcx mir::Lvalue<'gcx>,
+) -> Option<(NodeId, usize, usize)> {
+    match mir.kind {
+        mir::LvalueKind::Var(id) | mir::LvalueKind::Port(id) => {
+            Some((id, 0, mir.ty.get_bit_size()?))
+        }
+        mir::LvalueKind::Index {
+            value,
+            base,
+            length,
+        } => {
+            let (id, offset, width) = lvalue_net_bits(cx, value)?;
+            if !base.is_const() || !value.ty.coalesces_to_llhd_scalar() {
+                return Some((id, offset, width));
+            }
+            match cx.const_mir_rvalue_int(Ref(base)).ok()?.to_usize() {
+                Some(base) => Some((id, offset + base, std::cmp::max(1, length))),
+                None => Some((id, offset, width)),
+            }
+        }
+        mir::LvalueKind::Transmute(value)
+        | mir::LvalueKind::Unpack(value)
+        | mir::LvalueKind::Member { value, .. } => lvalue_net_bits(cx, value),
+        _ => None,
+    }
+}
+
 /// The different kinds of module ports.
 #[derive(Debug)]
 pub enum ModulePortKind<'a> {
@@ -2653,6 +7333,11 @@ pub enum ModulePortKind<'a> {
         env: ParamEnv,
         decl_id: NodeId,
     },
+    /// The value an inout port drives onto its connected net.
+    InoutValue,
+    /// The unknown bits of the value an inout port drives onto its connected
+    /// net, which are `z` where the port does not drive the net.
+    InoutUnknown,
 }
 
 /// An signal within an interface.
@@ -2668,3 +7353,32 @@ pub struct IntfSignal<'a> {
     /// The expression assigned as default to the signal.
     pub default: Option<NodeId>,
 }
+
+/// A visitor that finds the deferred assertions and fork-join blocks started
+/// by a procedure or fork branch.
+struct StartedFinder<'a, 'gcx, C> {
+    cx: &'a C,
+    stmts: Vec<&'gcx hir::Stmt>,
+}
+
+impl<'a, 'gcx: 'a, C> hir::Visitor<'gcx> for StartedFinder<'a, 'gcx, C>
+where
+    C: Context<'gcx>,
+{
+    type Context = C;
+    fn context(&self) -> &C {
+        self.cx
+    }
+
+    fn visit_stmt(&mut self, stmt: &'gcx hir::Stmt) {
+        match stmt.kind {
+            hir::StmtKind::Assertion {
+                deferred: Some(_), ..
+            } => self.stmts.push(stmt),
+            // Do not descend into the branches, which start their own nested
+            // fork-join blocks.
+            hir::StmtKind::Fork { .. } => self.stmts.push(stmt),
+            _ => hir::walk_stmt(self, stmt),
+        }
+    }
+}
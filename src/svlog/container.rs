@@ -0,0 +1,270 @@
+// Copyright (c) 2016-2020 Fabian Schuiki
+
+//! Dynamic arrays, queues, associative arrays, and their built-in methods.
+//!
+//! These containers are represented as handles to storage on the heap, which
+//! the simulator manages through a set of intrinsics. The null handle refers to
+//! an empty container. Assigning a container copies its storage, such that no
+//! two variables share the same container.
+
+use crate::crate_prelude::*;
+use crate::ty::{PackedType, UnpackedDim, UnpackedType};
+
+/// A built-in method of a dynamic array, queue, or associative array.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContainerMethod {
+    /// `size()`, or `num()` for associative arrays.
+    Size,
+    /// `delete()` or `delete(index)`.
+    Delete,
+    /// `exists(index)` of associative arrays.
+    Exists,
+    /// `push_front(item)` of queues.
+    PushFront,
+    /// `push_back(item)` of queues.
+    PushBack,
+    /// `pop_front()` of queues.
+    PopFront,
+    /// `pop_back()` of queues.
+    PopBack,
+    /// `insert(index, item)` of queues.
+    Insert,
+    /// `first(index)` of associative arrays.
+    First,
+    /// `last(index)` of associative arrays.
+    Last,
+    /// `next(index)` of associative arrays.
+    Next,
+    /// `prev(index)` of associative arrays.
+    Prev,
+}
+
+impl ContainerMethod {
+    /// Find a method of a container by name.
+    pub fn lookup(container: UnpackedDim, name: &str) -> Option<Self> {
+        use UnpackedDim::*;
+        Some(match (container, name) {
+            (_, "size") | (Assoc(_), "num") => Self::Size,
+            (_, "delete") => Self::Delete,
+            (Assoc(_), "exists") => Self::Exists,
+            (Queue(_), "push_front") => Self::PushFront,
+            (Queue(_), "push_back") => Self::PushBack,
+            (Queue(_), "pop_front") => Self::PopFront,
+            (Queue(_), "pop_back") => Self::PopBack,
+            (Queue(_), "insert") => Self::Insert,
+            (Assoc(_), "first") => Self::First,
+            (Assoc(_), "last") => Self::Last,
+            (Assoc(_), "next") => Self::Next,
+            (Assoc(_), "prev") => Self::Prev,
+            _ => return None,
+        })
+    }
+
+    /// The name of the method's LLHD intrinsic, without the container prefix.
+    pub fn llhd_name(self) -> &'static str {
+        match self {
+            Self::Size => "size",
+            Self::Delete => "delete",
+            Self::Exists => "exists",
+            Self::PushFront => "push_front",
+            Self::PushBack => "push_back",
+            Self::PopFront => "pop_front",
+            Self::PopBack => "pop_back",
+            Self::Insert => "insert",
+            Self::First => "first",
+            Self::Last => "last",
+            Self::Next => "next",
+            Self::Prev => "prev",
+        }
+    }
+
+    /// The minimum and maximum number of arguments the method accepts.
+    pub fn arity(self, container: UnpackedDim) -> (usize, usize) {
+        match self {
+            Self::Size | Self::PopFront | Self::PopBack => (0, 0),
+            Self::Delete => match container {
+                UnpackedDim::Unsized => (0, 0),
+                _ => (0, 1),
+            },
+            Self::Exists
+            | Self::PushFront
+            | Self::PushBack
+            | Self::First
+            | Self::Last
+            | Self::Next
+            | Self::Prev => (1, 1),
+            Self::Insert => (2, 2),
+        }
+    }
+
+    /// Check whether the method modifies the container.
+    pub fn modifies(self) -> bool {
+        match self {
+            Self::Delete
+            | Self::PushFront
+            | Self::PushBack
+            | Self::PopFront
+            | Self::PopBack
+            | Self::Insert => true,
+            _ => false,
+        }
+    }
+
+    /// Check whether the method assigns to its argument, which is passed by
+    /// reference.
+    pub fn assigns_arg(self) -> bool {
+        match self {
+            Self::First | Self::Last | Self::Next | Self::Prev => true,
+            _ => false,
+        }
+    }
+}
+
+/// The prefix of the LLHD intrinsics operating on a container, such as
+/// `llhd.queue`.
+pub fn llhd_prefix(container: UnpackedDim) -> &'static str {
+    match container {
+        UnpackedDim::Unsized => "llhd.darr",
+        UnpackedDim::Queue(_) => "llhd.queue",
+        UnpackedDim::Assoc(_) => "llhd.assoc",
+        _ => panic!("`{}` is not a dynamically sized dimension", container),
+    }
+}
+
+/// Determine the type of the indices into a container.
+///
+/// This is the key type of associative arrays, and `int` for dynamic arrays
+/// and queues. Returns `None` for associative arrays with a wildcard index
+/// `[*]`, whose indices keep their own type.
+pub fn index_type<'a>(
+    cx: &impl Context<'a>,
+    container: UnpackedDim<'a>,
+) -> Option<&'a UnpackedType<'a>> {
+    match container {
+        UnpackedDim::Assoc(key) => key,
+        _ => Some(int_type(cx)),
+    }
+}
+
+/// Determine the type of the value returned by a container method.
+pub fn method_type<'a>(
+    cx: &impl Context<'a>,
+    method: ContainerMethod,
+    container_ty: &'a UnpackedType<'a>,
+) -> &'a UnpackedType<'a> {
+    match method {
+        ContainerMethod::Size
+        | ContainerMethod::Exists
+        | ContainerMethod::First
+        | ContainerMethod::Last
+        | ContainerMethod::Next
+        | ContainerMethod::Prev => int_type(cx),
+        ContainerMethod::PopFront | ContainerMethod::PopBack => container_ty.pop_dim(cx).unwrap(),
+        ContainerMethod::Delete
+        | ContainerMethod::PushFront
+        | ContainerMethod::PushBack
+        | ContainerMethod::Insert => UnpackedType::make_void(),
+    }
+}
+
+/// Determine the type of an argument of a container method.
+///
+/// Returns `None` if the argument keeps its own type.
+pub fn arg_type<'a>(
+    cx: &impl Context<'a>,
+    method: ContainerMethod,
+    container_ty: &'a UnpackedType<'a>,
+    index: usize,
+) -> Option<&'a UnpackedType<'a>> {
+    let container = container_ty.get_container()?;
+    match (method, index) {
+        (ContainerMethod::PushFront, 0)
+        | (ContainerMethod::PushBack, 0)
+        | (ContainerMethod::Insert, 1) => container_ty.pop_dim(cx),
+        (ContainerMethod::Insert, 0) => Some(int_type(cx)),
+        _ => index_type(cx, container),
+    }
+}
+
+/// Determine the built-in container method called by an expression.
+///
+/// Returns `None` if the expression is not a method call on a dynamic array,
+/// queue, or associative array.
+#[moore_derive::query]
+pub(crate) fn container_method<'a>(
+    cx: &impl Context<'a>,
+    Ref(expr): Ref<'a, hir::Expr<'a>>,
+    env: ParamEnv,
+) -> Result<Option<ContainerMethod>> {
+    let (object, name) = match expr.kind {
+        hir::ExprKind::MethodCall(object, name, _) => (object, name),
+        _ => return Ok(None),
+    };
+    let ty = cx.need_self_determined_type(object, env);
+    if ty.is_error() {
+        return Err(());
+    }
+    let container = match ty.get_container() {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    let method = match ContainerMethod::lookup(container, &name.value.as_str()) {
+        Some(x) => x,
+        None => {
+            cx.emit(
+                DiagBuilder2::error(format!("no method `{}` in `{}`", name, ty)).span(name.span),
+            );
+            return Err(());
+        }
+    };
+
+    check_method_args(cx, expr, ty, method.arity(container))?;
+    Ok(Some(method))
+}
+
+/// Check the arguments passed to a built-in method.
+///
+/// Built-in methods accept between `min` and `max` positional arguments, none
+/// of which may be omitted.
+pub(crate) fn check_method_args<'a>(
+    cx: &impl Context<'a>,
+    expr: &'a hir::Expr<'a>,
+    ty: &'a UnpackedType<'a>,
+    (min, max): (usize, usize),
+) -> Result<()> {
+    let (name, args) = match expr.kind {
+        hir::ExprKind::MethodCall(_, name, ref args) => (name, args),
+        _ => unreachable!(),
+    };
+    if let Some(arg) = args.iter().find(|arg| arg.name.is_some()) {
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "built-in method `{}` does not accept named arguments",
+                name
+            ))
+            .span(arg.span),
+        );
+        return Err(());
+    }
+    let given = args.len();
+    if given < min || given > max || args.iter().any(|arg| arg.expr.is_none()) {
+        let expected = match min == max {
+            true => format!("{}", min),
+            false => format!("{} to {}", min, max),
+        };
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "`{}` of `{}` expects {} argument(s)",
+                name, ty, expected
+            ))
+            .span(expr.span),
+        );
+        return Err(());
+    }
+    Ok(())
+}
+
+/// The `int` type, used for sizes and indices.
+fn int_type<'a>(cx: &impl Context<'a>) -> &'a UnpackedType<'a> {
+    PackedType::make(cx, ty::IntAtomType::Int).to_unpacked(cx)
+}
@@ -62,6 +62,8 @@ pub struct GlobalContext<'gcx> {
     interfaces: RefCell<HashMap<Name, NodeId>>,
     /// The global imports in the AST.
     imports: RefCell<Vec<NodeId>>,
+    /// The class declarations in the AST.
+    classes: RefCell<Vec<NodeId>>,
     /// A mapping from node ids to spans for diagnostics.
     node_id_to_span: RefCell<HashMap<NodeId, Span>>,
     /// The tables.
@@ -82,6 +84,7 @@ impl<'gcx> GlobalContext<'gcx> {
             packages: Default::default(),
             interfaces: Default::default(),
             imports: Default::default(),
+            classes: Default::default(),
             node_id_to_span: Default::default(),
             tables: Default::default(),
         }
@@ -97,14 +100,16 @@ impl<'gcx> GlobalContext<'gcx> {
         root.link(None, &mut index);
         debug!("Linked {} nodes", index);
 
+        // Register nodes with the AST map. This is a necessary hack until
+        // we have moved away from querying nodes merely by ID. Do this before
+        // materializing the scopes, such that the parents assigned when
+        // lowering the port lists are not overwritten.
+        self.register_ast(root);
+
         // Ensure there are no naming conflicts in the scopes.
         debug!("Materializing scopes");
         crate::resolver::materialize_scope(self, root);
 
-        // Register nodes with the AST map. This is a necessary hack until
-        // we have moved away from querying nodes merely by ID.
-        self.register_ast(root);
-
         // Resolve names for debugging purposes.
         debug!("Checking names");
         self.nameck(root);
@@ -113,7 +118,7 @@ impl<'gcx> GlobalContext<'gcx> {
         for file in &root.files {
             for item in &file.items {
                 match &item.data {
-                    ast::ItemData::ModuleDecl(ref n) => {
+                    ast::ItemData::ModuleDecl(ref n) | ast::ItemData::ProgramDecl(ref n) => {
                         let id = self.map_ast(AstNode::Module(n));
                         self.modules.borrow_mut().insert(n.name.value, id);
                     }
@@ -171,6 +176,11 @@ impl<'gcx> GlobalContext<'gcx> {
     pub fn imports(&self) -> impl Iterator<Item = NodeId> {
         self.imports.borrow().clone().into_iter()
     }
+
+    /// Get an iterator over all class declarations in the AST.
+    pub fn classes(&self) -> impl Iterator<Item = NodeId> {
+        self.classes.borrow().clone().into_iter()
+    }
 }
 
 impl DiagEmitter for GlobalContext<'_> {
@@ -570,6 +580,12 @@ pub trait BaseContext<'gcx>:
         self.tables().param_envs.borrow()[env.0 as usize]
     }
 
+    /// Get all parameter environments interned so far.
+    fn param_envs(&self) -> Vec<ParamEnv> {
+        let len = self.tables().param_envs.borrow().len();
+        (0..len).map(|i| ParamEnv(i as u32)).collect()
+    }
+
     /// Get the default parameter environment.
     ///
     /// This is useful for instantiations without any parameter assignment, e.g.
@@ -825,4 +841,10 @@ impl<'a, 'b> ast::Visitor<'a> for AstMapRegistrator<'a, 'b> {
         // Ensure the ports are added to the AST map. Pretty ugly, but necessary.
         self.cx.canonicalize_ports(node);
     }
+
+    fn post_visit_class_decl(&mut self, node: &'a ast::ClassDecl<'a>) {
+        // Keep track of all classes, such that virtual method calls can find
+        // the overrides in derived classes.
+        self.cx.classes.borrow_mut().push(node.id());
+    }
 }
@@ -0,0 +1,101 @@
+// Copyright (c) 2016-2020 Fabian Schuiki
+
+//! Built-in methods of enums.
+//!
+//! Enum values provide the methods `first`, `last`, `next`, `prev`, `num`, and
+//! `name` to iterate over the variants of their type and to obtain their names.
+
+use crate::crate_prelude::*;
+use crate::ty::{PackedType, UnpackedCore, UnpackedType};
+
+/// A built-in method of an enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EnumMethod {
+    /// `first()`, the first variant.
+    First,
+    /// `last()`, the last variant.
+    Last,
+    /// `next(N)`, the variant `N` positions after the value, wrapping around.
+    Next,
+    /// `prev(N)`, the variant `N` positions before the value, wrapping around.
+    Prev,
+    /// `num()`, the number of variants.
+    Num,
+    /// `name()`, the name of the variant as a string.
+    Name,
+}
+
+impl EnumMethod {
+    /// Find a method of an enum by name.
+    pub fn lookup(name: &str) -> Option<Self> {
+        Some(match name {
+            "first" => Self::First,
+            "last" => Self::Last,
+            "next" => Self::Next,
+            "prev" => Self::Prev,
+            "num" => Self::Num,
+            "name" => Self::Name,
+            _ => return None,
+        })
+    }
+
+    /// The minimum and maximum number of arguments the method accepts.
+    pub fn arity(self) -> (usize, usize) {
+        match self {
+            Self::Next | Self::Prev => (0, 1),
+            _ => (0, 0),
+        }
+    }
+}
+
+/// Determine the type of the value returned by an enum method.
+pub fn method_type<'a>(
+    cx: &impl Context<'a>,
+    method: EnumMethod,
+    enum_ty: &'a UnpackedType<'a>,
+) -> &'a UnpackedType<'a> {
+    match method {
+        EnumMethod::First | EnumMethod::Last | EnumMethod::Next | EnumMethod::Prev => enum_ty,
+        EnumMethod::Num => int_type(cx),
+        EnumMethod::Name => UnpackedType::make(cx, UnpackedCore::String),
+    }
+}
+
+/// Determine the built-in enum method called by an expression.
+///
+/// Returns `None` if the expression is not a method call on an enum value.
+#[moore_derive::query]
+pub(crate) fn enum_method<'a>(
+    cx: &impl Context<'a>,
+    Ref(expr): Ref<'a, hir::Expr<'a>>,
+    env: ParamEnv,
+) -> Result<Option<EnumMethod>> {
+    let (object, name) = match expr.kind {
+        hir::ExprKind::MethodCall(object, name, _) => (object, name),
+        _ => return Ok(None),
+    };
+    let ty = cx.need_self_determined_type(object, env);
+    if ty.is_error() {
+        return Err(());
+    }
+    if ty.get_enum().is_none() {
+        return Ok(None);
+    }
+    let method = match EnumMethod::lookup(&name.value.as_str()) {
+        Some(x) => x,
+        None => {
+            cx.emit(
+                DiagBuilder2::error(format!("no method `{}` in `{}`", name, ty)).span(name.span),
+            );
+            return Err(());
+        }
+    };
+
+    container::check_method_args(cx, expr, ty, method.arity())?;
+    Ok(Some(method))
+}
+
+/// The `int` type, used for counts and steps.
+fn int_type<'a>(cx: &impl Context<'a>) -> &'a UnpackedType<'a> {
+    PackedType::make(cx, ty::IntAtomType::Int).to_unpacked(cx)
+}
@@ -32,6 +32,7 @@ pub(crate) fn hir_of<'gcx>(cx: &impl Context<'gcx>, node_id: NodeId) -> Result<H
             ast::TypeOrExpr::Expr(expr) => cx.hir_of_expr(Ref(expr)).map(HirNode::Expr),
         },
         AstNode::Expr(expr) => cx.hir_of_expr(Ref(expr)).map(HirNode::Expr),
+        AstNode::Assertion(_, assertion) => lower_concurrent_assertion(cx, node_id, assertion),
         AstNode::InstTarget(ast) => {
             let mut named_params = vec![];
             let mut pos_params = vec![];
@@ -239,11 +240,12 @@ pub(crate) fn hir_of<'gcx>(cx: &impl Context<'gcx>, node_id: NodeId) -> Result<H
                     }
                 }
                 ast::IfStmt {
+                    up,
                     ref cond,
                     ref main_stmt,
                     ref else_stmt,
-                    ..
                 } => hir::StmtKind::If {
+                    up,
                     cond: cx.map_ast_with_parent(AstNode::Expr(cond), node_id),
                     main_stmt: cx.map_ast_with_parent(AstNode::Stmt(main_stmt), node_id),
                     else_stmt: else_stmt
@@ -304,11 +306,11 @@ pub(crate) fn hir_of<'gcx>(cx: &impl Context<'gcx>, node_id: NodeId) -> Result<H
                     },
                 },
                 ast::CaseStmt {
+                    up,
                     ref expr,
                     mode: ast::CaseMode::Normal,
                     ref items,
                     kind,
-                    ..
                 } => {
                     let expr = cx.map_ast_with_parent(AstNode::Expr(expr), node_id);
                     let mut ways = vec![];
@@ -335,21 +337,61 @@ pub(crate) fn hir_of<'gcx>(cx: &impl Context<'gcx>, node_id: NodeId) -> Result<H
                                     .collect(),
                                 cx.map_ast_with_parent(AstNode::Stmt(stmt), node_id),
                             )),
+                            ast::CaseItem::Pattern(ref item) => bug_span!(
+                                item.span(),
+                                cx,
+                                "pattern in case statement without `matches`"
+                            ),
                         }
                     }
                     hir::StmtKind::Case {
+                        up,
                         expr,
                         ways,
                         default,
                         kind,
                     }
                 }
-                ast::AssertionStmt { .. } => {
-                    cx.emit(
-                        DiagBuilder2::warning("unsupported: immediate assertion; ignored")
-                            .span(stmt.human_span()),
-                    );
-                    hir::StmtKind::Null
+                ast::CaseStmt {
+                    up,
+                    ref expr,
+                    mode: ast::CaseMode::Pattern,
+                    ref items,
+                    ..
+                } => lower_case_matches(cx, node_id, up, expr, items),
+                ast::AssertionStmt(ref assertion) => lower_assertion_stmt(cx, node_id, assertion)?,
+                ast::ParallelBlock(ref stmts, join) => {
+                    let mut decls = vec![];
+                    let mut branches = vec![];
+                    let mut next_rib = node_id;
+                    for stmt in stmts {
+                        let id = cx.map_ast_with_parent(AstNode::Stmt(stmt), next_rib);
+                        if let ast::VarDeclStmt(_) = stmt.kind {
+                            decls.push(id);
+                            next_rib = id;
+                        } else {
+                            branches.push(id);
+                        }
+                    }
+                    hir::StmtKind::Fork {
+                        decls,
+                        branches,
+                        join,
+                    }
+                }
+                ast::WaitForkStmt => hir::StmtKind::WaitFork,
+                ast::DisableForkStmt => hir::StmtKind::DisableFork,
+                ast::ForeachStmt(ref array, ref indices, ref body) => {
+                    lower_foreach(cx, node_id, stmt.span, array, indices, body)
+                }
+                ast::BreakStmt => hir::StmtKind::Break,
+                ast::ContinueStmt => hir::StmtKind::Continue,
+                ast::ReturnStmt(ref expr) => hir::StmtKind::Return(
+                    expr.as_ref()
+                        .map(|expr| cx.map_ast_with_parent(AstNode::Expr(expr), node_id)),
+                ),
+                ast::DisableStmt(name) => {
+                    hir::StmtKind::Disable(lower_disable_target(cx, node_id, name, stmt.span)?)
                 }
                 _ => {
                     error!("{:#?}", stmt);
@@ -421,6 +463,46 @@ pub(crate) fn hir_of<'gcx>(cx: &impl Context<'gcx>, node_id: NodeId) -> Result<H
             };
             Ok(HirNode::Gen(cx.arena().alloc_hir(hir)))
         }
+        AstNode::GenCase(gen) => {
+            let expr = cx.map_ast_with_parent(AstNode::Expr(&gen.expr), node_id);
+            let mut items = vec![];
+            let mut default: Option<(Span, hir::ModuleBlock)> = None;
+            for item in &gen.items {
+                match item {
+                    ast::GenerateCaseItem::Expr(ref exprs, ref block) => {
+                        let labels = exprs
+                            .iter()
+                            .map(|expr| cx.map_ast_with_parent(AstNode::Expr(expr), node_id))
+                            .collect();
+                        let body = lower_module_block(cx, node_id, &block.items, false, false)?;
+                        items.push((labels, body));
+                    }
+                    ast::GenerateCaseItem::Default(ref block) => {
+                        if let Some((span, _)) = default {
+                            cx.emit(
+                                DiagBuilder2::error("multiple default items in case-generate")
+                                    .span(block.span())
+                                    .add_note("Previous default item was here:")
+                                    .span(span),
+                            );
+                            return Err(());
+                        }
+                        let body = lower_module_block(cx, node_id, &block.items, false, false)?;
+                        default = Some((block.span(), body));
+                    }
+                }
+            }
+            let hir = hir::Gen {
+                id: node_id,
+                span: gen.span(),
+                kind: hir::GenKind::Case {
+                    expr,
+                    items,
+                    default: default.map(|(_, body)| body),
+                },
+            };
+            Ok(HirNode::Gen(cx.arena().alloc_hir(hir)))
+        }
         AstNode::GenvarDecl(decl) => {
             let hir = hir::GenvarDecl {
                 id: node_id,
@@ -484,19 +566,83 @@ pub(crate) fn hir_of<'gcx>(cx: &impl Context<'gcx>, node_id: NodeId) -> Result<H
             Ok(HirNode::EnumVariant(cx.arena().alloc_hir(hir)))
         }
         AstNode::Import(import) => unreachable!("import should never be lowered: {:#?}", import),
-        AstNode::SubroutineDecl(decl) => {
-            let hir = hir::Subroutine {
+        AstNode::SubroutineDecl(decl) => lower_subroutine(cx, node_id, decl),
+        AstNode::Class(decl) => lower_class(cx, node_id, decl),
+        AstNode::LetDecl(decl) => {
+            let mut next_rib = node_id;
+            let mut args = vec![];
+            for arg in &decl.args {
+                next_rib = cx.map_ast_with_parent(AstNode::LetArg(arg), next_rib);
+                args.push(next_rib);
+            }
+            let hir = hir::Let {
                 id: node_id,
-                name: decl.prototype.name,
+                name: decl.name,
                 span: decl.span,
-                kind: decl.prototype.kind,
-                retty: decl
-                    .prototype
-                    .retty
+                args,
+                expr: cx.map_ast_with_parent(AstNode::Expr(&decl.expr), next_rib),
+            };
+            Ok(HirNode::Let(cx.arena().alloc_hir(hir)))
+        }
+        AstNode::PatternVar(var) => {
+            // Pattern variables are allocated when lowering the pattern that
+            // binds them, which is part of the `matches` expression or the
+            // `case` statement.
+            let owner = var.get_parent().unwrap();
+            let owner = match owner.as_all() {
+                ast::AllNode::CasePattern(item) => item.get_parent().unwrap(),
+                _ => owner,
+            };
+            cx.hir_of(owner.id())?;
+            match cx.get_interned_hir(node_id) {
+                Some(hir) => Ok(hir),
+                None => bug_span!(
+                    var.span(),
+                    cx,
+                    "pattern variable not lowered with its pattern"
+                ),
+            }
+        }
+        AstNode::LetArg(arg) => {
+            let hir = hir::LetArg {
+                id: node_id,
+                name: arg.name,
+                span: arg.span,
+                default: arg
+                    .default
                     .as_ref()
-                    .map(|ty| cx.map_ast_with_parent(AstNode::Type(ty), node_id)),
+                    .map(|expr| cx.map_ast_with_parent(AstNode::Expr(expr), node_id)),
+            };
+            Ok(HirNode::LetArg(cx.arena().alloc_hir(hir)))
+        }
+        AstNode::SubroutinePort(port, ty) => {
+            let name = port.name.as_ref().expect("subroutine port without name");
+            let hir = hir::VarDecl {
+                id: node_id,
+                name: name.name,
+                span: port.span,
+                ty: ty,
+                init: name
+                    .expr
+                    .as_ref()
+                    .map(|expr| cx.map_ast_with_parent(AstNode::Expr(expr), node_id)),
+                kind: ast::VarKind::Var,
+            };
+            Ok(HirNode::VarDecl(cx.arena().alloc_hir(hir)))
+        }
+        AstNode::SubroutinePortDecl(name, decl, ty) => {
+            let hir = hir::VarDecl {
+                id: node_id,
+                name: Spanned::new(name.name, name.name_span),
+                span: Span::union(name.span, decl.span),
+                ty: ty,
+                init: name
+                    .init
+                    .as_ref()
+                    .map(|expr| cx.map_ast_with_parent(AstNode::Expr(expr), node_id)),
+                kind: ast::VarKind::Var,
             };
-            Ok(HirNode::Subroutine(cx.arena().alloc_hir(hir)))
+            Ok(HirNode::VarDecl(cx.arena().alloc_hir(hir)))
         }
         _ => {
             error!("{:#?}", ast);
@@ -505,6 +651,176 @@ pub(crate) fn hir_of<'gcx>(cx: &impl Context<'gcx>, node_id: NodeId) -> Result<H
     }
 }
 
+/// Lower a function or task to HIR.
+fn lower_subroutine<'gcx>(
+    cx: &impl Context<'gcx>,
+    node_id: NodeId,
+    decl: &'gcx ast::SubroutineDecl<'gcx>,
+) -> Result<HirNode<'gcx>> {
+    let mut next_rib = node_id;
+    let retty = decl.prototype.retty.as_ref().map(|ty| {
+        next_rib = cx.map_ast_with_parent(AstNode::Type(ty), next_rib);
+        next_rib
+    });
+
+    // Lower the ports declared in the prototype. Ports without an explicit
+    // direction inherit the direction of the previous port, or default to
+    // `input`.
+    let mut args = vec![];
+    let mut dir = ast::SubroutinePortDir::Input;
+    for port in &decl.prototype.args {
+        dir = port.dir.unwrap_or(dir);
+        let name = match port.name {
+            Some(ref name) => name,
+            None => {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "port of {} `{}` has no name",
+                        match decl.prototype.kind {
+                            ast::SubroutineKind::Func => "function",
+                            ast::SubroutineKind::Task => "task",
+                        },
+                        decl.prototype.name
+                    ))
+                    .span(port.span),
+                );
+                return Err(());
+            }
+        };
+        let ty = cx.map_ast_with_parent(AstNode::Type(&port.ty), next_rib);
+        next_rib = cx.map_ast_with_parent(AstNode::SubroutinePort(port, ty), ty);
+        args.push(hir::SubroutineArg {
+            id: next_rib,
+            name: name.name,
+            dir,
+        });
+    }
+
+    // Lower the port declarations and statements in the body.
+    let mut stmts = vec![];
+    for item in &decl.items {
+        match item {
+            ast::SubroutineItem::PortDecl(ref pd) => {
+                if !decl.prototype.args.is_empty() {
+                    cx.emit(
+                        DiagBuilder2::error(
+                            "port declarations not allowed if ports are declared in the header",
+                        )
+                        .span(pd.span),
+                    );
+                    return Err(());
+                }
+                let ty = cx.map_ast_with_parent(AstNode::Type(&pd.ty), next_rib);
+                next_rib = ty;
+                for name in &pd.names {
+                    next_rib =
+                        cx.map_ast_with_parent(AstNode::SubroutinePortDecl(name, pd, ty), next_rib);
+                    args.push(hir::SubroutineArg {
+                        id: next_rib,
+                        name: Spanned::new(name.name, name.name_span),
+                        dir: pd.dir,
+                    });
+                }
+            }
+            ast::SubroutineItem::Stmt(ref stmt) => {
+                next_rib = cx.map_ast_with_parent(AstNode::Stmt(stmt), next_rib);
+                stmts.push(next_rib);
+            }
+        }
+    }
+
+    let hir = hir::Subroutine {
+        id: node_id,
+        name: decl.prototype.name,
+        span: decl.span,
+        kind: decl.prototype.kind,
+        retty,
+        args,
+        stmts,
+    };
+    Ok(HirNode::Subroutine(cx.arena().alloc_hir(hir)))
+}
+
+/// Lower a class declaration to HIR.
+fn lower_class<'gcx>(
+    cx: &impl Context<'gcx>,
+    node_id: NodeId,
+    decl: &'gcx ast::ClassDecl<'gcx>,
+) -> Result<HirNode<'gcx>> {
+    let mut next_rib = node_id;
+    let mut params = vec![];
+    for param in &decl.params {
+        next_rib = alloc_param_decl(cx, param, next_rib, &mut params);
+    }
+
+    // Lower the base class type. Arguments to the base class constructor are
+    // passed through an explicit `super.new(...)` call instead.
+    let extends = match decl.extends {
+        Some((ref ty, ref args)) => {
+            if !args.is_empty() {
+                cx.emit(
+                    DiagBuilder2::error("unsupported: arguments in extends clause")
+                        .span(ty.span())
+                        .add_note("Call `super.new(...)` in the constructor instead"),
+                );
+                return Err(());
+            }
+            next_rib = cx.map_ast_with_parent(AstNode::Type(ty), next_rib);
+            Some(next_rib)
+        }
+        None => None,
+    };
+
+    // Lower the class items.
+    let mut props = vec![];
+    let mut methods = vec![];
+    for item in &decl.items {
+        let has_qualifier = |q| item.qualifiers.iter().any(|&(x, _)| x == q);
+        if has_qualifier(ast::ClassItemQualifier::Static) {
+            cx.emit(DiagBuilder2::error("unsupported: static class members").span(item.span));
+            return Err(());
+        }
+        match item.data {
+            ast::ClassItemData::Property(ref decl) => {
+                next_rib = alloc_var_decl(cx, decl, next_rib, &mut props);
+            }
+            ast::ClassItemData::SubroutineDecl(ref decl) => {
+                next_rib = cx.map_ast_with_parent(AstNode::SubroutineDecl(decl), next_rib);
+                methods.push((next_rib, has_qualifier(ast::ClassItemQualifier::Virtual)));
+            }
+            ast::ClassItemData::Typedef(ref def) => {
+                next_rib = cx.map_ast_with_parent(AstNode::Typedef(def), next_rib);
+            }
+            ast::ClassItemData::ParamDecl(ref param) => {
+                next_rib = alloc_param_decl(cx, param, next_rib, &mut params);
+            }
+            ast::ClassItemData::Constraint(ref constraint) => {
+                cx.emit(
+                    DiagBuilder2::warning("unsupported: constraint; ignored").span(constraint.span),
+                );
+            }
+            ast::ClassItemData::Null => (),
+            _ => {
+                cx.emit(DiagBuilder2::error("unsupported: class item").span(item.span));
+                return Err(());
+            }
+        }
+    }
+
+    let hir = hir::Class {
+        id: node_id,
+        name: decl.name,
+        span: decl.span,
+        ast: decl,
+        params,
+        extends,
+        props,
+        methods,
+        last_rib: next_rib,
+    };
+    Ok(HirNode::Class(cx.arena().alloc_hir(hir)))
+}
+
 /// Lower a module to HIR.
 #[moore_derive::query]
 pub(crate) fn hir_of_module<'a>(
@@ -595,13 +911,12 @@ fn lower_module_block<'gcx>(
     let mut gens = Vec::new();
     let mut params = Vec::new();
     let mut assigns = Vec::new();
+    let mut asserts = Vec::new();
     for item in items {
         match item.data {
             ast::ItemData::Dummy => (),
-            ast::ItemData::ModuleDecl(ref decl) => {
-                let id = cx.map_ast_with_parent(AstNode::Module(decl), next_rib);
-                next_rib = id;
-                procs.push(id);
+            ast::ItemData::ModuleDecl(ref decl) | ast::ItemData::ProgramDecl(ref decl) => {
+                next_rib = cx.map_ast_with_parent(AstNode::Module(decl), next_rib);
             }
             ast::ItemData::PackageDecl(ref decl) => {
                 let id = cx.map_ast_with_parent(AstNode::Package(decl), next_rib);
@@ -609,21 +924,7 @@ fn lower_module_block<'gcx>(
                 procs.push(id);
             }
             ast::ItemData::InterfaceDecl(ref decl) => {
-                // let id = cx.map_ast_with_parent(AstNode::Interface(decl), next_rib);
-                // next_rib = id;
-                // procs.push(id);
-                cx.emit(
-                    DiagBuilder2::warning("unsupported: interface declaration; ignored")
-                        .span(decl.span),
-                );
-            }
-            ast::ItemData::ProgramDecl(ref _decl) => {
-                // let id = cx.map_ast_with_parent(AstNode::Program(decl), next_rib);
-                // next_rib = id;
-                // procs.push(id);
-                cx.emit(DiagBuilder2::warning(
-                    "unsupported: program declaration; ignored",
-                ));
+                next_rib = cx.map_ast_with_parent(AstNode::Interface(decl), next_rib);
             }
             ast::ItemData::Inst(ref inst) => {
                 let target_id = cx.map_ast_with_parent(AstNode::InstTarget(inst), next_rib);
@@ -703,19 +1004,45 @@ fn lower_module_block<'gcx>(
                 );
             }
             ast::ItemData::ClassDecl(ref decl) => {
-                cx.emit(
-                    DiagBuilder2::warning("unsupported: class declaration; ignored")
-                        .span(decl.span),
-                );
+                next_rib = cx.map_ast_with_parent(AstNode::Class(decl), next_rib);
             }
             ast::ItemData::SubroutineDecl(ref decl) => {
                 let id = cx.map_ast_with_parent(AstNode::SubroutineDecl(decl), next_rib);
                 next_rib = id;
             }
-            ast::ItemData::Assertion(ref assert) => {
+            ast::ItemData::LetDecl(ref decl) => {
+                let id = cx.map_ast_with_parent(AstNode::LetDecl(decl), next_rib);
+                next_rib = id;
+            }
+            ast::ItemData::ExportDecl(ref decl) => {
+                cx.emit(
+                    DiagBuilder2::error("export declaration outside of a package")
+                        .span(decl.span)
+                        .add_note("Export declarations can only appear in a package"),
+                );
+            }
+            ast::ItemData::Assertion(ref assert) => match assert.data {
+                ast::AssertionData::Concurrent(ast::ConcurrentAssertion::AssertProperty(..))
+                | ast::AssertionData::Concurrent(ast::ConcurrentAssertion::AssumeProperty(..)) => {
+                    let id = cx.map_ast_with_parent(AstNode::Assertion(item, assert), next_rib);
+                    next_rib = id;
+                    asserts.push(id);
+                }
+                _ => {
+                    cx.emit(
+                        DiagBuilder2::warning("unsupported: assertion; ignored").span(assert.span),
+                    );
+                }
+            },
+
+            ast::ItemData::Timescale(..) => {
                 cx.emit(
-                    DiagBuilder2::warning("unsupported: concurrent assertion; ignored")
-                        .span(assert.span),
+                    DiagBuilder2::warning("unsupported: `timescale directive in module; ignored")
+                        .span(item.span)
+                        .add_note(
+                            "Place the directive before the module, or use `timeunit` and \
+                             `timeprecision` declarations",
+                        ),
                 );
             }
 
@@ -732,6 +1059,7 @@ fn lower_module_block<'gcx>(
         gens,
         params,
         assigns,
+        asserts,
         last_rib: next_rib,
     })
 }
@@ -754,6 +1082,9 @@ fn lower_type<'gcx>(
         ast::LongIntType => hir::TypeKind::Builtin(hir::BuiltinType::LongInt),
         ast::StringType => hir::TypeKind::Builtin(hir::BuiltinType::String),
         ast::TimeType => hir::TypeKind::Builtin(hir::BuiltinType::Time),
+        ast::ShortRealType => hir::TypeKind::Builtin(hir::BuiltinType::ShortReal),
+        ast::RealType => hir::TypeKind::Builtin(hir::BuiltinType::Real),
+        ast::RealtimeType => hir::TypeKind::Builtin(hir::BuiltinType::RealTime),
         ast::NamedType(name) => hir::TypeKind::Named(name),
         ast::StructType(ref def) => {
             let mut fields = vec![];
@@ -826,16 +1157,17 @@ fn lower_type<'gcx>(
                 }
             }
         }
+        // The parameters of a class specialization are resolved by the type
+        // checker directly on the AST; only the specialized type is kept.
+        ast::SpecializedType(ref ty, _) => {
+            hir::TypeKind::RefType(cx.map_ast_with_parent(AstNode::Type(ty), node_id))
+        }
         ast::ChandleType
         | ast::VirtIntfType(..)
         | ast::EventType
         | ast::MailboxType
         | ast::ImplicitSignedType
         | ast::ImplicitUnsignedType
-        | ast::ShortRealType
-        | ast::RealType
-        | ast::RealtimeType
-        | ast::SpecializedType(..)
         | ast::ForwardType { .. }
         | ast::ScopedType { .. } => {
             error!("{:#?}", ty);
@@ -895,7 +1227,7 @@ fn lower_expr_inner<'gcx>(
 ) -> Result<hir::ExprKind<'gcx>> {
     use crate::syntax::token::{Lit, Op};
     Ok(match expr.data {
-        ast::LiteralExpr(Lit::Number(v, None)) => match v.as_str().parse() {
+        ast::LiteralExpr(Lit::Number(v, None, None)) => match v.as_str().parse() {
             Ok(v) => hir::ExprKind::IntConst {
                 width: 32,
                 value: v,
@@ -912,6 +1244,9 @@ fn lower_expr_inner<'gcx>(
                 return Err(());
             }
         },
+        ast::LiteralExpr(Lit::Number(int, frac, exp)) => {
+            hir::ExprKind::RealConst(parse_real_number(cx, expr.span, int, frac, exp)?)
+        }
         ast::LiteralExpr(Lit::UnbasedUnsized(c)) => hir::ExprKind::UnsizedConst(c),
 
         ast::LiteralExpr(Lit::BasedInteger(maybe_size, signed, base, value)) => {
@@ -1017,27 +1352,17 @@ fn lower_expr_inner<'gcx>(
         }
 
         ast::LiteralExpr(Lit::Time(int, frac, unit)) => {
-            use syntax::token::TimeUnit;
-            let mut value = parse_fixed_point_number(cx, expr.span, int, frac)?;
-            let magnitude = match unit {
-                TimeUnit::Second => 0,
-                TimeUnit::MilliSecond => 1,
-                TimeUnit::MicroSecond => 2,
-                TimeUnit::NanoSecond => 3,
-                TimeUnit::PicoSecond => 4,
-                TimeUnit::FemtoSecond => 5,
-            };
-            for _ in 0..magnitude {
-                value = value / num::BigInt::from(1000);
-            }
-            hir::ExprKind::TimeConst(value)
+            hir::ExprKind::TimeConst(time_literal_value(cx, expr.span, int, frac, unit)?)
         }
 
         ast::LiteralExpr(Lit::Str(value)) => {
             hir::ExprKind::StringConst(Spanned::new(value, expr.span))
         }
 
-        ast::IdentExpr(ident) => hir::ExprKind::Ident(ident),
+        ast::IdentExpr(ident) => match lower_task_enable(cx, node_id, ident)? {
+            Some(target) => hir::ExprKind::FunctionCall(target, vec![]),
+            None => hir::ExprKind::Ident(ident),
+        },
         ast::UnaryExpr {
             op,
             expr: ref arg,
@@ -1125,6 +1450,15 @@ fn lower_expr_inner<'gcx>(
             cx.map_ast_with_parent(AstNode::Expr(lhs), node_id),
             cx.map_ast_with_parent(AstNode::Expr(rhs), node_id),
         ),
+        // Member accesses enabled as a statement, as in `obj.foo;`, are method
+        // calls without arguments.
+        ast::MemberExpr { ref expr, name } if is_expr_stmt(cx, node_id) => {
+            hir::ExprKind::MethodCall(
+                cx.map_ast_with_parent(AstNode::Expr(expr), node_id),
+                name,
+                vec![],
+            )
+        }
         ast::MemberExpr { ref expr, name } => {
             hir::ExprKind::Field(cx.map_ast_with_parent(AstNode::Expr(expr), node_id), name)
         }
@@ -1148,86 +1482,27 @@ fn lower_expr_inner<'gcx>(
             // };
             hir::ExprKind::Index(indexee, mode)
         }
+        ast::SysIdentExpr(ident) => lower_system_call(cx, node_id, expr, ident, &[])?,
         ast::CallExpr(ref callee, ref args) => match callee.data {
-            ast::SysIdentExpr(ident) => {
-                let map_unary = || match args.as_slice() {
-                    [ast::CallArg {
-                        expr: Some(ref arg),
-                        ..
-                    }] => Ok(arg),
-                    _ => {
-                        cx.emit(
-                            DiagBuilder2::error(format!("`{}` takes one argument", ident))
-                                .span(expr.human_span()),
-                        );
-                        Err(())
-                    }
-                };
-                let map_unary_id =
-                    || Ok(cx.map_ast_with_parent(AstNode::Expr(map_unary()?), node_id));
-                let map_array_dim = |func| match args.as_slice() {
-                    [ast::CallArg {
-                        expr: Some(ref arg),
-                        ..
-                    }] => Ok(hir::BuiltinCall::ArrayDim(func, arg, None)),
-                    [ast::CallArg {
-                        expr: Some(ref arg),
-                        ..
-                    }, ast::CallArg {
-                        expr: Some(ref dim),
-                        ..
-                    }] => Ok(hir::BuiltinCall::ArrayDim(func, arg, Some(dim))),
-                    _ => {
-                        cx.emit(
-                            DiagBuilder2::error(format!("`{}` takes one or two arguments", ident))
-                                .span(expr.human_span()),
-                        );
-                        Err(())
-                    }
-                };
-                hir::ExprKind::Builtin(match &*ident.value.as_str() {
-                    "clog2" => hir::BuiltinCall::Clog2(map_unary_id()?),
-                    "signed" => hir::BuiltinCall::Signed(map_unary_id()?),
-                    "unsigned" => hir::BuiltinCall::Unsigned(map_unary_id()?),
-                    "countones" => hir::BuiltinCall::CountOnes(map_unary()?),
-                    "onehot" => hir::BuiltinCall::OneHot(map_unary()?),
-                    "onehot0" => hir::BuiltinCall::OneHot0(map_unary()?),
-                    "isunknown" => hir::BuiltinCall::IsUnknown(map_unary()?),
-                    "left" => map_array_dim(hir::ArrayDim::Left)?,
-                    "right" => map_array_dim(hir::ArrayDim::Right)?,
-                    "low" => map_array_dim(hir::ArrayDim::Low)?,
-                    "high" => map_array_dim(hir::ArrayDim::High)?,
-                    "increment" => map_array_dim(hir::ArrayDim::Increment)?,
-                    "size" => map_array_dim(hir::ArrayDim::Size)?,
-                    "display" | "info" | "warning" | "error" | "fatal" => {
-                        cx.emit(
-                            DiagBuilder2::warning(format!(
-                                "unsupported: system task `${}`; ignored",
-                                ident
-                            ))
-                            .span(expr.human_span()),
-                        );
-                        hir::BuiltinCall::Unsupported
-                    }
-                    _ => {
-                        cx.emit(
-                            DiagBuilder2::error(format!("unknown system task `${}`", ident))
-                                .span(expr.human_span()),
-                        );
-                        hir::BuiltinCall::Unsupported
-                    }
-                })
-            }
+            ast::SysIdentExpr(ident) => lower_system_call(cx, node_id, expr, ident, args)?,
             ast::IdentExpr(name) => {
                 let target =
                     cx.resolve_upwards_or_error(name, cx.parent_node_id(node_id).unwrap())?;
-                hir::ExprKind::FunctionCall(
-                    target,
-                    args.iter()
-                        .map(|arg| lower_call_arg(cx, arg, node_id))
-                        .collect(),
-                )
+                lower_call(cx, node_id, name, target, args)?
+            }
+            ast::ScopeExpr(ref scope, name) if scope_call_pkg(scope).is_some() => {
+                let pkg = scope_call_pkg(scope).unwrap();
+                let pkg = cx.resolve_upwards_or_error(pkg, cx.parent_node_id(node_id).unwrap())?;
+                let target = cx.resolve_downwards_or_error(name, pkg)?;
+                lower_call(cx, node_id, name, target, args)?
             }
+            ast::MemberExpr { ref expr, name } => hir::ExprKind::MethodCall(
+                cx.map_ast_with_parent(AstNode::Expr(expr), node_id),
+                name,
+                args.iter()
+                    .map(|arg| lower_call_arg(cx, arg, node_id))
+                    .collect(),
+            ),
             _ => {
                 error!("{:#?}", callee);
                 cx.emit(
@@ -1360,6 +1635,35 @@ fn lower_expr_inner<'gcx>(
                 .map(|expr| cx.map_ast_with_parent(AstNode::Expr(expr), node_id))
                 .collect(),
         ),
+        ast::StreamConcatExpr {
+            dir,
+            ref slice,
+            ref exprs,
+        } => {
+            let slice = slice.as_ref().map(|slice| match *slice {
+                ast::StreamConcatSlice::Expr(ref expr) => {
+                    hir::StreamSlice::Expr(cx.map_ast_with_parent(AstNode::Expr(expr), node_id))
+                }
+                ast::StreamConcatSlice::Type(ref ty) => {
+                    hir::StreamSlice::Type(cx.map_ast_with_parent(AstNode::Type(ty), node_id))
+                }
+            });
+            let mut mapped = vec![];
+            for expr in exprs {
+                if let Some(ref range) = expr.range {
+                    cx.emit(
+                        DiagBuilder2::error(
+                            "array slices with `with [...]` are not supported in streaming \
+                             concatenations",
+                        )
+                        .span(range.span),
+                    );
+                    return Err(());
+                }
+                mapped.push(cx.map_ast_with_parent(AstNode::Expr(&expr.expr), node_id));
+            }
+            hir::ExprKind::StreamConcat(dir, slice, mapped)
+        }
         ast::CastExpr(ref ty, ref expr) => {
             // Catch the corner case where a size cast looks like a type cast.
             if let ast::NamedType(n) = ty.kind.data {
@@ -1425,6 +1729,36 @@ fn lower_expr_inner<'gcx>(
                 .collect(),
         ),
         ast::BitsExpr { ref arg, .. } => hir::ExprKind::Builtin(hir::BuiltinCall::Bits(arg)),
+        ast::ConstructorCallExpr(ref args) => hir::ExprKind::New(
+            args.iter()
+                .map(|arg| lower_call_arg(cx, arg, node_id))
+                .collect(),
+        ),
+        ast::ClassNewExpr(None) => hir::ExprKind::New(vec![]),
+        ast::ClassNewExpr(Some(_)) => {
+            cx.emit(
+                DiagBuilder2::error("unsupported: copying an object with `new`").span(expr.span()),
+            );
+            return Err(());
+        }
+        ast::ThisExpr => hir::ExprKind::This,
+        ast::SuperExpr => hir::ExprKind::Super,
+        ast::NullExpr => hir::ExprKind::Null,
+        ast::ArrayNewExpr(ref size, ref init) => hir::ExprKind::ArrayNew(
+            cx.map_ast_with_parent(AstNode::Expr(size), node_id),
+            init.as_ref()
+                .map(|init| cx.map_ast_with_parent(AstNode::Expr(init), node_id)),
+        ),
+        ast::EmptyQueueExpr => hir::ExprKind::EmptyQueue,
+        ast::TaggedExpr(member, ref expr) => hir::ExprKind::Tagged(
+            member,
+            expr.as_ref()
+                .map(|expr| cx.map_ast_with_parent(AstNode::Expr(expr), node_id)),
+        ),
+        ast::MatchesExpr(ref expr, ref pattern) => hir::ExprKind::Matches(
+            cx.map_ast_with_parent(AstNode::Expr(expr), node_id),
+            lower_match_pattern(cx, pattern, node_id),
+        ),
         ast::AssignExpr {
             op,
             ref lhs,
@@ -1446,51 +1780,444 @@ fn lower_expr_inner<'gcx>(
     })
 }
 
-/// Parse a fixed point number into a [`BigRational`].
-///
-/// The fractional part of the number is optional, such that this function may
-/// also be used to parse integers into a ratio.
-fn parse_fixed_point_number<'gcx>(
+/// Lower a call to a system task or function, such as `$clog2(x)`.
+fn lower_system_call<'gcx>(
     cx: &impl Context<'gcx>,
-    span: Span,
-    int: Name,
-    frac: Option<Name>,
-) -> Result<num::BigRational> {
-    let mut num_digits = int.to_string();
-    let mut denom_digits = String::from("1");
-    if let Some(frac) = frac {
-        let s = frac.to_string();
-        num_digits.push_str(&s);
-        denom_digits.extend(s.chars().map(|_| '0'));
-    }
-    match (num_digits.parse(), denom_digits.parse()) {
-        (Ok(a), Ok(b)) => Ok((a, b).into()),
-        (Err(e), _) | (_, Err(e)) => {
-            let value = match frac {
-                Some(frac) => format!("{}.{}", int, frac),
-                None => format!("{}", int),
-            };
+    node_id: NodeId,
+    expr: &'gcx ast::Expr<'gcx>,
+    ident: Spanned<Name>,
+    args: &'gcx [ast::CallArg<'gcx>],
+) -> Result<hir::ExprKind<'gcx>> {
+    let map_unary = || match args {
+        [ast::CallArg {
+            expr: Some(ref arg),
+            ..
+        }] => Ok(arg),
+        _ => {
             cx.emit(
-                DiagBuilder2::error(format!("`{}` is not a number literal", value))
-                    .span(span)
-                    .add_note(format!("{}", e)),
+                DiagBuilder2::error(format!("`{}` takes one argument", ident))
+                    .span(expr.human_span()),
             );
             Err(())
         }
-    }
-}
-
-fn lower_event_expr<'gcx>(
-    cx: &impl Context<'gcx>,
-    expr: &'gcx ast::EventExpr<'gcx>,
-    parent_id: NodeId,
-    into: &mut Vec<hir::Event>,
-    cond_stack: &mut Vec<NodeId>,
-) -> Result<()> {
-    match *expr {
-        ast::EventExpr::Edge {
-            span,
-            edge,
+    };
+    let map_unary_id = || Ok(cx.map_ast_with_parent(AstNode::Expr(map_unary()?), node_id));
+    let map_array_dim = |func| match args {
+        [ast::CallArg {
+            expr: Some(ref arg),
+            ..
+        }] => Ok(hir::BuiltinCall::ArrayDim(func, arg, None)),
+        [ast::CallArg {
+            expr: Some(ref arg),
+            ..
+        }, ast::CallArg {
+            expr: Some(ref dim),
+            ..
+        }] => Ok(hir::BuiltinCall::ArrayDim(func, arg, Some(dim))),
+        _ => {
+            cx.emit(
+                DiagBuilder2::error(format!("`{}` takes one or two arguments", ident))
+                    .span(expr.human_span()),
+            );
+            Err(())
+        }
+    };
+    Ok(hir::ExprKind::Builtin(match &*ident.value.as_str() {
+        "clog2" => hir::BuiltinCall::Clog2(map_unary_id()?),
+        "signed" => hir::BuiltinCall::Signed(map_unary_id()?),
+        "unsigned" => hir::BuiltinCall::Unsigned(map_unary_id()?),
+        "countones" => hir::BuiltinCall::CountOnes(map_unary()?),
+        "onehot" => hir::BuiltinCall::OneHot(map_unary()?),
+        "onehot0" => hir::BuiltinCall::OneHot0(map_unary()?),
+        "isunknown" => hir::BuiltinCall::IsUnknown(map_unary()?),
+        "left" => map_array_dim(hir::ArrayDim::Left)?,
+        "right" => map_array_dim(hir::ArrayDim::Right)?,
+        "low" => map_array_dim(hir::ArrayDim::Low)?,
+        "high" => map_array_dim(hir::ArrayDim::High)?,
+        "increment" => map_array_dim(hir::ArrayDim::Increment)?,
+        "size" => map_array_dim(hir::ArrayDim::Size)?,
+        "display" => lower_display_call(cx, node_id, hir::DisplayTask::Display, args)?,
+        "write" => lower_display_call(cx, node_id, hir::DisplayTask::Write, args)?,
+        "info" => lower_display_call(cx, node_id, hir::DisplayTask::Info, args)?,
+        "warning" => lower_display_call(cx, node_id, hir::DisplayTask::Warning, args)?,
+        "error" => lower_display_call(cx, node_id, hir::DisplayTask::Error, args)?,
+        "fatal" => lower_display_call(cx, node_id, hir::DisplayTask::Fatal, args)?,
+        "sformatf" => {
+            if !args.first().map(is_string_literal).unwrap_or(false) {
+                cx.emit(
+                    DiagBuilder2::error(format!("`{}` requires a string literal as format", ident))
+                        .span(expr.human_span()),
+                );
+                return Err(());
+            }
+            hir::BuiltinCall::SFormatF(lower_format_items(cx, node_id, args)?)
+        }
+        "finish" | "stop" => {
+            // The optional argument controls the diagnostic output of the
+            // simulator, which we don't support.
+            if args.len() > 1 {
+                cx.emit(
+                    DiagBuilder2::error(format!("`{}` takes at most one argument", ident))
+                        .span(expr.human_span()),
+                );
+                return Err(());
+            }
+            match &*ident.value.as_str() {
+                "finish" => hir::BuiltinCall::Finish,
+                _ => hir::BuiltinCall::Stop,
+            }
+        }
+        "time" | "realtime" if !args.is_empty() => {
+            cx.emit(
+                DiagBuilder2::error(format!("`{}` takes no arguments", ident))
+                    .span(expr.human_span()),
+            );
+            return Err(());
+        }
+        "time" => hir::BuiltinCall::Time,
+        "realtime" => hir::BuiltinCall::RealTime,
+        "random" => match args {
+            [] => hir::BuiltinCall::Random(None),
+            _ => hir::BuiltinCall::Random(Some(map_unary_id()?)),
+        },
+        "rose" => hir::BuiltinCall::Rose(map_unary_id()?),
+        "fell" => hir::BuiltinCall::Fell(map_unary_id()?),
+        "stable" => hir::BuiltinCall::Stable(map_unary_id()?),
+        "rtoi" => hir::BuiltinCall::RealConv(hir::RealConv::RtoI, map_unary_id()?),
+        "itor" => hir::BuiltinCall::RealConv(hir::RealConv::ItoR, map_unary_id()?),
+        "realtobits" => hir::BuiltinCall::RealConv(hir::RealConv::RealToBits, map_unary_id()?),
+        "bitstoreal" => hir::BuiltinCall::RealConv(hir::RealConv::BitsToReal, map_unary_id()?),
+        "shortrealtobits" => {
+            hir::BuiltinCall::RealConv(hir::RealConv::ShortRealToBits, map_unary_id()?)
+        }
+        "bitstoshortreal" => {
+            hir::BuiltinCall::RealConv(hir::RealConv::BitsToShortReal, map_unary_id()?)
+        }
+        "past" => match args {
+            [ast::CallArg {
+                expr: Some(ref arg),
+                ..
+            }] => hir::BuiltinCall::Past(cx.map_ast_with_parent(AstNode::Expr(arg), node_id), None),
+            [ast::CallArg {
+                expr: Some(ref arg),
+                ..
+            }, ast::CallArg {
+                expr: Some(ref cycles),
+                ..
+            }] => hir::BuiltinCall::Past(
+                cx.map_ast_with_parent(AstNode::Expr(arg), node_id),
+                Some(cx.map_ast_with_parent(AstNode::Expr(cycles), node_id)),
+            ),
+            _ => {
+                cx.emit(
+                    DiagBuilder2::error(format!("`{}` takes one or two arguments", ident))
+                        .span(expr.human_span())
+                        .add_note("Gating expressions and clocking events are not supported"),
+                );
+                return Err(());
+            }
+        },
+        _ => {
+            cx.emit(
+                DiagBuilder2::error(format!("unknown system task `${}`", ident))
+                    .span(expr.human_span()),
+            );
+            hir::BuiltinCall::Unsupported
+        }
+    }))
+}
+
+/// Lower a call to one of the display or severity system tasks.
+fn lower_display_call<'gcx>(
+    cx: &impl Context<'gcx>,
+    node_id: NodeId,
+    task: hir::DisplayTask,
+    mut args: &'gcx [ast::CallArg<'gcx>],
+) -> Result<hir::BuiltinCall<'gcx>> {
+    // The first argument of `$fatal` may be the finish number, which controls
+    // the diagnostic output of the simulator. We don't support that.
+    if task == hir::DisplayTask::Fatal {
+        match args.first() {
+            Some(arg) if arg.expr.is_some() && !is_string_literal(arg) => args = &args[1..],
+            _ => (),
+        }
+    }
+    Ok(hir::BuiltinCall::Display(
+        task,
+        lower_format_items(cx, node_id, args)?,
+    ))
+}
+
+/// Check whether a call argument is a string literal.
+fn is_string_literal(arg: &ast::CallArg) -> bool {
+    match arg.expr.as_ref().map(|expr| &expr.data) {
+        Some(ast::LiteralExpr(crate::syntax::token::Lit::Str(..))) => true,
+        _ => false,
+    }
+}
+
+/// Lower the arguments of a call to a formatting system task or function.
+///
+/// String literals among the arguments are treated as format strings and
+/// consume the arguments following them. All other arguments are printed in
+/// decimal.
+fn lower_format_items<'gcx>(
+    cx: &impl Context<'gcx>,
+    node_id: NodeId,
+    args: &'gcx [ast::CallArg<'gcx>],
+) -> Result<Vec<hir::FormatItem>> {
+    use crate::syntax::token::Lit;
+    let mut items = vec![];
+    let mut failed = false;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let arg_expr = match arg.expr {
+            Some(ref x) => x,
+            None => {
+                items.push(hir::FormatItem::Text(" ".to_string()));
+                continue;
+            }
+        };
+        let format = match arg_expr.data {
+            ast::LiteralExpr(Lit::Str(format)) => format,
+            _ => {
+                items.push(hir::FormatItem::Arg(
+                    hir::FormatSpec {
+                        kind: hir::FormatKind::Default,
+                        width: None,
+                        precision: None,
+                    },
+                    cx.map_ast_with_parent(AstNode::Expr(arg_expr), node_id),
+                ));
+                continue;
+            }
+        };
+
+        // Parse the format string.
+        let format = format.as_str();
+        let mut text = String::new();
+        let mut chars = format.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                text.push(c);
+                continue;
+            }
+            let mut width = None;
+            while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                width = Some(width.unwrap_or(0) * 10 + digit as usize);
+                chars.next();
+            }
+            let mut precision = None;
+            if chars.peek() == Some(&'.') {
+                chars.next();
+                precision = Some(0);
+                while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                    precision = Some(precision.unwrap_or(0) * 10 + digit as usize);
+                    chars.next();
+                }
+            }
+            let kind = match chars.next() {
+                Some('%') if width.is_none() => {
+                    text.push('%');
+                    continue;
+                }
+                Some('m') | Some('M') if width.is_none() => {
+                    items.push(hir::FormatItem::Text(std::mem::take(&mut text)));
+                    items.push(hir::FormatItem::ScopeName);
+                    continue;
+                }
+                Some('d') | Some('D') => hir::FormatKind::Decimal,
+                Some('h') | Some('H') | Some('x') | Some('X') => hir::FormatKind::Hex,
+                Some('o') | Some('O') => hir::FormatKind::Octal,
+                Some('b') | Some('B') => hir::FormatKind::Binary,
+                Some('c') | Some('C') => hir::FormatKind::Char,
+                Some('s') | Some('S') => hir::FormatKind::String,
+                Some('t') | Some('T') => hir::FormatKind::Time,
+                Some('f') | Some('F') => hir::FormatKind::RealDecimal,
+                Some('e') | Some('E') => hir::FormatKind::RealExponential,
+                Some('g') | Some('G') => hir::FormatKind::RealGeneral,
+                Some(c) => {
+                    cx.emit(
+                        DiagBuilder2::error(format!("unsupported format specifier `%{}`", c))
+                            .span(arg_expr.span)
+                            .add_note(
+                                "Supported specifiers are `%d`, `%h`, `%x`, `%o`, `%b`, `%c`, \
+                                 `%s`, `%t`, `%f`, `%e`, `%g`, `%m`, and `%%`",
+                            ),
+                    );
+                    failed = true;
+                    continue;
+                }
+                None => {
+                    cx.emit(
+                        DiagBuilder2::error("incomplete format specifier at end of string")
+                            .span(arg_expr.span),
+                    );
+                    failed = true;
+                    continue;
+                }
+            };
+            let spec = hir::FormatSpec {
+                kind,
+                width,
+                precision,
+            };
+            if precision.is_some() && !kind.is_real() {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "format specifier `{}` cannot have a precision",
+                        spec
+                    ))
+                    .span(arg_expr.span)
+                    .add_note("Only `%f`, `%e`, and `%g` accept a precision, as in `%.3f`"),
+                );
+                failed = true;
+                continue;
+            }
+            match args.next().and_then(|arg| arg.expr.as_ref()) {
+                Some(expr) => {
+                    items.push(hir::FormatItem::Text(std::mem::take(&mut text)));
+                    items.push(hir::FormatItem::Arg(
+                        spec,
+                        cx.map_ast_with_parent(AstNode::Expr(expr), node_id),
+                    ));
+                }
+                None => {
+                    cx.emit(
+                        DiagBuilder2::error(format!("no argument for format specifier `{}`", spec))
+                            .span(arg_expr.span),
+                    );
+                    failed = true;
+                }
+            }
+        }
+        items.push(hir::FormatItem::Text(text));
+    }
+
+    if failed {
+        return Err(());
+    }
+    items.retain(|item| match item {
+        hir::FormatItem::Text(text) => !text.is_empty(),
+        _ => true,
+    });
+    Ok(items)
+}
+
+/// Compute the value of a time literal in seconds.
+pub(crate) fn time_literal_value<'gcx>(
+    cx: &impl Context<'gcx>,
+    span: Span,
+    int: Name,
+    frac: Option<Name>,
+    unit: syntax::token::TimeUnit,
+) -> Result<num::BigRational> {
+    use syntax::token::TimeUnit;
+    let mut value = parse_fixed_point_number(cx, span, int, frac)?;
+    let magnitude = match unit {
+        TimeUnit::Second => 0,
+        TimeUnit::MilliSecond => 1,
+        TimeUnit::MicroSecond => 2,
+        TimeUnit::NanoSecond => 3,
+        TimeUnit::PicoSecond => 4,
+        TimeUnit::FemtoSecond => 5,
+    };
+    for _ in 0..magnitude {
+        value = value / num::BigInt::from(1000);
+    }
+    Ok(value)
+}
+
+/// Parse a real number with an optional exponent into a [`BigRational`].
+///
+/// The number is rounded to the nearest double-precision value, which is how
+/// reals are represented.
+fn parse_real_number<'gcx>(
+    cx: &impl Context<'gcx>,
+    span: Span,
+    int: Name,
+    frac: Option<Name>,
+    exp: Option<Name>,
+) -> Result<num::BigRational> {
+    let mut text = format!("{}", int);
+    if let Some(frac) = frac {
+        text.push('.');
+        text.push_str(&frac.as_str());
+    }
+    if let Some(exp) = exp {
+        text.push('e');
+        text.push_str(&exp.as_str());
+    }
+    let value = match text.replace('_', "").parse::<f64>() {
+        Ok(x) => x,
+        Err(e) => {
+            cx.emit(
+                DiagBuilder2::error(format!("`{}` is not a number literal", text))
+                    .span(span)
+                    .add_note(format!("{}", e)),
+            );
+            return Err(());
+        }
+    };
+    match num::BigRational::from_float(value) {
+        Some(x) => Ok(x),
+        None => {
+            cx.emit(
+                DiagBuilder2::error(format!("real literal `{}` is out of range", text))
+                    .span(span)
+                    .add_note(format!("The largest real is {:e}", std::f64::MAX)),
+            );
+            Err(())
+        }
+    }
+}
+
+/// Parse a fixed point number into a [`BigRational`].
+///
+/// The fractional part of the number is optional, such that this function may
+/// also be used to parse integers into a ratio.
+fn parse_fixed_point_number<'gcx>(
+    cx: &impl Context<'gcx>,
+    span: Span,
+    int: Name,
+    frac: Option<Name>,
+) -> Result<num::BigRational> {
+    let mut num_digits = int.to_string();
+    let mut denom_digits = String::from("1");
+    if let Some(frac) = frac {
+        let s = frac.to_string();
+        num_digits.push_str(&s);
+        denom_digits.extend(s.chars().map(|_| '0'));
+    }
+    match (num_digits.parse(), denom_digits.parse()) {
+        (Ok(a), Ok(b)) => Ok((a, b).into()),
+        (Err(e), _) | (_, Err(e)) => {
+            let value = match frac {
+                Some(frac) => format!("{}.{}", int, frac),
+                None => format!("{}", int),
+            };
+            cx.emit(
+                DiagBuilder2::error(format!("`{}` is not a number literal", value))
+                    .span(span)
+                    .add_note(format!("{}", e)),
+            );
+            Err(())
+        }
+    }
+}
+
+fn lower_event_expr<'gcx>(
+    cx: &impl Context<'gcx>,
+    expr: &'gcx ast::EventExpr<'gcx>,
+    parent_id: NodeId,
+    into: &mut Vec<hir::Event>,
+    cond_stack: &mut Vec<NodeId>,
+) -> Result<()> {
+    match *expr {
+        ast::EventExpr::Edge {
+            span,
+            edge,
             ref value,
         } => {
             into.push(hir::Event {
@@ -1646,9 +2373,13 @@ fn lower_package<'gcx>(
     let mut params = Vec::new();
     for item in &ast.items {
         match item.data {
+            ast::ItemData::Dummy => (),
             ast::ItemData::VarDecl(ref decl) => {
                 next_rib = alloc_var_decl(cx, decl, next_rib, &mut decls);
             }
+            ast::ItemData::NetDecl(ref decl) => {
+                next_rib = alloc_net_decl(cx, decl, next_rib, &mut decls);
+            }
             ast::ItemData::ParamDecl(ref param) => {
                 next_rib = alloc_param_decl(cx, param, next_rib, &mut params);
             }
@@ -1657,8 +2388,25 @@ fn lower_package<'gcx>(
                 names.push((def.name, next_rib));
             }
             ast::ItemData::SubroutineDecl(ref decl) => {
-                warn!("ignoring unsupported subroutine `{}`", decl.prototype.name)
+                next_rib = cx.map_ast_with_parent(AstNode::SubroutineDecl(decl), next_rib);
+                names.push((decl.prototype.name, next_rib));
+            }
+            ast::ItemData::LetDecl(ref decl) => {
+                next_rib = cx.map_ast_with_parent(AstNode::LetDecl(decl), next_rib);
+                names.push((decl.name, next_rib));
+            }
+            ast::ItemData::ImportDecl(ref decl) => {
+                for item in &decl.items {
+                    next_rib = cx.map_ast_with_parent(AstNode::Import(item), next_rib);
+                }
+            }
+            ast::ItemData::ClassDecl(ref decl) => {
+                next_rib = cx.map_ast_with_parent(AstNode::Class(decl), next_rib);
+                names.push((decl.name, next_rib));
             }
+            // Exports are handled during name resolution, and DPI imports
+            // don't need an HIR representation.
+            ast::ItemData::ExportDecl(..) | ast::ItemData::DpiDecl(..) => (),
             _ => {
                 cx.emit(
                     DiagBuilder2::error(format!("{:#} cannot appear in a package", item))
@@ -1700,6 +2448,41 @@ pub(crate) fn lower_index_mode<'gcx>(
     }
 }
 
+/// Determine the package name in a call such as `pkg::foo()`.
+fn scope_call_pkg(scope: &ast::Expr) -> Option<Spanned<Name>> {
+    match scope.data {
+        ast::IdentExpr(pkg) => Some(pkg),
+        _ => None,
+    }
+}
+
+/// Lower a call to a function, task, or let declaration to HIR.
+fn lower_call<'gcx>(
+    cx: &impl Context<'gcx>,
+    node_id: NodeId,
+    name: Spanned<Name>,
+    target: NodeId,
+    args: &'gcx [ast::CallArg<'gcx>],
+) -> Result<hir::ExprKind<'gcx>> {
+    let args = args
+        .iter()
+        .map(|arg| lower_call_arg(cx, arg, node_id))
+        .collect();
+    match cx.ast_for_id(target).as_all() {
+        ast::AllNode::SubroutineDecl(..) => Ok(hir::ExprKind::FunctionCall(target, args)),
+        ast::AllNode::LetDecl(..) => Ok(hir::ExprKind::LetCall(target, args)),
+        _ => {
+            cx.emit(
+                DiagBuilder2::error(format!("`{}` is not a function or task", name))
+                    .span(name.span)
+                    .add_note(format!("`{}` was declared here:", name))
+                    .span(cx.ast_for_id(target).human_span()),
+            );
+            Err(())
+        }
+    }
+}
+
 /// Lower a function or method call argument to HIR.
 fn lower_call_arg<'gcx>(
     cx: &impl Context<'gcx>,
@@ -1715,3 +2498,475 @@ fn lower_call_arg<'gcx>(
             .map(|expr| cx.map_ast_with_parent(AstNode::Expr(expr), parent)),
     }
 }
+
+/// Check whether an expression is the expression of an expression statement,
+/// as in `foo;`.
+fn is_expr_stmt<'gcx>(cx: &impl Context<'gcx>, node_id: NodeId) -> bool {
+    let parent = match cx.parent_node_id(node_id) {
+        Some(x) => x,
+        None => return false,
+    };
+    match cx.ast_of(parent) {
+        Ok(AstNode::Stmt(stmt)) => match stmt.kind {
+            ast::ExprStmt(..) => true,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Check whether an identifier expression is a task or void function enabled
+/// without parentheses, as in `foo;`, and if so return the called subroutine.
+fn lower_task_enable<'gcx>(
+    cx: &impl Context<'gcx>,
+    node_id: NodeId,
+    name: Spanned<Name>,
+) -> Result<Option<NodeId>> {
+    if !is_expr_stmt(cx, node_id) {
+        return Ok(None);
+    }
+    let parent = cx.parent_node_id(node_id).unwrap();
+    let target = match cx.gcx().resolve_upwards(name.value, parent)? {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    if cx.ast_for_id(target).as_all().is_subroutine_decl() {
+        Ok(Some(target))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Lower a `foreach` loop into nested loops, one for each index variable.
+///
+/// The outermost loop is the `foreach` statement itself. The inner loops are
+/// new statements, each nested within the index variable of the loop around
+/// it. The index variables themselves are children of the `foreach`
+/// statement, such that walking up from the body never visits the inner
+/// loops.
+fn lower_foreach<'gcx>(
+    cx: &impl Context<'gcx>,
+    node_id: NodeId,
+    span: Span,
+    array: &'gcx ast::Expr<'gcx>,
+    indices: &'gcx [ast::ForeachIndex<'gcx>],
+    body: &'gcx ast::Stmt<'gcx>,
+) -> hir::StmtKind {
+    let array = cx.map_ast_with_parent(AstNode::Expr(array), node_id);
+
+    // Allocate the index variables and the loops that iterate over them.
+    let mut loops = vec![];
+    let mut parent = node_id;
+    for index in indices {
+        let stmt_id = if loops.is_empty() {
+            node_id
+        } else {
+            let id = cx.alloc_id(span);
+            cx.set_parent(id, parent);
+            id
+        };
+        let hir = hir::ForeachIndex {
+            id: index.id(),
+            name: index.name,
+            span: index.span(),
+            array,
+            dim: index.index,
+        };
+        cx.set_span(hir.id, hir.span);
+        cx.intern_hir_with_parent(
+            hir.id,
+            HirNode::ForeachIndex(cx.arena().alloc_hir(hir)),
+            node_id,
+        );
+        loops.push((stmt_id, index.id()));
+        parent = index.id();
+    }
+    let body = cx.map_ast_with_parent(AstNode::Stmt(body), parent);
+
+    // Assemble the inner loops, from the inside out.
+    let mut inner = body;
+    for &(stmt_id, index_id) in loops.iter().skip(1).rev() {
+        let hir = hir::Stmt {
+            id: stmt_id,
+            label: None,
+            span,
+            kind: hir::StmtKind::Loop {
+                kind: hir::LoopKind::Foreach(index_id),
+                body: inner,
+            },
+        };
+        cx.intern_hir(stmt_id, HirNode::Stmt(cx.arena().alloc_hir(hir)));
+        inner = stmt_id;
+    }
+    match loops.first() {
+        Some(&(_, index_id)) => hir::StmtKind::Loop {
+            kind: hir::LoopKind::Foreach(index_id),
+            body: inner,
+        },
+        None => hir::StmtKind::Block(vec![inner]),
+    }
+}
+
+/// Lower a `case ... matches` statement.
+///
+/// The items are lowered to a chain of `if` statements, each of which matches
+/// the case expression against the item's pattern. The first `if` takes the
+/// place of the `case` statement, and the default item becomes the final
+/// `else` branch. A `unique`, `unique0`, or `priority` qualifier carries over
+/// to the first `if`, from where it applies to the entire chain. Since the
+/// conditions have no counterpart in the source text, they are anchored to a
+/// dummy expression attached to each item. The first condition evaluates the
+/// case expression into a temporary, which the other conditions refer to.
+fn lower_case_matches<'gcx>(
+    cx: &impl Context<'gcx>,
+    node_id: NodeId,
+    up: Option<ast::UniquePriority>,
+    expr: &'gcx ast::Expr<'gcx>,
+    items: &'gcx [ast::CaseItem<'gcx>],
+) -> hir::StmtKind {
+    let expr_span = expr.span;
+    let expr = cx.map_ast_with_parent(AstNode::Expr(expr), node_id);
+    let mut patterns = vec![];
+    let mut default = None;
+    for item in items {
+        match *item {
+            ast::CaseItem::Default(ref stmt) => {
+                if default.is_none() {
+                    default = Some(cx.map_ast_with_parent(AstNode::Stmt(stmt), node_id));
+                } else {
+                    cx.emit(DiagBuilder2::error("multiple default cases").span(stmt.human_span()));
+                }
+            }
+            ast::CaseItem::Pattern(ref item) => patterns.push(item.as_ref()),
+            ast::CaseItem::Expr(_, ref stmt) => bug_span!(
+                stmt.span(),
+                cx,
+                "expression item in `case ... matches` statement"
+            ),
+        }
+    }
+
+    // Assemble the `if` statements, from the last item to the first.
+    let mut else_stmt = default;
+    for (i, item) in patterns.into_iter().enumerate().rev() {
+        let stmt_id = if i == 0 {
+            node_id
+        } else {
+            let id = cx.alloc_id(item.span());
+            cx.set_parent(id, node_id);
+            id
+        };
+        let anchor = cx
+            .arena()
+            .alloc_ast_expr(ast::Expr::new(item.span(), ast::DummyExpr));
+        anchor.link_attach(item, item.order());
+        let cond = cx.map_ast_with_parent(AstNode::Expr(anchor), stmt_id);
+        let value_anchor = cx
+            .arena()
+            .alloc_ast_expr(ast::Expr::new(expr_span, ast::DummyExpr));
+        value_anchor.link_attach(item, item.order());
+        let value = cx.map_ast_with_parent(AstNode::Expr(value_anchor), cond);
+        let hir = hir::Expr {
+            ast: value_anchor,
+            kind: hir::ExprKind::CaseValue {
+                expr,
+                first: i == 0,
+            },
+        };
+        cx.intern_hir(value, HirNode::Expr(cx.arena().alloc_hir(hir)));
+        let hir = hir::Expr {
+            ast: anchor,
+            kind: hir::ExprKind::Matches(value, lower_match_pattern(cx, &item.pattern, cond)),
+        };
+        cx.intern_hir(cond, HirNode::Expr(cx.arena().alloc_hir(hir)));
+        let kind = hir::StmtKind::If {
+            up: if i == 0 { up } else { None },
+            cond,
+            main_stmt: cx.map_ast_with_parent(AstNode::Stmt(&item.stmt), node_id),
+            else_stmt,
+        };
+        if i == 0 {
+            return kind;
+        }
+        let hir = hir::Stmt {
+            id: stmt_id,
+            label: None,
+            span: item.span(),
+            kind,
+        };
+        cx.intern_hir(stmt_id, HirNode::Stmt(cx.arena().alloc_hir(hir)));
+        else_stmt = Some(stmt_id);
+    }
+    match else_stmt {
+        Some(stmt) => hir::StmtKind::Block(vec![stmt]),
+        None => hir::StmtKind::Null,
+    }
+}
+
+/// Lower a pattern of a `matches` expression.
+///
+/// The pattern variables are allocated as children of the `matches`
+/// expression, which determines their type.
+fn lower_match_pattern<'gcx>(
+    cx: &impl Context<'gcx>,
+    pattern: &'gcx ast::MatchPattern<'gcx>,
+    matches: NodeId,
+) -> hir::MatchPattern {
+    match *pattern {
+        ast::MatchPattern::Wildcard(_) => hir::MatchPattern::Wildcard,
+        ast::MatchPattern::Var(ref var) => {
+            let hir = hir::PatternVar {
+                id: var.id(),
+                name: var.name,
+                span: var.span(),
+                matches,
+            };
+            cx.set_span(hir.id, hir.span);
+            cx.intern_hir_with_parent(
+                hir.id,
+                HirNode::PatternVar(cx.arena().alloc_hir(hir)),
+                matches,
+            );
+            hir::MatchPattern::Var(var.id())
+        }
+        ast::MatchPattern::Tagged(member, ref inner) => hir::MatchPattern::Tagged(
+            member,
+            inner
+                .as_ref()
+                .map(|inner| Box::new(lower_match_pattern(cx, inner, matches))),
+        ),
+        ast::MatchPattern::Expr(ref expr) => {
+            hir::MatchPattern::Expr(cx.map_ast_with_parent(AstNode::Expr(expr), matches))
+        }
+    }
+}
+
+/// Find the labelled statement disabled by a `disable` statement.
+///
+/// Only blocks enclosing the `disable` statement within the same procedure or
+/// subroutine are considered.
+fn lower_disable_target<'gcx>(
+    cx: &impl Context<'gcx>,
+    node_id: NodeId,
+    name: Name,
+    span: Span,
+) -> Result<NodeId> {
+    let mut current = node_id;
+    while let Some(parent) = cx.parent_node_id(current) {
+        current = parent;
+        match cx.ast_of(current)? {
+            // The parent chain also runs through the preceding statements in a
+            // block, so make sure the labelled statement actually encloses the
+            // `disable`.
+            AstNode::Stmt(stmt)
+                if stmt.label == Some(name)
+                    && stmt.span.source == span.source
+                    && stmt.span.begin <= span.begin
+                    && span.end <= stmt.span.end =>
+            {
+                return Ok(current)
+            }
+            AstNode::Proc(_) | AstNode::SubroutineDecl(_) => break,
+            _ => (),
+        }
+    }
+    cx.emit(
+        DiagBuilder2::error(format!("`{}` does not name an enclosing block", name))
+            .span(span)
+            .add_note("Only labelled blocks around the `disable` statement can be disabled"),
+    );
+    Err(())
+}
+
+/// Lower an immediate or deferred assertion statement.
+fn lower_assertion_stmt<'gcx>(
+    cx: &impl Context<'gcx>,
+    node_id: NodeId,
+    assertion: &'gcx ast::Assertion<'gcx>,
+) -> Result<hir::StmtKind> {
+    let (deferred, blocking) = match assertion.data {
+        ast::AssertionData::Immediate(ref blocking) => (None, blocking),
+        ast::AssertionData::Deferred(deferred, ref blocking) => (Some(deferred), blocking),
+        ast::AssertionData::Concurrent(..) => {
+            cx.emit(
+                DiagBuilder2::warning("unsupported: concurrent assertion in procedure; ignored")
+                    .span(assertion.span),
+            );
+            return Ok(hir::StmtKind::Null);
+        }
+    };
+    let (kind, cond, pass_stmt, fail_stmt) = match *blocking {
+        ast::BlockingAssertion::Assert(ref cond, ref action) => {
+            let (pass, fail) = split_assertion_action(action);
+            (hir::AssertionKind::Assert, cond, pass, fail)
+        }
+        ast::BlockingAssertion::Assume(ref cond, ref action) => {
+            let (pass, fail) = split_assertion_action(action);
+            (hir::AssertionKind::Assume, cond, pass, fail)
+        }
+        ast::BlockingAssertion::Cover(ref cond, ref stmt) => {
+            (hir::AssertionKind::Cover, cond, Some(stmt), None)
+        }
+    };
+
+    // The action blocks of deferred assertions may only contain a single
+    // subroutine call (IEEE 1800-2017 section 16.4).
+    if deferred.is_some() {
+        let mut failed = false;
+        for stmt in pass_stmt.iter().chain(fail_stmt.iter()) {
+            let ok = match stmt.kind {
+                ast::NullStmt => true,
+                ast::ExprStmt(ref expr) => match expr.data {
+                    ast::CallExpr(..) | ast::SysIdentExpr(..) | ast::IdentExpr(..) => true,
+                    _ => false,
+                },
+                _ => false,
+            };
+            if !ok {
+                cx.emit(
+                    DiagBuilder2::error(
+                        "action block of deferred assertion must be a single subroutine call",
+                    )
+                    .span(stmt.span()),
+                );
+                failed = true;
+            }
+        }
+        if failed {
+            return Err(());
+        }
+    }
+
+    Ok(hir::StmtKind::Assertion {
+        kind,
+        deferred,
+        cond: cx.map_ast_with_parent(AstNode::Expr(cond), node_id),
+        pass_stmt: pass_stmt.map(|stmt| cx.map_ast_with_parent(AstNode::Stmt(stmt), node_id)),
+        fail_stmt: fail_stmt.map(|stmt| cx.map_ast_with_parent(AstNode::Stmt(stmt), node_id)),
+    })
+}
+
+/// Split an assertion's action block into its pass and fail statements.
+fn split_assertion_action<'a>(
+    action: &'a ast::AssertionActionBlock<'a>,
+) -> (Option<&'a ast::Stmt<'a>>, Option<&'a ast::Stmt<'a>>) {
+    match *action {
+        ast::AssertionActionBlock::Positive(ref stmt) => (Some(stmt), None),
+        ast::AssertionActionBlock::Negative(ref stmt) => (None, Some(stmt)),
+        ast::AssertionActionBlock::Both(ref pass, ref fail) => (Some(pass), Some(fail)),
+    }
+}
+
+/// Lower a concurrent assertion.
+fn lower_concurrent_assertion<'gcx>(
+    cx: &impl Context<'gcx>,
+    node_id: NodeId,
+    assertion: &'gcx ast::Assertion<'gcx>,
+) -> Result<HirNode<'gcx>> {
+    let (kind, spec, action) = match assertion.data {
+        ast::AssertionData::Concurrent(ast::ConcurrentAssertion::AssertProperty(
+            ref spec,
+            ref action,
+        )) => (hir::AssertionKind::Assert, spec, action),
+        ast::AssertionData::Concurrent(ast::ConcurrentAssertion::AssumeProperty(
+            ref spec,
+            ref action,
+        )) => (hir::AssertionKind::Assume, spec, action),
+        _ => unreachable!("not a concurrent assertion: {:?}", assertion),
+    };
+    let clock = match spec.clock {
+        Some(ref clock) => cx.map_ast_with_parent(AstNode::EventExpr(clock), node_id),
+        None => {
+            cx.emit(
+                DiagBuilder2::error("concurrent assertion requires a clocking event")
+                    .span(spec.span)
+                    .add_note("Default clocking blocks are not supported"),
+            );
+            return Err(());
+        }
+    };
+    let disable = spec
+        .disable
+        .as_ref()
+        .map(|expr| cx.map_ast_with_parent(AstNode::Expr(expr), node_id));
+    let prop = lower_property(cx, node_id, &spec.prop)?;
+    let (pass_stmt, fail_stmt) = split_assertion_action(action);
+    let hir = hir::Assertion {
+        id: node_id,
+        span: assertion.span,
+        label: assertion.label.map(|(name, span)| Spanned::new(name, span)),
+        kind,
+        clock,
+        disable,
+        prop,
+        pass_stmt: pass_stmt.map(|stmt| cx.map_ast_with_parent(AstNode::Stmt(stmt), node_id)),
+        fail_stmt: fail_stmt.map(|stmt| cx.map_ast_with_parent(AstNode::Stmt(stmt), node_id)),
+    };
+    Ok(HirNode::Assertion(cx.arena().alloc_hir(hir)))
+}
+
+/// Lower a property expression of a concurrent assertion.
+fn lower_property<'gcx>(
+    cx: &impl Context<'gcx>,
+    node_id: NodeId,
+    prop: &'gcx ast::PropExpr<'gcx>,
+) -> Result<hir::Property> {
+    match prop.data {
+        ast::PropExprData::SeqOp(_, ref seq) => {
+            Ok(hir::Property::Seq(lower_sequence(cx, node_id, seq)?))
+        }
+        ast::PropExprData::SeqBinOp(op, _, ref seq, ref consequent)
+            if op == ast::PropSeqBinOp::ImplOverlap || op == ast::PropSeqBinOp::ImplNonoverlap =>
+        {
+            Ok(hir::Property::Impl {
+                antecedent: lower_sequence(cx, node_id, seq)?,
+                overlap: op == ast::PropSeqBinOp::ImplOverlap,
+                consequent: Box::new(lower_property(cx, node_id, consequent)?),
+            })
+        }
+        _ => {
+            cx.emit(
+                DiagBuilder2::error("unsupported property expression")
+                    .span(prop.span)
+                    .add_note("Only sequences and the `|->` and `|=>` implications are supported"),
+            );
+            Err(())
+        }
+    }
+}
+
+/// Lower a sequence expression of a concurrent assertion.
+fn lower_sequence<'gcx>(
+    cx: &impl Context<'gcx>,
+    node_id: NodeId,
+    seq: &'gcx ast::SeqExpr<'gcx>,
+) -> Result<hir::Sequence> {
+    let map_expr = |expr| cx.map_ast_with_parent(AstNode::Expr(expr), node_id);
+    match seq.data {
+        ast::SeqExprData::Expr(ref expr, None) => Ok(hir::Sequence::Expr(map_expr(expr))),
+        ast::SeqExprData::Expr(ref expr, Some(ast::SeqRep::Consec(ref count))) => {
+            Ok(hir::Sequence::Repeat(
+                Box::new(hir::Sequence::Expr(map_expr(expr))),
+                map_expr(count),
+            ))
+        }
+        ast::SeqExprData::Delay(ref lhs, ast::SeqDelay::Fixed(ref delay), ref rhs) => {
+            let lhs = match lhs {
+                Some(lhs) => Some(Box::new(lower_sequence(cx, node_id, lhs)?)),
+                None => None,
+            };
+            let rhs = lower_sequence(cx, node_id, rhs)?;
+            Ok(hir::Sequence::Delay(lhs, map_expr(delay), Box::new(rhs)))
+        }
+        _ => {
+            cx.emit(
+                DiagBuilder2::error("unsupported sequence expression")
+                    .span(seq.span)
+                    .add_note(
+                        "Only `##N` delays and `[*N]` repetitions with constant `N` are supported",
+                    ),
+            );
+            Err(())
+        }
+    }
+}
@@ -41,6 +41,12 @@ make_arenas!(
         packages: Package,
         enum_variants: EnumVariant,
         subroutines: Subroutine,
+        assertions: Assertion,
+        foreach_indices: ForeachIndex,
+        lets: Let,
+        let_args: LetArg,
+        classes: Class<'hir>,
+        pattern_vars: PatternVar,
     }
 );
 
@@ -59,6 +65,7 @@ pub(crate) fn accessed_nodes<'a>(
             read: Default::default(),
             written: Default::default(),
         },
+        by_ref: false,
     };
     k.visit_node_with_id(node_id, false);
     Ok(Arc::new(k.table))
@@ -108,6 +115,9 @@ struct AccessTableCollector<'a, C> {
     cx: &'a C,
     env: ParamEnv,
     table: AccessTable,
+    /// Whether the visited lvalue is passed by reference, and is therefore
+    /// read as well as written.
+    by_ref: bool,
 }
 
 impl<'a, 'gcx: 'a, C> Visitor<'gcx> for AccessTableCollector<'a, C>
@@ -133,23 +143,42 @@ where
     C: Context<'gcx>,
 {
     fn pre_visit_lvalue(&mut self, mir: &mir::Lvalue) -> bool {
-        match mir.kind {
+        let node = match mir.kind {
             mir::LvalueKind::Var(id) | mir::LvalueKind::Port(id)
                 if self.is_binding_interesting(id) =>
             {
-                self.table.written.insert(AccessedNode::Regular(id));
-                false
+                AccessedNode::Regular(id)
             }
             mir::LvalueKind::IntfSignal(intf, sig) => {
                 if let Some(intf) = intf.get_intf() {
                     if self.is_binding_interesting(intf) {
                         self.table.written.insert(AccessedNode::Intf(intf, sig));
+                        if self.by_ref {
+                            self.table.read.insert(AccessedNode::Intf(intf, sig));
+                        }
                     }
                 }
-                true
+                return true;
             }
-            _ => true,
+            _ => return true,
+        };
+        self.table.written.insert(node);
+        if self.by_ref {
+            self.table.read.insert(node);
         }
+        false
+    }
+
+    fn pre_visit_call_arg(&mut self, arg: &mir::CallArg) -> bool {
+        self.by_ref = match arg {
+            mir::CallArg::Ref(..) => true,
+            _ => false,
+        };
+        true
+    }
+
+    fn post_visit_call_arg(&mut self, _arg: &mir::CallArg) {
+        self.by_ref = false;
     }
 
     fn pre_visit_rvalue(&mut self, mir: &mir::Rvalue) -> bool {
@@ -168,6 +197,29 @@ where
                 }
                 true
             }
+            // Calls access whatever the called function or task accesses.
+            // Recursive calls are skipped since they access nothing new.
+            mir::RvalueKind::Call { target, .. } | mir::RvalueKind::MethodCall { target, .. }
+                if !self.is_local_to(target, self.table.node_id) =>
+            {
+                let env = match mir.kind {
+                    mir::RvalueKind::MethodCall { env, .. } => env,
+                    _ => self.env,
+                };
+                if let Ok(table) = self.cx.accessed_nodes(target, env) {
+                    for &node in &table.read {
+                        if self.is_binding_interesting(node.id()) {
+                            self.table.read.insert(node);
+                        }
+                    }
+                    for &node in &table.written {
+                        if self.is_binding_interesting(node.id()) {
+                            self.table.written.insert(node);
+                        }
+                    }
+                }
+                true
+            }
             _ => true,
         }
     }
@@ -178,6 +230,31 @@ where
     C: Context<'gcx>,
 {
     fn is_binding_interesting(&self, binding: NodeId) -> bool {
-        !self.cx.is_parent_of(self.table.node_id, binding)
+        !self.is_local_to(self.table.node_id, binding)
+    }
+
+    /// Check if a node is declared within a scope.
+    fn is_local_to(&self, scope: NodeId, node: NodeId) -> bool {
+        match self.cx.hir_of(scope) {
+            // Subroutines are part of the rib chain of the surrounding scope,
+            // which would make every later declaration look like a child.
+            // Only consider their arguments and body. Variables declared at
+            // the top of the body hang off the rib before their statement.
+            Ok(HirNode::Subroutine(sub)) => {
+                node == scope
+                    || sub.args.iter().any(|arg| arg.id == node)
+                    || sub.stmts.iter().any(|&stmt| {
+                        self.cx.is_parent_of(stmt, node)
+                            || match self.cx.hir_of(stmt) {
+                                Ok(HirNode::Stmt(hir::Stmt {
+                                    kind: hir::StmtKind::InlineGroup { stmts, .. },
+                                    ..
+                                })) => stmts.contains(&node),
+                                _ => false,
+                            }
+                    })
+            }
+            _ => self.cx.is_parent_of(scope, node),
+        }
     }
 }
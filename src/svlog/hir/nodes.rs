@@ -34,6 +34,12 @@ pub enum HirNode<'a> {
     Package(&'a Package),
     EnumVariant(&'a EnumVariant),
     Subroutine(&'a Subroutine),
+    Assertion(&'a Assertion),
+    ForeachIndex(&'a ForeachIndex),
+    Let(&'a Let),
+    LetArg(&'a LetArg),
+    Class(&'a Class<'a>),
+    PatternVar(&'a PatternVar),
 }
 
 impl<'hir> HasSpan for HirNode<'hir> {
@@ -60,6 +66,12 @@ impl<'hir> HasSpan for HirNode<'hir> {
             HirNode::Package(x) => x.span(),
             HirNode::EnumVariant(x) => x.span(),
             HirNode::Subroutine(x) => x.span(),
+            HirNode::Assertion(x) => x.span(),
+            HirNode::ForeachIndex(x) => x.span(),
+            HirNode::Let(x) => x.span(),
+            HirNode::LetArg(x) => x.span(),
+            HirNode::Class(x) => x.span(),
+            HirNode::PatternVar(x) => x.span(),
         }
     }
 
@@ -86,6 +98,12 @@ impl<'hir> HasSpan for HirNode<'hir> {
             HirNode::Package(x) => x.human_span(),
             HirNode::EnumVariant(x) => x.human_span(),
             HirNode::Subroutine(x) => x.human_span(),
+            HirNode::Assertion(x) => x.human_span(),
+            HirNode::ForeachIndex(x) => x.human_span(),
+            HirNode::Let(x) => x.human_span(),
+            HirNode::LetArg(x) => x.human_span(),
+            HirNode::Class(x) => x.human_span(),
+            HirNode::PatternVar(x) => x.human_span(),
         }
     }
 }
@@ -114,6 +132,12 @@ impl<'hir> HasDesc for HirNode<'hir> {
             HirNode::Package(x) => x.desc(),
             HirNode::EnumVariant(x) => x.desc(),
             HirNode::Subroutine(x) => x.desc(),
+            HirNode::Assertion(x) => x.desc(),
+            HirNode::ForeachIndex(x) => x.desc(),
+            HirNode::Let(x) => x.desc(),
+            HirNode::LetArg(x) => x.desc(),
+            HirNode::Class(x) => x.desc(),
+            HirNode::PatternVar(x) => x.desc(),
         }
     }
 
@@ -140,6 +164,12 @@ impl<'hir> HasDesc for HirNode<'hir> {
             HirNode::Package(x) => x.desc_full(),
             HirNode::EnumVariant(x) => x.desc_full(),
             HirNode::Subroutine(x) => x.desc_full(),
+            HirNode::Assertion(x) => x.desc_full(),
+            HirNode::ForeachIndex(x) => x.desc_full(),
+            HirNode::Let(x) => x.desc_full(),
+            HirNode::LetArg(x) => x.desc_full(),
+            HirNode::Class(x) => x.desc_full(),
+            HirNode::PatternVar(x) => x.desc_full(),
         }
     }
 }
@@ -202,6 +232,8 @@ pub struct ModuleBlock {
     pub params: Vec<NodeId>,
     /// The continuous assignments in the module.
     pub assigns: Vec<NodeId>,
+    /// The concurrent assertions in the module.
+    pub asserts: Vec<NodeId>,
     /// The bottom of the name scope tree.
     pub last_rib: NodeId,
 }
@@ -540,6 +572,9 @@ impl HasDesc for TypeKind {
             TypeKind::Builtin(BuiltinType::Int) => "int type",
             TypeKind::Builtin(BuiltinType::Integer) => "integer type",
             TypeKind::Builtin(BuiltinType::LongInt) => "long int type",
+            TypeKind::Builtin(BuiltinType::ShortReal) => "short real type",
+            TypeKind::Builtin(BuiltinType::Real) => "real type",
+            TypeKind::Builtin(BuiltinType::RealTime) => "real time type",
             TypeKind::Struct(_) => "struct type",
             TypeKind::PackedArray(..) => "packed array type",
             _ => "type",
@@ -567,6 +602,9 @@ pub enum BuiltinType {
     LongInt,
     Time,
     String,
+    ShortReal,
+    Real,
+    RealTime,
 }
 
 /// An expression.
@@ -602,6 +640,7 @@ impl HasDesc for Expr<'_> {
         match self.kind {
             ExprKind::IntConst { .. } => "integer constant",
             ExprKind::TimeConst(_) => "time constant",
+            ExprKind::RealConst(_) => "real constant",
             ExprKind::Ident(_) => "identifier",
             _ => "expression",
         }
@@ -612,6 +651,7 @@ impl HasDesc for Expr<'_> {
         match self.kind {
             ExprKind::IntConst { value: ref k, .. } => format!("{} `{}`", self.desc(), k),
             ExprKind::TimeConst(ref k) => format!("{} `{}`", self.desc(), k),
+            ExprKind::RealConst(_) => format!("{} `{}`", self.desc(), self.span().extract()),
             ExprKind::Ident(n) => format!("`{}`", n.value),
             ExprKind::PositionalPattern(..) => format!("positional pattern"),
             ExprKind::NamedPattern(..) => format!("named pattern"),
@@ -639,6 +679,8 @@ pub enum ExprKind<'a> {
     UnsizedConst(char),
     /// A time constant literal.
     TimeConst(BigRational),
+    /// A real constant literal such as `4.2`.
+    RealConst(BigRational),
     /// A string constant literal.
     StringConst(Spanned<Name>),
     /// An identifier.
@@ -665,6 +707,8 @@ pub enum ExprKind<'a> {
     RepeatPattern(NodeId, Vec<NodeId>),
     /// A concatenation such as `{a,b}` or `{4{a,b}}`.
     Concat(Option<NodeId>, Vec<NodeId>),
+    /// A streaming concatenation such as `{>>{a,b}}` or `{<<8{a,b}}`.
+    StreamConcat(ast::StreamDir, Option<StreamSlice>, Vec<NodeId>),
     /// A cast `(ty, expr)` such as `foo'(bar)`.
     Cast(NodeId, NodeId),
     /// A sign cast such as `unsigned'(foo)`.
@@ -675,6 +719,31 @@ pub enum ExprKind<'a> {
     Inside(NodeId, Vec<Spanned<InsideRange>>),
     /// A function call such as `foo(a, b, c)`.
     FunctionCall(NodeId, Vec<CallArg>),
+    /// An expansion of a let declaration such as `foo(a, b, c)`.
+    LetCall(NodeId, Vec<CallArg>),
+    /// A method call such as `obj.foo(a, b, c)`, on a class handle or a
+    /// dynamic array, queue, or associative array.
+    MethodCall(NodeId, Spanned<Name>, Vec<CallArg>),
+    /// A class instantiation such as `new(a, b, c)`.
+    New(Vec<CallArg>),
+    /// The `this` handle of the enclosing class.
+    This,
+    /// The `super` handle of the enclosing class.
+    Super,
+    /// The `null` class handle.
+    Null,
+    /// A dynamic array allocation such as `new[n]` or `new[n](old)`.
+    ArrayNew(NodeId, Option<NodeId>),
+    /// The empty queue `{}`.
+    EmptyQueue,
+    /// A tagged union expression such as `tagged Valid 42`.
+    Tagged(Spanned<Name>, Option<NodeId>),
+    /// A pattern match such as `a matches tagged Valid .v`.
+    Matches(NodeId, MatchPattern),
+    /// The value of the expression of a `case ... matches` statement. The
+    /// `first` item evaluates the expression into a temporary, which the other
+    /// items then match against.
+    CaseValue { expr: NodeId, first: bool },
     /// An assignment.
     Assign {
         op: ast::AssignOp,
@@ -831,7 +900,7 @@ pub enum IndexMode {
 }
 
 /// The different builtin function calls that are supported.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum BuiltinCall<'a> {
     /// An unsupported builtin. Will yield constant 0.
     Unsupported,
@@ -853,6 +922,32 @@ pub enum BuiltinCall<'a> {
     IsUnknown(&'a ast::Expr<'a>),
     /// A call to one of the array dimension functions.
     ArrayDim(ArrayDim, &'a ast::Expr<'a>, Option<&'a ast::Expr<'a>>),
+    /// A call to one of the display or severity system tasks, such as
+    /// `$display("%d", x)` or `$error("oops")`.
+    Display(DisplayTask, Vec<FormatItem>),
+    /// A call to the `$sformatf` function, which formats its arguments into a
+    /// string.
+    SFormatF(Vec<FormatItem>),
+    /// A call to the `$finish` task.
+    Finish,
+    /// A call to the `$stop` task.
+    Stop,
+    /// A call to the `$time` function.
+    Time,
+    /// A call to the `$realtime` function.
+    RealTime,
+    /// A call to the `$random` function, with an optional seed variable.
+    Random(Option<NodeId>),
+    /// A call to the `$rose(x)` sampled value function.
+    Rose(NodeId),
+    /// A call to the `$fell(x)` sampled value function.
+    Fell(NodeId),
+    /// A call to the `$stable(x)` sampled value function.
+    Stable(NodeId),
+    /// A call to the `$past(x, n)` sampled value function.
+    Past(NodeId, Option<NodeId>),
+    /// A call to one of the real conversion functions, such as `$rtoi(x)`.
+    RealConv(RealConv, NodeId),
 }
 
 /// The different builtin array dimension function calls that are supported.
@@ -872,6 +967,161 @@ pub enum ArrayDim {
     Size,
 }
 
+/// The builtin real conversion function calls that are supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RealConv {
+    /// The `$rtoi` function, which truncates a real to an `integer`.
+    RtoI,
+    /// The `$itor` function, which converts an `integer` to a real.
+    ItoR,
+    /// The `$realtobits` function.
+    RealToBits,
+    /// The `$bitstoreal` function.
+    BitsToReal,
+    /// The `$shortrealtobits` function.
+    ShortRealToBits,
+    /// The `$bitstoshortreal` function.
+    BitsToShortReal,
+}
+
+/// The display and severity system tasks that are supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayTask {
+    /// The `$display` task, which prints a message followed by a newline.
+    Display,
+    /// The `$write` task, which prints a message without a trailing newline.
+    Write,
+    /// The `$info` task.
+    Info,
+    /// The `$warning` task.
+    Warning,
+    /// The `$error` task.
+    Error,
+    /// The `$fatal` task, which also terminates the simulation.
+    Fatal,
+}
+
+impl std::fmt::Display for DisplayTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DisplayTask::Display => write!(f, "$display"),
+            DisplayTask::Write => write!(f, "$write"),
+            DisplayTask::Info => write!(f, "$info"),
+            DisplayTask::Warning => write!(f, "$warning"),
+            DisplayTask::Error => write!(f, "$error"),
+            DisplayTask::Fatal => write!(f, "$fatal"),
+        }
+    }
+}
+
+/// A piece of a formatted message, as passed to `$display` and friends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatItem {
+    /// Literal text.
+    Text(String),
+    /// An argument formatted according to a specifier such as `%d`.
+    Arg(FormatSpec, NodeId),
+    /// The hierarchical name of the current scope, as requested by `%m`.
+    ScopeName,
+}
+
+/// A format specifier such as `%d` or `%0h`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatSpec {
+    /// How the argument is formatted.
+    pub kind: FormatKind,
+    /// The field width, e.g. `0` in `%0d`. Defaults to the width of the
+    /// argument if omitted.
+    pub width: Option<usize>,
+    /// The number of fractional digits of a real, e.g. `3` in `%.3f`.
+    pub precision: Option<usize>,
+}
+
+/// The different ways an argument can be formatted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatKind {
+    /// Decimal number, `%d`.
+    Decimal,
+    /// Hexadecimal number, `%h` or `%x`.
+    Hex,
+    /// Octal number, `%o`.
+    Octal,
+    /// Binary number, `%b`.
+    Binary,
+    /// ASCII character, `%c`.
+    Char,
+    /// String, `%s`.
+    String,
+    /// Simulation time, `%t`.
+    Time,
+    /// Real number in decimal notation, `%f`.
+    RealDecimal,
+    /// Real number in exponential notation, `%e`.
+    RealExponential,
+    /// Real number in decimal or exponential notation, whichever is shorter,
+    /// `%g`.
+    RealGeneral,
+    /// No specifier, as for arguments outside a format string. Resolved to a
+    /// specifier matching the type of the argument.
+    Default,
+}
+
+impl FormatKind {
+    /// Get the character which selects this format in a format string.
+    pub fn as_char(self) -> char {
+        match self {
+            FormatKind::Decimal => 'd',
+            FormatKind::Hex => 'h',
+            FormatKind::Octal => 'o',
+            FormatKind::Binary => 'b',
+            FormatKind::Char => 'c',
+            FormatKind::String => 's',
+            FormatKind::Time => 't',
+            FormatKind::RealDecimal => 'f',
+            FormatKind::RealExponential => 'e',
+            FormatKind::RealGeneral => 'g',
+            FormatKind::Default => unreachable!("default format not resolved"),
+        }
+    }
+
+    /// Check whether this format applies to real numbers.
+    pub fn is_real(self) -> bool {
+        match self {
+            FormatKind::RealDecimal | FormatKind::RealExponential | FormatKind::RealGeneral => true,
+            _ => false,
+        }
+    }
+}
+
+impl FormatSpec {
+    /// Resolve a default specifier according to the type of its argument.
+    ///
+    /// Reals are formatted as if by `%g`, strings as if by `%s`, and all other
+    /// values as if by `%d`. Explicit specifiers are returned unchanged.
+    pub fn resolve(self, ty: &ty::UnpackedType) -> FormatSpec {
+        let kind = match self.kind {
+            FormatKind::Default if ty.is_real() => FormatKind::RealGeneral,
+            FormatKind::Default if ty.is_string() => FormatKind::String,
+            FormatKind::Default => FormatKind::Decimal,
+            kind => kind,
+        };
+        FormatSpec { kind, ..self }
+    }
+}
+
+impl std::fmt::Display for FormatSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "%")?;
+        if let Some(width) = self.width {
+            write!(f, "{}", width)?;
+        }
+        if let Some(precision) = self.precision {
+            write!(f, ".{}", precision)?;
+        }
+        write!(f, "{}", self.kind.as_char())
+    }
+}
+
 /// A variable or net declaration.
 #[derive(Debug, PartialEq, Eq)]
 pub struct VarDecl {
@@ -994,9 +1244,13 @@ pub enum StmtKind {
     /// An if statement.
     ///
     /// ```text
-    /// if (<cond>) <main_stmt> [else <else_stmt>]
+    /// [unique|unique0|priority] if (<cond>) <main_stmt> [else <else_stmt>]
     /// ```
+    ///
+    /// A `unique`, `unique0`, or `priority` qualifier applies to the entire
+    /// chain of `else if` statements that follows.
     If {
+        up: Option<ast::UniquePriority>,
         cond: NodeId,
         main_stmt: NodeId,
         else_stmt: Option<NodeId>,
@@ -1012,11 +1266,70 @@ pub enum StmtKind {
     InlineGroup { stmts: Vec<NodeId>, rib: NodeId },
     /// A case statement.
     Case {
+        up: Option<ast::UniquePriority>,
         expr: NodeId,
         ways: Vec<(Vec<NodeId>, NodeId)>,
         default: Option<NodeId>,
         kind: ast::CaseKind,
     },
+    /// An immediate or deferred assertion.
+    ///
+    /// ```text
+    /// assert [#0|final] (<cond>) [<pass_stmt>] [else <fail_stmt>]
+    /// ```
+    Assertion {
+        kind: AssertionKind,
+        deferred: Option<ast::AssertionDeferred>,
+        cond: NodeId,
+        pass_stmt: Option<NodeId>,
+        fail_stmt: Option<NodeId>,
+    },
+    /// A fork-join block, each statement of which runs in parallel.
+    ///
+    /// The declarations are initialized when the block is started, before the
+    /// branches are.
+    ///
+    /// ```text
+    /// fork <decls> <branches> join|join_any|join_none
+    /// ```
+    Fork {
+        decls: Vec<NodeId>,
+        branches: Vec<NodeId>,
+        join: ast::JoinKind,
+    },
+    /// A `wait fork` statement.
+    WaitFork,
+    /// A `disable fork` statement.
+    DisableFork,
+    /// A `break` statement.
+    Break,
+    /// A `continue` statement.
+    Continue,
+    /// A `return` statement, with an optional return value.
+    Return(Option<NodeId>),
+    /// A `disable` statement, with the labelled statement to be disabled.
+    Disable(NodeId),
+}
+
+/// The different forms an immediate assertion can take.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssertionKind {
+    /// An `assert` statement.
+    Assert,
+    /// An `assume` statement.
+    Assume,
+    /// A `cover` statement.
+    Cover,
+}
+
+impl std::fmt::Display for AssertionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AssertionKind::Assert => write!(f, "assert"),
+            AssertionKind::Assume => write!(f, "assume"),
+            AssertionKind::Cover => write!(f, "cover"),
+        }
+    }
 }
 
 /// The different forms an assignment can take.
@@ -1043,6 +1356,8 @@ pub enum LoopKind {
     Do(NodeId),
     //// A `for (<init>; <cond>; <step>)` loop.
     For(NodeId, NodeId, NodeId),
+    /// A loop over one dimension of a `foreach (<array>[<index>])`.
+    Foreach(NodeId),
 }
 
 /// The different forms of timing control that can be applied to a statement.
@@ -1123,6 +1438,12 @@ pub enum GenKind {
         step: NodeId,
         body: ModuleBlock,
     },
+    /// A case-generate statement.
+    Case {
+        expr: NodeId,
+        items: Vec<(Vec<NodeId>, ModuleBlock)>,
+        default: Option<ModuleBlock>,
+    },
 }
 
 /// A genvar declaration.
@@ -1154,6 +1475,130 @@ impl HasDesc for GenvarDecl {
     }
 }
 
+/// A foreach-loop index variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForeachIndex {
+    pub id: NodeId,
+    pub name: Spanned<Name>,
+    pub span: Span,
+    /// The array being iterated over.
+    pub array: NodeId,
+    /// The dimension of the array the index iterates over.
+    pub dim: usize,
+}
+
+impl HasSpan for ForeachIndex {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn human_span(&self) -> Span {
+        self.name.span
+    }
+}
+
+impl HasDesc for ForeachIndex {
+    fn desc(&self) -> &'static str {
+        "foreach index"
+    }
+
+    fn desc_full(&self) -> String {
+        format!("foreach index `{}`", self.name.value)
+    }
+}
+
+/// A variable bound by a pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternVar {
+    pub id: NodeId,
+    pub name: Spanned<Name>,
+    pub span: Span,
+    /// The `matches` expression whose pattern binds the variable.
+    pub matches: NodeId,
+}
+
+impl HasSpan for PatternVar {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn human_span(&self) -> Span {
+        self.name.span
+    }
+}
+
+impl HasDesc for PatternVar {
+    fn desc(&self) -> &'static str {
+        "pattern variable"
+    }
+
+    fn desc_full(&self) -> String {
+        format!("pattern variable `{}`", self.name.value)
+    }
+}
+
+/// A let declaration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Let {
+    pub id: NodeId,
+    pub name: Spanned<Name>,
+    pub span: Span,
+    /// The formal arguments.
+    pub args: Vec<NodeId>,
+    /// The expression the let expands to.
+    pub expr: NodeId,
+}
+
+impl HasSpan for Let {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn human_span(&self) -> Span {
+        self.name.span
+    }
+}
+
+impl HasDesc for Let {
+    fn desc(&self) -> &'static str {
+        "let declaration"
+    }
+
+    fn desc_full(&self) -> String {
+        format!("let declaration `{}`", self.name.value)
+    }
+}
+
+/// A formal argument of a let declaration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LetArg {
+    pub id: NodeId,
+    pub name: Spanned<Name>,
+    pub span: Span,
+    /// The default value of the argument.
+    pub default: Option<NodeId>,
+}
+
+impl HasSpan for LetArg {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn human_span(&self) -> Span {
+        self.name.span
+    }
+}
+
+impl HasDesc for LetArg {
+    fn desc(&self) -> &'static str {
+        "let argument"
+    }
+
+    fn desc_full(&self) -> String {
+        format!("let argument `{}`", self.name.value)
+    }
+}
+
 /// A typedef.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Typedef {
@@ -1204,6 +1649,120 @@ impl HasDesc for Assign {
     }
 }
 
+/// A concurrent assertion.
+///
+/// ```text
+/// [<label>:] assert property (@(<clock>) [disable iff (<disable>)] <prop>)
+///     [<pass_stmt>] [else <fail_stmt>]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assertion {
+    pub id: NodeId,
+    pub span: Span,
+    pub label: Option<Spanned<Name>>,
+    pub kind: AssertionKind,
+    pub clock: NodeId,
+    pub disable: Option<NodeId>,
+    pub prop: Property,
+    pub pass_stmt: Option<NodeId>,
+    pub fail_stmt: Option<NodeId>,
+}
+
+impl Assertion {
+    /// Collect the boolean expressions evaluated at the clock ticks.
+    pub fn bool_exprs(&self) -> Vec<NodeId> {
+        let mut exprs = vec![];
+        self.prop.collect_exprs(&mut exprs, &mut vec![]);
+        exprs.extend(self.disable);
+        exprs
+    }
+}
+
+impl HasSpan for Assertion {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn human_span(&self) -> Span {
+        self.label.map(|l| l.span).unwrap_or(self.span)
+    }
+}
+
+impl HasDesc for Assertion {
+    fn desc(&self) -> &'static str {
+        "concurrent assertion"
+    }
+
+    fn desc_full(&self) -> String {
+        match self.label {
+            Some(label) => format!("concurrent assertion `{}`", label.value),
+            None => "concurrent assertion".to_string(),
+        }
+    }
+}
+
+/// A property in a concurrent assertion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Property {
+    /// A sequence which must match.
+    Seq(Sequence),
+    /// An implication `<antecedent> |-> <consequent>`, or `|=>` if the two do
+    /// not overlap.
+    Impl {
+        antecedent: Sequence,
+        overlap: bool,
+        consequent: Box<Property>,
+    },
+}
+
+impl Property {
+    /// Collect the boolean expressions and constant cycle counts.
+    pub fn collect_exprs(&self, exprs: &mut Vec<NodeId>, counts: &mut Vec<NodeId>) {
+        match self {
+            Property::Seq(seq) => seq.collect_exprs(exprs, counts),
+            Property::Impl {
+                antecedent,
+                consequent,
+                ..
+            } => {
+                antecedent.collect_exprs(exprs, counts);
+                consequent.collect_exprs(exprs, counts);
+            }
+        }
+    }
+}
+
+/// A sequence in a concurrent assertion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sequence {
+    /// A boolean expression which must hold for one clock tick.
+    Expr(NodeId),
+    /// A consecutive repetition `<seq> [* <count>]`.
+    Repeat(Box<Sequence>, NodeId),
+    /// A cycle delay `[<lhs>] ##<delay> <rhs>`.
+    Delay(Option<Box<Sequence>>, NodeId, Box<Sequence>),
+}
+
+impl Sequence {
+    /// Collect the boolean expressions and constant cycle counts.
+    pub fn collect_exprs(&self, exprs: &mut Vec<NodeId>, counts: &mut Vec<NodeId>) {
+        match *self {
+            Sequence::Expr(expr) => exprs.push(expr),
+            Sequence::Repeat(ref seq, count) => {
+                seq.collect_exprs(exprs, counts);
+                counts.push(count);
+            }
+            Sequence::Delay(ref lhs, delay, ref rhs) => {
+                if let Some(lhs) = lhs {
+                    lhs.collect_exprs(exprs, counts);
+                }
+                counts.push(delay);
+                rhs.collect_exprs(exprs, counts);
+            }
+        }
+    }
+}
+
 /// A package.
 #[derive(Debug, PartialEq, Eq)]
 pub struct Package {
@@ -1240,6 +1799,47 @@ impl HasDesc for Package {
     }
 }
 
+/// A class declaration.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Class<'a> {
+    pub id: NodeId,
+    pub name: Spanned<Name>,
+    pub span: Span,
+    /// The AST node.
+    pub ast: &'a ast::ClassDecl<'a>,
+    /// The parameters of the class, including the ones declared in its body.
+    pub params: Vec<NodeId>,
+    /// The type of the base class, if the class extends another one.
+    pub extends: Option<NodeId>,
+    /// The properties of the class, in declaration order.
+    pub props: Vec<NodeId>,
+    /// The methods of the class, in declaration order, and whether they are
+    /// declared `virtual`.
+    pub methods: Vec<(NodeId, bool)>,
+    /// The bottom of the name scope tree.
+    pub last_rib: NodeId,
+}
+
+impl HasSpan for Class<'_> {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn human_span(&self) -> Span {
+        self.name.span
+    }
+}
+
+impl HasDesc for Class<'_> {
+    fn desc(&self) -> &'static str {
+        "class"
+    }
+
+    fn desc_full(&self) -> String {
+        format!("class `{}`", self.name.value)
+    }
+}
+
 /// A single variant of an enum.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct EnumVariant {
@@ -1292,6 +1892,29 @@ pub enum InsideRange {
     Range(NodeId, NodeId),
 }
 
+/// A pattern matched against a value in `matches` expressions and `case
+/// matches` statements.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchPattern {
+    /// The wildcard pattern `.*`.
+    Wildcard,
+    /// A pattern variable `.name`, which binds the matched value.
+    Var(NodeId),
+    /// A tagged union member pattern such as `tagged Valid .v`.
+    Tagged(Spanned<Name>, Option<Box<MatchPattern>>),
+    /// A constant expression the value must be equal to.
+    Expr(NodeId),
+}
+
+/// The slice size of a streaming concatenation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamSlice {
+    /// A constant slice size, such as the `8` in `{<<8{a}}`.
+    Expr(NodeId),
+    /// A type whose bit width is the slice size, such as `{<<byte{a}}`.
+    Type(NodeId),
+}
+
 /// An argument to a function or method call.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CallArg {
@@ -1304,7 +1927,7 @@ pub struct CallArg {
 }
 
 /// A subroutine declaration.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Subroutine {
     pub id: NodeId,
     pub name: Spanned<Name>,
@@ -1313,6 +1936,36 @@ pub struct Subroutine {
     pub kind: ast::SubroutineKind,
     /// Optional return type in case of a function.
     pub retty: Option<NodeId>,
+    /// The arguments of the subroutine, in declaration order.
+    pub args: Vec<SubroutineArg>,
+    /// The statements in the subroutine body.
+    pub stmts: Vec<NodeId>,
+}
+
+impl Subroutine {
+    /// Check whether this is a function.
+    pub fn is_function(&self) -> bool {
+        self.kind == ast::SubroutineKind::Func
+    }
+
+    /// Check whether this is a task.
+    pub fn is_task(&self) -> bool {
+        self.kind == ast::SubroutineKind::Task
+    }
+}
+
+/// An argument of a subroutine.
+///
+/// The `id` refers to a `VarDecl` node which holds the argument's type and
+/// default value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubroutineArg {
+    /// The id of the argument declaration.
+    pub id: NodeId,
+    /// The name of the argument.
+    pub name: Spanned<Name>,
+    /// The direction of the argument.
+    pub dir: ast::SubroutinePortDir,
 }
 
 impl HasSpan for Subroutine {
@@ -41,6 +41,8 @@ pub trait Visitor<'a>: Sized {
             HirNode::ExtPort(x) => self.visit_ext_port(x),
             HirNode::Inst(x) => self.visit_inst(x),
             HirNode::InstTarget(x) => self.visit_inst_target(x),
+            HirNode::Subroutine(x) => self.visit_subroutine(x),
+            HirNode::Assertion(x) => self.visit_assertion(x),
             _ => (),
         }
     }
@@ -104,6 +106,14 @@ pub trait Visitor<'a>: Sized {
     fn visit_inst_target(&mut self, hir: &'a InstTarget<'a>) {
         walk_inst_target(self, hir);
     }
+
+    fn visit_subroutine(&mut self, hir: &'a Subroutine) {
+        walk_subroutine(self, hir);
+    }
+
+    fn visit_assertion(&mut self, hir: &'a Assertion) {
+        walk_assertion(self, hir);
+    }
 }
 
 /// Walk the contents of a module.
@@ -140,6 +150,9 @@ pub fn walk_module_block<'a>(visitor: &mut impl Visitor<'a>, blk: &'a ModuleBloc
     for &id in &blk.assigns {
         visitor.visit_node_with_id(id, false);
     }
+    for &id in &blk.asserts {
+        visitor.visit_node_with_id(id, false);
+    }
 }
 
 /// Walk the contents of a procedure.
@@ -170,6 +183,7 @@ pub fn walk_stmt<'a>(visitor: &mut impl Visitor<'a>, stmt: &'a Stmt) {
             cond,
             main_stmt,
             else_stmt,
+            ..
         } => {
             visitor.visit_node_with_id(cond, false);
             visitor.visit_node_with_id(main_stmt, false);
@@ -179,7 +193,7 @@ pub fn walk_stmt<'a>(visitor: &mut impl Visitor<'a>, stmt: &'a Stmt) {
         }
         StmtKind::Loop { kind, body } => {
             match kind {
-                LoopKind::Forever => (),
+                LoopKind::Forever | LoopKind::Foreach(_) => (),
                 LoopKind::Repeat(id) | LoopKind::While(id) | LoopKind::Do(id) => {
                     visitor.visit_node_with_id(id, false);
                 }
@@ -213,6 +227,39 @@ pub fn walk_stmt<'a>(visitor: &mut impl Visitor<'a>, stmt: &'a Stmt) {
                 visitor.visit_node_with_id(default, false);
             }
         }
+        StmtKind::Assertion {
+            cond,
+            pass_stmt,
+            fail_stmt,
+            ..
+        } => {
+            visitor.visit_node_with_id(cond, false);
+            if let Some(pass_stmt) = pass_stmt {
+                visitor.visit_node_with_id(pass_stmt, false);
+            }
+            if let Some(fail_stmt) = fail_stmt {
+                visitor.visit_node_with_id(fail_stmt, false);
+            }
+        }
+        StmtKind::Fork {
+            ref decls,
+            ref branches,
+            ..
+        } => {
+            for &stmt in decls.iter().chain(branches.iter()) {
+                visitor.visit_node_with_id(stmt, false);
+            }
+        }
+        StmtKind::Return(expr) => {
+            if let Some(expr) = expr {
+                visitor.visit_node_with_id(expr, false);
+            }
+        }
+        StmtKind::WaitFork
+        | StmtKind::DisableFork
+        | StmtKind::Break
+        | StmtKind::Continue
+        | StmtKind::Disable(_) => (),
     }
 }
 
@@ -220,10 +267,20 @@ pub fn walk_stmt<'a>(visitor: &mut impl Visitor<'a>, stmt: &'a Stmt) {
 pub fn walk_expr<'a>(visitor: &mut impl Visitor<'a>, expr: &'a Expr, lvalue: bool) {
     match expr.kind {
         ExprKind::Builtin(BuiltinCall::Unsupported)
+        | ExprKind::Builtin(BuiltinCall::Finish)
+        | ExprKind::Builtin(BuiltinCall::Stop)
+        | ExprKind::Builtin(BuiltinCall::Time)
+        | ExprKind::Builtin(BuiltinCall::RealTime)
+        | ExprKind::Builtin(BuiltinCall::Random(None))
         | ExprKind::IntConst { .. }
         | ExprKind::UnsizedConst(_)
         | ExprKind::TimeConst(_)
-        | ExprKind::StringConst(_) => (),
+        | ExprKind::RealConst(_)
+        | ExprKind::StringConst(_)
+        | ExprKind::This
+        | ExprKind::Super
+        | ExprKind::Null
+        | ExprKind::EmptyQueue => (),
         ExprKind::Ident(x) => {
             visitor.visit_ident(x);
         }
@@ -239,6 +296,12 @@ pub fn walk_expr<'a>(visitor: &mut impl Visitor<'a>, expr: &'a Expr, lvalue: boo
         ExprKind::Field(expr, _) => {
             visitor.visit_node_with_id(expr, lvalue);
         }
+        ExprKind::ArrayNew(size, init) => {
+            visitor.visit_node_with_id(size, false);
+            if let Some(init) = init {
+                visitor.visit_node_with_id(init, false);
+            }
+        }
         ExprKind::Index(expr, mode) => {
             visitor.visit_node_with_id(expr, lvalue);
             match mode {
@@ -251,7 +314,8 @@ pub fn walk_expr<'a>(visitor: &mut impl Visitor<'a>, expr: &'a Expr, lvalue: boo
         }
         ExprKind::Builtin(BuiltinCall::Clog2(arg))
         | ExprKind::Builtin(BuiltinCall::Signed(arg))
-        | ExprKind::Builtin(BuiltinCall::Unsigned(arg)) => {
+        | ExprKind::Builtin(BuiltinCall::Unsigned(arg))
+        | ExprKind::Builtin(BuiltinCall::RealConv(_, arg)) => {
             visitor.visit_node_with_id(arg, false);
         }
         ExprKind::Builtin(BuiltinCall::CountOnes(arg))
@@ -269,6 +333,28 @@ pub fn walk_expr<'a>(visitor: &mut impl Visitor<'a>, expr: &'a Expr, lvalue: boo
         ExprKind::Builtin(BuiltinCall::Bits(arg)) => {
             visitor.visit_node_with_id(arg.id(), false);
         }
+        ExprKind::Builtin(BuiltinCall::Random(Some(seed))) => {
+            visitor.visit_node_with_id(seed, true);
+        }
+        ExprKind::Builtin(BuiltinCall::Rose(arg))
+        | ExprKind::Builtin(BuiltinCall::Fell(arg))
+        | ExprKind::Builtin(BuiltinCall::Stable(arg)) => {
+            visitor.visit_node_with_id(arg, false);
+        }
+        ExprKind::Builtin(BuiltinCall::Past(arg, cycles)) => {
+            visitor.visit_node_with_id(arg, false);
+            if let Some(cycles) = cycles {
+                visitor.visit_node_with_id(cycles, false);
+            }
+        }
+        ExprKind::Builtin(BuiltinCall::Display(_, ref items))
+        | ExprKind::Builtin(BuiltinCall::SFormatF(ref items)) => {
+            for item in items {
+                if let FormatItem::Arg(_, arg) = *item {
+                    visitor.visit_node_with_id(arg, false);
+                }
+            }
+        }
         ExprKind::Ternary(cond, true_expr, false_expr) => {
             visitor.visit_node_with_id(cond, false);
             visitor.visit_node_with_id(true_expr, lvalue);
@@ -306,6 +392,16 @@ pub fn walk_expr<'a>(visitor: &mut impl Visitor<'a>, expr: &'a Expr, lvalue: boo
                 visitor.visit_node_with_id(expr, lvalue);
             }
         }
+        ExprKind::StreamConcat(_, slice, ref exprs) => {
+            match slice {
+                Some(StreamSlice::Expr(expr)) => visitor.visit_node_with_id(expr, false),
+                Some(StreamSlice::Type(ty)) => visitor.visit_node_with_id(ty, false),
+                None => (),
+            }
+            for &expr in exprs {
+                visitor.visit_node_with_id(expr, lvalue);
+            }
+        }
         ExprKind::Cast(ty, expr) => {
             visitor.visit_node_with_id(ty, false);
             visitor.visit_node_with_id(expr, false);
@@ -329,7 +425,29 @@ pub fn walk_expr<'a>(visitor: &mut impl Visitor<'a>, expr: &'a Expr, lvalue: boo
                 }
             }
         }
-        ExprKind::FunctionCall(_, ref args) => {
+        ExprKind::Tagged(_, expr) => {
+            if let Some(expr) = expr {
+                visitor.visit_node_with_id(expr, false);
+            }
+        }
+        ExprKind::Matches(expr, ref pattern) => {
+            visitor.visit_node_with_id(expr, false);
+            walk_match_pattern(visitor, pattern);
+        }
+        ExprKind::CaseValue { expr, .. } => {
+            visitor.visit_node_with_id(expr, false);
+        }
+        ExprKind::FunctionCall(_, ref args)
+        | ExprKind::LetCall(_, ref args)
+        | ExprKind::New(ref args) => {
+            for &arg in args {
+                if let Some(expr) = arg.expr {
+                    visitor.visit_node_with_id(expr, false);
+                }
+            }
+        }
+        ExprKind::MethodCall(object, _, ref args) => {
+            visitor.visit_node_with_id(object, false);
             for &arg in args {
                 if let Some(expr) = arg.expr {
                     visitor.visit_node_with_id(expr, false);
@@ -343,6 +461,19 @@ pub fn walk_expr<'a>(visitor: &mut impl Visitor<'a>, expr: &'a Expr, lvalue: boo
     }
 }
 
+/// Walk the contents of a pattern.
+pub fn walk_match_pattern<'a>(visitor: &mut impl Visitor<'a>, pattern: &'a MatchPattern) {
+    match *pattern {
+        MatchPattern::Wildcard | MatchPattern::Var(_) => (),
+        MatchPattern::Tagged(_, ref inner) => {
+            if let Some(inner) = inner {
+                walk_match_pattern(visitor, inner);
+            }
+        }
+        MatchPattern::Expr(expr) => visitor.visit_node_with_id(expr, false),
+    }
+}
+
 /// Walk the contents of a timing control block.
 pub fn walk_timing_control<'a>(visitor: &mut impl Visitor<'a>, ctrl: &'a TimingControl) {
     match *ctrl {
@@ -417,3 +548,36 @@ pub fn walk_inst_target<'a>(visitor: &mut impl Visitor<'a>, hir: &'a InstTarget)
         visitor.visit_node_with_id(p, false);
     }
 }
+
+/// Walk the contents of a subroutine.
+pub fn walk_subroutine<'a>(visitor: &mut impl Visitor<'a>, hir: &'a Subroutine) {
+    if let Some(retty) = hir.retty {
+        visitor.visit_node_with_id(retty, false);
+    }
+    for arg in &hir.args {
+        visitor.visit_node_with_id(arg.id, false);
+    }
+    for &stmt in &hir.stmts {
+        visitor.visit_node_with_id(stmt, false);
+    }
+}
+
+/// Walk the contents of a concurrent assertion.
+pub fn walk_assertion<'a>(visitor: &mut impl Visitor<'a>, hir: &'a Assertion) {
+    visitor.visit_node_with_id(hir.clock, false);
+    if let Some(disable) = hir.disable {
+        visitor.visit_node_with_id(disable, false);
+    }
+    let mut exprs = vec![];
+    let mut counts = vec![];
+    hir.prop.collect_exprs(&mut exprs, &mut counts);
+    for id in exprs.into_iter().chain(counts) {
+        visitor.visit_node_with_id(id, false);
+    }
+    if let Some(pass_stmt) = hir.pass_stmt {
+        visitor.visit_node_with_id(pass_stmt, false);
+    }
+    if let Some(fail_stmt) = hir.fail_stmt {
+        visitor.visit_node_with_id(fail_stmt, false);
+    }
+}
@@ -8,6 +8,7 @@ use crate::{
     hir::{self, HirNode},
     port_list::AsPortedNode,
     resolver::InstTarget,
+    ty::UnpackedType,
     Context, ParamEnv, ParamEnvData, ParamEnvSource, PortMapping,
 };
 use std::sync::Arc;
@@ -136,6 +137,98 @@ pub(crate) fn inst_details<'a>(
     }))
 }
 
+/// Determine the number of instances created by an instantiation.
+///
+/// This is the product of the sizes of the instance array's dimensions, or 1
+/// if the instantiation is not an array, as in `foo bar[3:0] (...)`. The
+/// instances of multidimensional arrays are enumerated with the right-most
+/// dimension varying fastest.
+pub(crate) fn inst_array_len<'a>(
+    cx: &impl Context<'a>,
+    inst: &'a hir::Inst<'a>,
+    env: ParamEnv,
+) -> Result<usize> {
+    let ty = cx.type_of_inst(Ref(inst), env);
+    if ty.is_error() {
+        return Err(());
+    }
+    let mut len = 1;
+    for dim in ty.unpacked_dims() {
+        match dim.get_size() {
+            Some(size) => len *= size,
+            None => {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "instance array `{}` must have a fixed size",
+                        inst.name
+                    ))
+                    .span(inst.human_span())
+                    .add_note(format!("Dimension `{}` has no fixed size", dim)),
+                );
+                return Err(());
+            }
+        }
+    }
+    Ok(len)
+}
+
+/// Determine the element of an unpacked array connection passed to one
+/// instance of an instance array.
+///
+/// If the outermost unpacked dimensions of the connection match the dimensions
+/// of the instance array, and the remaining element type is identical to the
+/// port's type, each instance is connected to one element, as in
+/// `foo bar[3:0] (.a(x))` with `int x[3:0]`. Returns the offset of the
+/// instance's element within each dimension, counting the left-most instance
+/// as `index` 0. Returns `None` for all other connections.
+///
+/// See §23.3.3.5 "Unpacked array ports and arrays of instances".
+pub(crate) fn inst_array_element<'a>(
+    cx: &impl Context<'a>,
+    inst: &'a hir::Inst<'a>,
+    env: ParamEnv,
+    ty: &'a UnpackedType<'a>,
+    port_ty: &'a UnpackedType<'a>,
+    index: usize,
+) -> Option<Vec<usize>> {
+    let inst_ty = cx.type_of_inst(Ref(inst), env);
+    let mut elem_ty = ty;
+    let mut dims = vec![];
+    for inst_dim in inst_ty.unpacked_dims() {
+        let dim = elem_ty.unpacked_dims().next()?;
+        if dim.get_size()? != inst_dim.get_size()? {
+            return None;
+        }
+        dims.push(dim);
+        elem_ty = elem_ty.pop_dim(cx)?;
+    }
+    if dims.is_empty() || !elem_ty.is_identical(port_ty) {
+        return None;
+    }
+    inst_array_offsets(&dims, index)
+}
+
+/// Determine the offsets of the element associated with one instance of an
+/// instance array, within an array with the given dimensions.
+///
+/// The instances are enumerated with the right-most dimension varying fastest,
+/// counting the left-most instance as `index` 0. Offsets count from the lower
+/// bound of each dimension, as elements are laid out in LLHD.
+pub(crate) fn inst_array_offsets(dims: &[ty::UnpackedDim], index: usize) -> Option<Vec<usize>> {
+    let mut offsets = vec![0; dims.len()];
+    let mut rest = index;
+    for (offset, dim) in offsets.iter_mut().zip(dims.iter()).rev() {
+        let size = dim.get_size()?;
+        let pos = rest % size;
+        rest /= size;
+        *offset = match dim {
+            ty::UnpackedDim::Range(range) if range.dir == ty::RangeDir::Down => size - 1 - pos,
+            _ => pos,
+        };
+    }
+    Some(offsets)
+}
+
 /// Compute the details of an instantiated module or interface.
 #[moore_derive::query]
 pub(crate) fn inst_target_details<'a>(
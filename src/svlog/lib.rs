@@ -112,8 +112,16 @@ macro_rules! assert_type {
 }
 
 mod ast_map;
+#[warn(missing_docs)]
+pub mod call_mapping;
+#[warn(missing_docs)]
+pub mod class;
 mod codegen;
+#[warn(missing_docs)]
+pub mod container;
 mod context;
+#[warn(missing_docs)]
+pub mod enums;
 pub mod hir;
 mod inst_details;
 pub mod mir;
@@ -125,6 +133,12 @@ mod port_mapping;
 pub mod resolver;
 pub mod rst;
 #[warn(missing_docs)]
+pub mod strings;
+#[warn(missing_docs)]
+pub mod sva;
+#[warn(missing_docs)]
+pub mod timescale;
+#[warn(missing_docs)]
 pub mod ty;
 pub mod typeck;
 pub mod value;
@@ -264,6 +278,10 @@ mod queries {
     use crate::crate_prelude::*;
     #[allow(deprecated)]
     use crate::{
+        call_mapping::*,
+        class::*,
+        container::*,
+        enums::*,
         hir::lowering::*,
         hir::{accessed_nodes, AccessTable},
         inst_details::*,
@@ -276,6 +294,9 @@ mod queries {
         port_mapping::*,
         resolver::*,
         rst::*,
+        strings::*,
+        sva::*,
+        timescale::*,
         ty::UnpackedType,
         typeck::*,
         value::*,
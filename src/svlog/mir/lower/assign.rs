@@ -199,6 +199,60 @@ fn simplify<'a>(
                 base += sbvt.size;
             }
         }
+        LvalueKind::Unpack(bits) if !rhs.is_error() => {
+            let builder = lower::rvalue::Builder {
+                cx,
+                span: rhs.span,
+                expr: rhs.id,
+                env: rhs.env,
+            };
+            let mut a = root.clone();
+            a.lhs = bits;
+            a.rhs = lower::rvalue::pack_simple_bit_vector(&builder, rhs);
+            let a = cx.arena().alloc_mir_assignment(a);
+            simplify(cx, a, a.lhs, a.rhs, into);
+        }
+        LvalueKind::Stream {
+            value,
+            slice,
+            reverse,
+        } if !rhs.is_error() => {
+            let builder = lower::rvalue::Builder {
+                cx,
+                span: rhs.span,
+                expr: rhs.id,
+                env: rhs.env,
+            };
+
+            // Take the left-most bits of the assigned value as the stream. The
+            // type context ensures that it provides enough bits.
+            let width = lhs.ty.simple_bit_vector(cx, lhs.span).size;
+            let rhs_sbvt = rhs.ty.simple_bit_vector(cx, rhs.span);
+            let mut stream = rhs;
+            if rhs_sbvt.size > width {
+                let base = builder.constant_u32((rhs_sbvt.size - width) as u32);
+                stream = builder.build(
+                    lhs.ty,
+                    RvalueKind::Index {
+                        value: rhs,
+                        base,
+                        length: width,
+                    },
+                );
+            }
+
+            // Reorder the slices of right-to-left streams.
+            if reverse {
+                stream = lower::rvalue::reverse_stream(&builder, stream, slice, true);
+            }
+
+            // Assign the stream to the concatenated targets.
+            let mut a = root.clone();
+            a.lhs = value;
+            a.rhs = stream;
+            let a = cx.arena().alloc_mir_assignment(a);
+            simplify(cx, a, a.lhs, a.rhs, into);
+        }
         LvalueKind::Index {
             value,
             base,
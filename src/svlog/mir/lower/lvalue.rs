@@ -7,9 +7,12 @@ use crate::{
     hir::HirNode,
     mir::{
         lower,
-        lower::rvalue::{adjust_indexing, compute_indexing},
+        lower::rvalue::{
+            adjust_indexing, compute_indexing, lower_container_index, lower_this, packed_sbvt,
+            property_slot,
+        },
         lvalue::*,
-        rvalue::RvalueKind,
+        rvalue::{IntBinaryArithOp, RvalueKind},
     },
     syntax::ast::BasicNode,
     ty::{SbvType, UnpackedType},
@@ -127,14 +130,30 @@ fn lower_expr_inner<'a>(
             let binding = cx.resolve_node(expr_id, env)?;
             return match cx.hir_of(binding)? {
                 HirNode::GenvarDecl(decl) => Ok(builder.build(ty, LvalueKind::Genvar(decl.id))),
-                HirNode::VarDecl(decl) => Ok(builder.build(ty, LvalueKind::Var(decl.id))),
+                HirNode::VarDecl(decl) => match property_slot(cx, expr_id, decl.id, env)? {
+                    Some(slot) => {
+                        let object = lower_this(cx, expr_id, env)?;
+                        Ok(builder.build(ty, LvalueKind::Property { object, slot }))
+                    }
+                    None => Ok(builder.build(ty, LvalueKind::Var(decl.id))),
+                },
                 HirNode::IntPort(port) if ty.resolve_full().core.get_interface().is_some() => {
                     Ok(builder.build(ty, LvalueKind::Intf(port.id)))
                 }
                 HirNode::IntPort(port) => Ok(builder.build(ty, LvalueKind::Port(port.id))),
-                HirNode::Inst(inst) if ty.resolve_full().core.get_interface().is_some() => {
+                HirNode::Inst(inst)
+                    if ty.resolve_full().core.get_interface().is_some()
+                        || ty.resolve_full().core.get_module().is_some() =>
+                {
                     Ok(builder.build(ty, LvalueKind::Intf(inst.id)))
                 }
+                // Assigning to the function name from within the function body
+                // sets the return value.
+                HirNode::Subroutine(sub)
+                    if sub.is_function() && cx.is_parent_of(sub.id, expr_id) =>
+                {
+                    Ok(builder.build(ty, LvalueKind::Var(sub.id)))
+                }
                 x => {
                     cx.emit(
                         DiagBuilder2::error(format!(
@@ -149,11 +168,36 @@ fn lower_expr_inner<'a>(
         }
 
         hir::ExprKind::Index(target, mode) => {
+            // Elements of dynamic arrays, queues, and associative arrays are
+            // accessed through their handle.
+            if cx
+                .self_determined_type(target, env)
+                .map_or(false, |ty| ty.get_container().is_some())
+            {
+                let container = cx.mir_lvalue(target, env);
+                let value = cx.mir_rvalue(target, env);
+                let index = lower_container_index(cx, expr_id, value.ty, env, mode)?;
+                if container.is_error() || value.is_error() || index.is_error() {
+                    return Err(());
+                }
+                return Ok(builder.build(
+                    ty,
+                    LvalueKind::ContainerElem {
+                        container,
+                        value,
+                        index,
+                    },
+                ));
+            }
+
             // Compute the indexing parameters.
             let (base, length) = compute_indexing(cx, builder.expr, env, mode)?;
 
             // Lower the indexee and make sure it can be indexed into.
             let target = cx.mir_lvalue(target, env);
+            if target.is_error() {
+                return Err(());
+            }
             assert_span!(
                 target.ty.dims().next().is_some(),
                 target.span,
@@ -174,6 +218,35 @@ fn lower_expr_inner<'a>(
             };
             let base = adjust_indexing(&rvalue_builder, base, target_dim);
 
+            // Arrays viewed through their bits are indexed by slicing the
+            // bits of the accessed elements.
+            if let LvalueKind::Unpack(bits) = target.kind {
+                let elem_ty = target.ty.pop_dim(cx).unwrap();
+                let elem_size = elem_ty.simple_bit_vector(cx, span).size;
+                let elem_size_rv = rvalue_builder.constant_u32(elem_size as u32);
+                let base = rvalue_builder.build(
+                    base.ty,
+                    RvalueKind::IntBinaryArith {
+                        op: IntBinaryArithOp::Mul,
+                        domain: base.ty.domain(),
+                        sign: base.ty.sign(),
+                        lhs: base,
+                        rhs: elem_size_rv,
+                    },
+                );
+                let length = std::cmp::max(1, length) * elem_size;
+                let sbvt = bits.ty.simple_bit_vector(cx, span).change_size(length);
+                let bits = builder.build(
+                    sbvt.to_unpacked(cx),
+                    LvalueKind::Index {
+                        value: bits,
+                        base,
+                        length,
+                    },
+                );
+                return Ok(unpack_simple_bit_vector(builder, bits, ty));
+            }
+
             // Build the cast lvalue.
             return Ok(builder.build(
                 ty,
@@ -187,6 +260,17 @@ fn lower_expr_inner<'a>(
 
         hir::ExprKind::Field(target, name) => {
             let target_ty = cx.self_determined_type(target, env);
+            if let Some(class) = target_ty.and_then(|ty| ty.get_class()) {
+                let (slot, _) = cx
+                    .class_details(class.ast.id(), class.env)?
+                    .find_prop(name.value)
+                    .ok_or(())?;
+                let object = cx.mir_rvalue(target, env);
+                if object.is_error() {
+                    return Err(());
+                }
+                return Ok(builder.build(ty, LvalueKind::Property { object, slot }));
+            }
             let value = cx.mir_lvalue(target, env);
             if let Some(intf) = target_ty.and_then(|ty| ty.get_interface()) {
                 let def = cx.resolve_hierarchical_or_error(name, intf.ast)?;
@@ -196,8 +280,23 @@ fn lower_expr_inner<'a>(
                 } else {
                     return Ok(builder.build(ty, LvalueKind::IntfSignal(value, def.node.id())));
                 }
+            } else if let Some(module) = target_ty.and_then(|ty| ty.get_module()) {
+                // Declarations in module instances are accessed like the
+                // signals of an interface.
+                let def = cx.resolve_hierarchical_or_error(name, module.ast)?;
+                return Ok(builder.build(ty, LvalueKind::IntfSignal(value, def.node.id())));
             } else {
                 let (field, _) = cx.resolve_field_access(expr_id, env)?;
+                if value.is_error() {
+                    return Err(());
+                }
+                if let Some(strukt) = value.ty.get_union() {
+                    return union_member(builder, value, strukt, field);
+                }
+                if let LvalueKind::Unpack(bits) = value.kind {
+                    let strukt = value.ty.get_struct().unwrap();
+                    return Ok(packed_member(builder, bits, strukt, field));
+                }
                 return Ok(builder.build(ty, LvalueKind::Member { value, field }));
             }
         }
@@ -250,6 +349,29 @@ fn lower_expr_inner<'a>(
             return Ok(repeat);
         }
 
+        hir::ExprKind::StreamConcat(dir, _, ref exprs) => {
+            // Concatenate the arguments, which have been cast to their SBVT
+            // by the type context, and unpack the stream into them.
+            let values = exprs
+                .iter()
+                .map(|&expr| builder.cx.mir_lvalue(expr, env))
+                .collect();
+            let concat = builder.build(ty, LvalueKind::Concat(values));
+            let reverse = dir == ast::StreamDir::Out;
+            let slice = match reverse {
+                true => cx.stream_slice_size(expr_id, env)?,
+                false => 1,
+            };
+            return Ok(builder.build(
+                ty,
+                LvalueKind::Stream {
+                    value: concat,
+                    slice,
+                    reverse,
+                },
+            ));
+        }
+
         _ => (),
     }
 
@@ -301,6 +423,19 @@ fn lower_cast<'a>(
             CastOp::PickModport => {
                 value = builder.build(to, value.kind.clone());
             }
+            CastOp::Range(..) => {
+                builder.cx.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` of type `{}` cannot be assigned a value of type `{}`",
+                        value.span.extract(),
+                        value.ty,
+                        to
+                    ))
+                    .span(value.span)
+                    .add_note("The width of an assigned value cannot be changed implicitly"),
+                );
+                return builder.error();
+            }
             _ => {
                 bug_span!(
                     value.span,
@@ -341,13 +476,13 @@ fn pack_simple_bit_vector<'a>(
     if value.is_error() {
         return value;
     }
-    let to = value
-        .ty
-        .simple_bit_vector(builder.cx, value.span)
+    let to = packed_sbvt(builder.cx, value.ty, value.span)
         .forget()
         .to_unpacked(builder.cx);
-    if value.ty.coalesces_to_llhd_scalar() {
+    if value.ty.coalesces_to_llhd_scalar() || value.ty.get_union().is_some() || value.ty.is_real() {
         builder.build(to, LvalueKind::Transmute(value))
+    } else if let LvalueKind::Unpack(bits) = value.kind {
+        builder.build(to, LvalueKind::Transmute(bits))
     } else if let Some(dim) = value.ty.outermost_dim() {
         pack_array(builder, value, dim, to)
     } else if let Some(strukt) = value.ty.get_struct() {
@@ -362,6 +497,78 @@ fn pack_simple_bit_vector<'a>(
     }
 }
 
+/// Generate the nodes necessary to view a simple bit vector as a value of
+/// another type.
+///
+/// This is the counterpart to `pack_simple_bit_vector`, used to assign to the
+/// members of a union.
+fn unpack_simple_bit_vector<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    value: &'a Lvalue<'a>,
+    to: &'a UnpackedType<'a>,
+) -> &'a Lvalue<'a> {
+    if value.is_error() {
+        return value;
+    }
+    if to.coalesces_to_llhd_scalar() || to.get_union().is_some() || to.is_real() {
+        builder.build(to, LvalueKind::Transmute(value))
+    } else {
+        builder.build(to, LvalueKind::Unpack(value))
+    }
+}
+
+/// Access a member of a union.
+///
+/// Members of tagged unions cannot be assigned individually, since this would
+/// not update the tag.
+fn union_member<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    value: &'a Lvalue<'a>,
+    strukt: &'a ty::StructType<'a>,
+    field: usize,
+) -> Result<&'a Lvalue<'a>> {
+    if strukt.is_tagged() {
+        builder.cx.emit(
+            DiagBuilder2::error(format!(
+                "member `{}` of tagged union cannot be assigned individually",
+                strukt.members[field].name
+            ))
+            .span(builder.span)
+            .add_note("Assign a `tagged` expression to the entire union instead"),
+        );
+        return Err(());
+    }
+    let bits = pack_simple_bit_vector(builder, value);
+    Ok(packed_member(builder, bits, strukt, field))
+}
+
+/// Access a member of a struct or union through the bits it is packed into.
+fn packed_member<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    bits: &'a Lvalue<'a>,
+    strukt: &'a ty::StructType<'a>,
+    field: usize,
+) -> &'a Lvalue<'a> {
+    let member = &strukt.members[field];
+    let sbvt = packed_sbvt(builder.cx, member.ty, builder.span);
+    let rvalue_builder = lower::rvalue::Builder {
+        cx: builder.cx,
+        span: builder.span,
+        expr: builder.expr,
+        env: builder.env,
+    };
+    let base = rvalue_builder.constant_u32(strukt.member_offset(field).unwrap() as u32);
+    let bits = builder.build(
+        sbvt.to_unpacked(builder.cx),
+        LvalueKind::Index {
+            value: bits,
+            base,
+            length: sbvt.size,
+        },
+    );
+    unpack_simple_bit_vector(builder, bits, member.ty)
+}
+
 /// Pack a struct as a simple bit vector.
 fn pack_struct<'a>(
     builder: &Builder<'_, impl Context<'a>>,
@@ -4,6 +4,7 @@
 
 use crate::crate_prelude::*;
 use crate::{
+    call_mapping::CallMapping,
     hir::HirNode,
     mir::rvalue::*,
     syntax::ast::BasicNode,
@@ -12,6 +13,7 @@ use crate::{
     value::{self, ValueData, ValueKind},
     ParamEnv,
 };
+use bit_vec::BitVec;
 use num::{BigInt, One, Signed, ToPrimitive, Zero};
 use std::{cmp::max, collections::HashMap};
 
@@ -152,13 +154,32 @@ fn lower_expr_inner<'gcx>(
             x_bits.clone(),
         ))),
         hir::ExprKind::UnsizedConst('0') => Ok(builder.constant(value::make_int(ty, num::zero()))),
-        hir::ExprKind::UnsizedConst('1') => Ok(builder.constant(value::make_int(ty, num::one()))),
-        hir::ExprKind::UnsizedConst('x') => Ok(builder.constant(value::make_int(ty, num::one()))),
-        hir::ExprKind::UnsizedConst('z') => Ok(builder.constant(value::make_int(ty, num::one()))),
+        hir::ExprKind::UnsizedConst(c @ '1')
+        | hir::ExprKind::UnsizedConst(c @ 'x')
+        | hir::ExprKind::UnsizedConst(c @ 'z') => {
+            // Unsized constants fill all bits of their type.
+            let width = ty.get_bit_size().unwrap_or(1);
+            let value = match c {
+                '1' => (BigInt::one() << width) - 1,
+                _ => BigInt::zero(),
+            };
+            Ok(builder.constant(value::make_int_special(
+                ty,
+                value,
+                BitVec::from_elem(width, c != '1'),
+                BitVec::from_elem(width, c == 'x'),
+            )))
+        }
         hir::ExprKind::UnsizedConst(c) => {
             bug_span!(span, cx, "unsized const with weird '{}' char", c)
         }
-        hir::ExprKind::TimeConst(ref k) => Ok(builder.constant(value::make_time(k.clone()))),
+        hir::ExprKind::TimeConst(ref k) => {
+            let k = cx.timescale_of(expr_id).round(k);
+            Ok(builder.constant(value::make_time(k)))
+        }
+        hir::ExprKind::RealConst(ref k) => {
+            Ok(builder.constant(value::make_real(ty, k.to_f64().unwrap_or(0.0))))
+        }
         hir::ExprKind::StringConst(string) => Ok(builder.constant(value::make_int(
             // TODO: This could use `value::make_string` to build a string
             // value, and then resort to the conversion function there to map
@@ -219,15 +240,93 @@ fn lower_expr_inner<'gcx>(
                 }
             }
         }
-        hir::ExprKind::Builtin(hir::BuiltinCall::CountOnes(_))
-        | hir::ExprKind::Builtin(hir::BuiltinCall::OneHot(_))
-        | hir::ExprKind::Builtin(hir::BuiltinCall::OneHot0(_)) => {
-            bug_span!(span, cx, "unsupported system function {:?}", hir.kind)
+        hir::ExprKind::Builtin(hir::BuiltinCall::CountOnes(arg)) => {
+            lower_count_ones(builder, ty, arg.id(), env)
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::OneHot(arg))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::OneHot0(arg)) => {
+            let int_ty = ty::PackedType::make(cx, ty::IntAtomType::Int).to_unpacked(cx);
+            let count = lower_count_ones(builder, int_ty, arg.id(), env)?;
+            let one = builder.constant(value::make_int(int_ty, BigInt::one()));
+            Ok(builder.build(
+                ty,
+                RvalueKind::IntComp {
+                    op: match hir.kind {
+                        hir::ExprKind::Builtin(hir::BuiltinCall::OneHot(_)) => IntCompOp::Eq,
+                        _ => IntCompOp::Leq,
+                    },
+                    sign: ty::Sign::Signed,
+                    domain: ty::Domain::TwoValued,
+                    lhs: count,
+                    rhs: one,
+                },
+            ))
         }
         hir::ExprKind::Builtin(hir::BuiltinCall::IsUnknown(_)) => {
             // Since we currently don't emit logic types, this is always zero.
             Ok(builder.constant(value::make_int(ty, num::zero())))
         }
+        hir::ExprKind::Builtin(hir::BuiltinCall::Display(task, ref items)) => {
+            let (mut format, args) = lower_format_items(builder, items)?;
+            if task != hir::DisplayTask::Write {
+                format.push('\n');
+            }
+            Ok(builder.build(ty, RvalueKind::Display { task, format, args }))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::SFormatF(ref items)) => {
+            let (format, args) = lower_format_items(builder, items)?;
+            Ok(builder.build(ty, RvalueKind::StringFormat { format, args }))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::Finish) => {
+            Ok(builder.build(ty, RvalueKind::Intrinsic(Intrinsic::Finish)))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::Stop) => {
+            Ok(builder.build(ty, RvalueKind::Intrinsic(Intrinsic::Stop)))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::Time) => {
+            Ok(builder.build(ty, RvalueKind::Intrinsic(Intrinsic::Time)))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::RealTime) => {
+            Ok(builder.build(ty, RvalueKind::Intrinsic(Intrinsic::RealTime)))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::Random(None)) => {
+            Ok(builder.build(ty, RvalueKind::Intrinsic(Intrinsic::Random)))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::Random(Some(seed))) => {
+            lower_seeded_random(builder, ty, seed)
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::RealConv(conv, arg)) => {
+            let value = cx.mir_rvalue(arg, env);
+            if value.is_error() {
+                return Err(());
+            }
+            Ok(builder.build(
+                ty,
+                match conv {
+                    hir::RealConv::RtoI => RvalueKind::RealToInt { trunc: true, value },
+                    hir::RealConv::ItoR => RvalueKind::IntToReal(
+                        value.ty.simple_bit_vector(cx, value.span).sign,
+                        value,
+                    ),
+                    hir::RealConv::RealToBits
+                    | hir::RealConv::BitsToReal
+                    | hir::RealConv::ShortRealToBits
+                    | hir::RealConv::BitsToShortReal => RvalueKind::Transmute(value),
+                },
+            ))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::Rose(arg)) => {
+            lower_sampled(builder, ty, SampledFunc::Rose, arg)
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::Fell(arg)) => {
+            lower_sampled(builder, ty, SampledFunc::Fell, arg)
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::Stable(arg)) => {
+            lower_sampled(builder, ty, SampledFunc::Stable, arg)
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::Past(arg, _)) => {
+            lower_sampled(builder, ty, SampledFunc::Past, arg)
+        }
         hir::ExprKind::Builtin(hir::BuiltinCall::ArrayDim(func, arg, dim)) => {
             // Decide which dimension to inspect.
             let dim = match dim {
@@ -290,18 +389,45 @@ fn lower_expr_inner<'gcx>(
         hir::ExprKind::Ident(..) | hir::ExprKind::Scope(..) => {
             let binding = builder.cx.resolve_node(expr_id, env)?;
             match builder.cx.hir_of(binding)? {
-                HirNode::VarDecl(decl) => Ok(builder.build(ty, RvalueKind::Var(decl.id))),
+                HirNode::VarDecl(decl) => match property_slot(cx, expr_id, decl.id, env)? {
+                    Some(slot) => {
+                        let object = lower_this(cx, expr_id, env)?;
+                        Ok(builder.build(ty, RvalueKind::Property { object, slot }))
+                    }
+                    None => Ok(builder.build(ty, RvalueKind::Var(decl.id))),
+                },
+                HirNode::ForeachIndex(index) => Ok(builder.build(ty, RvalueKind::Var(index.id))),
+                HirNode::PatternVar(var) => Ok(builder.build(ty, RvalueKind::Var(var.id))),
                 HirNode::IntPort(port) if ty.resolve_full().core.get_interface().is_some() => {
                     Ok(builder.build(ty, RvalueKind::Intf(port.id)))
                 }
                 HirNode::IntPort(port) => Ok(builder.build(ty, RvalueKind::Port(port.id))),
-                HirNode::Inst(inst) if ty.resolve_full().core.get_interface().is_some() => {
+                HirNode::Inst(inst)
+                    if ty.resolve_full().core.get_interface().is_some()
+                        || ty.resolve_full().core.get_module().is_some() =>
+                {
                     Ok(builder.build(ty, RvalueKind::Intf(inst.id)))
                 }
                 HirNode::EnumVariant(..) | HirNode::ValueParam(..) | HirNode::GenvarDecl(..) => {
                     let k = builder.cx.constant_value_of(binding, env);
                     Ok(builder.build(ty, RvalueKind::Const(k)))
                 }
+                // Let declarations and their arguments expand to the
+                // expression they are bound to.
+                HirNode::Let(decl) => Ok(cx.mir_rvalue(decl.expr, cx.let_env(expr_id, env)?)),
+                HirNode::LetArg(arg) => match cx.param_env_data(env).find_value(arg.id) {
+                    Some(ParamEnvBinding::Indirect(assigned)) => {
+                        Ok(cx.mir_rvalue(assigned.id(), assigned.env()))
+                    }
+                    _ => bug_span!(span, cx, "{} not bound in {:?}", arg.desc_full(), env),
+                },
+                // Referring to the function name from within the function body
+                // reads the return value.
+                HirNode::Subroutine(sub)
+                    if sub.is_function() && cx.is_parent_of(sub.id, expr_id) =>
+                {
+                    Ok(builder.build(ty, RvalueKind::Var(sub.id)))
+                }
                 x => {
                     builder.cx.emit(
                         DiagBuilder2::error(format!(
@@ -338,6 +464,17 @@ fn lower_expr_inner<'gcx>(
         | hir::ExprKind::RepeatPattern(..)
         | hir::ExprKind::NamedPattern(..) => Ok(lower_pattern(&builder, hir, ty)),
 
+        hir::ExprKind::Concat(repeat, ref exprs) if ty.is_string() => {
+            // The arguments have already been cast to strings by the type
+            // context. The repetition count of strings need not be constant.
+            let values: Vec<_> = exprs.iter().map(|&expr| cx.mir_rvalue(expr, env)).collect();
+            let repeat = repeat.map(|repeat| cx.mir_rvalue(repeat, env));
+            if values.iter().chain(repeat.iter()).any(|v| v.is_error()) {
+                return Err(());
+            }
+            Ok(builder.build(ty, RvalueKind::StringConcat { repeat, values }))
+        }
+
         hir::ExprKind::Concat(repeat, ref exprs) => {
             // Compute the SBVT for each expression and lower it to MIR,
             // implicitly casting to the SBVT.
@@ -380,7 +517,52 @@ fn lower_expr_inner<'gcx>(
             Ok(repeat)
         }
 
+        hir::ExprKind::StreamConcat(dir, _, ref exprs) => {
+            // Concatenate the arguments into a bit stream. The arguments have
+            // already been cast to their SBVT by the type context.
+            let stream_ty = builder.cx.need_self_determined_type(hir.id, env);
+            if stream_ty.is_error() {
+                return Err(());
+            }
+            let values = exprs
+                .iter()
+                .map(|&expr| builder.cx.mir_rvalue(expr, env))
+                .collect();
+            let mut stream = builder.build(stream_ty, RvalueKind::Concat(values));
+
+            // Reorder the slices of right-to-left streams.
+            if dir == ast::StreamDir::Out {
+                let slice = builder.cx.stream_slice_size(hir.id, env)?;
+                stream = reverse_stream(builder, stream, slice, false);
+            }
+
+            // Left-align the stream within the target type.
+            let width = stream_ty.get_bit_size().unwrap();
+            let target_width = ty.get_bit_size().unwrap();
+            let mut fields = vec![stream];
+            if target_width > width {
+                let pad_ty = SbvType::new(ty.domain(), ty::Sign::Unsigned, target_width - width)
+                    .to_unpacked(builder.cx);
+                fields.push(builder.constant(value::make_int(pad_ty, BigInt::zero())));
+            }
+            Ok(builder.build(ty, RvalueKind::Concat(fields)))
+        }
+
         hir::ExprKind::Index(target, mode) => {
+            // Elements of dynamic arrays, queues, and associative arrays are
+            // accessed through their handle.
+            if cx
+                .self_determined_type(target, env)
+                .map_or(false, |ty| ty.get_container().is_some())
+            {
+                let container = cx.mir_rvalue(target, env);
+                let index = lower_container_index(cx, expr_id, container.ty, env, mode)?;
+                if container.is_error() || index.is_error() {
+                    return Err(());
+                }
+                return Ok(builder.build(ty, RvalueKind::ContainerElem { container, index }));
+            }
+
             let (base, length) = compute_indexing(cx, builder.expr, env, mode)?;
 
             // Cast the target to a simple bit vector type if needed.
@@ -414,6 +596,17 @@ fn lower_expr_inner<'gcx>(
 
         hir::ExprKind::Field(target, name) => {
             let target_ty = cx.self_determined_type(target, env);
+            if let Some(class) = target_ty.and_then(|ty| ty.get_class()) {
+                let (slot, _) = cx
+                    .class_details(class.ast.id(), class.env)?
+                    .find_prop(name.value)
+                    .ok_or(())?;
+                let object = cx.mir_rvalue(target, env);
+                if object.is_error() {
+                    return Err(());
+                }
+                return Ok(builder.build(ty, RvalueKind::Property { object, slot }));
+            }
             let value = cx.mir_rvalue(target, env);
             if let Some(intf) = target_ty.and_then(|ty| ty.get_interface()) {
                 let def = cx.resolve_hierarchical_or_error(name, intf.ast)?;
@@ -423,8 +616,16 @@ fn lower_expr_inner<'gcx>(
                 } else {
                     Ok(builder.build(ty, RvalueKind::IntfSignal(value, def.node.id())))
                 }
+            } else if let Some(module) = target_ty.and_then(|ty| ty.get_module()) {
+                // Declarations in module instances are accessed like the
+                // signals of an interface.
+                let def = cx.resolve_hierarchical_or_error(name, module.ast)?;
+                Ok(builder.build(ty, RvalueKind::IntfSignal(value, def.node.id())))
             } else {
                 let (field, _) = cx.resolve_field_access(expr_id, env)?;
+                if let Some(strukt) = value.ty.get_union() {
+                    return union_member(builder, value, strukt, field);
+                }
                 Ok(builder.build(ty, RvalueKind::Member { value, field }))
             }
         }
@@ -499,20 +700,735 @@ fn lower_expr_inner<'gcx>(
                 check = make_binary_bitwise(builder, ty, BinaryBitwiseOp::Or, false, check, arg);
             }
 
-            Ok(check)
-        }
+            Ok(check)
+        }
+
+        hir::ExprKind::Tagged(member, value) => lower_tagged(builder, ty, member, value),
+
+        hir::ExprKind::CaseValue { expr, first } => match first {
+            true => Ok(cx.mir_rvalue(expr, env)),
+            false => Ok(builder.build(ty, RvalueKind::Var(expr))),
+        },
+
+        hir::ExprKind::Matches(value_id, ref pattern) => {
+            let mut value = cx.mir_rvalue(value_id, env);
+            if value.is_error() {
+                return Err(());
+            }
+            let mut bindings = vec![];
+
+            // The first item of a `case ... matches` statement holds the value
+            // in a temporary, such that it is evaluated only once.
+            if let HirNode::Expr(hir::Expr {
+                kind: hir::ExprKind::CaseValue { expr, first: true },
+                ..
+            }) = cx.hir_of(value_id)?
+            {
+                bindings.push((*expr, value));
+                value = builder.build(value.ty, RvalueKind::Var(*expr));
+            }
+            let cond = lower_match_pattern(builder, ty, value, pattern, &mut bindings)?;
+            Ok(builder.build(ty, RvalueKind::Matches { cond, bindings }))
+        }
+
+        hir::ExprKind::LetCall(target, _) => match cx.hir_of(target)? {
+            HirNode::Let(decl) => Ok(cx.mir_rvalue(decl.expr, cx.let_env(expr_id, env)?)),
+            _ => unreachable!(),
+        },
+
+        hir::ExprKind::FunctionCall(target, _) => {
+            let mapping = cx.call_mapping(Ref(hir), env)?;
+            check_call_context(cx, expr_id, mapping.subroutine)?;
+            let args = lower_call_args(builder, &mapping)?;
+
+            // Calling a method from within a class calls it on `this`.
+            match cx.called_method(Ref(hir), env)? {
+                Some(method) => Ok(builder.build(
+                    ty,
+                    RvalueKind::MethodCall {
+                        object: lower_this(cx, expr_id, env)?,
+                        target,
+                        env: method.env,
+                        virt: method.virt,
+                        args,
+                    },
+                )),
+                None => Ok(builder.build(ty, RvalueKind::Call { target, args })),
+            }
+        }
+
+        hir::ExprKind::MethodCall(object, _, ref call_args) => {
+            if let Some(method) = cx.container_method(Ref(hir), env)? {
+                return lower_container_method(builder, ty, method, object, call_args);
+            }
+            if let Some(method) = cx.enum_method(Ref(hir), env)? {
+                return lower_enum_method(builder, ty, method, object, call_args);
+            }
+            if let Some(method) = cx.string_method(Ref(hir), env)? {
+                return lower_string_method(builder, ty, method, object, call_args);
+            }
+            let method = cx.called_method(Ref(hir), env)?.unwrap();
+            let mapping = cx.call_mapping(Ref(hir), env)?;
+            check_call_context(cx, expr_id, mapping.subroutine)?;
+            let args = lower_call_args(builder, &mapping)?;
+
+            // Methods called through `super` are never dispatched dynamically.
+            let is_super = match cx.hir_of(object)? {
+                HirNode::Expr(hir::Expr {
+                    kind: hir::ExprKind::Super,
+                    ..
+                }) => true,
+                _ => false,
+            };
+            let object = cx.mir_rvalue(object, env);
+            if object.is_error() {
+                return Err(());
+            }
+            Ok(builder.build(
+                ty,
+                RvalueKind::MethodCall {
+                    object,
+                    target: method.id,
+                    env: method.env,
+                    virt: method.virt && !is_super,
+                    args,
+                },
+            ))
+        }
+
+        hir::ExprKind::New(ref call_args) => {
+            let class = ty.get_class().unwrap();
+            let args = match cx.called_method(Ref(hir), env)? {
+                Some(_) => lower_call_args(builder, &*cx.call_mapping(Ref(hir), env)?)?,
+                None if !call_args.is_empty() => {
+                    cx.emit(
+                        DiagBuilder2::error(format!(
+                            "class `{}` has no constructor that accepts arguments",
+                            ty
+                        ))
+                        .span(span),
+                    );
+                    return Err(());
+                }
+                None => vec![],
+            };
+            Ok(builder.build(
+                ty,
+                RvalueKind::New {
+                    class: class.ast.id(),
+                    env: class.env,
+                    args,
+                },
+            ))
+        }
+
+        // `super` refers to the same object as `this`, but has the type of the
+        // base class.
+        hir::ExprKind::This | hir::ExprKind::Super => Ok(builder.build(ty, RvalueKind::This)),
+        hir::ExprKind::Null => Ok(builder.build(ty, RvalueKind::Null)),
+
+        // The empty queue is represented by the null handle.
+        hir::ExprKind::EmptyQueue => Ok(builder.build(ty, RvalueKind::Null)),
+
+        hir::ExprKind::ArrayNew(size, init) => {
+            let size = cx.mir_rvalue(size, env);
+            let init = init.map(|init| cx.mir_rvalue(init, env));
+            if size.is_error() || init.map_or(false, |init| init.is_error()) {
+                return Err(());
+            }
+            Ok(builder.build(ty, RvalueKind::ArrayNew { size, init }))
+        }
+
+        hir::ExprKind::Assign { op, lhs, rhs } => Ok(lower_assign(&builder, ty, op, lhs, rhs)),
+    }
+}
+
+/// Check that a subroutine may be called at the location of a call.
+fn check_call_context<'a>(
+    cx: &impl Context<'a>,
+    expr_id: NodeId,
+    subroutine: &'a hir::Subroutine,
+) -> Result<()> {
+    let span = cx.span(expr_id);
+
+    // Tasks may only be called as a statement.
+    let is_stmt = match cx.hir_of(cx.parent_node_id(expr_id).unwrap())? {
+        HirNode::Stmt(stmt) => match stmt.kind {
+            hir::StmtKind::Expr(..) => true,
+            _ => false,
+        },
+        _ => false,
+    };
+    if subroutine.is_task() && !is_stmt {
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "{} cannot be called in an expression",
+                subroutine.desc_full()
+            ))
+            .span(span)
+            .add_note("Tasks can only be called as a statement"),
+        );
+        return Err(());
+    }
+
+    // Functions cannot call tasks, since tasks may consume time.
+    if subroutine.is_task() {
+        let mut parent = cx.ast_for_id(expr_id).get_parent();
+        while let Some(node) = parent {
+            if node.as_all().is_subroutine_decl() {
+                match cx.hir_of(node.id())? {
+                    HirNode::Subroutine(caller) if caller.is_function() => {
+                        cx.emit(
+                            DiagBuilder2::error(format!(
+                                "{} cannot call {}",
+                                caller.desc_full(),
+                                subroutine.desc_full()
+                            ))
+                            .span(span)
+                            .add_note("Functions cannot call tasks"),
+                        );
+                        return Err(());
+                    }
+                    _ => break,
+                }
+            }
+            parent = node.get_parent();
+        }
+    }
+    Ok(())
+}
+
+/// Lower the arguments of a call according to their direction.
+///
+/// Arguments passed in the call are lowered in the caller's parametrization,
+/// default values in the one of the called subroutine.
+fn lower_call_args<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    mapping: &CallMapping<'a>,
+) -> Result<Vec<CallArg<'a>>> {
+    let cx = builder.cx;
+    let mut args = vec![];
+    let mut failed = false;
+    for arg in &mapping.args {
+        let env = match arg.is_default {
+            true => mapping.env,
+            false => builder.env,
+        };
+        let mir_arg = match arg.arg.dir {
+            ast::SubroutinePortDir::Input | ast::SubroutinePortDir::ConstRef => {
+                let value = cx.mir_rvalue(arg.expr, env);
+                failed |= value.is_error();
+                CallArg::Input(value)
+            }
+            dir => {
+                let lvalue = cx.mir_lvalue(arg.expr, env);
+                if lvalue.is_error() {
+                    failed = true;
+                    continue;
+                }
+                let formal_ty = cx.type_of(arg.arg.id, mapping.env)?;
+                if !call_arg_types_match(formal_ty, lvalue.ty) {
+                    cx.emit(
+                        DiagBuilder2::error(format!(
+                            "{} argument `{}` of {} has type `{}`, but `{}` has type `{}`",
+                            dir,
+                            arg.arg.name,
+                            mapping.subroutine.desc_full(),
+                            formal_ty,
+                            lvalue.span.extract(),
+                            lvalue.ty
+                        ))
+                        .span(lvalue.span)
+                        .add_note(format!(
+                            "{} arguments must be passed a variable of the same type",
+                            dir
+                        )),
+                    );
+                    failed = true;
+                    continue;
+                }
+                match dir {
+                    ast::SubroutinePortDir::Output => CallArg::Output(lvalue),
+                    ast::SubroutinePortDir::Inout => {
+                        let value = cx.mir_rvalue(arg.expr, env);
+                        failed |= value.is_error();
+                        CallArg::Inout(lvalue, value)
+                    }
+                    _ => CallArg::Ref(lvalue),
+                }
+            }
+        };
+        args.push(mir_arg);
+    }
+    if failed {
+        return Err(());
+    }
+    Ok(args)
+}
+
+/// Lower a reference to the object the method enclosing an expression is
+/// called on.
+pub(crate) fn lower_this<'a>(
+    cx: &impl Context<'a>,
+    expr_id: NodeId,
+    env: ParamEnv,
+) -> Result<&'a Rvalue<'a>> {
+    let class = match class::enclosing_class(cx, expr_id) {
+        Some(x) => x,
+        None => bug_span!(
+            cx.span(expr_id),
+            cx,
+            "class member accessed outside of class"
+        ),
+    };
+    let builder = Builder {
+        cx,
+        span: cx.span(expr_id),
+        expr: expr_id,
+        env,
+    };
+    Ok(builder.build(class::class_type(cx, class, env), RvalueKind::This))
+}
+
+/// Determine the slot of a class property referred to by name from within a
+/// class.
+///
+/// Returns `None` if the declaration is not a class property.
+pub(crate) fn property_slot<'a>(
+    cx: &impl Context<'a>,
+    expr_id: NodeId,
+    decl_id: NodeId,
+    env: ParamEnv,
+) -> Result<Option<usize>> {
+    if class::member_at(cx, expr_id, decl_id, env)?.is_none() {
+        return Ok(None);
+    }
+    let class = class::enclosing_class(cx, expr_id).unwrap();
+    Ok(cx
+        .class_details(class.id(), env)?
+        .find_prop_by_id(decl_id)
+        .map(|(slot, _)| slot))
+}
+
+/// Lower a call to a built-in method of a dynamic array, queue, or associative
+/// array.
+fn lower_container_method<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    ty: &'a UnpackedType<'a>,
+    method: container::ContainerMethod,
+    object: NodeId,
+    call_args: &[hir::CallArg],
+) -> Result<&'a Rvalue<'a>> {
+    let cx = builder.cx;
+    let env = builder.env;
+    let container = cx.mir_rvalue(object, env);
+    if container.is_error() {
+        return Err(());
+    }
+    let lvalue = if method.modifies() {
+        let lvalue = cx.mir_lvalue(object, env);
+        if lvalue.is_error() {
+            return Err(());
+        }
+        Some(lvalue)
+    } else {
+        None
+    };
+
+    // Lower the arguments. Arguments the method assigns to must have exactly
+    // the index type of the container.
+    let mut args = vec![];
+    for (i, arg) in call_args.iter().enumerate() {
+        let expr = arg.expr.unwrap();
+        if !method.assigns_arg() {
+            let value = cx.mir_rvalue(expr, env);
+            if value.is_error() {
+                return Err(());
+            }
+            args.push(CallArg::Input(value));
+            continue;
+        }
+        let lvalue = cx.mir_lvalue(expr, env);
+        if lvalue.is_error() {
+            return Err(());
+        }
+        if let Some(index_ty) = container::arg_type(cx, method, container.ty, i) {
+            if !call_arg_types_match(index_ty, lvalue.ty) {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` has type `{}`, but the index of `{}` has type `{}`",
+                        lvalue.span.extract(),
+                        lvalue.ty,
+                        container.ty,
+                        index_ty
+                    ))
+                    .span(lvalue.span)
+                    .add_note(format!(
+                        "`{}` must be passed a variable of the index type",
+                        method.llhd_name()
+                    )),
+                );
+                return Err(());
+            }
+        }
+        args.push(CallArg::Ref(lvalue));
+    }
+
+    Ok(builder.build(
+        ty,
+        RvalueKind::ContainerMethod {
+            method,
+            container,
+            lvalue,
+            args,
+        },
+    ))
+}
+
+/// Lower a call to a built-in method of a string.
+fn lower_string_method<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    ty: &'a UnpackedType<'a>,
+    method: strings::StringMethod,
+    object: NodeId,
+    call_args: &[hir::CallArg],
+) -> Result<&'a Rvalue<'a>> {
+    let cx = builder.cx;
+    let env = builder.env;
+    let string = cx.mir_rvalue(object, env);
+    if string.is_error() {
+        return Err(());
+    }
+    let lvalue = if method.modifies() {
+        let lvalue = cx.mir_lvalue(object, env);
+        if lvalue.is_error() {
+            return Err(());
+        }
+        Some(lvalue)
+    } else {
+        None
+    };
+    let args: Vec<_> = call_args
+        .iter()
+        .map(|arg| cx.mir_rvalue(arg.expr.unwrap(), env))
+        .collect();
+    if args.iter().any(|arg| arg.is_error()) {
+        return Err(());
+    }
+    Ok(builder.build(
+        ty,
+        RvalueKind::StringMethod {
+            method,
+            string,
+            lvalue,
+            args,
+        },
+    ))
+}
+
+/// Lower a call to a built-in method of an enum.
+///
+/// `next` and `prev` wrap around at the ends of the enum and return the default
+/// value if called on a value that is not one of the variants. `name` returns
+/// the empty string in that case.
+fn lower_enum_method<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    ty: &'a UnpackedType<'a>,
+    method: enums::EnumMethod,
+    object: NodeId,
+    call_args: &[hir::CallArg],
+) -> Result<&'a Rvalue<'a>> {
+    let cx = builder.cx;
+    let env = builder.env;
+    let value = cx.mir_rvalue(object, env);
+    if value.is_error() {
+        return Err(());
+    }
+    let enm = value.ty.get_enum().unwrap();
+    let int_ty = ty::PackedType::make(cx, ty::IntAtomType::Int).to_unpacked(cx);
+    let int = |x: usize| builder.constant(value::make_int(int_ty, x.into()));
+    let select = |cond, true_value: &'a Rvalue<'a>, false_value| {
+        builder.build(
+            true_value.ty,
+            RvalueKind::Ternary {
+                cond,
+                true_value,
+                false_value,
+            },
+        )
+    };
+
+    // Determine the values of the variants.
+    let mut variants = vec![];
+    for &(_, variant) in &enm.variants {
+        let konst = cx.constant_value_of(variant.id(), env);
+        if konst.is_error() {
+            return Err(());
+        }
+        let konst = konst.get_int().unwrap().clone();
+        variants.push(builder.constant(value::make_int(value.ty, konst)));
+    }
+    let count = variants.len();
+    match method {
+        enums::EnumMethod::First => return Ok(variants[0]),
+        enums::EnumMethod::Last => return Ok(variants[count - 1]),
+        enums::EnumMethod::Num => return Ok(int(count)),
+        _ => (),
+    }
+
+    // Determine the position of the value among the variants, which is the
+    // number of variants if the value is none of them.
+    let position = variants
+        .iter()
+        .enumerate()
+        .rev()
+        .fold(int(count), |rest, (i, &variant)| {
+            let cond = make_int_comparison(
+                builder,
+                UnpackedType::make_logic(),
+                value.ty,
+                IntCompOp::Eq,
+                value,
+                variant,
+            );
+            select(cond, int(i), rest)
+        });
+
+    match method {
+        enums::EnumMethod::Name => {
+            let names: Vec<_> = enm
+                .variants
+                .iter()
+                .map(|(name, _)| name.value.to_string())
+                .collect();
+            let length = names.iter().map(|name| name.len()).max().unwrap_or(0);
+            let chars_ty =
+                SbvType::new(ty::Domain::TwoValued, ty::Sign::Unsigned, 8 * length).to_unpacked(cx);
+            let chars = builder.build(
+                chars_ty,
+                RvalueKind::EnumName {
+                    index: position,
+                    names,
+                },
+            );
+            Ok(builder.build(ty, RvalueKind::UnpackString(chars)))
+        }
+        enums::EnumMethod::Next | enums::EnumMethod::Prev => {
+            let step = match call_args.first() {
+                Some(arg) => {
+                    let step = cx.mir_rvalue(arg.expr.unwrap(), env);
+                    if step.is_error() {
+                        return Err(());
+                    }
+                    step
+                }
+                None => int(1),
+            };
+
+            // Reduce the step to a forward step in `0..num()`.
+            let arith = |op, lhs, rhs| make_int_binary_arith(builder, int_ty, op, lhs, rhs);
+            let step = arith(IntBinaryArithOp::Mod, step, int(count));
+            let step = arith(IntBinaryArithOp::Add, step, int(count));
+            let step = arith(IntBinaryArithOp::Mod, step, int(count));
+            let step = match method {
+                enums::EnumMethod::Prev => arith(IntBinaryArithOp::Sub, int(count), step),
+                _ => step,
+            };
+            let target = arith(IntBinaryArithOp::Add, position, step);
+            let target = arith(IntBinaryArithOp::Mod, target, int(count));
+
+            // Select the variant at the target position.
+            let selected = variants.iter().enumerate().rev().skip(1).fold(
+                variants[count - 1],
+                |rest, (i, &variant)| {
+                    let cond = make_int_comparison(
+                        builder,
+                        UnpackedType::make_logic(),
+                        int_ty,
+                        IntCompOp::Eq,
+                        target,
+                        int(i),
+                    );
+                    select(cond, variant, rest)
+                },
+            );
+            let valid = make_int_comparison(
+                builder,
+                UnpackedType::make_logic(),
+                int_ty,
+                IntCompOp::Neq,
+                position,
+                int(count),
+            );
+            Ok(select(
+                valid,
+                selected,
+                builder.constant(value::make_int(value.ty, Zero::zero())),
+            ))
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Lower the items of a formatting system task or function into a format
+/// string and its arguments.
+fn lower_format_items<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    items: &[hir::FormatItem],
+) -> Result<(String, Vec<&'a Rvalue<'a>>)> {
+    let mut format = String::new();
+    let mut args = vec![];
+    for item in items {
+        match *item {
+            hir::FormatItem::Text(ref text) => format.push_str(&text.replace('%', "%%")),
+            hir::FormatItem::Arg(spec, arg) => {
+                let (spec, arg) = lower_format_arg(builder, spec, arg);
+                // Strings are passed as a handle to their characters, which
+                // the uppercase `%S` tells the simulator.
+                match arg.ty.is_string() {
+                    true => format.push_str(&spec.to_string().to_uppercase()),
+                    false => format.push_str(&spec.to_string()),
+                }
+                args.push(arg);
+            }
+            hir::FormatItem::ScopeName => format.push_str(
+                &resolver::hierarchical_scope_name(builder.cx, builder.expr).replace('%', "%%"),
+            ),
+        }
+    }
+    if args.iter().any(|arg| arg.is_error()) {
+        return Err(());
+    }
+    Ok((format, args))
+}
+
+/// Lower the index of an element access into a dynamic array, queue, or
+/// associative array.
+pub(crate) fn lower_container_index<'a>(
+    cx: &impl Context<'a>,
+    expr_id: NodeId,
+    container_ty: &'a UnpackedType<'a>,
+    env: ParamEnv,
+    mode: hir::IndexMode,
+) -> Result<&'a Rvalue<'a>> {
+    match mode {
+        hir::IndexMode::One(index) => Ok(cx.mir_rvalue(index, env)),
+        hir::IndexMode::Many(..) => {
+            cx.emit(
+                DiagBuilder2::error(format!("unsupported: slices of `{}`", container_ty))
+                    .span(cx.span(expr_id)),
+            );
+            Err(())
+        }
+    }
+}
+
+/// Lower a call to `$random(seed)`.
+///
+/// The seed is advanced and mapped onto the full 32 bit range as by the
+/// `uniform` generator of IEEE 1800-2017 Annex N, which the simulator also uses
+/// for `$random` without a seed: A zero seed is replaced by 259341593 and
+/// advanced as `seed * 69069 + 1`. The upper 23 bits `m` of the new seed then
+/// yield the random number `(m + 1) * 2**9 + m / 2**14 - 2**31`.
+fn lower_seeded_random<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    ty: &'a UnpackedType<'a>,
+    seed: NodeId,
+) -> Result<&'a Rvalue<'a>> {
+    let cx = builder.cx;
+    let lv = cx.mir_lvalue(seed, builder.env);
+    let rv = cx.mir_rvalue(seed, builder.env);
+    if lv.is_error() || rv.is_error() {
+        return Err(());
+    }
+    if lv.ty.get_simple_bit_vector().map(|sbv| sbv.size) != Some(32) {
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "seed of `$random` must be a 32 bit integer, but `{}` has type `{}`",
+                lv.span.extract(),
+                lv.ty
+            ))
+            .span(lv.span),
+        );
+        return Err(());
+    }
+    let int = |value: BigInt| builder.constant(value::make_int(lv.ty, value));
+    let arith = |op, lhs, rhs| make_int_binary_arith(builder, lv.ty, op, lhs, rhs);
+    let shr = |value, amount| {
+        make_shift(
+            builder,
+            lv.ty,
+            ShiftOp::Right,
+            false,
+            value,
+            int(BigInt::from(amount)),
+        )
+    };
+
+    // Advance the seed.
+    let is_zero = make_int_comparison(
+        builder,
+        UnpackedType::make_logic(),
+        lv.ty,
+        IntCompOp::Eq,
+        rv,
+        int(Zero::zero()),
+    );
+    let seed = builder.build(
+        lv.ty,
+        RvalueKind::Ternary {
+            cond: is_zero,
+            true_value: int(BigInt::from(259341593)),
+            false_value: rv,
+        },
+    );
+    let next = arith(IntBinaryArithOp::Mul, seed, int(BigInt::from(69069)));
+    let next = arith(IntBinaryArithOp::Add, next, int(BigInt::one()));
+
+    // Map the new seed to the random number.
+    let m = shr(next, 9);
+    let value = arith(IntBinaryArithOp::Add, m, int(BigInt::one()));
+    let value = arith(IntBinaryArithOp::Mul, value, int(BigInt::from(1 << 9)));
+    let value = arith(IntBinaryArithOp::Add, value, shr(m, 14));
+    let value = arith(IntBinaryArithOp::Add, value, int(BigInt::one() << 31));
+    let result = if lv.ty.is_identical(ty) {
+        value
+    } else {
+        builder.build(ty, RvalueKind::Transmute(value))
+    };
+    Ok(builder.build(
+        ty,
+        RvalueKind::Assignment {
+            lvalue: lv,
+            rvalue: next,
+            result,
+        },
+    ))
+}
 
-        hir::ExprKind::FunctionCall(..) => {
-            bug_span!(
-                span,
-                cx,
-                "lowering of {} to mir not yet supported",
-                hir.desc_full()
-            );
-        }
+/// Lower a call to one of the sampled value functions.
+fn lower_sampled<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    ty: &'a UnpackedType<'a>,
+    func: SampledFunc,
+    arg: NodeId,
+) -> Result<&'a Rvalue<'a>> {
+    let value = builder.cx.mir_rvalue(arg, builder.env);
+    if value.is_error() {
+        return Err(());
+    }
+    Ok(builder.build(ty, RvalueKind::Sampled { func, value }))
+}
 
-        hir::ExprKind::Assign { op, lhs, rhs } => Ok(lower_assign(&builder, ty, op, lhs, rhs)),
+/// Check whether the type of a variable passed to an `output`, `inout`, or
+/// `ref` argument is compatible with the argument's declared type.
+fn call_arg_types_match<'a>(formal: &'a UnpackedType<'a>, actual: &'a UnpackedType<'a>) -> bool {
+    if formal.is_identical(actual) {
+        return true;
     }
+    formal.coalesces_to_llhd_scalar()
+        && actual.coalesces_to_llhd_scalar()
+        && formal.get_bit_size() == actual.get_bit_size()
 }
 
 /// Compute the base and length of an indexing operation.
@@ -632,6 +1548,142 @@ pub(crate) fn adjust_indexing<'a>(
     }
 }
 
+/// Lower an argument of a formatting system task.
+///
+/// Resolves the default specifier according to the argument's type, and
+/// converts between integral and real values where the specifier expects the
+/// other kind of number.
+fn lower_format_arg<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    spec: hir::FormatSpec,
+    arg: NodeId,
+) -> (hir::FormatSpec, &'a Rvalue<'a>) {
+    let arg = builder.cx.mir_rvalue(arg, builder.env);
+    let spec = spec.resolve(arg.ty);
+    if arg.is_error() {
+        return (spec, arg);
+    }
+    let arg = match spec.kind {
+        hir::FormatKind::RealDecimal
+        | hir::FormatKind::RealExponential
+        | hir::FormatKind::RealGeneral
+            if !arg.ty.is_real() =>
+        {
+            let sign = arg.ty.simple_bit_vector(builder.cx, arg.span).sign;
+            let value = pack_simple_bit_vector(builder, arg);
+            builder.build(
+                UnpackedType::make_real(ty::RealType::Real),
+                RvalueKind::IntToReal(sign, value),
+            )
+        }
+        hir::FormatKind::Decimal
+        | hir::FormatKind::Hex
+        | hir::FormatKind::Octal
+        | hir::FormatKind::Binary
+            if arg.ty.is_real() =>
+        {
+            builder.build(
+                SbvType::new(ty::Domain::TwoValued, ty::Sign::Signed, 64).to_unpacked(builder.cx),
+                RvalueKind::RealToInt {
+                    trunc: false,
+                    value: arg,
+                },
+            )
+        }
+        hir::FormatKind::Time if arg.ty.is_real() => {
+            builder.build(UnpackedType::make_time(), RvalueKind::RealToTime(arg))
+        }
+        _ => arg,
+    };
+    (spec, arg)
+}
+
+/// Reorder the slices of a right-to-left bit stream.
+///
+/// Packing cuts the stream into slices starting at the LSB and concatenates
+/// them in that order, such that the least significant slice ends up at the
+/// MSB and a partial last slice at the LSB. For example, `{<<4{6'b11_0101}}`
+/// yields `6'b0101_11`. Unpacking is the inverse operation, which cuts the
+/// stream into slices starting at the MSB and concatenates them in reverse
+/// order.
+///
+/// See §11.4.14 "Streaming operators (pack/unpack)".
+pub(crate) fn reverse_stream<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    value: &'a Rvalue<'a>,
+    slice: usize,
+    unpack: bool,
+) -> &'a Rvalue<'a> {
+    let sbvt = value.ty.simple_bit_vector(builder.cx, value.span);
+    if slice >= sbvt.size {
+        return value;
+    }
+    let num_slices = (sbvt.size + slice - 1) / slice;
+    let mut slices: Vec<_> = (0..num_slices)
+        .map(|i| {
+            let length = std::cmp::min(slice, sbvt.size - i * slice);
+            let base = match unpack {
+                true => sbvt.size - i * slice - length,
+                false => i * slice,
+            };
+            builder.build(
+                SbvType::new(sbvt.domain, ty::Sign::Unsigned, length).to_unpacked(builder.cx),
+                RvalueKind::Index {
+                    value,
+                    base: builder.constant_u32(base as u32),
+                    length,
+                },
+            )
+        })
+        .collect();
+    if unpack {
+        slices.reverse();
+    }
+    builder.build(value.ty, RvalueKind::Concat(slices))
+}
+
+/// Count the bits of a value that are set, as for `$countones`.
+///
+/// Each bit is selected individually, zero-extended to the result type, and
+/// added up.
+fn lower_count_ones<'gcx>(
+    builder: &Builder<'_, impl Context<'gcx>>,
+    ty: &'gcx UnpackedType<'gcx>,
+    arg: NodeId,
+    env: ParamEnv,
+) -> Result<&'gcx Rvalue<'gcx>> {
+    let value = builder.cx.mir_rvalue(arg, env);
+    if value.is_error() {
+        return Err(());
+    }
+    let sbvt = value.ty.simple_bit_vector(builder.cx, value.span);
+    let bit_ty = SbvType::new(sbvt.domain, ty::Sign::Unsigned, 1).to_unpacked(builder.cx);
+    let width = ty.simple_bit_vector(builder.cx, builder.span).size;
+    let mut count = builder.constant(value::make_int(ty, BigInt::zero()));
+    for i in 0..sbvt.size {
+        let bit = builder.build(
+            bit_ty,
+            RvalueKind::Index {
+                value,
+                base: builder.constant_u32(i as u32),
+                length: 0,
+            },
+        );
+        let bit = builder.build(ty, RvalueKind::ZeroExtend(width, bit));
+        count = builder.build(
+            ty,
+            RvalueKind::IntBinaryArith {
+                op: IntBinaryArithOp::Add,
+                sign: ty::Sign::Signed,
+                domain: ty::Domain::TwoValued,
+                lhs: count,
+                rhs: bit,
+            },
+        );
+    }
+    Ok(count)
+}
+
 /// Generate the nodes necessary for a cast operation.
 fn lower_cast<'gcx>(
     builder: &Builder<'_, impl Context<'gcx>>,
@@ -666,6 +1718,17 @@ fn lower_cast<'gcx>(
     for &(op, to) in &to.casts {
         debug!("- {:?} from `{}` to `{}`", op, value.ty, to);
         match op {
+            CastOp::Bool if value.ty.is_real() => {
+                let zero = builder.constant(value::make_real(value.ty, 0.0));
+                value = builder.build(
+                    to,
+                    RvalueKind::RealComp {
+                        op: RealCompOp::Neq,
+                        lhs: value,
+                        rhs: zero,
+                    },
+                );
+            }
             CastOp::Bool => {
                 assert_span!(value.ty.is_simple_bit_vector(), value.span, builder.cx);
                 value = builder.build(to, RvalueKind::CastToBool(value));
@@ -709,6 +1772,9 @@ fn lower_cast<'gcx>(
             CastOp::PickModport => {
                 value = builder.build(to, value.kind.clone());
             }
+            CastOp::Upcast => {
+                value = builder.build(to, RvalueKind::Transmute(value));
+            }
             CastOp::PackString => {
                 assert_span!(to.is_simple_bit_vector(), value.span, builder.cx);
                 assert_span!(value.ty.is_string(), value.span, builder.cx);
@@ -719,6 +1785,31 @@ fn lower_cast<'gcx>(
                 assert_span!(value.ty.is_simple_bit_vector(), value.span, builder.cx);
                 value = builder.build(to, RvalueKind::UnpackString(value));
             }
+            CastOp::IntToReal => {
+                assert_span!(to.is_real(), value.span, builder.cx);
+                let sbvt = value.ty.simple_bit_vector(builder.cx, value.span);
+                value = builder.build(to, RvalueKind::IntToReal(sbvt.sign, value));
+            }
+            CastOp::RealToInt => {
+                assert_span!(to.is_simple_bit_vector(), value.span, builder.cx);
+                assert_span!(value.ty.is_real(), value.span, builder.cx);
+                value = builder.build(
+                    to,
+                    RvalueKind::RealToInt {
+                        trunc: false,
+                        value,
+                    },
+                );
+            }
+            CastOp::ResizeReal => {
+                assert_span!(to.is_real(), value.span, builder.cx);
+                assert_span!(value.ty.is_real(), value.span, builder.cx);
+                value = if value.ty.get_bit_size() == to.get_bit_size() {
+                    builder.build(to, RvalueKind::Transmute(value))
+                } else {
+                    builder.build(to, RvalueKind::ResizeReal(value))
+                };
+            }
         }
         if !value.ty.is_identical(to) {
             error!(
@@ -743,20 +1834,20 @@ fn lower_cast<'gcx>(
 
 /// Generate the nodes necessary to pack a value to its corresponding simple bit
 /// vector type.
-fn pack_simple_bit_vector<'gcx>(
+pub(crate) fn pack_simple_bit_vector<'gcx>(
     builder: &Builder<'_, impl Context<'gcx>>,
     value: &'gcx Rvalue<'gcx>,
 ) -> &'gcx Rvalue<'gcx> {
     if value.is_error() {
         return value;
     }
-    let to = value
-        .ty
-        .simple_bit_vector(builder.cx, value.span)
+    let to = packed_sbvt(builder.cx, value.ty, value.span)
         .forget()
         .to_unpacked(builder.cx);
-    if value.ty.coalesces_to_llhd_scalar() {
+    if value.ty.coalesces_to_llhd_scalar() || value.ty.get_union().is_some() || value.ty.is_real() {
         builder.build(to, RvalueKind::Transmute(value))
+    } else if value.ty.get_packed().map(|p| p.is_time()).unwrap_or(false) {
+        builder.build(to, RvalueKind::TimeToInt(value))
     } else if let Some(dim) = value.ty.outermost_dim() {
         pack_array(builder, value, dim, to)
     } else if let Some(strukt) = value.ty.get_struct() {
@@ -854,8 +1945,11 @@ fn unpack_simple_bit_vector<'a>(
     if value.is_error() {
         return value;
     }
-    if to.coalesces_to_llhd_scalar() {
+    if to.coalesces_to_llhd_scalar() || to.get_union().is_some() || to.is_real() {
         builder.build(to, RvalueKind::Transmute(value))
+    } else if to.get_packed().map(|p| p.is_time()).unwrap_or(false) {
+        let sign = value.ty.simple_bit_vector(builder.cx, value.span).sign;
+        builder.build(to, RvalueKind::IntToTime(sign, value))
     } else if let Some(dim) = to.outermost_dim() {
         unpack_array(builder, value, to, dim)
     } else if let Some(strukt) = to.get_struct() {
@@ -872,11 +1966,12 @@ fn unpack_struct<'a>(
     to: &'a UnpackedType<'a>,
     strukt: &'a ty::StructType<'a>,
 ) -> &'a Rvalue<'a> {
-    // Unpack each of the fields.
+    // Unpack each of the fields, starting with the last one, which occupies
+    // the least significant bits.
     let mut offset = 0;
     let mut unpacked_fields = vec![];
-    for field in &strukt.members {
-        let sbvt = field.ty.simple_bit_vector(builder.cx, value.span);
+    for field in strukt.members.iter().rev() {
+        let sbvt = packed_sbvt(builder.cx, field.ty, value.span);
         let ty =
             SbvType::new(ty::Domain::TwoValued, ty::Sign::Unsigned, 32).to_unpacked(builder.cx);
         let w = sbvt.size;
@@ -896,11 +1991,172 @@ fn unpack_struct<'a>(
         unpacked_fields.push(value);
         offset += w;
     }
+    unpacked_fields.reverse();
 
     // Construct the struct.
     builder.build(to, RvalueKind::ConstructStruct(unpacked_fields))
 }
 
+/// Determine the simple bit vector type a value is packed into.
+///
+/// Unpacked unions are stored as a bit vector just like packed ones, but have
+/// no simple bit vector equivalent in the type system. The same holds for
+/// reals, unpacked structs, and unpacked arrays stored in a union.
+pub(crate) fn packed_sbvt<'a>(
+    cx: &impl Context<'a>,
+    ty: &'a UnpackedType<'a>,
+    span: Span,
+) -> SbvType {
+    if let Some(sbvt) = ty.get_simple_bit_vector() {
+        return sbvt;
+    }
+    match ty.get_bit_size() {
+        Some(size) => SbvType::new(ty.domain(), ty::Sign::Unsigned, size),
+        None => bug_span!(span, cx, "`{}` has no simple bit vector equivalent", ty),
+    }
+}
+
+/// Access a member of a union.
+///
+/// The member is read from its bits within the union, and reinterpreted as the
+/// member's type.
+fn union_member<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    value: &'a Rvalue<'a>,
+    strukt: &'a ty::StructType<'a>,
+    field: usize,
+) -> Result<&'a Rvalue<'a>> {
+    let member = &strukt.members[field];
+    let sbvt = packed_sbvt(builder.cx, member.ty, builder.span);
+    if sbvt.size == 0 {
+        builder.cx.emit(
+            DiagBuilder2::error(format!("`{}` of type `void` has no value", member.name))
+                .span(builder.span),
+        );
+        return Err(());
+    }
+    let bits = pack_simple_bit_vector(builder, value);
+    let base = builder.constant_u32(strukt.member_offset(field).unwrap() as u32);
+    let bits = builder.build(
+        sbvt.to_unpacked(builder.cx),
+        RvalueKind::Index {
+            value: bits,
+            base,
+            length: sbvt.size,
+        },
+    );
+    Ok(unpack_simple_bit_vector(builder, bits, member.ty))
+}
+
+/// Lower a tagged union expression such as `tagged Valid 42`.
+///
+/// The tag occupies the most significant bits of the union, followed by the
+/// member value. Any remaining bits are zero.
+fn lower_tagged<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    ty: &'a UnpackedType<'a>,
+    member: Spanned<Name>,
+    value: Option<NodeId>,
+) -> Result<&'a Rvalue<'a>> {
+    let cx = builder.cx;
+    let strukt = ty.get_union().unwrap();
+    let (index, _) = crate::typeck::find_tagged_member(ty, member.value).ok_or(())?;
+    let sbvt = packed_sbvt(cx, ty, builder.span);
+    let tag_size = strukt.tag_size();
+    let tag_ty = SbvType::new(sbvt.domain, ty::Sign::Unsigned, tag_size).to_unpacked(cx);
+    let mut parts = vec![builder.constant(value::make_int(tag_ty, index.into()))];
+    let mut width = tag_size;
+    if let Some(value) = value {
+        let value = cx.mir_rvalue(value, builder.env);
+        if value.is_error() {
+            return Err(());
+        }
+        let bits = pack_simple_bit_vector(builder, value);
+        width += packed_sbvt(cx, value.ty, value.span).size;
+        parts.push(bits);
+    }
+    if width < sbvt.size {
+        let pad_ty = SbvType::new(sbvt.domain, ty::Sign::Unsigned, sbvt.size - width);
+        parts.push(builder.constant(value::make_int(pad_ty.to_unpacked(cx), Zero::zero())));
+    }
+    let bits = builder.build(sbvt.to_unpacked(cx), RvalueKind::Concat(parts));
+    Ok(unpack_simple_bit_vector(builder, bits, ty))
+}
+
+/// Lower the check whether a value matches a pattern.
+///
+/// Returns a boolean of type `ty` which is set if the value matches. The
+/// parts of the value matched by pattern variables are added to `bindings`.
+fn lower_match_pattern<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    ty: &'a UnpackedType<'a>,
+    value: &'a Rvalue<'a>,
+    pattern: &hir::MatchPattern,
+    bindings: &mut Vec<(NodeId, &'a Rvalue<'a>)>,
+) -> Result<&'a Rvalue<'a>> {
+    let cx = builder.cx;
+    match *pattern {
+        hir::MatchPattern::Wildcard => Ok(builder.constant(value::make_int(ty, One::one()))),
+        hir::MatchPattern::Var(id) => {
+            bindings.push((id, value));
+            Ok(builder.constant(value::make_int(ty, One::one())))
+        }
+        hir::MatchPattern::Expr(id) => {
+            let expr = cx.mir_rvalue(id, builder.env);
+            if expr.is_error() {
+                return Err(());
+            }
+            let lhs = pack_simple_bit_vector(builder, value);
+            let rhs = pack_simple_bit_vector(builder, expr);
+            Ok(make_int_comparison(
+                &builder.with(id),
+                ty,
+                lhs.ty,
+                IntCompOp::Eq,
+                lhs,
+                rhs,
+            ))
+        }
+        hir::MatchPattern::Tagged(member, ref inner) => {
+            let strukt = value.ty.get_union().unwrap();
+            let (index, _) = crate::typeck::find_tagged_member(value.ty, member.value).ok_or(())?;
+
+            // Compare the tag in the most significant bits.
+            let sbvt = packed_sbvt(cx, value.ty, value.span);
+            let tag_size = strukt.tag_size();
+            let tag_ty = SbvType::new(sbvt.domain, ty::Sign::Unsigned, tag_size).to_unpacked(cx);
+            let bits = pack_simple_bit_vector(builder, value);
+            let tag = builder.build(
+                tag_ty,
+                RvalueKind::Index {
+                    value: bits,
+                    base: builder.constant_u32((sbvt.size - tag_size) as u32),
+                    length: tag_size,
+                },
+            );
+            let expected = builder.constant(value::make_int(tag_ty, index.into()));
+            let cond = make_int_comparison(builder, ty, tag_ty, IntCompOp::Eq, tag, expected);
+
+            // Match the member value against the inner pattern.
+            match inner {
+                Some(inner) => {
+                    let member_value = union_member(builder, value, strukt, index)?;
+                    let inner = lower_match_pattern(builder, ty, member_value, inner, bindings)?;
+                    Ok(make_binary_bitwise(
+                        builder,
+                        ty,
+                        BinaryBitwiseOp::And,
+                        false,
+                        cond,
+                        inner,
+                    ))
+                }
+                None => Ok(cond),
+            }
+        }
+    }
+}
+
 /// Unpack an array from a simple bit vector.
 fn unpack_array<'a>(
     builder: &Builder<'_, impl Context<'a>>,
@@ -1012,6 +2268,9 @@ fn lower_unary<'gcx>(
 ) -> &'gcx Rvalue<'gcx> {
     // Determine the category of the operation.
     match op {
+        hir::UnaryOp::Pos | hir::UnaryOp::Neg if ty.is_real() => {
+            lower_real_unary_arith(builder, ty, op, arg)
+        }
         hir::UnaryOp::Pos | hir::UnaryOp::Neg => lower_int_unary_arith(builder, ty, op, arg),
         hir::UnaryOp::BitNot => lower_unary_bitwise(builder, ty, op, arg),
         hir::UnaryOp::LogicNot => lower_unary_logic(builder, ty, op, arg),
@@ -1037,6 +2296,15 @@ fn lower_binary<'gcx>(
     rhs: NodeId,
 ) -> &'gcx Rvalue<'gcx> {
     match op {
+        hir::BinaryOp::Add
+        | hir::BinaryOp::Sub
+        | hir::BinaryOp::Mul
+        | hir::BinaryOp::Div
+        | hir::BinaryOp::Pow
+            if ty.is_real() =>
+        {
+            lower_real_binary_arith(builder, ty, op, lhs, rhs)
+        }
         hir::BinaryOp::Add
         | hir::BinaryOp::Sub
         | hir::BinaryOp::Mul
@@ -1052,6 +2320,8 @@ fn lower_binary<'gcx>(
             let op_ty = builder.cx.need_operation_type(builder.expr, builder.env);
             if op_ty.is_string() {
                 lower_string_comparison(builder, ty, op_ty, op, lhs, rhs)
+            } else if op_ty.is_real() {
+                lower_real_comparison(builder, ty, op_ty, op, lhs, rhs)
             } else {
                 lower_int_comparison(builder, ty, op_ty, op, lhs, rhs)
             }
@@ -1221,8 +2491,14 @@ fn make_int_comparison<'a>(
     lhs: &'a Rvalue<'a>,
     rhs: &'a Rvalue<'a>,
 ) -> &'a Rvalue<'a> {
-    // Check that the operands are of the right type.
-    let sbvt = op_ty.simple_bit_vector(builder.cx, builder.span);
+    // Check that the operands are of the right type. Class handles compare as
+    // the unsigned address of the object they refer to.
+    let (sign, domain) = if op_ty.get_class().is_some() {
+        (ty::Sign::Unsigned, ty::Domain::TwoValued)
+    } else {
+        let sbvt = op_ty.simple_bit_vector(builder.cx, builder.span);
+        (sbvt.sign, sbvt.domain)
+    };
     assert_type!(lhs.ty, op_ty, builder.span, builder.cx);
     assert_type!(rhs.ty, op_ty, builder.span, builder.cx);
 
@@ -1231,14 +2507,122 @@ fn make_int_comparison<'a>(
         result_ty,
         RvalueKind::IntComp {
             op,
-            sign: sbvt.sign,
-            domain: sbvt.domain,
+            sign,
+            domain,
             lhs,
             rhs,
         },
     )
 }
 
+/// Map a floating-point unary arithmetic operator to MIR.
+fn lower_real_unary_arith<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    result_ty: &'a UnpackedType<'a>,
+    op: hir::UnaryOp,
+    arg: NodeId,
+) -> &'a Rvalue<'a> {
+    // Lower the operand.
+    let arg = builder.cx.mir_rvalue(arg, builder.env);
+    if arg.is_error() {
+        return builder.error();
+    }
+    assert_type!(arg.ty, result_ty, builder.span, builder.cx);
+
+    // Determine the operation.
+    let op = match op {
+        hir::UnaryOp::Pos => return arg,
+        hir::UnaryOp::Neg => RealUnaryArithOp::Neg,
+        _ => bug_span!(
+            builder.span,
+            builder.cx,
+            "{:?} is not a real unary arithmetic operator",
+            op
+        ),
+    };
+
+    // Assemble the node.
+    builder.build(result_ty, RvalueKind::RealUnaryArith { op, arg })
+}
+
+/// Map a floating-point binary arithmetic operator to MIR.
+fn lower_real_binary_arith<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    result_ty: &'a UnpackedType<'a>,
+    op: hir::BinaryOp,
+    lhs: NodeId,
+    rhs: NodeId,
+) -> &'a Rvalue<'a> {
+    // Lower the operands.
+    let lhs = builder.cx.mir_rvalue(lhs, builder.env);
+    let rhs = builder.cx.mir_rvalue(rhs, builder.env);
+    if lhs.is_error() || rhs.is_error() {
+        return builder.error();
+    }
+
+    // Determine the operation.
+    let op = match op {
+        hir::BinaryOp::Add => RealBinaryArithOp::Add,
+        hir::BinaryOp::Sub => RealBinaryArithOp::Sub,
+        hir::BinaryOp::Mul => RealBinaryArithOp::Mul,
+        hir::BinaryOp::Div => RealBinaryArithOp::Div,
+        hir::BinaryOp::Pow => RealBinaryArithOp::Pow,
+        _ => bug_span!(
+            builder.span,
+            builder.cx,
+            "{:?} is not a real binary arithmetic operator",
+            op
+        ),
+    };
+
+    // Check that the operands are of the right type.
+    assert_type!(lhs.ty, result_ty, builder.span, builder.cx);
+    assert_type!(rhs.ty, result_ty, builder.span, builder.cx);
+
+    // Assemble the node.
+    builder.build(result_ty, RvalueKind::RealBinaryArith { op, lhs, rhs })
+}
+
+/// Map a floating-point comparison operator to MIR.
+fn lower_real_comparison<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    result_ty: &'a UnpackedType<'a>,
+    op_ty: &'a UnpackedType<'a>,
+    op: hir::BinaryOp,
+    lhs: NodeId,
+    rhs: NodeId,
+) -> &'a Rvalue<'a> {
+    // Lower the operands.
+    let lhs = builder.cx.mir_rvalue(lhs, builder.env);
+    let rhs = builder.cx.mir_rvalue(rhs, builder.env);
+    if lhs.is_error() || rhs.is_error() || op_ty.is_error() {
+        return builder.error();
+    }
+
+    // Determine the operation.
+    let op = match op {
+        hir::BinaryOp::Eq => RealCompOp::Eq,
+        hir::BinaryOp::Neq => RealCompOp::Neq,
+        hir::BinaryOp::Lt => RealCompOp::Lt,
+        hir::BinaryOp::Leq => RealCompOp::Leq,
+        hir::BinaryOp::Gt => RealCompOp::Gt,
+        hir::BinaryOp::Geq => RealCompOp::Geq,
+        _ => bug_span!(
+            builder.span,
+            builder.cx,
+            "{:?} is not a real comparison operator",
+            op
+        ),
+    };
+
+    // Check that the operands are of the right type.
+    assert_type!(lhs.ty, op_ty, builder.span, builder.cx);
+    assert_type!(rhs.ty, op_ty, builder.span, builder.cx);
+
+    // Assemble the node.
+    builder.build(result_ty, RvalueKind::RealComp { op, lhs, rhs })
+}
+
 /// Map a string comparison operator to MIR.
 fn lower_string_comparison<'a>(
     builder: &Builder<'_, impl Context<'a>>,
@@ -1259,6 +2643,10 @@ fn lower_string_comparison<'a>(
     let op = match op {
         hir::BinaryOp::Eq => StringCompOp::Eq,
         hir::BinaryOp::Neq => StringCompOp::Neq,
+        hir::BinaryOp::Lt => StringCompOp::Lt,
+        hir::BinaryOp::Leq => StringCompOp::Leq,
+        hir::BinaryOp::Gt => StringCompOp::Gt,
+        hir::BinaryOp::Geq => StringCompOp::Geq,
         _ => bug_span!(
             builder.span,
             builder.cx,
@@ -1572,8 +2960,23 @@ fn lower_int_incdec<'gcx>(
     }
 
     // Compute the new value, depending on the operand type.
-    let sbvt = lv.ty.simple_bit_vector(builder.cx, builder.span);
-    let new = if sbvt.size == 1 {
+    let new = if lv.ty.is_real() {
+        // Real values add/subtract one.
+        let op = match op {
+            hir::UnaryOp::PreInc | hir::UnaryOp::PostInc => RealBinaryArithOp::Add,
+            hir::UnaryOp::PreDec | hir::UnaryOp::PostDec => RealBinaryArithOp::Sub,
+            _ => unreachable!(),
+        };
+        let one = builder.constant(value::make_real(lv.ty, 1.0));
+        builder.build(
+            lv.ty,
+            RvalueKind::RealBinaryArith {
+                op,
+                lhs: rv,
+                rhs: one,
+            },
+        )
+    } else if lv.ty.simple_bit_vector(builder.cx, builder.span).size == 1 {
         // Single bit values simply toggle the bit.
         builder.build(
             lv.ty,
@@ -1584,6 +2987,7 @@ fn lower_int_incdec<'gcx>(
         )
     } else {
         // Bit vector values add/subtract one.
+        let sbvt = lv.ty.simple_bit_vector(builder.cx, builder.span);
         let op = match op {
             hir::UnaryOp::PreInc | hir::UnaryOp::PostInc => IntBinaryArithOp::Add,
             hir::UnaryOp::PreDec | hir::UnaryOp::PostDec => IntBinaryArithOp::Sub,
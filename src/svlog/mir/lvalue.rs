@@ -109,12 +109,38 @@ impl<'a> Print for Lvalue<'a> {
             LvalueKind::Member { value, field } => {
                 write!(inner, "{}.{}", ctx.print(outer, value), field)?
             }
+            LvalueKind::Property { object, slot } => {
+                write!(inner, "Property({}, {})", ctx.print(outer, object), slot)?
+            }
+            LvalueKind::ContainerElem {
+                container, index, ..
+            } => write!(
+                inner,
+                "{}[{}]",
+                ctx.print(outer, container),
+                ctx.print(outer, index)
+            )?,
             LvalueKind::Concat(ref args) => {
                 write!(inner, "Concat({})", ctx.print_comma_separated(outer, args))?
             }
             LvalueKind::Repeat(num, arg) => {
                 write!(inner, "Repeat({} x {})", num, ctx.print(outer, arg))?
             }
+            LvalueKind::Unpack(arg) => write!(inner, "Unpack({})", ctx.print(outer, arg))?,
+            LvalueKind::Stream {
+                value,
+                slice,
+                reverse,
+            } => write!(
+                inner,
+                "Stream{}({})",
+                if reverse {
+                    format!("Reverse{}", slice)
+                } else {
+                    String::new()
+                },
+                ctx.print(outer, value)
+            )?,
             LvalueKind::Error => write!(inner, "<error>")?,
         }
         write!(inner, " : {}", self.ty)?;
@@ -140,9 +166,10 @@ pub enum LvalueKind<'a> {
     Var(NodeId),
     /// A reference to a port declaration.
     Port(NodeId),
-    /// A reference to an interface.
+    /// A reference to an interface or module instance.
     Intf(NodeId),
-    /// A reference to an interface's signal.
+    /// A reference to an interface's signal, or a declaration of a module
+    /// instance.
     IntfSignal(&'a Lvalue<'a>, NodeId),
     /// A bit- or part-select.
     Index {
@@ -152,6 +179,18 @@ pub enum LvalueKind<'a> {
     },
     /// A struct field access.
     Member { value: &'a Lvalue<'a>, field: usize },
+    /// A property of an object, stored in the given slot of the object.
+    Property { object: &'a Rvalue<'a>, slot: usize },
+    /// An element of a dynamic array, queue, or associative array.
+    ///
+    /// Accessing an element of an associative array as an lvalue creates it,
+    /// which updates the container through `container`. The current container
+    /// is read through `value`.
+    ContainerElem {
+        container: &'a Lvalue<'a>,
+        value: &'a Rvalue<'a>,
+        index: &'a Rvalue<'a>,
+    },
     /// Concatenate multiple values.
     ///
     /// The values are cast to and treated as packed bit vectors, and the result
@@ -164,6 +203,23 @@ pub enum LvalueKind<'a> {
     /// The value is cast to and treated as a packed bit vector, and the result
     /// is yet another packed bit vector.
     Repeat(usize, &'a Lvalue<'a>),
+    /// A simple bit vector viewed as a value of a struct or array type.
+    ///
+    /// This is the target of an assignment to a struct member of a union.
+    /// Accesses to the fields and elements of the value are mapped to the
+    /// corresponding bits.
+    Unpack(&'a Lvalue<'a>),
+    /// Unpack a bit stream into a value.
+    ///
+    /// This is the target of an assignment to a streaming concatenation. The
+    /// value is treated as a packed bit vector and assigned the left-most bits
+    /// of the stream. If `reverse` is set, the stream is cut into slices of
+    /// `slice` bits, which are assigned in reverse order.
+    Stream {
+        value: &'a Lvalue<'a>,
+        slice: usize,
+        reverse: bool,
+    },
     /// An error occurred during lowering.
     Error,
 }
@@ -7,11 +7,13 @@
 
 use crate::crate_prelude::*;
 use crate::{
+    container::ContainerMethod,
     mir::{
         lvalue::Lvalue,
         print::{Context, Print},
         visit::{AcceptVisitor, Visitor, WalkVisitor},
     },
+    strings::StringMethod,
     ty::{Domain, Sign, UnpackedType},
     ParamEnv,
 };
@@ -163,6 +165,34 @@ impl<'a> Print for Rvalue<'a> {
                 sign,
                 domain
             )?,
+            RvalueKind::IntToReal(sign, arg) => {
+                write!(inner, "IntToReal({}, {})", sign, ctx.print(outer, arg))?
+            }
+            RvalueKind::RealToInt { trunc, value } => write!(
+                inner,
+                "RealToInt({}, {})",
+                if trunc { "trunc" } else { "round" },
+                ctx.print(outer, value)
+            )?,
+            RvalueKind::ResizeReal(arg) => write!(inner, "ResizeReal({})", ctx.print(outer, arg))?,
+            RvalueKind::RealToTime(arg) => write!(inner, "RealToTime({})", ctx.print(outer, arg))?,
+            RvalueKind::RealUnaryArith { op, arg } => {
+                write!(inner, "RealUnaryArith {:?} {}", op, ctx.print(outer, arg))?
+            }
+            RvalueKind::RealBinaryArith { op, lhs, rhs } => write!(
+                inner,
+                "RealBinaryArith {} {:?} {}",
+                ctx.print(outer, lhs),
+                op,
+                ctx.print(outer, rhs)
+            )?,
+            RvalueKind::RealComp { op, lhs, rhs } => write!(
+                inner,
+                "RealComp {} {:?} {}",
+                ctx.print(outer, lhs),
+                op,
+                ctx.print(outer, rhs)
+            )?,
             RvalueKind::Concat(ref args) => {
                 write!(inner, "Concat({})", ctx.print_comma_separated(outer, args))?
             }
@@ -242,6 +272,10 @@ impl<'a> Print for Rvalue<'a> {
             RvalueKind::UnpackString(arg) => {
                 write!(inner, "UnpackString({})", ctx.print(outer, arg))?
             }
+            RvalueKind::IntToTime(sign, arg) => {
+                write!(inner, "IntToTime({}, {})", sign, ctx.print(outer, arg))?
+            }
+            RvalueKind::TimeToInt(arg) => write!(inner, "TimeToInt({})", ctx.print(outer, arg))?,
             RvalueKind::StringComp { op, lhs, rhs } => write!(
                 inner,
                 "StringComp {} {:?} {}",
@@ -249,6 +283,118 @@ impl<'a> Print for Rvalue<'a> {
                 op,
                 ctx.print(outer, rhs)
             )?,
+            RvalueKind::Display {
+                task,
+                ref format,
+                ref args,
+            } => write!(
+                inner,
+                "{}({:?}, {})",
+                task,
+                format,
+                ctx.print_comma_separated(outer, args)
+            )?,
+            RvalueKind::StringFormat {
+                ref format,
+                ref args,
+            } => write!(
+                inner,
+                "StringFormat({:?}, {})",
+                format,
+                ctx.print_comma_separated(outer, args)
+            )?,
+            RvalueKind::Intrinsic(op) => write!(inner, "Intrinsic {:?}", op)?,
+            RvalueKind::Sampled { func, value } => {
+                write!(inner, "{:?}({})", func, ctx.print(outer, value))?
+            }
+            RvalueKind::Call { target, ref args } => write!(
+                inner,
+                "Call {:?}({})",
+                target,
+                print_call_args(outer, ctx, args)
+            )?,
+            RvalueKind::This => write!(inner, "This")?,
+            RvalueKind::Null => write!(inner, "Null")?,
+            RvalueKind::Property { object, slot } => {
+                write!(inner, "Property({}, {})", ctx.print(outer, object), slot)?
+            }
+            RvalueKind::New {
+                class, ref args, ..
+            } => write!(
+                inner,
+                "New {:?}({})",
+                class,
+                print_call_args(outer, ctx, args)
+            )?,
+            RvalueKind::MethodCall {
+                object,
+                target,
+                virt,
+                ref args,
+                ..
+            } => write!(
+                inner,
+                "MethodCall{} {}.{:?}({})",
+                if virt { " virtual" } else { "" },
+                ctx.print(outer, object),
+                target,
+                print_call_args(outer, ctx, args)
+            )?,
+            RvalueKind::ArrayNew { size, init } => {
+                write!(inner, "ArrayNew[{}]", ctx.print(outer, size))?;
+                if let Some(init) = init {
+                    write!(inner, "({})", ctx.print(outer, init))?;
+                }
+            }
+            RvalueKind::ContainerElem { container, index } => write!(
+                inner,
+                "{}[{}]",
+                ctx.print(outer, container),
+                ctx.print(outer, index)
+            )?,
+            RvalueKind::ContainerMethod {
+                method,
+                container,
+                ref args,
+                ..
+            } => write!(
+                inner,
+                "{}.{}({})",
+                ctx.print(outer, container),
+                method.llhd_name(),
+                print_call_args(outer, ctx, args)
+            )?,
+            RvalueKind::StringConcat { repeat, ref values } => {
+                if let Some(repeat) = repeat {
+                    write!(inner, "{} x ", ctx.print(outer, repeat))?;
+                }
+                write!(
+                    inner,
+                    "StringConcat({})",
+                    ctx.print_comma_separated(outer, values)
+                )?
+            }
+            RvalueKind::StringMethod {
+                method,
+                string,
+                ref args,
+                ..
+            } => write!(
+                inner,
+                "{}.{}({})",
+                ctx.print(outer, string),
+                method.llhd_name(),
+                ctx.print_comma_separated(outer, args)
+            )?,
+            RvalueKind::EnumName { index, ref names } => {
+                write!(inner, "EnumName {} {:?}", ctx.print(outer, index), names)?
+            }
+            RvalueKind::Matches { cond, ref bindings } => {
+                write!(inner, "Matches {}", ctx.print(outer, cond))?;
+                for &(var, value) in bindings {
+                    write!(inner, " {:?}={}", var, ctx.print(outer, value))?;
+                }
+            }
             RvalueKind::Error => write!(inner, "<error>")?,
         }
         write!(inner, " : {}", self.ty)?;
@@ -256,6 +402,21 @@ impl<'a> Print for Rvalue<'a> {
     }
 }
 
+/// Print the arguments of a call.
+fn print_call_args(outer: &mut impl Write, ctx: &mut Context, args: &[CallArg]) -> String {
+    args.iter()
+        .map(|arg| match *arg {
+            CallArg::Input(v) => format!("input {}", ctx.print(outer, v)),
+            CallArg::Output(v) => format!("output {}", ctx.print(outer, v)),
+            CallArg::Inout(lv, rv) => {
+                format!("inout {}/{}", ctx.print(outer, lv), ctx.print(outer, rv))
+            }
+            CallArg::Ref(v) => format!("ref {}", ctx.print(outer, v)),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// The different forms an rvalue expression may take.
 #[moore_derive::visit_without_foreach]
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -340,6 +501,35 @@ pub enum RvalueKind<'a> {
         lhs: &'a Rvalue<'a>,
         rhs: &'a Rvalue<'a>,
     },
+    /// A conversion from an integral value to a floating-point value, rounding
+    /// to the nearest representable number. Unknown bits are treated as zero.
+    IntToReal(Sign, &'a Rvalue<'a>),
+    /// A conversion from a floating-point value to an integral value of the
+    /// rvalue's type. Rounds to the nearest integer, with ties away from zero,
+    /// or truncates towards zero if `trunc` is set.
+    RealToInt { trunc: bool, value: &'a Rvalue<'a> },
+    /// A conversion between `real` and `shortreal`.
+    ResizeReal(&'a Rvalue<'a>),
+    /// Convert a floating-point value to a `time`, multiplying it by the time
+    /// unit in effect for the rvalue.
+    RealToTime(&'a Rvalue<'a>),
+    /// A floating-point unary arithmetic operator.
+    RealUnaryArith {
+        op: RealUnaryArithOp,
+        arg: &'a Rvalue<'a>,
+    },
+    /// A floating-point binary arithmetic operator.
+    RealBinaryArith {
+        op: RealBinaryArithOp,
+        lhs: &'a Rvalue<'a>,
+        rhs: &'a Rvalue<'a>,
+    },
+    /// A floating-point comparison operator.
+    RealComp {
+        op: RealCompOp,
+        lhs: &'a Rvalue<'a>,
+        rhs: &'a Rvalue<'a>,
+    },
     /// Concatenate multiple values.
     ///
     /// The values are cast to and treated as packed bit vectors, and the result
@@ -357,9 +547,10 @@ pub enum RvalueKind<'a> {
     Var(NodeId),
     /// A reference to a port declaration.
     Port(NodeId),
-    /// A reference to an interface.
+    /// A reference to an interface or module instance.
     Intf(NodeId),
-    /// A reference to a locally instantiated interface signal.
+    /// A reference to a signal of a locally instantiated interface, or a
+    /// declaration of a locally instantiated module.
     IntfSignal(&'a Rvalue<'a>, NodeId),
     /// A bit- or part-select.
     Index {
@@ -401,12 +592,135 @@ pub enum RvalueKind<'a> {
     PackString(&'a Rvalue<'a>),
     /// Unpack a string value from a fixed-size packed bit vector.
     UnpackString(&'a Rvalue<'a>),
+    /// Convert an integer to a `time`, multiplying it by the time unit in
+    /// effect for the rvalue.
+    IntToTime(ty::Sign, &'a Rvalue<'a>),
+    /// Convert a `time` to an integer, dividing it by the time unit in effect
+    /// for the rvalue.
+    TimeToInt(&'a Rvalue<'a>),
     /// A string comparison operator.
     StringComp {
         op: StringCompOp,
         lhs: &'a Rvalue<'a>,
         rhs: &'a Rvalue<'a>,
     },
+    /// A call to one of the display or severity system tasks.
+    ///
+    /// The format string contains one specifier for each argument, and a
+    /// trailing newline if the task prints one.
+    Display {
+        task: hir::DisplayTask,
+        format: String,
+        args: Vec<&'a Rvalue<'a>>,
+    },
+    /// A call to `$sformatf`, which formats its arguments into a string.
+    ///
+    /// The format string contains one specifier for each argument.
+    StringFormat {
+        format: String,
+        args: Vec<&'a Rvalue<'a>>,
+    },
+    /// A call to a simulator intrinsic.
+    Intrinsic(Intrinsic),
+    /// A call to one of the sampled value functions, such as `$rose(x)`.
+    ///
+    /// These compare the current value against the value sampled at an
+    /// earlier tick of the clock of the enclosing concurrent assertion.
+    Sampled {
+        func: SampledFunc,
+        value: &'a Rvalue<'a>,
+    },
+    /// A call to a function or task.
+    ///
+    /// The arguments are in the order of the subroutine's ports, with defaults
+    /// already filled in.
+    Call {
+        target: NodeId,
+        args: Vec<CallArg<'a>>,
+    },
+    /// The object a method is called on, as in `this`.
+    This,
+    /// A class handle that refers to no object, as in `null`, or an empty
+    /// dynamic array, queue, or associative array, as in `{}`.
+    Null,
+    /// A property of an object, stored in the given slot of the object.
+    Property { object: &'a Rvalue<'a>, slot: usize },
+    /// The creation of an object, as in `new(...)`.
+    ///
+    /// The arguments are those of the constructor of the class, or one of its
+    /// base classes, with defaults already filled in.
+    New {
+        class: NodeId,
+        env: ParamEnv,
+        args: Vec<CallArg<'a>>,
+    },
+    /// A call to a method of an object.
+    ///
+    /// Calls to virtual methods call the method overriding the target in the
+    /// class the object was created as. The arguments are in the order of the
+    /// method's ports, with defaults already filled in.
+    MethodCall {
+        object: &'a Rvalue<'a>,
+        target: NodeId,
+        env: ParamEnv,
+        virt: bool,
+        args: Vec<CallArg<'a>>,
+    },
+    /// The allocation of a dynamic array, as in `new[size]` or
+    /// `new[size](init)`.
+    ///
+    /// The elements are copied from `init` as far as they fit.
+    ArrayNew {
+        size: &'a Rvalue<'a>,
+        init: Option<&'a Rvalue<'a>>,
+    },
+    /// An element of a dynamic array, queue, or associative array.
+    ContainerElem {
+        container: &'a Rvalue<'a>,
+        index: &'a Rvalue<'a>,
+    },
+    /// A call to a built-in method of a dynamic array, queue, or associative
+    /// array.
+    ///
+    /// Methods which modify the container assign the updated container to
+    /// `lvalue`. Arguments the method assigns to are passed by reference.
+    ContainerMethod {
+        method: ContainerMethod,
+        container: &'a Rvalue<'a>,
+        lvalue: Option<&'a Lvalue<'a>>,
+        args: Vec<CallArg<'a>>,
+    },
+    /// A concatenation of strings, optionally repeated a number of times
+    /// determined at runtime.
+    StringConcat {
+        repeat: Option<&'a Rvalue<'a>>,
+        values: Vec<&'a Rvalue<'a>>,
+    },
+    /// A call to a built-in method of a string.
+    ///
+    /// Methods which modify the string assign the updated string to `lvalue`.
+    StringMethod {
+        method: StringMethod,
+        string: &'a Rvalue<'a>,
+        lvalue: Option<&'a Lvalue<'a>>,
+        args: Vec<&'a Rvalue<'a>>,
+    },
+    /// The name of an enum variant, as returned by `name()`.
+    ///
+    /// Evaluates to the characters of the string at `index` in `names` as a
+    /// packed bit vector, or zero if `index` is out of bounds.
+    EnumName {
+        index: &'a Rvalue<'a>,
+        names: Vec<String>,
+    },
+    /// A pattern match, as in `a matches tagged Valid .v`.
+    ///
+    /// Evaluates to `cond`. The pattern variables in `bindings` are assigned
+    /// the part of the value they match beforehand.
+    Matches {
+        cond: &'a Rvalue<'a>,
+        bindings: Vec<(NodeId, &'a Rvalue<'a>)>,
+    },
     /// An error occurred during lowering.
     Error,
 }
@@ -433,16 +747,25 @@ impl<'a> RvalueKind<'a> {
             | RvalueKind::Repeat(_, value)
             | RvalueKind::Member { value, .. }
             | RvalueKind::PackString(value)
-            | RvalueKind::UnpackString(value) => value.is_const(),
+            | RvalueKind::UnpackString(value)
+            | RvalueKind::IntToTime(_, value)
+            | RvalueKind::TimeToInt(value)
+            | RvalueKind::IntToReal(_, value)
+            | RvalueKind::RealToInt { value, .. }
+            | RvalueKind::ResizeReal(value)
+            | RvalueKind::RealToTime(value) => value.is_const(),
             RvalueKind::ConstructArray(values) => values.values().all(|v| v.is_const()),
             RvalueKind::ConstructStruct(values) => values.iter().all(|v| v.is_const()),
             RvalueKind::Const(_) => true,
             RvalueKind::UnaryBitwise { arg, .. }
             | RvalueKind::IntUnaryArith { arg, .. }
+            | RvalueKind::RealUnaryArith { arg, .. }
             | RvalueKind::Reduction { arg, .. } => arg.is_const(),
             RvalueKind::BinaryBitwise { lhs, rhs, .. }
             | RvalueKind::IntBinaryArith { lhs, rhs, .. }
             | RvalueKind::IntComp { lhs, rhs, .. }
+            | RvalueKind::RealBinaryArith { lhs, rhs, .. }
+            | RvalueKind::RealComp { lhs, rhs, .. }
             | RvalueKind::StringComp { lhs, rhs, .. } => lhs.is_const() && rhs.is_const(),
             RvalueKind::Concat(values) => values.iter().all(|v| v.is_const()),
             RvalueKind::Var(_) => false,
@@ -458,11 +781,91 @@ impl<'a> RvalueKind<'a> {
             } => cond.is_const() && true_value.is_const() && false_value.is_const(),
             RvalueKind::Shift { value, amount, .. } => value.is_const() && amount.is_const(),
             RvalueKind::Assignment { .. } => false,
+            RvalueKind::StringConcat { repeat, ref values } => {
+                repeat.map(|r| r.is_const()).unwrap_or(true) && values.iter().all(|v| v.is_const())
+            }
+            RvalueKind::Display { .. }
+            | RvalueKind::StringFormat { .. }
+            | RvalueKind::StringMethod { .. }
+            | RvalueKind::Intrinsic(..)
+            | RvalueKind::Sampled { .. }
+            | RvalueKind::Call { .. } => false,
+            RvalueKind::This
+            | RvalueKind::Null
+            | RvalueKind::Property { .. }
+            | RvalueKind::New { .. }
+            | RvalueKind::MethodCall { .. }
+            | RvalueKind::ArrayNew { .. }
+            | RvalueKind::ContainerElem { .. }
+            | RvalueKind::ContainerMethod { .. }
+            | RvalueKind::EnumName { .. }
+            | RvalueKind::Matches { .. } => false,
             RvalueKind::Error => true,
         }
     }
 }
 
+/// The intrinsic operations provided by the simulator.
+#[moore_derive::visit_without_foreach]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intrinsic {
+    /// Terminate the simulation, as in `$finish`.
+    Finish,
+    /// Suspend the simulation, as in `$stop`.
+    Stop,
+    /// Read the current simulation time, rounded to an integer multiple of the
+    /// time unit, as in `$time`.
+    Time,
+    /// Read the current simulation time as a multiple of the time unit, as in
+    /// `$realtime`.
+    RealTime,
+    /// Produce a random number from the simulator's seed, as in `$random`.
+    /// The seed is advanced like the one passed to `$random(seed)`.
+    Random,
+}
+
+impl Intrinsic {
+    /// Get the name of the LLHD function which implements this intrinsic.
+    pub fn llhd_name(self) -> &'static str {
+        match self {
+            Intrinsic::Finish => "llhd.finish",
+            Intrinsic::Stop => "llhd.stop",
+            Intrinsic::Time => "llhd.time",
+            Intrinsic::RealTime => "llhd.realtime",
+            Intrinsic::Random => "llhd.random",
+        }
+    }
+}
+
+/// The sampled value functions.
+#[moore_derive::visit_without_foreach]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampledFunc {
+    /// Check whether the LSB changed to 1, as in `$rose(x)`.
+    Rose,
+    /// Check whether the LSB changed to 0, as in `$fell(x)`.
+    Fell,
+    /// Check whether the value did not change, as in `$stable(x)`.
+    Stable,
+    /// The value a number of clock ticks ago, as in `$past(x)`.
+    Past,
+}
+
+/// An argument passed to a function or task call.
+#[moore_derive::visit_without_foreach]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CallArg<'a> {
+    /// An `input` or `const ref` argument, passed by value.
+    Input(&'a Rvalue<'a>),
+    /// An `output` argument, assigned when the subroutine returns.
+    Output(&'a Lvalue<'a>),
+    /// An `inout` argument, passed by value and assigned when the subroutine
+    /// returns.
+    Inout(&'a Lvalue<'a>, &'a Rvalue<'a>),
+    /// A `ref` argument, passed by reference.
+    Ref(&'a Lvalue<'a>),
+}
+
 /// The unary bitwise operators.
 #[moore_derive::visit_without_foreach]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -515,6 +918,66 @@ pub enum IntCompOp {
     Geq,
 }
 
+/// The floating-point unary arithmetic operators.
+#[moore_derive::visit_without_foreach]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum RealUnaryArithOp {
+    Neg,
+}
+
+/// The floating-point binary arithmetic operators.
+#[moore_derive::visit_without_foreach]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum RealBinaryArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+impl RealBinaryArithOp {
+    /// Get the name of the LLHD function which implements this operator.
+    pub fn llhd_name(self) -> &'static str {
+        match self {
+            RealBinaryArithOp::Add => "llhd.real.add",
+            RealBinaryArithOp::Sub => "llhd.real.sub",
+            RealBinaryArithOp::Mul => "llhd.real.mul",
+            RealBinaryArithOp::Div => "llhd.real.div",
+            RealBinaryArithOp::Pow => "llhd.real.pow",
+        }
+    }
+}
+
+/// The floating-point comparison operators.
+#[moore_derive::visit_without_foreach]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum RealCompOp {
+    Eq,
+    Neq,
+    Lt,
+    Leq,
+    Gt,
+    Geq,
+}
+
+impl RealCompOp {
+    /// Get the name of the LLHD function which implements this operator.
+    pub fn llhd_name(self) -> &'static str {
+        match self {
+            RealCompOp::Eq => "llhd.real.eq",
+            RealCompOp::Neq => "llhd.real.neq",
+            RealCompOp::Lt => "llhd.real.lt",
+            RealCompOp::Leq => "llhd.real.leq",
+            RealCompOp::Gt => "llhd.real.gt",
+            RealCompOp::Geq => "llhd.real.geq",
+        }
+    }
+}
+
 /// The string comparison operators.
 #[moore_derive::visit_without_foreach]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -522,6 +985,10 @@ pub enum IntCompOp {
 pub enum StringCompOp {
     Eq,
     Neq,
+    Lt,
+    Leq,
+    Gt,
+    Geq,
 }
 
 /// The shift operators.
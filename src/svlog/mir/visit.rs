@@ -31,6 +31,10 @@ impl<'a> WalkVisitor<'a> for ty::UnpackedType<'a> {}
 impl<'a> WalkVisitor<'a> for ty::Sign {}
 impl<'a> WalkVisitor<'a> for ty::Domain {}
 impl<'a> WalkVisitor<'a> for value::Value<'_> {}
+impl<'a> WalkVisitor<'a> for String {}
+impl<'a> WalkVisitor<'a> for crate::hir::DisplayTask {}
+impl<'a> WalkVisitor<'a> for crate::container::ContainerMethod {}
+impl<'a> WalkVisitor<'a> for crate::strings::StringMethod {}
 
 impl<'a, T: WalkVisitor<'a>> WalkVisitor<'a> for &'_ T {
     fn walk(&'a self, visitor: &mut dyn Visitor<'a>) {
@@ -46,6 +50,21 @@ impl<'a, T: WalkVisitor<'a>> WalkVisitor<'a> for Vec<T> {
     }
 }
 
+impl<'a, A: WalkVisitor<'a>, B: WalkVisitor<'a>> WalkVisitor<'a> for (A, B) {
+    fn walk(&'a self, visitor: &mut dyn Visitor<'a>) {
+        self.0.walk(visitor);
+        self.1.walk(visitor);
+    }
+}
+
+impl<'a, T: WalkVisitor<'a>> WalkVisitor<'a> for Option<T> {
+    fn walk(&'a self, visitor: &mut dyn Visitor<'a>) {
+        if let Some(x) = self {
+            x.walk(visitor);
+        }
+    }
+}
+
 impl<'a, K, T: WalkVisitor<'a>> WalkVisitor<'a> for HashMap<K, T> {
     fn walk(&'a self, visitor: &mut dyn Visitor<'a>) {
         for x in self.values() {
@@ -5,7 +5,7 @@
 use crate::{
     ast_map::AstNode,
     crate_prelude::*,
-    hir::{NamedParam, PosParam},
+    hir::{HirNode, NamedParam, PosParam},
     ty::UnpackedType,
     value::Value,
 };
@@ -84,6 +84,11 @@ pub struct ParamEnvData<'t> {
 }
 
 impl<'t> ParamEnvData<'t> {
+    /// The module, interface, or class which the environment parametrizes.
+    pub fn module(&self) -> Option<NodeId> {
+        self.module
+    }
+
     /// Find the value assigned to a node.
     pub fn find_value(&self, node_id: NodeId) -> Option<ParamEnvBinding<Value<'t>>> {
         self.values
@@ -125,6 +130,13 @@ impl<'t> ParamEnvData<'t> {
         self.values.push((node_id, ParamEnvBinding::Direct(value)));
     }
 
+    /// Bind a node to the value of another node.
+    pub fn bind_value(&mut self, node_id: NodeId, bound: NodeEnvId) {
+        self.values.retain(|&(n, _)| n != node_id);
+        self.values
+            .push((node_id, ParamEnvBinding::Indirect(bound)));
+    }
+
     /// Add additional interface parametrizations.
     pub fn add_interfaces(&mut self, iter: impl IntoIterator<Item = (NodeId, NodeEnvId)>) {
         self.intfs.extend(iter);
@@ -155,6 +167,11 @@ pub enum ParamEnvSource<'hir> {
         pos: &'hir [PosParam],
         named: &'hir [NamedParam],
     },
+    ClassInst {
+        class: Ref<'hir, hir::Class<'hir>>,
+        env: ParamEnv,
+        params: Ref<'hir, [ast::ParamAssignment<'hir>]>,
+    },
 }
 
 pub(crate) fn compute<'gcx>(
@@ -205,6 +222,22 @@ pub(crate) fn compute<'gcx>(
             pos,
             named,
         ),
+        ParamEnvSource::ClassInst { class, env, params } => {
+            let mut pos = vec![];
+            let mut named = vec![];
+            for param in params.iter() {
+                let value_id = cx.map_ast(AstNode::TypeOrExpr(&param.expr));
+                match param.name {
+                    Some(name) => named.push((
+                        param.span,
+                        Spanned::new(name.name, name.span),
+                        Some(value_id),
+                    )),
+                    None => pos.push((param.span, Some(value_id))),
+                }
+            }
+            param_env_from_instance(cx, class.ast, class.params.clone(), env, &pos, &named)
+        }
     }
 }
 
@@ -290,12 +323,109 @@ fn param_env_from_instance<'a>(
         }
     }
 
-    let env = cx.intern_param_env(ParamEnvData {
-        module: Some(node.id()),
-        types,
-        values,
-        intfs: Default::default(),
-    });
+    // Modules and interfaces declared within another module inherit the
+    // parametrization of their parent.
+    let mut data = if is_nested_unit(cx, node) {
+        cx.param_env_data(env).clone()
+    } else {
+        ParamEnvData::default()
+    };
+    data.module = Some(node.id());
+    data.types.extend(types);
+    data.values.extend(values);
+    let env = cx.intern_param_env(data);
     cx.add_param_env_context(env, node.id());
     Ok(env)
 }
+
+/// Check whether a module or interface is declared within another one.
+pub(crate) fn is_nested_unit<'a>(cx: &impl Context<'a>, node: &'a dyn ast::AnyNode<'a>) -> bool {
+    let parent = cx.scope_location(node).scope.as_all();
+    parent.is_module() || parent.is_interface()
+}
+
+/// Determine the parameter environment for the expansion of a let declaration.
+///
+/// The node is either a call such as `foo(a, b)`, or an identifier that refers
+/// to a let declaration. The resulting environment binds the formal arguments
+/// of the let declaration to the actual arguments, or to their default values.
+#[moore_derive::query]
+pub(crate) fn let_env<'a>(
+    cx: &impl Context<'a>,
+    node_id: NodeId,
+    env: ParamEnv,
+) -> Result<ParamEnv> {
+    let hir = match cx.hir_of(node_id)? {
+        HirNode::Expr(x) => x,
+        _ => unreachable!(),
+    };
+    let (target, args) = match hir.kind {
+        hir::ExprKind::LetCall(target, ref args) => (target, args.as_slice()),
+        _ => (cx.resolve_node(node_id, env)?, [].as_ref()),
+    };
+    let decl = match cx.hir_of(target)? {
+        HirNode::Let(x) => x,
+        _ => unreachable!(),
+    };
+
+    // Check that there are not too many positional arguments, and that all
+    // named arguments exist.
+    let mut failed = false;
+    let num_pos = args.iter().filter(|arg| arg.name.is_none()).count();
+    if num_pos > decl.args.len() {
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "{} only has {} argument(s)",
+                decl.desc_full(),
+                decl.args.len()
+            ))
+            .span(hir.span()),
+        );
+        failed = true;
+    }
+    let formals = decl
+        .args
+        .iter()
+        .map(|&id| match cx.hir_of(id)? {
+            HirNode::LetArg(x) => Ok(x),
+            _ => unreachable!(),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    for name in args.iter().flat_map(|arg| arg.name) {
+        if !formals.iter().any(|formal| formal.name.value == name.value) {
+            cx.emit(
+                DiagBuilder2::error(format!("no argument `{}` in {}", name, decl.desc_full()))
+                    .span(name.span),
+            );
+            failed = true;
+        }
+    }
+
+    // Bind each formal argument to its actual argument or default value.
+    let mut data = cx.param_env_data(env).clone();
+    for (index, formal) in formals.into_iter().enumerate() {
+        let actual = args
+            .iter()
+            .find(|arg| arg.name.map(|n| n.value) == Some(formal.name.value))
+            .or_else(|| args.get(index).filter(|arg| arg.name.is_none()))
+            .and_then(|arg| arg.expr);
+        match actual.or(formal.default) {
+            Some(expr) => data.bind_value(formal.id, expr.env(env)),
+            None => {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "{} requires a value for argument `{}`",
+                        decl.desc_full(),
+                        formal.name
+                    ))
+                    .span(hir.span()),
+                );
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        return Err(());
+    }
+    Ok(cx.intern_param_env(data))
+}
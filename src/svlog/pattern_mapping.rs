@@ -60,6 +60,19 @@ pub(crate) fn map_pattern<'a>(
     }
     let ty = ty.ty();
 
+    // Unions have no notion of their members being assigned all at once.
+    if ty.get_union().is_some() {
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "cannot construct a value of type `{}` with `'{{...}}`",
+                ty
+            ))
+            .span(expr.span)
+            .add_note("Assign to a member of the union instead."),
+        );
+        return Err(());
+    }
+
     // Then handle the different pattern styles.
     let fields = match expr.kind {
         hir::ExprKind::PositionalPattern(ref mapping) => {
@@ -92,6 +92,13 @@ pub(crate) fn local_rib<'gcx>(cx: &impl Context<'gcx>, node_id: NodeId) -> Resul
     let mut kind = match ast {
         AstNode::TypeParam(_, decl) => Some(RibKind::Normal(decl.name, node_id)),
         AstNode::ValueParam(_, decl) => Some(RibKind::Normal(decl.name, node_id)),
+        // Nested modules and interfaces are local definitions in their parent.
+        AstNode::Module(decl) if cx.parent_node_id(node_id).is_some() => {
+            Some(RibKind::Normal(decl.name, node_id))
+        }
+        AstNode::Interface(decl) if cx.parent_node_id(node_id).is_some() => {
+            Some(RibKind::Normal(decl.name, node_id))
+        }
         AstNode::Module(_) => Some(RibKind::Module(HashMap::new())),
         AstNode::VarDecl(decl, _, _) | AstNode::NetDecl(decl, _, _) => Some(RibKind::Normal(
             Spanned::new(decl.name, decl.name_span),
@@ -401,7 +408,7 @@ pub(crate) fn resolve_field_access<'a>(
     let strukt = if let Some(strukt) = ty.get_struct() {
         strukt
     } else {
-        let mut d = DiagBuilder2::error(format!("value of type `{}` is not a struct", ty))
+        let mut d = DiagBuilder2::error(format!("value of type `{}` is not a struct or union", ty))
             .span(hir.human_span());
         if ty.resolve_full() != ty {
             d = d.add_note(format!("`{}` is defined as `{}`", ty, ty.resolve_full()));
@@ -456,6 +463,8 @@ impl<'a> ScopedNode<'a> for ast::GenerateFor<'a> {}
 impl<'a> ScopedNode<'a> for ast::GenerateIf<'a> {}
 impl<'a> ScopedNode<'a> for ast::GenerateCase<'a> {}
 impl<'a> ScopedNode<'a> for ast::GenerateBlock<'a> {}
+impl<'a> ScopedNode<'a> for ast::LetDecl<'a> {}
+impl<'a> ScopedNode<'a> for ast::CasePattern<'a> {}
 
 // Compare and hash scoped nodes by reference for use in the query system.
 impl<'a> Eq for &'a dyn ScopedNode<'a> {}
@@ -510,6 +519,8 @@ impl<'a> AsScopedNode<'a> for ast::AllNode<'a> {
             ast::AllNode::GenerateIf(x) => Some(x),
             ast::AllNode::GenerateCase(x) => Some(x),
             ast::AllNode::GenerateBlock(x) => Some(x),
+            ast::AllNode::LetDecl(x) => Some(x),
+            ast::AllNode::CasePattern(x) => Some(x),
             _ => None,
         }
     }
@@ -534,6 +545,7 @@ pub(crate) fn generated_scope<'a>(
             parent,
             defs: Default::default(),
             wildcard_imports: Default::default(),
+            exports: Default::default(),
             subscopes: Default::default(),
         },
     );
@@ -586,6 +598,8 @@ pub struct Scope<'a> {
     pub defs: HashMap<Name, Def<'a>>,
     /// The wildcard imports in this scope.
     pub wildcard_imports: Vec<&'a ast::ImportItem<'a>>,
+    /// The exports in this scope.
+    pub exports: Vec<&'a ast::ExportItem<'a>>,
     /// The subscopes.
     pub subscopes: Vec<&'a dyn ScopedNode<'a>>,
 }
@@ -736,6 +750,23 @@ impl<'a, 'c, C: Context<'a>> ScopeGenerator<'a, 'c, C> {
         self.scope.wildcard_imports.push(node);
     }
 
+    /// Register an export.
+    pub fn add_export(&mut self, node: &'a ast::ExportItem<'a>) {
+        trace!(" - Adding export {:?}", node);
+        self.scope.exports.push(node);
+    }
+
+    /// Determine the visibility of a module or interface declared in this
+    /// scope. Nested declarations are only visible within their parent.
+    fn unit_vis(&self) -> DefVis {
+        let node = self.scope.node.as_all();
+        if node.is_module() || node.is_interface() {
+            DefVis::LOCAL
+        } else {
+            DefVis::LOCAL | DefVis::GLOBAL
+        }
+    }
+
     /// Register a definition.
     pub fn add_def(&mut self, mut def: Def<'a>) {
         trace!(" - Adding definition {:?}", def);
@@ -801,7 +832,7 @@ impl<'a, C: Context<'a>> ast::Visitor<'a> for ScopeGenerator<'a, '_, C> {
         self.add_def(Def {
             node: DefNode::Ast(node),
             name: node.name,
-            vis: DefVis::LOCAL | DefVis::GLOBAL,
+            vis: self.unit_vis(),
             may_override: true,
             ordered: false,
         });
@@ -813,7 +844,7 @@ impl<'a, C: Context<'a>> ast::Visitor<'a> for ScopeGenerator<'a, '_, C> {
         self.add_def(Def {
             node: DefNode::Ast(node),
             name: node.name,
-            vis: DefVis::LOCAL | DefVis::GLOBAL,
+            vis: self.unit_vis(),
             may_override: true,
             ordered: false,
         });
@@ -858,6 +889,11 @@ impl<'a, C: Context<'a>> ast::Visitor<'a> for ScopeGenerator<'a, '_, C> {
         true
     }
 
+    fn pre_visit_export_item(&mut self, node: &'a ast::ExportItem<'a>) -> bool {
+        self.add_export(node);
+        true
+    }
+
     fn pre_visit_var_decl_name(&mut self, node: &'a ast::VarDeclName<'a>) -> bool {
         // Don't register definitions for struct members.
         if node
@@ -867,12 +903,20 @@ impl<'a, C: Context<'a>> ast::Visitor<'a> for ScopeGenerator<'a, '_, C> {
         {
             return true;
         }
+        // Variables and nets in a package are accessible as `pkg::name`.
+        let vis = if self.scope.node.as_all().is_package() {
+            DefVis::LOCAL | DefVis::NAMESPACE | DefVis::HIERARCHICAL
+        } else {
+            DefVis::LOCAL | DefVis::HIERARCHICAL
+        };
+        // Class properties are visible throughout the class body.
+        let ordered = !self.scope.node.as_all().is_class_decl();
         self.add_def(Def {
             node: DefNode::Ast(node),
             name: Spanned::new(node.name, node.name_span),
-            vis: DefVis::LOCAL | DefVis::HIERARCHICAL,
+            vis,
             may_override: false,
-            ordered: true,
+            ordered,
         });
         true
     }
@@ -939,13 +983,13 @@ impl<'a, C: Context<'a>> ast::Visitor<'a> for ScopeGenerator<'a, '_, C> {
 
     fn pre_visit_class_decl(&mut self, node: &'a ast::ClassDecl<'a>) -> bool {
         self.add_subscope(node);
-        // self.add_def(Def {
-        //     node: DefNode::Ast(node),
-        //     name: node.name,
-        //     vis: DefVis::LOCAL | DefVis::NAMESPACE,
-        //     may_override: false,
-        //     ordered: true,
-        // });
+        self.add_def(Def {
+            node: DefNode::Ast(node),
+            name: node.name,
+            vis: self.unit_vis() | DefVis::NAMESPACE,
+            may_override: false,
+            ordered: false,
+        });
         false
     }
 
@@ -961,6 +1005,29 @@ impl<'a, C: Context<'a>> ast::Visitor<'a> for ScopeGenerator<'a, '_, C> {
         false
     }
 
+    fn pre_visit_let_decl(&mut self, node: &'a ast::LetDecl<'a>) -> bool {
+        self.add_subscope(node);
+        self.add_def(Def {
+            node: DefNode::Ast(node),
+            name: node.name,
+            vis: DefVis::LOCAL | DefVis::NAMESPACE | DefVis::HIERARCHICAL,
+            may_override: false,
+            ordered: true,
+        });
+        false
+    }
+
+    fn pre_visit_let_arg(&mut self, node: &'a ast::LetArg<'a>) -> bool {
+        self.add_def(Def {
+            node: DefNode::Ast(node),
+            name: node.name,
+            vis: DefVis::LOCAL,
+            may_override: false,
+            ordered: false,
+        });
+        true
+    }
+
     fn pre_visit_subroutine_port(&mut self, node: &'a ast::SubroutinePort<'a>) -> bool {
         if let Some(ref name) = node.name {
             self.add_def(Def {
@@ -1045,6 +1112,22 @@ impl<'a, C: Context<'a>> ast::Visitor<'a> for ScopeGenerator<'a, '_, C> {
         true
     }
 
+    fn pre_visit_case_pattern(&mut self, node: &'a ast::CasePattern<'a>) -> bool {
+        self.add_subscope(node);
+        false
+    }
+
+    fn pre_visit_pattern_var(&mut self, node: &'a ast::PatternVar<'a>) -> bool {
+        self.add_def(Def {
+            node: DefNode::Ast(node),
+            name: node.name,
+            vis: DefVis::LOCAL,
+            may_override: false,
+            ordered: true,
+        });
+        true
+    }
+
     fn pre_visit_dpi_decl(&mut self, node: &'a ast::DpiDecl<'a>) -> bool {
         match node.data {
             ast::DpiDeclData::Import { ref prototype, .. } => {
@@ -1158,20 +1241,75 @@ pub(crate) fn resolve_local<'a>(
             }
         }
 
-        // Check the wildcard imports for any luck.
+        // Members of a class's base classes are visible in the class.
+        if let Some(class) = scope.node.as_all().get_class_decl() {
+            if let Some(def) = resolve_inherited(cx, name, class)? {
+                debug!(" - Found inherited {:?}", def);
+                return Ok(Some(def));
+            }
+        }
+
+        // Check the wildcard imports for any luck. The name must not be
+        // imported from more than one package.
         if skip_imports {
             continue;
         }
-        for &import in scope.wildcard_imports.iter().rev() {
+        let mut found: Option<(&ast::ImportItem, &Def)> = None;
+        for &import in &scope.wildcard_imports {
             if import.order() > at.order {
                 continue;
             }
             let inside = cx.resolve_imported_scope(import)?;
-            let def = cx.resolve_namespace(name, inside);
-            if def.is_some() {
-                return Ok(def);
+            let def = match cx.resolve_namespace(name, inside) {
+                Some(def) => def,
+                None => continue,
+            };
+            match found {
+                Some((prev_import, prev_def)) if prev_def.node.id() != def.node.id() => {
+                    cx.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` is imported from both `{}` and `{}`",
+                            name, prev_import.pkg, import.pkg
+                        ))
+                        .span(import.span())
+                        .add_note(format!("`{}` was also imported here:", name))
+                        .span(prev_import.span())
+                        .add_note(
+                            "Import the name explicitly, e.g. `import pkg::name;`, to resolve \
+                             the ambiguity",
+                        ),
+                    );
+                    return Err(());
+                }
+                Some(_) => (),
+                None => found = Some((import, def)),
             }
         }
+        if let Some((_, def)) = found {
+            return Ok(Some(def));
+        }
+    }
+    Ok(None)
+}
+
+/// Resolve a name among the members inherited by a class.
+///
+/// Looks for the name in the base class of `class`, and transitively in its
+/// base classes. Returns `None` if no base class declares the name.
+fn resolve_inherited<'a>(
+    cx: &impl Context<'a>,
+    name: Name,
+    class: &'a ast::ClassDecl<'a>,
+) -> Result<Option<&'a Def<'a>>> {
+    let mut next = cx.base_class_decl(Ref(class))?;
+    while let Some(base) = next {
+        let scope = cx.generated_scope(base);
+        if let Some(def) = scope.defs.get(&name) {
+            if def.vis.contains(DefVis::LOCAL) {
+                return Ok(Some(def));
+            }
+        }
+        next = cx.base_class_decl(Ref(base))?;
     }
     Ok(None)
 }
@@ -1220,10 +1358,37 @@ pub(crate) fn resolve_namespace<'a>(
     match scope.defs.get(&name) {
         Some(def) if def.vis.contains(DefVis::NAMESPACE) => {
             debug!(" - Found {:?}", def);
-            Some(def)
+            return Some(def);
+        }
+        _ => (),
+    }
+
+    // Check if the name is imported into the scope and exported again, as in
+    // `import foo::*; export foo::*;`.
+    for &export in &scope.exports {
+        if export.name.map_or(false, |n| n.value != name) {
+            continue;
+        }
+        let explicit = scope.defs.get(&name).and_then(|def| match def.node {
+            DefNode::Ast(node) => node.as_all().get_import_item(),
+            _ => None,
+        });
+        let imports = explicit
+            .into_iter()
+            .chain(scope.wildcard_imports.iter().cloned())
+            .filter(|import| export.pkg.map_or(true, |pkg| pkg.value == import.pkg.value));
+        for import in imports {
+            let def = cx
+                .resolve_imported_scope(import)
+                .ok()
+                .and_then(|inside| cx.resolve_namespace(name, inside));
+            if let Some(def) = def {
+                debug!(" - Found {:?} through {:?}", def, export);
+                return Some(def);
+            }
         }
-        _ => None,
     }
+    None
 }
 
 /// Resolve a name in a scope as a namespace lookup or emit an error.
@@ -1493,3 +1658,36 @@ pub(crate) fn resolve_inst_target<'a>(
         }
     }
 }
+
+/// Determine the hierarchical name of the scope that contains a node.
+///
+/// This is the name printed by the `%m` format specifier, built from the
+/// enclosing design element and any named subroutines, classes, generate
+/// blocks, and statement blocks, e.g. `top.gen_loop.my_task`.
+pub(crate) fn hierarchical_scope_name<'a>(cx: &impl Context<'a>, node_id: NodeId) -> String {
+    let mut names = vec![];
+    let mut parent = cx.ast_for_id(node_id).get_parent();
+    while let Some(node) = parent {
+        let name = match node.as_all() {
+            ast::AllNode::Module(x) => Some(x.name.value),
+            ast::AllNode::Interface(x) => Some(x.name.value),
+            ast::AllNode::Package(x) => Some(x.name.value),
+            ast::AllNode::ClassDecl(x) => Some(x.name.value),
+            ast::AllNode::SubroutineDecl(x) => Some(x.prototype.name.value),
+            ast::AllNode::GenerateBlock(x) => x.label.map(|label| label.value),
+            ast::AllNode::Stmt(x) => match x.kind {
+                ast::SequentialBlock(..) | ast::ParallelBlock(..) => x.label,
+                _ => None,
+            },
+            _ => None,
+        };
+        names.extend(name);
+        parent = node.get_parent();
+    }
+    names.reverse();
+    names
+        .iter()
+        .map(|name| name.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
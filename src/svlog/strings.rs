@@ -0,0 +1,203 @@
+// Copyright (c) 2016-2020 Fabian Schuiki
+
+//! Runtime strings and their built-in methods.
+//!
+//! Strings are represented as handles to immutable character storage on the
+//! heap, which the simulator manages through a set of intrinsics. The null
+//! handle refers to the empty string. Operations that modify a string produce
+//! a new handle, such that assigning a string never aliases another variable.
+
+use crate::crate_prelude::*;
+use crate::ty::{PackedType, UnpackedCore, UnpackedType};
+
+/// A built-in method of a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StringMethod {
+    /// `len()`, the number of characters.
+    Len,
+    /// `putc(i, c)`, which replaces the character at index `i`.
+    Putc,
+    /// `getc(i)`, the character at index `i`.
+    Getc,
+    /// `toupper()`, the string with all characters in upper case.
+    ToUpper,
+    /// `tolower()`, the string with all characters in lower case.
+    ToLower,
+    /// `compare(s)`, a lexical comparison with another string.
+    Compare,
+    /// `icompare(s)`, a case-insensitive lexical comparison.
+    ICompare,
+    /// `substr(i, j)`, the characters from index `i` to `j`.
+    Substr,
+    /// `atoi()`, the decimal number the string represents.
+    AtoI,
+    /// `atohex()`, the hexadecimal number the string represents.
+    AtoHex,
+    /// `atooct()`, the octal number the string represents.
+    AtoOct,
+    /// `atobin()`, the binary number the string represents.
+    AtoBin,
+    /// `itoa(i)`, which replaces the string with a decimal number.
+    ItoA,
+    /// `hextoa(i)`, which replaces the string with a hexadecimal number.
+    HextoA,
+    /// `octtoa(i)`, which replaces the string with an octal number.
+    OcttoA,
+    /// `bintoa(i)`, which replaces the string with a binary number.
+    BintoA,
+}
+
+impl StringMethod {
+    /// Find a method of a string by name.
+    pub fn lookup(name: &str) -> Option<Self> {
+        Some(match name {
+            "len" => Self::Len,
+            "putc" => Self::Putc,
+            "getc" => Self::Getc,
+            "toupper" => Self::ToUpper,
+            "tolower" => Self::ToLower,
+            "compare" => Self::Compare,
+            "icompare" => Self::ICompare,
+            "substr" => Self::Substr,
+            "atoi" => Self::AtoI,
+            "atohex" => Self::AtoHex,
+            "atooct" => Self::AtoOct,
+            "atobin" => Self::AtoBin,
+            "itoa" => Self::ItoA,
+            "hextoa" => Self::HextoA,
+            "octtoa" => Self::OcttoA,
+            "bintoa" => Self::BintoA,
+            _ => return None,
+        })
+    }
+
+    /// The name of the method's LLHD intrinsic, without the `llhd.string`
+    /// prefix.
+    pub fn llhd_name(self) -> &'static str {
+        match self {
+            Self::Len => "len",
+            Self::Putc => "putc",
+            Self::Getc => "getc",
+            Self::ToUpper => "toupper",
+            Self::ToLower => "tolower",
+            Self::Compare => "compare",
+            Self::ICompare => "icompare",
+            Self::Substr => "substr",
+            Self::AtoI => "atoi",
+            Self::AtoHex => "atohex",
+            Self::AtoOct => "atooct",
+            Self::AtoBin => "atobin",
+            Self::ItoA => "itoa",
+            Self::HextoA => "hextoa",
+            Self::OcttoA => "octtoa",
+            Self::BintoA => "bintoa",
+        }
+    }
+
+    /// The minimum and maximum number of arguments the method accepts.
+    pub fn arity(self) -> (usize, usize) {
+        match self {
+            Self::Len
+            | Self::ToUpper
+            | Self::ToLower
+            | Self::AtoI
+            | Self::AtoHex
+            | Self::AtoOct
+            | Self::AtoBin => (0, 0),
+            Self::Getc
+            | Self::Compare
+            | Self::ICompare
+            | Self::ItoA
+            | Self::HextoA
+            | Self::OcttoA
+            | Self::BintoA => (1, 1),
+            Self::Putc | Self::Substr => (2, 2),
+        }
+    }
+
+    /// Check whether the method modifies the string.
+    pub fn modifies(self) -> bool {
+        match self {
+            Self::Putc | Self::ItoA | Self::HextoA | Self::OcttoA | Self::BintoA => true,
+            _ => false,
+        }
+    }
+}
+
+/// Determine the type of the value returned by a string method.
+pub fn method_type<'a>(cx: &impl Context<'a>, method: StringMethod) -> &'a UnpackedType<'a> {
+    match method {
+        StringMethod::Len | StringMethod::Compare | StringMethod::ICompare => {
+            atom_type(cx, ty::IntAtomType::Int)
+        }
+        StringMethod::Getc => atom_type(cx, ty::IntAtomType::Byte),
+        StringMethod::ToUpper | StringMethod::ToLower | StringMethod::Substr => {
+            UnpackedType::make(cx, UnpackedCore::String)
+        }
+        StringMethod::AtoI | StringMethod::AtoHex | StringMethod::AtoOct | StringMethod::AtoBin => {
+            atom_type(cx, ty::IntAtomType::Integer)
+        }
+        StringMethod::Putc
+        | StringMethod::ItoA
+        | StringMethod::HextoA
+        | StringMethod::OcttoA
+        | StringMethod::BintoA => UnpackedType::make_void(),
+    }
+}
+
+/// Determine the type of an argument of a string method.
+pub fn arg_type<'a>(
+    cx: &impl Context<'a>,
+    method: StringMethod,
+    index: usize,
+) -> &'a UnpackedType<'a> {
+    match (method, index) {
+        (StringMethod::Putc, 1) => atom_type(cx, ty::IntAtomType::Byte),
+        (StringMethod::Compare, _) | (StringMethod::ICompare, _) => {
+            UnpackedType::make(cx, UnpackedCore::String)
+        }
+        (StringMethod::ItoA, _)
+        | (StringMethod::HextoA, _)
+        | (StringMethod::OcttoA, _)
+        | (StringMethod::BintoA, _) => atom_type(cx, ty::IntAtomType::Integer),
+        _ => atom_type(cx, ty::IntAtomType::Int),
+    }
+}
+
+/// Determine the built-in string method called by an expression.
+///
+/// Returns `None` if the expression is not a method call on a string.
+#[moore_derive::query]
+pub(crate) fn string_method<'a>(
+    cx: &impl Context<'a>,
+    Ref(expr): Ref<'a, hir::Expr<'a>>,
+    env: ParamEnv,
+) -> Result<Option<StringMethod>> {
+    let (object, name) = match expr.kind {
+        hir::ExprKind::MethodCall(object, name, _) => (object, name),
+        _ => return Ok(None),
+    };
+    let ty = cx.need_self_determined_type(object, env);
+    if ty.is_error() {
+        return Err(());
+    }
+    if !ty.is_string() {
+        return Ok(None);
+    }
+    let method = match StringMethod::lookup(&name.value.as_str()) {
+        Some(x) => x,
+        None => {
+            cx.emit(
+                DiagBuilder2::error(format!("no method `{}` in `{}`", name, ty)).span(name.span),
+            );
+            return Err(());
+        }
+    };
+    container::check_method_args(cx, expr, ty, method.arity())?;
+    Ok(Some(method))
+}
+
+/// An integer atom type, used for indices, characters, and numbers.
+fn atom_type<'a>(cx: &impl Context<'a>, atom: ty::IntAtomType) -> &'a UnpackedType<'a> {
+    PackedType::make(cx, atom).to_unpacked(cx)
+}
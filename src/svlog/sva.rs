@@ -0,0 +1,210 @@
+// Copyright (c) 2016-2020 Fabian Schuiki
+
+//! Support for concurrent assertions.
+//!
+//! This module flattens the properties of concurrent assertions into a fixed
+//! window of boolean terms, each of which is checked at a known clock tick
+//! relative to the start of an evaluation attempt. Code generation turns this
+//! window into a checker process which keeps a history of past term values.
+
+use crate::crate_prelude::*;
+use crate::hir::{HirNode, Visitor};
+use num::ToPrimitive;
+use std::sync::Arc;
+
+/// A flattened concurrent assertion.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Checker {
+    /// The number of clock ticks spanned by one evaluation attempt.
+    pub length: usize,
+    /// The terms of the antecedent, as clock tick offset and boolean
+    /// expression. Empty if the property has no implication.
+    pub antecedent: Vec<(usize, NodeId)>,
+    /// The terms of the consequent, as clock tick offset and boolean
+    /// expression.
+    pub consequent: Vec<(usize, NodeId)>,
+    /// The calls to sampled value functions in the boolean expressions.
+    pub sampled: Vec<SampledCall>,
+}
+
+/// A call to a sampled value function, such as `$rose` or `$past`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SampledCall {
+    /// The call expression.
+    pub call: NodeId,
+    /// The argument whose past values are needed.
+    pub arg: NodeId,
+    /// The number of past clock ticks to keep around.
+    pub depth: usize,
+}
+
+/// Flatten a concurrent assertion into a checker.
+#[moore_derive::query]
+pub(crate) fn checker_of<'a>(
+    cx: &impl Context<'a>,
+    node_id: NodeId,
+    env: ParamEnv,
+) -> Result<Arc<Checker>> {
+    let hir = match cx.hir_of(node_id)? {
+        HirNode::Assertion(x) => x,
+        x => unreachable!("checker for non-assertion {:?}", x),
+    };
+
+    // Lay out the terms of the property.
+    let mut antecedent = vec![];
+    let mut consequent = vec![];
+    let length = flatten_property(cx, env, &hir.prop, 0, &mut antecedent, &mut consequent)?;
+
+    // Find the sampled value functions and determine how far back they look.
+    let mut finder = SampledCallFinder {
+        cx,
+        env,
+        calls: vec![],
+        failed: false,
+    };
+    for expr in hir.bool_exprs() {
+        finder.visit_node_with_id(expr, false);
+    }
+    if finder.failed {
+        return Err(());
+    }
+
+    Ok(Arc::new(Checker {
+        length,
+        antecedent,
+        consequent,
+        sampled: finder.calls,
+    }))
+}
+
+/// Lay out the terms of a property, starting at a clock tick offset.
+///
+/// Returns the number of clock ticks the property spans.
+fn flatten_property<'a>(
+    cx: &impl Context<'a>,
+    env: ParamEnv,
+    prop: &hir::Property,
+    offset: usize,
+    antecedent: &mut Vec<(usize, NodeId)>,
+    consequent: &mut Vec<(usize, NodeId)>,
+) -> Result<usize> {
+    match *prop {
+        hir::Property::Seq(ref seq) => flatten_sequence(cx, env, seq, offset, consequent),
+        hir::Property::Impl {
+            antecedent: ref seq,
+            overlap,
+            consequent: ref prop,
+        } => {
+            let len = flatten_sequence(cx, env, seq, offset, antecedent)?;
+            let start = offset + len - if overlap { 1 } else { 0 };
+            let len = flatten_property(cx, env, prop, start, antecedent, consequent)?;
+            Ok(start - offset + len)
+        }
+    }
+}
+
+/// Lay out the terms of a sequence, starting at a clock tick offset.
+///
+/// Returns the number of clock ticks the sequence spans.
+fn flatten_sequence<'a>(
+    cx: &impl Context<'a>,
+    env: ParamEnv,
+    seq: &hir::Sequence,
+    offset: usize,
+    terms: &mut Vec<(usize, NodeId)>,
+) -> Result<usize> {
+    match *seq {
+        hir::Sequence::Expr(expr) => {
+            terms.push((offset, expr));
+            Ok(1)
+        }
+        hir::Sequence::Repeat(ref seq, count) => {
+            let count = cycle_count(cx, env, count, 1, "repetition count")?;
+            let mut len = 0;
+            for _ in 0..count {
+                len += flatten_sequence(cx, env, seq, offset + len, terms)?;
+            }
+            Ok(len)
+        }
+        hir::Sequence::Delay(ref lhs, delay, ref rhs) => {
+            let delay = cycle_count(cx, env, delay, 0, "cycle delay")?;
+            let start = match lhs {
+                Some(lhs) => offset + flatten_sequence(cx, env, lhs, offset, terms)? - 1 + delay,
+                None => offset + delay,
+            };
+            let len = flatten_sequence(cx, env, rhs, start, terms)?;
+            Ok(start - offset + len)
+        }
+    }
+}
+
+/// Evaluate a constant number of clock ticks.
+fn cycle_count<'a>(
+    cx: &impl Context<'a>,
+    env: ParamEnv,
+    expr: NodeId,
+    min: usize,
+    what: &str,
+) -> Result<usize> {
+    let value = cx.constant_int_value_of(expr, env)?;
+    match value.to_usize() {
+        Some(v) if v >= min => Ok(v),
+        _ => {
+            cx.emit(
+                DiagBuilder2::error(format!("{} must be at least {}", what, min))
+                    .span(cx.span(expr))
+                    .add_note(format!(
+                        "`{}` evaluates to {}",
+                        cx.span(expr).extract(),
+                        value
+                    )),
+            );
+            Err(())
+        }
+    }
+}
+
+/// A visitor that collects the calls to sampled value functions.
+struct SampledCallFinder<'a, C> {
+    cx: &'a C,
+    env: ParamEnv,
+    calls: Vec<SampledCall>,
+    failed: bool,
+}
+
+impl<'a, 'gcx: 'a, C> Visitor<'gcx> for SampledCallFinder<'a, C>
+where
+    C: Context<'gcx>,
+{
+    type Context = C;
+    fn context(&self) -> &C {
+        self.cx
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr, lvalue: bool) {
+        let found = match expr.kind {
+            hir::ExprKind::Builtin(hir::BuiltinCall::Rose(arg))
+            | hir::ExprKind::Builtin(hir::BuiltinCall::Fell(arg))
+            | hir::ExprKind::Builtin(hir::BuiltinCall::Stable(arg))
+            | hir::ExprKind::Builtin(hir::BuiltinCall::Past(arg, None)) => Some((arg, 1)),
+            hir::ExprKind::Builtin(hir::BuiltinCall::Past(arg, Some(ticks))) => {
+                match cycle_count(self.cx, self.env, ticks, 1, "number of ticks of `$past`") {
+                    Ok(depth) => Some((arg, depth)),
+                    Err(()) => {
+                        self.failed = true;
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        if let Some((arg, depth)) = found {
+            self.calls.push(SampledCall {
+                call: expr.id,
+                arg,
+                depth,
+            });
+        }
+        hir::walk_expr(self, expr, lvalue);
+    }
+}
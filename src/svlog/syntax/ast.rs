@@ -636,8 +636,9 @@ pub enum Item<'a> {
     InterfaceDecl(#[forward] Interface<'a>),
     PackageDecl(#[forward] Package<'a>),
     ClassDecl(#[forward] ClassDecl<'a>),
-    ProgramDecl(()),
+    ProgramDecl(#[forward] Module<'a>),
     ImportDecl(#[forward] ImportDecl<'a>),
+    ExportDecl(#[forward] ExportDecl<'a>),
     DpiDecl(#[forward] DpiDecl<'a>),
     ParamDecl(#[forward] ParamDecl<'a>),
     ModportDecl(#[forward] Modport<'a>),
@@ -645,6 +646,7 @@ pub enum Item<'a> {
     PortDecl(#[forward] PortDecl<'a>),
     Procedure(#[forward] Procedure<'a>),
     SubroutineDecl(#[forward] SubroutineDecl<'a>),
+    LetDecl(#[forward] LetDecl<'a>),
     ContAssign(#[forward] ContAssign<'a>),
     GenvarDecl(Vec<GenvarDecl<'a>>),
     GenerateRegion(Span, Vec<Item<'a>>),
@@ -655,20 +657,24 @@ pub enum Item<'a> {
     NetDecl(NetDecl<'a>),
     VarDecl(#[forward] VarDecl<'a>),
     Inst(Inst<'a>),
+    #[indefinite("`timescale directive")]
+    Timescale(Timeunit),
 }
 
-/// A module.
+/// A module or program.
 #[moore_derive::node]
 #[indefinite("module")]
 #[definite("module `{}`", name)]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Module<'a> {
+    pub kind: ModuleKind,
     pub lifetime: Lifetime, // default static
     #[name]
     pub name: Spanned<Name>,
     pub imports: Vec<ImportDecl<'a>>,
     pub params: Vec<ParamDecl<'a>>,
     pub ports: Vec<Port<'a>>,
+    pub timeunits: Timeunit,
     pub items: Vec<Item<'a>>,
 }
 
@@ -683,6 +689,7 @@ pub struct Interface<'a> {
     pub name: Spanned<Name>,
     pub params: Vec<ParamDecl<'a>>,
     pub ports: Vec<Port<'a>>,
+    pub timeunits: Timeunit,
     pub items: Vec<Item<'a>>,
 }
 
@@ -699,6 +706,14 @@ pub struct Package<'a> {
     pub items: Vec<Item<'a>>,
 }
 
+/// Whether a module is declared as a `module` or a `program`.
+#[moore_derive::visit]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ModuleKind {
+    Module,
+    Program,
+}
+
 /// Lifetime specifier for variables, tasks, and functions. Defaults to static.
 #[moore_derive::visit]
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -712,6 +727,7 @@ pub enum Lifetime {
 /// ```text
 /// "timeunit" time_literal ["/" time_literal] ";"
 /// "timeprecision" time_literal ";"
+/// "`timescale" time_literal "/" time_literal
 /// ```
 #[moore_derive::visit]
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -1046,7 +1062,7 @@ impl std::fmt::Display for PortDir {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
 pub enum NetType {
     Supply0,
     Supply1,
@@ -1211,6 +1227,48 @@ pub enum CaseMode {
 pub enum CaseItem<'a> {
     Default(Box<Stmt<'a>>),
     Expr(Vec<Expr<'a>>, Box<Stmt<'a>>),
+    Pattern(Box<CasePattern<'a>>),
+}
+
+/// An item of a `case ... matches` statement.
+///
+/// For example the `tagged Valid .v: x = v;` in `case (a) matches tagged Valid
+/// .v: x = v; endcase`. The variables bound by the pattern are visible in the
+/// statement.
+#[moore_derive::node]
+#[indefinite("case pattern")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CasePattern<'a> {
+    pub pattern: MatchPattern<'a>,
+    pub stmt: Box<Stmt<'a>>,
+}
+
+/// A pattern matched against a value in `matches` expressions and `case ...
+/// matches` statements.
+#[moore_derive::visit]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchPattern<'a> {
+    /// A wildcard `.*`, which matches any value.
+    Wildcard(Span),
+    /// A pattern variable `.v`, which matches any value and binds it to `v`.
+    Var(PatternVar<'a>),
+    /// A tagged union pattern `tagged Valid [<pattern>]`, which matches if the
+    /// member is active and its value matches the inner pattern.
+    Tagged(Spanned<Name>, Option<Box<MatchPattern<'a>>>),
+    /// A constant expression, which matches values equal to it.
+    Expr(Box<Expr<'a>>),
+}
+
+/// A variable bound by a pattern.
+///
+/// For example the `v` in `tagged Valid .v`.
+#[moore_derive::node]
+#[indefinite("pattern variable")]
+#[definite("pattern variable `{}`", name)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternVar {
+    #[name]
+    pub name: Spanned<Name>,
 }
 
 #[moore_derive::visit]
@@ -1325,6 +1383,7 @@ pub enum Expr<'a> {
     /// A system identifier, like `$foo`.
     SysIdentExpr(Spanned<Name>),
     ThisExpr,
+    SuperExpr,
     DollarExpr,
     NullExpr,
     ScopeExpr(Box<Expr<'a>>, Spanned<Name>),
@@ -1358,7 +1417,12 @@ pub enum Expr<'a> {
     ClassNewExpr(Option<Box<Expr<'a>>>),
     ArrayNewExpr(Box<Expr<'a>>, Option<Box<Expr<'a>>>),
     EmptyQueueExpr,
+    /// A tagged union expression, like `tagged Valid 42`.
+    TaggedExpr(Spanned<Name>, Option<Box<Expr<'a>>>),
+    /// A pattern match, like `a matches tagged Valid .v`.
+    MatchesExpr(Box<Expr<'a>>, Box<MatchPattern<'a>>),
     StreamConcatExpr {
+        dir: StreamDir,
         slice: Option<StreamConcatSlice<'a>>,
         exprs: Vec<StreamExpr<'a>>,
     },
@@ -1523,6 +1587,16 @@ pub struct CallArg<'a> {
     pub expr: Option<Expr<'a>>,
 }
 
+/// The direction of a streaming concatenation.
+#[moore_derive::visit]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamDir {
+    /// A left-to-right stream `{>>{...}}`.
+    In,
+    /// A right-to-left stream `{<<{...}}`.
+    Out,
+}
+
 #[moore_derive::visit]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum StreamConcatSlice<'a> {
@@ -1590,6 +1664,7 @@ impl HasDesc for EventExpr<'_> {
 pub struct ClassDecl<'a> {
     pub virt: bool,
     pub lifetime: Lifetime, // default static
+    #[name]
     pub name: Spanned<Name>,
     pub params: Vec<ParamDecl<'a>>,
     pub extends: Option<(Type<'a>, Vec<CallArg<'a>>)>,
@@ -1621,7 +1696,7 @@ pub enum ClassItemQualifier {
 #[moore_derive::visit]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ClassItemData<'a> {
-    Property,
+    Property(VarDecl<'a>),
     Typedef(Typedef<'a>),
     SubroutineDecl(SubroutineDecl<'a>),
     ExternSubroutine(SubroutinePrototype<'a>),
@@ -1762,6 +1837,18 @@ pub enum SubroutinePortDir {
     ConstRef,
 }
 
+impl std::fmt::Display for SubroutinePortDir {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SubroutinePortDir::Input => write!(f, "input"),
+            SubroutinePortDir::Output => write!(f, "output"),
+            SubroutinePortDir::Inout => write!(f, "inout"),
+            SubroutinePortDir::Ref => write!(f, "ref"),
+            SubroutinePortDir::ConstRef => write!(f, "const ref"),
+        }
+    }
+}
+
 /// A net declaration.
 ///
 /// For example `wire x, y, z`.
@@ -1848,6 +1935,54 @@ pub struct ImportItem {
     pub name: Option<Spanned<Name>>, // None means `import pkg::*`
 }
 
+/// An export declaration.
+///
+/// For example `export a::b, c::*`.
+#[moore_derive::node]
+#[indefinite("export declaration")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportDecl<'a> {
+    pub items: Vec<ExportItem<'a>>,
+}
+
+/// A single export.
+///
+/// For example the `a::b` in `export a::b, c::*`.
+#[moore_derive::node]
+#[indefinite("export")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportItem {
+    pub pkg: Option<Spanned<Name>>,  // None means `export *::*`
+    pub name: Option<Spanned<Name>>, // None means `export pkg::*`
+}
+
+/// A let declaration.
+///
+/// For example `let max(a, b) = a > b ? a : b`.
+#[moore_derive::node]
+#[indefinite("let declaration")]
+#[definite("let declaration `{}`", name)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LetDecl<'a> {
+    #[name]
+    pub name: Spanned<Name>,
+    pub args: Vec<LetArg<'a>>,
+    pub expr: Expr<'a>,
+}
+
+/// A formal argument of a let declaration.
+///
+/// For example the `a` or `b = 1` in `let foo(a, b = 1) = a + b`.
+#[moore_derive::node]
+#[indefinite("let argument")]
+#[definite("let argument `{}`", name)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LetArg<'a> {
+    #[name]
+    pub name: Spanned<Name>,
+    pub default: Option<Expr<'a>>,
+}
+
 #[moore_derive::visit]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Assertion<'a> {
@@ -1865,7 +2000,7 @@ pub enum AssertionData<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AssertionDeferred {
     /// `assert #0`
     Observed,
@@ -1884,12 +2019,12 @@ pub enum BlockingAssertion<'a> {
 #[moore_derive::visit]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ConcurrentAssertion<'a> {
-    AssertProperty(PropSpec, AssertionActionBlock<'a>),
-    AssumeProperty(PropSpec, AssertionActionBlock<'a>),
-    CoverProperty(PropSpec, Stmt<'a>),
+    AssertProperty(PropSpec<'a>, AssertionActionBlock<'a>),
+    AssumeProperty(PropSpec<'a>, AssertionActionBlock<'a>),
+    CoverProperty(PropSpec<'a>, Stmt<'a>),
     CoverSequence,
-    ExpectProperty(PropSpec, AssertionActionBlock<'a>),
-    RestrictProperty(PropSpec),
+    ExpectProperty(PropSpec<'a>, AssertionActionBlock<'a>),
+    RestrictProperty(PropSpec<'a>),
 }
 
 #[moore_derive::visit]
@@ -1914,6 +2049,15 @@ pub enum SeqExprData<'a> {
     BinOp(SeqBinOp, Box<SeqExpr<'a>>, Box<SeqExpr<'a>>),
     Throughout(Expr<'a>, Box<SeqExpr<'a>>),
     Clocked(EventExpr<'a>, Box<SeqExpr<'a>>),
+    /// A cycle delay `[<lhs>] ##<delay> <rhs>`.
+    Delay(Option<Box<SeqExpr<'a>>>, SeqDelay<'a>, Box<SeqExpr<'a>>),
+}
+
+#[moore_derive::visit]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeqDelay<'a> {
+    Fixed(Expr<'a>),           // ## expr
+    Range(Expr<'a>, Expr<'a>), // ##[ expr : expr ]
 }
 
 #[moore_derive::visit]
@@ -1935,9 +2079,19 @@ pub enum SeqBinOp {
     Within,
 }
 
+/// A property specification.
+///
+/// ```text
+/// [clocking_event] ["disable" "iff" "(" expr ")"] property_expr
+/// ```
 #[moore_derive::visit]
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct PropSpec;
+pub struct PropSpec<'a> {
+    pub span: Span,
+    pub clock: Option<EventExpr<'a>>,
+    pub disable: Option<Expr<'a>>,
+    pub prop: PropExpr<'a>,
+}
 
 #[moore_derive::visit]
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -2183,8 +2337,17 @@ pub struct GenerateIf<'a> {
 #[moore_derive::node]
 #[indefinite("case-generate statement")]
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct GenerateCase {
-    // TODO
+pub struct GenerateCase<'a> {
+    pub expr: Expr<'a>,
+    pub items: Vec<GenerateCaseItem<'a>>,
+}
+
+/// An item of a `case` generate statement.
+#[moore_derive::visit]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenerateCaseItem<'a> {
+    Default(GenerateBlock<'a>),
+    Expr(Vec<Expr<'a>>, GenerateBlock<'a>),
 }
 
 /// A body of a generate construct.
@@ -262,6 +262,22 @@ impl<'a> Lexer<'a> {
                     };
                 }
 
+                // Compiler directives that are not handled by the preprocessor
+                // are forwarded as a backtick followed by the directive name.
+                (CatTokenKind::Symbol('`'), sp) => {
+                    self.bump()?;
+                    return match self.peek[0].0 {
+                        CatTokenKind::Text | CatTokenKind::Symbol('_') => {
+                            let (m, msp) = self.match_ident()?;
+                            Ok((CompDir(name_table.intern(&m, true)), Span::union(sp, msp)))
+                        }
+                        _ => {
+                            Err(DiagBuilder2::fatal("expected compiler directive after '`'")
+                                .span(sp))
+                        }
+                    };
+                }
+
                 // Escaped identifiers are introduced with a backslash and last
                 // until the next whitespace or newline character.
                 // IEEE 1800-2009 5.6.1 Escaped identifiers
@@ -314,6 +330,9 @@ impl<'a> Lexer<'a> {
                     } else {
                         None
                     };
+                    let exp = self
+                        .try_exponent(&mut sp)?
+                        .map(|s| name_table.intern(&s, true));
                     if let Some(unit) = self.try_time_unit() {
                         sp.expand(self.peek[0].1);
                         self.bump()?; // eat the unit
@@ -327,8 +346,8 @@ impl<'a> Lexer<'a> {
                         ))
                         .span(sp));
                     }
-                    if frac.is_some() {
-                        return Ok((Literal(Number(value, frac)), sp));
+                    if frac.is_some() || exp.is_some() {
+                        return Ok((Literal(Number(value, frac, exp)), sp));
                     }
                     self.skip_noise()?; // whitespace allowed after size indication
                     match (self.peek[0].0, self.peek[1].0) {
@@ -337,7 +356,7 @@ impl<'a> Lexer<'a> {
                             self.bump()?; // eat the apostrophe
                             return self.match_based_number(Some(value), sp);
                         }
-                        _ => return Ok((Literal(Number(value, None)), sp)),
+                        _ => return Ok((Literal(Number(value, None, None)), sp)),
                     }
                 }
 
@@ -369,7 +388,65 @@ impl<'a> Lexer<'a> {
                                     }
                                     (CatTokenKind::Text, sp) => {
                                         span.expand(sp);
-                                        s.push_str(&sp.extract());
+                                        let text = sp.extract();
+                                        let mut chars = text.chars();
+                                        match chars.next() {
+                                            Some('n') => s.push('\n'),
+                                            Some('t') => s.push('\t'),
+                                            Some('v') => s.push('\x0b'),
+                                            Some('f') => s.push('\x0c'),
+                                            Some('a') => s.push('\x07'),
+                                            Some('x') => {
+                                                // A character given by one or
+                                                // two hexadecimal digits.
+                                                let rest = chars.as_str();
+                                                let len = rest
+                                                    .chars()
+                                                    .take(2)
+                                                    .take_while(|c| c.is_ascii_hexdigit())
+                                                    .count();
+                                                if len == 0 {
+                                                    return Err(DiagBuilder2::fatal(
+                                                        "Expected hexadecimal digits after `\\x` in string",
+                                                    )
+                                                    .span(span));
+                                                }
+                                                let code =
+                                                    u8::from_str_radix(&rest[..len], 16).unwrap();
+                                                s.push(code as char);
+                                                chars = rest[len..].chars();
+                                            }
+                                            Some(c) => s.push(c),
+                                            None => (),
+                                        }
+                                        s.push_str(chars.as_str());
+                                    }
+                                    (CatTokenKind::Digits, sp) => {
+                                        // A character given by one to three
+                                        // octal digits.
+                                        span.expand(sp);
+                                        let text = sp.extract();
+                                        let len = text
+                                            .chars()
+                                            .take(3)
+                                            .take_while(|c| c.is_digit(8))
+                                            .count();
+                                        if len == 0 {
+                                            return Err(DiagBuilder2::fatal(
+                                                "Expected octal digits after `\\` in string",
+                                            )
+                                            .span(span));
+                                        }
+                                        let code = u32::from_str_radix(&text[..len], 8).unwrap();
+                                        if code > 0o377 {
+                                            return Err(DiagBuilder2::fatal(format!(
+                                                "Octal escape `\\{}` in string is larger than `\\377`",
+                                                &text[..len]
+                                            ))
+                                            .span(span));
+                                        }
+                                        s.push(code as u8 as char);
+                                        s.push_str(&text[len..]);
                                     }
                                     _ => {
                                         return Err(DiagBuilder2::fatal(
@@ -631,6 +708,40 @@ impl<'a> Lexer<'a> {
         Ok(())
     }
 
+    /// Try to parse the exponent of a real number, such as `e-9` or `E3`.
+    ///
+    /// Returns the exponent's optional sign and digits.
+    fn try_exponent(&mut self, span: &mut Span) -> DiagResult2<Option<String>> {
+        if self.peek[0].0 != CatTokenKind::Text {
+            return Ok(None);
+        }
+        let text = self.peek[0].1.extract();
+        if !text.starts_with('e') && !text.starts_with('E') {
+            return Ok(None);
+        }
+        let mut s = String::new();
+        if text.len() == 1 {
+            // The sign is lexed as a separate symbol.
+            match (self.peek[1].0, self.peek[2].0) {
+                (CatTokenKind::Symbol(c), CatTokenKind::Digits) if c == '+' || c == '-' => {
+                    s.push(c);
+                    span.expand(self.peek[1].1);
+                    self.bump()?; // eat the `e`
+                    self.bump()?; // eat the sign
+                }
+                _ => return Ok(None),
+            }
+        } else if text[1..].chars().all(|c| c.is_ascii_digit()) {
+            s.push_str(&text[1..]);
+            span.expand(self.peek[0].1);
+            self.bump()?; // eat the `e` and the digits
+        } else {
+            return Ok(None);
+        }
+        self.eat_number_body_into(&mut s, span, false)?;
+        Ok(Some(s))
+    }
+
     /// Try to parse the next text token as a time unit.
     fn try_time_unit(&mut self) -> Option<TimeUnit> {
         if self.peek[0].0 == CatTokenKind::Text {
@@ -750,7 +861,7 @@ mod tests {
         check(
             "659; 'h 837FF; 'o7460",
             &[
-                Literal(Number(name("659"), None)),
+                Literal(Number(name("659"), None, None)),
                 Semicolon,
                 Literal(BasedInteger(None, false, 'h', name("837FF"))),
                 Semicolon,
@@ -801,7 +912,7 @@ mod tests {
         check(
             "27_195_000; 16'b0011_0101_0001_1111; 32 'h 12ab_f001",
             &[
-                Literal(Number(name("27195000"), None)),
+                Literal(Number(name("27195000"), None, None)),
                 Semicolon,
                 Literal(BasedInteger(
                     Some(name("16")),
@@ -831,6 +942,34 @@ mod tests {
         );
     }
 
+    /// According to IEEE 1800-2009 5.9.1
+    #[test]
+    fn string_literal_escapes() {
+        check(
+            "\"a\\nb\\tc\\\\d\\\"e\"",
+            &[Literal(Str(name("a\nb\tc\\d\"e")))],
+        );
+    }
+
+    /// According to IEEE 1800-2017 5.9.1
+    #[test]
+    fn string_literal_char_escapes() {
+        check(
+            "\"\\x41\\x4a2\\101\\0\\1019\"",
+            &[Literal(Str(name("AJ2A\0A9")))],
+        );
+    }
+
+    /// According to IEEE 1800-2017 5.9.1
+    #[test]
+    fn string_literal_octal_escape_too_large() {
+        let sm = get_source_manager();
+        let source = sm.add("test_octal_escape.sv", "\"\\377\\400\"");
+        let pp = Preprocessor::new(source, &[], &[]);
+        let mut lexer = Lexer::new(pp);
+        assert!(lexer.next().unwrap().is_err());
+    }
+
     #[test]
     fn time_literal() {
         check(
@@ -849,10 +988,13 @@ mod tests {
     #[test]
     fn number_literal() {
         check(
-            "42 4.2",
+            "42 4.2 1e-9 2.5E3 3e+10",
             &[
-                Literal(Number(name("42"), None)),
-                Literal(Number(name("4"), Some(name("2")))),
+                Literal(Number(name("42"), None, None)),
+                Literal(Number(name("4"), Some(name("2")), None)),
+                Literal(Number(name("1"), None, Some(name("-9")))),
+                Literal(Number(name("2"), Some(name("5")), Some(name("3")))),
+                Literal(Number(name("3"), None, Some(name("+10")))),
             ],
         );
     }
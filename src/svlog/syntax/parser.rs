@@ -683,6 +683,45 @@ fn parse_time_units<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Timeun
     Ok(Timeunit { unit, prec })
 }
 
+fn parse_timescale_directive<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Timeunit> {
+    p.bump(); // eat the "`timescale"
+    let unit = parse_timescale_value(p)?;
+    p.require_reported(Operator(Op::Div))?;
+    let prec = parse_timescale_value(p)?;
+    Ok(Timeunit {
+        unit: Some(unit),
+        prec: Some(prec),
+    })
+}
+
+/// Parse a time value in a "`timescale" directive. In contrast to a regular
+/// time literal, the number and the unit may be separated by whitespace.
+fn parse_timescale_value<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Spanned<Lit>> {
+    let (tkn, sp) = p.peek(0);
+    if let Literal(Number(value, None, None)) = tkn {
+        if let (Ident(unit), unit_sp) = p.peek(1) {
+            let unit = match &*unit.as_str() {
+                "s" => Some(TimeUnit::Second),
+                "ms" => Some(TimeUnit::MilliSecond),
+                "us" => Some(TimeUnit::MicroSecond),
+                "ns" => Some(TimeUnit::NanoSecond),
+                "ps" => Some(TimeUnit::PicoSecond),
+                "fs" => Some(TimeUnit::FemtoSecond),
+                _ => None,
+            };
+            if let Some(unit) = unit {
+                p.bump();
+                p.bump();
+                return Ok(Spanned::new(
+                    Time(value, None, unit),
+                    Span::union(sp, unit_sp),
+                ));
+            }
+        }
+    }
+    parse_time_literal(p)
+}
+
 fn parse_time_literal<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Spanned<Lit>> {
     let (tkn, sp) = p.peek(0);
     match tkn {
@@ -754,6 +793,9 @@ fn parse_interface_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<In
             );
         }
 
+        // Eat the optional timeunits declaration.
+        let timeunits = parse_time_units(p)?;
+
         // Eat the items in the interface.
         let mut items = Vec::new();
         while !p.is_fatal() && p.peek(0).0 != Keyword(Kw::Endinterface) && p.peek(0).0 != Eof {
@@ -771,6 +813,7 @@ fn parse_interface_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<In
                 name: Spanned::new(name, name_sp),
                 params: param_ports,
                 ports: ports,
+                timeunits: timeunits,
                 items: items,
             },
         ))
@@ -873,9 +916,26 @@ fn parse_parameter_port_list<'n>(
 /// Parse a module declaration, assuming that the leading `module` keyword has
 /// already been consumed.
 fn parse_module_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Module<'n>> {
+    parse_module_or_program_decl(p, ModuleKind::Module)
+}
+
+fn parse_program_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Module<'n>> {
+    parse_module_or_program_decl(p, ModuleKind::Program)
+}
+
+/// Parse a module or program declaration. The two only differ in their
+/// keywords, and in how their processes are scheduled.
+fn parse_module_or_program_decl<'n>(
+    p: &mut dyn AbstractParser<'n>,
+    kind: ModuleKind,
+) -> ReportedResult<Module<'n>> {
+    let (kw, end_kw, what) = match kind {
+        ModuleKind::Module => (Kw::Module, Kw::Endmodule, "module"),
+        ModuleKind::Program => (Kw::Program, Kw::Endprogram, "program"),
+    };
     let mut span = p.peek(0).1;
-    p.require_reported(Keyword(Kw::Module))?;
-    let result = recovered(p, Keyword(Kw::Endmodule), |p| {
+    p.require_reported(Keyword(kw))?;
+    let result = recovered(p, Keyword(end_kw), |p| {
         // Eat the optional lifetime.
         let lifetime = match as_lifetime(p.peek(0).0) {
             Some(l) => {
@@ -886,7 +946,7 @@ fn parse_module_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Modul
         };
 
         // Eat the module name.
-        let (name, name_sp) = p.eat_ident("module name")?;
+        let (name, name_sp) = p.eat_ident(&format!("{} name", what))?;
 
         // TODO: Parse package import declarations.
         // Eat the optional package import declarations.
@@ -914,14 +974,17 @@ fn parse_module_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Modul
         if !p.try_eat(Semicolon) {
             let q = p.peek(0).1.end();
             p.add_diag(
-                DiagBuilder2::error(format!("Missing ; after header of module \"{}\"", name))
+                DiagBuilder2::error(format!("Missing ; after header of {} \"{}\"", what, name))
                     .span(q),
             );
         }
 
+        // Parse the optional timeunits declaration.
+        let timeunits = parse_time_units(p)?;
+
         // Parse the module items.
         let mut items = Vec::new();
-        while !p.is_fatal() && p.peek(0).0 != Keyword(Kw::Endmodule) && p.peek(0).0 != Eof {
+        while !p.is_fatal() && p.peek(0).0 != Keyword(end_kw) && p.peek(0).0 != Eof {
             if p.try_eat(Semicolon) {
                 continue;
             }
@@ -932,19 +995,20 @@ fn parse_module_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Modul
         Ok(Module::new(
             span,
             ModuleData {
+                kind,
                 lifetime,
                 name: Spanned::new(name, name_sp),
                 imports,
                 params,
                 ports,
+                timeunits,
                 items,
             },
         ))
     });
-    let sp = p.peek(0).1;
-    p.require_reported(Keyword(Kw::Endmodule))?;
+    p.require_reported(Keyword(end_kw))?;
     if p.try_eat(Colon) {
-        p.eat_ident("module name")?;
+        p.eat_ident(&format!("{} name", what))?;
     }
     result
 }
@@ -966,11 +1030,8 @@ fn parse_package_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Pack
         let (name, name_span) = p.eat_ident("package name")?;
         p.require_reported(Semicolon)?;
 
-        // TODO: Parse the optional timeunits declaration.
-        let timeunits = Timeunit {
-            unit: None,
-            prec: None,
-        };
+        // Parse the optional timeunits declaration.
+        let timeunits = parse_time_units(p)?;
 
         // Parse the package items.
         let mut items = Vec::new();
@@ -999,17 +1060,6 @@ fn parse_package_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Pack
     result
 }
 
-fn parse_program_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<()> {
-    p.require_reported(Keyword(Kw::Program))?;
-    let result = recovered(p, Keyword(Kw::Endprogram), |p| {
-        let q = p.peek(0).1;
-        p.add_diag(DiagBuilder2::error("Don't know how to parse program declarations").span(q));
-        Err(())
-    });
-    p.require_reported(Keyword(Kw::Endprogram))?;
-    result
-}
-
 fn parse_item<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Item<'n>> {
     let mut span = p.peek(0).1;
     let item = parse_item_data(p)?;
@@ -1032,6 +1082,9 @@ fn parse_item_data<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ItemDat
         _ => false,
     };
     match p.peek(0).0 {
+        CompDir(name) if &*name.as_str() == "timescale" => {
+            return parse_timescale_directive(p).map(ItemData::Timescale)
+        }
         Keyword(Kw::Module) => return parse_module_decl(p).map(ItemData::ModuleDecl),
         Keyword(Kw::Interface) | Keyword(Kw::Virtual) if class_follows => {
             return parse_class_decl(p).map(ItemData::ClassDecl)
@@ -1049,8 +1102,10 @@ fn parse_item_data<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ItemDat
         Keyword(Kw::Modport) => return parse_modport_decl(p).map(|x| ItemData::ModportDecl(x)),
         Keyword(Kw::Typedef) => return parse_typedef(p).map(|x| ItemData::Typedef(x)),
         Keyword(Kw::Import) if strlit_follows => return parse_dpi_decl(p).map(ItemData::DpiDecl),
-        Keyword(Kw::Export) => return parse_dpi_decl(p).map(ItemData::DpiDecl),
+        Keyword(Kw::Export) if strlit_follows => return parse_dpi_decl(p).map(ItemData::DpiDecl),
         Keyword(Kw::Import) => return parse_import_decl(p).map(|x| ItemData::ImportDecl(x)),
+        Keyword(Kw::Export) => return parse_export_decl(p).map(|x| ItemData::ExportDecl(x)),
+        Keyword(Kw::Let) => return parse_let_decl(p).map(|x| ItemData::LetDecl(x)),
 
         // Structured procedures as per IEEE 1800-2009 section 9.2
         Keyword(Kw::Initial) => {
@@ -1112,6 +1167,14 @@ fn parse_item_data<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ItemDat
         | Keyword(Kw::Cover)
         | Keyword(Kw::Expect)
         | Keyword(Kw::Restrict) => return parse_assertion(p).map(|x| ItemData::Assertion(x)),
+        Ident(name) if p.peek(1).0 == Colon && is_assertion_keyword(p.peek(2).0) => {
+            let label_span = p.peek(0).1;
+            p.bump();
+            p.bump();
+            let mut assertion = parse_assertion(p)?;
+            assertion.label = Some((name, label_span));
+            return Ok(ItemData::Assertion(assertion));
+        }
         Semicolon => {
             p.bump();
             return Ok(ItemData::Dummy);
@@ -2094,7 +2157,7 @@ fn parse_expr_suffix<'n>(
     let (tkn, sp) = p.peek(0);
     match tkn {
         // Index: "[" range_expression "]"
-        OpenDelim(Brack) if precedence <= Precedence::Postfix => {
+        OpenDelim(Brack) if precedence <= Precedence::Postfix && !is_seqrep_start(p) => {
             p.bump();
             let expr = match parse_range_expr(p) {
                 Ok(x) => x,
@@ -2135,7 +2198,12 @@ fn parse_expr_suffix<'n>(
         // expr "." ident
         Period if precedence <= Precedence::Scope => {
             p.bump();
-            let name = parse_identifier_name(p, "member name")?;
+            // Accept the `new` in `super.new(...)` as a member name.
+            let name = if p.try_eat(Keyword(Kw::New)) {
+                Spanned::new(get_name_table().intern("new", true), p.last_span())
+            } else {
+                parse_identifier_name(p, "member name")?
+            };
             let expr = Expr::new(
                 Span::union(prefix.span, p.last_span()),
                 MemberExpr {
@@ -2228,6 +2296,18 @@ fn parse_expr_suffix<'n>(
             return parse_expr_suffix(p, expr, precedence);
         }
 
+        // expr "matches" pattern
+        Keyword(Kw::Matches) if precedence < Precedence::Ternary => {
+            p.bump();
+            let pattern = parse_match_pattern(p)?;
+            parse_pattern_guard(p)?;
+            let expr = Expr::new(
+                Span::union(prefix.span, p.last_span()),
+                MatchesExpr(Box::new(prefix), Box::new(pattern)),
+            );
+            return parse_expr_suffix(p, expr, precedence);
+        }
+
         // expr "'" "(" expr ")"
         Apostrophe if precedence <= Precedence::Postfix => {
             p.bump();
@@ -2314,9 +2394,21 @@ fn parse_expr_first<'n>(
             ));
         }
 
-        (Keyword(Kw::Tagged), sp) => {
-            p.add_diag(DiagBuilder2::error("Tagged union expressions not implemented").span(sp));
-            return Err(());
+        // "tagged" member_identifier [primary]
+        (Keyword(Kw::Tagged), _) => {
+            p.bump();
+            let member = parse_identifier_name(p, "union member name")?;
+            let expr = match p.peek(0).0 {
+                Literal(_) | Ident(_) | EscIdent(_) | SysIdent(_) | OpenDelim(Paren)
+                | OpenDelim(Brace) | Apostrophe | Keyword(Kw::Tagged) => {
+                    Some(Box::new(parse_expr_prec(p, Precedence::Postfix)?))
+                }
+                _ => None,
+            };
+            return Ok(Expr::new(
+                Span::union(first, p.last_span()),
+                TaggedExpr(member, expr),
+            ));
         }
 
         _ => (),
@@ -2365,6 +2457,12 @@ fn parse_primary_expr<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Expr
             return Ok(Expr::new(sp, ThisExpr));
         }
 
+        // `super`
+        Keyword(Kw::Super) => {
+            p.bump();
+            return Ok(Expr::new(sp, SuperExpr));
+        }
+
         // `$`
         Dollar => {
             p.bump();
@@ -2430,6 +2528,65 @@ fn parse_primary_expr<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Expr
     }
 }
 
+/// Parse a pattern of a `matches` expression or `case ... matches` statement.
+fn parse_match_pattern<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<MatchPattern<'n>> {
+    let (tkn, sp) = p.peek(0);
+    match tkn {
+        // "." "*"
+        // "." ident
+        Period => {
+            p.bump();
+            if p.try_eat(Operator(Op::Mul)) {
+                return Ok(MatchPattern::Wildcard(Span::union(sp, p.last_span())));
+            }
+            let name = parse_identifier_name(p, "pattern variable name")?;
+            Ok(MatchPattern::Var(PatternVar::new(
+                Span::union(sp, name.span),
+                PatternVarData { name },
+            )))
+        }
+
+        // "tagged" member_identifier [pattern]
+        Keyword(Kw::Tagged) => {
+            p.bump();
+            let member = parse_identifier_name(p, "union member name")?;
+            let inner = match p.peek(0).0 {
+                Period | Literal(_) | Ident(_) | EscIdent(_) | OpenDelim(Paren)
+                | Keyword(Kw::Tagged) => Some(Box::new(parse_match_pattern(p)?)),
+                _ => None,
+            };
+            Ok(MatchPattern::Tagged(member, inner))
+        }
+
+        // constant_expression
+        _ => Ok(MatchPattern::Expr(Box::new(parse_expr_prec(
+            p,
+            Precedence::Unary,
+        )?))),
+    }
+}
+
+/// Diagnose a `&&&` guard following a pattern, which is not supported.
+///
+/// The lexer splits `&&&` into `&&` and `&`, such that the guard would
+/// otherwise be parsed as a logical and with a reduction.
+fn parse_pattern_guard<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<()> {
+    let (tkn0, sp0) = p.peek(0);
+    let (tkn1, sp1) = p.peek(1);
+    if tkn0 != Operator(Op::LogicAnd) || tkn1 != Operator(Op::BitAnd) || sp0.end != sp1.begin {
+        return Ok(());
+    }
+    p.bump();
+    p.bump();
+    parse_expr_prec(p, Precedence::LogicAnd)?;
+    p.add_diag(
+        DiagBuilder2::error("unsupported: `&&&` guard in pattern")
+            .span(Span::union(sp0, p.last_span()))
+            .add_note("Match the pattern and check the guard in a nested `if` instead"),
+    );
+    Err(())
+}
+
 fn parse_pattern_field<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<PatternField<'n>> {
     let mut span = p.peek(0).1;
 
@@ -2493,11 +2650,6 @@ fn parse_pattern_field<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Pat
     Ok(PatternField::new(span, data))
 }
 
-pub enum StreamDir {
-    In,
-    Out,
-}
-
 fn parse_concat_expr<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ExprData<'n>> {
     // Streaming concatenations have a "<<" or ">>" following the opening "{".
     let stream = match p.peek(0).0 {
@@ -2550,6 +2702,7 @@ fn parse_concat_expr<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ExprD
         })?;
 
         return Ok(StreamConcatExpr {
+            dir,
             slice: slice_size,
             exprs: exprs,
         });
@@ -3900,6 +4053,26 @@ fn parse_case<'n>(
             let stmt = Box::new(parse_stmt(p)?);
             items.push(CaseItem::Default(stmt));
         }
+        // Handle pattern case items.
+        else if mode == CaseMode::Pattern {
+            let pattern = match parse_match_pattern(p).and_then(|x| {
+                parse_pattern_guard(p)?;
+                Ok(x)
+            }) {
+                Ok(x) => x,
+                Err(()) => {
+                    p.recover_balanced(&[Colon], false);
+                    return Err(());
+                }
+            };
+            p.require_reported(Colon)?;
+            let stmt = Box::new(parse_stmt(p)?);
+            span.expand(p.last_span());
+            items.push(CaseItem::Pattern(Box::new(CasePattern::new(
+                span,
+                CasePatternData { pattern, stmt },
+            ))));
+        }
         // Handle regular case items.
         else {
             let mut exprs = Vec::new();
@@ -4404,13 +4577,46 @@ fn parse_generate_if<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Gener
 fn parse_generate_case<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<GenerateCase<'n>> {
     let mut span = p.peek(0).1;
     p.require_reported(Keyword(Kw::Case))?;
-    p.recover_balanced(&[Keyword(Kw::Endcase)], true);
-    if p.try_eat(Colon) {
-        parse_identifier_name(p, "generate block label")?;
+    let expr = flanked(p, Paren, parse_expr)?;
+
+    // Parse the case items.
+    let mut items = Vec::new();
+    while p.peek(0).0 != Keyword(Kw::Endcase) && p.peek(0).0 != Eof {
+        // Handle the default case items.
+        if p.try_eat(Keyword(Kw::Default)) {
+            p.try_eat(Colon);
+            items.push(GenerateCaseItem::Default(parse_generate_block(p)?));
+            continue;
+        }
+
+        // Handle regular case items.
+        let mut exprs = Vec::new();
+        loop {
+            match parse_expr(p) {
+                Ok(x) => exprs.push(x),
+                Err(()) => {
+                    p.recover_balanced(&[Colon], false);
+                    break;
+                }
+            }
+            match p.peek(0) {
+                (Comma, _) => p.bump(),
+                (Colon, _) => break,
+                (_, sp) => {
+                    p.add_diag(
+                        DiagBuilder2::error("expected , or : after case expression").span(sp),
+                    );
+                    break;
+                }
+            }
+        }
+        p.require_reported(Colon)?;
+        let block = parse_generate_block(p)?;
+        items.push(GenerateCaseItem::Expr(exprs, block));
     }
+    p.require_reported(Keyword(Kw::Endcase))?;
     span.expand(p.last_span());
-    p.add_diag(DiagBuilder2::error("case-generate statements not supported").span(span));
-    Ok(GenerateCase::new(span, GenerateCaseData {}))
+    Ok(GenerateCase::new(span, GenerateCaseData { expr, items }))
 }
 
 fn parse_generate_block<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<GenerateBlock<'n>> {
@@ -4658,6 +4864,7 @@ fn parse_class_item<'n>(
     let data = {
         let mut pp = ParallelParser::new();
         pp.add("class property", |p| {
+            let mut span = p.peek(0).1;
             let ty = parse_data_type(p)?;
             let names = comma_list_nonempty(
                 p,
@@ -4666,7 +4873,17 @@ fn parse_class_item<'n>(
                 parse_variable_decl_assignment,
             )?;
             p.require_reported(Semicolon)?;
-            Ok(ClassItemData::Property)
+            span.expand(p.last_span());
+            Ok(ClassItemData::Property(VarDecl::new(
+                span,
+                VarDeclData {
+                    konst: false,
+                    var: false,
+                    lifetime: None,
+                    ty,
+                    names,
+                },
+            )))
         });
         if intf {
             pp.add("class function or task prototype", |p| {
@@ -5410,6 +5627,92 @@ fn parse_import_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Impor
     Ok(ImportDecl::new(span, ImportDeclData { items }))
 }
 
+fn parse_export_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ExportDecl<'n>> {
+    let mut span = p.peek(0).1;
+    p.require_reported(Keyword(Kw::Export))?;
+    let items = comma_list_nonempty(p, Semicolon, "export item", |p| {
+        // "*" "::" "*"
+        // package_ident "::" ident
+        // package_ident "::" "*"
+        let mut span = p.peek(0).1;
+        let pkg = if p.try_eat(Operator(Op::Mul)) {
+            None
+        } else {
+            Some(parse_identifier_name(p, "package name")?)
+        };
+        p.require_reported(Namespace)?;
+        let (tkn, sp) = p.peek(0);
+        match tkn {
+            // package_ident "::" "*"
+            Operator(Op::Mul) => {
+                p.bump();
+                span.expand(p.last_span());
+                Ok(ExportItem::new(span, ExportItemData { pkg, name: None }))
+            }
+
+            // package_ident "::" ident
+            Ident(n) | EscIdent(n) if pkg.is_some() => {
+                p.bump();
+                span.expand(p.last_span());
+                Ok(ExportItem::new(
+                    span,
+                    ExportItemData {
+                        pkg,
+                        name: Some(Spanned::new(n, sp)),
+                    },
+                ))
+            }
+
+            _ => {
+                p.add_diag(
+                    DiagBuilder2::error(if pkg.is_some() {
+                        "expected identifier or `*` after `::` in export declaration"
+                    } else {
+                        "expected `*` after `*::` in export declaration"
+                    })
+                    .span(sp),
+                );
+                Err(())
+            }
+        }
+    })?;
+    p.require_reported(Semicolon)?;
+    span.expand(p.last_span());
+    Ok(ExportDecl::new(span, ExportDeclData { items }))
+}
+
+fn parse_let_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<LetDecl<'n>> {
+    let mut span = p.peek(0).1;
+    p.require_reported(Keyword(Kw::Let))?;
+    let name = parse_identifier_name(p, "let name")?;
+
+    // Parse the optional list of formal arguments.
+    let args = if p.try_eat(OpenDelim(Paren)) {
+        let args = comma_list(p, CloseDelim(Paren), "let argument", |p| {
+            let mut span = p.peek(0).1;
+            p.try_eat(Keyword(Kw::Untyped));
+            let name = parse_identifier_name(p, "let argument name")?;
+            let default = if p.try_eat(Operator(Op::Assign)) {
+                Some(parse_expr(p)?)
+            } else {
+                None
+            };
+            span.expand(p.last_span());
+            Ok(LetArg::new(span, LetArgData { name, default }))
+        })?;
+        p.require_reported(CloseDelim(Paren))?;
+        args
+    } else {
+        vec![]
+    };
+
+    p.require_reported(Operator(Op::Assign))?;
+    let expr = parse_expr(p)?;
+    p.require_reported(Semicolon)?;
+    span.expand(p.last_span());
+    Ok(LetDecl::new(span, LetDeclData { name, args, expr }))
+}
+
 fn parse_assertion<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Assertion<'n>> {
     let mut span = p.peek(0).1;
 
@@ -5419,7 +5722,8 @@ fn parse_assertion<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Asserti
     let null = get_name_table().intern("0", false);
     let is_property = p.peek(1).0 == Keyword(Kw::Property);
     let is_sequence = p.peek(1).0 == Keyword(Kw::Sequence);
-    let is_deferred_observed = p.peek(1).0 == Hashtag && p.peek(2).0 == Literal(Number(null, None));
+    let is_deferred_observed =
+        p.peek(1).0 == Hashtag && p.peek(2).0 == Literal(Number(null, None, None));
     let is_deferred_final = p.peek(1).0 == Keyword(Kw::Final);
     let is_deferred = is_deferred_observed || is_deferred_final;
     let deferred_mode = match is_deferred_final {
@@ -5562,6 +5866,18 @@ fn parse_assertion<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Asserti
     })
 }
 
+/// Check whether a token starts an assertion.
+fn is_assertion_keyword(tkn: Token) -> bool {
+    match tkn {
+        Keyword(Kw::Assert)
+        | Keyword(Kw::Assume)
+        | Keyword(Kw::Cover)
+        | Keyword(Kw::Expect)
+        | Keyword(Kw::Restrict) => true,
+        _ => false,
+    }
+}
+
 fn parse_assertion_action_block<'n>(
     p: &mut dyn AbstractParser<'n>,
 ) -> ReportedResult<AssertionActionBlock<'n>> {
@@ -5578,31 +5894,33 @@ fn parse_assertion_action_block<'n>(
     }
 }
 
-fn parse_property_spec<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<PropSpec> {
+fn parse_property_spec<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<PropSpec<'n>> {
     let mut span = p.peek(0).1;
 
-    // TODO: Actually parse this stuff, rather than just chicken out.
-    p.recover_balanced(&[CloseDelim(Paren)], false);
-    return Ok(PropSpec);
-
-    // // Parse the optional event expression.
-    // let event = if p.try_eat(At) {
-    //     Some(parse_event_expr(p, EventPrecedence::Min)?)
-    // } else {
-    //     None
-    // };
+    // Parse the optional event expression.
+    let clock = if p.try_eat(At) {
+        Some(parse_event_expr(p, EventPrecedence::Max)?)
+    } else {
+        None
+    };
 
-    // // Parse the optional "disable iff" clause.
-    // let disable = if p.try_eat(Keyword(Kw::Disable)) {
-    //     p.require_reported(Keyword(Kw::Iff))?;
-    //     Some(flanked(p, Paren, parse_expr)?)
-    // } else {
-    //     None
-    // };
+    // Parse the optional "disable iff" clause.
+    let disable = if p.try_eat(Keyword(Kw::Disable)) {
+        p.require_reported(Keyword(Kw::Iff))?;
+        Some(flanked(p, Paren, parse_expr)?)
+    } else {
+        None
+    };
 
-    // // Parse the property expression.
-    // let prop = parse_propexpr(p)?;
-    // Ok(PropSpec)
+    // Parse the property expression.
+    let prop = parse_propexpr(p)?;
+    span.expand(p.last_span());
+    Ok(PropSpec {
+        span,
+        clock,
+        disable,
+        prop,
+    })
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -5793,6 +6111,13 @@ fn parse_seqexpr_expr<'n>(
     p: &mut dyn AbstractParser<'n>,
     precedence: PropSeqPrecedence,
 ) -> ReportedResult<SeqExprData<'n>> {
+    // Handle sequences that start with a cycle delay, e.g. `##1 foo`.
+    if p.try_eat(DoubleHashtag) {
+        let delay = parse_seqdelay(p)?;
+        let rhs = parse_seqexpr_prec(p, PropSeqPrecedence::CycleDelay)?;
+        return Ok(SeqExprData::Delay(None, delay, Box::new(rhs)));
+    }
+
     // TODO: Handle all the non-trivial cases.
     let q = p.peek(0).1;
     p.add_diag(
@@ -5830,10 +6155,60 @@ fn parse_seqexpr_suffix<'n>(
     prefix: SeqExpr<'n>,
     precedence: PropSeqPrecedence,
 ) -> ReportedResult<SeqExpr<'n>> {
-    // TODO: Handle all the binary operators.
+    // Handle the cycle delay operator, e.g. `foo ##1 bar`.
+    if precedence < PropSeqPrecedence::CycleDelay && p.try_eat(DoubleHashtag) {
+        let delay = parse_seqdelay(p)?;
+        let rhs = parse_seqexpr_prec(p, PropSeqPrecedence::CycleDelay)?;
+        let expr = SeqExpr {
+            span: Span::union(prefix.span, rhs.span),
+            data: SeqExprData::Delay(Some(Box::new(prefix)), delay, Box::new(rhs)),
+        };
+        return parse_seqexpr_suffix(p, expr, precedence);
+    }
+
+    // TODO: Handle all the other binary operators.
     Ok(prefix)
 }
 
+/// Parse the delay value after a `##` cycle delay operator.
+fn parse_seqdelay<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<SeqDelay<'n>> {
+    let (tkn, sp) = p.peek(0);
+    match tkn {
+        // ##[m:n]
+        OpenDelim(Brack) => {
+            p.bump();
+            let lo = parse_expr(p)?;
+            p.require_reported(Colon)?;
+            let hi = parse_expr(p)?;
+            p.require_reported(CloseDelim(Brack))?;
+            Ok(SeqDelay::Range(lo, hi))
+        }
+
+        // ##(expr)
+        OpenDelim(Paren) => Ok(SeqDelay::Fixed(flanked(p, Paren, parse_expr)?)),
+
+        // ##1, ##N
+        Literal(Number(..)) | Ident(..) => {
+            Ok(SeqDelay::Fixed(parse_expr_first(p, Precedence::Max)?))
+        }
+
+        _ => {
+            p.add_diag(DiagBuilder2::error("expected cycle delay value after ##").span(sp));
+            Err(())
+        }
+    }
+}
+
+/// Check whether the parser is at the opening bracket of a sequence
+/// repetition, such as `[*3]`, rather than an index.
+fn is_seqrep_start(p: &mut dyn AbstractParser) -> bool {
+    match p.peek(1).0 {
+        Operator(Op::Mul) | Operator(Op::Assign) | Operator(Op::LogicImpl) => true,
+        Operator(Op::Add) => p.peek(2).0 == CloseDelim(Brack),
+        _ => false,
+    }
+}
+
 fn parse_seqrep<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<SeqRep<'n>> {
     match p.peek(0).0 {
         // [*]
@@ -416,7 +416,8 @@ impl<'a> Preprocessor<'a> {
                 }
             }
 
-            // Ignore the "`timescale" directive for now.
+            // Active "`timescale" directives are forwarded to the lexer in
+            // `next()`. Inside inactive regions we simply skip the line.
             Directive::Timescale => {
                 while let Some((tkn, _)) = self.token {
                     if tkn == Newline {
@@ -1108,6 +1109,16 @@ impl<'a> Iterator for Preprocessor<'a> {
                         // followed by text was encountered. In this case we
                         // call upon the handle_directive function to
                         // perform the necessary actions.
+                        // The "`timescale" directive is passed on to the
+                        // lexer and parser, which need to know where in the
+                        // source text it appears.
+                        if name == "timescale" && !self.is_inactive() {
+                            if let Some(tkn) = self.token {
+                                self.macro_stack.push(tkn);
+                            }
+                            self.token = Some((Text, sp));
+                            return Some(Ok((Symbol('`'), sp_backtick)));
+                        }
                         let dir_span = Span::union(sp_backtick, sp);
                         match self.handle_directive(name, dir_span) {
                             Err(x) => return Some(Err(x)),
@@ -123,8 +123,9 @@ pub enum Lit {
     BasedInteger(Option<Name>, bool, char, Name),
     /// One of `'0`, `'1`, `'x`, and `'z`.
     UnbasedUnsized(char),
-    /// A number given as integer and optional fractional part.
-    Number(Name, Option<Name>),
+    /// A number given as integer part, optional fractional part, and optional
+    /// exponent.
+    Number(Name, Option<Name>, Option<Name>),
     /// A time literal given as integer part, fractional part, and unit.
     Time(Name, Option<Name>, TimeUnit),
 }
@@ -0,0 +1,302 @@
+// Copyright (c) 2016-2020 Fabian Schuiki
+
+//! Time units and precisions.
+//!
+//! Delays without an explicit unit are given in the time unit of the enclosing
+//! module, interface, or package, and all delays are rounded to its time
+//! precision. The two are set by `timeunit` and `timeprecision` declarations,
+//! or by a preceding `` `timescale `` directive.
+
+use crate::crate_prelude::*;
+use crate::syntax::token::Lit;
+use num::{BigInt, BigRational, One};
+use std::sync::Arc;
+
+/// The time unit and precision in effect for a node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Timescale {
+    /// The time unit in seconds.
+    pub unit: BigRational,
+    /// The time precision in seconds.
+    pub prec: BigRational,
+}
+
+impl Timescale {
+    /// Round a time value in seconds to the precision.
+    pub fn round(&self, value: &BigRational) -> BigRational {
+        (value / &self.prec).round() * &self.prec
+    }
+
+    /// Convert a unitless value to seconds, rounded to the precision.
+    pub fn scale(&self, value: &BigRational) -> BigRational {
+        self.round(&(value * &self.unit))
+    }
+}
+
+impl Default for Timescale {
+    /// The timescale used if none is specified, `1ns / 1ns`.
+    fn default() -> Timescale {
+        let ns = BigRational::new(BigInt::one(), BigInt::from(1_000_000_000));
+        Timescale {
+            unit: ns.clone(),
+            prec: ns,
+        }
+    }
+}
+
+impl std::fmt::Display for Timescale {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} / {}",
+            DisplayTime(&self.unit),
+            DisplayTime(&self.prec)
+        )
+    }
+}
+
+/// Print a time value in seconds with the coarsest unit that represents it
+/// exactly.
+struct DisplayTime<'a>(&'a BigRational);
+
+impl std::fmt::Display for DisplayTime<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut value = self.0.clone();
+        for unit in &["s", "ms", "us", "ns", "ps", "fs"] {
+            if value.is_integer() {
+                return write!(f, "{}{}", value, unit);
+            }
+            value = value * BigInt::from(1000);
+        }
+        write!(f, "{}s", self.0)
+    }
+}
+
+/// Determine the timescale in effect for a node.
+///
+/// Modules, interfaces, and packages use their own `timeunit` and
+/// `timeprecision` declarations. Anything not declared there is inherited from
+/// the enclosing module for nested declarations, or otherwise taken from the
+/// last `` `timescale `` directive or the compilation unit's declarations. All
+/// other nodes use the timescale of the declaration they appear in.
+#[moore_derive::query]
+pub(crate) fn timescale_of<'a>(cx: &impl Context<'a>, node_id: NodeId) -> Arc<Timescale> {
+    let node = cx.ast_for_id(node_id);
+    let (name, timeunits, desc) = match node.as_all() {
+        ast::AllNode::Module(x) => (x.name, &x.timeunits, x.to_definite_string()),
+        ast::AllNode::Interface(x) => (x.name, &x.timeunits, x.to_definite_string()),
+        ast::AllNode::Package(x) => (x.name, &x.timeunits, x.to_definite_string()),
+        _ => {
+            return match node.get_parent() {
+                Some(parent) => cx.timescale_of(parent.id()),
+                None => Default::default(),
+            }
+        }
+    };
+
+    // Collect the declarations that contribute to the timescale, in order of
+    // decreasing precedence.
+    let mut sources = vec![timeunits];
+    let parent = enclosing_decl(node);
+    let mut context = None;
+    if parent.is_none() {
+        let context = context.get_or_insert(find_context(cx, node_id));
+        if let Some((file, directive)) = context.directive {
+            if file.span.source != context.file.span.source && timeunits.unit.is_none() {
+                cx.emit(
+                    DiagBuilder2::warning(format!(
+                        "{} uses the `timescale directive of another file",
+                        desc
+                    ))
+                    .span(name.span)
+                    .add_note("The timescale of the module depends on the order of the files:")
+                    .span(directive_span(directive)),
+                );
+            }
+            sources.push(directive);
+        }
+        sources.push(&context.file.timeunits);
+    }
+
+    // Resolve the time unit and precision.
+    let default = Timescale::default();
+    let mut unit = None;
+    let mut prec = None;
+    for source in sources {
+        if unit.is_none() {
+            unit = source
+                .unit
+                .as_ref()
+                .map(|lit| time_value(cx, lit).unwrap_or_else(|_| default.unit.clone()));
+        }
+        if prec.is_none() {
+            prec = source
+                .prec
+                .as_ref()
+                .map(|lit| time_value(cx, lit).unwrap_or_else(|_| default.prec.clone()));
+        }
+    }
+    if let Some(parent) = parent {
+        let outer = cx.timescale_of(parent);
+        unit = unit.or_else(|| Some(outer.unit.clone()));
+        prec = prec.or_else(|| Some(outer.prec.clone()));
+    }
+
+    // Warn about top-level declarations without a timescale if other parts
+    // of the design specify one.
+    if unit.is_none() && prec.is_none() {
+        let context = context.get_or_insert_with(|| find_context(cx, node_id));
+        if context.any_timescale {
+            cx.emit(
+                DiagBuilder2::warning(format!("{} has no timescale", desc))
+                    .span(name.span)
+                    .add_note(format!(
+                        "Other parts of the design specify a timescale; assuming `{}`",
+                        default
+                    ))
+                    .add_note("Add a `timescale directive, or `timeunit` and `timeprecision`"),
+            );
+        }
+    }
+
+    let unit = unit.unwrap_or(default.unit);
+    let prec = prec.unwrap_or_else(|| unit.clone());
+    if prec > unit {
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "time precision of {} is coarser than its time unit",
+                desc
+            ))
+            .span(name.span)
+            .add_note(format!(
+                "Precision is {}, but unit is {}",
+                DisplayTime(&prec),
+                DisplayTime(&unit)
+            )),
+        );
+        return Arc::new(Timescale {
+            prec: unit.clone(),
+            unit,
+        });
+    }
+    Arc::new(Timescale { unit, prec })
+}
+
+/// Find the module, interface, or package a declaration is nested in.
+fn enclosing_decl<'a>(node: &'a dyn ast::AnyNode<'a>) -> Option<NodeId> {
+    let mut current = node.get_parent();
+    while let Some(parent) = current {
+        match parent.as_all() {
+            ast::AllNode::Module(_) | ast::AllNode::Interface(_) | ast::AllNode::Package(_) => {
+                return Some(parent.id())
+            }
+            _ => current = parent.get_parent(),
+        }
+    }
+    None
+}
+
+/// Compute the value of a time unit or precision in seconds.
+fn time_value<'a>(cx: &impl Context<'a>, lit: &Spanned<Lit>) -> Result<BigRational> {
+    let (int, frac, unit) = match lit.value {
+        Lit::Time(int, frac, unit) => (int, frac, unit),
+        _ => bug_span!(lit.span, cx, "time unit is not a time literal"),
+    };
+    match (&*int.as_str(), frac) {
+        ("1", None) | ("10", None) | ("100", None) => (),
+        _ => {
+            cx.emit(
+                DiagBuilder2::error(format!(
+                    "`{}` is not a valid time unit or precision",
+                    lit.span.extract()
+                ))
+                .span(lit.span)
+                .add_note("Time units and precisions must be 1, 10, or 100 of a unit, e.g. `10ns`"),
+            );
+            return Err(());
+        }
+    }
+    crate::hir::lowering::time_literal_value(cx, lit.span, int, frac, unit)
+}
+
+/// The compilation unit around a top-level declaration.
+struct CompilationContext<'a> {
+    /// The file that contains the declaration.
+    file: &'a ast::SourceFile<'a>,
+    /// The last `` `timescale `` directive before the declaration, and the file
+    /// it appears in.
+    directive: Option<(&'a ast::SourceFile<'a>, &'a ast::Timeunit)>,
+    /// Whether any part of the design specifies a timescale.
+    any_timescale: bool,
+}
+
+/// Locate a top-level declaration within the files being compiled.
+fn find_context<'a>(cx: &impl Context<'a>, node_id: NodeId) -> CompilationContext<'a> {
+    let node = cx.ast_for_id(node_id);
+    let span = node.span();
+    let mut file = None;
+    let mut root = None;
+    let mut current = node;
+    while let Some(parent) = current.get_parent() {
+        match parent.as_all() {
+            ast::AllNode::SourceFile(x) => file = Some(x),
+            ast::AllNode::Root(x) => root = Some(x),
+            _ => (),
+        }
+        current = parent;
+    }
+    let file = match file {
+        Some(x) => x,
+        None => bug_span!(span, cx, "{} is not in a source file", node),
+    };
+    let files = match root {
+        Some(x) => x.files.as_slice(),
+        None => std::slice::from_ref(&file),
+    };
+
+    let mut directive = None;
+    let mut found = false;
+    let mut any_timescale = false;
+    for &f in files {
+        any_timescale |= has_timeunits(&f.timeunits);
+        for item in &f.items {
+            let encloses = item.span.source == span.source
+                && item.span.begin <= span.begin
+                && item.span.end >= span.end;
+            found |= encloses;
+            match item.data {
+                ast::ItemData::Timescale(ref x) => {
+                    any_timescale = true;
+                    if !found {
+                        directive = Some((f, x));
+                    }
+                }
+                ast::ItemData::ModuleDecl(ref x) | ast::ItemData::ProgramDecl(ref x) => {
+                    any_timescale |= has_timeunits(&x.timeunits)
+                }
+                ast::ItemData::InterfaceDecl(ref x) => any_timescale |= has_timeunits(&x.timeunits),
+                ast::ItemData::PackageDecl(ref x) => any_timescale |= has_timeunits(&x.timeunits),
+                _ => (),
+            }
+        }
+    }
+    CompilationContext {
+        file,
+        directive,
+        any_timescale,
+    }
+}
+
+/// Check whether a time unit specification declares anything.
+fn has_timeunits(timeunits: &ast::Timeunit) -> bool {
+    timeunits.unit.is_some() || timeunits.prec.is_some()
+}
+
+/// Determine the span of a `` `timescale `` directive for diagnostics.
+fn directive_span(timeunits: &ast::Timeunit) -> Span {
+    match (&timeunits.unit, &timeunits.prec) {
+        (Some(unit), Some(prec)) => Span::union(unit.span, prec.span),
+        (Some(x), None) | (None, Some(x)) => x.span,
+        (None, None) => moore_common::source::INVALID_SPAN,
+    }
+}
@@ -253,7 +253,6 @@ pub enum UnpackedCore<'a> {
     /// An event.
     Event,
     // TODO: Add virtual interfaces
-    // TODO: Add class types
     // TODO: Add covergroups
     /// A named type.
     Named {
@@ -273,6 +272,8 @@ pub enum UnpackedCore<'a> {
     Module(ModuleType<'a>),
     /// An interface instance.
     Interface(InterfaceType<'a>),
+    /// A handle to an object of a class.
+    Class(ClassType<'a>),
 }
 
 /// An unpacked dimension.
@@ -364,6 +365,15 @@ pub struct InterfaceType<'a> {
     pub modport: Option<&'a ast::ModportName<'a>>,
 }
 
+/// A class handle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClassType<'a> {
+    /// The AST node of the class.
+    pub ast: &'a ast::ClassDecl<'a>,
+    /// The parametrization of the class.
+    pub env: ParamEnv,
+}
+
 /// A simple bit vector type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct SbvType {
@@ -621,6 +631,15 @@ impl<'a> PackedType<'a> {
         self.core.is_error()
     }
 
+    /// Check if this is the `void` type.
+    pub fn is_void(&self) -> bool {
+        let ty = self.resolve_full();
+        match ty.core {
+            PackedCore::Void => ty.dims.is_empty(),
+            _ => false,
+        }
+    }
+
     /// Check if this type is equal to another one.
     ///
     /// Types which coalesce to `iN` in LLHD are checked for equality of their
@@ -1268,6 +1287,21 @@ impl<'a> UnpackedType<'a> {
         unsafe { std::mem::transmute(ty) }
     }
 
+    /// Create a `real`, `shortreal`, or `realtime` type.
+    pub fn make_real(kind: RealType) -> &'a Self {
+        static SHORTREAL: Lazy<UnpackedType> = Lazy::new(|| UnpackedType::new(RealType::ShortReal));
+        static REAL: Lazy<UnpackedType> = Lazy::new(|| UnpackedType::new(RealType::Real));
+        static REALTIME: Lazy<UnpackedType> = Lazy::new(|| UnpackedType::new(RealType::RealTime));
+        let ty: &UnpackedType = match kind {
+            RealType::ShortReal => &SHORTREAL,
+            RealType::Real => &REAL,
+            RealType::RealTime => &REALTIME,
+        };
+        // SAFETY: This is safe since the cell which causes 'a to need to
+        // outlive 'static is actually never mutated after AST construction.
+        unsafe { std::mem::transmute(ty) }
+    }
+
     /// Internalize this type in a context and resolve it.
     pub fn intern(mut self, cx: &impl TypeContext<'a>) -> &'a Self {
         let inner = match self.core {
@@ -1332,6 +1366,11 @@ impl<'a> UnpackedType<'a> {
         self.core.is_error()
     }
 
+    /// Check if this is the `void` type.
+    pub fn is_void(&self) -> bool {
+        self.get_packed().map(|p| p.is_void()).unwrap_or(false)
+    }
+
     /// Check if this type is equal to another one.
     ///
     /// Types which coalesce to `iN` in LLHD are checked for equality of their
@@ -1375,7 +1414,8 @@ impl<'a> UnpackedType<'a> {
             | UnpackedCore::Chandle
             | UnpackedCore::Event
             | UnpackedCore::Module { .. }
-            | UnpackedCore::Interface { .. } => Domain::TwoValued,
+            | UnpackedCore::Interface { .. }
+            | UnpackedCore::Class { .. } => Domain::TwoValued,
         }
     }
 
@@ -1391,7 +1431,8 @@ impl<'a> UnpackedType<'a> {
             | UnpackedCore::Chandle
             | UnpackedCore::Event
             | UnpackedCore::Module { .. }
-            | UnpackedCore::Interface { .. } => Sign::Unsigned,
+            | UnpackedCore::Interface { .. }
+            | UnpackedCore::Class { .. } => Sign::Unsigned,
         }
     }
 
@@ -1411,7 +1452,8 @@ impl<'a> UnpackedType<'a> {
             | UnpackedCore::Chandle
             | UnpackedCore::Event
             | UnpackedCore::Module { .. }
-            | UnpackedCore::Interface { .. } => return None,
+            | UnpackedCore::Interface { .. }
+            | UnpackedCore::Class { .. } => return None,
         };
         for &dim in &self.dims {
             match dim {
@@ -1451,6 +1493,24 @@ impl<'a> UnpackedType<'a> {
         self.dims.is_empty() && self.resolve_full().core == UnpackedCore::String
     }
 
+    /// Check if this type is a floating-point type, like `real`.
+    pub fn is_real(&self) -> bool {
+        self.get_real().is_some()
+    }
+
+    /// Get the floating-point type, or `None` if the type is not `real`,
+    /// `shortreal`, or `realtime`.
+    pub fn get_real(&self) -> Option<RealType> {
+        if self.dims.is_empty() {
+            match self.resolve_full().core {
+                UnpackedCore::Real(x) => Some(x),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
     /// Check if this type will coalesce to a scalar type in LLHD, like `i42`.
     pub fn coalesces_to_llhd_scalar(&self) -> bool {
         self.get_packed()
@@ -1558,7 +1618,7 @@ impl<'a> UnpackedType<'a> {
         self.dims().next()
     }
 
-    /// Get the underlying struct, or `None` if the type is no struct.
+    /// Get the underlying struct or union, or `None` if the type is neither.
     pub fn get_struct(&self) -> Option<&StructType<'a>> {
         if self.dims.is_empty() {
             self.resolve_full().core.get_struct()
@@ -1567,6 +1627,11 @@ impl<'a> UnpackedType<'a> {
         }
     }
 
+    /// Get the underlying union, or `None` if the type is no union.
+    pub fn get_union(&self) -> Option<&StructType<'a>> {
+        self.get_struct().filter(|strukt| strukt.is_union())
+    }
+
     /// Get the underlying enum, or `None` if the type is no enum.
     pub fn get_enum(&self) -> Option<&EnumType<'a>> {
         if self.dims.is_empty() {
@@ -1594,6 +1659,28 @@ impl<'a> UnpackedType<'a> {
         }
     }
 
+    /// Get the underlying class, or `None` if the type is not a class handle.
+    pub fn get_class(&self) -> Option<&ClassType<'a>> {
+        if self.dims.is_empty() {
+            self.resolve_full().core.get_class()
+        } else {
+            None
+        }
+    }
+
+    /// Get the outermost dimension if it is sized dynamically, or `None`
+    /// otherwise.
+    ///
+    /// This is the case for dynamic arrays, queues, and associative arrays.
+    pub fn get_container(&self) -> Option<UnpackedDim<'a>> {
+        match self.unpacked_dims().next()? {
+            dim @ UnpackedDim::Unsized
+            | dim @ UnpackedDim::Queue(..)
+            | dim @ UnpackedDim::Assoc(..) => Some(dim),
+            _ => None,
+        }
+    }
+
     /// Helper function to format this type around a declaration name.
     fn format_around(
         &self,
@@ -1690,6 +1777,7 @@ impl<'a> UnpackedCore<'a> {
             (Self::Ref { ty: a, .. }, Self::Ref { ty: b, .. }) => a.is_identical(b),
             (Self::Module(a), Self::Module(b)) => a == b,
             (Self::Interface(a), Self::Interface(b)) => a == b,
+            (Self::Class(a), Self::Class(b)) => a == b,
             _ => false,
         }
     }
@@ -1708,6 +1796,7 @@ impl<'a> UnpackedCore<'a> {
             (Self::Ref { ty: a, .. }, Self::Ref { ty: b, .. }) => a.is_strictly_identical(b),
             (Self::Module(a), Self::Module(b)) => a == b,
             (Self::Interface(a), Self::Interface(b)) => a == b,
+            (Self::Class(a), Self::Class(b)) => a == b,
             _ => false,
         }
     }
@@ -1753,6 +1842,15 @@ impl<'a> UnpackedCore<'a> {
             _ => None,
         }
     }
+
+    /// Get the underlying class, or `None` if the type is not a class handle.
+    pub fn get_class(&self) -> Option<&ClassType<'a>> {
+        match *self {
+            UnpackedCore::Class(ref x) => Some(x),
+            UnpackedCore::Named { ty, .. } | UnpackedCore::Ref { ty, .. } => ty.get_class(),
+            _ => None,
+        }
+    }
 }
 
 impl<'a> From<&'a PackedType<'a>> for UnpackedCore<'a> {
@@ -1785,6 +1883,12 @@ impl<'a> From<InterfaceType<'a>> for UnpackedCore<'a> {
     }
 }
 
+impl<'a> From<ClassType<'a>> for UnpackedCore<'a> {
+    fn from(inner: ClassType<'a>) -> Self {
+        Self::Class(inner)
+    }
+}
+
 impl Display for UnpackedCore<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -1800,6 +1904,7 @@ impl Display for UnpackedCore<'_> {
                 Some(y) => write!(f, "{}.{}", x.ast.name, y.name),
                 None => write!(f, "{}", x.ast.name),
             },
+            Self::Class(x) => write!(f, "{}", x.ast.name),
             Self::Named { name, .. } => write!(f, "{}", name),
             Self::Ref { span, .. } => write!(f, "{}", span.extract()),
         }
@@ -1884,15 +1989,70 @@ impl<'a> StructType<'a> {
         }
     }
 
+    /// Check whether this is a `union` or `union tagged`.
+    pub fn is_union(&self) -> bool {
+        self.kind != ast::StructKind::Struct
+    }
+
+    /// Check whether this is a `union tagged`.
+    pub fn is_tagged(&self) -> bool {
+        self.kind == ast::StructKind::TaggedUnion
+    }
+
     /// Compute the size of this struct in bits.
     ///
-    /// Returns `None` if any member of the type has a `[]` dimension.
+    /// Unions are as large as their largest member, plus the bits of the tag
+    /// in case of a tagged union. Returns `None` if any member of the type has
+    /// a `[]` dimension.
     pub fn get_bit_size(&self) -> Option<usize> {
         let mut size = 0;
         for m in &self.members {
-            size += m.ty.get_bit_size()?;
+            let member_size = m.ty.get_bit_size()?;
+            if self.is_union() {
+                size = std::cmp::max(size, member_size);
+            } else {
+                size += member_size;
+            }
+        }
+        Some(size + self.tag_size())
+    }
+
+    /// Compute the number of bits used to store the tag of a tagged union.
+    ///
+    /// The tag occupies the most significant bits of the union, and holds the
+    /// index of the active member. Returns 0 for structs and untagged unions.
+    pub fn tag_size(&self) -> usize {
+        if !self.is_tagged() {
+            return 0;
+        }
+        let mut size = 1;
+        while (1 << size) < self.members.len() {
+            size += 1;
+        }
+        size
+    }
+
+    /// Compute the offset of a member from the least significant bit of the
+    /// packed struct or union.
+    ///
+    /// The first struct member occupies the most significant bits. Union
+    /// members overlap and start at the least significant bit, except for
+    /// tagged unions, whose members are left-justified towards the tag.
+    /// Returns `None` if any member of the type has a `[]` dimension.
+    pub fn member_offset(&self, index: usize) -> Option<usize> {
+        match self.kind {
+            ast::StructKind::Struct => {
+                let mut offset = 0;
+                for m in &self.members[index + 1..] {
+                    offset += m.ty.get_bit_size()?;
+                }
+                Some(offset)
+            }
+            ast::StructKind::Union => Some(0),
+            ast::StructKind::TaggedUnion => Some(
+                self.get_bit_size()? - self.tag_size() - self.members[index].ty.get_bit_size()?,
+            ),
         }
-        Some(size)
     }
 
     /// Helper function to format this struct.
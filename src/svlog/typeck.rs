@@ -30,8 +30,10 @@
 
 use crate::crate_prelude::*;
 use crate::{
+    ast_map::AstNode,
     common::arenas::Alloc,
     hir::HirNode,
+    inst_details::{inst_array_element, inst_array_len},
     port_list,
     resolver::{DefNode, InstTarget},
     syntax::ast::BasicNode,
@@ -59,9 +61,15 @@ pub(crate) fn type_of<'a>(
             ast::AllNode::VarDecl(..) => return Ok(cx.type_of_var_decl(Ref(name), env)),
             ast::AllNode::NetDecl(..) => return Ok(cx.type_of_net_decl(Ref(name), env)),
             ast::AllNode::StructMember(..) => return Ok(cx.type_of_struct_member(Ref(name), env)),
+            ast::AllNode::SubroutineDecl(..) => {
+                return Ok(cx.type_of_subroutine_port_decl(Ref(name), env))
+            }
+            // Non-ANSI port declarations are handled as internal ports below.
+            ast::AllNode::PortDecl(..) => (),
             x => bug_span!(ast.span(), cx, "VarDeclName with weird parent {:?}", x),
         },
         ast::AllNode::ParamValueDecl(x) => return Ok(cx.type_of_value_param(Ref(x), env)),
+        ast::AllNode::SubroutinePort(x) => return Ok(cx.type_of_subroutine_port(Ref(x), env)),
         _ => (),
     };
 
@@ -72,7 +80,7 @@ pub(crate) fn type_of<'a>(
         HirNode::IntPort(p) => Ok(cx.type_of_int_port(Ref(p), env)),
         HirNode::ExtPort(p) => Ok(cx.type_of_ext_port(Ref(p), env)),
         HirNode::Expr(_) => Ok(cx.cast_type(node_id, env).unwrap().ty),
-        HirNode::GenvarDecl(_) => {
+        HirNode::GenvarDecl(_) | HirNode::ForeachIndex(_) => {
             Ok(SbvType::nice(ty::Domain::TwoValued, ty::Sign::Signed, 32).to_unpacked(cx))
         }
         HirNode::EnumVariant(v) => {
@@ -99,8 +107,31 @@ pub(crate) fn type_of<'a>(
             Ok(enm.base.to_unpacked(cx))
         }
         HirNode::Package(_) => Ok(UnpackedType::make_void()),
+        HirNode::Let(x) => Ok(cx.need_self_determined_type(x.expr, env)),
+        HirNode::PatternVar(x) => match cx.hir_of(x.matches)? {
+            HirNode::Expr(hir::Expr {
+                kind: hir::ExprKind::Matches(value, ref pattern),
+                ..
+            }) => {
+                let ty = cx.need_self_determined_type(*value, env);
+                Ok(match_pattern_type(pattern, ty, x.id).unwrap())
+            }
+            _ => unreachable!("pattern variable outside of `matches`"),
+        },
+        HirNode::LetArg(x) => match cx.param_env_data(env).find_value(x.id) {
+            Some(ParamEnvBinding::Indirect(assigned)) => cx.type_of(assigned.id(), assigned.env()),
+            _ => bug_span!(x.span, cx, "{} not bound in {:?}", x.desc_full(), env),
+        },
         HirNode::Assign(_) => unreachable!("has no type: {:?}", hir),
         HirNode::Inst(hir) => Ok(cx.type_of_inst(Ref(hir), env)),
+        HirNode::Subroutine(hir) => Ok(match hir.retty {
+            Some(retty_id) => cx.packed_type_from_ast(
+                Ref(cx.ast_for_id(retty_id).as_all().get_type().unwrap()),
+                env,
+                Some(ty::PackedCore::IntVec(ty::IntVecType::Logic)),
+            ),
+            None => UnpackedType::make_void(),
+        }),
         _ => {
             error!("{:#?}", hir);
             bug_span!(
@@ -174,30 +205,137 @@ pub(crate) fn type_of_ext_port<'a>(
     Ref(port): Ref<'a, port_list::ExtPort<'a>>,
     env: ParamEnv,
 ) -> &'a UnpackedType<'a> {
-    if port.exprs.len() == 1 {
-        let expr = &port.exprs[0];
-        if !expr.selects.is_empty() {
-            bug_span!(
-                port.span,
-                cx,
-                "port{} contains a `[...]` selection; not yet supported in typeck",
-                port.name
-                    .map(|n| format!(" `{}`", n))
-                    .unwrap_or_else(String::new)
-            );
+    let port_list = cx.canonicalize_ports(port.node);
+    match port.exprs.as_slice() {
+        // Ports that map directly to an internal port have that port's type.
+        [expr] if expr.selects.is_empty() => {
+            cx.type_of_int_port(Ref(&port_list.int[expr.port]), env)
         }
-        let port_list = cx.canonicalize_ports(port.node);
-        cx.type_of_int_port(Ref(&port_list.int[expr.port]), env)
-    } else {
-        bug_span!(
-            port.span,
-            cx,
-            "port{} is a concatenation; not yet supported in typeck",
-            port.name
-                .map(|n| format!(" `{}`", n))
-                .unwrap_or_else(String::new)
+
+        // Concatenations and selects yield an unsigned bit vector as wide as
+        // all the selected bits.
+        exprs => {
+            let mut width = 0;
+            let mut domain = Domain::TwoValued;
+            for expr in exprs {
+                match ext_port_expr_bits(cx, port_list, expr, env) {
+                    Ok((_, w)) => width += w,
+                    Err(()) => return UnpackedType::make_error(),
+                }
+                let ty = cx.type_of_int_port(Ref(&port_list.int[expr.port]), env);
+                if ty.domain() == Domain::FourValued {
+                    domain = Domain::FourValued;
+                }
+            }
+            SbvType::new(domain, Sign::Unsigned, width).to_unpacked(cx)
+        }
+    }
+}
+
+/// Determine the bits of an internal port selected by a port expression.
+///
+/// Returns the offset and width of the selected bits within the internal port.
+/// For example, `x[5:2]` in `module foo(.a(x[5:2]))` selects 4 bits at offset
+/// 2 of `x`.
+pub(crate) fn ext_port_expr_bits<'a>(
+    cx: &impl Context<'a>,
+    port_list: &'a port_list::PortList<'a>,
+    expr: &'a port_list::ExtPortExpr,
+    env: ParamEnv,
+) -> Result<(usize, usize)> {
+    let port = &port_list.int[expr.port];
+    let mut ty = cx.type_of_int_port(Ref(port), env);
+    if ty.is_error() {
+        return Err(());
+    }
+    if !ty.coalesces_to_llhd_scalar() {
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "port `{}` of type `{}` cannot be used in a port expression",
+                port.name, ty
+            ))
+            .span(port.span)
+            .add_note(
+                "Only ports of integral type can be concatenated or selected from in port \
+                 expressions",
+            ),
         );
+        return Err(());
+    }
+    let mut offset = 0;
+    for select in &expr.selects {
+        let mode = match *select {
+            port_list::ExtPortSelect::Index(mode) => mode,
+            port_list::ExtPortSelect::Error => return Err(()),
+        };
+        let (dim, elem_ty) = match (ty.outermost_dim(), ty.pop_dim(cx)) {
+            (Some(dim), Some(elem_ty)) => (dim, elem_ty),
+            _ => {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "cannot select into port `{}` of type `{}`",
+                        port.name, ty
+                    ))
+                    .span(port.span),
+                );
+                return Err(());
+            }
+        };
+
+        // Determine the selected elements.
+        let (lo, len) = match mode {
+            hir::IndexMode::One(index) => (cx.constant_int_value_of(index, env)?.clone(), None),
+            hir::IndexMode::Many(ast::RangeMode::Absolute, lhs, rhs) => {
+                let lhs = cx.constant_int_value_of(lhs, env)?;
+                let rhs = cx.constant_int_value_of(rhs, env)?;
+                (
+                    std::cmp::min(lhs, rhs).clone(),
+                    Some((lhs - rhs).abs() + BigInt::one()),
+                )
+            }
+            hir::IndexMode::Many(ast::RangeMode::RelativeUp, base, delta) => {
+                let base = cx.constant_int_value_of(base, env)?;
+                let delta = cx.constant_int_value_of(delta, env)?;
+                (base.clone(), Some(delta.clone()))
+            }
+            hir::IndexMode::Many(ast::RangeMode::RelativeDown, base, delta) => {
+                let base = cx.constant_int_value_of(base, env)?;
+                let delta = cx.constant_int_value_of(delta, env)?;
+                (base - delta + BigInt::one(), Some(delta.clone()))
+            }
+        };
+        let dim_offset = dim.get_range().map(|r| r.offset).unwrap_or(0);
+        let pos = (lo - BigInt::from(dim_offset)).to_usize();
+        let num = len.as_ref().map(|l| l.to_usize()).unwrap_or(Some(1));
+        let (pos, num) = match (pos, num, dim.get_size()) {
+            (Some(pos), Some(num), Some(size)) if num > 0 && pos + num <= size => (pos, num),
+            _ => {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "select into port `{}` is out of bounds",
+                        port.name
+                    ))
+                    .span(port.span)
+                    .add_note(format!("Port `{}` has type `{}`", port.name, ty)),
+                );
+                return Err(());
+            }
+        };
+
+        // Narrow down the selected bits.
+        offset += pos * elem_ty.get_bit_size().unwrap();
+        ty = match len {
+            Some(_) => ty.replace_dim(
+                cx,
+                match dim {
+                    ty::Dim::Packed(..) => ty::Dim::Packed(ty::Range::with_size(num).into()),
+                    ty::Dim::Unpacked(..) => ty::Dim::Unpacked(ty::Range::with_size(num).into()),
+                },
+            ),
+            None => elem_ty,
+        };
     }
+    Ok((offset, ty.get_bit_size().unwrap()))
 }
 
 /// Determine the type of a variable declaration.
@@ -248,6 +386,83 @@ pub(crate) fn type_of_struct_member<'a>(
     type_of_varlike(cx, ast_member, &ast_member.ty, ast, &ast.dims, env)
 }
 
+/// Determine the type of a subroutine port declared in the prototype.
+#[moore_derive::query]
+pub(crate) fn type_of_subroutine_port<'a>(
+    cx: &impl Context<'a>,
+    Ref(ast): Ref<'a, ast::SubroutinePort<'a>>,
+    env: ParamEnv,
+) -> &'a UnpackedType<'a> {
+    let dims = match ast.name {
+        Some(ref name) => name.dims.as_slice(),
+        None => &[],
+    };
+    let ty = subroutine_port_packed_type(cx, ast, env);
+    apply_unpacked_dims(cx, ty, dims, env, ast.ty.span())
+}
+
+/// Determine the type of a subroutine port, without unpacked dimensions.
+///
+/// Ports with neither an explicit direction nor an explicit type inherit the
+/// type of the previous port. Otherwise implicit types default to `logic`.
+fn subroutine_port_packed_type<'a>(
+    cx: &impl Context<'a>,
+    ast: &'a ast::SubroutinePort<'a>,
+    env: ParamEnv,
+) -> &'a UnpackedType<'a> {
+    if ast.dir.is_none() && ast.ty.is_implicit() && ast.ty.dims.is_empty() {
+        let proto = ast
+            .get_parent()
+            .unwrap()
+            .as_all()
+            .get_subroutine_prototype()
+            .expect("parent not a SubroutinePrototype");
+        let index = proto
+            .args
+            .iter()
+            .position(|arg| std::ptr::eq(arg, ast))
+            .expect("port not in its prototype");
+        if index > 0 {
+            return subroutine_port_packed_type(cx, &proto.args[index - 1], env);
+        }
+    }
+    cx.packed_type_from_ast(
+        Ref(&ast.ty),
+        env,
+        Some(ty::PackedCore::IntVec(ty::IntVecType::Logic)),
+    )
+}
+
+/// Determine the type of a subroutine port declared in the subroutine body.
+#[moore_derive::query]
+pub(crate) fn type_of_subroutine_port_decl<'a>(
+    cx: &impl Context<'a>,
+    Ref(ast): Ref<'a, ast::VarDeclName<'a>>,
+    env: ParamEnv,
+) -> &'a UnpackedType<'a> {
+    let subroutine = ast
+        .get_parent()
+        .unwrap()
+        .as_all()
+        .get_subroutine_decl()
+        .expect("parent not a SubroutineDecl");
+    let decl = subroutine
+        .items
+        .iter()
+        .flat_map(|item| match item {
+            ast::SubroutineItem::PortDecl(pd) => Some(pd),
+            _ => None,
+        })
+        .find(|pd| pd.names.iter().any(|name| std::ptr::eq(name, ast)))
+        .expect("name not in a port declaration");
+    cx.unpacked_type_from_ast(
+        Ref(&decl.ty),
+        Ref(&ast.dims),
+        env,
+        Some(ty::PackedCore::IntVec(ty::IntVecType::Logic)),
+    )
+}
+
 /// Determine the type of something variable-like. This includes variable and
 /// net declarations, as well as struct fields.
 fn type_of_varlike<'a>(
@@ -407,6 +622,21 @@ pub(crate) fn map_to_type<'a>(
                 }),
             ))
         }
+        ast::AllNode::ClassDecl(ast) => {
+            let hir = match cx.hir_of(ast.id()) {
+                Ok(HirNode::Class(x)) => x,
+                _ => return Some(UnpackedType::make_error()),
+            };
+            let env = match cx.param_env(ParamEnvSource::ClassInst {
+                class: Ref(hir),
+                env,
+                params: Ref(&[]),
+            }) {
+                Ok(x) => x,
+                _ => return Some(UnpackedType::make_error()),
+            };
+            Some(class::class_type(cx, ast, env))
+        }
         // The following is an ugly hack, and should actually never happen. But
         // as the HIR is implemented at the moment, certain parameter bindings
         // can bind expressions to type parameters.
@@ -601,6 +831,12 @@ pub(crate) fn packed_type_from_ast<'a>(
                 }
             }
 
+            // Union members overlap in storage, which requires them to have a
+            // bit-level representation.
+            if def.is_union() && !check_union_members(cx, &def, strukt.packed) {
+                failed = true;
+            }
+
             // Keep track of the sign, and complain if the packed type itself
             // has separate sign information.
             if ast_sign != ast::TypeSign::None {
@@ -801,7 +1037,61 @@ pub(crate) fn packed_type_from_ast<'a>(
             }
         }
 
-        ast::VirtIntfType { .. } | ast::MailboxType | ast::SpecializedType(..) => {
+        // Specializations of parametrized classes
+        ast::SpecializedType(ref inner, ref params) => {
+            let name = match inner.kind.data {
+                ast::NamedType(name) => name,
+                _ => {
+                    cx.emit(
+                        DiagBuilder2::error(format!(
+                            "unsupported: specialization of `{}`",
+                            inner.span().extract()
+                        ))
+                        .span(ast.span()),
+                    );
+                    return UnpackedType::make_error();
+                }
+            };
+            let loc = cx.scope_location(ast);
+            let def = match cx.resolve_local_or_error(name, loc, false) {
+                Ok(def) => def,
+                Err(()) => return UnpackedType::make_error(),
+            };
+            let class = match def.node {
+                DefNode::Ast(node) => node.as_all().get_class_decl(),
+                _ => None,
+            };
+            let class = match class {
+                Some(x) => x,
+                None => {
+                    cx.emit(
+                        DiagBuilder2::error(format!("`{}` is not a parametrized class", name))
+                            .span(name.span)
+                            .add_note(format!("`{}` was declared here:", name))
+                            .span(def.node.span()),
+                    );
+                    return UnpackedType::make_error();
+                }
+            };
+            let hir = match cx.hir_of(class.id()) {
+                Ok(HirNode::Class(x)) => x,
+                _ => return UnpackedType::make_error(),
+            };
+            let env = match cx.param_env(ParamEnvSource::ClassInst {
+                class: Ref(hir),
+                env,
+                params: Ref(params),
+            }) {
+                Ok(x) => x,
+                _ => return UnpackedType::make_error(),
+            };
+            Unpacked(UnpackedCore::Named {
+                name,
+                ty: class::class_type(cx, class, env),
+            })
+        }
+
+        ast::VirtIntfType { .. } | ast::MailboxType => {
             bug_span!(ast.span(), cx, "type {:#1?} not implemented", ast.kind)
         }
     };
@@ -913,6 +1203,167 @@ fn packed_type_from_def<'a>(
     }
 }
 
+/// Check that the members of a union can share the same storage.
+///
+/// Every member must have a bit-level representation, and the members of an
+/// untagged packed union must all be of the same size. Returns `false` if a
+/// member is invalid.
+fn check_union_members<'a>(cx: &impl Context<'a>, def: &ty::StructType<'a>, packed: bool) -> bool {
+    let mut ok = true;
+    let mut first_size: Option<(Spanned<Name>, usize)> = None;
+    for member in &def.members {
+        if member.ty.is_error() {
+            ok = false;
+            continue;
+        }
+        let size = match member.ty.get_bit_size() {
+            Some(size) => size,
+            None => {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "unsupported: union member `{}` of type `{}`",
+                        member.name, member.ty
+                    ))
+                    .span(member.name.span)
+                    .add_note("Only members of a fixed size can share the storage of a union"),
+                );
+                ok = false;
+                continue;
+            }
+        };
+        if !packed || def.is_tagged() {
+            continue;
+        }
+        match first_size {
+            Some((first, first_size)) if first_size != size => {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "members of packed union must all have the same size, but `{}` has {} \
+                         bits and `{}` has {} bits",
+                        first, first_size, member.name, size
+                    ))
+                    .span(member.name.span)
+                    .add_note(format!("`{}` was declared here:", first))
+                    .span(first.span),
+                );
+                ok = false;
+            }
+            Some(_) => (),
+            None => first_size = Some((member.name, size)),
+        }
+    }
+    ok
+}
+
+/// Find a member of a tagged union by name.
+///
+/// Returns the index and the member, or `None` if `ty` is not a tagged union
+/// or has no such member.
+pub(crate) fn find_tagged_member<'a>(
+    ty: &'a UnpackedType<'a>,
+    name: Name,
+) -> Option<(usize, &'a ty::StructMember<'a>)> {
+    ty.get_union()
+        .filter(|x| x.is_tagged())?
+        .members
+        .iter()
+        .enumerate()
+        .find(|(_, x)| x.name.value == name)
+}
+
+/// Find a member of a tagged union by name, or emit a diagnostic.
+///
+/// Expects `ty` to be a tagged union.
+fn tagged_member<'a>(
+    cx: &impl Context<'a>,
+    ty: &'a UnpackedType<'a>,
+    name: Spanned<Name>,
+) -> Result<(usize, &'a ty::StructMember<'a>)> {
+    match find_tagged_member(ty, name.value) {
+        Some(x) => Ok(x),
+        None => {
+            let mut d =
+                DiagBuilder2::error(format!("`{}` has no member `{}`", ty, name)).span(name.span);
+            if let Some(strukt) = ty.get_union() {
+                d = d
+                    .add_note(format!("`{}` was defined here:", ty))
+                    .span(strukt.ast.span());
+            }
+            cx.emit(d);
+            Err(())
+        }
+    }
+}
+
+/// Check that a pattern can be matched against a value of type `ty`.
+///
+/// Returns `false` if a diagnostic was emitted.
+fn typeck_match_pattern<'a>(
+    cx: &impl Context<'a>,
+    pattern: &hir::MatchPattern,
+    ty: &'a UnpackedType<'a>,
+) -> bool {
+    match *pattern {
+        hir::MatchPattern::Tagged(member, ref inner) => {
+            if ty.is_error() {
+                return false;
+            }
+            if ty.get_union().map(|x| x.is_tagged()) != Some(true) {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "pattern `tagged {}` cannot match a value of type `{}`",
+                        member, ty
+                    ))
+                    .span(member.span)
+                    .add_note("`tagged` patterns only match tagged unions"),
+                );
+                return false;
+            }
+            let member_ty = match tagged_member(cx, ty, member) {
+                Ok((_, x)) => x.ty,
+                Err(()) => return false,
+            };
+            match inner {
+                Some(_) if member_ty.is_void() => {
+                    cx.emit(
+                        DiagBuilder2::error(format!(
+                            "member `{}` of `{}` is `void` and cannot match a pattern",
+                            member, ty
+                        ))
+                        .span(member.span),
+                    );
+                    false
+                }
+                Some(inner) => typeck_match_pattern(cx, inner, member_ty),
+                None => true,
+            }
+        }
+        _ => true,
+    }
+}
+
+/// Determine the type of the value matched by a part of a pattern.
+///
+/// Starts at the type of the value matched by the entire pattern, and follows
+/// the tagged union members selected along the way to the pattern variable or
+/// constant `id`. Returns `None` if `id` is not part of the pattern.
+fn match_pattern_type<'a>(
+    pattern: &hir::MatchPattern,
+    ty: &'a UnpackedType<'a>,
+    id: NodeId,
+) -> Option<&'a UnpackedType<'a>> {
+    match *pattern {
+        hir::MatchPattern::Var(x) | hir::MatchPattern::Expr(x) if x == id => Some(ty),
+        hir::MatchPattern::Tagged(member, Some(ref inner)) => {
+            let member_ty = find_tagged_member(ty, member.value)
+                .map(|(_, x)| x.ty)
+                .unwrap_or(UnpackedType::make_error());
+            match_pattern_type(inner, member_ty, id)
+        }
+        _ => None,
+    }
+}
+
 // A simple enum that keeps either a packed or unpacked core type.
 enum PackedOrUnpacked<'a> {
     Packed(PackedCore<'a>),
@@ -1014,6 +1465,7 @@ pub(crate) fn type_of_expr<'a>(
         // These expressions have a fully self-determined type.
         hir::ExprKind::IntConst { .. }
         | hir::ExprKind::TimeConst(..)
+        | hir::ExprKind::RealConst(..)
         | hir::ExprKind::StringConst(..)
         | hir::ExprKind::Ident(..)
         | hir::ExprKind::Scope(..)
@@ -1030,10 +1482,26 @@ pub(crate) fn type_of_expr<'a>(
         | hir::ExprKind::Builtin(hir::BuiltinCall::OneHot0(_))
         | hir::ExprKind::Builtin(hir::BuiltinCall::IsUnknown(_))
         | hir::ExprKind::Builtin(hir::BuiltinCall::ArrayDim(..))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Display(..))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::SFormatF(..))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Finish)
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Stop)
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Time)
+        | hir::ExprKind::Builtin(hir::BuiltinCall::RealTime)
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Random(..))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Rose(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Fell(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Stable(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Past(..))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::RealConv(..))
         | hir::ExprKind::Field(..)
         | hir::ExprKind::Index(..)
         | hir::ExprKind::Assign { .. } => cx.need_self_determined_type(expr.id, env),
 
+        // Streaming concatenations take on the width of a wider target, and
+        // otherwise fall back to a self-determined mode.
+        hir::ExprKind::StreamConcat(..) => stream_concat_type(cx, expr, env),
+
         // Unsized constants infer their type from the context if possible, and
         // otherwise fall back to a self-determined mode.
         hir::ExprKind::UnsizedConst(..) => cx
@@ -1106,7 +1574,128 @@ pub(crate) fn type_of_expr<'a>(
         // Other things simply evaluate to their self-determined type.
         hir::ExprKind::Builtin(hir::BuiltinCall::Signed(_))
         | hir::ExprKind::Builtin(hir::BuiltinCall::Unsigned(_))
-        | hir::ExprKind::FunctionCall(..) => cx.need_self_determined_type(expr.id, env),
+        | hir::ExprKind::FunctionCall(..)
+        | hir::ExprKind::MethodCall(..)
+        | hir::ExprKind::LetCall(..)
+        | hir::ExprKind::CaseValue { .. }
+        | hir::ExprKind::This
+        | hir::ExprKind::Super => cx.need_self_determined_type(expr.id, env),
+
+        // Object creation and the null handle take on the class type of their
+        // context.
+        hir::ExprKind::New(..) | hir::ExprKind::Null => {
+            let ty = cx.need_type_context(expr.id, env).ty();
+            if ty.is_error() || ty.get_class().is_some() {
+                ty
+            } else {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` cannot be used as a value of type `{}`",
+                        expr.span.extract(),
+                        ty
+                    ))
+                    .span(expr.span)
+                    .add_note(format!(
+                        "`{}` may only be assigned to a class handle",
+                        expr.span.extract()
+                    )),
+                );
+                UnpackedType::make_error()
+            }
+        }
+
+        // Dynamic array allocations and the empty queue take on the container
+        // type of their context.
+        hir::ExprKind::ArrayNew(..) | hir::ExprKind::EmptyQueue => {
+            let ty = cx.need_type_context(expr.id, env).ty();
+            let (allowed, what) = match expr.kind {
+                hir::ExprKind::ArrayNew(..) => (
+                    ty.get_container() == Some(ty::UnpackedDim::Unsized),
+                    "a dynamic array",
+                ),
+                _ => (
+                    ty.get_container().is_some(),
+                    "a dynamic array, queue, or associative array",
+                ),
+            };
+            if ty.is_error() || allowed {
+                ty
+            } else {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` cannot be used as a value of type `{}`",
+                        expr.span.extract(),
+                        ty
+                    ))
+                    .span(expr.span)
+                    .add_note(format!(
+                        "`{}` may only be assigned to {}",
+                        expr.span.extract(),
+                        what
+                    )),
+                );
+                UnpackedType::make_error()
+            }
+        }
+
+        // Tagged union expressions take on the union type of their context.
+        hir::ExprKind::Tagged(member, value) => {
+            let ty = cx.need_type_context(expr.id, env).ty();
+            if ty.is_error() {
+                return ty;
+            }
+            if ty.get_union().map(|x| x.is_tagged()) != Some(true) {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` cannot be used as a value of type `{}`",
+                        expr.span.extract(),
+                        ty
+                    ))
+                    .span(expr.span)
+                    .add_note("`tagged` expressions may only be assigned to a tagged union"),
+                );
+                return UnpackedType::make_error();
+            }
+            let member_ty = match tagged_member(cx, ty, member) {
+                Ok((_, x)) => x.ty,
+                Err(()) => return UnpackedType::make_error(),
+            };
+            match (member_ty.is_void(), value.is_some()) {
+                (true, true) => {
+                    cx.emit(
+                        DiagBuilder2::error(format!(
+                            "member `{}` of `{}` is `void` and cannot have a value",
+                            member, ty
+                        ))
+                        .span(expr.span),
+                    );
+                    UnpackedType::make_error()
+                }
+                (false, false) => {
+                    cx.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` requires a value of type `{}`",
+                            expr.span.extract(),
+                            member_ty
+                        ))
+                        .span(expr.span),
+                    );
+                    UnpackedType::make_error()
+                }
+                _ => ty,
+            }
+        }
+
+        // Pattern matches evaluate to a boolean, provided the pattern fits the
+        // type of the matched value.
+        hir::ExprKind::Matches(value, ref pattern) => {
+            let ty = cx.need_self_determined_type(value, env);
+            if typeck_match_pattern(cx, pattern, ty) {
+                cx.need_self_determined_type(expr.id, env)
+            } else {
+                UnpackedType::make_error()
+            }
+        }
 
         // Pattern expressions require a type context.
         hir::ExprKind::PositionalPattern(..)
@@ -1166,8 +1755,12 @@ pub(crate) fn cast_expr_type<'a>(
                     ty
                 ),
                 CastOp::PickModport => format!("implicitly picking modport `{}`", ty),
+                CastOp::Upcast => format!("upcast to base class `{}`", ty),
                 CastOp::PackString => format!("pack as string `{}`", ty),
                 CastOp::UnpackString => format!("unpack string as `{}`", ty),
+                CastOp::IntToReal => format!("convert to real `{}`", ty),
+                CastOp::RealToInt => format!("round to integer `{}`", ty),
+                CastOp::ResizeReal => format!("convert to real `{}`", ty),
             };
             d = d.add_note(msg);
         }
@@ -1253,19 +1846,72 @@ fn cast_expr_type_inner<'gcx>(
         }
     }
 
-    // Cast strings to SBVTs.
-    let inferred = match context.ty().get_simple_bit_vector() {
-        Some(context_sbvt) if inferred.is_string() => {
-            let ty = context_sbvt.forget().to_unpacked(cx);
-            trace!("  Packing string as SBVT ({})", ty);
-            cast.add_cast(CastOp::PackString, ty);
-            ty
-        }
-        _ => inferred,
-    };
-
-    // Cast the expression to a simple bit vector type.
-    let inferred_sbvt = match inferred.get_simple_bit_vector() {
+    // Cast class handles to a handle of the class itself or one of its base
+    // classes.
+    if let TypeContext::Type(context) = context {
+        if let Some(to) = context.get_class() {
+            let base = inferred.get_class().and_then(|from| {
+                cx.class_details(from.ast.id(), from.env)
+                    .ok()
+                    .and_then(|details| details.find_class(to.ast.id()).map(|b| b.env))
+            });
+            if base == Some(to.env) {
+                trace!("  Casting `{}` to base class `{}`", inferred, context);
+                cast.add_cast(CastOp::Upcast, context);
+                return cast;
+            }
+            cx.emit(
+                DiagBuilder2::error(format!(
+                    "cannot cast a value of type `{}` to `{}`",
+                    inferred, context
+                ))
+                .span(expr.span)
+                .add_note(format!(
+                    "`{}` is not a class derived from `{}`",
+                    inferred, context
+                )),
+            );
+            return ty::UnpackedType::make_error().into();
+        }
+    }
+
+    // Cast reals to booleans or other reals, or round them to an SBVT.
+    let inferred = match (inferred.get_real(), context) {
+        (Some(_), TypeContext::Bool) => {
+            trace!("  Casting real to bool ({})", context.ty());
+            cast.add_cast(CastOp::Bool, context.ty());
+            return cast;
+        }
+        (Some(_), TypeContext::Type(ty)) if ty.is_real() => {
+            trace!("  Casting real to real ({})", ty);
+            cast.add_cast(CastOp::ResizeReal, ty);
+            return cast;
+        }
+        (Some(_), TypeContext::Type(ty)) => match ty.get_simple_bit_vector() {
+            Some(sbvt) => {
+                let ty = SbvType::new(Domain::TwoValued, sbvt.sign, sbvt.size).to_unpacked(cx);
+                trace!("  Rounding real to SBVT ({})", ty);
+                cast.add_cast(CastOp::RealToInt, ty);
+                ty
+            }
+            None => inferred,
+        },
+        _ => inferred,
+    };
+
+    // Cast strings to SBVTs.
+    let inferred = match context.ty().get_simple_bit_vector() {
+        Some(context_sbvt) if inferred.is_string() => {
+            let ty = context_sbvt.forget().to_unpacked(cx);
+            trace!("  Packing string as SBVT ({})", ty);
+            cast.add_cast(CastOp::PackString, ty);
+            ty
+        }
+        _ => inferred,
+    };
+
+    // Cast the expression to a simple bit vector type.
+    let inferred_sbvt = match inferred.get_simple_bit_vector() {
         Some(ty) => {
             let ty = ty.forget();
             if !inferred.is_simple_bit_vector() {
@@ -1311,6 +1957,13 @@ fn cast_expr_type_inner<'gcx>(
         TypeContext::Type(ty) => ty,
     };
 
+    // Cast the SBVT to a real.
+    if context.is_real() {
+        trace!("  Converting SBVT to real ({})", context);
+        cast.add_cast(CastOp::IntToReal, context);
+        return cast;
+    }
+
     // Cast the SBVT to a string.
     if context.is_string() {
         trace!("  Unpacking string from SBVT ({})", context);
@@ -1500,6 +2153,101 @@ pub(crate) fn need_self_determined_type<'a>(
     }
 }
 
+/// Determine the type of a streaming concatenation.
+///
+/// Streams assigned to a wider simple bit vector take on the width of that
+/// vector, since they are left-aligned and padded with zeros on the right.
+/// Streams that are wider than their target are rejected.
+///
+/// See §11.4.14 "Streaming operators (pack/unpack)".
+fn stream_concat_type<'a>(
+    cx: &impl Context<'a>,
+    expr: &'a hir::Expr<'a>,
+    env: ParamEnv,
+) -> &'a UnpackedType<'a> {
+    let ty = cx.need_self_determined_type(expr.id, env);
+    if ty.is_error() || expr_is_lvalue(cx, expr.id, env) {
+        return ty;
+    }
+    let context = match cx.type_context(expr.id, env) {
+        Some(TypeContext::Type(x)) if !x.is_error() => x,
+        _ => return ty,
+    };
+    let sbvt = match context.get_simple_bit_vector() {
+        Some(x) => x,
+        None => return ty,
+    };
+    let width = ty.get_bit_size().unwrap();
+    if sbvt.size < width {
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "streaming concatenation is too wide for `{}`",
+                context
+            ))
+            .span(expr.span)
+            .add_note(format!(
+                "The stream has {} bits, but `{}` only has {}",
+                width, context, sbvt.size
+            )),
+        );
+        return UnpackedType::make_error();
+    }
+    SbvType::new(ty.domain(), sbvt.sign, sbvt.size).to_unpacked(cx)
+}
+
+/// Determine the slice size of a streaming concatenation.
+///
+/// Streams without an explicit slice size use a slice size of 1.
+#[moore_derive::query]
+pub(crate) fn stream_slice_size<'a>(
+    cx: &impl Context<'a>,
+    expr_id: NodeId,
+    env: ParamEnv,
+) -> Result<usize> {
+    let span = cx.span(expr_id);
+    let slice = match cx.hir_of(expr_id)? {
+        HirNode::Expr(hir::Expr {
+            kind: hir::ExprKind::StreamConcat(_, slice, _),
+            ..
+        }) => *slice,
+        _ => bug_span!(span, cx, "not a streaming concatenation"),
+    };
+    let (size, span) = match slice {
+        None => return Ok(1),
+        Some(hir::StreamSlice::Expr(expr)) => {
+            (cx.constant_int_value_of(expr, env)?.clone(), cx.span(expr))
+        }
+        Some(hir::StreamSlice::Type(ty)) => {
+            let ast = cx.ast_for_id(ty).as_all().get_type().unwrap();
+            let ty = cx.packed_type_from_ast(Ref(ast), env, None);
+            if ty.is_error() {
+                return Err(());
+            }
+            match ty.get_bit_size() {
+                Some(size) => (BigInt::from(size), ast.span),
+                None => {
+                    cx.emit(
+                        DiagBuilder2::error(format!("`{}` cannot be used as a slice size", ty))
+                            .span(ast.span)
+                            .add_note(format!("`{}` has no fixed bit width", ty)),
+                    );
+                    return Err(());
+                }
+            }
+        }
+    };
+    match size.to_usize() {
+        Some(size) if size > 0 => Ok(size),
+        _ => {
+            cx.emit(
+                DiagBuilder2::error(format!("slice size must be positive, but is {}", size))
+                    .span(span),
+            );
+            Err(())
+        }
+    }
+}
+
 /// Get the self-determined type of an expression.
 fn self_determined_expr_type<'gcx>(
     cx: &impl Context<'gcx>,
@@ -1535,6 +2283,9 @@ fn self_determined_expr_type<'gcx>(
         // Time constants are of time type.
         hir::ExprKind::TimeConst(_) => Some(UnpackedType::make_time()),
 
+        // Real constants are of real type.
+        hir::ExprKind::RealConst(_) => Some(UnpackedType::make_real(ty::RealType::Real)),
+
         // String literals behave like a packed array containing the characters.
         hir::ExprKind::StringConst(string) => Some(
             ty::PackedType::make_dims(
@@ -1553,10 +2304,63 @@ fn self_determined_expr_type<'gcx>(
         // node.
         hir::ExprKind::Ident(_) | hir::ExprKind::Scope(..) => Some(
             cx.resolve_node(expr.id, env)
-                .and_then(|x| cx.type_of(x, env))
+                .and_then(|x| match cx.hir_of(x)? {
+                    HirNode::Let(_) => cx.type_of(x, cx.let_env(expr.id, env)?),
+                    _ => cx.type_of(x, class::member_env(cx, expr.id, x, env)),
+                })
                 .unwrap_or(UnpackedType::make_error()),
         ),
 
+        // `this` refers to the object a method is called on.
+        hir::ExprKind::This => match class::enclosing_class(cx, expr.id) {
+            Some(class) => Some(class::class_type(cx, class, env)),
+            None => {
+                cx.emit(DiagBuilder2::error("`this` used outside of a class").span(expr.span));
+                Some(UnpackedType::make_error())
+            }
+        },
+
+        // `super` refers to the object a method is called on, as an object of
+        // the base class.
+        hir::ExprKind::Super => {
+            let details = match class::enclosing_class(cx, expr.id) {
+                Some(class) => cx.class_details(class.id(), env).ok(),
+                None => {
+                    cx.emit(DiagBuilder2::error("`super` used outside of a class").span(expr.span));
+                    return Some(UnpackedType::make_error());
+                }
+            };
+            match details {
+                Some(details) => match details.base {
+                    Some(ref base) => Some(class::class_type(cx, base.hir.ast, base.env)),
+                    None => {
+                        cx.emit(
+                            DiagBuilder2::error(format!(
+                                "`super` used in class `{}`, which does not extend another class",
+                                details.hir.name
+                            ))
+                            .span(expr.span),
+                        );
+                        Some(UnpackedType::make_error())
+                    }
+                },
+                None => Some(UnpackedType::make_error()),
+            }
+        }
+
+        // Concatenation involving a string yields a string.
+        //
+        // See §6.16 "String data type".
+        hir::ExprKind::Concat(_, ref exprs)
+            if exprs.iter().any(|&expr| {
+                cx.self_determined_type(expr, env)
+                    .map(|ty| ty.is_string())
+                    .unwrap_or(false)
+            }) =>
+        {
+            Some(UnpackedType::make(cx, UnpackedCore::String))
+        }
+
         // Concatenation yields an unsigned logic vector whose bit width is the
         // sum of the simple bit vector types of each argument.
         //
@@ -1616,6 +2420,49 @@ fn self_determined_expr_type<'gcx>(
             }
         }
 
+        // Streaming concatenations yield an unsigned bit vector that is as wide
+        // as the bit stream formed by their arguments.
+        //
+        // See §11.4.14 "Streaming operators (pack/unpack)".
+        hir::ExprKind::StreamConcat(_, _, ref exprs) => {
+            let mut failed = cx.stream_slice_size(expr.id, env).is_err();
+
+            // Determine the cumulative width of all arguments.
+            let mut bit_width = 0;
+            let mut domain = ty::Domain::TwoValued;
+            for &expr in exprs {
+                let ty = cx.need_self_determined_type(expr, env);
+                if ty.is_error() {
+                    failed = true;
+                    continue;
+                }
+                if ty.domain() == ty::Domain::FourValued {
+                    domain = ty::Domain::FourValued;
+                }
+                match ty.get_simple_bit_vector() {
+                    Some(sbv) => bit_width += sbv.size,
+                    None => {
+                        cx.emit(
+                            DiagBuilder2::error(format!("cannot stream a value of type `{}`", ty))
+                                .span(cx.span(expr))
+                                .add_note(format!(
+                                    "`{}` has no simple bit-vector type representation",
+                                    ty
+                                )),
+                        );
+                        failed = true;
+                        continue;
+                    }
+                }
+            }
+
+            if failed {
+                Some(UnpackedType::make_error())
+            } else {
+                Some(SbvType::new(domain, Sign::Unsigned, bit_width).to_unpacked(cx))
+            }
+        }
+
         // Casts trivially evaluate to the cast type.
         hir::ExprKind::Cast(ty, _) => Some(cx.packed_type_from_ast(
             Ref(cx.ast_for_id(ty).as_all().get_type().unwrap()),
@@ -1671,29 +2518,87 @@ fn self_determined_expr_type<'gcx>(
             Some(sbv.change_size(size).to_unpacked(cx))
         }
 
-        // The `inside` expression evaluates to a boolean.
-        hir::ExprKind::Inside(..) => Some(UnpackedType::make_logic()),
+        // The `inside` and `matches` expressions evaluate to a boolean.
+        hir::ExprKind::Inside(..) | hir::ExprKind::Matches(..) => Some(UnpackedType::make_logic()),
+
+        // The value of a `case ... matches` statement is that of its
+        // expression.
+        hir::ExprKind::CaseValue { expr, .. } => Some(cx.need_self_determined_type(expr, env)),
 
         // Most builtin functions evaluate to the integer type.
         hir::ExprKind::Builtin(hir::BuiltinCall::Unsupported)
         | hir::ExprKind::Builtin(hir::BuiltinCall::Clog2(_))
         | hir::ExprKind::Builtin(hir::BuiltinCall::Bits(_))
         | hir::ExprKind::Builtin(hir::BuiltinCall::CountOnes(_))
-        | hir::ExprKind::Builtin(hir::BuiltinCall::ArrayDim(..)) => {
+        | hir::ExprKind::Builtin(hir::BuiltinCall::ArrayDim(..))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Random(..)) => {
             Some(PackedType::make(cx, ty::IntAtomType::Int).to_unpacked(cx))
         }
 
+        // The simulation time functions evaluate to the time and realtime
+        // types, respectively.
+        hir::ExprKind::Builtin(hir::BuiltinCall::Time) => Some(UnpackedType::make_time()),
+        hir::ExprKind::Builtin(hir::BuiltinCall::RealTime) => {
+            Some(UnpackedType::make_real(ty::RealType::RealTime))
+        }
+
+        // Simulation control tasks evaluate to void.
+        hir::ExprKind::Builtin(hir::BuiltinCall::Finish)
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Stop) => Some(UnpackedType::make_void()),
+
         // These builtin functions evaluate to the bit type.
         hir::ExprKind::Builtin(hir::BuiltinCall::OneHot(_))
         | hir::ExprKind::Builtin(hir::BuiltinCall::OneHot0(_))
-        | hir::ExprKind::Builtin(hir::BuiltinCall::IsUnknown(_)) => {
+        | hir::ExprKind::Builtin(hir::BuiltinCall::IsUnknown(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Rose(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Fell(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Stable(_)) => {
             Some(PackedType::make(cx, ty::IntVecType::Bit).to_unpacked(cx))
         }
 
+        // The `$past` function evaluates to the type of its argument.
+        hir::ExprKind::Builtin(hir::BuiltinCall::Past(arg, _)) => cx.self_determined_type(arg, env),
+
+        // The real conversion functions evaluate to the type they convert to.
+        hir::ExprKind::Builtin(hir::BuiltinCall::RealConv(conv, _)) => Some(match conv {
+            hir::RealConv::RtoI => PackedType::make(cx, ty::IntAtomType::Integer).to_unpacked(cx),
+            hir::RealConv::ItoR | hir::RealConv::BitsToReal => {
+                UnpackedType::make_real(ty::RealType::Real)
+            }
+            hir::RealConv::BitsToShortReal => UnpackedType::make_real(ty::RealType::ShortReal),
+            hir::RealConv::RealToBits => {
+                SbvType::new(Domain::TwoValued, Sign::Unsigned, 64).to_unpacked(cx)
+            }
+            hir::RealConv::ShortRealToBits => {
+                SbvType::new(Domain::TwoValued, Sign::Unsigned, 32).to_unpacked(cx)
+            }
+        }),
+
+        // Display tasks evaluate to void and `$sformatf` to a string, provided
+        // their arguments match the format specifiers.
+        hir::ExprKind::Builtin(hir::BuiltinCall::Display(_, ref items))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::SFormatF(ref items)) => {
+            let mut failed = false;
+            for item in items {
+                if let hir::FormatItem::Arg(spec, arg) = *item {
+                    failed |= typeck_format_arg(cx, spec, arg, env).is_err();
+                }
+            }
+            if failed {
+                Some(UnpackedType::make_error())
+            } else if let hir::ExprKind::Builtin(hir::BuiltinCall::SFormatF(..)) = expr.kind {
+                Some(UnpackedType::make(cx, UnpackedCore::String))
+            } else {
+                Some(UnpackedType::make_void())
+            }
+        }
+
         // Member field accesses resolve to the type of the member.
         hir::ExprKind::Field(target, name) => {
             let target_ty = cx.self_determined_type(target, env)?;
-            if let Some(intf) = target_ty.get_interface() {
+            if target_ty.is_error() {
+                Some(target_ty)
+            } else if let Some(intf) = target_ty.get_interface() {
                 let def = cx.resolve_hierarchical_or_error(name, intf.ast).ok()?;
                 // If we are selecting a modport, just modify the type of the
                 // expression and let the implicit casting logic take care of
@@ -1728,6 +2633,55 @@ fn self_determined_expr_type<'gcx>(
                             .unwrap_or(UnpackedType::make_error()),
                     )
                 }
+            } else if let Some(class) = target_ty.get_class() {
+                // Property accesses resolve to the type of the property, in
+                // the parametrization of the class that declares it.
+                let details = match cx.class_details(class.ast.id(), class.env) {
+                    Ok(x) => x,
+                    Err(()) => return Some(UnpackedType::make_error()),
+                };
+                match details.find_prop(name.value) {
+                    Some((_, prop)) => Some(
+                        cx.type_of(prop.id, prop.env)
+                            .unwrap_or(UnpackedType::make_error()),
+                    ),
+                    None => {
+                        cx.emit(
+                            DiagBuilder2::error(format!(
+                                "no property `{}` in class `{}`",
+                                name, target_ty
+                            ))
+                            .span(name.span),
+                        );
+                        Some(UnpackedType::make_error())
+                    }
+                }
+            } else if let Some(module) = target_ty.get_module() {
+                // Hierarchical references into module instances, such as
+                // `u_foo[2].x`, resolve to the variables and nets declared in
+                // the instantiated module.
+                let def = cx.resolve_hierarchical_or_error(name, module.ast).ok()?;
+                match cx.hir_of(def.node.id()) {
+                    Ok(HirNode::VarDecl(..)) => Some(
+                        cx.type_of(def.node.id(), module.env)
+                            .unwrap_or(UnpackedType::make_error()),
+                    ),
+                    _ => {
+                        cx.emit(
+                            DiagBuilder2::error(format!(
+                                "unsupported: hierarchical reference `{}` to {}",
+                                expr.span.extract(),
+                                def.node
+                            ))
+                            .span(expr.span)
+                            .add_note(
+                                "Only the variables and nets declared in a module instance can \
+                                 be accessed hierarchically",
+                            ),
+                        );
+                        Some(UnpackedType::make_error())
+                    }
+                }
             } else {
                 Some(
                     cx.resolve_field_access(expr.id, env)
@@ -1838,7 +2792,9 @@ fn self_determined_expr_type<'gcx>(
             | hir::UnaryOp::PreDec
             | hir::UnaryOp::PostInc
             | hir::UnaryOp::PostDec => {
-                unify_operator_types(cx, env, cx.self_determined_type(arg, env).into_iter())
+                let targ = cx.self_determined_type(arg, env);
+                unify_real_operator_types(targ.into_iter())
+                    .or_else(|| unify_operator_types(cx, env, targ.into_iter()))
             }
         },
 
@@ -1869,12 +2825,21 @@ fn self_determined_expr_type<'gcx>(
             | hir::BinaryOp::BitXnor => {
                 let tlhs = cx.self_determined_type(lhs, env);
                 let trhs = cx.self_determined_type(rhs, env);
-                unify_operator_types(cx, env, tlhs.into_iter().chain(trhs.into_iter()))
+                unify_real_operator_types(tlhs.into_iter().chain(trhs.into_iter())).or_else(|| {
+                    unify_operator_types(cx, env, tlhs.into_iter().chain(trhs.into_iter()))
+                })
             }
 
-            // Exponentiation and shifts operate on the left-hand side type.
-            hir::BinaryOp::Pow
-            | hir::BinaryOp::LogicShL
+            // Exponentiation operates on the left-hand side type, unless the
+            // exponent is a real.
+            hir::BinaryOp::Pow => {
+                let tlhs = cx.self_determined_type(lhs, env);
+                let trhs = cx.self_determined_type(rhs, env);
+                unify_real_operator_types(tlhs.into_iter().chain(trhs.into_iter())).or(tlhs)
+            }
+
+            // Shifts operate on the left-hand side type.
+            hir::BinaryOp::LogicShL
             | hir::BinaryOp::LogicShR
             | hir::BinaryOp::ArithShL
             | hir::BinaryOp::ArithShR => cx.self_determined_type(lhs, env),
@@ -1885,26 +2850,35 @@ fn self_determined_expr_type<'gcx>(
         hir::ExprKind::Ternary(_, lhs, rhs) => {
             let tlhs = cx.self_determined_type(lhs, env);
             let trhs = cx.self_determined_type(rhs, env);
-            unify_operator_types(cx, env, tlhs.into_iter().chain(trhs.into_iter()))
+            unify_real_operator_types(tlhs.into_iter().chain(trhs.into_iter()))
+                .or_else(|| unify_operator_types(cx, env, tlhs.into_iter().chain(trhs.into_iter())))
         }
 
         // Function calls resolve to the function's return type.
         hir::ExprKind::FunctionCall(target, _) => Some(
-            cx.hir_of(target)
-                .and_then(|hir| {
-                    let hir = match hir {
-                        HirNode::Subroutine(s) => s,
-                        _ => unreachable!(),
-                    };
-                    match hir.retty {
-                        Some(retty_id) => Ok(cx.packed_type_from_ast(
-                            Ref(cx.ast_for_id(retty_id).as_all().get_type().unwrap()),
-                            env,
-                            None,
-                        )),
-                        None => Ok(UnpackedType::make_void()),
-                    }
-                })
+            cx.type_of(target, class::member_env(cx, expr.id, target, env))
+                .unwrap_or(UnpackedType::make_error()),
+        ),
+
+        // Method calls resolve to the method's return type.
+        hir::ExprKind::MethodCall(object, ..) => {
+            Some(match builtin_method_type(cx, expr, object, env) {
+                Ok(Some(ty)) => ty,
+                Ok(None) => cx
+                    .called_method(Ref(expr), env)
+                    .and_then(|method| {
+                        let method = method.unwrap();
+                        cx.type_of(method.id, method.env)
+                    })
+                    .unwrap_or(UnpackedType::make_error()),
+                Err(()) => UnpackedType::make_error(),
+            })
+        }
+
+        // Let expansions resolve to the type of the expanded expression.
+        hir::ExprKind::LetCall(target, _) => Some(
+            cx.let_env(expr.id, env)
+                .and_then(|env| cx.type_of(target, env))
                 .unwrap_or(UnpackedType::make_error()),
         ),
 
@@ -1919,6 +2893,68 @@ fn self_determined_expr_type<'gcx>(
     }
 }
 
+/// Determine the type returned by a call to a built-in method of a container,
+/// enum, or string.
+///
+/// Returns `None` if the call does not target a built-in method.
+fn builtin_method_type<'gcx>(
+    cx: &impl Context<'gcx>,
+    expr: &'gcx hir::Expr<'gcx>,
+    object: NodeId,
+    env: ParamEnv,
+) -> Result<Option<&'gcx UnpackedType<'gcx>>> {
+    let object_ty = cx.need_self_determined_type(object, env);
+    if let Some(method) = cx.container_method(Ref(expr), env)? {
+        return Ok(Some(container::method_type(cx, method, object_ty)));
+    }
+    if let Some(method) = cx.enum_method(Ref(expr), env)? {
+        return Ok(Some(enums::method_type(cx, method, object_ty)));
+    }
+    if let Some(method) = cx.string_method(Ref(expr), env)? {
+        return Ok(Some(strings::method_type(cx, method)));
+    }
+    Ok(None)
+}
+
+/// Check that an argument matches its format specifier in a `$display` call.
+fn typeck_format_arg<'gcx>(
+    cx: &impl Context<'gcx>,
+    spec: hir::FormatSpec,
+    arg: NodeId,
+    env: ParamEnv,
+) -> Result<()> {
+    let ty = cx.need_self_determined_type(arg, env);
+    if ty.is_error() {
+        return Err(());
+    }
+    let spec = spec.resolve(ty);
+    let integral = ty.get_simple_bit_vector().is_some();
+    let ok = match spec.kind {
+        hir::FormatKind::String => ty.is_string() || integral,
+        hir::FormatKind::Char => integral,
+        _ => ty.is_real() || integral,
+    };
+    if ok {
+        return Ok(());
+    }
+    let span = cx.span(arg);
+    cx.emit(
+        DiagBuilder2::error(format!(
+            "format specifier `{}` cannot format `{}` of type `{}`",
+            spec,
+            span.extract(),
+            ty
+        ))
+        .span(span)
+        .add_note(match spec.kind {
+            hir::FormatKind::String => "`%s` expects a string or integral value",
+            hir::FormatKind::Char => "`%c` expects an integral value",
+            _ => "Format specifier expects an integral or real value",
+        }),
+    );
+    Err(())
+}
+
 fn self_determined_sign_cast_type<'gcx>(
     cx: &impl Context<'gcx>,
     sign: Sign,
@@ -1985,7 +3021,12 @@ pub(crate) fn operation_type<'a>(
                 | hir::UnaryOp::PostDec => {
                     let tc = cx.type_context(node_id, env).map(|x| x.ty());
                     let targ = cx.self_determined_type(arg, env);
-                    unify_operator_types(cx, env, tc.into_iter().chain(targ.into_iter()))
+                    match unify_real_operator_types(targ.into_iter()) {
+                        Some(ty) => Some(real_operation_type(cx, expr, ty)),
+                        None => {
+                            unify_operator_types(cx, env, tc.into_iter().chain(targ.into_iter()))
+                        }
+                    }
                 }
 
                 // Handle the self-determined cases.
@@ -2027,13 +3068,16 @@ pub(crate) fn operation_type<'a>(
                     let tc = cx.type_context(node_id, env).map(|x| x.ty());
                     let tlhs = cx.self_determined_type(lhs, env);
                     let trhs = cx.self_determined_type(rhs, env);
-                    unify_operator_types(
-                        cx,
-                        env,
-                        tc.into_iter()
-                            .chain(tlhs.into_iter())
-                            .chain(trhs.into_iter()),
-                    )
+                    match unify_real_operator_types(tlhs.into_iter().chain(trhs.into_iter())) {
+                        Some(ty) => Some(real_operation_type(cx, expr, ty)),
+                        None => unify_operator_types(
+                            cx,
+                            env,
+                            tc.into_iter()
+                                .chain(tlhs.into_iter())
+                                .chain(trhs.into_iter()),
+                        ),
+                    }
                 }
 
                 // Comparison operations do not consider their type context, but
@@ -2046,10 +3090,28 @@ pub(crate) fn operation_type<'a>(
                 | hir::BinaryOp::Geq => {
                     let tlhs = cx.self_determined_type(lhs, env);
                     let trhs = cx.self_determined_type(rhs, env);
-                    if tlhs.map(|t| t.is_string()).unwrap_or(false)
-                        && trhs.map(|t| t.is_string()).unwrap_or(false)
+                    let is_equality = op == hir::BinaryOp::Eq || op == hir::BinaryOp::Neq;
+                    if let Some(ty) = unify_class_operator_types(cx, tlhs, trhs) {
+                        if !is_equality {
+                            cx.emit(
+                                DiagBuilder2::error(format!(
+                                    "class handles can only be compared with `==` and `!=`"
+                                ))
+                                .span(expr.span()),
+                            );
+                            return Some(UnpackedType::make_error());
+                        }
+                        Some(ty)
+                    } else if let Some(ty) =
+                        unify_real_operator_types(tlhs.into_iter().chain(trhs.into_iter()))
                     {
+                        Some(real_operation_type(cx, expr, ty))
+                    } else if tlhs.map(|t| t.is_string()).unwrap_or(false) {
+                        // Strings compare lexically, with a string literal on
+                        // the other side being converted to a string.
                         tlhs
+                    } else if trhs.map(|t| t.is_string()).unwrap_or(false) {
+                        trhs
                     } else {
                         unify_operator_types(cx, env, tlhs.into_iter().chain(trhs.into_iter()))
                     }
@@ -2060,7 +3122,8 @@ pub(crate) fn operation_type<'a>(
                     Some(UnpackedType::make_logic())
                 }
 
-                // Exponentiation and shifts operate on the left-hand side type.
+                // Exponentiation and shifts operate on the left-hand side type,
+                // unless any operand is a real.
                 hir::BinaryOp::Pow
                 | hir::BinaryOp::LogicShL
                 | hir::BinaryOp::LogicShR
@@ -2068,7 +3131,13 @@ pub(crate) fn operation_type<'a>(
                 | hir::BinaryOp::ArithShR => {
                     let tc = cx.type_context(node_id, env).map(|x| x.ty());
                     let sdt = cx.self_determined_type(lhs, env);
-                    unify_operator_types(cx, env, tc.into_iter().chain(sdt.into_iter()))
+                    let trhs = cx.self_determined_type(rhs, env);
+                    match unify_real_operator_types(sdt.into_iter().chain(trhs.into_iter())) {
+                        Some(ty) => Some(real_operation_type(cx, expr, ty)),
+                        None => {
+                            unify_operator_types(cx, env, tc.into_iter().chain(sdt.into_iter()))
+                        }
+                    }
                 }
             };
             if ty.is_none() {
@@ -2096,13 +3165,15 @@ pub(crate) fn operation_type<'a>(
             let tc = cx.type_context(node_id, env).map(|x| x.ty());
             let tlhs = cx.self_determined_type(lhs, env);
             let trhs = cx.self_determined_type(rhs, env);
-            unify_operator_types(
-                cx,
-                env,
-                tc.into_iter()
-                    .chain(tlhs.into_iter())
-                    .chain(trhs.into_iter()),
-            )
+            unify_real_operator_types(tlhs.into_iter().chain(trhs.into_iter())).or_else(|| {
+                unify_operator_types(
+                    cx,
+                    env,
+                    tc.into_iter()
+                        .chain(tlhs.into_iter())
+                        .chain(trhs.into_iter()),
+                )
+            })
         }
 
         // The inside expression uses an operation type for its comparisons. It
@@ -2160,7 +3231,11 @@ pub(crate) fn operation_type<'a>(
 
         hir::ExprKind::Assign { lhs, rhs, .. } => cx
             .self_determined_type(lhs.id, env)
-            .or_else(|| cx.self_determined_type(rhs.id, env)),
+            .or_else(|| cx.self_determined_type(rhs.id, env))
+            .map(|ty| match ty.is_real() {
+                true => real_operation_type(cx, expr, ty),
+                false => ty,
+            }),
 
         _ => None,
     }
@@ -2191,6 +3266,115 @@ fn unify_operator_types<'gcx>(
     width.map(|w| SbvType::nice(domain, sign, w).to_unpacked(cx))
 }
 
+/// Determine the class type two class handles are compared as.
+///
+/// This is the type of the operand whose class the other operand's class
+/// derives from. Operands without a self-determined type, such as `null`, take
+/// on the type of the other operand. Returns `None` if neither operand is a
+/// class handle.
+fn unify_class_operator_types<'gcx>(
+    cx: &impl Context<'gcx>,
+    lhs: Option<&'gcx UnpackedType<'gcx>>,
+    rhs: Option<&'gcx UnpackedType<'gcx>>,
+) -> Option<&'gcx UnpackedType<'gcx>> {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => match (lhs.get_class(), rhs.get_class()) {
+            (Some(l), Some(r)) => match cx.class_details(l.ast.id(), l.env) {
+                Ok(details) if details.derives_from(r.ast.id()) => Some(rhs),
+                _ => Some(lhs),
+            },
+            (Some(_), _) => Some(lhs),
+            (_, Some(_)) => Some(rhs),
+            _ => None,
+        },
+        (Some(ty), None) | (None, Some(ty)) => ty.get_class().map(|_| ty),
+        (None, None) => None,
+    }
+}
+
+/// Determine the floating-point type of an operator if any of its operands is
+/// a `real`, `shortreal`, or `realtime`.
+///
+/// The result is `shortreal` if all floating-point operands are `shortreal`,
+/// and `real` otherwise. Returns `None` if the operator works on integers.
+fn unify_real_operator_types<'gcx>(
+    types: impl Iterator<Item = &'gcx UnpackedType<'gcx>>,
+) -> Option<&'gcx UnpackedType<'gcx>> {
+    let mut result = None;
+    for real in types.flat_map(|ty| ty.get_real()) {
+        result = match (result, real) {
+            (None, ty::RealType::ShortReal)
+            | (Some(ty::RealType::ShortReal), ty::RealType::ShortReal) => {
+                Some(ty::RealType::ShortReal)
+            }
+            _ => Some(ty::RealType::Real),
+        };
+    }
+    result.map(UnpackedType::make_real)
+}
+
+/// Check that an operator is defined for floating-point operands.
+///
+/// Returns the operation type `ty` if it is, or emits a diagnostic and returns
+/// an error type otherwise.
+fn real_operation_type<'gcx>(
+    cx: &impl Context<'gcx>,
+    expr: &'gcx hir::Expr<'gcx>,
+    ty: &'gcx UnpackedType<'gcx>,
+) -> &'gcx UnpackedType<'gcx> {
+    let valid = match expr.kind {
+        hir::ExprKind::Unary(op, _) => match op {
+            hir::UnaryOp::Neg
+            | hir::UnaryOp::Pos
+            | hir::UnaryOp::PreInc
+            | hir::UnaryOp::PreDec
+            | hir::UnaryOp::PostInc
+            | hir::UnaryOp::PostDec => true,
+            _ => false,
+        },
+        hir::ExprKind::Binary(op, ..) => match op {
+            hir::BinaryOp::Add
+            | hir::BinaryOp::Sub
+            | hir::BinaryOp::Mul
+            | hir::BinaryOp::Div
+            | hir::BinaryOp::Pow
+            | hir::BinaryOp::Eq
+            | hir::BinaryOp::Neq
+            | hir::BinaryOp::Lt
+            | hir::BinaryOp::Leq
+            | hir::BinaryOp::Gt
+            | hir::BinaryOp::Geq => true,
+            _ => false,
+        },
+        hir::ExprKind::Assign { op, .. } => match op {
+            ast::AssignOp::Identity
+            | ast::AssignOp::Add
+            | ast::AssignOp::Sub
+            | ast::AssignOp::Mul
+            | ast::AssignOp::Div => true,
+            _ => false,
+        },
+        _ => true,
+    };
+    if valid {
+        return ty;
+    }
+    cx.emit(
+        DiagBuilder2::error(format!(
+            "operator in {} cannot be applied to `{}` values",
+            expr.desc_full(),
+            ty
+        ))
+        .span(expr.human_span())
+        .add_note(
+            "Real values only support arithmetic other than `%`, and comparisons; bitwise \
+             operators, reductions, and shifts require integral operands",
+        )
+        .add_note("Convert the value to an integer first, e.g. with `$rtoi`"),
+    );
+    UnpackedType::make_error()
+}
+
 /// Require a node to have an operation type.
 ///
 /// Emits an error if the node has no operation type.
@@ -2231,7 +3415,7 @@ pub(crate) fn type_context<'a>(
             if a.lhs == onto {
                 cx.self_determined_type(a.rhs, env).map(Into::into)
             } else if a.rhs == onto {
-                cx.self_determined_type(a.lhs, env).map(Into::into)
+                assign_rhs_type_context(cx, a.lhs, a.rhs, env)
             } else {
                 None
             }
@@ -2278,10 +3462,12 @@ pub(crate) fn type_context<'a>(
                         details.inner_env,
                         onto
                     );
-                    cx.type_of_ext_port(Ref(id), details.inner_env)
+                    let ty = cx.type_of_ext_port(Ref(id), details.inner_env);
+                    inst_array_port_type(cx, inst, onto, ty, env)
                 })
                 .map(Into::into)
         }
+        HirNode::Assertion(a) if a.bool_exprs().contains(&onto) => Some(TypeContext::Bool),
         HirNode::InstTarget(inst) => {
             let details = cx.inst_target_details(Ref(inst), env).ok()?;
             details
@@ -2294,6 +3480,111 @@ pub(crate) fn type_context<'a>(
     }
 }
 
+/// Determine the type context an assignment imposes on its right-hand side.
+///
+/// This is usually the self-determined type of the left-hand side. Streaming
+/// concatenations on the left-hand side unpack the left-most bits of the
+/// assigned value, which therefore keeps its width.
+fn assign_rhs_type_context<'a>(
+    cx: &impl Context<'a>,
+    lhs: NodeId,
+    rhs: NodeId,
+    env: ParamEnv,
+) -> Option<TypeContext<'a>> {
+    let lhs_ty = cx.self_determined_type(lhs, env)?;
+    let is_stream = match cx.hir_of(lhs) {
+        Ok(HirNode::Expr(x)) => match x.kind {
+            hir::ExprKind::StreamConcat(..) => true,
+            _ => false,
+        },
+        _ => false,
+    };
+    if !is_stream || lhs_ty.is_error() {
+        return Some(lhs_ty.into());
+    }
+
+    // Make sure the value provides enough bits for the stream.
+    let rhs_ty = match cx.self_determined_type(rhs, env) {
+        Some(x) if !x.is_error() => x,
+        _ => return Some(lhs_ty.into()),
+    };
+    let width = lhs_ty.get_bit_size().unwrap();
+    let sbvt = match rhs_ty.get_simple_bit_vector() {
+        Some(x) => x,
+        None => {
+            cx.emit(
+                DiagBuilder2::error(format!("cannot unpack a value of type `{}`", rhs_ty))
+                    .span(cx.span(rhs))
+                    .add_note(format!(
+                        "`{}` has no simple bit-vector type representation",
+                        rhs_ty
+                    )),
+            );
+            return Some(UnpackedType::make_error().into());
+        }
+    };
+    if sbvt.size < width {
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "`{}` has too few bits to unpack into the stream",
+                cx.span(rhs).extract()
+            ))
+            .span(cx.span(rhs))
+            .add_note(format!(
+                "The stream needs {} bits, but `{}` only has {}",
+                width, rhs_ty, sbvt.size
+            ))
+            .span(cx.span(lhs)),
+        );
+        return Some(UnpackedType::make_error().into());
+    }
+    Some(
+        SbvType::new(lhs_ty.domain(), sbvt.sign, sbvt.size)
+            .to_unpacked(cx)
+            .into(),
+    )
+}
+
+/// Determine the type of a port connection of an instance array.
+///
+/// Unpacked array connections whose elements match the port connect one
+/// element to each instance, and connections that are as wide as the port
+/// times the number of instances are sliced up among the instances. Both keep
+/// their full type. All other connections are replicated to each instance and
+/// use the port's type.
+///
+/// See §23.3.3.5 "Unpacked array ports and arrays of instances".
+fn inst_array_port_type<'a>(
+    cx: &impl Context<'a>,
+    inst: &'a hir::Inst<'a>,
+    onto: NodeId,
+    port_ty: &'a UnpackedType<'a>,
+    env: ParamEnv,
+) -> &'a UnpackedType<'a> {
+    let len = match inst_array_len(cx, inst, env) {
+        Ok(len) if len > 1 => len,
+        _ => return port_ty,
+    };
+    if let Some(ty) = cx.self_determined_type(onto, env) {
+        if inst_array_element(cx, inst, env, ty, port_ty, 0).is_some() {
+            return ty;
+        }
+    }
+    let port_sbvt = match port_ty.get_simple_bit_vector() {
+        Some(x) => x,
+        None => return port_ty,
+    };
+    match cx
+        .self_determined_type(onto, env)
+        .and_then(|ty| ty.get_simple_bit_vector())
+    {
+        Some(sbvt) if sbvt.size == len * port_sbvt.size => {
+            SbvType::new(port_sbvt.domain, port_sbvt.sign, sbvt.size).to_unpacked(cx)
+        }
+        _ => port_ty,
+    }
+}
+
 /// Get the type context of a node.
 #[moore_derive::query]
 pub(crate) fn need_type_context<'a>(
@@ -2408,6 +3699,11 @@ fn type_context_imposed_by_expr<'gcx>(
             // The logic operators require boolean arguments.
             hir::BinaryOp::LogicAnd | hir::BinaryOp::LogicOr => Some(TypeContext::Bool),
 
+            // Real exponentiation imposes its type onto both operands.
+            hir::BinaryOp::Pow if cx.need_operation_type(expr.id, env).is_real() => {
+                Some(cx.need_operation_type(expr.id, env).into())
+            }
+
             // Exponentiation and shifts impose a type context on their left
             // hand side.
             hir::BinaryOp::Pow
@@ -2465,9 +3761,41 @@ fn type_context_imposed_by_expr<'gcx>(
             Some(cx.need_self_determined_type(expr.id, env).into())
         }
 
+        // The real conversion functions impose the type they convert from.
+        hir::ExprKind::Builtin(hir::BuiltinCall::RealConv(conv, arg)) if onto == arg => Some(
+            match conv {
+                hir::RealConv::RtoI | hir::RealConv::RealToBits => {
+                    UnpackedType::make_real(ty::RealType::Real)
+                }
+                hir::RealConv::ShortRealToBits => UnpackedType::make_real(ty::RealType::ShortReal),
+                hir::RealConv::ItoR => {
+                    PackedType::make(cx, ty::IntAtomType::Integer).to_unpacked(cx)
+                }
+                hir::RealConv::BitsToReal => {
+                    SbvType::new(Domain::TwoValued, Sign::Unsigned, 64).to_unpacked(cx)
+                }
+                hir::RealConv::BitsToShortReal => {
+                    SbvType::new(Domain::TwoValued, Sign::Unsigned, 32).to_unpacked(cx)
+                }
+            }
+            .into(),
+        ),
+
+        // The slice size of streaming concatenations is self-determined.
+        hir::ExprKind::StreamConcat(_, Some(hir::StreamSlice::Expr(slice)), _) if onto == slice => {
+            None
+        }
+
+        // String concatenations impose `string` onto their arguments.
+        hir::ExprKind::Concat(repeat, _)
+            if repeat != Some(onto) && cx.need_self_determined_type(expr.id, env).is_string() =>
+        {
+            Some(UnpackedType::make(cx, UnpackedCore::String).into())
+        }
+
         // Concatenations require their arguments (including repetition counts)
         // to map to a corresponding SBVT.
-        hir::ExprKind::Concat(..) => {
+        hir::ExprKind::Concat(..) | hir::ExprKind::StreamConcat(..) => {
             let ty = cx.need_self_determined_type(onto, env);
             if ty.is_error() {
                 return Some(ty.into());
@@ -2513,6 +3841,19 @@ fn type_context_imposed_by_expr<'gcx>(
         // The `inside` expression imposes its operation type as type context.
         hir::ExprKind::Inside(..) => Some(cx.need_operation_type(expr.id, env).into()),
 
+        // Tagged union expressions impose the member type on their value.
+        hir::ExprKind::Tagged(member, Some(value)) if onto == value => {
+            let ty = cx.need_type_context(expr.id, env).ty();
+            find_tagged_member(ty, member.value).map(|(_, x)| x.ty.into())
+        }
+
+        // Constants in patterns take on the type of the value they are
+        // compared against.
+        hir::ExprKind::Matches(value, ref pattern) if onto != value => {
+            let ty = cx.need_self_determined_type(value, env);
+            match_pattern_type(pattern, ty, onto).map(Into::into)
+        }
+
         // Bit- and part-select expressions impose their operation type as type
         // context.
         hir::ExprKind::Index(target, _) if onto == target => {
@@ -2525,9 +3866,76 @@ fn type_context_imposed_by_expr<'gcx>(
             Some(opty.into())
         }
 
+        // Indices into dynamic arrays, queues, and associative arrays are
+        // converted to the container's index type.
+        hir::ExprKind::Index(target, hir::IndexMode::One(index)) if onto == index => cx
+            .self_determined_type(target, env)
+            .and_then(|ty| ty.get_container())
+            .and_then(|dim| container::index_type(cx, dim))
+            .map(Into::into),
+
+        // Dynamic array allocations impose `int` on their size, and their own
+        // type on the array whose elements they copy.
+        hir::ExprKind::ArrayNew(size, _) if onto == size => Some(
+            PackedType::make(cx, IntAtomType::Int)
+                .to_unpacked(cx)
+                .into(),
+        ),
+        hir::ExprKind::ArrayNew(..) => Some(cx.need_type_context(expr.id, env)),
+
         // Assignments impose their operation type as context.
         hir::ExprKind::Assign { .. } => Some(cx.need_operation_type(expr.id, env).into()),
 
+        // Built-in methods of dynamic arrays, queues, and associative arrays
+        // impose the type of the corresponding argument.
+        hir::ExprKind::MethodCall(object, _, ref args) if is_container_method(cx, expr, env) => {
+            let method = cx.container_method(Ref(expr), env).unwrap().unwrap();
+            let index = args.iter().position(|arg| arg.expr == Some(onto))?;
+            let ty = cx.need_self_determined_type(object, env);
+            container::arg_type(cx, method, ty, index).map(Into::into)
+        }
+
+        // The steps taken by the built-in `next` and `prev` methods of enums
+        // are of type `int`. The enum itself keeps its type.
+        hir::ExprKind::MethodCall(object, ..) if is_enum_method(cx, expr, env) => {
+            if onto == object {
+                None
+            } else {
+                Some(
+                    PackedType::make(cx, IntAtomType::Int)
+                        .to_unpacked(cx)
+                        .into(),
+                )
+            }
+        }
+
+        // Built-in methods of strings impose the type of the corresponding
+        // argument.
+        hir::ExprKind::MethodCall(_, _, ref args) if is_string_method(cx, expr, env) => {
+            let method = cx.string_method(Ref(expr), env).unwrap().unwrap();
+            let index = args.iter().position(|arg| arg.expr == Some(onto))?;
+            Some(strings::arg_type(cx, method, index).into())
+        }
+
+        // Function calls impose the type of the corresponding subroutine
+        // argument onto the argument expression.
+        hir::ExprKind::FunctionCall(..)
+        | hir::ExprKind::MethodCall(..)
+        | hir::ExprKind::New(..) => {
+            if !has_call_mapping(cx, expr, env) {
+                return None;
+            }
+            let mapping = match cx.call_mapping(Ref(expr), env) {
+                Ok(x) => x,
+                Err(()) => return Some(UnpackedType::make_error().into()),
+            };
+            mapping.find(onto).map(|arg| {
+                cx.type_of(arg.arg.id, mapping.env)
+                    .unwrap_or(UnpackedType::make_error())
+                    .into()
+            })
+        }
+
         _ => None,
     }
 }
@@ -2589,7 +3997,7 @@ fn type_context_imposed_by_stmt<'gcx>(
             if lhs == onto {
                 cx.self_determined_type(rhs, env).map(Into::into)
             } else if rhs == onto {
-                cx.self_determined_type(lhs, env).map(Into::into)
+                assign_rhs_type_context(cx, lhs, rhs, env)
             } else {
                 None
             }
@@ -2598,6 +4006,9 @@ fn type_context_imposed_by_stmt<'gcx>(
         // If statements and do/while loops require a boolean condition.
         hir::StmtKind::If { cond, .. } if onto == cond => Some(TypeContext::Bool),
 
+        // Assertions require a boolean condition.
+        hir::StmtKind::Assertion { cond, .. } if onto == cond => Some(TypeContext::Bool),
+
         // Do/while loops require a boolean condition.
         hir::StmtKind::Loop { kind, .. } => {
             match kind {
@@ -2626,6 +4037,19 @@ fn type_context_imposed_by_stmt<'gcx>(
             }
         }
 
+        // Return statements impose the return type of the enclosing
+        // subroutine on their value.
+        hir::StmtKind::Return(Some(expr)) if onto == expr => {
+            let mut current = stmt.id;
+            while let Some(parent) = cx.parent_node_id(current) {
+                current = parent;
+                if let Ok(AstNode::SubroutineDecl(_)) = cx.ast_of(current) {
+                    return cx.type_of(current, env).ok().map(Into::into);
+                }
+            }
+            None
+        }
+
         _ => None,
     }
 }
@@ -2658,10 +4082,18 @@ pub enum CastOp {
     Domain(ty::Domain),
     /// Pick an interface's modport.
     PickModport,
+    /// Convert a class handle to a handle of one of its base classes.
+    Upcast,
     /// Pack a string into an SBVT.
     PackString,
     /// Unpack a string from an SBVT.
     UnpackString,
+    /// Convert an SBVT to a real.
+    IntToReal,
+    /// Convert a real to an SBVT, rounding to the nearest integer.
+    RealToInt,
+    /// Convert a real to a real of different precision.
+    ResizeReal,
 }
 
 impl<'a> CastType<'a> {
@@ -2698,7 +4130,7 @@ impl std::fmt::Display for CastType<'_> {
 }
 
 /// Check if an expression is in lvalue position.
-pub(crate) fn expr_is_lvalue<'gcx>(cx: &impl Context<'gcx>, onto: NodeId, _env: ParamEnv) -> bool {
+pub(crate) fn expr_is_lvalue<'gcx>(cx: &impl Context<'gcx>, onto: NodeId, env: ParamEnv) -> bool {
     let hir = match cx.hir_of(cx.parent_node_id(onto).unwrap()) {
         Ok(x) => x,
         Err(()) => return false,
@@ -2712,6 +4144,29 @@ pub(crate) fn expr_is_lvalue<'gcx>(cx: &impl Context<'gcx>, onto: NodeId, _env:
                 | hir::UnaryOp::PostDec => true,
                 _ => false,
             },
+            // The seed passed to `$random` is updated by the call.
+            hir::ExprKind::Builtin(hir::BuiltinCall::Random(Some(seed))) => seed == onto,
+            // Built-in methods such as `first(index)` assign to their
+            // argument.
+            hir::ExprKind::MethodCall(_, _, ref args) if is_container_method(cx, e, env) => {
+                let method = cx.container_method(Ref(e), env).unwrap().unwrap();
+                method.assigns_arg() && args.iter().any(|arg| arg.expr == Some(onto))
+            }
+            // Arguments passed to `output`, `inout`, and `ref` ports of a
+            // function or task are assigned to.
+            hir::ExprKind::FunctionCall(..)
+            | hir::ExprKind::MethodCall(..)
+            | hir::ExprKind::New(..)
+                if has_call_mapping(cx, e, env) =>
+            {
+                match cx.call_mapping(Ref(e), env) {
+                    Ok(mapping) => mapping.find(onto).map_or(false, |arg| match arg.arg.dir {
+                        ast::SubroutinePortDir::Input | ast::SubroutinePortDir::ConstRef => false,
+                        _ => true,
+                    }),
+                    Err(()) => false,
+                }
+            }
             _ => false,
         },
         HirNode::Stmt(s) => match s.kind {
@@ -2723,6 +4178,50 @@ pub(crate) fn expr_is_lvalue<'gcx>(cx: &impl Context<'gcx>, onto: NodeId, _env:
     }
 }
 
+/// Check whether a call has a mapping of its arguments.
+///
+/// This is the case for all calls except `new` for classes without a
+/// constructor, built-in methods of dynamic arrays, queues, associative arrays,
+/// enums, and strings, and calls whose target cannot be determined.
+fn has_call_mapping<'gcx>(
+    cx: &impl Context<'gcx>,
+    expr: &'gcx hir::Expr<'gcx>,
+    env: ParamEnv,
+) -> bool {
+    match expr.kind {
+        hir::ExprKind::FunctionCall(..) => true,
+        _ => cx.called_method(Ref(expr), env).ok().flatten().is_some(),
+    }
+}
+
+/// Check whether an expression calls a built-in method of a dynamic array,
+/// queue, or associative array.
+fn is_container_method<'gcx>(
+    cx: &impl Context<'gcx>,
+    expr: &'gcx hir::Expr<'gcx>,
+    env: ParamEnv,
+) -> bool {
+    cx.container_method(Ref(expr), env).ok().flatten().is_some()
+}
+
+/// Check whether an expression calls a built-in method of an enum.
+fn is_enum_method<'gcx>(
+    cx: &impl Context<'gcx>,
+    expr: &'gcx hir::Expr<'gcx>,
+    env: ParamEnv,
+) -> bool {
+    cx.enum_method(Ref(expr), env).ok().flatten().is_some()
+}
+
+/// Check whether an expression calls a built-in method of a string.
+fn is_string_method<'gcx>(
+    cx: &impl Context<'gcx>,
+    expr: &'gcx hir::Expr<'gcx>,
+    env: ParamEnv,
+) -> bool {
+    cx.string_method(Ref(expr), env).ok().flatten().is_some()
+}
+
 fn size_from_bounds_expr<'a>(
     cx: &impl Context<'a>,
     expr: NodeId,
@@ -14,12 +14,12 @@
 use crate::{
     crate_prelude::*,
     hir::HirNode,
-    ty::{SbvType, UnpackedType},
+    ty::{RealType, SbvType, UnpackedType},
     ParamEnv, ParamEnvBinding,
 };
 use bit_vec::BitVec;
 use itertools::Itertools;
-use num::{BigInt, BigRational, Integer, One, ToPrimitive, Zero};
+use num::{BigInt, BigRational, FromPrimitive, Integer, One, ToPrimitive, Zero};
 
 /// A verilog value.
 pub type Value<'t> = &'t ValueData<'t>;
@@ -50,8 +50,10 @@ impl<'t> ValueData<'t> {
             ValueKind::Void => true,
             ValueKind::Int(ref v, ..) => v.is_zero(),
             ValueKind::Time(ref v) => v.is_zero(),
+            ValueKind::Real(v) => f64::from_bits(v) == 0.0,
             ValueKind::StructOrArray(_) => false,
             ValueKind::String(ref v) => v.is_empty(),
+            ValueKind::Null => true,
             ValueKind::Error => true,
         }
     }
@@ -63,6 +65,14 @@ impl<'t> ValueData<'t> {
             _ => None,
         }
     }
+
+    /// Convert the value to a floating-point number.
+    pub fn get_real(&self) -> Option<f64> {
+        match self.kind {
+            ValueKind::Real(v) => Some(f64::from_bits(v)),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for ValueData<'_> {
@@ -83,6 +93,12 @@ pub enum ValueKind<'t> {
     Int(BigInt, BitVec, BitVec),
     /// An arbitrary precision time interval.
     Time(BigRational),
+    /// A floating-point number.
+    ///
+    /// Contains the bits of an IEEE 754 double-precision number, such that
+    /// values can be compared and hashed. `shortreal` values are rounded to
+    /// single precision.
+    Real(u64),
     /// A struct.
     StructOrArray(Vec<Value<'t>>),
     /// A string.
@@ -91,6 +107,9 @@ pub enum ValueKind<'t> {
     /// is due to `String` guaranteeing that the encoded byte sequence is valid
     /// UTF8, which SystemVerilog does not guarantee.
     String(Vec<u8>),
+    /// A null class handle, or an empty dynamic array, queue, or associative
+    /// array.
+    Null,
     /// An error occurred during value computation.
     Error,
 }
@@ -111,6 +130,7 @@ impl std::fmt::Display for ValueKind<'_> {
             ValueKind::Void => write!(f, "void"),
             ValueKind::Int(v, ..) => write!(f, "{}", v),
             ValueKind::Time(v) => write!(f, "{}", v),
+            ValueKind::Real(v) => write!(f, "{:?}", f64::from_bits(*v)),
             ValueKind::StructOrArray(v) => {
                 write!(f, "{{ {} }}", v.iter().map(|v| &v.kind).format(", "))
             }
@@ -125,6 +145,7 @@ impl std::fmt::Display for ValueKind<'_> {
                 }
                 write!(f, "\"")
             }
+            ValueKind::Null => write!(f, "null"),
             ValueKind::Error => write!(f, "<error>"),
         }
     }
@@ -178,6 +199,22 @@ pub fn make_time<'a>(value: BigRational) -> ValueData<'a> {
     }
 }
 
+/// Create a new floating-point value.
+///
+/// Panics if `ty` is not a real type. Rounds the value to single precision if
+/// `ty` is `shortreal`.
+pub fn make_real<'a>(ty: &'a UnpackedType<'a>, value: f64) -> ValueData<'a> {
+    let value = match ty.get_real() {
+        Some(RealType::ShortReal) => value as f32 as f64,
+        Some(_) => value,
+        None => panic!("make_real got non-real type `{}`", ty),
+    };
+    ValueData {
+        ty,
+        kind: ValueKind::Real(value.to_bits()),
+    }
+}
+
 /// Create a new struct value.
 pub fn make_struct<'a>(ty: &'a UnpackedType<'a>, fields: Vec<Value<'a>>) -> ValueData<'a> {
     assert!(ty.dims().next().is_none() && ty.get_struct().is_some());
@@ -205,6 +242,15 @@ pub fn make_string<'a>(ty: &'a UnpackedType<'a>, bytes: Vec<u8>) -> ValueData<'a
     }
 }
 
+/// Create a new null value for a class handle or container.
+pub fn make_null<'a>(ty: &'a UnpackedType<'a>) -> ValueData<'a> {
+    assert!(ty.get_class().is_some() || ty.get_container().is_some());
+    ValueData {
+        ty,
+        kind: ValueKind::Null,
+    }
+}
+
 /// Determine the constant value of a node.
 #[moore_derive::query]
 pub(crate) fn constant_value_of<'a>(
@@ -389,10 +435,99 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
 
         mir::RvalueKind::Transmute(value) => {
             let v = cx.const_mir_rvalue(value.into());
-            cx.intern_value(ValueData {
-                ty: mir.ty,
-                kind: v.kind.clone(),
-            })
+            match (&v.kind, mir.ty.get_real()) {
+                // Reinterpret the bits of a floating-point number as integer.
+                (&ValueKind::Real(bits), None) => {
+                    let bits = match value.ty.get_real() {
+                        Some(RealType::ShortReal) => (f64::from_bits(bits) as f32).to_bits() as u64,
+                        _ => bits,
+                    };
+                    cx.intern_value(make_int(mir.ty, bits.into()))
+                }
+                // Reinterpret the bits of an integer as floating-point number.
+                (ValueKind::Int(int, ..), Some(real)) => {
+                    let bits = int.mod_floor(&(BigInt::one() << real.bit_size()));
+                    let bits = bits.to_u64().unwrap();
+                    let real = match real {
+                        RealType::ShortReal => f32::from_bits(bits as u32) as f64,
+                        _ => f64::from_bits(bits),
+                    };
+                    cx.intern_value(make_real(mir.ty, real))
+                }
+                _ => cx.intern_value(ValueData {
+                    ty: mir.ty,
+                    kind: v.kind.clone(),
+                }),
+            }
+        }
+
+        mir::RvalueKind::IntToReal(sign, value) => {
+            let v = match cx.const_mir_rvalue_int(value.into()) {
+                Ok(v) => v,
+                Err(()) => return cx.intern_value(make_error(mir.ty)),
+            };
+            let size = value.ty.simple_bit_vector(cx, value.span).size;
+            let mut v = v.mod_floor(&(BigInt::one() << size));
+            if sign.is_signed() && size > 0 && v.bit(size as u64 - 1) {
+                v -= BigInt::one() << size;
+            }
+            cx.intern_value(make_real(mir.ty, v.to_f64().unwrap_or(0.0)))
+        }
+
+        mir::RvalueKind::RealToInt { trunc, value } => {
+            let v = match cx.const_mir_rvalue(value.into()).get_real() {
+                Some(v) => v,
+                None => return cx.intern_value(make_error(mir.ty)),
+            };
+            let v = if trunc { v.trunc() } else { v.round() };
+            cx.intern_value(make_int(
+                mir.ty,
+                BigInt::from_f64(v).unwrap_or_else(BigInt::zero),
+            ))
+        }
+
+        mir::RvalueKind::ResizeReal(value) => match cx.const_mir_rvalue(value.into()).get_real() {
+            Some(v) => cx.intern_value(make_real(mir.ty, v)),
+            None => cx.intern_value(make_error(mir.ty)),
+        },
+
+        mir::RvalueKind::RealToTime(value) => {
+            let v = cx.const_mir_rvalue(value.into()).get_real();
+            match v.and_then(BigRational::from_float) {
+                Some(v) => cx.intern_value(make_time(cx.timescale_of(mir.origin).scale(&v))),
+                None => cx.intern_value(make_error(mir.ty)),
+            }
+        }
+
+        mir::RvalueKind::RealUnaryArith { op, arg } => {
+            let arg = match cx.const_mir_rvalue(arg.into()).get_real() {
+                Some(v) => v,
+                None => return cx.intern_value(make_error(mir.ty)),
+            };
+            cx.intern_value(make_real(mir.ty, const_unary_arith_real(op, arg)))
+        }
+
+        mir::RvalueKind::RealBinaryArith { op, lhs, rhs } => {
+            let lhs_val = cx.const_mir_rvalue(lhs.into());
+            let rhs_val = cx.const_mir_rvalue(rhs.into());
+            match (lhs_val.get_real(), rhs_val.get_real()) {
+                (Some(lhs), Some(rhs)) => {
+                    cx.intern_value(make_real(mir.ty, const_binary_arith_real(op, lhs, rhs)))
+                }
+                _ => cx.intern_value(make_error(mir.ty)),
+            }
+        }
+
+        mir::RvalueKind::RealComp { op, lhs, rhs } => {
+            let lhs_val = cx.const_mir_rvalue(lhs.into());
+            let rhs_val = cx.const_mir_rvalue(rhs.into());
+            match (lhs_val.get_real(), rhs_val.get_real()) {
+                (Some(lhs), Some(rhs)) => cx.intern_value(make_int(
+                    mir.ty,
+                    (const_comp_real(op, lhs, rhs) as usize).into(),
+                )),
+                _ => cx.intern_value(make_error(mir.ty)),
+            }
         }
 
         mir::RvalueKind::CastToBool(value) => {
@@ -549,6 +684,22 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         }
 
         mir::RvalueKind::Assignment { .. }
+        | mir::RvalueKind::Display { .. }
+        | mir::RvalueKind::Intrinsic(..)
+        | mir::RvalueKind::Sampled { .. }
+        | mir::RvalueKind::Call { .. }
+        | mir::RvalueKind::This
+        | mir::RvalueKind::Null
+        | mir::RvalueKind::Property { .. }
+        | mir::RvalueKind::New { .. }
+        | mir::RvalueKind::MethodCall { .. }
+        | mir::RvalueKind::ArrayNew { .. }
+        | mir::RvalueKind::ContainerElem { .. }
+        | mir::RvalueKind::ContainerMethod { .. }
+        | mir::RvalueKind::EnumName { .. }
+        | mir::RvalueKind::StringFormat { .. }
+        | mir::RvalueKind::StringMethod { .. }
+        | mir::RvalueKind::Matches { .. }
         | mir::RvalueKind::Var(_)
         | mir::RvalueKind::Port(_)
         | mir::RvalueKind::IntfSignal(..)
@@ -721,6 +872,42 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
             cx.intern_value(make_string(mir.ty, bytes))
         }
 
+        // Scale an integer by the time unit.
+        mir::RvalueKind::IntToTime(_, value) => match cx.const_mir_rvalue_int(value.into()) {
+            Ok(v) => {
+                let v = BigRational::from_integer(v.clone());
+                cx.intern_value(make_time(cx.timescale_of(mir.origin).scale(&v)))
+            }
+            Err(()) => cx.intern_value(make_error(mir.ty)),
+        },
+
+        // Express a time as a multiple of the time unit.
+        mir::RvalueKind::TimeToInt(value) => match cx.const_mir_rvalue(value.into()).kind {
+            ValueKind::Time(ref v) => {
+                let v = (v / &cx.timescale_of(mir.origin).unit).round();
+                cx.intern_value(make_int(mir.ty, v.to_integer()))
+            }
+            _ => cx.intern_value(make_error(mir.ty)),
+        },
+
+        mir::RvalueKind::StringConcat { repeat, ref values } => {
+            let mut bytes = vec![];
+            for &value in values {
+                match cx.const_mir_rvalue_string(value.into()) {
+                    Ok(v) => bytes.extend(v),
+                    Err(()) => return cx.intern_value(make_error(mir.ty)),
+                }
+            }
+            if let Some(repeat) = repeat {
+                let repeat = match cx.const_mir_rvalue_int(repeat.into()) {
+                    Ok(v) => v.to_usize().unwrap_or(0),
+                    Err(()) => return cx.intern_value(make_error(mir.ty)),
+                };
+                bytes = bytes.repeat(repeat);
+            }
+            cx.intern_value(make_string(mir.ty, bytes))
+        }
+
         mir::RvalueKind::StringComp { op, lhs, rhs, .. } => {
             let lhs_val = cx.const_mir_rvalue(lhs.into());
             let rhs_val = cx.const_mir_rvalue(rhs.into());
@@ -860,6 +1047,33 @@ fn const_reduction_int<'gcx>(
     }
 }
 
+fn const_unary_arith_real(op: mir::RealUnaryArithOp, arg: f64) -> f64 {
+    match op {
+        mir::RealUnaryArithOp::Neg => -arg,
+    }
+}
+
+fn const_binary_arith_real(op: mir::RealBinaryArithOp, lhs: f64, rhs: f64) -> f64 {
+    match op {
+        mir::RealBinaryArithOp::Add => lhs + rhs,
+        mir::RealBinaryArithOp::Sub => lhs - rhs,
+        mir::RealBinaryArithOp::Mul => lhs * rhs,
+        mir::RealBinaryArithOp::Div => lhs / rhs,
+        mir::RealBinaryArithOp::Pow => lhs.powf(rhs),
+    }
+}
+
+fn const_comp_real(op: mir::RealCompOp, lhs: f64, rhs: f64) -> bool {
+    match op {
+        mir::RealCompOp::Eq => lhs == rhs,
+        mir::RealCompOp::Neq => lhs != rhs,
+        mir::RealCompOp::Lt => lhs < rhs,
+        mir::RealCompOp::Leq => lhs <= rhs,
+        mir::RealCompOp::Gt => lhs > rhs,
+        mir::RealCompOp::Geq => lhs >= rhs,
+    }
+}
+
 /// Perform a constant comparison of two string values.
 fn const_comp_string<'gcx>(
     _cx: &impl Context<'gcx>,
@@ -870,6 +1084,10 @@ fn const_comp_string<'gcx>(
     match op {
         mir::StringCompOp::Eq => ((lhs == rhs) as usize).into(),
         mir::StringCompOp::Neq => ((lhs != rhs) as usize).into(),
+        mir::StringCompOp::Lt => ((lhs < rhs) as usize).into(),
+        mir::StringCompOp::Leq => ((lhs <= rhs) as usize).into(),
+        mir::StringCompOp::Gt => ((lhs > rhs) as usize).into(),
+        mir::StringCompOp::Geq => ((lhs >= rhs) as usize).into(),
     }
 }
 
@@ -896,6 +1114,22 @@ pub(crate) fn type_default_value<'a>(cx: &impl Context<'a>, ty: &'a UnpackedType
         });
     }
 
+    // Handle unions, which are stored as a bit vector.
+    if ty.get_union().is_some() {
+        return cx.intern_value(make_int(ty, Zero::zero()));
+    }
+
+    // Handle class handles and dynamically sized containers, which are null
+    // by default.
+    if ty.get_class().is_some() || ty.get_container().is_some() {
+        return cx.intern_value(make_null(ty));
+    }
+
+    // Handle strings, which are empty by default.
+    if ty.is_string() {
+        return cx.intern_value(make_string(ty, vec![]));
+    }
+
     // Handle structs.
     if let Some(strukt) = ty.get_struct() {
         let fields = strukt
@@ -906,6 +1140,11 @@ pub(crate) fn type_default_value<'a>(cx: &impl Context<'a>, ty: &'a UnpackedType
         return cx.intern_value(make_struct(ty, fields));
     }
 
+    // Handle floating-point types.
+    if ty.is_real() {
+        return cx.intern_value(make_real(ty, 0.0));
+    }
+
     // Handle packed base cases.
     if let Some(packed) = ty.get_packed() {
         let packed = packed;
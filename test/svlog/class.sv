@@ -0,0 +1,356 @@
+// RUN: moore %s -e foo -O0
+
+class Transaction;
+    int addr;
+    logic [7:0] data = 8'h42;
+
+    function new(int a, logic [7:0] d = 8'h00);
+        addr = a;
+        this.data = d;
+    endfunction
+
+    function int sum();
+        return addr + data;
+    endfunction
+
+    virtual function int kind();
+        return 1;
+    endfunction
+
+    task show();
+        $display("addr=%0d data=%0d", addr, data);
+    endtask
+endclass
+
+class ReadTransaction extends Transaction;
+    bit write;
+
+    function new(int a);
+        super.new(a, 8'hFF);
+        write = 0;
+    endfunction
+
+    virtual function int kind();
+        return 2 + super.kind();
+    endfunction
+endclass
+
+class Scaled #(int K = 1) extends Transaction;
+    function new(int a);
+        super.new(a);
+    endfunction
+
+    virtual function int kind();
+        return K * 10;
+    endfunction
+endclass
+
+class Box #(int W = 8, type T = int);
+    logic [W-1:0] value;
+    T tag;
+
+    function logic [W-1:0] get();
+        return value;
+    endfunction
+endclass
+
+module foo;
+    Transaction t;
+    ReadTransaction r;
+    Box #(16) b;
+    Box c;
+    Scaled #(7) s;
+    int x;
+    initial begin
+        t = new(1, 8'h10);
+        t.addr = 5;
+        x = t.sum();
+        t.show();
+        r = new(3);
+        t = r;
+        x = t.kind();
+        s = new(4);
+        t = s;
+        x = t.kind();
+        b = new;
+        b.value = 16'hBEEF;
+        x = b.get();
+        c = new();
+        if (c != null) x = c.get();
+        t = null;
+    end
+endmodule
+
+// CHECK: proc %foo.initial.515.0 () -> (i64$ %t, i64$ %r, i64$ %b, i64$ %c, i64$ %s, i32$ %x) {
+// CHECK: %0:
+// CHECK:     %6 = const i32 3
+// CHECK:     %Transaction.obj = call i64 @llhd.obj.new (i32 %6)
+// CHECK:     %7 = const i32 0
+// CHECK:     %8 = call i32* @llhd.obj.field (i64 %Transaction.obj, i32 %7)
+// CHECK:     %9 = const i32 1
+// CHECK:     st i32* %8, %9
+// CHECK:     %10 = const i32 0
+// CHECK:     %11 = const i32 1
+// CHECK:     %12 = call i32* @llhd.obj.field (i64 %Transaction.obj, i32 %11)
+// CHECK:     st i32* %12, %10
+// CHECK:     %13 = const i8 66
+// CHECK:     %14 = const i32 2
+// CHECK:     %15 = call i8* @llhd.obj.field (i64 %Transaction.obj, i32 %14)
+// CHECK:     st i8* %15, %13
+// CHECK:     %16 = const i32 1
+// CHECK:     %new.a = var i32 %16
+// CHECK:     %17 = const i8 16
+// CHECK:     %new.d = var i8 %17
+// CHECK:     %18 = const i32 1
+// CHECK:     %19 = call i32* @llhd.obj.field (i64 %Transaction.obj, i32 %18)
+// CHECK:     %new.a.ld = ld i32* %new.a
+// CHECK:     st i32* %19, %new.a.ld
+// CHECK:     %20 = const i32 2
+// CHECK:     %21 = call i8* @llhd.obj.field (i64 %Transaction.obj, i32 %20)
+// CHECK:     %new.d.ld = ld i8* %new.d
+// CHECK:     st i8* %21, %new.d.ld
+// CHECK:     %22 = const time 0s 1e
+// CHECK:     drv i64$ %t, %Transaction.obj, %22
+// CHECK:     %t.shadow.ld = ld i64* %t.shadow
+// CHECK:     %23 = const i32 1
+// CHECK:     %24 = call i32* @llhd.obj.field (i64 %t.shadow.ld, i32 %23)
+// CHECK:     %25 = const i32 5
+// CHECK:     st i32* %24, %25
+// CHECK:     %26 = const i32 0
+// CHECK:     %sum.ret = var i32 %26
+// CHECK:     %27 = const i32 1
+// CHECK:     %29 = ld i32* %28
+// CHECK:     %30 = const i32 0
+// CHECK:     %31 = const i32 2
+// CHECK:     %33 = ld i8* %32
+// CHECK:     %zext = inss i32 %30, i8 %33, 0, 8
+// CHECK:     %34 = add i32 %29, %zext
+// CHECK:     st i32* %sum.ret, %34
+// CHECK:     br %return
+// CHECK: %return:
+// CHECK:     %35 = ld i32* %sum.ret
+// CHECK:     %36 = const time 0s 1e
+// CHECK:     drv i32$ %x, %35, %36
+// CHECK:     %37 = const i8 0
+// CHECK:     %38 = const i8 97
+// CHECK:     %39 = const i8 100
+// CHECK:     %40 = const i8 100
+// CHECK:     %41 = const i8 114
+// CHECK:     %42 = const i8 61
+// CHECK:     %43 = const i8 37
+// CHECK:     %44 = const i8 48
+// CHECK:     %45 = const i8 100
+// CHECK:     %46 = const i8 32
+// CHECK:     %47 = const i8 100
+// CHECK:     %48 = const i8 97
+// CHECK:     %49 = const i8 116
+// CHECK:     %50 = const i8 97
+// CHECK:     %51 = const i8 61
+// CHECK:     %52 = const i8 37
+// CHECK:     %53 = const i8 48
+// CHECK:     %54 = const i8 100
+// CHECK:     %55 = const i8 10
+// CHECK:     %56 = const i8 0
+// CHECK:     %57 = [i8 %38, %39, %40, %41, %42, %43, %44, %45, %46, %47, %48, %49, %50, %51, %52, %53, %54, %55, %56]
+// CHECK:     %58 = const i32 1
+// CHECK:     %60 = ld i32* %59
+// CHECK:     %61 = const i32 2
+// CHECK:     %63 = ld i8* %62
+// CHECK:     call void @llhd.display (i8 %37, [19 x i8] %57, i32 %60, i8 %63)
+// CHECK:     %64 = const i32 4
+// CHECK:     %ReadTransaction.obj = call i64 @llhd.obj.new (i32 %64)
+// CHECK:     %65 = const i32 0
+// CHECK:     %66 = call i32* @llhd.obj.field (i64 %ReadTransaction.obj, i32 %65)
+// CHECK:     %67 = const i32 2
+// CHECK:     st i32* %66, %67
+// CHECK:     %68 = const i32 0
+// CHECK:     %69 = const i32 1
+// CHECK:     %70 = call i32* @llhd.obj.field (i64 %ReadTransaction.obj, i32 %69)
+// CHECK:     st i32* %70, %68
+// CHECK:     %71 = const i8 66
+// CHECK:     %72 = const i32 2
+// CHECK:     %73 = call i8* @llhd.obj.field (i64 %ReadTransaction.obj, i32 %72)
+// CHECK:     st i8* %73, %71
+// CHECK:     %74 = const i1 0
+// CHECK:     %75 = const i32 3
+// CHECK:     %76 = call i1* @llhd.obj.field (i64 %ReadTransaction.obj, i32 %75)
+// CHECK:     st i1* %76, %74
+// CHECK:     %77 = const i32 3
+// CHECK:     %78 = const i8 255
+// CHECK:     %79 = const i32 1
+// CHECK:     %80 = call i32* @llhd.obj.field (i64 %ReadTransaction.obj, i32 %79)
+// CHECK:     %81 = const i32 2
+// CHECK:     %82 = call i8* @llhd.obj.field (i64 %ReadTransaction.obj, i32 %81)
+// CHECK:     %83 = const i32 3
+// CHECK:     %84 = call i1* @llhd.obj.field (i64 %ReadTransaction.obj, i32 %83)
+// CHECK:     %85 = const i1 0
+// CHECK:     st i1* %84, %85
+// CHECK:     %86 = const time 0s 1e
+// CHECK:     drv i64$ %r, %ReadTransaction.obj, %86
+// CHECK:     %r.shadow.ld = ld i64* %r.shadow
+// CHECK:     %87 = const time 0s 1e
+// CHECK:     drv i64$ %t, %r.shadow.ld, %87
+// CHECK:     %88 = const i32 0
+// CHECK:     %kind.result = var i32 %88
+// CHECK:     %89 = const i32 0
+// CHECK:     %91 = ld i32* %90
+// CHECK:     %92 = const i32 2
+// CHECK:     %93 = eq i32 %91, %92
+// CHECK:     br %93, %dispatch_next, %dispatch_call
+// CHECK: %94:
+// CHECK:     br %return
+// CHECK: %dispatch_exit:
+// CHECK:     %95 = ld i32* %kind.result
+// CHECK:     %96 = const time 0s 1e
+// CHECK:     drv i32$ %x, %95, %96
+// CHECK:     %97 = const i32 3
+// CHECK:     %Scaled.obj = call i64 @llhd.obj.new (i32 %97)
+// CHECK:     %98 = const i32 0
+// CHECK:     %99 = call i32* @llhd.obj.field (i64 %Scaled.obj, i32 %98)
+// CHECK:     %100 = const i32 3
+// CHECK:     st i32* %99, %100
+// CHECK:     %101 = const i32 0
+// CHECK:     %102 = const i32 1
+// CHECK:     %103 = call i32* @llhd.obj.field (i64 %Scaled.obj, i32 %102)
+// CHECK:     st i32* %103, %101
+// CHECK:     %104 = const i8 66
+// CHECK:     %105 = const i32 2
+// CHECK:     %106 = call i8* @llhd.obj.field (i64 %Scaled.obj, i32 %105)
+// CHECK:     st i8* %106, %104
+// CHECK:     %107 = const i32 4
+// CHECK:     %108 = const i8 0
+// CHECK:     %109 = const i32 1
+// CHECK:     %110 = call i32* @llhd.obj.field (i64 %Scaled.obj, i32 %109)
+// CHECK:     %111 = const i32 2
+// CHECK:     %112 = call i8* @llhd.obj.field (i64 %Scaled.obj, i32 %111)
+// CHECK:     %113 = const time 0s 1e
+// CHECK:     drv i64$ %s, %Scaled.obj, %113
+// CHECK:     %s.shadow.ld = ld i64* %s.shadow
+// CHECK:     %114 = const time 0s 1e
+// CHECK:     drv i64$ %t, %s.shadow.ld, %114
+// CHECK:     %115 = const i32 0
+// CHECK:     %116 = const i32 0
+// CHECK:     %118 = ld i32* %117
+// CHECK:     %119 = const i32 2
+// CHECK:     %120 = eq i32 %118, %119
+// CHECK: %dispatch_call:
+// CHECK:     %121 = const i32 0
+// CHECK:     %kind.ret = var i32 %121
+// CHECK:     %122 = const i32 2
+// CHECK:     %123 = const i32 0
+// CHECK:     %124 = const i32 1
+// CHECK: %dispatch_next:
+// CHECK:     %125 = const i32 3
+// CHECK:     %126 = eq i32 %91, %125
+// CHECK:     %128 = add i32 %122, %127
+// CHECK:     st i32* %kind.ret, %128
+// CHECK: %129:
+// CHECK:     %130 = ld i32* %kind.ret
+// CHECK:     st i32* %kind.result, %130
+// CHECK:     br %dispatch_exit
+// CHECK: %131:
+// CHECK:     %132 = const i32 0
+// CHECK:     %133 = const i32 70
+// CHECK:     %134 = const i32 0
+// CHECK:     %135 = const i32 1
+// CHECK:     st i32* %kind.result, %136
+// CHECK:     br %dispatch_exit
+// CHECK: %137:
+// CHECK:     st i32* %kind.result, %138
+// CHECK:     br %dispatch_exit
+// CHECK: %139:
+// CHECK:     %141 = const time 0s 1e
+// CHECK:     drv i32$ %x, %140, %141
+// CHECK:     %142 = const i32 3
+// CHECK:     %Box.obj = call i64 @llhd.obj.new (i32 %142)
+// CHECK:     %143 = const i32 0
+// CHECK:     %144 = call i32* @llhd.obj.field (i64 %Box.obj, i32 %143)
+// CHECK:     %145 = const i32 4
+// CHECK:     st i32* %144, %145
+// CHECK:     %146 = const i16 0
+// CHECK:     %147 = const i32 1
+// CHECK:     %148 = call i16* @llhd.obj.field (i64 %Box.obj, i32 %147)
+// CHECK:     st i16* %148, %146
+// CHECK:     %149 = const i32 0
+// CHECK:     %150 = const i32 2
+// CHECK:     %151 = call i32* @llhd.obj.field (i64 %Box.obj, i32 %150)
+// CHECK:     st i32* %151, %149
+// CHECK:     %152 = const time 0s 1e
+// CHECK:     drv i64$ %b, %Box.obj, %152
+// CHECK:     %b.shadow.ld = ld i64* %b.shadow
+// CHECK:     %153 = const i32 1
+// CHECK:     %154 = call i16* @llhd.obj.field (i64 %b.shadow.ld, i32 %153)
+// CHECK:     %155 = const i16 48879
+// CHECK:     st i16* %154, %155
+// CHECK:     %156 = const i32 0
+// CHECK:     %157 = const i16 0
+// CHECK:     %get.ret = var i16 %157
+// CHECK:     %158 = const i32 1
+// CHECK:     %160 = ld i16* %159
+// CHECK:     st i16* %get.ret, %160
+// CHECK:     %161 = const i32 0
+// CHECK:     %162 = const i32 2
+// CHECK:     %163 = const i32 0
+// CHECK:     %164 = const i32 1
+// CHECK:     %165 = const i32 3
+// CHECK:     %166 = eq i32 %118, %165
+// CHECK:     %168 = add i32 %162, %167
+// CHECK: %169:
+// CHECK: %171:
+// CHECK:     %172 = const i32 0
+// CHECK:     %173 = const i32 70
+// CHECK:     %174 = const i32 0
+// CHECK:     %175 = const i32 1
+// CHECK: %177:
+// CHECK: %179:
+// CHECK:     %180 = ld i16* %get.ret
+// CHECK:     %181 = const time 0s 1e
+// CHECK:     %182 = const i32 3
+// CHECK:     %183 = const i32 0
+// CHECK:     %185 = const i32 5
+// CHECK:     st i32* %184, %185
+// CHECK:     %186 = const i8 0
+// CHECK:     %187 = const i32 1
+// CHECK:     st i8* %188, %186
+// CHECK:     %189 = const i32 0
+// CHECK:     %190 = const i32 2
+// CHECK:     st i32* %191, %189
+// CHECK:     %192 = const time 0s 1e
+// CHECK:     %c.shadow.ld = ld i64* %c.shadow
+// CHECK:     %193 = const i64 0
+// CHECK:     %194 = neq i64 %c.shadow.ld, %193
+// CHECK:     %195 = const i1 0
+// CHECK:     %196 = neq i1 %194, %195
+// CHECK:     br %196, %if_false, %if_true
+// CHECK: %197:
+// CHECK: %if_true:
+// CHECK:     %198 = const i32 0
+// CHECK:     %199 = const i8 0
+// CHECK:     %200 = const i32 1
+// CHECK:     %202 = ld i8* %201
+// CHECK: %if_false:
+// CHECK:     br %if_exit
+// CHECK: %if_exit:
+// CHECK:     %203 = const i64 0
+// CHECK:     %204 = const time 0s 1e
+// CHECK:     drv i64$ %t, %203, %204
+// CHECK:     halt
+// CHECK:     %206 = const time 0s 1e
+// CHECK:     br %if_exit
+// CHECK: %207:
+// CHECK: }
+// CHECK: entity @foo () -> () {
+// CHECK:     %0 = const i64 0
+// CHECK:     %t = sig i64 %0
+// CHECK:     %1 = const i64 0
+// CHECK:     %r = sig i64 %1
+// CHECK:     %2 = const i64 0
+// CHECK:     %b = sig i64 %2
+// CHECK:     %3 = const i64 0
+// CHECK:     %c = sig i64 %3
+// CHECK:     %4 = const i64 0
+// CHECK:     %s = sig i64 %4
+// CHECK:     %5 = const i32 0
+// CHECK:     %x = sig i32 %5
+// CHECK:     inst %foo.initial.515.0 () -> (i64$ %t, i64$ %r, i64$ %b, i64$ %c, i64$ %s, i32$ %x)
+// CHECK: }
@@ -0,0 +1,37 @@
+// RUN: moore %s -e foo
+// FAIL
+
+class Counter;
+    int count;
+    function void incr();
+        count++;
+    endfunction
+endclass
+
+// A class cannot be its own base class.
+class Looped extends Looped;
+endclass
+
+// Nor can it extend itself through another class.
+class PingA extends PingB;
+endclass
+class PingB extends PingA;
+endclass
+
+module foo;
+    PingA p;
+    Counter c;
+    int i;
+    initial begin
+        p = new;
+        c = new;
+        c.decr(); // no such method
+        i.incr(); // not a class handle
+    end
+endmodule
+// CHECK-ERR: error: class `Looped` cannot extend itself
+// CHECK-ERR: error: class `PingA` cannot extend itself
+// CHECK-ERR: = note: `PingB` extends `PingA` here:
+// CHECK-ERR: error: no method `decr` in class `Counter`
+// CHECK-ERR: error: cannot call method `incr` on value of type `int`
+// CHECK-ERR: = note: `i` is not a class handle
@@ -0,0 +1,104 @@
+// RUN: moore %s -e foo -O0
+
+module foo (input logic clk, input logic rst, input logic req, input logic gnt);
+    logic [3:0] count;
+
+    assert property (@(posedge clk) req |-> gnt);
+    assert property (@(posedge clk) disable iff (rst) req |=> gnt);
+    assume property (@(posedge clk) req ##2 gnt |-> ##1 !req);
+    assert property (@(posedge clk) req [*3] |=> gnt) else $error("req held without gnt");
+
+    req_rose: assert property (@(posedge clk) $rose(req) |-> $stable(gnt))
+        $display("request");
+    else
+        $warning("gnt changed");
+    assert property (@(negedge clk) $fell(req) |-> count == $past(count, 2) + 1);
+    assert property (@(posedge clk) ##1 count != 0);
+
+    always_ff @(posedge clk) count <= count + 1;
+endmodule
+
+// CHECK: proc %foo.assert.37.0 (i1$ %clk, i1$ %req, i1$ %gnt) -> (i1$ %violation) {
+// CHECK:     %posedge = and i1 %4, %5
+// CHECK:     %matched = and i1 %2, %8
+// CHECK:     %12 = not i1 %11
+// CHECK:     %failed = and i1 %matched, %12
+// CHECK:     drv i1$ %violation, %failed, %13
+// CHECK:     br %failed, %assert_pass, %assert_fail
+// CHECK: }
+
+// The consequent of `|=>` is checked one tick after the antecedent. Attempts
+// are dropped if `rst` was high in between.
+// CHECK: proc %foo.assert.51.0 (i1$ %clk, i1$ %rst, i1$ %req, i1$ %gnt) -> (i1$ %violation) {
+// CHECK:     %ant.past1 = var i1 %1
+// CHECK:     %valid.past1 = var i1 %1
+// CHECK:     %disable.past1 = var i1 %1
+// CHECK:     %8 = ld i1* %ant.past1
+// CHECK:     st i1* %ant.past1, %7
+// CHECK:     %9 = and i1 %2, %8
+// CHECK:     %17 = or i1 %15, %16
+// CHECK:     st i1* %disable.past1, %15
+// CHECK:     %19 = not i1 %17
+// CHECK:     %20 = and i1 %13, %19
+// CHECK:     %matched = and i1 %20, %9
+// CHECK:     %failed = and i1 %matched, %21
+// CHECK: }
+
+// CHECK: proc %foo.assert.68.0 (i1$ %clk, i1$ %req, i1$ %gnt) -> (i1$ %violation) {
+// CHECK:     %ant.past3 = var i1 %1
+// CHECK:     %valid.past3 = var i1 %1
+// CHECK:     st i1* %ant.past3, %9
+// CHECK:     %11 = and i1 %2, %10
+// CHECK:     %15 = and i1 %11, %14
+// CHECK:     %18 = not i1 %17
+// CHECK:     %matched = and i1 %24, %15
+// CHECK:     %failed = and i1 %matched, %25
+// CHECK: }
+
+// CHECK: proc %foo.assert.85.0 (i1$ %clk, i1$ %req, i1$ %gnt) -> (i1$ %violation) {
+// CHECK:     %16 = and i1 %11, %15
+// CHECK:     %20 = and i1 %16, %19
+// CHECK:     %matched = and i1 %26, %20
+// CHECK:     %failed = and i1 %matched, %27
+// CHECK:     call void @llhd.display (i8 %29, [22 x i8] %52)
+// CHECK: }
+
+// CHECK: proc %foo.assert.113.0 (i1$ %clk, i1$ %req, i1$ %gnt) -> (i1$ %violation) {
+// CHECK:     %sampled.past1 = var i1 %3
+// CHECK:     %8 = ld i1* %sampled.past1
+// CHECK:     %9 = not i1 %8
+// CHECK:     %10 = and i1 %req.prb, %9
+// CHECK:     %15 = eq i1 %gnt.prb, %14
+// CHECK:     %matched = and i1 %2, %13
+// CHECK:     %failed = and i1 %matched, %19
+// CHECK:     call void @llhd.display (i8 %21, [13 x i8] %35)
+// CHECK: %assert_exit:
+// CHECK:     call void @llhd.display (i8 %38, [9 x i8] %48)
+// CHECK: }
+
+// CHECK: proc %foo.assert.135.0 (i1$ %clk, i1$ %req, i4$ %count) -> (i1$ %violation) {
+// CHECK:     %sampled.past2 = var i4 %4
+// CHECK:     %negedge = and i1 %7, %6
+// CHECK:     %10 = and i1 %8, %9
+// CHECK:     %16 = ld i4* %sampled.past2
+// CHECK:     %19 = eq i32 %zext, %18
+// CHECK:     %failed = and i1 %matched, %23
+// CHECK: }
+
+// CHECK: proc %foo.assert.146.0 (i1$ %clk, i4$ %count) -> (i1$ %violation) {
+// CHECK:     %8 = neq i32 %zext, %7
+// CHECK:     %12 = ld i1* %valid.past1
+// CHECK:     %matched = and i1 %12, %2
+// CHECK:     %failed = and i1 %matched, %13
+// CHECK: }
+
+// CHECK: entity @foo (i1$ %clk, i1$ %rst, i1$ %req, i1$ %gnt) -> () {
+// CHECK:     %assert37.violation = sig i1 %1
+// CHECK:     inst %foo.assert.37.0 (i1$ %clk, i1$ %req, i1$ %gnt) -> (i1$ %assert37.violation)
+// CHECK:     inst %foo.assert.51.0 (i1$ %clk, i1$ %rst, i1$ %req, i1$ %gnt) -> (i1$ %assert51.violation)
+// CHECK:     inst %foo.assert.68.0 (i1$ %clk, i1$ %req, i1$ %gnt) -> (i1$ %assert68.violation)
+// CHECK:     inst %foo.assert.85.0 (i1$ %clk, i1$ %req, i1$ %gnt) -> (i1$ %assert85.violation)
+// CHECK:     inst %foo.assert.113.0 (i1$ %clk, i1$ %req, i1$ %gnt) -> (i1$ %assert113.violation)
+// CHECK:     inst %foo.assert.135.0 (i1$ %clk, i1$ %req, i4$ %count) -> (i1$ %assert135.violation)
+// CHECK:     inst %foo.assert.146.0 (i1$ %clk, i4$ %count) -> (i1$ %assert146.violation)
+// CHECK: }
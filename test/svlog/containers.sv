@@ -0,0 +1,89 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    int q[$];
+    int d[];
+    int a[string];
+    logic [7:0] b[int];
+    int x;
+    string k;
+    int i;
+
+    initial begin
+        // Queues
+        q.push_back(1);
+        q.push_back(2);
+        q.push_front(0);
+        q.insert(1, 42);
+        x = q.size();
+        x = q[1];
+        q[0] = 7;
+        x = q.pop_front();
+        x = q.pop_back();
+        q.delete(0);
+        q.delete();
+        q = {};
+
+        // Dynamic arrays
+        d = new[4];
+        d[2] = 3;
+        x = d[2] + d.size();
+        d = new[8](d);
+        d.delete();
+
+        // Associative arrays
+        a["foo"] = 1;
+        a["bar"] = 2;
+        x = a.num();
+        if (a.exists("foo"))
+            x = a["foo"];
+        if (a.first(k)) begin
+            x = a[k];
+            while (a.next(k))
+                x = x + a[k];
+        end
+        a.delete("bar");
+        b[3] = 8'h2A;
+        if (b.last(i)) x = b.prev(i);
+        b.delete();
+    end
+endmodule
+
+// CHECK: proc %foo.initial.572.0 () -> (i64$ %q, i64$ %d, i64$ %a, i64$ %b, i32$ %x, i64$ %k, i32$ %i) {
+// CHECK:     %9 = call i64 @llhd.queue.push_back (i64 %q.shadow.ld, i32 %8)
+// CHECK:     drv i64$ %q, %9, %10
+// CHECK:     %15 = call i64 @llhd.queue.push_front (i64 %q.shadow.ld?2, i32 %14)
+// CHECK:     %19 = call i64 @llhd.queue.insert (i64 %q.shadow.ld?3, i32 %17, i32 %18)
+// CHECK:     %21 = call i32 @llhd.queue.size (i64 %q.shadow.ld?4)
+// CHECK:     drv i32$ %x, %21, %22
+// CHECK:     %24 = call i32 @llhd.queue.get (i64 %q.shadow.ld?5, i32 %23)
+// CHECK:     %27 = call i32* @llhd.queue.elem (i64 %q.shadow.ld?6, i32 %26)
+// CHECK:     %29 = call i32 @llhd.queue.front (i64 %q.shadow.ld?7)
+// CHECK:     %30 = call i64 @llhd.queue.pop_front (i64 %q.shadow.ld?7)
+// CHECK:     %33 = call i32 @llhd.queue.back (i64 %q.shadow.ld?8)
+// CHECK:     %34 = call i64 @llhd.queue.pop_back (i64 %q.shadow.ld?8)
+// CHECK:     %38 = call i64 @llhd.queue.delete (i64 %q.shadow.ld?9, i32 %37)
+// CHECK:     %45 = call i64 @llhd.darr.new (i32 %44)
+// CHECK:     drv i64$ %d, %45, %46
+// CHECK:     %48 = call i32* @llhd.darr.elem (i64 %d.shadow.ld, i32 %47)
+// CHECK:     %51 = call i32 @llhd.darr.get (i64 %d.shadow.ld?1, i32 %50)
+// CHECK:     %52 = call i32 @llhd.darr.size (i64 %d.shadow.ld?2)
+// CHECK:     %58 = call i64 @llhd.darr.delete (i64 %d.shadow.ld?4)
+// CHECK:     %66 = call i64 @llhd.assoc.insert (i64 %a.shadow.ld, i64 %65)
+// CHECK:     drv i64$ %a, %66, %67
+// CHECK:     %68 = call i32* @llhd.assoc.elem (i64 %66, i64 %65)
+// CHECK:     %80 = call i32 @llhd.assoc.size (i64 %a.shadow.ld?2)
+// CHECK:     %88 = call i32 @llhd.assoc.exists (i64 %a.shadow.ld?3, i64 %87)
+// CHECK:     %97 = call i32 @llhd.assoc.get (i64 %a.shadow.ld?4, i64 %96)
+// CHECK:     %99 = call i32 @llhd.assoc.first (i64 %a.shadow.ld?5, i64 %k.shadow.ld)
+// CHECK:     %100 = call i64 @llhd.assoc.first_index (i64 %a.shadow.ld?5, i64 %k.shadow.ld)
+// CHECK:     drv i64$ %k, %100, %101
+// CHECK:     %112 = call i64 @llhd.assoc.delete (i64 %a.shadow.ld?7, i64 %111)
+// CHECK:     %119 = call i32 @llhd.assoc.last (i64 %b.shadow.ld?1, i32 %i.shadow.ld)
+// CHECK:     %120 = call i32 @llhd.assoc.last_index (i64 %b.shadow.ld?1, i32 %i.shadow.ld)
+// CHECK:     %124 = call i32 @llhd.assoc.next (i64 %a.shadow.ld?8, i64 %k.shadow.ld?2)
+// CHECK:     %125 = call i64 @llhd.assoc.next_index (i64 %a.shadow.ld?8, i64 %k.shadow.ld?2)
+// CHECK:     %132 = call i32 @llhd.assoc.prev (i64 %b.shadow.ld?2, i32 %i.shadow.ld?1)
+// CHECK:     %133 = call i32 @llhd.assoc.prev_index (i64 %b.shadow.ld?2, i32 %i.shadow.ld?1)
+// CHECK:     halt
+// CHECK: }
@@ -0,0 +1,24 @@
+// RUN: moore %s -e foo -O0
+
+// Containers have value semantics. Assigning one copies its storage, such
+// that modifying the original does not affect the copy.
+module foo;
+    int q1[$];
+    int q2[$];
+    int d[];
+    int x;
+
+    initial begin
+        q1.push_back(1);
+        q2 = q1;
+        q1[0] = 2;
+        x = q2[0];
+        d = new[4];
+    end
+endmodule
+
+// CHECK:     %6 = call i64 @llhd.queue.copy (i64 %q1.shadow.ld?1)
+// CHECK:     drv i64$ %q2, %6, %7
+// CHECK:     %9 = call i32* @llhd.queue.elem (i64 %q1.shadow.ld?2, i32 %8)
+// CHECK:     %15 = call i64 @llhd.darr.new (i32 %14)
+// CHECK:     drv i64$ %d, %15, %16
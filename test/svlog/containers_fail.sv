@@ -0,0 +1,10 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    int d[];
+    initial begin
+        d.push_back(1); // queue method on a dynamic array
+    end
+endmodule
+// CHECK-ERR: error: no method `push_back` in `int $ []`
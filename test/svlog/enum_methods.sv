@@ -0,0 +1,44 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    typedef enum logic [1:0] { IDLE = 0, BUSY = 2, DONE = 3 } state_t;
+    state_t state;
+    state_t other;
+    int n;
+    int i;
+
+    initial begin
+        state = state.first();
+        other = state.last();
+        n = state.num();
+        state = state.next();
+        state = state.next(2);
+        state = state.prev();
+        state = state.prev(i);
+        $display("state = %s", state.name());
+        $display("%0d states, last is %s", n, other.name());
+    end
+endmodule
+
+// CHECK: proc %foo.initial.200.0 (i32$ %i) -> (i2$ %state, i2$ %other, i32$ %n) {
+// CHECK:     %4 = const i2 0
+// CHECK:     drv i2$ %state, %4, %5
+// CHECK:     %6 = const i2 3
+// CHECK:     drv i2$ %other, %6, %7
+// CHECK:     %8 = const i32 3
+// CHECK:     drv i32$ %n, %8, %9
+// CHECK:     %11 = eq i2 %state.shadow.ld, %10
+// CHECK:     %29 = add i32 %25, %28
+// CHECK:     %31 = smod i32 %29, %30
+// CHECK:     drv i2$ %state, %42, %43
+// CHECK:     drv i2$ %state, %76, %77
+// CHECK:     drv i2$ %state, %110, %111
+// CHECK:     %132 = smod i32 %i.prb, %131
+// CHECK:     %137 = sub i32 %130, %136
+// CHECK:     drv i2$ %state, %151, %152
+// CHECK:     %189 = call i64 @llhd.string.from_bits (i32 %188)
+// CHECK:     call void @llhd.display (i8 %153, [12 x i8] %166, i64 %189)
+// CHECK:     %222 = eq i2 %other.shadow.ld, %221
+// CHECK:     call void @llhd.display (i8 %190, [24 x i8] %215, i32 %n.shadow.ld, i64 %238)
+// CHECK:     halt
+// CHECK: }
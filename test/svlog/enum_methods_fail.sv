@@ -0,0 +1,11 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    typedef enum { A, B } foo_t;
+    foo_t x;
+    initial begin
+        x = x.size(); // no such method
+    end
+endmodule
+// CHECK-ERR: error: no method `size` in `foo_t`
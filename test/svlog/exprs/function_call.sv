@@ -0,0 +1,72 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    int a, b, c;
+    bit [7:0] d;
+
+    function int add(int x, int y = 1);
+        add = x + y;
+    endfunction
+
+    function bit [7:0] swap;
+        input bit [7:0] v;
+        swap = {v[3:0], v[7:4]};
+    endfunction
+
+    function int fact(int n);
+        if (n <= 1)
+            fact = 1;
+        else
+            fact = n * fact(n - 1);
+    endfunction
+
+    function int add_a(int x);
+        int tmp = x;
+        add_a = tmp + a;
+    endfunction
+
+    always_comb begin
+        b = add(a, 2);
+        c = add(.y(3), .x(a)) + add(a);
+        d = swap(d);
+    end
+
+    initial begin
+        a = fact(5);
+        b = add_a(b);
+    end
+endmodule
+
+// CHECK: func %add.46.0 (i32 %x, i32 %y) i32 {
+// CHECK:     %x.var = var i32 %x
+// CHECK:     %y.var = var i32 %y
+// CHECK:     %add.ret = var i32 %1
+// CHECK:     st i32* %add.ret, %2
+// CHECK:     ret i32 %3
+// CHECK: }
+
+// CHECK: func %swap.90.0 (i8 %v) i8 {
+// CHECK:     st i8* %swap.ret, %concat
+// CHECK: }
+
+// CHECK: proc %foo.always_comb.241.0 (i32$ %a) -> (i32$ %b, i32$ %c, i8$ %d) {
+// CHECK:     %4 = call i32 %add.46.0 (i32 %a.prb, i32 %3)
+// CHECK:     drv i32$ %b, %4, %5
+// CHECK:     %10 = add i32 %7, %9
+// CHECK:     %12 = call i8 %swap.90.0 (i8 %d.shadow.ld)
+// CHECK: }
+
+// CHECK: func %fact.140.0 (i32 %n) i32 {
+// CHECK:     %9 = call i32 %fact.140.0 (i32 %8)
+// CHECK:     st i32* %fact.ret, %10
+// CHECK: }
+
+// CHECK: func %add_a.180.0 (i32 %x, i32 %a) i32 {
+// CHECK:     %tmp = var i32 %x.var.ld
+// CHECK:     %2 = add i32 %tmp.ld, %a
+// CHECK: }
+
+// CHECK: proc %foo.initial.274.0 () -> (i32$ %a, i32$ %b) {
+// CHECK:     %4 = call i32 %fact.140.0 (i32 %3)
+// CHECK:     %6 = call i32 %add_a.180.0 (i32 %b.shadow.ld, i32 %a.shadow.ld)
+// CHECK: }
@@ -0,0 +1,12 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    int v0 = $countones(8'b10110001);
+    // CHECK: %0 = const i32 4
+    bit v1 = $onehot(4'b0100);
+    // CHECK: %1 = const i1 1
+    bit v2 = $onehot(4'b0110);
+    // CHECK: %2 = const i1 0
+    bit v3 = $onehot0(4'b0000);
+    // CHECK: %3 = const i1 1
+endmodule
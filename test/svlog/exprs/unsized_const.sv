@@ -0,0 +1,14 @@
+// RUN: moore %s -e foo -O0
+module foo;
+    // Unsized literals fill all bits of their type.
+    bar #('0) i0();
+    bar #('1) i1();
+    bar #('x) i2();
+    // CHECK: %0 = const i8 0
+    // CHECK: %0 = const i8 255
+    // CHECK: %0 = const i8 0
+endmodule
+
+module bar #(parameter logic [7:0] X);
+    logic [7:0] x = X;
+endmodule
@@ -0,0 +1,53 @@
+// RUN: moore %s -e A -O0
+
+module A;
+  B #(0) b0();
+  B #(1) b1();
+  B #(2) b2();
+  B #(7) b7();
+endmodule
+
+module B #(int K);
+    int x;
+    case (K)
+        0: initial x = 10;
+        1, 2: begin : g
+            initial x = 20;
+        end
+        default initial x = 30;
+    endcase
+    case (K + 1)
+        3: initial x = 40;
+    endcase
+endmodule
+
+// K = 0 selects the first item.
+// CHECK: proc %B.param1.initial.67.1 () -> (i32$ %x) {
+// CHECK:     %1 = const i32 10
+// CHECK: entity @B.param1 () -> () {
+// CHECK:     inst %B.param1.initial.67.1 () -> (i32$ %x)
+// CHECK: }
+
+// K = 1 selects the named block.
+// CHECK: proc %B.param2.initial.82.2 () -> (i32$ %x) {
+// CHECK:     %1 = const i32 20
+// CHECK: entity @B.param2 () -> () {
+// CHECK:     inst %B.param2.initial.82.2 () -> (i32$ %x)
+// CHECK: }
+
+// K = 2 selects the named block and the item of the second case.
+// CHECK: proc %B.param3.initial.82.3 () -> (i32$ %x) {
+// CHECK:     %1 = const i32 20
+// CHECK: proc %B.param3.initial.116.3 () -> (i32$ %x) {
+// CHECK:     %1 = const i32 40
+// CHECK: entity @B.param3 () -> () {
+// CHECK:     inst %B.param3.initial.82.3 () -> (i32$ %x)
+// CHECK:     inst %B.param3.initial.116.3 () -> (i32$ %x)
+// CHECK: }
+
+// K = 7 selects the default item.
+// CHECK: proc %B.param4.initial.95.4 () -> (i32$ %x) {
+// CHECK:     %1 = const i32 30
+// CHECK: entity @B.param4 () -> () {
+// CHECK:     inst %B.param4.initial.95.4 () -> (i32$ %x)
+// CHECK: }
@@ -0,0 +1,131 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    logic clk;
+    logic [15:0] d, q;
+    logic [3:0] en;
+
+    // `d` and `q` are sliced into 4 bits per instance, with `u_reg[3]`
+    // connected to `d[15:12]`. `clk` is replicated to all instances.
+    dff #(4) u_reg [3:0] (.clk(clk), .d(d), .q(q));
+
+    // One enable bit per instance.
+    buffer u_buf [0:3] (.a(en), .z());
+endmodule
+
+module dff #(parameter int N = 1) (
+    input logic clk,
+    input logic [N-1:0] d,
+    output logic [N-1:0] q
+);
+    always_ff @(posedge clk) q <= d;
+endmodule
+
+module buffer (input logic a, output logic z);
+    assign z = a;
+endmodule
+
+// CHECK: entity @dff.param1 (i1$ %clk, i4$ %d) -> (i4$ %q) {
+// CHECK:     inst %dff.param1.always_ff.113.1 (i1$ %clk, i4$ %d) -> (i4$ %q)
+// CHECK: }
+// CHECK: entity @buffer.param2 (i1$ %a) -> (i1$ %z) {
+// CHECK: entity @foo () -> () {
+// CHECK:     %0 = const i1 0
+// CHECK:     %clk = sig i1 %0
+// CHECK:     %1 = const i16 0
+// CHECK:     %d = sig i16 %1
+// CHECK:     %2 = const i16 0
+// CHECK:     %q = sig i16 %2
+// CHECK:     %3 = const i4 0
+// CHECK:     %en = sig i4 %3
+// CHECK:     %d.prb = prb i16$ %d
+// CHECK:     %4 = const i32 12
+// CHECK:     %5 = const i16 0
+// CHECK:     %6 = shr i16 %d.prb, i16 %5, i32 %4
+// CHECK:     %7 = exts i4, i16 %6, 0, 4
+// CHECK:     %8 = const i4 0
+// CHECK:     %9 = sig i4 %8
+// CHECK:     %10 = const time 0s 1d
+// CHECK:     drv i4$ %9, %7, %10
+// CHECK:     %11 = const i32 12
+// CHECK:     %12 = const i16 0
+// CHECK:     %13 = sig i16 %12
+// CHECK:     %14 = shr i16$ %q, i16$ %13, i32 %11
+// CHECK:     %15 = exts i4$, i16$ %14, 0, 4
+// CHECK:     inst @dff.param1 (i1$ %clk, i4$ %9) -> (i4$ %15)
+// CHECK:     %16 = const i32 8
+// CHECK:     %17 = const i16 0
+// CHECK:     %19 = exts i4, i16 %18, 0, 4
+// CHECK:     %20 = const i4 0
+// CHECK:     %21 = sig i4 %20
+// CHECK:     %22 = const time 0s 1d
+// CHECK:     drv i4$ %21, %19, %22
+// CHECK:     %23 = const i32 8
+// CHECK:     %24 = const i16 0
+// CHECK:     %25 = sig i16 %24
+// CHECK:     %26 = shr i16$ %q, i16$ %25, i32 %23
+// CHECK:     %27 = exts i4$, i16$ %26, 0, 4
+// CHECK:     inst @dff.param1 (i1$ %clk, i4$ %21) -> (i4$ %27)
+// CHECK:     %28 = const i32 4
+// CHECK:     %29 = const i16 0
+// CHECK:     %31 = exts i4, i16 %30, 0, 4
+// CHECK:     %32 = const i4 0
+// CHECK:     %33 = sig i4 %32
+// CHECK:     %34 = const time 0s 1d
+// CHECK:     drv i4$ %33, %31, %34
+// CHECK:     %35 = const i32 4
+// CHECK:     %36 = const i16 0
+// CHECK:     %37 = sig i16 %36
+// CHECK:     %38 = shr i16$ %q, i16$ %37, i32 %35
+// CHECK:     %39 = exts i4$, i16$ %38, 0, 4
+// CHECK:     inst @dff.param1 (i1$ %clk, i4$ %33) -> (i4$ %39)
+// CHECK:     %40 = const i32 0
+// CHECK:     %41 = const i16 0
+// CHECK:     %43 = exts i4, i16 %42, 0, 4
+// CHECK:     %44 = const i4 0
+// CHECK:     %45 = sig i4 %44
+// CHECK:     %46 = const time 0s 1d
+// CHECK:     drv i4$ %45, %43, %46
+// CHECK:     %47 = const i32 0
+// CHECK:     %48 = const i16 0
+// CHECK:     %49 = sig i16 %48
+// CHECK:     %50 = shr i16$ %q, i16$ %49, i32 %47
+// CHECK:     %51 = exts i4$, i16$ %50, 0, 4
+// CHECK:     inst @dff.param1 (i1$ %clk, i4$ %45) -> (i4$ %51)
+// CHECK:     %en.prb = prb i4$ %en
+// CHECK:     %52 = const i32 3
+// CHECK:     %53 = const i4 0
+// CHECK:     %54 = shr i4 %en.prb, i4 %53, i32 %52
+// CHECK:     %55 = exts i1, i4 %54, 0, 1
+// CHECK:     %56 = const i1 0
+// CHECK:     %57 = sig i1 %56
+// CHECK:     %58 = const time 0s 1d
+// CHECK:     drv i1$ %57, %55, %58
+// CHECK:     %59 = const i1 0
+// CHECK:     %u_buf.z.default = sig i1 %59
+// CHECK:     inst @buffer.param2 (i1$ %57) -> (i1$ %u_buf.z.default)
+// CHECK:     %60 = const i32 2
+// CHECK:     %61 = const i4 0
+// CHECK:     %63 = exts i1, i4 %62, 0, 1
+// CHECK:     %64 = const i1 0
+// CHECK:     %65 = sig i1 %64
+// CHECK:     %66 = const time 0s 1d
+// CHECK:     drv i1$ %65, %63, %66
+// CHECK:     %67 = const i1 0
+// CHECK:     %68 = const i32 1
+// CHECK:     %69 = const i4 0
+// CHECK:     %71 = exts i1, i4 %70, 0, 1
+// CHECK:     %72 = const i1 0
+// CHECK:     %73 = sig i1 %72
+// CHECK:     %74 = const time 0s 1d
+// CHECK:     drv i1$ %73, %71, %74
+// CHECK:     %75 = const i1 0
+// CHECK:     %76 = const i32 0
+// CHECK:     %77 = const i4 0
+// CHECK:     %79 = exts i1, i4 %78, 0, 1
+// CHECK:     %80 = const i1 0
+// CHECK:     %81 = sig i1 %80
+// CHECK:     %82 = const time 0s 1d
+// CHECK:     drv i1$ %81, %79, %82
+// CHECK:     %83 = const i1 0
+// CHECK: }
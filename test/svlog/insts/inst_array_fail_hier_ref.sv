@@ -0,0 +1,17 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    logic [3:0] x;
+    bar u_bar [3:0] ();
+    assign x = u_bar[2].u_baz.y;
+    // CHECK-ERR: error: unsupported: hierarchical reference `u_bar[2].u_baz` to instance `u_baz`
+endmodule
+
+module bar;
+    baz u_baz ();
+endmodule
+
+module baz;
+    logic [3:0] y;
+endmodule
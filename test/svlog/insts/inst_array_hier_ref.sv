@@ -0,0 +1,31 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    logic [3:0] x, z;
+    bar u_bar [3:0] ();
+    bar u_one ();
+    assign x = u_bar[2].y;
+    assign z = u_one.y;
+    initial u_bar[0].y = 9;
+endmodule
+
+module bar;
+    logic [3:0] y = 5;
+endmodule
+
+// CHECK: entity @bar.param1 () -> (i4$ %y.export) {
+// CHECK:     con i4$ %y, %y.export
+// CHECK:     drv i4$ %y.export, %1, %2
+// CHECK: }
+// CHECK: entity @foo () -> () {
+// CHECK:     %u_bar.y = sig [4 x i4] %6
+// CHECK:     %u_one.y = sig i4 %7
+// CHECK:     %13 = extf i4, [4 x i4] %12, 0
+// CHECK:     drv i4$ %x, %13, %8
+// CHECK:     drv i4$ %z, %u_one.y.prb, %14
+// CHECK:     %15 = extf i4$, [4 x i4]$ %u_bar.y, 3
+// CHECK:     inst @bar.param1 () -> (i4$ %15)
+// CHECK:     %18 = extf i4$, [4 x i4]$ %u_bar.y, 0
+// CHECK:     inst @bar.param1 () -> (i4$ %18)
+// CHECK:     inst @bar.param1 () -> (i4$ %u_one.y)
+// CHECK: }
@@ -0,0 +1,27 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    logic [7:0] a [3:0];
+    logic [7:0] z [0:3];
+
+    // Unpacked arrays whose dimensions match the instance array connect one
+    // element to each instance, with `u_buf[3]` connected to `a[3]`.
+    buffer u_buf [3:0] (.a(a), .z(z));
+endmodule
+
+module buffer (input logic [7:0] a, output logic [7:0] z);
+    assign z = a;
+endmodule
+
+// CHECK: entity @foo () -> () {
+// CHECK:     %10 = const i32 3
+// CHECK:     %13 = shr [4 x i8] %a.prb, [4 x i8] %12, i32 %10
+// CHECK:     %18 = const i32 0
+// CHECK:     %22 = shr [4 x i8]$ %z, [4 x i8]$ %21, i32 %18
+// CHECK:     inst @buffer.param1 (i8$ %16) -> (i8$ %23)
+// CHECK:     %52 = const i32 0
+// CHECK:     %55 = shr [4 x i8] %a.prb?3, [4 x i8] %54, i32 %52
+// CHECK:     %60 = const i32 3
+// CHECK:     %64 = shr [4 x i8]$ %z, [4 x i8]$ %63, i32 %60
+// CHECK:     inst @buffer.param1 (i8$ %58) -> (i8$ %65)
+// CHECK: }
@@ -0,0 +1,37 @@
+// RUN: moore %s -e foo -O0
+
+module foo #(parameter int N = 8);
+    logic [N-1:0] a, z;
+
+    // Nested declarations see the parameters of the enclosing module.
+    module inverter (input logic [N-1:0] a, output logic [N-1:0] z);
+        assign z = ~a;
+    endmodule
+
+    interface bus;
+        logic [N-1:0] data;
+    endinterface
+
+    inverter i_inv (.a(a), .z(z));
+    bus i_bus ();
+    assign i_bus.data = z;
+endmodule
+
+// CHECK: entity @foo.inverter.param2 (i8$ %a) -> (i8$ %z) {
+// CHECK:     %0 = const time 0s 1e
+// CHECK:     %a.prb = prb i8$ %a
+// CHECK:     %1 = not i8 %a.prb
+// CHECK:     drv i8$ %z, %1, %0
+// CHECK: }
+// CHECK: entity @foo () -> () {
+// CHECK:     %0 = const i8 0
+// CHECK:     %a = sig i8 %0
+// CHECK:     %1 = const i8 0
+// CHECK:     %z = sig i8 %1
+// CHECK:     %2 = const i8 0
+// CHECK:     %i_bus.data = sig i8 %2
+// CHECK:     %3 = const time 0s 1e
+// CHECK:     %z.prb = prb i8$ %z
+// CHECK:     drv i8$ %i_bus.data, %z.prb, %3
+// CHECK:     inst @foo.inverter.param2 (i8$ %a) -> (i8$ %z)
+// CHECK: }
@@ -0,0 +1,13 @@
+// RUN: moore %s -e bar
+// FAIL
+
+module foo;
+    module helper;
+    endmodule
+    helper i_helper ();
+endmodule
+
+module bar;
+    helper i_helper ();
+endmodule
+// CHECK-ERR: error: `helper` not found
@@ -0,0 +1,17 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    int q[$];
+    int a[string];
+    initial begin
+        q.insert(1); // missing the item
+        q.insert(, 1); // empty argument
+        a.delete("foo", "bar"); // too many keys
+        q.push_back(.item(1)); // named argument
+    end
+endmodule
+// CHECK-ERR: error: `insert` of `int $ [$]` expects 2 argument(s)
+// CHECK-ERR: error: `insert` of `int $ [$]` expects 2 argument(s)
+// CHECK-ERR: error: `delete` of `int $ [string]` expects 0 to 1 argument(s)
+// CHECK-ERR: error: built-in method `push_back` does not accept named arguments
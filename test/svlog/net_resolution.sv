@@ -0,0 +1,546 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    logic [7:0] data_a, data_b;
+    logic oe_a, oe_b;
+
+    // Two tri-state drivers share a bus.
+    wire [7:0] bus;
+    assign bus = oe_a ? data_a : 'z;
+    assign bus = oe_b ? data_b : 8'bz;
+
+    // Wired-AND and wired-OR nets.
+    wand [3:0] all_ready;
+    wor [3:0] any_valid;
+    assign all_ready = data_a[3:0];
+    assign all_ready = data_b[3:0];
+    assign any_valid = data_a[7:4];
+    assign any_valid = data_b[7:4];
+
+    // Pulled nets and supplies.
+    tri0 pulled_low = oe_a ? 1'b1 : 1'bz;
+    tri1 pulled_high;
+    assign pulled_high = oe_b ? 1'bx : 1'bz;
+    supply0 gnd;
+    supply1 vdd;
+
+    // Disjoint slices of a wire do not need resolution.
+    wire [7:0] halves;
+    assign halves[3:0] = data_a[3:0];
+    assign halves[7:4] = {4'bzzzz};
+
+    // Nets declared in a generate loop only see the drivers of their own
+    // iteration.
+    for (genvar i = 0; i < 2; i++) begin : g
+        uwire [3:0] nibble;
+        assign nibble = data_a[i*4 +: 4];
+    end
+
+    // A bidirectional bus between two pads.
+    wire [7:0] pad;
+    pad_cell u_pad_a (.oe(oe_a), .o(data_a), .i(), .pad(pad));
+    pad_cell u_pad_b (.oe(oe_b), .o(data_b), .i(), .pad(pad));
+endmodule
+
+module pad_cell (
+    input logic oe,
+    input logic [7:0] o,
+    output logic [7:0] i,
+    inout wire [7:0] pad
+);
+    assign pad = oe ? o : 'z;
+    assign i = pad;
+endmodule
+
+// CHECK: func %wire.resolve.8.1 (i8 %value0, i8 %unknown0) {i8, i8} {
+// CHECK: %0:
+// CHECK:     %1 = const i8 0
+// CHECK:     %2 = not i8 %unknown0
+// CHECK:     %3 = not i8 %value0
+// CHECK:     %4 = and i8 %3, %2
+// CHECK:     %5 = or i8 %1, %4
+// CHECK:     %6 = and i8 %value0, %2
+// CHECK:     %7 = or i8 %1, %6
+// CHECK:     %8 = and i8 %value0, %unknown0
+// CHECK:     %9 = or i8 %1, %8
+// CHECK:     %10 = or i8 %5, %7
+// CHECK:     %11 = or i8 %10, %9
+// CHECK:     %12 = not i8 %11
+// CHECK:     %13 = and i8 %5, %7
+// CHECK:     %14 = or i8 %9, %13
+// CHECK:     %15 = not i8 %14
+// CHECK:     %16 = and i8 %7, %15
+// CHECK:     %17 = or i8 %16, %14
+// CHECK:     %18 = or i8 %14, %12
+// CHECK:     %19 = {i8 %17, i8 %18}
+// CHECK:     ret {i8, i8} %19
+// CHECK: }
+// CHECK: entity @pad_cell.param1 (i1$ %oe, i8$ %o, i8$ %pad) -> (i8$ %i, i8$ %pad.drv, i8$ %pad.unk) {
+// CHECK:     %0 = const time 0s 1e
+// CHECK:     %1 = const i8 0
+// CHECK:     %2 = not i8 %1
+// CHECK:     %pad.drv0 = sig i8 %1
+// CHECK:     %pad.unk0 = sig i8 %2
+// CHECK:     %oe.prb = prb i1$ %oe
+// CHECK:     %3 = const i1 0
+// CHECK:     %4 = neq i1 %oe.prb, %3
+// CHECK:     %o.prb = prb i8$ %o
+// CHECK:     %5 = const i8 0
+// CHECK:     %6 = const i8 0
+// CHECK:     %7 = const i8 255
+// CHECK:     %8 = [i8 %6, %o.prb]
+// CHECK:     %9 = [i8 %7, %5]
+// CHECK:     %10 = mux [2 x i8] %8, i1 %4
+// CHECK:     %11 = mux [2 x i8] %9, i1 %4
+// CHECK:     drv i8$ %pad.drv0, %10, %0
+// CHECK:     drv i8$ %pad.unk0, %11, %0
+// CHECK:     %12 = const time 0s 1e
+// CHECK:     %pad.prb = prb i8$ %pad
+// CHECK:     drv i8$ %i, %pad.prb, %12
+// CHECK:     %13 = prb i8$ %pad.drv0
+// CHECK:     %14 = prb i8$ %pad.unk0
+// CHECK:     %15 = call {i8, i8} %wire.resolve.8.1 (i8 %13, i8 %14)
+// CHECK:     %pad.resolved = extf i8, {i8, i8} %15, 0
+// CHECK:     %16 = extf i8, {i8, i8} %15, 1
+// CHECK:     %17 = const time 0s 1e
+// CHECK:     drv i8$ %pad.drv, %pad.resolved, %17
+// CHECK:     drv i8$ %pad.unk, %16, %17
+// CHECK: }
+// CHECK: func %wire.resolve.8.2 (i8 %value0, i8 %unknown0, i8 %value1, i8 %unknown1) {i8, i8} {
+// CHECK: %0:
+// CHECK:     %1 = const i8 0
+// CHECK:     %2 = not i8 %unknown0
+// CHECK:     %3 = not i8 %value0
+// CHECK:     %4 = and i8 %3, %2
+// CHECK:     %5 = or i8 %1, %4
+// CHECK:     %6 = and i8 %value0, %2
+// CHECK:     %7 = or i8 %1, %6
+// CHECK:     %8 = and i8 %value0, %unknown0
+// CHECK:     %9 = or i8 %1, %8
+// CHECK:     %10 = not i8 %unknown1
+// CHECK:     %11 = not i8 %value1
+// CHECK:     %12 = and i8 %11, %10
+// CHECK:     %13 = or i8 %5, %12
+// CHECK:     %14 = and i8 %value1, %10
+// CHECK:     %15 = or i8 %7, %14
+// CHECK:     %16 = and i8 %value1, %unknown1
+// CHECK:     %17 = or i8 %9, %16
+// CHECK:     %18 = or i8 %13, %15
+// CHECK:     %19 = or i8 %18, %17
+// CHECK:     %20 = not i8 %19
+// CHECK:     %21 = and i8 %13, %15
+// CHECK:     %22 = or i8 %17, %21
+// CHECK:     %23 = not i8 %22
+// CHECK:     %24 = and i8 %15, %23
+// CHECK:     %25 = or i8 %24, %22
+// CHECK:     %26 = or i8 %22, %20
+// CHECK:     %27 = {i8 %25, i8 %26}
+// CHECK:     ret {i8, i8} %27
+// CHECK: }
+// CHECK: func %wand.resolve.4.2 (i4 %value0, i4 %unknown0, i4 %value1, i4 %unknown1) {i4, i4} {
+// CHECK: %0:
+// CHECK:     %1 = const i4 0
+// CHECK:     %2 = not i4 %unknown0
+// CHECK:     %3 = not i4 %value0
+// CHECK:     %4 = and i4 %3, %2
+// CHECK:     %5 = or i4 %1, %4
+// CHECK:     %6 = and i4 %value0, %2
+// CHECK:     %7 = or i4 %1, %6
+// CHECK:     %8 = and i4 %value0, %unknown0
+// CHECK:     %9 = or i4 %1, %8
+// CHECK:     %10 = not i4 %unknown1
+// CHECK:     %11 = not i4 %value1
+// CHECK:     %12 = and i4 %11, %10
+// CHECK:     %13 = or i4 %5, %12
+// CHECK:     %14 = and i4 %value1, %10
+// CHECK:     %15 = or i4 %7, %14
+// CHECK:     %16 = and i4 %value1, %unknown1
+// CHECK:     %17 = or i4 %9, %16
+// CHECK:     %18 = or i4 %13, %15
+// CHECK:     %19 = or i4 %18, %17
+// CHECK:     %20 = not i4 %19
+// CHECK:     %21 = not i4 %13
+// CHECK:     %22 = and i4 %17, %21
+// CHECK:     %23 = not i4 %22
+// CHECK:     %24 = and i4 %15, %21
+// CHECK:     %25 = and i4 %24, %23
+// CHECK:     %26 = or i4 %25, %22
+// CHECK:     %27 = or i4 %22, %20
+// CHECK:     %28 = {i4 %26, i4 %27}
+// CHECK:     ret {i4, i4} %28
+// CHECK: }
+// CHECK: func %wor.resolve.4.2 (i4 %value0, i4 %unknown0, i4 %value1, i4 %unknown1) {i4, i4} {
+// CHECK: %0:
+// CHECK:     %1 = const i4 0
+// CHECK:     %2 = not i4 %unknown0
+// CHECK:     %3 = not i4 %value0
+// CHECK:     %4 = and i4 %3, %2
+// CHECK:     %5 = or i4 %1, %4
+// CHECK:     %6 = and i4 %value0, %2
+// CHECK:     %7 = or i4 %1, %6
+// CHECK:     %8 = and i4 %value0, %unknown0
+// CHECK:     %9 = or i4 %1, %8
+// CHECK:     %10 = not i4 %unknown1
+// CHECK:     %11 = not i4 %value1
+// CHECK:     %12 = and i4 %11, %10
+// CHECK:     %13 = or i4 %5, %12
+// CHECK:     %14 = and i4 %value1, %10
+// CHECK:     %15 = or i4 %7, %14
+// CHECK:     %16 = and i4 %value1, %unknown1
+// CHECK:     %17 = or i4 %9, %16
+// CHECK:     %18 = or i4 %13, %15
+// CHECK:     %19 = or i4 %18, %17
+// CHECK:     %20 = not i4 %19
+// CHECK:     %21 = not i4 %15
+// CHECK:     %22 = and i4 %17, %21
+// CHECK:     %23 = or i4 %15, %22
+// CHECK:     %24 = or i4 %22, %20
+// CHECK:     %25 = {i4 %23, i4 %24}
+// CHECK:     ret {i4, i4} %25
+// CHECK: }
+// CHECK: func %tri0.resolve.1.1 (i1 %value0, i1 %unknown0) {i1, i1} {
+// CHECK: %0:
+// CHECK:     %1 = const i1 0
+// CHECK:     %2 = not i1 %unknown0
+// CHECK:     %3 = not i1 %value0
+// CHECK:     %4 = and i1 %3, %2
+// CHECK:     %5 = or i1 %1, %4
+// CHECK:     %6 = and i1 %value0, %2
+// CHECK:     %7 = or i1 %1, %6
+// CHECK:     %8 = and i1 %value0, %unknown0
+// CHECK:     %9 = or i1 %1, %8
+// CHECK:     %10 = or i1 %5, %7
+// CHECK:     %11 = or i1 %10, %9
+// CHECK:     %12 = not i1 %11
+// CHECK:     %13 = and i1 %5, %7
+// CHECK:     %14 = or i1 %9, %13
+// CHECK:     %15 = not i1 %14
+// CHECK:     %16 = and i1 %7, %15
+// CHECK:     %17 = or i1 %16, %14
+// CHECK:     %18 = or i1 %14, %1
+// CHECK:     %19 = {i1 %17, i1 %18}
+// CHECK:     ret {i1, i1} %19
+// CHECK: }
+// CHECK: func %tri1.resolve.1.1 (i1 %value0, i1 %unknown0) {i1, i1} {
+// CHECK: %0:
+// CHECK:     %1 = const i1 0
+// CHECK:     %2 = not i1 %unknown0
+// CHECK:     %3 = not i1 %value0
+// CHECK:     %4 = and i1 %3, %2
+// CHECK:     %5 = or i1 %1, %4
+// CHECK:     %6 = and i1 %value0, %2
+// CHECK:     %7 = or i1 %1, %6
+// CHECK:     %8 = and i1 %value0, %unknown0
+// CHECK:     %9 = or i1 %1, %8
+// CHECK:     %10 = or i1 %5, %7
+// CHECK:     %11 = or i1 %10, %9
+// CHECK:     %12 = not i1 %11
+// CHECK:     %13 = and i1 %5, %7
+// CHECK:     %14 = or i1 %9, %13
+// CHECK:     %15 = not i1 %14
+// CHECK:     %16 = and i1 %7, %15
+// CHECK:     %17 = or i1 %16, %12
+// CHECK:     %18 = or i1 %17, %14
+// CHECK:     %19 = or i1 %14, %1
+// CHECK:     %20 = {i1 %18, i1 %19}
+// CHECK:     ret {i1, i1} %20
+// CHECK: }
+// CHECK: func %supply0.resolve.1.0 () {i1, i1} {
+// CHECK: %0:
+// CHECK:     %1 = const i1 0
+// CHECK:     %2 = or i1 %1, %1
+// CHECK:     %3 = or i1 %2, %1
+// CHECK:     %4 = not i1 %3
+// CHECK:     %5 = or i1 %1, %1
+// CHECK:     %6 = or i1 %1, %1
+// CHECK:     %7 = {i1 %5, i1 %6}
+// CHECK:     ret {i1, i1} %7
+// CHECK: }
+// CHECK: func %supply1.resolve.1.0 () {i1, i1} {
+// CHECK: %0:
+// CHECK:     %1 = const i1 0
+// CHECK:     %2 = or i1 %1, %1
+// CHECK:     %3 = or i1 %2, %1
+// CHECK:     %4 = not i1 %3
+// CHECK:     %5 = not i1 %1
+// CHECK:     %6 = or i1 %5, %1
+// CHECK:     %7 = or i1 %1, %1
+// CHECK:     %8 = {i1 %6, i1 %7}
+// CHECK:     ret {i1, i1} %8
+// CHECK: }
+// CHECK: entity @foo () -> () {
+// CHECK:     %0 = const i8 0
+// CHECK:     %data_a = sig i8 %0
+// CHECK:     %1 = const i8 0
+// CHECK:     %data_b = sig i8 %1
+// CHECK:     %2 = const i1 0
+// CHECK:     %oe_a = sig i1 %2
+// CHECK:     %3 = const i1 0
+// CHECK:     %oe_b = sig i1 %3
+// CHECK:     %4 = const i8 0
+// CHECK:     %bus = sig i8 %4
+// CHECK:     %5 = const i8 0
+// CHECK:     %6 = not i8 %5
+// CHECK:     %bus.unk = sig i8 %6
+// CHECK:     %7 = const i4 0
+// CHECK:     %all_ready = sig i4 %7
+// CHECK:     %8 = const i4 0
+// CHECK:     %9 = not i4 %8
+// CHECK:     %all_ready.unk = sig i4 %9
+// CHECK:     %10 = const i4 0
+// CHECK:     %any_valid = sig i4 %10
+// CHECK:     %11 = const i4 0
+// CHECK:     %12 = not i4 %11
+// CHECK:     %any_valid.unk = sig i4 %12
+// CHECK:     %13 = const i1 0
+// CHECK:     %pulled_low = sig i1 %13
+// CHECK:     %14 = const i1 0
+// CHECK:     %15 = not i1 %14
+// CHECK:     %pulled_low.unk = sig i1 %15
+// CHECK:     %16 = const i1 0
+// CHECK:     %17 = not i1 %16
+// CHECK:     %pulled_low.drv0 = sig i1 %16
+// CHECK:     %pulled_low.unk0 = sig i1 %17
+// CHECK:     %oe_a.prb = prb i1$ %oe_a
+// CHECK:     %18 = const i1 0
+// CHECK:     %19 = neq i1 %oe_a.prb, %18
+// CHECK:     %20 = const i1 1
+// CHECK:     %21 = const i1 0
+// CHECK:     %22 = const i1 0
+// CHECK:     %23 = const i1 1
+// CHECK:     %24 = [i1 %22, %20]
+// CHECK:     %25 = [i1 %23, %21]
+// CHECK:     %26 = mux [2 x i1] %24, i1 %19
+// CHECK:     %27 = mux [2 x i1] %25, i1 %19
+// CHECK:     %28 = const time 0s 1e
+// CHECK:     drv i1$ %pulled_low.drv0, %26, %28
+// CHECK:     drv i1$ %pulled_low.unk0, %27, %28
+// CHECK:     %29 = const i1 0
+// CHECK:     %pulled_high = sig i1 %29
+// CHECK:     %30 = const i1 0
+// CHECK:     %31 = not i1 %30
+// CHECK:     %pulled_high.unk = sig i1 %31
+// CHECK:     %32 = const i1 0
+// CHECK:     %gnd = sig i1 %32
+// CHECK:     %33 = const i1 0
+// CHECK:     %34 = not i1 %33
+// CHECK:     %gnd.unk = sig i1 %34
+// CHECK:     %35 = const i1 0
+// CHECK:     %vdd = sig i1 %35
+// CHECK:     %36 = const i1 0
+// CHECK:     %37 = not i1 %36
+// CHECK:     %vdd.unk = sig i1 %37
+// CHECK:     %38 = const i8 0
+// CHECK:     %halves = sig i8 %38
+// CHECK:     %39 = const i8 0
+// CHECK:     %pad = sig i8 %39
+// CHECK:     %40 = const i8 0
+// CHECK:     %41 = not i8 %40
+// CHECK:     %pad.unk = sig i8 %41
+// CHECK:     %42 = const time 0s 1e
+// CHECK:     %43 = const i8 0
+// CHECK:     %44 = not i8 %43
+// CHECK:     %bus.drv0 = sig i8 %43
+// CHECK:     %bus.unk0 = sig i8 %44
+// CHECK:     %45 = const i1 0
+// CHECK:     %data_a.prb = prb i8$ %data_a
+// CHECK:     %47 = const i8 0
+// CHECK:     %48 = const i8 0
+// CHECK:     %49 = const i8 255
+// CHECK:     %50 = [i8 %48, %data_a.prb]
+// CHECK:     %51 = [i8 %49, %47]
+// CHECK:     %52 = mux [2 x i8] %50, i1 %46
+// CHECK:     %53 = mux [2 x i8] %51, i1 %46
+// CHECK:     drv i8$ %bus.drv0, %52, %42
+// CHECK:     drv i8$ %bus.unk0, %53, %42
+// CHECK:     %54 = const time 0s 1e
+// CHECK:     %55 = const i8 0
+// CHECK:     %56 = not i8 %55
+// CHECK:     %bus.drv1 = sig i8 %55
+// CHECK:     %bus.unk1 = sig i8 %56
+// CHECK:     %oe_b.prb = prb i1$ %oe_b
+// CHECK:     %57 = const i1 0
+// CHECK:     %58 = neq i1 %oe_b.prb, %57
+// CHECK:     %data_b.prb = prb i8$ %data_b
+// CHECK:     %59 = const i8 0
+// CHECK:     %60 = const i8 0
+// CHECK:     %61 = const i8 255
+// CHECK:     %62 = [i8 %60, %data_b.prb]
+// CHECK:     %63 = [i8 %61, %59]
+// CHECK:     %64 = mux [2 x i8] %62, i1 %58
+// CHECK:     %65 = mux [2 x i8] %63, i1 %58
+// CHECK:     drv i8$ %bus.drv1, %64, %54
+// CHECK:     drv i8$ %bus.unk1, %65, %54
+// CHECK:     %66 = const time 0s 1e
+// CHECK:     %67 = const i4 0
+// CHECK:     %68 = not i4 %67
+// CHECK:     %all_ready.drv0 = sig i4 %67
+// CHECK:     %all_ready.unk0 = sig i4 %68
+// CHECK:     %69 = const i1 0
+// CHECK:     %70 = const i8 0
+// CHECK:     %72 = exts i4, i8 %71, 0, 4
+// CHECK:     %73 = const i4 0
+// CHECK:     drv i4$ %all_ready.drv0, %72, %66
+// CHECK:     drv i4$ %all_ready.unk0, %73, %66
+// CHECK:     %74 = const time 0s 1e
+// CHECK:     %75 = const i4 0
+// CHECK:     %76 = not i4 %75
+// CHECK:     %all_ready.drv1 = sig i4 %75
+// CHECK:     %all_ready.unk1 = sig i4 %76
+// CHECK:     %77 = const i1 0
+// CHECK:     %78 = const i8 0
+// CHECK:     %80 = exts i4, i8 %79, 0, 4
+// CHECK:     %81 = const i4 0
+// CHECK:     drv i4$ %all_ready.drv1, %80, %74
+// CHECK:     drv i4$ %all_ready.unk1, %81, %74
+// CHECK:     %82 = const time 0s 1e
+// CHECK:     %83 = const i4 0
+// CHECK:     %84 = not i4 %83
+// CHECK:     %any_valid.drv0 = sig i4 %83
+// CHECK:     %any_valid.unk0 = sig i4 %84
+// CHECK:     %85 = const i3 4
+// CHECK:     %86 = const i8 0
+// CHECK:     %88 = exts i4, i8 %87, 0, 4
+// CHECK:     %89 = const i4 0
+// CHECK:     drv i4$ %any_valid.drv0, %88, %82
+// CHECK:     drv i4$ %any_valid.unk0, %89, %82
+// CHECK:     %90 = const time 0s 1e
+// CHECK:     %91 = const i4 0
+// CHECK:     %92 = not i4 %91
+// CHECK:     %any_valid.drv1 = sig i4 %91
+// CHECK:     %any_valid.unk1 = sig i4 %92
+// CHECK:     %93 = const i3 4
+// CHECK:     %94 = const i8 0
+// CHECK:     %96 = exts i4, i8 %95, 0, 4
+// CHECK:     %97 = const i4 0
+// CHECK:     drv i4$ %any_valid.drv1, %96, %90
+// CHECK:     drv i4$ %any_valid.unk1, %97, %90
+// CHECK:     %98 = const time 0s 1e
+// CHECK:     %99 = const i1 0
+// CHECK:     %100 = not i1 %99
+// CHECK:     %pulled_high.drv0 = sig i1 %99
+// CHECK:     %pulled_high.unk0 = sig i1 %100
+// CHECK:     %101 = const i1 0
+// CHECK:     %103 = const i1 1
+// CHECK:     %104 = const i1 1
+// CHECK:     %105 = const i1 0
+// CHECK:     %106 = const i1 1
+// CHECK:     %107 = [i1 %105, %103]
+// CHECK:     %108 = [i1 %106, %104]
+// CHECK:     %109 = mux [2 x i1] %107, i1 %102
+// CHECK:     %110 = mux [2 x i1] %108, i1 %102
+// CHECK:     drv i1$ %pulled_high.drv0, %109, %98
+// CHECK:     drv i1$ %pulled_high.unk0, %110, %98
+// CHECK:     %111 = const time 0s 1e
+// CHECK:     %112 = const i1 0
+// CHECK:     %113 = const i8 0
+// CHECK:     %114 = sig i8 %113
+// CHECK:     %115 = shr i8$ %halves, i8$ %114, i1 %112
+// CHECK:     %116 = exts i4$, i8$ %115, 0, 4
+// CHECK:     %117 = const i1 0
+// CHECK:     %118 = const i8 0
+// CHECK:     %120 = exts i4, i8 %119, 0, 4
+// CHECK:     drv i4$ %116, %120, %111
+// CHECK:     %121 = const time 0s 1e
+// CHECK:     %122 = const i3 4
+// CHECK:     %123 = const i8 0
+// CHECK:     %124 = sig i8 %123
+// CHECK:     %125 = shr i8$ %halves, i8$ %124, i3 %122
+// CHECK:     %126 = exts i4$, i8$ %125, 0, 4
+// CHECK:     %127 = const i4 0
+// CHECK:     drv i4$ %126, %127, %121
+// CHECK:     %128 = const i8 0
+// CHECK:     %u_pad_a.i.default = sig i8 %128
+// CHECK:     %129 = const i8 0
+// CHECK:     %130 = not i8 %129
+// CHECK:     %pad.drv0 = sig i8 %129
+// CHECK:     %pad.unk0 = sig i8 %130
+// CHECK:     inst @pad_cell.param1 (i1$ %oe_a, i8$ %data_a, i8$ %pad) -> (i8$ %u_pad_a.i.default, i8$ %pad.drv0, i8$ %pad.unk0)
+// CHECK:     %131 = const i8 0
+// CHECK:     %u_pad_b.i.default = sig i8 %131
+// CHECK:     %132 = const i8 0
+// CHECK:     %133 = not i8 %132
+// CHECK:     %pad.drv1 = sig i8 %132
+// CHECK:     %pad.unk1 = sig i8 %133
+// CHECK:     inst @pad_cell.param1 (i1$ %oe_b, i8$ %data_b, i8$ %pad) -> (i8$ %u_pad_b.i.default, i8$ %pad.drv1, i8$ %pad.unk1)
+// CHECK:     %134 = const i4 0
+// CHECK:     %nibble = sig i4 %134
+// CHECK:     %135 = const time 0s 1e
+// CHECK:     %136 = const i32 0
+// CHECK:     %137 = const i8 0
+// CHECK:     %139 = exts i4, i8 %138, 0, 4
+// CHECK:     drv i4$ %nibble, %139, %135
+// CHECK:     %140 = const i4 0
+// CHECK:     %141 = const time 0s 1e
+// CHECK:     %142 = const i32 4
+// CHECK:     %143 = const i8 0
+// CHECK:     %145 = exts i4, i8 %144, 0, 4
+// CHECK:     %146 = prb i8$ %bus.drv0
+// CHECK:     %147 = prb i8$ %bus.unk0
+// CHECK:     %148 = prb i8$ %bus.drv1
+// CHECK:     %149 = prb i8$ %bus.unk1
+// CHECK:     %150 = call {i8, i8} %wire.resolve.8.2 (i8 %146, i8 %147, i8 %148, i8 %149)
+// CHECK:     %bus.resolved = extf i8, {i8, i8} %150, 0
+// CHECK:     %151 = extf i8, {i8, i8} %150, 1
+// CHECK:     %152 = const time 0s 1e
+// CHECK:     drv i8$ %bus, %bus.resolved, %152
+// CHECK:     drv i8$ %bus.unk, %151, %152
+// CHECK:     %153 = prb i4$ %all_ready.drv0
+// CHECK:     %154 = prb i4$ %all_ready.unk0
+// CHECK:     %155 = prb i4$ %all_ready.drv1
+// CHECK:     %156 = prb i4$ %all_ready.unk1
+// CHECK:     %157 = call {i4, i4} %wand.resolve.4.2 (i4 %153, i4 %154, i4 %155, i4 %156)
+// CHECK:     %all_ready.resolved = extf i4, {i4, i4} %157, 0
+// CHECK:     %158 = extf i4, {i4, i4} %157, 1
+// CHECK:     %159 = const time 0s 1e
+// CHECK:     drv i4$ %all_ready, %all_ready.resolved, %159
+// CHECK:     drv i4$ %all_ready.unk, %158, %159
+// CHECK:     %160 = prb i4$ %any_valid.drv0
+// CHECK:     %161 = prb i4$ %any_valid.unk0
+// CHECK:     %162 = prb i4$ %any_valid.drv1
+// CHECK:     %163 = prb i4$ %any_valid.unk1
+// CHECK:     %164 = call {i4, i4} %wor.resolve.4.2 (i4 %160, i4 %161, i4 %162, i4 %163)
+// CHECK:     %any_valid.resolved = extf i4, {i4, i4} %164, 0
+// CHECK:     %165 = extf i4, {i4, i4} %164, 1
+// CHECK:     %166 = const time 0s 1e
+// CHECK:     drv i4$ %any_valid, %any_valid.resolved, %166
+// CHECK:     drv i4$ %any_valid.unk, %165, %166
+// CHECK:     %167 = prb i1$ %pulled_low.drv0
+// CHECK:     %168 = prb i1$ %pulled_low.unk0
+// CHECK:     %169 = call {i1, i1} %tri0.resolve.1.1 (i1 %167, i1 %168)
+// CHECK:     %pulled_low.resolved = extf i1, {i1, i1} %169, 0
+// CHECK:     %170 = extf i1, {i1, i1} %169, 1
+// CHECK:     %171 = const time 0s 1e
+// CHECK:     drv i1$ %pulled_low, %pulled_low.resolved, %171
+// CHECK:     drv i1$ %pulled_low.unk, %170, %171
+// CHECK:     %172 = prb i1$ %pulled_high.drv0
+// CHECK:     %173 = prb i1$ %pulled_high.unk0
+// CHECK:     %174 = call {i1, i1} %tri1.resolve.1.1 (i1 %172, i1 %173)
+// CHECK:     %pulled_high.resolved = extf i1, {i1, i1} %174, 0
+// CHECK:     %175 = extf i1, {i1, i1} %174, 1
+// CHECK:     %176 = const time 0s 1e
+// CHECK:     drv i1$ %pulled_high, %pulled_high.resolved, %176
+// CHECK:     drv i1$ %pulled_high.unk, %175, %176
+// CHECK:     %177 = call {i1, i1} %supply0.resolve.1.0 ()
+// CHECK:     %gnd.resolved = extf i1, {i1, i1} %177, 0
+// CHECK:     %178 = extf i1, {i1, i1} %177, 1
+// CHECK:     %179 = const time 0s 1e
+// CHECK:     drv i1$ %gnd, %gnd.resolved, %179
+// CHECK:     drv i1$ %gnd.unk, %178, %179
+// CHECK:     %180 = call {i1, i1} %supply1.resolve.1.0 ()
+// CHECK:     %vdd.resolved = extf i1, {i1, i1} %180, 0
+// CHECK:     %181 = extf i1, {i1, i1} %180, 1
+// CHECK:     %182 = const time 0s 1e
+// CHECK:     drv i1$ %vdd, %vdd.resolved, %182
+// CHECK:     drv i1$ %vdd.unk, %181, %182
+// CHECK:     %183 = prb i8$ %pad.drv0
+// CHECK:     %184 = prb i8$ %pad.unk0
+// CHECK:     %185 = prb i8$ %pad.drv1
+// CHECK:     %186 = prb i8$ %pad.unk1
+// CHECK:     %187 = call {i8, i8} %wire.resolve.8.2 (i8 %183, i8 %184, i8 %185, i8 %186)
+// CHECK:     %pad.resolved = extf i8, {i8, i8} %187, 0
+// CHECK:     %188 = extf i8, {i8, i8} %187, 1
+// CHECK:     %189 = const time 0s 1e
+// CHECK:     drv i8$ %pad, %pad.resolved, %189
+// CHECK:     drv i8$ %pad.unk, %188, %189
+// CHECK: }
@@ -0,0 +1,10 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    logic a, b;
+    uwire x;
+    assign x = a;
+    assign x = b;
+endmodule
+// CHECK-ERR: error: `x` has multiple drivers
@@ -0,0 +1,32 @@
+// RUN: moore %s -e foo -O0
+
+// A resolved net driving another resolved net passes on its `x` and `z` bits.
+module foo;
+    logic oe;
+    logic [3:0] data;
+    wire [3:0] bus;
+    assign bus = oe ? data : 'z;
+    assign bus = 4'b0000;
+    tri1 [3:0] pulled = bus;
+endmodule
+
+// CHECK: entity @foo () -> () {
+// CHECK:     %2 = const i4 0
+// CHECK:     %bus = sig i4 %2
+// CHECK:     %3 = const i4 0
+// CHECK:     %4 = not i4 %3
+// CHECK:     %bus.unk = sig i4 %4
+// CHECK:     %5 = const i4 0
+// CHECK:     %pulled = sig i4 %5
+// CHECK:     %6 = const i4 0
+// CHECK:     %7 = not i4 %6
+// CHECK:     %pulled.unk = sig i4 %7
+// CHECK:     %8 = const i4 0
+// CHECK:     %9 = not i4 %8
+// CHECK:     %pulled.drv0 = sig i4 %8
+// CHECK:     %pulled.unk0 = sig i4 %9
+// CHECK:     %bus.prb = prb i4$ %bus
+// CHECK:     %10 = prb i4$ %bus.unk
+// CHECK:     %11 = const time 0s 1e
+// CHECK:     drv i4$ %pulled.drv0, %bus.prb, %11
+// CHECK:     drv i4$ %pulled.unk0, %10, %11
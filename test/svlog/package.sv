@@ -0,0 +1,147 @@
+// RUN: moore %s -e foo -O0
+
+package pkg_base;
+    typedef struct packed { logic [7:0] lo, hi; } pair_t;
+    typedef enum logic [1:0] { IDLE, BUSY, DONE } state_t;
+    localparam pair_t DEFAULT_PAIR = '{lo: 8'h12, hi: 8'h34};
+
+    function automatic int max(int a, int b);
+        return a > b ? a : b;
+    endfunction
+
+    task automatic clear(output int x);
+        x = 0;
+    endtask
+endpackage
+
+package pkg_util;
+    import pkg_base::*;
+    export pkg_base::*;
+
+    let min(a, b = 0) = a < b ? a : b;
+    int counter = 3;
+    wire [7:0] unused;
+endpackage
+
+module foo;
+    import pkg_util::*;
+    int x, y, z;
+    pkg_base::state_t state;
+    pkg_base::pair_t pair;
+    initial begin
+        x = pkg_base::max(1, 2);
+        y = max(x, 3);
+        z = min(x, y) + min(x);
+        pkg_base::clear(x);
+        state = pkg_base::BUSY;
+        pair = DEFAULT_PAIR;
+        counter = counter + 1;
+    end
+    bar i_bar();
+endmodule
+
+module bar;
+    int y;
+    initial y = pkg_util::counter;
+endmodule
+
+// CHECK: proc %bar.param3.initial.317.3 (i32$ %counter) -> (i32$ %y) {
+// CHECK: %0:
+// CHECK:     %counter.prb = prb i32$ %counter
+// CHECK:     %1 = const time 0s 1e
+// CHECK:     drv i32$ %y, %counter.prb, %1
+// CHECK:     halt
+// CHECK: }
+// CHECK: entity @bar.param3 () -> (i32$ %pkg_util.counter) {
+// CHECK:     %0 = const i32 0
+// CHECK:     %y = sig i32 %0
+// CHECK:     inst %bar.param3.initial.317.3 (i32$ %pkg_util.counter) -> (i32$ %y)
+// CHECK:     %1 = const i32 3
+// CHECK:     %2 = const time 0s
+// CHECK:     drv i32$ %pkg_util.counter, %1, %2
+// CHECK: }
+// CHECK: func %max.72.0 (i32 %a, i32 %b) i32 {
+// CHECK: %0:
+// CHECK:     %a.var = var i32 %a
+// CHECK:     %b.var = var i32 %b
+// CHECK:     %1 = const i32 0
+// CHECK:     %max.ret = var i32 %1
+// CHECK:     %a.var.ld = ld i32* %a.var
+// CHECK:     %b.var.ld = ld i32* %b.var
+// CHECK:     %2 = sgt i32 %a.var.ld, %b.var.ld
+// CHECK:     %3 = const i1 0
+// CHECK:     %4 = neq i1 %2, %3
+// CHECK:     %6 = mux [2 x i32] %5, i1 %4
+// CHECK:     st i32* %max.ret, %6
+// CHECK:     br %return
+// CHECK: %return:
+// CHECK:     %7 = ld i32* %max.ret
+// CHECK:     ret i32 %7
+// CHECK: %8:
+// CHECK:     br %return
+// CHECK: }
+// CHECK: proc %foo.initial.287.0 () -> (i32$ %counter, i32$ %x, i32$ %y, i32$ %z, i2$ %state, {i8, i8}$ %pair) {
+// CHECK: %0:
+// CHECK:     %4 = const i32 1
+// CHECK:     %5 = const i32 2
+// CHECK:     %6 = call i32 %max.72.0 (i32 %4, i32 %5)
+// CHECK:     %7 = const time 0s 1e
+// CHECK:     drv i32$ %x, %6, %7
+// CHECK:     %x.shadow.ld = ld i32* %x.shadow
+// CHECK:     %8 = const i32 3
+// CHECK:     %9 = call i32 %max.72.0 (i32 %x.shadow.ld, i32 %8)
+// CHECK:     %10 = const time 0s 1e
+// CHECK:     drv i32$ %y, %9, %10
+// CHECK:     %y.shadow.ld = ld i32* %y.shadow
+// CHECK:     %12 = const i1 0
+// CHECK:     %13 = neq i1 %11, %12
+// CHECK:     %15 = mux [2 x i32] %14, i1 %13
+// CHECK:     %16 = const i32 0
+// CHECK:     %18 = const i1 0
+// CHECK:     %19 = neq i1 %17, %18
+// CHECK:     %20 = const i32 0
+// CHECK:     %22 = mux [2 x i32] %21, i1 %19
+// CHECK:     %23 = add i32 %15, %22
+// CHECK:     %24 = const time 0s 1e
+// CHECK:     drv i32$ %z, %23, %24
+// CHECK:     %25 = const i32 0
+// CHECK:     %clear.x = var i32 %25
+// CHECK:     %26 = const i32 0
+// CHECK:     st i32* %clear.x, %26
+// CHECK:     %27 = ld i32* %clear.x
+// CHECK:     %28 = const time 0s 1e
+// CHECK:     drv i32$ %x, %27, %28
+// CHECK:     %29 = const i2 1
+// CHECK:     %30 = const time 0s 1e
+// CHECK:     drv i2$ %state, %29, %30
+// CHECK:     %31 = const i8 18
+// CHECK:     %32 = const i8 52
+// CHECK:     %33 = {i8 %31, i8 %32}
+// CHECK:     %34 = const time 0s 1e
+// CHECK:     drv {i8, i8}$ %pair, %33, %34
+// CHECK:     %counter.shadow.ld = ld i32* %counter.shadow
+// CHECK:     %35 = const i32 1
+// CHECK:     %36 = add i32 %counter.shadow.ld, %35
+// CHECK:     %37 = const time 0s 1e
+// CHECK:     drv i32$ %counter, %36, %37
+// CHECK:     halt
+// CHECK: }
+// CHECK: entity @foo () -> (i32$ %pkg_util.counter) {
+// CHECK:     %0 = const i32 0
+// CHECK:     %x = sig i32 %0
+// CHECK:     %1 = const i32 0
+// CHECK:     %y = sig i32 %1
+// CHECK:     %2 = const i32 0
+// CHECK:     %z = sig i32 %2
+// CHECK:     %3 = const i2 0
+// CHECK:     %state = sig i2 %3
+// CHECK:     %4 = const i8 0
+// CHECK:     %5 = const i8 0
+// CHECK:     %6 = {i8 %4, i8 %5}
+// CHECK:     %pair = sig {i8, i8} %6
+// CHECK:     inst @bar.param3 () -> (i32$ %pkg_util.counter)
+// CHECK:     inst %foo.initial.287.0 () -> (i32$ %pkg_util.counter, i32$ %x, i32$ %y, i32$ %z, i2$ %state, {i8, i8}$ %pair)
+// CHECK:     %7 = const i32 3
+// CHECK:     %8 = const time 0s
+// CHECK:     drv i32$ %pkg_util.counter, %7, %8
+// CHECK: }
@@ -0,0 +1,13 @@
+// RUN: moore %s -e foo
+// FAIL
+
+package pkgA; localparam int X = 1; endpackage
+package pkgB; localparam int X = 2; endpackage
+
+module foo;
+    import pkgA::*;
+    import pkgB::*;
+    int a = X;
+endmodule
+// CHECK-ERR: error: `X` is imported from both `pkgA` and `pkgB`
+// CHECK-ERR: = note: `X` was also imported here:
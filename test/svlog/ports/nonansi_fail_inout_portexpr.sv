@@ -0,0 +1,14 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    wire [7:0] a;
+    bar i_bar (.a(a));
+endmodule
+
+module bar (.a({x, y}));
+    inout [3:0] x;
+    input [3:0] y;
+endmodule
+
+// CHECK-ERR: error: inout port `x` cannot be part of a port expression
@@ -0,0 +1,20 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    logic [7:0] a;
+    logic [3:0] c;
+    bar i_bar (.a(a), .c(c));
+endmodule
+
+module bar (.a({hi, lo}), .c({p, q}));
+    input [3:0] hi;
+    input [3:0] lo;
+    output [1:0] p;
+    output [5:0] q;
+    assign p = hi[1:0];
+    assign q = {lo, 2'b0};
+endmodule
+
+// CHECK-ERR: error: `c` of type `logic [3:0]` cannot be assigned a value of type `logic [7:0]`
+// CHECK-ERR: = note: The width of an assigned value cannot be changed implicitly
@@ -0,0 +1,81 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    logic [7:0] a;
+    logic [3:0] b;
+    logic [7:0] c;
+    bar i_bar (.a(a), .b(b), .c(c));
+endmodule
+
+module bar (.a({hi, lo}), .b(v[3:0]), .c({p, q}));
+    input [3:0] hi;
+    input [3:0] lo;
+    input [7:0] v;
+    output [1:0] p;
+    output [5:0] q;
+    assign p = hi[1:0];
+    assign q = {lo, v[1:0]};
+endmodule
+
+// CHECK: entity @bar.param1 (i4$ %hi, i4$ %lo, i8$ %v) -> (i2$ %p, i6$ %q) {
+// CHECK:     %0 = const time 0s 1e
+// CHECK:     %hi.prb = prb i4$ %hi
+// CHECK:     %1 = const i1 0
+// CHECK:     %2 = const i4 0
+// CHECK:     %3 = shr i4 %hi.prb, i4 %2, i1 %1
+// CHECK:     %4 = exts i2, i4 %3, 0, 2
+// CHECK:     drv i2$ %p, %4, %0
+// CHECK:     %5 = const time 0s 1e
+// CHECK:     %6 = const i6 0
+// CHECK:     %v.prb = prb i8$ %v
+// CHECK:     %7 = const i1 0
+// CHECK:     %8 = const i8 0
+// CHECK:     %9 = shr i8 %v.prb, i8 %8, i1 %7
+// CHECK:     %10 = exts i2, i8 %9, 0, 2
+// CHECK:     %11 = inss i6 %6, i2 %10, 0, 2
+// CHECK:     %lo.prb = prb i4$ %lo
+// CHECK:     %concat = inss i6 %11, i4 %lo.prb, 2, 4
+// CHECK:     drv i6$ %q, %concat, %5
+// CHECK: }
+// CHECK: entity @foo () -> () {
+// CHECK:     %0 = const i8 0
+// CHECK:     %a = sig i8 %0
+// CHECK:     %1 = const i4 0
+// CHECK:     %b = sig i4 %1
+// CHECK:     %2 = const i8 0
+// CHECK:     %c = sig i8 %2
+// CHECK:     %3 = const i4 0
+// CHECK:     %hi.slices = sig i4 %3
+// CHECK:     %4 = const time 0s 1e
+// CHECK:     %a.prb = prb i8$ %a
+// CHECK:     %5 = exts i4, i8 %a.prb, 4, 4
+// CHECK:     %6 = exts i4$, i4$ %hi.slices, 0, 4
+// CHECK:     drv i4$ %6, %5, %4
+// CHECK:     %7 = const i4 0
+// CHECK:     %lo.slices = sig i4 %7
+// CHECK:     %8 = const time 0s 1e
+// CHECK:     %10 = exts i4$, i4$ %lo.slices, 0, 4
+// CHECK:     drv i4$ %10, %9, %8
+// CHECK:     %11 = const i8 0
+// CHECK:     %v.slices = sig i8 %11
+// CHECK:     %12 = const time 0s 1e
+// CHECK:     %b.prb = prb i4$ %b
+// CHECK:     %13 = exts i4, i4 %b.prb, 0, 4
+// CHECK:     %14 = exts i4$, i8$ %v.slices, 0, 4
+// CHECK:     drv i4$ %14, %13, %12
+// CHECK:     %15 = const i2 0
+// CHECK:     %p.slices = sig i2 %15
+// CHECK:     %16 = const time 0s 1e
+// CHECK:     %17 = prb i2$ %p.slices
+// CHECK:     %18 = exts i2, i2 %17, 0, 2
+// CHECK:     %19 = exts i2$, i8$ %c, 6, 2
+// CHECK:     drv i2$ %19, %18, %16
+// CHECK:     %20 = const i6 0
+// CHECK:     %q.slices = sig i6 %20
+// CHECK:     %21 = const time 0s 1e
+// CHECK:     %22 = prb i6$ %q.slices
+// CHECK:     %23 = exts i6, i6 %22, 0, 6
+// CHECK:     %24 = exts i6$, i8$ %c, 0, 6
+// CHECK:     drv i6$ %24, %23, %21
+// CHECK:     inst @bar.param1 (i4$ %hi.slices, i4$ %lo.slices, i8$ %v.slices) -> (i2$ %p.slices, i6$ %q.slices)
+// CHECK: }
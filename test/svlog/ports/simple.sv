@@ -14,11 +14,14 @@ module B (
 );
 endmodule
 
-// CHECK: entity @B (i1$ %x) -> (i1$ %y, i1$ %z) {
-// CHECK:     %0 = const i1 0
-// CHECK:     drv i1$ %y, %0, %1
-// CHECK:     %2 = const i1 0
-// CHECK:     drv i1$ %z, %2, %3
+// CHECK: entity @B (i1$ %x, i1$ %z) -> (i1$ %y, i1$ %z.drv, i1$ %z.unk) {
+// CHECK:     %0 = call {i1, i1} %wire.resolve.1.0 ()
+// CHECK:     %z.resolved = extf i1, {i1, i1} %0, 0
+// CHECK:     %1 = extf i1, {i1, i1} %0, 1
+// CHECK:     %2 = const time 0s 1e
+// CHECK:     drv i1$ %z.drv, %z.resolved, %2
+// CHECK:     drv i1$ %z.unk, %1, %2
+// CHECK:     drv i1$ %y, %3, %4
 // CHECK: }
 
 
@@ -0,0 +1,193 @@
+// RUN: moore %s -e top -O0
+
+module top;
+    logic clk = 0;
+    logic [7:0] q;
+    always #5 clk = ~clk;
+    always_ff @(posedge clk) q <= q + 1;
+    test #(4) i_test (.clk(clk), .q(q));
+    test #(8) i_test_late (.clk(clk), .q(q));
+endmodule
+
+// The initial procedure runs in the reactive region, so it observes `q` after
+// the nonblocking assignment on the clock edge has taken effect. Once the
+// initial procedures of all programs complete, the simulation finishes
+// implicitly.
+program test #(int N = 1) (input logic clk, input logic [7:0] q);
+    initial begin
+        repeat (N) @(posedge clk);
+        $display("q = %d", q);
+    end
+endprogram : test
+
+// CHECK: proc %test.param1.initial.130.1 (i1$ %clk, i8$ %q) -> (i1$ %done) {
+// CHECK: %0:
+// CHECK:     %1 = const time 0s 1d
+// CHECK:     wait %reactive for %1
+// CHECK: %reactive:
+// CHECK:     %2 = const i32 4
+// CHECK:     %loop_count = var i32 %2
+// CHECK:     br %loop_body
+// CHECK: %loop_body:
+// CHECK:     %3 = ld i32* %loop_count
+// CHECK:     %4 = const i32 0
+// CHECK:     %5 = neq i32 %3, %4
+// CHECK:     br %5, %loop_exit, %loop_continue
+// CHECK: %loop_exit:
+// CHECK:     %6 = const i8 0
+// CHECK:     %7 = const i8 113
+// CHECK:     %8 = const i8 32
+// CHECK:     %9 = const i8 61
+// CHECK:     %10 = const i8 32
+// CHECK:     %11 = const i8 37
+// CHECK:     %12 = const i8 100
+// CHECK:     %13 = const i8 10
+// CHECK:     %14 = const i8 0
+// CHECK:     %15 = [i8 %7, %8, %9, %10, %11, %12, %13, %14]
+// CHECK:     %q.prb = prb i8$ %q
+// CHECK:     call void @llhd.display (i8 %6, [8 x i8] %15, i8 %q.prb)
+// CHECK:     %16 = const i1 1
+// CHECK:     %17 = const time 0s 1e
+// CHECK:     drv i1$ %done, %16, %17
+// CHECK:     halt
+// CHECK: %loop_continue:
+// CHECK:     br %init
+// CHECK: %init:
+// CHECK:     %clk.prb = prb i1$ %clk
+// CHECK:     wait %check, %clk
+// CHECK: %check:
+// CHECK:     %18 = const i1 0
+// CHECK:     %19 = eq i1 %clk.prb, %18
+// CHECK:     %posedge = and i1 %19, %20
+// CHECK:     br %posedge, %init, %event
+// CHECK: %event:
+// CHECK:     %21 = const time 0s 1d
+// CHECK:     %22 = ld i32* %loop_count
+// CHECK:     %23 = const i32 1
+// CHECK:     %24 = sub i32 %22, %23
+// CHECK:     st i32* %loop_count, %24
+// CHECK:     br %loop_body
+// CHECK: }
+// CHECK: entity @test.param1 (i1$ %clk, i8$ %q) -> (i1$ %program.done) {
+// CHECK:     %0 = const i1 0
+// CHECK:     %initial130.done = sig i1 %0
+// CHECK:     inst %test.param1.initial.130.1 (i1$ %clk, i8$ %q) -> (i1$ %initial130.done)
+// CHECK:     %1 = prb i1$ %initial130.done
+// CHECK:     %2 = const time 0s 1e
+// CHECK:     drv i1$ %program.done, %1, %2
+// CHECK: }
+// CHECK: proc %test.param2.initial.130.2 (i1$ %clk, i8$ %q) -> (i1$ %done) {
+// CHECK: %0:
+// CHECK:     %1 = const time 0s 1d
+// CHECK:     wait %reactive for %1
+// CHECK: %reactive:
+// CHECK:     %2 = const i32 8
+// CHECK:     %loop_count = var i32 %2
+// CHECK:     br %loop_body
+// CHECK: %loop_body:
+// CHECK:     %3 = ld i32* %loop_count
+// CHECK:     %4 = const i32 0
+// CHECK:     %5 = neq i32 %3, %4
+// CHECK:     br %5, %loop_exit, %loop_continue
+// CHECK: %loop_exit:
+// CHECK:     %6 = const i8 0
+// CHECK:     %7 = const i8 113
+// CHECK:     %8 = const i8 32
+// CHECK:     %9 = const i8 61
+// CHECK:     %10 = const i8 32
+// CHECK:     %11 = const i8 37
+// CHECK:     %12 = const i8 100
+// CHECK:     %13 = const i8 10
+// CHECK:     %14 = const i8 0
+// CHECK:     %15 = [i8 %7, %8, %9, %10, %11, %12, %13, %14]
+// CHECK:     %q.prb = prb i8$ %q
+// CHECK:     call void @llhd.display (i8 %6, [8 x i8] %15, i8 %q.prb)
+// CHECK:     %16 = const i1 1
+// CHECK:     %17 = const time 0s 1e
+// CHECK:     drv i1$ %done, %16, %17
+// CHECK:     halt
+// CHECK: %loop_continue:
+// CHECK:     br %init
+// CHECK: %init:
+// CHECK:     %clk.prb = prb i1$ %clk
+// CHECK:     wait %check, %clk
+// CHECK: %check:
+// CHECK:     %18 = const i1 0
+// CHECK:     %19 = eq i1 %clk.prb, %18
+// CHECK:     %posedge = and i1 %19, %20
+// CHECK:     br %posedge, %init, %event
+// CHECK: %event:
+// CHECK:     %21 = const time 0s 1d
+// CHECK:     %22 = ld i32* %loop_count
+// CHECK:     %23 = const i32 1
+// CHECK:     %24 = sub i32 %22, %23
+// CHECK:     st i32* %loop_count, %24
+// CHECK:     br %loop_body
+// CHECK: }
+// CHECK: entity @test.param2 (i1$ %clk, i8$ %q) -> (i1$ %program.done) {
+// CHECK:     %0 = const i1 0
+// CHECK:     %initial130.done = sig i1 %0
+// CHECK:     inst %test.param2.initial.130.2 (i1$ %clk, i8$ %q) -> (i1$ %initial130.done)
+// CHECK:     %1 = prb i1$ %initial130.done
+// CHECK:     %2 = const time 0s 1e
+// CHECK:     drv i1$ %program.done, %1, %2
+// CHECK: }
+// CHECK: proc %top.always.28.0 () -> (i1$ %clk) {
+// CHECK: %0:
+// CHECK:     %2 = const time 5ns
+// CHECK:     wait %3 for %2
+// CHECK: %3:
+// CHECK:     %clk.shadow.ld = ld i1* %clk.shadow
+// CHECK:     %5 = not i1 %clk.shadow.ld
+// CHECK:     %6 = const time 0s 1e
+// CHECK:     drv i1$ %clk, %5, %6
+// CHECK:     br %0
+// CHECK: }
+// CHECK: proc %top.always_ff.48.0 (i1$ %clk) -> (i8$ %q) {
+// CHECK: %0:
+// CHECK:     br %init
+// CHECK: %init:
+// CHECK:     %clk.prb = prb i1$ %clk
+// CHECK:     wait %check, %clk
+// CHECK: %check:
+// CHECK:     %3 = const i1 0
+// CHECK:     %4 = eq i1 %clk.prb, %3
+// CHECK:     %posedge = and i1 %4, %5
+// CHECK:     br %posedge, %init, %event
+// CHECK: %event:
+// CHECK:     %6 = const time 0s 1d
+// CHECK:     %7 = const i32 0
+// CHECK:     %q.shadow.ld = ld i8* %q.shadow
+// CHECK:     %zext = inss i32 %7, i8 %q.shadow.ld, 0, 8
+// CHECK:     %8 = const i32 1
+// CHECK:     %9 = add i32 %zext, %8
+// CHECK:     %10 = exts i8, i32 %9, 0, 8
+// CHECK:     drv i8$ %q, %10, %6
+// CHECK:     br %0
+// CHECK: }
+// CHECK: %0:
+// CHECK:     br %check
+// CHECK: %check:
+// CHECK:     %1 = prb i1$ %done
+// CHECK:     %3 = and i1 %1, %2
+// CHECK:     br %3, %idle, %finish
+// CHECK: %idle:
+// CHECK: %finish:
+// CHECK:     call void @llhd.finish ()
+// CHECK:     halt
+// CHECK: }
+// CHECK: entity @top () -> () {
+// CHECK:     %0 = const i1 0
+// CHECK:     %clk = sig i1 %0
+// CHECK:     %1 = const i8 0
+// CHECK:     %q = sig i8 %1
+// CHECK:     %2 = const i1 0
+// CHECK:     %i_test.done = sig i1 %2
+// CHECK:     inst @test.param1 (i1$ %clk, i8$ %q) -> (i1$ %i_test.done)
+// CHECK:     %3 = const i1 0
+// CHECK:     %i_test_late.done = sig i1 %3
+// CHECK:     inst @test.param2 (i1$ %clk, i8$ %q) -> (i1$ %i_test_late.done)
+// CHECK:     inst %top.always.28.0 () -> (i1$ %clk)
+// CHECK:     inst %top.always_ff.48.0 (i1$ %clk) -> (i8$ %q)
+// CHECK:     inst %top.finish (i1$ %i_test.done, i1$ %i_test_late.done) -> ()
+// CHECK: }
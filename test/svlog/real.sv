@@ -0,0 +1,105 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    real r;
+    shortreal s;
+    realtime t;
+    int i;
+    bit [63:0] b;
+    initial begin
+        r = 1.5;
+        t = 1e-9;
+        s = 2.5E3;
+        s = r * 2.0;
+        t = r + s;
+        i = r;          // rounds to 2
+        r = i / 4.0;
+        r = -r;
+        r++;
+        if (r > 0.25 && r != s)
+            $display("r = %f, s = %.3f, t = %g", r, s, t);
+        i = $rtoi(2.75);
+        r = $itor(i);
+        b = $realtobits(r);
+        r = $bitstoreal(b);
+        #2.5;
+        $display("%e", r);
+    end
+endmodule
+
+// CHECK: proc %foo.initial.246.0 () -> (i64$ %r, i32$ %s, i64$ %t, i32$ %i, i64$ %b) {
+// CHECK:     %6 = const i64 4609434218613702656
+// CHECK:     %7 = const time 0s 1e
+// CHECK:     drv i64$ %r, %6, %7
+// CHECK:     %8 = const i64 4472406533629990549
+// CHECK:     %9 = const time 0s 1e
+// CHECK:     drv i64$ %t, %8, %9
+// CHECK:     %10 = const i32 1159479296
+// CHECK:     %11 = const time 0s 1e
+// CHECK:     drv i32$ %s, %10, %11
+// CHECK:     %r.shadow.ld = ld i64* %r.shadow
+// CHECK:     %12 = const i64 4611686018427387904
+// CHECK:     %13 = call i64 @llhd.real.mul (i64 %r.shadow.ld, i64 %12)
+// CHECK:     %14 = call i32 @llhd.real.resize (i64 %13)
+// CHECK:     %15 = const time 0s 1e
+// CHECK:     drv i32$ %s, %14, %15
+// CHECK:     %s.shadow.ld = ld i32* %s.shadow
+// CHECK:     %16 = call i64 @llhd.real.resize (i32 %s.shadow.ld)
+// CHECK:     %18 = const time 0s 1e
+// CHECK:     drv i64$ %t, %17, %18
+// CHECK:     %20 = const time 0s 1e
+// CHECK:     drv i32$ %i, %19, %20
+// CHECK:     %i.shadow.ld = ld i32* %i.shadow
+// CHECK:     %21 = call i64 @llhd.real.from_sint (i32 %i.shadow.ld)
+// CHECK:     %22 = const i64 4616189618054758400
+// CHECK:     %23 = call i64 @llhd.real.div (i64 %21, i64 %22)
+// CHECK:     %24 = const time 0s 1e
+// CHECK:     drv i64$ %r, %23, %24
+// CHECK:     %25 = const i64 9223372036854775808
+// CHECK:     %27 = const time 0s 1e
+// CHECK:     drv i64$ %r, %26, %27
+// CHECK:     %28 = const i64 4607182418800017408
+// CHECK:     %30 = const time 0s 1e
+// CHECK:     drv i64$ %r, %29, %30
+// CHECK:     %31 = const i64 4598175219545276416
+// CHECK:     %33 = const i1 0
+// CHECK:     %34 = neq i1 %32, %33
+// CHECK:     %37 = const i1 0
+// CHECK:     %38 = neq i1 %36, %37
+// CHECK:     %39 = and i1 %34, %38
+// CHECK:     %40 = const i1 0
+// CHECK:     %41 = neq i1 %39, %40
+// CHECK:     br %41, %if_false, %if_true
+// CHECK: %if_true:
+// CHECK:     %t.shadow.ld = ld i64* %t.shadow
+// CHECK:     br %if_exit
+// CHECK: %if_false:
+// CHECK:     br %if_exit
+// CHECK: %if_exit:
+// CHECK:     %70 = const i32 2
+// CHECK:     %71 = const time 0s 1e
+// CHECK:     drv i32$ %i, %70, %71
+// CHECK:     %73 = const time 0s 1e
+// CHECK:     drv i64$ %r, %72, %73
+// CHECK:     %74 = const time 0s 1e
+// CHECK:     %b.shadow.ld = ld i64* %b.shadow
+// CHECK:     %75 = const time 0s 1e
+// CHECK:     drv i64$ %r, %b.shadow.ld, %75
+// CHECK:     %76 = const time 3ns
+// CHECK:     wait %77 for %76
+// CHECK: %77:
+// CHECK:     halt
+// CHECK: }
+// CHECK: entity @foo () -> () {
+// CHECK:     %0 = const i64 0
+// CHECK:     %r = sig i64 %0
+// CHECK:     %1 = const i32 0
+// CHECK:     %s = sig i32 %1
+// CHECK:     %2 = const i64 0
+// CHECK:     %t = sig i64 %2
+// CHECK:     %3 = const i32 0
+// CHECK:     %i = sig i32 %3
+// CHECK:     %4 = const i64 0
+// CHECK:     %b = sig i64 %4
+// CHECK:     inst %foo.initial.246.0 () -> (i64$ %r, i32$ %s, i64$ %t, i32$ %i, i64$ %b)
+// CHECK: }
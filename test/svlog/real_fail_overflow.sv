@@ -0,0 +1,12 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    real r;
+    initial begin
+        r = 1e-2147483648;
+        r = 1.5e999999999;
+    end
+endmodule
+
+// CHECK-ERR: error: real literal `1.5e999999999` is out of range
@@ -0,0 +1,15 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    int x;
+    initial begin
+        assert #0 (x == 0) else begin
+            $error("x is %0d", x);
+        end
+        cover final (x == 0) x++;
+    end
+endmodule
+
+// CHECK-ERR: error: action block of deferred assertion must be a single subroutine call
+// CHECK-ERR: error: action block of deferred assertion must be a single subroutine call
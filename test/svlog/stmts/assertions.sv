@@ -0,0 +1,59 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    int x, y;
+    initial begin
+        int i;
+        x = 42;
+        i = 3;
+        assert (x != 0) else $error("x is zero");
+        assert (x > 10) $display("x is large"); else $warning("x is small");
+        cover (x == 42) $info("covered");
+        check_x: assert #0 (x == 42) else $error("deferred %0d", i);
+        final_x: assume final (x == 42);
+        assert (x == 42);
+        assume (x < 100);
+    end
+    always_comb begin
+        y = x + 1;
+        check_y: assert (y > x);
+    end
+endmodule
+
+// Immediate assertions branch within the procedure.
+// CHECK: proc %foo.initial.122.0 () -> (i32$ %x, i32$ %check_x.ctl, i32$ %check_x.i, i1$ %check_x.cond, i32$ %final_x.ctl, i1$ %final_x.cond) {
+// CHECK:     br %11, %assert_fail, %assert_pass
+// CHECK:     call void @llhd.display (i8 %12, [11 x i8] %24)
+// CHECK:     br %28, %assert_fail?1, %assert_pass?1
+
+// Deferred assertions pass the outcome and the captured locals on.
+// CHECK:     drv i1$ %check_x.cond, %75, %76
+// CHECK:     drv i32$ %check_x.i, %77, %78
+// CHECK:     drv i32$ %check_x.ctl, %82, %83
+// CHECK:     drv i1$ %final_x.cond, %87, %88
+// CHECK:     drv i32$ %final_x.ctl, %92, %93
+// CHECK:     br %97, %assert_fail?3, %assert_pass?3
+// CHECK:     halt
+
+// CHECK: proc %foo.always_comb.148.0 (i32$ %x) -> (i32$ %y) {
+// CHECK:     br %8, %assert_fail, %assert_pass
+
+// The action blocks run a delta cycle later, unless the assertion executes
+// again in the meantime.
+// CHECK: proc %foo.assert.99.0 (i32$ %check_x.ctl, i1$ %check_x.cond, i32$ %check_x.i) -> () {
+// CHECK:     wait %idle, %check_x.ctl
+// CHECK:     %5 = const time 0s 1d
+// CHECK:     wait %check for %5, %check_x.ctl
+// CHECK:     br %8, %report, %idle
+// CHECK:     %9 = prb i32$ %check_x.i
+// CHECK:     %10 = prb i1$ %check_x.cond
+// CHECK:     br %10, %assert_fail, %assert_pass
+// CHECK:     call void @llhd.display (i8 %11, [14 x i8] %26, i32 %i.ld)
+
+// CHECK: proc %foo.assert.106.0 (i32$ %final_x.ctl, i1$ %final_x.cond) -> () {
+// CHECK:     wait %check for %5, %final_x.ctl
+// CHECK:     %9 = prb i1$ %final_x.cond
+// CHECK:     br %9, %assert_fail, %assert_pass
+
+// CHECK:     inst %foo.assert.99.0 (i32$ %check_x.ctl, i1$ %check_x.cond, i32$ %check_x.i) -> ()
+// CHECK:     inst %foo.assert.106.0 (i32$ %final_x.ctl, i1$ %final_x.cond) -> ()
@@ -0,0 +1,45 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    bit [7:0] a;
+    int b;
+    time t;
+
+    initial begin
+        $display;
+        $display("hello world");
+        $display("a = %d, b = %0h, a = %b", a, b, a);
+        $write("%s in %m at %t\n", "message", t);
+        $display("100%% of ", a, " and ", b);
+        $info("info %d", a);
+        $warning("warning");
+        $error("error %h", b);
+        $fatal(1, "fatal");
+        $fatal;
+        begin : blk
+            $display("%m:\x41\102");
+        end
+    end
+endmodule
+
+// CHECK: proc %foo.initial.142.0 (i8$ %a, i32$ %b, time$ %t) -> () {
+// CHECK:     call void @llhd.display (i8 %1, [2 x i8] %4)
+// CHECK:     call void @llhd.display (i8 %5, [13 x i8] %19)
+// `%m` is replaced by the name of the module:
+// CHECK:     %54 = const i8 102
+// CHECK:     %55 = const i8 111
+// CHECK:     %56 = const i8 111
+// CHECK:     call void @llhd.display (i8 %47, [17 x i8] %65, i56 %66, time %t.prb)
+// CHECK:     call void @llhd.display (i8 %100, [9 x i8] %110)
+// CHECK:     call void @llhd.display (i8 %123, [7 x i8] %131)
+// CHECK:     call void @llhd.display (i8 %132, [2 x i8] %135)
+// `%m` in a named block, followed by the escaped `A` and `B`:
+// CHECK:     %140 = const i8 46
+// CHECK:     %141 = const i8 98
+// CHECK:     %142 = const i8 108
+// CHECK:     %143 = const i8 107
+// CHECK:     %144 = const i8 58
+// CHECK:     %145 = const i8 65
+// CHECK:     %146 = const i8 66
+// CHECK:     call void @llhd.display (i8 %136, [12 x i8] %149)
+// CHECK: }
@@ -0,0 +1,27 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    int i;
+    real r;
+    realtime t;
+
+    initial begin
+        $display("%f %d", i, r);
+        $display(r);
+        $display("%t", t);
+    end
+endmodule
+
+// Integral values are converted for `%f`, and reals are rounded for `%d`:
+// CHECK:     %10 = call i64 @llhd.real.from_sint (i32 %i.prb)
+// CHECK:     %11 = call i64 @llhd.real.round (i64 %r.prb)
+// CHECK:     call void @llhd.display (i8 %1, [7 x i8] %9, i64 %10, i64 %11)
+// Reals without a format string are formatted as if by `%g`:
+// CHECK:     %13 = const i8 37
+// CHECK:     %14 = const i8 103
+// CHECK:     call void @llhd.display (i8 %12, [4 x i8] %17, i64 %r.prb?1)
+// Reals are scaled by the time unit for `%t`:
+// CHECK:     %24 = const time 1ns
+// CHECK:     %25 = const time 1ns
+// CHECK:     %26 = call time @llhd.time.from_real (i64 %t.prb, time %24, time %25)
+// CHECK:     call void @llhd.display (i8 %18, [4 x i8] %23, time %26)
@@ -0,0 +1,49 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    int sum;
+    bit [7:0] x;
+    int a [4];
+    logic [3:0] b [2:5];
+    int m [3:0][0:1];
+
+    initial begin
+        foreach (a[i]) a[i] = i;
+        foreach (b[i, j]) b[i][j] = 1;
+        foreach (m[i, j]) begin
+            if (i == 2) break;
+            sum += i * j;
+        end
+        foreach (m[, j]) sum += j;
+        foreach (x[i]) begin
+            if (i == 3) continue;
+            x[i] = ~x[i];
+        end
+    end
+endmodule
+
+// CHECK: proc %foo.initial.231.0 () -> (i32$ %sum, i8$ %x, [4 x i32]$ %a, [4 x i4]$ %b) {
+// CHECK:     %i = var i32 %2
+// CHECK:     %4 = const i32 4
+// CHECK:     %5 = neq i32 %3, %4
+// CHECK:     %10 = shr [4 x i32]$ %a, [4 x i32]$ %9, i32 %i.ld
+// CHECK:     drv i32$ %11, %i.ld?1, %12
+// CHECK:     %14 = const i32 1
+// CHECK:     %15 = add i32 %13, %14
+// CHECK:     %20 = const i32 3
+// CHECK:     %j = var i32 %20
+// CHECK:     %22 = const i32 4294967295
+// CHECK:     %27 = const i32 2
+// CHECK:     drv i1$ %37, %38, %39
+// CHECK:     br %57, %if_false, %if_true
+// CHECK:     drv i32$ %sum, %59, %60
+// CHECK:     %x.shadow.ld = ld i8* %x.shadow
+// CHECK:     %92 = not i1 %91
+// CHECK:     drv i1$ %87, %92, %93
+// CHECK:     br %loop_step
+// CHECK: }
+// CHECK: entity @foo () -> () {
+// CHECK:     %b = sig [4 x i4] %11
+// CHECK:     %m = sig [4 x [2 x i32]] %24
+// CHECK:     inst %foo.initial.231.0 () -> (i32$ %sum, i8$ %x, [4 x i32]$ %a, [4 x i4]$ %b)
+// CHECK: }
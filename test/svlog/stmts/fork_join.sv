@@ -0,0 +1,130 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    int a, b, c, d;
+
+    initial begin
+        fork
+            #1 a = 1;
+            #2 b = 2;
+        join
+        fork
+            #1 a = 3;
+            begin
+                #5 b = 4;
+            end
+        join_any
+        disable fork;
+        fork
+            #1 c = 5;
+            #2 c = 6;
+        join_none
+        wait fork;
+        $display("a=%0d b=%0d c=%0d", a, b, c);
+    end
+
+    always @(a) begin
+        fork : outer
+            #1 c = a;
+            fork
+                #1 b = a;
+            join_none
+        join
+    end
+
+    initial begin
+        int k = 3;
+        fork
+            automatic int i = k + 1;
+            #1ns d = i + k;
+        join_none
+    end
+endmodule
+
+// CHECK: proc %foo.initial.191.0 (i32$ %fork.branch188.done) -> (i32$ %d, i32$ %fork189.ctl, i32$ %fork189.k, i32$ %fork189.i) {
+// CHECK: %0:
+// CHECK:     %1 = prb i32$ %fork189.ctl
+// CHECK:     %fork189.ctl.gen = var i32 %1
+// CHECK:     %2 = const i32 3
+// CHECK:     %k = var i32 %2
+// CHECK:     %k.ld = ld i32* %k
+// CHECK:     %3 = const i32 1
+// CHECK:     %4 = add i32 %k.ld, %3
+// CHECK:     %i = var i32 %4
+// CHECK:     %5 = ld i32* %k
+// CHECK:     %6 = const time 0s 1e
+// CHECK:     drv i32$ %fork189.k, %5, %6
+// CHECK:     %7 = ld i32* %i
+// CHECK:     %8 = const time 0s 1e
+// CHECK:     drv i32$ %fork189.i, %7, %8
+// CHECK:     %9 = const i32 1
+// CHECK:     %10 = ld i32* %fork189.ctl.gen
+// CHECK:     %11 = or i32 %10, %9
+// CHECK:     %12 = add i32 %11, %9
+// CHECK:     st i32* %fork189.ctl.gen, %12
+// CHECK:     %13 = const time 0s 1e
+// CHECK:     drv i32$ %fork189.ctl, %12, %13
+// CHECK:     halt
+// CHECK: }
+
+// CHECK: proc %foo.fork.188.0 (i32$ %fork189.ctl, i32$ %fork189.k, i32$ %fork189.i) -> (i32$ %d, i32$ %fork.branch188.done) {
+// CHECK: %0:
+// CHECK:     %1 = const i32 0
+// CHECK:     %gen = var i32 %1
+// CHECK:     br %idle
+// CHECK: %abort:
+// CHECK:     %2 = prb i32$ %fork189.ctl
+// CHECK:     st i32* %gen, %2
+// CHECK:     %3 = const time 0s 1e
+// CHECK:     drv i32$ %fork.branch188.done, %2, %3
+// CHECK:     br %idle
+// CHECK: %idle:
+// CHECK:     %4 = prb i32$ %fork189.ctl
+// CHECK:     %5 = ld i32* %gen
+// CHECK:     %6 = neq i32 %4, %5
+// CHECK:     br %6, %wait, %check
+// CHECK: %wait:
+// CHECK:     wait %idle, %fork189.ctl
+// CHECK: %check:
+// CHECK:     %7 = exts i1, i32 %4, 0, 1
+// CHECK:     br %7, %start, %abort
+// CHECK: %start:
+// CHECK:     %8 = const i32 1
+// CHECK:     %9 = or i32 %5, %8
+// CHECK:     %10 = add i32 %9, %8
+// CHECK:     st i32* %gen, %10
+// CHECK:     %11 = prb i32$ %fork189.k
+// CHECK:     %k = var i32 %11
+// CHECK:     %12 = prb i32$ %fork189.i
+// CHECK:     %i = var i32 %12
+// CHECK:     %13 = const time 1ns
+// CHECK:     wait %14 for %13
+// CHECK: %14:
+// CHECK:     %15 = prb i32$ %fork189.ctl
+// CHECK:     %16 = exts i1, i32 %15, 0, 1
+// CHECK:     br %16, %17, %abort
+// CHECK: %17:
+// CHECK:     %i.ld = ld i32* %i
+// CHECK:     %k.ld = ld i32* %k
+// CHECK:     %18 = add i32 %i.ld, %k.ld
+// CHECK:     %19 = const time 0s 1e
+// CHECK:     drv i32$ %d, %18, %19
+// CHECK:     %20 = ld i32* %gen
+// CHECK:     %21 = const time 0s 1e
+// CHECK:     drv i32$ %fork.branch188.done, %20, %21
+// CHECK:     br %idle
+// CHECK: }
+
+// CHECK:     inst %foo.initial.112.0 (i32$ %fork.branch19.done, i32$ %fork.branch31.done, i32$ %fork.branch44.done, i32$ %fork.branch57.done, i32$ %fork.branch71.done, i32$ %fork.branch83.done) -> (i32$ %a, i32$ %b, i32$ %c, i32$ %fork32.ctl, i32$ %fork58.ctl, i32$ %fork84.ctl)
+// CHECK:     inst %foo.always.149.0 (i32$ %a, i32$ %fork.branch130.done, i32$ %fork.branch145.done) -> (i32$ %b, i32$ %c, i32$ %outer.ctl)
+// CHECK:     inst %foo.initial.191.0 (i32$ %fork.branch188.done) -> (i32$ %d, i32$ %fork189.ctl, i32$ %fork189.k, i32$ %fork189.i)
+// CHECK:     inst %foo.fork.188.0 (i32$ %fork189.ctl, i32$ %fork189.k, i32$ %fork189.i) -> (i32$ %d, i32$ %fork.branch188.done)
+// CHECK:     inst %foo.fork.130.0 (i32$ %a, i32$ %outer.ctl) -> (i32$ %c, i32$ %fork.branch130.done)
+// CHECK:     inst %foo.fork.145.0 (i32$ %a, i32$ %outer.ctl, i32$ %fork.branch144.done) -> (i32$ %b, i32$ %fork.branch145.done, i32$ %fork145.ctl)
+// CHECK:     inst %foo.fork.144.0 (i32$ %a, i32$ %fork145.ctl) -> (i32$ %b, i32$ %fork.branch144.done)
+// CHECK:     inst %foo.fork.19.0 (i32$ %fork32.ctl) -> (i32$ %a, i32$ %fork.branch19.done)
+// CHECK:     inst %foo.fork.31.0 (i32$ %fork32.ctl) -> (i32$ %b, i32$ %fork.branch31.done)
+// CHECK:     inst %foo.fork.44.0 (i32$ %fork58.ctl) -> (i32$ %a, i32$ %fork.branch44.done)
+// CHECK:     inst %foo.fork.57.0 (i32$ %fork58.ctl) -> (i32$ %b, i32$ %fork.branch57.done)
+// CHECK:     inst %foo.fork.71.0 (i32$ %fork84.ctl) -> (i32$ %c, i32$ %fork.branch71.done)
+// CHECK:     inst %foo.fork.83.0 (i32$ %fork84.ctl) -> (i32$ %c, i32$ %fork.branch83.done)
@@ -0,0 +1,52 @@
+// RUN: moore %s -e foo -O0
+
+// Re-entering a fork-join block while its branches are still running queues
+// another execution of the branches.
+module foo;
+    int n;
+
+    initial begin
+        for (int i = 0; i < 3; i++)
+            fork : spawn
+                #1ns n = n + 1;
+            join_none
+        wait fork;
+    end
+endmodule
+
+// CHECK:     %1 = prb i32$ %n
+// CHECK:     %n.shadow = var i32 %1
+// CHECK:     %2 = const i32 0
+// CHECK:     %gen = var i32 %2
+// CHECK:     br %idle
+// CHECK: %abort:
+// CHECK:     %3 = prb i32$ %spawn.ctl
+// CHECK:     st i32* %gen, %3
+// CHECK:     %4 = const time 0s 1e
+// CHECK:     br %idle
+// CHECK: %idle:
+// CHECK:     %5 = prb i32$ %spawn.ctl
+// CHECK:     %6 = ld i32* %gen
+// CHECK:     %7 = neq i32 %5, %6
+// CHECK:     br %7, %wait, %check
+// CHECK: %wait:
+// CHECK:     wait %idle, %spawn.ctl
+// CHECK: %check:
+// CHECK:     %8 = exts i1, i32 %5, 0, 1
+// CHECK:     br %8, %start, %abort
+// CHECK: %start:
+// CHECK:     %9 = const i32 1
+// CHECK:     %10 = or i32 %6, %9
+// CHECK:     %11 = add i32 %10, %9
+// CHECK:     st i32* %gen, %11
+// CHECK:     %12 = prb i32$ %n
+// CHECK:     st i32* %n.shadow, %12
+// CHECK:     %13 = const time 1ns
+// CHECK:     wait %14 for %13
+// CHECK: %14:
+// CHECK:     %15 = prb i32$ %n
+// CHECK:     st i32* %n.shadow, %15
+// CHECK:     %16 = prb i32$ %spawn.ctl
+// CHECK:     %17 = exts i1, i32 %16, 0, 1
+// CHECK:     br %17, %18, %abort
+// CHECK: %18:
@@ -0,0 +1,134 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    int a, b;
+
+    function int first_set(logic [7:0] x);
+        for (int i = 0; i < 8; i++) begin
+            if (x[i])
+                return i;
+        end
+        return -1;
+    endfunction
+
+    task bump(output int y);
+        y = 1;
+        if (a > 3)
+            return;
+        y = 2;
+    endtask
+
+    initial begin
+        for (int i = 0; i < 10; i++) begin
+            if (i == 2)
+                continue;
+            if (i == 7)
+                break;
+            a += i;
+        end
+        while (1) begin
+            b++;
+            if (b > 4) break;
+        end
+        begin : outer
+            forever begin
+                #1 a++;
+                if (a > 20)
+                    disable outer;
+            end
+        end
+        b = first_set(8'b00100100);
+        bump(b);
+    end
+endmodule
+
+// CHECK: func %first_set.57.0 (i8 %x) i32 {
+// CHECK: %0:
+// CHECK:     %x.var = var i8 %x
+// CHECK:     %1 = const i32 0
+// CHECK:     %first_set.ret = var i32 %1
+// CHECK:     %2 = const i32 0
+// CHECK:     %i = var i32 %2
+// CHECK:     br %loop_body
+// CHECK: %loop_body:
+// CHECK:     %i.ld = ld i32* %i
+// CHECK:     %3 = const i32 8
+// CHECK:     %4 = slt i32 %i.ld, %3
+// CHECK:     br %4, %loop_exit, %loop_continue
+// CHECK: %loop_exit:
+// CHECK:     %5 = const i32 4294967295
+// CHECK:     st i32* %first_set.ret, %5
+// CHECK:     br %return
+// CHECK: %loop_continue:
+// CHECK:     %x.var.ld = ld i8* %x.var
+// CHECK:     %6 = const i8 0
+// CHECK:     %8 = exts i1, i8 %7, 0, 1
+// CHECK:     %9 = const i1 0
+// CHECK:     %10 = neq i1 %8, %9
+// CHECK:     br %10, %if_false, %if_true
+// CHECK: %if_true:
+// CHECK:     br %return
+// CHECK: %if_false:
+// CHECK:     br %if_exit
+// CHECK: %if_exit:
+// CHECK:     %11 = const i32 1
+// CHECK:     st i32* %i, %12
+// CHECK:     br %loop_body
+// CHECK: %return:
+// CHECK:     %13 = ld i32* %first_set.ret
+// CHECK:     ret i32 %13
+// CHECK: %14:
+// CHECK: proc %foo.initial.215.0 () -> (i32$ %a, i32$ %b) {
+// CHECK: %0:
+// CHECK:     %3 = const i32 0
+// CHECK:     %i = var i32 %3
+// CHECK:     br %loop_body
+// CHECK: %loop_body:
+// CHECK:     %i.ld = ld i32* %i
+// CHECK:     %4 = const i32 10
+// CHECK:     %5 = slt i32 %i.ld, %4
+// CHECK:     br %5, %loop_exit, %loop_continue
+// CHECK: %loop_exit:
+// CHECK: %loop_continue:
+// CHECK:     %6 = const i32 2
+// CHECK:     %8 = const i1 0
+// CHECK:     %9 = neq i1 %7, %8
+// CHECK:     br %9, %if_false, %if_true
+// CHECK: %if_true:
+// CHECK:     br %loop_step
+// CHECK: %if_false:
+// CHECK:     br %if_exit
+// CHECK: %if_exit:
+// CHECK:     %39 = neq i1 %37, %38
+// CHECK:     br %disable
+// CHECK: %disable:
+// CHECK:     %40 = const i8 36
+// CHECK:     %41 = call i32 %first_set.57.0 (i8 %40)
+// CHECK:     %42 = const time 0s 1e
+// CHECK:     drv i32$ %b, %41, %42
+// CHECK:     %43 = const i32 0
+// CHECK:     %bump.y = var i32 %43
+// CHECK:     %44 = const i32 1
+// CHECK:     st i32* %bump.y, %44
+// CHECK:     %45 = const i32 3
+// CHECK:     %47 = const i1 0
+// CHECK:     %48 = neq i1 %46, %47
+// CHECK: %49:
+// CHECK:     br %return
+// CHECK:     %50 = const i32 2
+// CHECK:     st i32* %bump.y, %50
+// CHECK:     br %return
+// CHECK: %return:
+// CHECK:     %51 = ld i32* %bump.y
+// CHECK:     %52 = const time 0s 1e
+// CHECK:     drv i32$ %b, %51, %52
+// CHECK:     halt
+// CHECK: %53:
+// CHECK: }
+// CHECK: entity @foo () -> () {
+// CHECK:     %0 = const i32 0
+// CHECK:     %a = sig i32 %0
+// CHECK:     %1 = const i32 0
+// CHECK:     %b = sig i32 %1
+// CHECK:     inst %foo.initial.215.0 () -> (i32$ %a, i32$ %b)
+// CHECK: }
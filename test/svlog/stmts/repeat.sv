@@ -0,0 +1,14 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    int x;
+    initial repeat (3) x++;
+endmodule
+
+// The loop counter counts down by one per iteration.
+// CHECK:     %2 = const i32 3
+// CHECK:     %loop_count = var i32 %2
+// CHECK:     %9 = ld i32* %loop_count
+// CHECK:     %10 = const i32 1
+// CHECK:     %11 = sub i32 %9, %10
+// CHECK:     st i32* %loop_count, %11
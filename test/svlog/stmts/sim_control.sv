@@ -0,0 +1,74 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    time t0, t1;
+    int r0, r1;
+    int seed = 42;
+
+    initial begin
+        t0 = $time;
+        #10ns;
+        t1 = $realtime;
+        r0 = $random;
+        r1 = $random(seed);
+        $display("%t %t %d %d", $time, t1, r0, r1);
+        if (r0 == r1)
+            $stop;
+        $finish;
+    end
+
+    final $finish(0);
+endmodule
+
+// CHECK: proc %foo.initial.107.0 () -> (time$ %t0, time$ %t1, i32$ %r0, i32$ %r1, i32$ %seed) {
+// CHECK:     %5 = const time 1ns
+// CHECK:     %6 = call time @llhd.time (time %5)
+// CHECK:     %7 = const time 0s 1e
+// CHECK:     drv time$ %t0, %6, %7
+// CHECK:     %14 = const time 1ns
+// CHECK:     %15 = call i64 @llhd.realtime (time %14)
+// CHECK:     %16 = call i64 @llhd.real.round (i64 %15)
+// CHECK:     %17 = const time 1ns
+// CHECK:     %18 = const time 1ns
+// CHECK:     %19 = call time @llhd.time.from_uint (i64 %16, time %17, time %18)
+// CHECK:     %20 = const time 0s 1e
+// CHECK:     drv time$ %t1, %19, %20
+// CHECK:     %21 = call i32 @llhd.random ()
+// CHECK:     %22 = const time 0s 1e
+// CHECK:     drv i32$ %r0, %21, %22
+// CHECK:     %seed.shadow.ld = ld i32* %seed.shadow
+// CHECK:     %23 = const i32 0
+// CHECK:     %24 = eq i32 %seed.shadow.ld, %23
+// CHECK:     %25 = const i32 259341593
+// CHECK:     %26 = [i32 %seed.shadow.ld, %25]
+// CHECK:     %27 = mux [2 x i32] %26, i1 %24
+// CHECK:     %28 = const i32 69069
+// CHECK:     %29 = smul i32 %27, %28
+// CHECK:     %30 = const i32 1
+// CHECK:     %31 = add i32 %29, %30
+// CHECK:     %32 = const time 0s 1e
+// CHECK:     drv i32$ %seed, %31, %32
+// CHECK:     %33 = const i32 9
+// CHECK:     %34 = const i32 0
+// CHECK:     %35 = shr i32 %31, i32 %34, i32 %33
+// CHECK:     %36 = const i32 1
+// CHECK:     %37 = add i32 %35, %36
+// CHECK:     %38 = const i32 512
+// CHECK:     %39 = smul i32 %37, %38
+// CHECK:     %40 = const i32 14
+// CHECK:     %41 = const i32 0
+// CHECK:     %42 = shr i32 %35, i32 %41, i32 %40
+// CHECK:     %43 = add i32 %39, %42
+// CHECK:     %44 = const i32 2147483648
+// CHECK:     %45 = add i32 %43, %44
+// CHECK:     %46 = const time 0s 1e
+// CHECK:     drv i32$ %r1, %45, %46
+// CHECK:     %62 = const time 1ns
+// CHECK:     %63 = call time @llhd.time (time %62)
+// CHECK:     call void @llhd.display (i8 %47, [13 x i8] %61, time %63, time %t1.shadow.ld, i32 %r0.shadow.ld, i32 %r1.shadow.ld)
+// CHECK:     call void @llhd.stop ()
+// CHECK:     call void @llhd.finish ()
+// CHECK: }
+// CHECK: proc %foo.final.116.0 () -> () {
+// CHECK:     call void @llhd.finish ()
+// CHECK: }
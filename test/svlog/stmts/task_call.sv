@@ -0,0 +1,64 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    bit clk;
+    bit valid;
+    bit [7:0] data;
+    int count;
+
+    task drive_packet(input bit [7:0] payload, output int sent);
+        @(posedge clk);
+        valid = 1;
+        data = payload;
+        #1ns;
+        valid = 0;
+        sent = 1;
+    endtask
+
+    task automatic accumulate;
+        inout int acc;
+        input int x;
+        acc = acc + x;
+    endtask
+
+    task set_flag(ref bit flag);
+        flag = 1;
+    endtask
+
+    task tick;
+        #1ns clk = ~clk;
+    endtask
+
+    initial begin
+        int sent;
+        int sum = 0;
+        drive_packet(8'h42, sent);
+        accumulate(sum, sent);
+        accumulate(.x(2), .acc(sum));
+        set_flag(valid);
+        count = sum;
+        tick;
+        tick();
+    end
+endmodule
+
+// CHECK: proc %foo.initial.282.0 () -> (i1$ %clk, i1$ %valid, i8$ %data, i32$ %count) {
+// CHECK:     %drive_packet.payload = var i8 %5
+// CHECK:     %drive_packet.sent = var i32 %6
+// CHECK:     wait %check, %clk
+// CHECK:     %posedge = and i1 %10, %11
+// CHECK:     drv i8$ %data, %drive_packet.payload.ld, %14
+// CHECK:     wait %16 for %15
+// CHECK:     st i32* %drive_packet.sent, %21
+// CHECK:     st i32* %sent, %22
+// CHECK:     %accumulate.acc = var i32 %sum.ld
+// CHECK:     %23 = add i32 %accumulate.acc.ld, %accumulate.x.ld
+// CHECK:     st i32* %sum, %24
+// CHECK:     st i32* %sum, %27
+// CHECK:     drv i1$ %valid, %28, %29
+// CHECK:     wait %32 for %31
+// CHECK:     drv i1$ %clk, %35, %36
+// CHECK:     wait %38 for %37
+// CHECK:     drv i1$ %clk, %41, %42
+// CHECK:     halt
+// CHECK: }
@@ -0,0 +1,64 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    bit [31:0] a, b;
+    bit [7:0] c, d;
+    bit [15:0] e;
+    bit [39:0] f;
+    typedef struct packed { bit [7:0] hi; bit [7:0] lo; } pair_t;
+    pair_t p;
+    bit [15:0] g;
+    int j = {"A", "B", "C", "D"};
+    int j0, j1, j2;
+    bit [7:0] k0;
+    bit [5:0] k1, k2;
+    bit [3:0] k3;
+
+    initial begin
+        b = {>>{a}};            // identity
+        b = {<<{a}};            // bit reversal
+        b = {<<8{a}};           // byte swap
+        b = {<<byte{a}};        // byte swap with a type slice
+        e = {<<4{c, d}};        // nibble reversal across two values
+        f = {>>{c, d}};         // left-aligned, zero-padded on the right
+        p = {<<8{e}};
+        {>>{c, d}} = e;         // unpack
+        {<<8{c, d}} = a;        // unpack the upper 16 bits, swapped
+        {>>{c, {<<{d}}}} = e;
+
+        // Examples from IEEE 1800-2017 11.4.14.2
+        j0 = {>>{j}};                   // "A" "B" "C" "D"
+        j1 = {<<byte{j}};               // "D" "C" "B" "A"
+        j2 = {<<16{j}};                 // "C" "D" "A" "B"
+        k0 = {<<{8'b0011_0101}};        // 'b1010_1100
+        k1 = {<<4{6'b11_0101}};         // 'b0101_11
+        k2 = {>>4{6'b11_0101}};         // 'b1101_01
+        k3 = {<<2{{<<{4'b1101}}}};      // 'b1110
+        {<<4{k1}} = 6'b0101_11;         // 'b11_0101
+    end
+
+    assign g = {<<2{c, d}};
+endmodule
+
+// CHECK: proc %foo.initial.365.0 (i32$ %a, i32$ %j) -> (i32$ %b, i8$ %c, i8$ %d, i16$ %e, i40$ %f, {i8, i8}$ %p, i32$ %j0, i32$ %j1, i32$ %j2, i8$ %k0, i6$ %k1, i6$ %k2, i4$ %k3) {
+// CHECK:     %concat?5 = inss i32 %172, i32 %a.prb?2, 0, 32
+// CHECK:     %173 = const i32 24
+// CHECK:     %177 = inss i32 %171, i8 %176, 0, 8
+// CHECK:     %concat?6 = inss i32 %187, i8 %191, 24, 8
+// CHECK:     drv i32$ %b, %concat?7, %192
+// CHECK:     drv i16$ %e, %concat?13, %239
+// CHECK:     drv i40$ %f, %concat?15, %245
+// CHECK:     drv {i8, i8}$ %p, %266, %267
+// CHECK:     drv i8$ %d, %271, %272
+// CHECK:     drv i8$ %c, %276, %277
+// CHECK:     drv i32$ %j0, %concat?22, %354
+// CHECK:     drv i32$ %j1, %concat?25, %377
+// CHECK:     drv i32$ %j2, %concat?28, %390
+// CHECK:     %447 = const i6 53
+// CHECK:     drv i6$ %k2, %447, %448
+// CHECK:     drv i6$ %k1, %concat?38, %495
+// CHECK:     halt
+// CHECK: }
+// CHECK: entity @foo () -> () {
+// CHECK:     drv i16$ %g, %concat?2, %18
+// CHECK: }
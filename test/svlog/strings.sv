@@ -0,0 +1,65 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    string s;
+    string t;
+    string name;
+    int i;
+    int n;
+    byte c;
+    logic [7:0] data;
+
+    initial begin
+        s = "hello";
+        t = {s, " ", "world"};
+        t = {3{s}};
+        t = {i{s}};
+        n = s.len();
+        c = s.getc(0);
+        s.putc(0, "H");
+        t = s.toupper();
+        t = s.tolower();
+        t = s.substr(1, 3);
+        n = s.compare(t);
+        n = s.icompare(t);
+        s = "42";
+        n = s.atoi();
+        n = s.atohex();
+        s.itoa(n);
+        s.hextoa(data);
+        if (s == "42" || s < t)
+            $display("%s and %s", s, t);
+        name = $sformatf("item_%0d: %h", i, data);
+        $display("%s (%0d characters)", name, name.len());
+    end
+endmodule
+
+// CHECK: proc %foo.initial.407.0 (i32$ %i, i8$ %data) -> (i64$ %s, i64$ %t, i64$ %name, i32$ %n, i8$ %c) {
+// CHECK:     %12 = call i64 @llhd.string.new ([6 x i8] %11)
+// CHECK:     drv i64$ %s, %12, %13
+// CHECK:     %18 = call i64 @llhd.string.concat (i64 %s.shadow.ld, i64 %17)
+// CHECK:     %27 = call i64 @llhd.string.concat (i64 %18, i64 %26)
+// CHECK:     drv i64$ %t, %27, %28
+// CHECK:     %30 = call i64 @llhd.string.repeat (i64 %s.shadow.ld?1, i32 %29)
+// CHECK:     %34 = call i32 @llhd.string.len (i64 %s.shadow.ld?3)
+// CHECK:     drv i32$ %n, %34, %35
+// CHECK:     %37 = call i8 @llhd.string.getc (i64 %s.shadow.ld?4, i32 %36)
+// CHECK:     drv i8$ %c, %37, %38
+// CHECK:     %41 = call i64 @llhd.string.putc (i64 %s.shadow.ld?5, i32 %39, i8 %40)
+// CHECK:     drv i64$ %s, %41, %42
+// CHECK:     %43 = call i64 @llhd.string.toupper (i64 %s.shadow.ld?6)
+// CHECK:     %45 = call i64 @llhd.string.tolower (i64 %s.shadow.ld?7)
+// CHECK:     %49 = call i64 @llhd.string.substr (i64 %s.shadow.ld?8, i32 %47, i32 %48)
+// CHECK:     %51 = call i32 @llhd.string.compare (i64 %s.shadow.ld?9, i64 %t.shadow.ld)
+// CHECK:     %53 = call i32 @llhd.string.icompare (i64 %s.shadow.ld?10, i64 %t.shadow.ld?1)
+// CHECK:     %61 = call i32 @llhd.string.atoi (i64 %s.shadow.ld?11)
+// CHECK:     %63 = call i32 @llhd.string.atohex (i64 %s.shadow.ld?12)
+// CHECK:     %65 = call i64 @llhd.string.itoa (i64 %s.shadow.ld?13, i32 %n.shadow.ld)
+// CHECK:     %68 = call i64 @llhd.string.hextoa (i64 %s.shadow.ld?14, i32 %zext)
+// CHECK:     %75 = call i32 @llhd.string.compare (i64 %s.shadow.ld?15, i64 %74)
+// CHECK:     call void @llhd.display (i8 %88, [11 x i8] %100, i64 %s.shadow.ld?17, i64 %t.shadow.ld?3)
+// CHECK:     %115 = call i64 @llhd.string.format ([13 x i8] %114, i32 %i.prb?1, i8 %data.prb?1)
+// CHECK:     drv i64$ %name, %115, %116
+// CHECK:     call void @llhd.display (i8 %117, [21 x i8] %139, i64 %name.shadow.ld, i32 %140)
+// CHECK:     halt
+// CHECK: }
@@ -0,0 +1,13 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    string s;
+    int n;
+    initial begin
+        n = s.size(); // no such method
+        s = $sformatf("%0d and %0d", n);
+    end
+endmodule
+// CHECK-ERR: error: no method `size` in `string`
+// CHECK-ERR: error: no argument for format specifier `%0d`
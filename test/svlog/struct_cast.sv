@@ -0,0 +1,27 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    typedef struct packed {
+        logic [3:0] a;
+        logic [11:0] b;
+    } s_t;
+
+    s_t s;
+    logic [3:0] a;
+    logic [11:0] b;
+
+    initial begin
+        s = s_t'(16'h1234);
+        a = s.a;
+        b = s.b;
+    end
+endmodule
+
+// The first field occupies the most significant bits.
+// CHECK:     %2 = const i16 4660
+// CHECK:     %3 = const i32 12
+// CHECK:     %6 = exts i4, i16 %5, 0, 4
+// CHECK:     %7 = const i32 0
+// CHECK:     %10 = exts i12, i16 %9, 0, 12
+// CHECK:     %11 = {i4 %6, i12 %10}
+// CHECK:     drv {i4, i12}$ %s, %11, %12
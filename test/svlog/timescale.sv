@@ -0,0 +1,78 @@
+// RUN: moore %s -e foo -O0
+
+`timescale 1ns / 10ps
+
+module foo;
+    bar i_bar();
+    baz i_baz();
+    logic a;
+    initial begin
+        #10;          // 10ns
+        #2.5ns;       // 2.5ns
+        a <= #3 1;    // 3ns
+        #1.234ns;     // rounded to 1.23ns
+    end
+endmodule
+
+module bar;
+    timeunit 1us;
+    timeprecision 1ns;
+    initial #2 $display("bar %0t", $time);     // 2us
+endmodule
+
+`timescale 100ps / 1ps
+
+module baz;
+    localparam int D = 4;
+    initial #D $display("baz %0t", $time);    // 400ps
+endmodule
+
+// CHECK: proc %bar.param1.initial.54.1 () -> () {
+// CHECK: %0:
+// CHECK:     %1 = const time 2us
+// CHECK:     wait %2 for %1
+// CHECK: %2:
+// CHECK:     %14 = const time 1us
+// CHECK:     %15 = call time @llhd.time (time %14)
+// CHECK:     call void @llhd.display (i8 %3, [9 x i8] %13, time %15)
+// CHECK:     halt
+// CHECK: }
+// CHECK: entity @bar.param1 () -> () {
+// CHECK:     inst %bar.param1.initial.54.1 () -> ()
+// CHECK: }
+// CHECK: proc %baz.param2.initial.78.2 () -> () {
+// CHECK: %0:
+// CHECK:     %1 = const time 400ps
+// CHECK:     wait %2 for %1
+// CHECK: %2:
+// CHECK:     %14 = const time 100ps
+// CHECK:     %15 = call time @llhd.time (time %14)
+// CHECK:     call void @llhd.display (i8 %3, [9 x i8] %13, time %15)
+// CHECK:     halt
+// CHECK: }
+// CHECK: entity @baz.param2 () -> () {
+// CHECK:     inst %baz.param2.initial.78.2 () -> ()
+// CHECK: }
+// CHECK: proc %foo.initial.39.0 () -> (i1$ %a) {
+// CHECK: %0:
+// CHECK:     %1 = const time 10ns
+// CHECK:     wait %2 for %1
+// CHECK: %2:
+// CHECK:     %3 = const time 2500ps
+// CHECK:     wait %4 for %3
+// CHECK: %4:
+// CHECK:     %5 = const time 3ns
+// CHECK:     %6 = const i1 1
+// CHECK:     drv i1$ %a, %6, %5
+// CHECK:     %7 = const time 1230ps
+// CHECK:     wait %8 for %7
+// CHECK: %8:
+// CHECK:     halt
+// CHECK: }
+// CHECK: entity @foo () -> () {
+// CHECK:     %0 = const i1 0
+// CHECK:     %a = sig i1 %0
+// CHECK:     inst @bar.param1 () -> ()
+// CHECK:     inst @baz.param2 () -> ()
+// CHECK:     inst %foo.initial.39.0 () -> (i1$ %a)
+// CHECK: }
@@ -0,0 +1,23 @@
+// RUN: moore %s -e foo -O0
+
+`timescale 1ns / 1ps
+
+module foo;
+    int d;
+    real r;
+    initial begin
+        #d;           // d * 1ns
+        #r;           // r * 1ns, rounded to 1ps
+    end
+endmodule
+
+// CHECK:     %d.prb = prb i32$ %d
+// CHECK:     %1 = const time 1ns
+// CHECK:     %2 = const time 1ps
+// CHECK:     %3 = call time @llhd.time.from_sint (i32 %d.prb, time %1, time %2)
+// CHECK:     wait %4 for %3
+// CHECK:     %r.prb = prb i64$ %r
+// CHECK:     %5 = const time 1ns
+// CHECK:     %6 = const time 1ps
+// CHECK:     %7 = call time @llhd.time.from_real (i64 %r.prb, time %5, time %6)
+// CHECK:     wait %8 for %7
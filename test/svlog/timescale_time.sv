@@ -0,0 +1,19 @@
+// RUN: moore %s -e foo -O0
+
+`timescale 1ns / 1ps
+
+module foo;
+    time t = 10;               // 10ns
+    int n;
+    initial #t;
+    initial n = t + 5;         // t / 1ns + 5
+endmodule
+
+// CHECK:     %t.prb = prb time$ %t
+// CHECK:     wait %1 for %t.prb
+// CHECK:     %t.prb = prb time$ %t
+// CHECK:     %1 = const time 1ns
+// CHECK:     %2 = const time 1ps
+// CHECK:     %3 = call i64 @llhd.time.to_uint (time %t.prb, time %1, time %2)
+// CHECK:     %0 = const time 10ns
+// CHECK:     %t = sig time %0
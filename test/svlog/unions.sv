@@ -0,0 +1,86 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    // A packed union offering a header view of a raw word.
+    typedef struct packed {
+        logic [3:0] version;
+        logic [3:0] length;
+        logic [7:0] proto;
+    } header_t;
+
+    typedef union packed {
+        logic [15:0] raw;
+        header_t hdr;
+    } view_t;
+
+    union {
+        int i;
+        byte b;
+        shortint s;
+    } u;
+
+    typedef union tagged {
+        void Invalid;
+        int Valid;
+        struct packed {
+            logic [7:0] a;
+            logic [7:0] b;
+        } Pair;
+    } maybe_t;
+
+    view_t v;
+    maybe_t m;
+    logic [3:0] version;
+    logic [7:0] proto;
+    int x;
+
+    initial begin
+        // Packed unions reinterpret the same bits.
+        v.raw = 16'h4506;
+        version = v.hdr.version;
+        proto = v.hdr.proto;
+        v.hdr.length = 4'd5;
+        x = v;
+
+        // Unpacked unions share the storage of their largest member.
+        u.i = 42;
+        x = u.b;
+        u.s = 7;
+        x = u.i;
+
+        // Tagged unions track the active member.
+        m = tagged Invalid;
+        m = tagged Valid 42;
+        m = tagged Pair '{a: 1, b: 2};
+        if (m matches tagged Valid .n)
+            x = n;
+        if (m matches tagged Pair .*)
+            x = 1;
+        case (m) matches
+            tagged Invalid: x = 0;
+            tagged Valid 0: x = 1;
+            tagged Valid .n: x = n;
+            tagged Pair .p: x = p.a + p.b;
+        endcase
+        x = m matches tagged Invalid;
+    end
+endmodule
+
+// CHECK: proc %foo.initial.427.0 () -> (i32$ %u, i16$ %v, i34$ %m, i4$ %version, i8$ %proto, i32$ %x) {
+// CHECK:     %13 = const i16 17670
+// CHECK:     drv i4$ %version, %32, %33
+// CHECK:     drv i8$ %proto, %51, %52
+// CHECK:     %71 = const i4 5
+// CHECK:     drv i32$ %x, %sext, %95
+// CHECK:     %114 = const i34 4294967338
+// CHECK:     drv i34$ %m, %114, %115
+// CHECK:     %116 = const i34 8606842880
+// CHECK:     drv i34$ %m, %116, %117
+// CHECK:     %n = var i32 %121
+// CHECK:     drv i32$ %x, %n.ld, %132
+// CHECK:     %145 = var i34 %m.shadow.ld?3
+// CHECK:     %146 = ld i34* %145
+// CHECK:     %155 = ld i34* %145
+// CHECK:     %n?1 = var i32 %186
+// CHECK:     %p = var {i8, i8} %212
+// CHECK:     drv i32$ %x, %228, %229
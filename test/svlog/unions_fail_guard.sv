@@ -0,0 +1,18 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    union tagged {
+        void Invalid;
+        int Valid;
+    } m;
+    int x;
+    initial begin
+        case (m) matches
+            tagged Valid .n &&& (n > 3): x = n;
+        endcase
+    end
+endmodule
+
+// CHECK-ERR: error: unsupported: `&&&` guard in pattern
+// CHECK-ERR: = note: Match the pattern and check the guard in a nested `if` instead
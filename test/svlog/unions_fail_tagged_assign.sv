@@ -0,0 +1,13 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    union tagged {
+        int Valid;
+        void Invalid;
+    } m;
+    initial m.Valid = 42;
+endmodule
+
+// CHECK-ERR: error: member `Valid` of tagged union cannot be assigned individually
+// CHECK-ERR: = note: Assign a `tagged` expression to the entire union instead
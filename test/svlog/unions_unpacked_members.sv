@@ -0,0 +1,37 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    // Members of unpacked unions may be of any type with a fixed size.
+    union {
+        real r;
+        longint l;
+        struct {
+            int hi;
+            int lo;
+        } s;
+    } u;
+
+    real r;
+    longint l;
+    int x;
+
+    initial begin
+        u.r = 1.5;
+        l = u.l;
+        u.s.hi = 1;
+        u.s.lo = 2;
+        x = u.s.lo;
+        u.l = 42;
+        r = u.r;
+    end
+endmodule
+
+// CHECK: proc %foo.initial.179.0 () -> (i64$ %u, i64$ %r, i64$ %l, i32$ %x) {
+// CHECK:     %11 = const i64 4609434218613702656
+// CHECK:     drv i64$ %9, %11, %12
+// CHECK:     drv i64$ %l, %16, %17
+// CHECK:     %27 = const i32 32
+// CHECK:     %70 = {i32 %65, i32 %69}
+// CHECK:     %71 = extf i32, {i32, i32} %70, 1
+// CHECK:     drv i32$ %x, %71, %72
+// CHECK:     drv i64$ %r, %87, %88
@@ -0,0 +1,87 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    logic [3:0] x;
+    logic a, b;
+    int y;
+
+    initial begin
+        unique if (a) y = 1;
+        else if (b) y = 2;
+    end
+
+    initial begin
+        unique0 if (a) y = 1;
+        else if (b) y = 2;
+        else if (!a) y = 3;
+    end
+
+    initial begin
+        priority if (a) y = 1;
+        else if (b) y = 2;
+        else y = 3;
+    end
+
+    initial begin
+        unique case (x)
+            0, 1: y = 1;
+            2: y = 2;
+            3: y = 3;
+        endcase
+    end
+
+    initial begin
+        unique0 casez (x)
+            4'b1???: y = 1;
+            4'b?1??: y = 2;
+            4'b0001: y = 3;
+        endcase
+    end
+
+    initial begin
+        priority casex (x)
+            4'b1xxx: y = 1;
+            4'bx1xx: y = 2;
+            default: y = 0;
+        endcase
+    end
+endmodule
+
+// CHECK: proc %foo.initial.47.0 (i1$ %a, i1$ %b) -> (i32$ %y) {
+// CHECK:     %6 = or i1 %5, %4
+// CHECK:     br %6, %7, %violation
+// CHECK: %violation:
+// CHECK:     %10 = const i8 2
+// CHECK: }
+// CHECK: proc %foo.initial.93.0 (i1$ %a, i1$ %b) -> (i32$ %y) {
+// CHECK:     %12 = or i1 %11, %9
+// CHECK:     br %12, %13, %violation
+// CHECK: %violation:
+// CHECK:     %16 = const i8 2
+// CHECK: %violation?1:
+// CHECK: }
+// CHECK: proc %foo.initial.134.0 (i1$ %a, i1$ %b) -> (i32$ %y) {
+// CHECK:     br %2, %if_false, %if_true
+// CHECK:     drv i32$ %y, %3, %4
+// CHECK:     drv i32$ %y, %9, %10
+// CHECK: }
+// CHECK: proc %foo.initial.175.0 (i4$ %x) -> (i32$ %y) {
+// CHECK:     %7 = or i1 %4, %6
+// CHECK:     br %7, %16, %case_body
+// CHECK:     drv i32$ %y, %17, %18
+// CHECK:     br %15, %22, %case_body?2
+// CHECK: %22:
+// CHECK:     %25 = const i8 2
+// CHECK: }
+// CHECK: proc %foo.initial.215.0 (i4$ %x) -> (i32$ %y) {
+// CHECK:     br %7, %22, %case_body
+// CHECK: %case_body:
+// CHECK:     %24 = or i1 %23, %14
+// CHECK:     br %24, %25, %violation
+// CHECK: %violation:
+// CHECK:     %27 = const i8 2
+// CHECK: }
+// CHECK: proc %foo.initial.254.0 (i4$ %x) -> (i32$ %y) {
+// CHECK:     drv i32$ %y, %16, %17
+// CHECK:     drv i32$ %y, %21, %22
+// CHECK: }